@@ -32,11 +32,191 @@ export {
     AcmeChallenge,
 };
 
+/**
+ * Flattened error code to switch on, see {@link CoreCryptoError.errorCode}.
+ *
+ * Codes are grouped by hundreds: conversation state (1xx), messages (2xx), identity & authorization (3xx),
+ * end-to-end identity & certificates (4xx), setup & misuse (5xx), Proteus (6xx), keystore (7xx),
+ * MLS internals (8xx) and encoding (9xx).
+ */
+export enum CoreCryptoErrorCode {
+    /**
+     * Not classified, see the error context
+     */
+    Other = 0,
+    ConversationNotFound = 100,
+    ConversationAlreadyExists = 101,
+    ParentGroupNotFound = 102,
+    PendingProposalNotFound = 103,
+    PendingCommitNotFound = 104,
+    ClientNotFound = 105,
+    UnmergedPendingGroup = 106,
+    OrphanWelcome = 107,
+    InternalMlsError = 108,
+    MissingExternalSenderExtension = 109,
+    /**
+     * The merged conversation could not be persisted and has been rolled back, the operation can be retried
+     */
+    MergeRolledBack = 110,
+    /**
+     * A client being added does not support every capability required by the conversation
+     */
+    MissingRequiredCapabilities = 111,
+    /**
+     * A commit of the conversation is in flight and its epoch fence is {@link EpochFence.FailFast}
+     */
+    PendingCommitInFlight = 112,
+    WrongEpoch = 200,
+    DecryptionError = 201,
+    BufferedFutureMessage = 202,
+    MessageEpochTooOld = 203,
+    DuplicateMessage = 204,
+    SelfCommitIgnored = 205,
+    StaleCommit = 206,
+    StaleProposal = 207,
+    ClearingPendingCommitError = 208,
+    MalformedMessage = 209,
+    /**
+     * Some messages between the last processed one and the incoming one have not been delivered
+     */
+    MessageEpochGap = 210,
+    /**
+     * The application message could have been decrypted but its epoch is older than
+     * {@link CustomConfiguration.maxApplicationMessageEpochAge} allows
+     */
+    ApplicationMessageTooOld = 211,
+    /**
+     * The message, Welcome or GroupInfo is larger than {@link CoreCrypto.setMessageLimits} allows
+     */
+    InputTooLarge = 212,
+    /**
+     * Messages of the sender in the current epoch have not been delivered before the incoming one, more than
+     * {@link CustomConfiguration.maximumForwardDistance} allows
+     */
+    MessageGenerationGap = 213,
+    Unauthorized = 300,
+    UnauthorizedExternalAddProposal = 301,
+    UnauthorizedExternalCommit = 302,
+    InvalidIdentity = 303,
+    InvalidKeyPackage = 304,
+    InvalidClientId = 305,
+    MalformedIdentifier = 306,
+    CredentialNotFound = 307,
+    CredentialBundleConflict = 308,
+    IdentityAlreadyPresent = 309,
+    NoProvisionalIdentityFound = 310,
+    TooManyIdentitiesPresent = 311,
+    ClientSignatureNotFound = 312,
+    IdentityInitializationError = 313,
+    UnauthorizedExternalRemoveProposal = 314,
+    UnauthorizedMemberChange = 315,
+    IdentityExportNotAllowed = 316,
+    IdentityConflict = 317,
+    MalformedIdentityBackup = 318,
+    JoinPolicyViolation = 319,
+    ClaimedKeyPackageMismatch = 320,
+    MalformedClientId = 321,
+    UnsupportedGroupExtension = 322,
+    NotConversationAdmin = 323,
+    /**
+     * A signature was not made over the data by the expected identity key, or is malformed
+     */
+    InvalidIdentitySignature = 324,
+    /**
+     * The group to join uses another ciphersuite than the expected one, see {@link CoreCrypto.joinByExternalCommit}
+     */
+    GroupCiphersuiteMismatch = 325,
+    InvalidConfiguration = 326,
+    /**
+     * A proposal this version can't follow has been received, e.g. a ReInit
+     */
+    UnsupportedProposal = 327,
+    E2eiEnrollmentNotDone = 400,
+    E2eIdentityError = 401,
+    InvalidCertificateChain = 402,
+    DomainNameNotFound = 403,
+    DomainNamesDontMatch = 404,
+    DuplicateDomainName = 405,
+    EmptyTrustAnchorUpdate = 406,
+    DuplicateCertificateChain = 407,
+    /**
+     * A certificate could not be parsed
+     */
+    CertificateParsingError = 408,
+    MlsNotInitialized = 500,
+    ProteusNotInitialized = 501,
+    ProteusSupportNotEnabled = 502,
+    CallbacksNotSet = 503,
+    ConsumerError = 504,
+    UnsupportedCiphersuite = 505,
+    /**
+     * A bug on our side
+     */
+    ImplementationError = 506,
+    /**
+     * The operation was cancelled by the caller
+     */
+    OperationCancelled = 507,
+    /**
+     * Proteus error, the detailed code is given by {@link CoreCryptoError.proteusErrorCode}
+     */
+    ProteusError = 600,
+    CryptoboxMigrationError = 601,
+    /**
+     * Another instance sharing the database used the Proteus session in the meantime. Retrying uses its latest state
+     */
+    ProteusSessionOutOfSync = 602,
+    /**
+     * The remote identity of the Proteus session changed and the change was not confirmed through
+     * {@link CoreCryptoCallbacks.proteusIdentityChanged}
+     */
+    ProteusIdentityChanged = 603,
+    /**
+     * The signatures of an identity binding do not match the Proteus identity and the MLS credential it binds
+     */
+    InvalidIdentityBinding = 604,
+    KeyStoreError = 700,
+    /**
+     * The requested entity is not in the keystore
+     */
+    MissingKeyInStore = 701,
+    /**
+     * There is not enough storage left to persist the data
+     */
+    StorageQuotaExceeded = 702,
+    /**
+     * A keystore transaction was started while another one is in progress or ended while none is
+     */
+    TransactionError = 703,
+    /**
+     * Another instance (e.g. in another tab) holds the database, see {@link CoreCryptoDeferredParams.takeOverStore}
+     */
+    StoreAlreadyInUse = 704,
+    /**
+     * The database cannot be accessed until the device is unlocked. Only on iOS
+     */
+    DeviceLocked = 705,
+    /**
+     * The browser evicted the database under storage pressure, losing the keying material it held. The device has
+     * to be enrolled again
+     */
+    StoreEvicted = 706,
+    MlsError = 800,
+    MlsProviderError = 801,
+    /**
+     * Some data could not be (de)serialized or converted
+     */
+    SerializationError = 900,
+    IoError = 901,
+}
+
 interface CoreCryptoRichError {
     errorName: string;
     message: string;
     rustStackTrace: string;
     proteusErrorCode: number;
+    errorCode: CoreCryptoErrorCode;
+    context: string;
 }
 
 /**
@@ -51,6 +231,14 @@ interface CoreCryptoRichError {
 export class CoreCryptoError extends Error {
     rustStackTrace: string;
     proteusErrorCode: number;
+    /**
+     * Flattened code to switch on rather than parsing the message
+     */
+    errorCode: CoreCryptoErrorCode;
+    /**
+     * Detailed description including all the nested errors, meant for logs and bug reports
+     */
+    context: string;
 
     private constructor(
         msg: string,
@@ -64,6 +252,8 @@ export class CoreCryptoError extends Error {
         this.name = richError.errorName;
         this.rustStackTrace = richError.rustStackTrace;
         this.proteusErrorCode = richError.proteusErrorCode;
+        this.errorCode = richError.errorCode;
+        this.context = richError.context;
     }
 
     private static fallback(msg: string, ...params: any[]): Error {
@@ -181,6 +371,30 @@ export interface ConversationConfiguration {
      * Implementation specific configuration
      */
     custom?: CustomConfiguration;
+    /**
+     * Capabilities every member of the conversation has to support
+     */
+    requiredCapabilities?: RequiredCapabilities;
+}
+
+/**
+ * Capabilities every member of a conversation has to support. Adding a client whose KeyPackage does not support them
+ * fails with {@link CoreCryptoErrorCode.MissingRequiredCapabilities}.
+ * Beware that the KeyPackages generated by CoreCrypto only support the default extensions and proposals
+ */
+export interface RequiredCapabilities {
+    /**
+     * Non-default extension types, as registered by the IANA. Empty by default
+     */
+    extensionTypes?: number[];
+    /**
+     * Non-default proposal types, as registered by the IANA. Empty by default
+     */
+    proposalTypes?: number[];
+    /**
+     * Credential types. Both Basic and X509 by default
+     */
+    credentialTypes?: CredentialType[];
 }
 
 /**
@@ -197,13 +411,31 @@ export enum WirePolicy {
     Ciphertext = 0x0002,
 }
 
+/**
+ * see [core_crypto::prelude::MlsEpochFence]
+ */
+export enum EpochFence {
+    /**
+     * The message is encrypted for the current epoch, as if there was no pending commit
+     */
+    Disabled = 0x0000,
+    /**
+     * Encrypting fails with {@link CoreCryptoErrorCode.PendingCommitInFlight}
+     */
+    FailFast = 0x0001,
+    /**
+     * The message is queued and encrypted once the pending commit is resolved, see {@link CoreCrypto.takeFencedMessages}
+     */
+    Queue = 0x0002,
+}
+
 /**
  * Implementation specific configuration object for a conversation
  */
 export interface CustomConfiguration {
     /**
-     * Duration in seconds after which we will automatically force a self_update commit
-     * Note: This isn't currently implemented
+     * Duration in seconds after which the own key material should be rotated with a self update commit.
+     * See {@link CoreCrypto.conversationsNeedingKeyUpdate}
      */
     keyRotationSpan?: number;
     /**
@@ -211,6 +443,226 @@ export interface CustomConfiguration {
      * Note: Ciphertext is not currently supported by wire-server
      */
     wirePolicy?: WirePolicy;
+    /**
+     * How many application messages of a sender, older than the latest one received from it in the epoch, can still be
+     * decrypted. Keeping their secrets weakens forward secrecy within an epoch. Defaults to 2
+     */
+    outOfOrderTolerance?: number;
+    /**
+     * How many application messages of a sender can be skipped. Deriving the skipped secrets costs time so a large
+     * value lets a malicious member slow the client down with a single message. Defaults to 1000
+     */
+    maximumForwardDistance?: number;
+    /**
+     * How many epochs an application message can be behind the current epoch and still be accepted. Older messages are
+     * rejected with {@link CoreCryptoErrorCode.ApplicationMessageTooOld} even when they could be decrypted. Any
+     * decryptable message is accepted when not set
+     */
+    maxApplicationMessageEpochAge?: number;
+    /**
+     * What happens to the messages encrypted while a commit of this client is in flight, i.e. neither accepted with
+     * {@link CoreCrypto.commitAccepted} nor cleared yet. Disabled when not set
+     */
+    epochFence?: EpochFence;
+    /**
+     * Ciphersuites a conversation joined by Welcome is allowed to use. Any is accepted when empty or not set.
+     * A Welcome for any other ciphersuite is rejected with {@link CoreCryptoErrorCode.JoinPolicyViolation}
+     */
+    allowedCiphersuites?: Ciphersuite[];
+    /**
+     * Reject Welcomes for conversations in which a member has a Basic credential, e.g. when end-to-end identity is
+     * mandated. Defaults to false
+     */
+    requireX509Credentials?: boolean;
+}
+
+/** @hidden */
+const customConfigurationFfi = (configuration?: CustomConfiguration): CustomConfigurationFfi => {
+    const {
+        keyRotationSpan,
+        wirePolicy,
+        outOfOrderTolerance,
+        maximumForwardDistance,
+        maxApplicationMessageEpochAge,
+        epochFence,
+        allowedCiphersuites,
+        requireX509Credentials,
+    } = configuration || {};
+    return new CustomConfigurationFfi(
+        keyRotationSpan,
+        wirePolicy,
+        outOfOrderTolerance,
+        maximumForwardDistance,
+        maxApplicationMessageEpochAge,
+        epochFence,
+        allowedCiphersuites && Uint16Array.of(...allowedCiphersuites.map((cs) => cs.valueOf())),
+        requireX509Credentials
+    );
+};
+
+/** @hidden */
+const conversationConfigurationFfi = (configuration?: ConversationConfiguration): ConversationConfigurationFfi => {
+    const {
+        ciphersuite,
+        externalSenders,
+        custom = {},
+        requiredCapabilities = {},
+    } = configuration || {};
+    const { extensionTypes, proposalTypes, credentialTypes } =
+        requiredCapabilities;
+    return new ConversationConfigurationFfi(
+        ciphersuite,
+        externalSenders,
+        custom?.keyRotationSpan,
+        custom?.wirePolicy,
+        custom?.outOfOrderTolerance,
+        custom?.maximumForwardDistance,
+        custom?.maxApplicationMessageEpochAge,
+        custom?.epochFence,
+        extensionTypes && Uint16Array.of(...extensionTypes),
+        proposalTypes && Uint16Array.of(...proposalTypes),
+        credentialTypes &&
+            Uint16Array.of(...credentialTypes.map((ct) => ct.valueOf())),
+        custom?.allowedCiphersuites &&
+            Uint16Array.of(...custom.allowedCiphersuites.map((cs) => cs.valueOf())),
+        custom?.requireX509Credentials
+    );
+};
+
+/**
+ * Builds a {@link CustomConfiguration} which is checked up front, rather than failing once a conversation is joined
+ * with it. Requires {@link CoreCrypto.init} or {@link CoreCrypto.deferredInit} to have been called
+ *
+ * @example
+ * ```ts
+ * const custom = new CustomConfigurationBuilder()
+ *     .keyRotationSpan(86400)
+ *     .requireX509Credentials(true)
+ *     .build();
+ * ```
+ */
+export class CustomConfigurationBuilder {
+    #configuration: CustomConfiguration = {};
+
+    /** see {@link CustomConfiguration.keyRotationSpan} */
+    keyRotationSpan(keyRotationSpan: number): this {
+        this.#configuration.keyRotationSpan = keyRotationSpan;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.wirePolicy} */
+    wirePolicy(wirePolicy: WirePolicy): this {
+        this.#configuration.wirePolicy = wirePolicy;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.outOfOrderTolerance} */
+    outOfOrderTolerance(outOfOrderTolerance: number): this {
+        this.#configuration.outOfOrderTolerance = outOfOrderTolerance;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.maximumForwardDistance} */
+    maximumForwardDistance(maximumForwardDistance: number): this {
+        this.#configuration.maximumForwardDistance = maximumForwardDistance;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.maxApplicationMessageEpochAge} */
+    maxApplicationMessageEpochAge(maxApplicationMessageEpochAge: number): this {
+        this.#configuration.maxApplicationMessageEpochAge = maxApplicationMessageEpochAge;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.epochFence} */
+    epochFence(epochFence: EpochFence): this {
+        this.#configuration.epochFence = epochFence;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.allowedCiphersuites} */
+    allowedCiphersuites(allowedCiphersuites: Ciphersuite[]): this {
+        this.#configuration.allowedCiphersuites = allowedCiphersuites;
+        return this;
+    }
+
+    /** see {@link CustomConfiguration.requireX509Credentials} */
+    requireX509Credentials(requireX509Credentials: boolean): this {
+        this.#configuration.requireX509Credentials = requireX509Credentials;
+        return this;
+    }
+
+    /**
+     * @returns the configuration, once checked
+     * @throws {@link CoreCryptoErrorCode.InvalidConfiguration} naming the first invalid field in its message
+     */
+    build(): CustomConfiguration {
+        const config = customConfigurationFfi(this.#configuration);
+        try {
+            config.validate();
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        } finally {
+            config.free();
+        }
+        return { ...this.#configuration };
+    }
+}
+
+/**
+ * Builds a {@link ConversationConfiguration} which is checked up front, rather than failing once the conversation is
+ * created or a member is added. Requires {@link CoreCrypto.init} or {@link CoreCrypto.deferredInit} to have been
+ * called
+ *
+ * @example
+ * ```ts
+ * const configuration = new ConversationConfigurationBuilder(Ciphersuite.MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519)
+ *     .externalSenders([deliveryServiceKey])
+ *     .custom(new CustomConfigurationBuilder().requireX509Credentials(true).build())
+ *     .build(CredentialType.X509);
+ * ```
+ */
+export class ConversationConfigurationBuilder {
+    #configuration: ConversationConfiguration;
+
+    constructor(ciphersuite: Ciphersuite) {
+        this.#configuration = { ciphersuite };
+    }
+
+    /** see {@link ConversationConfiguration.externalSenders} */
+    externalSenders(externalSenders: Uint8Array[]): this {
+        this.#configuration.externalSenders = externalSenders;
+        return this;
+    }
+
+    /** see {@link CustomConfigurationBuilder} */
+    custom(custom: CustomConfiguration): this {
+        this.#configuration.custom = custom;
+        return this;
+    }
+
+    /** see {@link ConversationConfiguration.requiredCapabilities} */
+    requiredCapabilities(requiredCapabilities: RequiredCapabilities): this {
+        this.#configuration.requiredCapabilities = requiredCapabilities;
+        return this;
+    }
+
+    /**
+     * @param creatorCredentialType - kind of credential the conversation will be created with
+     * @returns the configuration, once checked
+     * @throws {@link CoreCryptoErrorCode.InvalidConfiguration} naming the first invalid field in its message
+     */
+    build(creatorCredentialType: CredentialType): ConversationConfiguration {
+        const config = conversationConfigurationFfi(this.#configuration);
+        try {
+            config.validate(creatorCredentialType);
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        } finally {
+            config.free();
+        }
+        return { ...this.#configuration };
+    }
 }
 
 /**
@@ -300,6 +752,54 @@ export interface CommitBundle {
     groupInfo: GroupInfoBundle;
 }
 
+/**
+ * Kind of commit generated by {@link CoreCrypto.commitWithOperationKey}
+ */
+export enum CommitOperationType {
+    /**
+     * Adds the clients whose key packages are given, see {@link CoreCrypto.addClientsToConversation}
+     */
+    AddClients = 0x01,
+    /**
+     * Removes the clients whose ids are given, see {@link CoreCrypto.removeClientsFromConversation}
+     */
+    RemoveClients = 0x02,
+    /**
+     * Updates the own leaf, see {@link CoreCrypto.updateKeyingMaterial}
+     */
+    UpdateKeyingMaterial = 0x03,
+    /**
+     * Commits the pending proposals, if any, see {@link CoreCrypto.commitPendingProposals}
+     */
+    CommitPendingProposals = 0x04,
+}
+
+/**
+ * Commit-producing operation run by {@link CoreCrypto.commitWithOperationKey}
+ */
+export type CommitOperation =
+    | { type: CommitOperationType.AddClients; keyPackages: Uint8Array[] }
+    | { type: CommitOperationType.RemoveClients; clientIds: ClientId[] }
+    | { type: CommitOperationType.UpdateKeyingMaterial }
+    | { type: CommitOperationType.CommitPendingProposals };
+
+/**
+ * Commit generated by {@link CoreCrypto.commitWithOperationKey}
+ */
+export interface IdempotentCommitBundle extends CommitBundle {
+    /**
+     * New CRL distribution points that appeared by the introduction of a new credential
+     */
+    crlNewDistributionPoints?: string[];
+    /**
+     * Whether the commit was already generated by a previous call with the same operation key, in which case it has
+     * to be sent as is instead of a new one
+     *
+     * @readonly
+     */
+    replayed: boolean;
+}
+
 /**
  * Wraps a GroupInfo in order to efficiently upload it to the Delivery Service.
  * This is not part of MLS protocol but parts might be standardized at some point.
@@ -370,7 +870,8 @@ export interface RotateBundle {
      */
     newKeyPackages: Uint8Array[];
     /**
-     * All the now deprecated KeyPackages. Once deleted remotely, delete them locally with {@link CoreCrypto.deleteKeyPackages}
+     * All the now deprecated KeyPackages, to be deleted remotely. They are deleted locally once all the commits have been accepted
+     * (or with {@link CoreCrypto.deleteKeyPackages} when there was no conversation to rotate)
      *
      * @readonly
      */
@@ -381,6 +882,41 @@ export interface RotateBundle {
     crlNewDistributionPoints?: string[];
 }
 
+/**
+ * Severity of what {@link CoreCrypto} logs
+ */
+export enum CoreCryptoLogLevel {
+    /**
+     * Details only useful when debugging CoreCrypto itself
+     */
+    Debug = 1,
+    /**
+     * Normal operation worth knowing about
+     */
+    Info = 2,
+    /**
+     * Something went wrong but CoreCrypto recovered from it
+     */
+    Warn = 3,
+    /**
+     * Something went wrong and could not be recovered from
+     */
+    Error = 4,
+}
+
+/**
+ * Receives the issues {@link CoreCrypto} recovered from internally, e.g. a Proteus session which could not be found
+ * while saving it, see {@link CoreCrypto.setLogger}
+ */
+export interface CoreCryptoLogger {
+    /**
+     * @param level - severity of the entry
+     * @param message - human readable description of what happened
+     * @param context - JSON object with the details e.g. the conversation id, `{}` when there are none
+     */
+    log: (level: CoreCryptoLogLevel, message: string, context: string) => void;
+}
+
 /**
  * Params for CoreCrypto deferred initialization
  * Please note that the `entropySeed` parameter MUST be exactly 32 bytes
@@ -412,6 +948,38 @@ export interface CoreCryptoDeferredParams {
      * Number of initial KeyPackage to create when initializing the client
      */
     nbKeyPackage?: number;
+    /**
+     * Allows exporting and importing the MLS client identity with {@link CoreCrypto.exportIdentity} and
+     * {@link CoreCrypto.importIdentity}. Defaults to `false`
+     */
+    allowIdentityExport?: boolean;
+    /**
+     * Opens the database even if another instance (e.g. in another tab) holds it. Every write of that instance then
+     * fails with {@link CoreCryptoErrorCode.StoreAlreadyInUse} until it calls {@link CoreCrypto.takeOverStore}.
+     * Otherwise initialization fails with this same error. Defaults to `false`
+     */
+    takeOverStore?: boolean;
+    /**
+     * Returns without restoring the persisted conversations, which delays initialization on accounts with thousands of
+     * them. Conversations are loaded from the database when first used and the others can be restored in the
+     * background with {@link CoreCrypto.restoreNextGroups}. Defaults to `false`
+     */
+    lazyGroupRestore?: boolean;
+    /**
+     * Does not keep the conversations in memory, for devices which can't afford it: each operation loads the
+     * conversation from the database and drops it once persisted. Defaults to `false`
+     */
+    disableGroupCache?: boolean;
+    /**
+     * Clock skew in seconds tolerated when validating KeyPackage lifetimes and certificate validity periods, see
+     * {@link CoreCrypto.setClockSkewLeeway}. Defaults to 1 hour
+     */
+    clockSkewLeewaySeconds?: number;
+    /**
+     * Registered before anything else so that it also gets what happens while restoring the persisted state, see
+     * {@link CoreCrypto.setLogger}
+     */
+    logger?: CoreCryptoLogger;
 }
 
 /**
@@ -426,6 +994,89 @@ export interface CoreCryptoParams extends CoreCryptoDeferredParams {
     clientId: ClientId;
 }
 
+/**
+ * Everything needed to open, in a Web Worker, the {@link CoreCrypto} instance handed over by
+ * {@link CoreCrypto.transferToWorker}, with the same options it was initialized with. It is a plain object, hence
+ * can be posted to the worker with
+ * {@link https://developer.mozilla.org/en-US/docs/Web/API/Worker/postMessage | postMessage}.
+ *
+ * Two options are not part of it:
+ * - {@link CoreCryptoDeferredParams.entropySeed}, since two instances seeded alike would generate the same randomness
+ * - {@link CoreCryptoDeferredParams.logger}, a function which can't be posted. Give it to
+ *   {@link CoreCrypto.fromTransferHandle} instead
+ *
+ * **CAUTION**: it contains the encryption key of the database, only post it to a worker of the same origin
+ */
+export interface CoreCryptoTransferHandle {
+    /**
+     * see {@link CoreCryptoDeferredParams.databaseName}
+     */
+    databaseName: string;
+    /**
+     * see {@link CoreCryptoDeferredParams.key}
+     */
+    key: string;
+    /**
+     * MLS Client ID, unset when MLS had not been initialized yet
+     */
+    clientId?: ClientId;
+    /**
+     * see {@link CoreCryptoDeferredParams.ciphersuites}
+     */
+    ciphersuites: Ciphersuite[];
+    /**
+     * see {@link CoreCryptoDeferredParams.wasmFilePath}
+     */
+    wasmFilePath?: string;
+    /**
+     * see {@link CoreCryptoDeferredParams.nbKeyPackage}
+     */
+    nbKeyPackage?: number;
+    /**
+     * see {@link CoreCryptoDeferredParams.allowIdentityExport}
+     */
+    allowIdentityExport?: boolean;
+    /**
+     * see {@link CoreCryptoDeferredParams.takeOverStore}
+     */
+    takeOverStore?: boolean;
+    /**
+     * see {@link CoreCryptoDeferredParams.lazyGroupRestore}
+     */
+    lazyGroupRestore?: boolean;
+    /**
+     * see {@link CoreCryptoDeferredParams.disableGroupCache}
+     */
+    disableGroupCache?: boolean;
+    /**
+     * see {@link CoreCryptoDeferredParams.clockSkewLeewaySeconds}, as last changed by
+     * {@link CoreCrypto.setClockSkewLeeway}
+     */
+    clockSkewLeewaySeconds?: number;
+}
+
+/**
+ * Outcome of rejoining a conversation, see {@link CoreCrypto.rejoinAllFromGroupInfos}
+ */
+export interface RejoinResult {
+    /**
+     * @readonly
+     */
+    conversationId: ConversationId;
+    /**
+     * The external commit to send, absent when the conversation could not be joined
+     *
+     * @readonly
+     */
+    bundle?: ConversationInitBundle;
+    /**
+     * Why the conversation could not be joined
+     *
+     * @readonly
+     */
+    errorCode?: CoreCryptoErrorCode;
+}
+
 export interface ConversationInitBundle {
     /**
      * Conversation ID of the conversation created
@@ -517,20 +1168,46 @@ export interface DecryptedMessage {
      * New CRL distribution points that appeared by the introduction of a new credential
      */
     crlNewDistributionPoints?: string[];
+    /**
+     * Only set when the decrypted message is a commit. Describes the proposals it applied
+     */
+    commitDelta?: CommitDelta;
 }
 
 /**
- * Almost same as {@link DecryptedMessage} but avoids recursion
+ * What a commit changed in a conversation, see {@link DecryptedMessage.commitDelta}
  */
-export interface BufferedDecryptedMessage {
+export interface CommitDelta {
     /**
-     * see {@link DecryptedMessage.message}
+     * Clients added by the commit, including the committer of an external commit
      */
-    message?: Uint8Array;
+    added: ClientId[];
     /**
-     * see {@link DecryptedMessage.proposals}
+     * Clients removed by the commit
      */
-    proposals: ProposalBundle[];
+    removed: ClientId[];
+    /**
+     * Clients whose leaf was updated, either by an Update proposal or, for the committer, by the commit itself
+     */
+    updated: ClientId[];
+    /**
+     * Whether the leaf of this client was updated, either by one of its Update proposals or because it is the committer
+     */
+    selfUpdated: boolean;
+}
+
+/**
+ * Almost same as {@link DecryptedMessage} but avoids recursion
+ */
+export interface BufferedDecryptedMessage {
+    /**
+     * see {@link DecryptedMessage.message}
+     */
+    message?: Uint8Array;
+    /**
+     * see {@link DecryptedMessage.proposals}
+     */
+    proposals: ProposalBundle[];
     /**
      * see {@link DecryptedMessage.isActive}
      */
@@ -555,6 +1232,10 @@ export interface BufferedDecryptedMessage {
      * see {@link DecryptedMessage.crlNewDistributionPoints}
      */
     crlNewDistributionPoints?: string[];
+    /**
+     * see {@link DecryptedMessage.commitDelta}
+     */
+    commitDelta?: CommitDelta;
 }
 
 /**
@@ -620,6 +1301,16 @@ const mapWireIdentity = (ffiIdentity?: CoreCryptoFfiTypes.WireIdentity): WireIde
     };
 };
 
+const mapCommitDelta = (ffiDelta?: CoreCryptoFfiTypes.CommitDelta): CommitDelta|undefined => {
+    if (!ffiDelta) { return undefined; }
+    return {
+        added: ffiDelta.added,
+        removed: ffiDelta.removed,
+        updated: ffiDelta.updated,
+        selfUpdated: ffiDelta.self_updated,
+    };
+};
+
 export interface AcmeDirectory {
     /**
      * URL for fetching a new nonce. Use this only for creating a new account.
@@ -713,6 +1404,179 @@ export enum ProposalType {
      * This allows to propose to update the client keying material (i.e. keypackage rotation) and the group root key
      */
     Update,
+    /**
+     * This allows to propose to inject an external pre-shared key, see {@link CoreCrypto.addExternalPsk}
+     */
+    PreSharedKey,
+}
+
+/**
+ * Type of a proposal pending in a conversation, see {@link CoreCrypto.pendingProposals}
+ */
+export enum PendingProposalType {
+    Add = 0x01,
+    Update = 0x02,
+    Remove = 0x03,
+    PreSharedKey = 0x04,
+    ReInit = 0x05,
+    ExternalInit = 0x06,
+    GroupContextExtensions = 0x07,
+    Other = 0xff,
+}
+
+/**
+ * Describes a proposal pending in a conversation
+ */
+/**
+ * Outcome of {@link CoreCrypto.migrateProteusConversation}
+ */
+export interface ProteusMigrationReport {
+    /**
+     * Proteus session the conversation has been migrated from
+     *
+     * @readonly
+     */
+    sessionId: string;
+    /**
+     * MLS conversation now standing for the Proteus session
+     *
+     * @readonly
+     */
+    conversationId: ConversationId;
+    /**
+     * Fingerprint of the remote identity of the Proteus session, to be matched against the credential the peer
+     * joins the MLS conversation with
+     *
+     * @readonly
+     */
+    remoteFingerprint: string;
+    /**
+     * Whether the MLS conversation has been created. `false` when it already existed e.g. when retrying
+     *
+     * @readonly
+     */
+    conversationCreated: boolean;
+}
+
+/**
+ * Outcome of migrating a Proteus session, see {@link CoreCrypto.migrateProteusConversations}
+ */
+export interface ProteusMigrationResult {
+    /**
+     * @readonly
+     */
+    sessionId: string;
+    /**
+     * Report of the migration, absent when the session could not be migrated
+     *
+     * @readonly
+     */
+    report?: ProteusMigrationReport;
+    /**
+     * Why the session could not be migrated
+     *
+     * @readonly
+     */
+    errorCode?: CoreCryptoErrorCode;
+}
+
+/**
+ * Metadata of a persisted conversation, see {@link CoreCrypto.listConversations}
+ */
+export interface ConversationSummary {
+    /**
+     * Conversation ID
+     *
+     * @readonly
+     */
+    id: ConversationId;
+    /**
+     * Ciphersuite of the conversation
+     *
+     * @readonly
+     */
+    ciphersuite: Ciphersuite;
+    /**
+     * Epoch of the conversation when it was last persisted
+     *
+     * @readonly
+     */
+    epoch: number;
+    /**
+     * Number of members of the conversation when it was last persisted
+     *
+     * @readonly
+     */
+    memberCount: number;
+    /**
+     * Unix timestamp (in seconds) of the last time the conversation was persisted
+     *
+     * @readonly
+     */
+    lastPersisted: number;
+    /**
+     * ID of the parent conversation, for a sub-conversation
+     *
+     * @readonly
+     */
+    parentId?: ConversationId;
+}
+
+/**
+ * What has been removed along with a conversation, see {@link CoreCrypto.wipeConversation}
+ */
+export interface ConversationWipeReport {
+    /**
+     * Proposals which were not committed yet
+     *
+     * @readonly
+     */
+    pendingProposals: number;
+    /**
+     * Encryption keypairs of the own leaf node and of the current epoch
+     *
+     * @readonly
+     */
+    encryptionKeypairs: number;
+    /**
+     * Messages buffered because they were received before the commit creating their epoch
+     *
+     * @readonly
+     */
+    bufferedMessages: number;
+    /**
+     * Whether a pending group created when joining by external commit existed
+     *
+     * @readonly
+     */
+    pendingGroup: boolean;
+}
+
+export interface ProposalInfo {
+    /**
+     * Kind of proposal
+     *
+     * @readonly
+     */
+    proposalType: PendingProposalType;
+    /**
+     * Client who authored the proposal. Undefined for external senders
+     *
+     * @readonly
+     */
+    proposer?: ClientId;
+    /**
+     * Clients added, removed or updated by this proposal
+     *
+     * @readonly
+     */
+    affectedClients: ClientId[];
+    /**
+     * Unique identifier of a proposal. Use this in {@link CoreCrypto.clearPendingProposal} to roll back (delete) the proposal
+     *
+     * @readonly
+     */
+    proposalRef: ProposalRef;
 }
 
 /**
@@ -745,6 +1609,16 @@ export interface RemoveProposalArgs extends ProposalArgs {
     clientId: ClientId;
 }
 
+/**
+ * Arguments for a proposal of type `PreSharedKey`
+ */
+export interface PskProposalArgs extends ProposalArgs {
+    /**
+     * Id of the external pre-shared key, added with {@link CoreCrypto.addExternalPsk}
+     */
+    pskId: Uint8Array;
+}
+
 /**
  * MLS External Proposal type
  */
@@ -827,6 +1701,168 @@ export interface CoreCryptoCallbacks {
         existingClients: Uint8Array[],
         parent_conversation_clients?: Uint8Array[]
     ) => Promise<boolean>;
+
+    /**
+     * This callback is called by CoreCrypto before merging a commit, received from another member, which adds or
+     * removes clients from the conversation. Rejecting it makes {@link CoreCrypto.decryptMessage} fail with
+     * {@link CoreCryptoErrorCode.UnauthorizedMemberChange} and leaves the conversation untouched.
+     *
+     * @param conversationId - id of the group/conversation
+     * @param committer - id of the client who created the commit
+     * @param addedClients - clients added to the conversation by the commit
+     * @param removedClients - clients removed from the conversation by the commit
+     * @returns whether the committer is authorized to perform these membership changes
+     */
+    memberChangeAuthorize: (
+        conversationId: Uint8Array,
+        committer: Uint8Array,
+        addedClients: Uint8Array[],
+        removedClients: Uint8Array[]
+    ) => Promise<boolean>;
+
+    /**
+     * This callback is called by CoreCrypto when a Proteus session is created with a remote identity other than the
+     * one previously recorded for the same session id, e.g. because the remote client has been reinstalled or because
+     * someone impersonates it. The user should be asked to confirm the new identity. Rejecting it makes
+     * {@link CoreCrypto.proteusSessionFromPrekey} and {@link CoreCrypto.proteusSessionFromMessage} fail with
+     * {@link CoreCryptoErrorCode.ProteusIdentityChanged}.
+     *
+     * @param sessionId - id of the Proteus session
+     * @param previousFingerprint - hex-encoded fingerprint of the remote identity recorded so far
+     * @param newFingerprint - hex-encoded fingerprint of the remote identity the session is created with
+     * @returns whether the new remote identity is trusted
+     */
+    proteusIdentityChanged: (
+        sessionId: string,
+        previousFingerprint: string,
+        newFingerprint: string
+    ) => Promise<boolean>;
+}
+
+/**
+ * Operations performed within a transaction, see {@link CoreCrypto.transaction}.
+ * It must not be used anymore once the transaction is over, every call fails with {@link CoreCryptoErrorCode.ConsumerError} then
+ */
+export class CoreCryptoContext {
+    /** @hidden */
+    #ctx: CoreCryptoFfiTypes.CoreCryptoContext;
+
+    /** @hidden */
+    constructor(ctx: CoreCryptoFfiTypes.CoreCryptoContext) {
+        this.#ctx = ctx;
+    }
+
+    /** @hidden */
+    static #commitBundle(ffiRet: CoreCryptoFfiTypes.CommitBundle): CommitBundle {
+        const gi = ffiRet.group_info;
+        return {
+            welcome: ffiRet.welcome,
+            commit: ffiRet.commit,
+            groupInfo: {
+                encryptionType: gi.encryption_type,
+                ratchetTreeType: gi.ratchet_tree_type,
+                payload: gi.payload,
+            },
+        };
+    }
+
+    /**
+     * See {@link CoreCrypto.createConversation}
+     */
+    async createConversation(
+        conversationId: ConversationId,
+        creatorCredentialType: CredentialType,
+        configuration: ConversationConfiguration = {}
+    ): Promise<void> {
+        try {
+            const config = conversationConfigurationFfi(configuration);
+            return await CoreCryptoError.asyncMapErr(
+                this.#ctx.create_conversation(
+                    conversationId,
+                    creatorCredentialType,
+                    config
+                )
+            );
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * See {@link CoreCrypto.addClientsToConversation}
+     */
+    async addClientsToConversation(
+        conversationId: ConversationId,
+        keyPackages: Uint8Array[]
+    ): Promise<MemberAddedMessages> {
+        const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+            await CoreCryptoError.asyncMapErr(
+                this.#ctx.add_clients_to_conversation(
+                    conversationId,
+                    keyPackages
+                )
+            );
+
+        return {
+            ...CoreCryptoContext.#commitBundle(ffiRet),
+            welcome: ffiRet.welcome,
+            crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+        };
+    }
+
+    /**
+     * See {@link CoreCrypto.removeClientsFromConversation}
+     */
+    async removeClientsFromConversation(
+        conversationId: ConversationId,
+        clientIds: ClientId[]
+    ): Promise<CommitBundle> {
+        const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+            await CoreCryptoError.asyncMapErr(
+                this.#ctx.remove_clients_from_conversation(
+                    conversationId,
+                    clientIds
+                )
+            );
+        return CoreCryptoContext.#commitBundle(ffiRet);
+    }
+
+    /**
+     * See {@link CoreCrypto.updateKeyingMaterial}
+     */
+    async updateKeyingMaterial(
+        conversationId: ConversationId
+    ): Promise<CommitBundle> {
+        const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+            await CoreCryptoError.asyncMapErr(
+                this.#ctx.update_keying_material(conversationId)
+            );
+        return CoreCryptoContext.#commitBundle(ffiRet);
+    }
+
+    /**
+     * See {@link CoreCrypto.commitAccepted}
+     */
+    async commitAccepted(
+        conversationId: ConversationId
+    ): Promise<BufferedDecryptedMessage[] | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#ctx.commit_accepted(conversationId)
+        );
+    }
+
+    /**
+     * See {@link CoreCrypto.commitPendingProposals}
+     */
+    async commitPendingProposals(
+        conversationId: ConversationId
+    ): Promise<CommitBundle | undefined> {
+        const ffiRet: CoreCryptoFfiTypes.CommitBundle | undefined =
+            await CoreCryptoError.asyncMapErr(
+                this.#ctx.commit_pending_proposals(conversationId)
+            );
+        return ffiRet ? CoreCryptoContext.#commitBundle(ffiRet) : undefined;
+    }
 }
 
 /**
@@ -837,6 +1873,8 @@ export class CoreCrypto {
     static #module: typeof CoreCryptoFfiTypes;
     /** @hidden */
     #cc: CoreCryptoFfiTypes.CoreCrypto;
+    /** @hidden */
+    #transferParams: Omit<CoreCryptoTransferHandle, "clientId">;
 
     /**
      * Should only be used internally
@@ -904,8 +1942,17 @@ export class CoreCrypto {
         ciphersuites,
         entropySeed,
         nbKeyPackage,
+        allowIdentityExport,
+        takeOverStore,
+        lazyGroupRestore,
+        disableGroupCache,
+        clockSkewLeewaySeconds,
+        logger,
     }: CoreCryptoParams): Promise<CoreCrypto> {
         await this.#loadModule(wasmFilePath);
+        if (logger) {
+            this.setLogger(logger);
+        }
 
         let cs = ciphersuites.map((cs) => cs.valueOf());
         const cc = await CoreCryptoError.asyncMapErr(
@@ -915,10 +1962,26 @@ export class CoreCrypto {
                 clientId,
                 Uint16Array.of(...cs),
                 entropySeed,
-                nbKeyPackage
+                nbKeyPackage,
+                allowIdentityExport,
+                takeOverStore,
+                lazyGroupRestore,
+                disableGroupCache,
+                clockSkewLeewaySeconds
             )
         );
-        return new this(cc);
+        return new this(cc, {
+            databaseName,
+            key,
+            ciphersuites,
+            wasmFilePath,
+            nbKeyPackage,
+            allowIdentityExport,
+            takeOverStore,
+            lazyGroupRestore,
+            disableGroupCache,
+            clockSkewLeewaySeconds,
+        });
     }
 
     /**
@@ -935,8 +1998,17 @@ export class CoreCrypto {
         entropySeed,
         wasmFilePath,
         nbKeyPackage,
+        allowIdentityExport,
+        takeOverStore,
+        lazyGroupRestore,
+        disableGroupCache,
+        clockSkewLeewaySeconds,
+        logger,
     }: CoreCryptoDeferredParams): Promise<CoreCrypto> {
         await this.#loadModule(wasmFilePath);
+        if (logger) {
+            this.setLogger(logger);
+        }
 
         let cs = ciphersuites.map((cs) => cs.valueOf());
         const cc = await CoreCryptoError.asyncMapErr(
@@ -945,10 +2017,26 @@ export class CoreCrypto {
                 key,
                 Uint16Array.of(...cs),
                 entropySeed,
-                nbKeyPackage
+                nbKeyPackage,
+                allowIdentityExport,
+                takeOverStore,
+                lazyGroupRestore,
+                disableGroupCache,
+                clockSkewLeewaySeconds
             )
         );
-        return new this(cc);
+        return new this(cc, {
+            databaseName,
+            key,
+            ciphersuites,
+            wasmFilePath,
+            nbKeyPackage,
+            allowIdentityExport,
+            takeOverStore,
+            lazyGroupRestore,
+            disableGroupCache,
+            clockSkewLeewaySeconds,
+        });
     }
 
     /**
@@ -970,25 +2058,60 @@ export class CoreCrypto {
     }
 
     /**
-     * Generates a MLS KeyPair/CredentialBundle with a temporary, random client ID.
-     * This method is designed to be used in conjunction with {@link CoreCrypto.mlsInitWithClientId} and represents the first step in this process
+     * Exports the MLS client identity (credentials and signature keys) encrypted with `backupKey`, so that it can be
+     * carried over to another device with {@link CoreCrypto.importIdentity}. Conversations are not part of it.
+     * Requires {@link CoreCryptoDeferredParams.allowIdentityExport}
      *
-     * @param ciphersuites - All the ciphersuites supported by this MLS client
-     * @returns This returns the TLS-serialized identity key (i.e. the signature keypair's public key)
+     * @param backupKey - 32 bytes key the backup is encrypted with
+     * @returns the encrypted identity
      */
-    async mlsGenerateKeypair(
-        ciphersuites: Ciphersuite[]
-    ): Promise<Uint8Array[]> {
-        let cs = ciphersuites.map((cs) => cs.valueOf());
+    async exportIdentity(backupKey: Uint8Array): Promise<Uint8Array> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.mls_generate_keypair(Uint16Array.of(...cs))
+            this.#cc.export_identity(backupKey)
         );
     }
 
     /**
-     * Updates the current temporary Client ID with the newly provided one. This is the second step in the externally-generated clients process
+     * Imports an identity exported with {@link CoreCrypto.exportIdentity} and initializes MLS with it, i.e. instead of
+     * {@link CoreCrypto.mlsInit} after {@link CoreCrypto.deferredInit}. Fails with
+     * {@link CoreCryptoErrorCode.IdentityConflict} when the store already contains another identity.
+     * Requires {@link CoreCryptoDeferredParams.allowIdentityExport}
      *
-     * Important: This is designed to be called after {@link CoreCrypto.mlsGenerateKeypair}
+     * @param backup - the exported identity
+     * @param backupKey - key the backup has been encrypted with
+     * @param ciphersuites - All the ciphersuites supported by this MLS client
+     */
+    async importIdentity(
+        backup: Uint8Array,
+        backupKey: Uint8Array,
+        ciphersuites: Ciphersuite[]
+    ): Promise<void> {
+        let cs = ciphersuites.map((cs) => cs.valueOf());
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.import_identity(backup, backupKey, Uint16Array.of(...cs))
+        );
+    }
+
+    /**
+     * Generates a MLS KeyPair/CredentialBundle with a temporary, random client ID.
+     * This method is designed to be used in conjunction with {@link CoreCrypto.mlsInitWithClientId} and represents the first step in this process
+     *
+     * @param ciphersuites - All the ciphersuites supported by this MLS client
+     * @returns This returns the TLS-serialized identity key (i.e. the signature keypair's public key)
+     */
+    async mlsGenerateKeypair(
+        ciphersuites: Ciphersuite[]
+    ): Promise<Uint8Array[]> {
+        let cs = ciphersuites.map((cs) => cs.valueOf());
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.mls_generate_keypair(Uint16Array.of(...cs))
+        );
+    }
+
+    /**
+     * Updates the current temporary Client ID with the newly provided one. This is the second step in the externally-generated clients process
+     *
+     * Important: This is designed to be called after {@link CoreCrypto.mlsGenerateKeypair}
      *
      * @param clientId - The newly-allocated client ID by the MLS Authentication Service
      * @param signaturePublicKeys - The public key you were given at the first step; This is for authentication purposes
@@ -1009,9 +2132,29 @@ export class CoreCrypto {
         );
     }
 
+    /**
+     * Opens, typically in a Web Worker, the {@link CoreCrypto} instance handed over by
+     * {@link CoreCrypto.transferToWorker}
+     *
+     * @param handle - as returned by {@link CoreCrypto.transferToWorker}
+     * @param logger - see {@link CoreCryptoDeferredParams.logger}
+     */
+    static async fromTransferHandle(
+        { clientId, ...params }: CoreCryptoTransferHandle,
+        logger?: CoreCryptoLogger
+    ): Promise<CoreCrypto> {
+        return clientId
+            ? await this.init({ ...params, clientId, logger })
+            : await this.deferredInit({ ...params, logger });
+    }
+
     /** @hidden */
-    private constructor(cc: CoreCryptoFfiTypes.CoreCrypto) {
+    private constructor(
+        cc: CoreCryptoFfiTypes.CoreCrypto,
+        transferParams: Omit<CoreCryptoTransferHandle, "clientId">
+    ) {
         this.#cc = cc;
+        this.#transferParams = transferParams;
     }
 
     /**
@@ -1042,6 +2185,27 @@ export class CoreCrypto {
         await CoreCryptoError.asyncMapErr(this.#cc.close());
     }
 
+    /**
+     * Closes this {@link CoreCrypto} instance so that it can be opened in a Web Worker with
+     * {@link CoreCrypto.fromTransferHandle}, keeping the CPU-heavy operations (e.g. processing a Welcome or a large
+     * commit) off the main thread. A wasm instance can't be shared between threads, hence the worker opens the same
+     * database with its own instance. See {@link CoreCryptoWorkerClient} to then call it from the main thread.
+     *
+     * Callbacks are not part of the handle and have to be registered again in the worker, and so is the logger which
+     * {@link CoreCrypto.fromTransferHandle} takes aside.
+     *
+     * **CAUTION**: This {@link CoreCrypto} instance won't be usable after a call to this method, as with {@link CoreCrypto.close}
+     *
+     * @returns what the worker needs to open the instance, see {@link CoreCryptoTransferHandle}
+     */
+    async transferToWorker(): Promise<CoreCryptoTransferHandle> {
+        const clientId: ClientId | undefined = await CoreCryptoError.asyncMapErr(
+            this.#cc.client_id()
+        );
+        await this.close();
+        return { ...this.#transferParams, clientId };
+    }
+
     /**
      * Registers the callbacks for CoreCrypto to use in order to gain additional information
      *
@@ -1056,6 +2220,8 @@ export class CoreCrypto {
                 callbacks.authorize,
                 callbacks.userAuthorize,
                 callbacks.clientIsExistingGroupUser,
+                callbacks.memberChangeAuthorize,
+                callbacks.proteusIdentityChanged,
                 ctx
             );
             await this.#cc.set_callbacks(wasmCallbacks);
@@ -1065,331 +2231,525 @@ export class CoreCrypto {
     }
 
     /**
-     * Checks if the Client is member of a given conversation and if the MLS Group is loaded up
-     *
-     * @returns Whether the given conversation ID exists
+     * Registers a callback invoked when a write would not fit in the storage left for the origin, before it is
+     * refused with a `StorageQuotaExceeded` error. Gives the application a chance to prompt the user to free some space.
      *
-     * @example
-     * ```ts
-     *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
-     *  const encoder = new TextEncoder();
-     *  if (await cc.conversationExists(encoder.encode("my super chat"))) {
-     *    // Do something
-     *  } else {
-     *    // Do something else
-     *  }
-     * ```
+     * @param callback - called with the size of the write and the space left (when the browser reports it), both in bytes.
+     * Pass `undefined` to unregister it
      */
-    async conversationExists(conversationId: ConversationId): Promise<boolean> {
+    async registerStoragePressureCallback(
+        callback?: (requiredBytes: number, availableBytes?: number) => void
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.conversation_exists(conversationId)
+            this.#cc.set_storage_pressure_callback(callback)
         );
     }
 
     /**
-     * Marks a conversation as child of another one
-     * This will mostly affect the behavior of the callbacks (the parentConversationClients parameter will be filled)
+     * Registers a callback invoked when a conversation could not be persisted, for example because the storage is full.
+     * While one is registered, such a failure is not thrown anymore: the conversation is marked dirty, its latest state
+     * kept in memory and persisted again on the next successful write.
      *
-     * @param childId - conversation identifier of the child conversation
-     * @param parentId - conversation identifier of the parent conversation
+     * @param callback - called with the id of the conversation and the error message. Pass `undefined` to unregister it
      */
-    async markConversationAsChildOf(
-        childId: ConversationId,
-        parentId: ConversationId
+    async registerPersistenceFailureCallback(
+        callback?: (conversationId: ConversationId, error: string) => void
     ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.mark_conversation_as_child_of(childId, parentId)
+            this.#cc.set_persistence_failure_callback(callback)
         );
     }
 
     /**
-     * Returns the current epoch of a conversation
+     * @returns The ids of the conversations whose latest state could not be persisted yet
+     */
+    async dirtyConversations(): Promise<ConversationId[]> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.dirty_conversations());
+    }
+
+    /**
+     * Checks if the Client is member of a given conversation and if the MLS Group is loaded up
      *
-     * @returns the epoch of the conversation
+     * @returns Whether the given conversation ID exists
      *
      * @example
      * ```ts
      *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
      *  const encoder = new TextEncoder();
-     *  console.log(await cc.conversationEpoch(encoder.encode("my super chat")))
+     *  if (await cc.conversationExists(encoder.encode("my super chat"))) {
+     *    // Do something
+     *  } else {
+     *    // Do something else
+     *  }
      * ```
      */
-    async conversationEpoch(conversationId: ConversationId): Promise<number> {
+    async conversationExists(conversationId: ConversationId): Promise<boolean> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.conversation_epoch(conversationId)
+            this.#cc.conversation_exists(conversationId)
         );
     }
 
     /**
-     * Wipes and destroys the local storage of a given conversation / MLS group
+     * Runs a quick self-test of the crypto primitives of every supported ciphersuite (signature, HPKE and AEAD
+     * roundtrips) and of the keystore, meant to detect a broken platform (e.g. WebCrypto quirks) on startup.
+     * Failures are reported rather than thrown so that every check runs.
+     * Only available when the bindings are built with the `self-test` feature
      *
-     * @param conversationId - The ID of the conversation to remove
+     * @returns see {@link SelfTestReport}
      */
-    async wipeConversation(conversationId: ConversationId): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.wipe_conversation(conversationId)
-        );
+    async selfTest(): Promise<SelfTestReport> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.self_test());
     }
 
     /**
-     * Creates a new conversation with the current client being the sole member
-     * You will want to use {@link CoreCrypto.addClientsToConversation} afterwards to add clients to this conversation
+     * Describes the local state to attach to a support ticket, e.g. when a conversation can't be decrypted anymore:
+     * epoch, member count, pending proposals and commit of each conversation along with the number of records of each
+     * kind in the keystore. Key material never appears in the report. At most 256 conversations are described.
      *
-     * @param conversationId - The conversation ID; You can either make them random or let the backend attribute MLS group IDs
-     * @param creatorCredentialType - kind of credential the creator wants to create the group with
-     * @param configuration - configuration of the MLS group
-     * @param configuration.ciphersuite - The {@link Ciphersuite} that is chosen to be the group's
-     * @param configuration.externalSenders - Array of Client IDs that are qualified as external senders within the group
-     * @param configuration.custom - {@link CustomConfiguration}
+     * @param redaction - how identifiers appear in the report, see {@link RedactionLevel}
+     * @returns the report, as a JSON object
      */
-    async createConversation(
-        conversationId: ConversationId,
-        creatorCredentialType: CredentialType,
-        configuration: ConversationConfiguration = {}
-    ) {
-        try {
-            const {
-                ciphersuite,
-                externalSenders,
-                custom = {},
-            } = configuration || {};
-            const config = new ConversationConfigurationFfi(
-                ciphersuite,
-                externalSenders,
-                custom?.keyRotationSpan,
-                custom?.wirePolicy,
-            );
-            const ret = await CoreCryptoError.asyncMapErr(
-                this.#cc.create_conversation(
-                    conversationId,
-                    creatorCredentialType,
-                    config
-                )
-            );
-            return ret;
-        } catch (e) {
-            throw CoreCryptoError.fromStdError(e as Error);
-        }
+    async generateDiagnosticReport(redaction: RedactionLevel = RedactionLevel.Hashed): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.generate_diagnostic_report(redaction));
     }
 
     /**
-     * Decrypts a message for a given conversation.
-     *
-     * Note: you should catch & ignore the following error reasons:
-     * * "We already decrypted this message once"
-     * * "You tried to join with an external commit but did not merge it yet. We will reapply this message for you when you merge your external commit"
-     * * "Incoming message is for a future epoch. We will buffer it until the commit for that epoch arrives"
+     * Health of a conversation based on the number of consecutive messages which could not be decrypted. Only tracked
+     * in memory, hence every conversation is healthy again after a restart
      *
      * @param conversationId - The ID of the conversation
-     * @param payload - The encrypted message buffer
-     *
-     * @returns a {@link DecryptedMessage}. Note that {@link DecryptedMessage#message} is `undefined` when the encrypted payload contains a system message such a proposal or commit
+     * @returns see {@link ConversationHealth}
      */
-    async decryptMessage(
-        conversationId: ConversationId,
-        payload: Uint8Array
-    ): Promise<DecryptedMessage> {
-        if (!payload?.length) {
-            throw new Error("decryptMessage payload is empty or null");
-        }
-
-        try {
-            const ffiDecryptedMessage: CoreCryptoFfiTypes.DecryptedMessage =
-                await CoreCryptoError.asyncMapErr(
-                    this.#cc.decrypt_message(conversationId, payload)
-                );
-
-            const ffiCommitDelay = ffiDecryptedMessage.commit_delay;
-
-            let commitDelay = undefined;
-            if (typeof ffiCommitDelay === "number" && ffiCommitDelay >= 0) {
-                commitDelay = ffiCommitDelay * 1000;
-            }
-
-            const identity = mapWireIdentity(ffiDecryptedMessage.identity);
-
-            const ret: DecryptedMessage = {
-                message: ffiDecryptedMessage.message,
-                proposals: ffiDecryptedMessage.proposals,
-                isActive: ffiDecryptedMessage.is_active,
-                senderClientId: ffiDecryptedMessage.sender_client_id,
-                commitDelay,
-                identity,
-                hasEpochChanged: ffiDecryptedMessage.has_epoch_changed,
-                bufferedMessages: ffiDecryptedMessage.buffered_messages?.map(
-                    (m) => ({
-                        message: m.message,
-                        proposals: m.proposals,
-                        isActive: m.is_active,
-                        senderClientId: m.sender_client_id,
-                        commitDelay: m.commit_delay,
-                        identity: mapWireIdentity(m.identity),
-                        hasEpochChanged: m.has_epoch_changed,
-                        crlNewDistributionPoints: m.crl_new_distribution_points,
-                    })
-                ),
-                crlNewDistributionPoints: ffiDecryptedMessage.crl_new_distribution_points,
-            };
-
-            return ret;
-        } catch (e) {
-            throw CoreCryptoError.fromStdError(e as Error);
-        }
+    async conversationHealth(conversationId: ConversationId): Promise<ConversationHealth> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_health(conversationId)
+        );
     }
 
     /**
-     * Encrypts a message for a given conversation
-     *
-     * @param conversationId - The ID of the conversation
-     * @param message - The plaintext message to encrypt
+     * Sets the maximum sizes of the inputs, checked before they are parsed so that a malicious peer can't exhaust the
+     * memory of the device. Larger inputs are rejected with {@link CoreCryptoErrorCode.InputTooLarge}
      *
-     * @returns The encrypted payload for the given group. This needs to be fanned out to the other members of the group.
+     * @param maxMessageSize - maximum size in bytes of a message given to {@link CoreCrypto.decryptMessage}, commits included. Defaults to 1MiB
+     * @param maxWelcomeSize - maximum size in bytes of a Welcome given to {@link CoreCrypto.processWelcomeMessage}. Defaults to 16MiB
+     * @param maxGroupInfoSize - maximum size in bytes of a GroupInfo e.g. given to {@link CoreCrypto.joinByExternalCommit}. Defaults to 16MiB
      */
-    async encryptMessage(
-        conversationId: ConversationId,
-        message: Uint8Array
-    ): Promise<Uint8Array> {
+    async setMessageLimits(
+        maxMessageSize: number,
+        maxWelcomeSize: number,
+        maxGroupInfoSize: number
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.encrypt_message(conversationId, message)
+            this.#cc.set_message_limits(
+                maxMessageSize,
+                maxWelcomeSize,
+                maxGroupInfoSize
+            )
         );
     }
 
     /**
-     * Ingest a TLS-serialized MLS welcome message to join an existing MLS group
-     *
-     * Important: you have to catch the error with this reason "Although this Welcome seems valid, the local KeyPackage
-     * it references has already been deleted locally. Join this group with an external commit", ignore it and then try
-     * to join this group with an external commit.
+     * Sets the number of consecutive decryption failures after which a conversation is deemed unhealthy
      *
-     * @param welcomeMessage - TLS-serialized MLS Welcome message
-     * @param configuration - configuration of the MLS group
-     * @returns The conversation ID of the newly joined group. You can use the same ID to decrypt/encrypt messages
+     * @param suspect - number of failures from which the conversation is {@link ConversationHealthState.Suspect}. Defaults to 1
+     * @param broken - number of failures from which the conversation is {@link ConversationHealthState.Broken}. Defaults to 5
      */
-    async processWelcomeMessage(
-        welcomeMessage: Uint8Array,
-        configuration: CustomConfiguration = {}
-    ): Promise<WelcomeBundle> {
-        try {
-            const { keyRotationSpan, wirePolicy } = configuration || {};
-            const config = new CustomConfigurationFfi(
-                keyRotationSpan,
-                wirePolicy
-            );
-            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle = await CoreCryptoError.asyncMapErr(
-                this.#cc.process_welcome_message(welcomeMessage, config)
-            );
-
-            const ret: WelcomeBundle = {
-                id: ffiRet.id,
-                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
-            };
-
-            return ret;
-        } catch (e) {
-            throw CoreCryptoError.fromStdError(e as Error);
-        }
+    async setConversationHealthThresholds(suspect: number, broken: number): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.set_conversation_health_thresholds(suspect, broken)
+        );
     }
 
     /**
-     * Get the client's public signature key. To upload to the DS for further backend side validation
+     * Registers a callback invoked every time the health of a conversation changes. A broken conversation should be
+     * rejoined with {@link CoreCrypto.joinByExternalCommit}
      *
-     * @param ciphersuite - of the signature key to get
-     * @param credentialType - of the public key to look for
-     * @returns the client's public signature key
+     * @param callback - called with the id of the conversation and its new health
      */
-    async clientPublicKey(ciphersuite: Ciphersuite, credentialType: CredentialType): Promise<Uint8Array> {
+    async registerConversationHealthObserver(
+        callback: (conversationId: ConversationId, health: ConversationHealth) => void
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.client_public_key(ciphersuite, credentialType)
+            this.#cc.set_conversation_health_observer(callback)
         );
     }
 
     /**
+     * Registers a callback invoked every time a credential is seen for the first time in a conversation, including
+     * the own one when creating or joining it. Meant to feed a key transparency / identity log
      *
-     * @param ciphersuite - of the KeyPackages to count
-     * @param credentialType - of the KeyPackages to count
-     * @returns The amount of valid, non-expired KeyPackages that are persisted in the backing storage
+     * @param callback - called with the id of the conversation, the id of the client and its TLS-serialized credential
      */
-    async clientValidKeypackagesCount(
-        ciphersuite: Ciphersuite,
-        credentialType: CredentialType
-    ): Promise<number> {
+    async registerCredentialObserver(
+        callback: (conversationId: ConversationId, clientId: ClientId, credential: Uint8Array) => void
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.client_valid_keypackages_count(ciphersuite, credentialType)
+            this.#cc.set_credential_observer(callback)
         );
     }
 
     /**
-     * Fetches a requested amount of keypackages
+     * Every credential seen in a conversation along with the time it was first seen, oldest first. Records are kept
+     * after a member left or changed its credential and removed along with the conversation
      *
-     * @param ciphersuite - of the KeyPackages to generate
-     * @param credentialType - of the KeyPackages to generate
-     * @param amountRequested - The amount of keypackages requested
-     * @returns An array of length `amountRequested` containing TLS-serialized KeyPackages
+     * @param conversationId - The ID of the conversation
+     * @returns see {@link CredentialFirstSeen}
      */
-    async clientKeypackages(
-        ciphersuite: Ciphersuite,
-        credentialType: CredentialType,
-        amountRequested: number
-    ): Promise<Array<Uint8Array>> {
+    async seenCredentials(conversationId: ConversationId): Promise<CredentialFirstSeen[]> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.client_keypackages(
-                ciphersuite,
-                credentialType,
-                amountRequested
-            )
+            this.#cc.seen_credentials(conversationId)
         );
     }
 
     /**
-     * Prunes local KeyPackages after making sure they also have been deleted on the backend side
-     * You should only use this after {@link CoreCrypto.e2eiRotateAll}
+     * The last epochs entered by a conversation, newest first. Only the last 1000 epochs are kept and they're removed
+     * along with the conversation
      *
-     * @param refs - KeyPackage references to delete obtained from a {RotateBundle}
+     * @param conversationId - The ID of the conversation
+     * @param limit - maximum number of epochs to return
+     * @returns see {@link EpochHistoryEntry}
      */
-    async deleteKeypackages(refs: Uint8Array[]): Promise<void> {
+    async epochHistory(conversationId: ConversationId, limit: number): Promise<EpochHistoryEntry[]> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.delete_keypackages(refs)
+            this.#cc.epoch_history(conversationId, limit)
         );
     }
 
     /**
-     * Adds new clients to a conversation, assuming the current client has the right to add new clients to the conversation.
+     * Counters of the cleanup of stale epoch key material, which runs after every merged commit. Only tracked in
+     * memory, hence they start over after a restart
      *
-     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
-     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
-     * epoch, use new encryption secrets etc...
+     * @returns see {@link EpochCleanupMetrics}
+     */
+    async epochCleanupMetrics(): Promise<EpochCleanupMetrics> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.epoch_cleanup_metrics()
+        );
+    }
+
+    /**
+     * Starts or stops collecting the stats of every conversation. Disabled by default and not persisted, hence has to
+     * be enabled again after a restart. Stats collected so far are kept when disabling
+     *
+     * @param enabled - whether to collect stats
+     */
+    async setConversationStatsEnabled(enabled: boolean): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.set_conversation_stats_enabled(enabled)
+        );
+    }
+
+    /**
+     * Activity of a conversation, counted while {@link CoreCrypto.setConversationStatsEnabled} was on. Stats are
+     * persisted and removed along with the conversation
      *
      * @param conversationId - The ID of the conversation
-     * @param keyPackages - KeyPackages of the new clients to add
+     * @returns see {@link ConversationStats}
+     */
+    async conversationStats(conversationId: ConversationId): Promise<ConversationStats> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_stats(conversationId)
+        );
+    }
+
+    /**
+     * Tells how close a conversation is to the limits of its ratchets in the current epoch, e.g. to warn the user
+     * before messages can no longer be decrypted or to commit in order to start a new epoch. Nothing is changed
      *
-     * @returns A {@link CommitBundle}
+     * @param conversationId - The ID of the conversation
+     * @returns see {@link RatchetDiagnostics}
      */
-    async addClientsToConversation(
-        conversationId: ConversationId,
-        keyPackages: Uint8Array[]
-    ): Promise<MemberAddedMessages> {
+    async ratchetDiagnostics(conversationId: ConversationId): Promise<RatchetDiagnostics> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.ratchet_diagnostics(conversationId)
+        );
+    }
+
+    /**
+     * Loads the given conversations in memory in a single database read, so that opening them later does not hit the
+     * database. The ones already in memory are left untouched and unknown ids are ignored
+     *
+     * @param conversationIds - The IDs of the conversations about to be opened
+     * @returns how many conversations were loaded
+     */
+    async prefetchConversations(conversationIds: ConversationId[]): Promise<number> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.prefetch_conversations(conversationIds)
+        );
+    }
+
+    /**
+     * Hits and misses of the in-memory conversation cache, to tune its capacity. They are reset when the conversations
+     * are reloaded from the database
+     *
+     * @returns see {@link ConversationCacheStats}
+     */
+    async conversationCacheStats(): Promise<ConversationCacheStats> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_cache_stats()
+        );
+    }
+
+    /**
+     * Restores the next batch of the conversations left over when initializing with
+     * {@link CoreCryptoParams.lazyGroupRestore}, most recently persisted first. Call it until it returns `undefined`,
+     * e.g. in the background once the app started. Once the conversation cache is full, the others are left to be
+     * loaded when first used and the restoration ends early
+     *
+     * @returns the progress made so far, or `undefined` once there is nothing left to restore
+     */
+    async restoreNextGroups(): Promise<GroupRestoreProgress | undefined> {
+        const progress = await CoreCryptoError.asyncMapErr(
+            this.#cc.restore_next_groups()
+        );
+        return progress ?? undefined;
+    }
+
+    /**
+     * Counters of the activity since this instance was created, for platforms without a tracing subscriber. Only kept
+     * in memory, hence they start over after a restart
+     *
+     * @returns see {@link MetricsSnapshot}
+     */
+    async metricsSnapshot(): Promise<MetricsSnapshot> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.metrics_snapshot()
+        );
+    }
+
+    /**
+     * Starts logging the keystore operations taking longer than the given threshold, e.g. to find out why sending
+     * messages is slow on a device. The last ones are listed in the report of {@link CoreCrypto.generateDiagnosticReport}.
+     * Disabled by default
+     *
+     * @param thresholdMs - in milliseconds. Stops logging and discards the logged operations when not set
+     */
+    async setKeystoreSlowOperationThreshold(thresholdMs?: number): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.set_keystore_slow_operation_threshold(thresholdMs)
+        );
+    }
+
+    /**
+     * Takes the database back over from the instance which opened it with {@link CoreCryptoDeferredParams.takeOverStore},
+     * then reloads the conversations from it since that instance most likely changed them
+     */
+    async takeOverStore(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.take_over_store()
+        );
+    }
+
+    /**
+     * Runs `callback` within a transaction: the keystore writes made through the {@link CoreCryptoContext} it is handed
+     * are all persisted once it resolves and all discarded, along with the in-memory changes, when it throws. Other
+     * calls on this instance wait until the transaction is over.
+     *
+     * @example
+     * ```ts
+     * await cc.transaction(async (ctx) => {
+     *     await ctx.createConversation(conversationId, CredentialType.Basic);
+     *     return await ctx.addClientsToConversation(conversationId, keyPackages);
+     * });
+     * ```
+     *
+     * @param callback - operations to run within the transaction
+     * @returns the value `callback` resolved with
+     */
+    async transaction<R>(
+        callback: (ctx: CoreCryptoContext) => Promise<R>
+    ): Promise<R> {
+        // the error thrown by the callback is rethrown as is instead of being mapped
+        let callbackError: { error: unknown } | undefined;
         try {
-            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
-                await CoreCryptoError.asyncMapErr(
-                    this.#cc.add_clients_to_conversation(
-                        conversationId,
-                        keyPackages
-                    )
-                );
+            return await CoreCryptoError.asyncMapErr(
+                this.#cc.transaction(
+                    async (ctx: CoreCryptoFfiTypes.CoreCryptoContext) => {
+                        try {
+                            return await callback(new CoreCryptoContext(ctx));
+                        } catch (error) {
+                            callbackError = { error };
+                            throw error;
+                        }
+                    }
+                )
+            );
+        } catch (e) {
+            throw callbackError ? callbackError.error : e;
+        }
+    }
 
-            const gi = ffiRet.group_info;
+    /**
+     * Marks a conversation as child of another one
+     * This will mostly affect the behavior of the callbacks (the parentConversationClients parameter will be filled)
+     *
+     * @param childId - conversation identifier of the child conversation
+     * @param parentId - conversation identifier of the parent conversation
+     */
+    async markConversationAsChildOf(
+        childId: ConversationId,
+        parentId: ConversationId
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.mark_conversation_as_child_of(childId, parentId)
+        );
+    }
 
-            const ret: MemberAddedMessages = {
-                welcome: ffiRet.welcome,
-                commit: ffiRet.commit,
-                groupInfo: {
-                    encryptionType: gi.encryption_type,
-                    ratchetTreeType: gi.ratchet_tree_type,
-                    payload: gi.payload,
-                },
-                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
-            };
+    /**
+     * Creates the sub-conversation of a conversation for a given purpose e.g. a conference call, with the same
+     * ciphersuite and kind of credential as its parent. Every member of the parent derives the same ID for it so
+     * they can then join it by external commit
+     *
+     * @param parentId - conversation identifier of the parent conversation
+     * @param purpose - what the sub-conversation is for e.g. `conference`
+     * @returns The ID of the sub-conversation
+     */
+    async createSubConversation(
+        parentId: ConversationId,
+        purpose: string
+    ): Promise<ConversationId> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.create_sub_conversation(parentId, purpose)
+        );
+    }
+
+    /**
+     * Lists the sub-conversations of a conversation, without loading them
+     *
+     * @param parentId - conversation identifier of the parent conversation
+     * @returns The IDs of the sub-conversations
+     */
+    async subConversations(
+        parentId: ConversationId
+    ): Promise<ConversationId[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.sub_conversations(parentId)
+        );
+    }
+
+    /**
+     * Removes from a sub-conversation the clients which are not members of its parent anymore. Meant to be called
+     * after every commit removing members from the parent
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterwards **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @param conversationId - conversation identifier of the sub-conversation
+     * @returns A {@link CommitBundle} or `undefined` when every member is still a member of the parent
+     */
+    async syncSubConversationMembers(
+        conversationId: ConversationId
+    ): Promise<CommitBundle | undefined> {
+        const ffiCommitBundle: CoreCryptoFfiTypes.CommitBundle | undefined =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.sync_sub_conversation_members(conversationId)
+            );
+
+        if (!ffiCommitBundle) {
+            return undefined;
+        }
+
+        const gi = ffiCommitBundle.group_info;
+
+        return {
+            welcome: ffiCommitBundle.welcome,
+            commit: ffiCommitBundle.commit,
+            groupInfo: {
+                encryptionType: gi.encryption_type,
+                ratchetTreeType: gi.ratchet_tree_type,
+                payload: gi.payload,
+            },
+        };
+    }
+
+    /**
+     * Leaves a sub-conversation by wiping it locally. The Delivery Service still has to be told so that the remaining
+     * members remove this client
+     *
+     * @param conversationId - conversation identifier of the sub-conversation
+     * @returns A report of what has been removed
+     */
+    async leaveSubConversation(
+        conversationId: ConversationId
+    ): Promise<ConversationWipeReport> {
+        const ffiReport: CoreCryptoFfiTypes.ConversationWipeReport =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.leave_sub_conversation(conversationId)
+            );
+        return {
+            pendingProposals: ffiReport.pending_proposals,
+            encryptionKeypairs: ffiReport.encryption_keypairs,
+            bufferedMessages: ffiReport.buffered_messages,
+            pendingGroup: ffiReport.pending_group,
+        };
+    }
+
+    /**
+     * Returns the current epoch of a conversation
+     *
+     * @returns the epoch of the conversation
+     *
+     * @example
+     * ```ts
+     *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
+     *  const encoder = new TextEncoder();
+     *  console.log(await cc.conversationEpoch(encoder.encode("my super chat")))
+     * ```
+     */
+    async conversationEpoch(conversationId: ConversationId): Promise<number> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_epoch(conversationId)
+        );
+    }
+
+    /**
+     * Wipes and destroys the local storage of a given conversation / MLS group along with all the key material
+     * and state related to it
+     *
+     * @param conversationId - The ID of the conversation to remove
+     * @returns A report of what has been removed
+     */
+    async wipeConversation(
+        conversationId: ConversationId
+    ): Promise<ConversationWipeReport> {
+        const ffiReport: CoreCryptoFfiTypes.ConversationWipeReport =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.wipe_conversation(conversationId)
+            );
+        return {
+            pendingProposals: ffiReport.pending_proposals,
+            encryptionKeypairs: ffiReport.encryption_keypairs,
+            bufferedMessages: ffiReport.buffered_messages,
+            pendingGroup: ffiReport.pending_group,
+        };
+    }
 
+    /**
+     * Creates a new conversation with the current client being the sole member
+     * You will want to use {@link CoreCrypto.addClientsToConversation} afterwards to add clients to this conversation
+     *
+     * @param conversationId - The conversation ID; You can either make them random or let the backend attribute MLS group IDs
+     * @param creatorCredentialType - kind of credential the creator wants to create the group with
+     * @param configuration - configuration of the MLS group
+     * @param configuration.ciphersuite - The {@link Ciphersuite} that is chosen to be the group's
+     * @param configuration.externalSenders - Array of Client IDs that are qualified as external senders within the group
+     * @param configuration.custom - {@link CustomConfiguration}
+     * @param configuration.requiredCapabilities - {@link RequiredCapabilities}
+     */
+    async createConversation(
+        conversationId: ConversationId,
+        creatorCredentialType: CredentialType,
+        configuration: ConversationConfiguration = {}
+    ) {
+        try {
+            const config = conversationConfigurationFfi(configuration);
+            const ret = await CoreCryptoError.asyncMapErr(
+                this.#cc.create_conversation(
+                    conversationId,
+                    creatorCredentialType,
+                    config
+                )
+            );
             return ret;
         } catch (e) {
             throw CoreCryptoError.fromStdError(e as Error);
@@ -1397,34 +2757,39 @@ export class CoreCrypto {
     }
 
     /**
-     * Removes the provided clients from a conversation; Assuming those clients exist and the current client is allowed
-     * to do so, otherwise this operation does nothing.
-     *
-     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
-     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
-     * epoch, use new encryption secrets etc...
+     * Creates a new conversation and adds the given clients to it in a single call. Unlike
+     * {@link CoreCrypto.addClientsToConversation}, the commit is merged right away since nobody else can commit to a
+     * conversation which has just been created. Hence {@link CoreCrypto.commitAccepted} must not be called afterwards.
+     * If anything fails, nothing remains of the conversation.
      *
-     * @param conversationId - The ID of the conversation
-     * @param clientIds - Array of Client IDs to remove.
+     * @param conversationId - The conversation ID; You can either make them random or let the backend attribute MLS group IDs
+     * @param creatorCredentialType - kind of credential the creator wants to create the group with
+     * @param keyPackages - KeyPackages of the clients to add
+     * @param configuration - configuration of the MLS group, see {@link CoreCrypto.createConversation}
      *
-     * @returns A {@link CommitBundle}
+     * @returns A {@link MemberAddedMessages} whose welcome has to be sent to the new clients
      */
-    async removeClientsFromConversation(
+    async createConversationWithMembers(
         conversationId: ConversationId,
-        clientIds: ClientId[]
-    ): Promise<CommitBundle> {
+        creatorCredentialType: CredentialType,
+        keyPackages: Uint8Array[],
+        configuration: ConversationConfiguration = {}
+    ): Promise<MemberAddedMessages> {
         try {
-            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+            const config = conversationConfigurationFfi(configuration);
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
                 await CoreCryptoError.asyncMapErr(
-                    this.#cc.remove_clients_from_conversation(
+                    this.#cc.create_conversation_with_members(
                         conversationId,
-                        clientIds
+                        creatorCredentialType,
+                        config,
+                        keyPackages
                     )
                 );
 
             const gi = ffiRet.group_info;
 
-            const ret: CommitBundle = {
+            return {
                 welcome: ffiRet.welcome,
                 commit: ffiRet.commit,
                 groupInfo: {
@@ -1432,44 +2797,72 @@ export class CoreCrypto {
                     ratchetTreeType: gi.ratchet_tree_type,
                     payload: gi.payload,
                 },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
             };
-
-            return ret;
         } catch (e) {
             throw CoreCryptoError.fromStdError(e as Error);
         }
     }
 
     /**
-     * Creates an update commit which forces every client to update their LeafNode in the conversation
+     * Decrypts a message for a given conversation.
      *
-     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
-     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
-     * epoch, use new encryption secrets etc...
+     * Note: you should catch & ignore the following error reasons:
+     * * "We already decrypted this message once"
+     * * "You tried to join with an external commit but did not merge it yet. We will reapply this message for you when you merge your external commit"
+     * * "Incoming message is for a future epoch. We will buffer it until the commit for that epoch arrives"
      *
      * @param conversationId - The ID of the conversation
+     * @param payload - The encrypted message buffer
      *
-     * @returns A {@link CommitBundle}
+     * @returns a {@link DecryptedMessage}. Note that {@link DecryptedMessage#message} is `undefined` when the encrypted payload contains a system message such a proposal or commit
      */
-    async updateKeyingMaterial(
-        conversationId: ConversationId
-    ): Promise<CommitBundle> {
+    async decryptMessage(
+        conversationId: ConversationId,
+        payload: Uint8Array
+    ): Promise<DecryptedMessage> {
+        if (!payload?.length) {
+            throw new Error("decryptMessage payload is empty or null");
+        }
+
         try {
-            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+            const ffiDecryptedMessage: CoreCryptoFfiTypes.DecryptedMessage =
                 await CoreCryptoError.asyncMapErr(
-                    this.#cc.update_keying_material(conversationId)
+                    this.#cc.decrypt_message(conversationId, payload)
                 );
 
-            const gi = ffiRet.group_info;
+            const ffiCommitDelay = ffiDecryptedMessage.commit_delay;
 
-            const ret: CommitBundle = {
-                welcome: ffiRet.welcome,
-                commit: ffiRet.commit,
-                groupInfo: {
-                    encryptionType: gi.encryption_type,
-                    ratchetTreeType: gi.ratchet_tree_type,
-                    payload: gi.payload,
-                },
+            let commitDelay = undefined;
+            if (typeof ffiCommitDelay === "number" && ffiCommitDelay >= 0) {
+                commitDelay = ffiCommitDelay * 1000;
+            }
+
+            const identity = mapWireIdentity(ffiDecryptedMessage.identity);
+
+            const ret: DecryptedMessage = {
+                message: ffiDecryptedMessage.message,
+                proposals: ffiDecryptedMessage.proposals,
+                isActive: ffiDecryptedMessage.is_active,
+                senderClientId: ffiDecryptedMessage.sender_client_id,
+                commitDelay,
+                identity,
+                hasEpochChanged: ffiDecryptedMessage.has_epoch_changed,
+                bufferedMessages: ffiDecryptedMessage.buffered_messages?.map(
+                    (m) => ({
+                        message: m.message,
+                        proposals: m.proposals,
+                        isActive: m.is_active,
+                        senderClientId: m.sender_client_id,
+                        commitDelay: m.commit_delay,
+                        identity: mapWireIdentity(m.identity),
+                        hasEpochChanged: m.has_epoch_changed,
+                        crlNewDistributionPoints: m.crl_new_distribution_points,
+                        commitDelta: mapCommitDelta(m.commit_delta),
+                    })
+                ),
+                crlNewDistributionPoints: ffiDecryptedMessage.crl_new_distribution_points,
+                commitDelta: mapCommitDelta(ffiDecryptedMessage.commit_delta),
             };
 
             return ret;
@@ -1479,1143 +2872,3179 @@ export class CoreCrypto {
     }
 
     /**
-     * Commits the local pending proposals and returns the {@link CommitBundle} object containing what can result from this operation.
-     *
-     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterwards **ONLY IF** the Delivery Service responds
-     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
-     * epoch, use new encryption secrets etc...
+     * Encrypts a message for a given conversation
      *
      * @param conversationId - The ID of the conversation
+     * @param message - The plaintext message to encrypt
      *
-     * @returns A {@link CommitBundle} or `undefined` when there was no pending proposal to commit
+     * @returns The encrypted payload for the given group. This needs to be fanned out to the other members of the group.
+     * It is empty when the message has been queued by the epoch fence of the conversation, see {@link takeFencedMessages}
      */
-    async commitPendingProposals(
-        conversationId: ConversationId
-    ): Promise<CommitBundle | undefined> {
-        try {
-            const ffiCommitBundle: CoreCryptoFfiTypes.CommitBundle | undefined =
-                await CoreCryptoError.asyncMapErr(
-                    this.#cc.commit_pending_proposals(conversationId)
-                );
-
-            if (!ffiCommitBundle) {
-                return undefined;
-            }
-
-            const gi = ffiCommitBundle.group_info;
-
-            return {
-                welcome: ffiCommitBundle.welcome,
-                commit: ffiCommitBundle.commit,
-                groupInfo: {
-                    encryptionType: gi.encryption_type,
-                    ratchetTreeType: gi.ratchet_tree_type,
-                    payload: gi.payload,
-                },
-            };
-        } catch (e) {
-            throw CoreCryptoError.fromStdError(e as Error);
-        }
+    async encryptMessage(
+        conversationId: ConversationId,
+        message: Uint8Array
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.encrypt_message(conversationId, message)
+        );
     }
 
     /**
-     * Creates a new proposal for the provided Conversation ID
+     * Takes the messages queued by the epoch fence of a conversation which have been encrypted since its pending commit
+     * was resolved, see {@link EpochFence.Queue}
      *
-     * @param proposalType - The type of proposal, see {@link ProposalType}
-     * @param args - The arguments of the proposal, see {@link ProposalArgs}, {@link AddProposalArgs} or {@link RemoveProposalArgs}
+     * @param conversationId - The ID of the conversation
      *
-     * @returns A {@link ProposalBundle} containing the Proposal and its reference in order to roll it back if necessary
+     * @returns The encrypted payloads, to be fanned out in this order once the commit, if accepted, has been
      */
-    async newProposal(
-        proposalType: ProposalType,
-        args: ProposalArgs | AddProposalArgs | RemoveProposalArgs
-    ): Promise<ProposalBundle> {
-        switch (proposalType) {
-            case ProposalType.Add: {
-                if (!(args as AddProposalArgs).kp) {
-                    throw new Error(
-                        "kp is not contained in the proposal arguments"
-                    );
-                }
-                return await CoreCryptoError.asyncMapErr(
-                    this.#cc.new_add_proposal(
-                        args.conversationId,
-                        (args as AddProposalArgs).kp
-                    )
-                );
-            }
-            case ProposalType.Remove: {
-                if (!(args as RemoveProposalArgs).clientId) {
-                    throw new Error(
-                        "clientId is not contained in the proposal arguments"
-                    );
-                }
-                return await CoreCryptoError.asyncMapErr(
-                    this.#cc.new_remove_proposal(
-                        args.conversationId,
-                        (args as RemoveProposalArgs).clientId
-                    )
-                );
-            }
-            case ProposalType.Update: {
-                return await CoreCryptoError.asyncMapErr(
-                    this.#cc.new_update_proposal(args.conversationId)
-                );
-            }
-            default:
-                throw new Error("Invalid proposal type!");
-        }
+    async takeFencedMessages(
+        conversationId: ConversationId
+    ): Promise<Array<Uint8Array>> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.take_fenced_messages(conversationId)
+        );
     }
 
     /**
-     * Creates a new external Add proposal for self client to join a conversation.
+     * Ingest a TLS-serialized MLS welcome message to join an existing MLS group
+     *
+     * Important: you have to catch the error with this reason "Although this Welcome seems valid, the local KeyPackage
+     * it references has already been deleted locally. Join this group with an external commit", ignore it and then try
+     * to join this group with an external commit.
+     *
+     * @param welcomeMessage - TLS-serialized MLS Welcome message
+     * @param configuration - configuration of the MLS group
+     * @returns The conversation ID of the newly joined group. You can use the same ID to decrypt/encrypt messages
      */
-    async newExternalProposal(
-        externalProposalType: ExternalProposalType,
-        args: ExternalAddProposalArgs
-    ): Promise<Uint8Array> {
-        switch (externalProposalType) {
-            case ExternalProposalType.Add: {
-                let addArgs = args as ExternalAddProposalArgs;
-                return await CoreCryptoError.asyncMapErr(
-                    this.#cc.new_external_add_proposal(
-                        args.conversationId,
-                        args.epoch,
-                        addArgs.ciphersuite,
-                        addArgs.credentialType
-                    )
-                );
-            }
-            default:
-                throw new Error("Invalid external proposal type!");
+    async processWelcomeMessage(
+        welcomeMessage: Uint8Array,
+        configuration: CustomConfiguration = {}
+    ): Promise<WelcomeBundle> {
+        try {
+            const config = customConfigurationFfi(configuration);
+            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle = await CoreCryptoError.asyncMapErr(
+                this.#cc.process_welcome_message(welcomeMessage, config)
+            );
+
+            const ret: WelcomeBundle = {
+                id: ffiRet.id,
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
         }
     }
 
     /**
-     * Allows to create an external commit to "apply" to join a group through its GroupInfo.
-     *
-     * If the Delivery Service accepts the external commit, you have to {@link CoreCrypto.mergePendingGroupFromExternalCommit}
-     * in order to get back a functional MLS group. On the opposite, if it rejects it, you can either retry by just
-     * calling again {@link CoreCrypto.joinByExternalCommit}, no need to {@link CoreCrypto.clearPendingGroupFromExternalCommit}.
-     * If you want to abort the operation (too many retries or the user decided to abort), you can use
-     * {@link CoreCrypto.clearPendingGroupFromExternalCommit} in order not to bloat the user's storage but nothing
-     * bad can happen if you forget to except some storage space wasted.
+     * Same as {@link CoreCrypto.processWelcomeMessage} but when this client still has a conversation or a pending
+     * group with the same id, e.g. because it missed the commit removing it, it is wiped and replaced by the joined
+     * one instead of failing. This has to be decided upfront: a Welcome can't be processed again after failing since
+     * the KeyPackage it references is consumed.
      *
-     * @param groupInfo - a TLS encoded GroupInfo fetched from the Delivery Service
-     * @param credentialType - kind of Credential to use for joining this group. If {@link CredentialType.Basic} is
-     * chosen and no Credential has been created yet for it, a new one will be generated.
+     * @param welcomeMessage - TLS-serialized MLS Welcome message
      * @param configuration - configuration of the MLS group
-     * When {@link CredentialType.X509} is chosen, it fails when no Credential has been created for the given {@link Ciphersuite}.
-     * @returns see {@link ConversationInitBundle}
+     * @returns The conversation ID of the newly joined group. You can use the same ID to decrypt/encrypt messages
      */
-    async joinByExternalCommit(
-        groupInfo: Uint8Array,
-        credentialType: CredentialType,
+    async processWelcomeMessageReplacing(
+        welcomeMessage: Uint8Array,
         configuration: CustomConfiguration = {}
-    ): Promise<ConversationInitBundle> {
+    ): Promise<WelcomeBundle> {
         try {
-            const { keyRotationSpan, wirePolicy } = configuration || {};
-            const config = new CustomConfigurationFfi(
-                keyRotationSpan,
-                wirePolicy
+            const config = customConfigurationFfi(configuration);
+            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle = await CoreCryptoError.asyncMapErr(
+                this.#cc.process_welcome_message_replacing(welcomeMessage, config)
             );
-            const ffiInitMessage: CoreCryptoFfiTypes.ConversationInitBundle =
-                await CoreCryptoError.asyncMapErr(
-                    this.#cc.join_by_external_commit(
-                        groupInfo,
-                        config,
-                        credentialType
-                    )
-                );
-
-            const gi = ffiInitMessage.group_info;
 
-            const ret: ConversationInitBundle = {
-                conversationId: ffiInitMessage.conversation_id,
-                commit: ffiInitMessage.commit,
-                groupInfo: {
-                    encryptionType: gi.encryption_type,
-                    ratchetTreeType: gi.ratchet_tree_type,
-                    payload: gi.payload,
-                },
-                crlNewDistributionPoints: ffiInitMessage.crl_new_distribution_points,
+            return {
+                id: ffiRet.id,
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
             };
-
-            return ret;
         } catch (e) {
             throw CoreCryptoError.fromStdError(e as Error);
         }
     }
 
     /**
-     * This merges the commit generated by {@link CoreCrypto.joinByExternalCommit}, persists the group permanently
-     * and deletes the temporary one. This step makes the group operational and ready to encrypt/decrypt message
+     * Get the client's public signature key. To upload to the DS for further backend side validation
      *
-     * @param conversationId - The ID of the conversation
-     * @returns eventually decrypted buffered messages if any
+     * @param ciphersuite - of the signature key to get
+     * @param credentialType - of the public key to look for
+     * @returns the client's public signature key
      */
-    async mergePendingGroupFromExternalCommit(
-        conversationId: ConversationId
-    ): Promise<BufferedDecryptedMessage[] | undefined> {
+    async clientPublicKey(ciphersuite: Ciphersuite, credentialType: CredentialType): Promise<Uint8Array> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.merge_pending_group_from_external_commit(conversationId)
+            this.#cc.client_public_key(ciphersuite, credentialType)
         );
     }
 
     /**
-     * In case the external commit generated by {@link CoreCrypto.joinByExternalCommit} is rejected by the Delivery Service, and we
-     * want to abort this external commit once for all, we can wipe out the pending group from the keystore in order
-     * not to waste space
+     * Get the public part of the client's most recent credential: its public signature key along with, for a X509
+     * credential, the certificate chain
      *
-     * @param conversationId - The ID of the conversation
+     * @param ciphersuite - whose signature scheme the credential uses
+     * @param credentialType - of the credential to look for
+     * @returns see {@link PublicKeyBundle}
      */
-    async clearPendingGroupFromExternalCommit(
-        conversationId: ConversationId
-    ): Promise<void> {
+    async clientPublicKeyBundle(ciphersuite: Ciphersuite, credentialType: CredentialType): Promise<PublicKeyBundle> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_group_from_external_commit(conversationId)
+            this.#cc.client_public_key_bundle(ciphersuite, credentialType)
         );
     }
 
     /**
-     * Allows to mark the latest commit produced as "accepted" and be able to safely merge it into the local group state
      *
-     * @param conversationId - The group's ID
-     * @returns the messages from current epoch which had been buffered, if any
-     */
-    async commitAccepted(
-        conversationId: ConversationId
-    ): Promise<BufferedDecryptedMessage[] | undefined> {
+     * @param ciphersuite - of the KeyPackages to count
+     * @param credentialType - of the KeyPackages to count
+     * @returns The amount of valid, non-expired KeyPackages that are persisted in the backing storage
+     */
+    async clientValidKeypackagesCount(
+        ciphersuite: Ciphersuite,
+        credentialType: CredentialType
+    ): Promise<number> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.commit_accepted(conversationId)
+            this.#cc.client_valid_keypackages_count(ciphersuite, credentialType)
         );
     }
 
     /**
-     * Allows to remove a pending proposal (rollback). Use this when backend rejects the proposal you just sent e.g. if permissions have changed meanwhile.
-     *
-     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
-     * e.g. 403 or 409. Do not use otherwise e.g. 5xx responses, timeout etc…
+     * Sets the clock skew tolerated when validating KeyPackage lifetimes and certificate validity periods.
+     * It is also used to backdate the lifetime of newly generated KeyPackages. Defaults to 1 hour.
      *
-     * @param conversationId - The group's ID
-     * @param proposalRef - A reference to the proposal to delete. You get one when using {@link CoreCrypto.newProposal}
+     * @param leewaySeconds - tolerated clock skew in seconds
      */
-    async clearPendingProposal(
-        conversationId: ConversationId,
-        proposalRef: ProposalRef
-    ): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_proposal(conversationId, proposalRef)
+    async setClockSkewLeeway(leewaySeconds: number): Promise<void> {
+        await CoreCryptoError.asyncMapErr(
+            this.#cc.set_clock_skew_leeway(leewaySeconds)
         );
+        this.#transferParams.clockSkewLeewaySeconds = leewaySeconds;
     }
 
     /**
-     * Allows to remove a pending commit (rollback). Use this when backend rejects the commit you just sent e.g. if permissions have changed meanwhile.
-     *
-     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
-     * e.g. 403. Do not use otherwise e.g. 5xx responses, timeout etc..
-     * **DO NOT** use when Delivery Service responds 409, pending state will be renewed
-     * in {@link CoreCrypto.decryptMessage}
+     * Fetches a requested amount of keypackages
      *
-     * @param conversationId - The group's ID
+     * @param ciphersuite - of the KeyPackages to generate
+     * @param credentialType - of the KeyPackages to generate
+     * @param amountRequested - The amount of keypackages requested
+     * @returns An array of length `amountRequested` containing TLS-serialized KeyPackages
      */
-    async clearPendingCommit(conversationId: ConversationId): Promise<void> {
+    async clientKeypackages(
+        ciphersuite: Ciphersuite,
+        credentialType: CredentialType,
+        amountRequested: number
+    ): Promise<Array<Uint8Array>> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_commit(conversationId)
+            this.#cc.client_keypackages(
+                ciphersuite,
+                credentialType,
+                amountRequested
+            )
         );
     }
 
     /**
-     * Derives a new key from the group
-     *
-     * @param conversationId - The group's ID
-     * @param keyLength - the length of the key to be derived. If the value is higher than the
-     * bounds of `u16` or the context hash * 255, an error will be returned
+     * Checks a KeyPackage claimed from the Delivery Service before adding its owner to a conversation, so that a bad
+     * one can be skipped and reported to the backend instead of failing the whole commit
      *
-     * @returns A `Uint8Array` representing the derived key
+     * @param keyPackage - TLS-serialized KeyPackage
+     * @returns see {@link KeyPackageValidationReport}
+     * @throws when the KeyPackage is malformed
      */
-    async exportSecretKey(
-        conversationId: ConversationId,
-        keyLength: number
-    ): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.export_secret_key(conversationId, keyLength)
-        );
+    async validateKeypackage(keyPackage: Uint8Array): Promise<KeyPackageValidationReport> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.validate_keypackage(keyPackage));
     }
 
     /**
-     * Returns the raw public key of the single external sender present in this group.
-     * This should be used to initialize a subconversation
+     * Same as {@link CoreCrypto.clientKeypackages} but returns the KeyPackages ready to be uploaded to the Delivery
+     * Service
      *
-     * @param conversationId - The group's ID
-     *
-     * @returns A `Uint8Array` representing the external sender raw public key
+     * @param ciphersuite - of the KeyPackages to generate
+     * @param credentialType - of the KeyPackages to generate
+     * @param count - The amount of keypackages to upload
+     * @returns see {@link KeyPackageUploadPayload}
      */
-    async getExternalSender(conversationId: ConversationId): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(this.#cc.get_external_sender(conversationId));
+    async keypackagesUploadPayload(
+        ciphersuite: Ciphersuite,
+        credentialType: CredentialType,
+        count: number
+    ): Promise<KeyPackageUploadPayload> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.keypackages_upload_payload(
+                ciphersuite,
+                credentialType,
+                count
+            )
+        );
     }
 
     /**
-     * Returns all clients from group's members
-     *
-     * @param conversationId - The group's ID
+     * Prunes local KeyPackages after making sure they also have been deleted on the backend side
+     * You should only need this when the commits of {@link CoreCrypto.e2eiRotateAll} could not all be accepted or when
+     * there was no conversation to rotate
      *
-     * @returns A list of clients from the members of the group
+     * @param refs - KeyPackage references to delete obtained from a {RotateBundle}
      */
-    async getClientIds(conversationId: ConversationId): Promise<ClientId[]> {
+    async deleteKeypackages(refs: Uint8Array[]): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.get_client_ids(conversationId)
+            this.#cc.delete_keypackages(refs)
         );
     }
 
     /**
-     * Allows {@link CoreCrypto} to act as a CSPRNG provider
-     * @note The underlying CSPRNG algorithm is ChaCha20 and takes in account the external seed provider either at init time or provided with {@link CoreCrypto.reseedRng}
-     *
-     * @param length - The number of bytes to be returned in the `Uint8Array`
+     * Deletes the KeyPackages whose ciphersuite is not in `ciphersuites` along with their HPKE private keys, for example
+     * after the list of supported ciphersuites changed on upgrade. Expired KeyPackages are pruned as well.
+     * KeyPackages of dropped ciphersuites should also be deleted on the backend side, otherwise peers could still
+     * claim them
      *
-     * @returns A `Uint8Array` buffer that contains `length` cryptographically-secure random bytes
+     * @param ciphersuites - the ciphersuites still supported
+     * @returns the count of KeyPackages remaining for each of `ciphersuites`
      */
-    async randomBytes(length: number): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(this.#cc.random_bytes(length));
+    async pruneKeypackagesNotIn(ciphersuites: Ciphersuite[]): Promise<Map<Ciphersuite, number>> {
+        let cs = ciphersuites.map((cs) => cs.valueOf());
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.prune_keypackages_not_in(Uint16Array.of(...cs))
+        );
     }
 
     /**
-     * Allows to reseed {@link CoreCrypto}'s internal CSPRNG with a new seed.
+     * Adds new clients to a conversation, assuming the current client has the right to add new clients to the conversation.
      *
-     * @param seed - **exactly 32** bytes buffer seed
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     * @param keyPackages - KeyPackages of the new clients to add
+     *
+     * @returns A {@link CommitBundle}
      */
-    async reseedRng(seed: Uint8Array): Promise<void> {
-        if (seed.length !== 32) {
-            throw new Error(
-                `The seed length needs to be exactly 32 bytes. ${seed.length} bytes provided.`
-            );
-        }
+    async addClientsToConversation(
+        conversationId: ConversationId,
+        keyPackages: Uint8Array[]
+    ): Promise<MemberAddedMessages> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.add_clients_to_conversation(
+                        conversationId,
+                        keyPackages
+                    )
+                );
 
-        return await CoreCryptoError.asyncMapErr(this.#cc.reseed_rng(seed));
-    }
+            const gi = ffiRet.group_info;
 
-    /**
-     * Initializes the proteus client
-     */
-    async proteusInit(): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(this.#cc.proteus_init());
-    }
+            const ret: MemberAddedMessages = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
 
-    /**
-     * Create a Proteus session using a prekey
-     *
-     * @param sessionId - ID of the Proteus session
-     * @param prekey - CBOR-encoded Proteus prekey of the other client
-     */
-    async proteusSessionFromPrekey(
-        sessionId: string,
-        prekey: Uint8Array
-    ): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_session_from_prekey(sessionId, prekey)
-        );
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Create a Proteus session from a handshake message
+     * Adds to a conversation the clients whose KeyPackages have been claimed from the backend, after checking that each
+     * KeyPackage belongs to the client it has been claimed for. Fails with
+     * {@link CoreCryptoErrorCode.ClaimedKeyPackageMismatch} otherwise, in which case nothing is committed
      *
-     * @param sessionId - ID of the Proteus session
-     * @param envelope - CBOR-encoded Proteus message
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
      *
-     * @returns A `Uint8Array` containing the message that was sent along with the session handshake
+     * @param conversationId - The ID of the conversation
+     * @param claimResponse - JSON response of the KeyPackage claim endpoint
+     *
+     * @returns A {@link CommitBundle}
      */
-    async proteusSessionFromMessage(
-        sessionId: string,
-        envelope: Uint8Array
-    ): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_session_from_message(sessionId, envelope)
-        );
+    async addClaimedClientsToConversation(
+        conversationId: ConversationId,
+        claimResponse: Uint8Array
+    ): Promise<MemberAddedMessages> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.add_claimed_clients_to_conversation(
+                        conversationId,
+                        claimResponse
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: MemberAddedMessages = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Locally persists a session to the keystore
+     * Removes the provided clients from a conversation; Assuming those clients exist and the current client is allowed
+     * to do so, otherwise this operation does nothing.
      *
-     * **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
      *
-     * @param sessionId - ID of the Proteus session
+     * @param conversationId - The ID of the conversation
+     * @param clientIds - Array of Client IDs to remove.
+     *
+     * @returns A {@link CommitBundle}
      */
-    async proteusSessionSave(sessionId: string): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_session_save(sessionId)
-        );
+    async removeClientsFromConversation(
+        conversationId: ConversationId,
+        clientIds: ClientId[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.remove_clients_from_conversation(
+                        conversationId,
+                        clientIds
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Deletes a session
-     * Note: this also deletes the persisted data within the keystore
+     * Runs a commit-producing operation which can be retried safely, e.g. after the application crashed before sending
+     * the commit. As long as the commit generated with the same operation key is pending, it is returned again instead
+     * of a new one which would fork the conversation if the first one got delivered anyway
      *
-     * @param sessionId - ID of the Proteus session
+     * @param conversationId - The ID of the conversation
+     * @param operationKey - Chosen by the application, e.g. a random value persisted along with the operation before
+     * running it
+     * @param operation - The operation generating the commit
+     *
+     * @returns A {@link IdempotentCommitBundle}, or `undefined` when there were no pending proposals to commit
      */
-    async proteusSessionDelete(sessionId: string): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_session_delete(sessionId)
-        );
+    async commitWithOperationKey(
+        conversationId: ConversationId,
+        operationKey: Uint8Array,
+        operation: CommitOperation
+    ): Promise<IdempotentCommitBundle | undefined> {
+        let members: Uint8Array[] | undefined;
+        if (operation.type === CommitOperationType.AddClients) {
+            members = operation.keyPackages;
+        } else if (operation.type === CommitOperationType.RemoveClients) {
+            members = operation.clientIds;
+        }
+
+        try {
+            const ffiRet: CoreCryptoFfiTypes.IdempotentCommitBundle | undefined =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.commit_with_operation_key(
+                        conversationId,
+                        operationKey,
+                        operation.type,
+                        members
+                    )
+                );
+
+            if (!ffiRet) {
+                return undefined;
+            }
+
+            const gi = ffiRet.group_info;
+
+            return {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+                replayed: ffiRet.replayed,
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Checks if a session exists
+     * Describes the commit adding and removing the provided clients, along with the pending proposals, without changing
+     * anything: the commit is generated on a copy of the conversation and discarded. Adds only or removes only are
+     * committed as {@link CoreCrypto.addClientsToConversation} and {@link CoreCrypto.removeClientsFromConversation}
+     * do, anything else along with an update of the own leaf.
      *
-     * @param sessionId - ID of the Proteus session
+     * @param conversationId - The ID of the conversation
+     * @param keyPackages - KeyPackages of the clients to add
+     * @param clientIds - Client IDs of the clients to remove
+     * @param update - Whether to update the own leaf
      *
-     * @returns whether the session exists or not
+     * @returns A {@link CommitPreview}
      */
-    async proteusSessionExists(sessionId: string): Promise<boolean> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_session_exists(sessionId)
+    async previewCommit(
+        conversationId: ConversationId,
+        keyPackages: Uint8Array[],
+        clientIds: ClientId[],
+        update: boolean = false
+    ): Promise<CommitPreview> {
+        const ffiRet = await CoreCryptoError.asyncMapErr(
+            this.#cc.preview_commit(conversationId, keyPackages, clientIds, update)
         );
+        return {
+            members: ffiRet.members,
+            epoch: ffiRet.epoch,
+            delta: {
+                added: ffiRet.added,
+                removed: ffiRet.removed,
+                updated: ffiRet.updated,
+                selfUpdated: ffiRet.selfUpdated,
+            },
+            commitSize: ffiRet.commitSize,
+            welcomeSize: ffiRet.welcomeSize ?? undefined,
+            groupInfoSize: ffiRet.groupInfoSize,
+        };
     }
 
     /**
-     * Decrypt an incoming message for an existing Proteus session
+     * Replaces the admins of a conversation, i.e. the only clients allowed to add or remove members and to change the
+     * admins. Commits and proposals of other members doing so are rejected by {@link CoreCrypto.decryptMessage} with
+     * {@link CoreCryptoErrorCode.NotConversationAdmin}. Anyone can when empty, which is the default.
      *
-     * @param sessionId - ID of the Proteus session
-     * @param ciphertext - CBOR encoded, encrypted proteus message
-     * @returns The decrypted payload contained within the message
+     * The admins are part of the group context, hence shared by every member once the returned commit is merged.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service
+     * responds '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @param conversationId - The ID of the conversation
+     * @param admins - Client IDs of the admins
+     *
+     * @returns A {@link CommitBundle}
      */
-    async proteusDecrypt(
-        sessionId: string,
-        ciphertext: Uint8Array
-    ): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_decrypt(sessionId, ciphertext)
-        );
+    async setConversationAdmins(
+        conversationId: ConversationId,
+        admins: ClientId[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.set_conversation_admins(conversationId, admins)
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Encrypt a message for a given Proteus session
+     * @param conversationId - The ID of the conversation
      *
-     * @param sessionId - ID of the Proteus session
-     * @param plaintext - payload to encrypt
-     * @returns The CBOR-serialized encrypted message
+     * @returns The client IDs of the admins of the conversation, empty when anyone can add or remove members
      */
-    async proteusEncrypt(
-        sessionId: string,
-        plaintext: Uint8Array
-    ): Promise<Uint8Array> {
+    async conversationAdmins(
+        conversationId: ConversationId
+    ): Promise<ClientId[]> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_encrypt(sessionId, plaintext)
+            this.#cc.conversation_admins(conversationId)
         );
     }
 
     /**
-     * Batch encryption for proteus messages
-     * This is used to minimize FFI roundtrips when used in the context of a multi-client session (i.e. conversation)
+     * Lists every persisted conversation along with some metadata, without loading them. Meant for diagnostics or UI
      *
-     * @param sessions - List of Proteus session IDs to encrypt the message for
-     * @param plaintext - payload to encrypt
-     * @returns A map indexed by each session ID and the corresponding CBOR-serialized encrypted message for this session
+     * @returns The metadata of each conversation
      */
-    async proteusEncryptBatched(
-        sessions: string[],
-        plaintext: Uint8Array
-    ): Promise<Map<string, Uint8Array>> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_encrypt_batched(sessions, plaintext)
-        );
+    async listConversations(): Promise<ConversationSummary[]> {
+        const ffiSummaries: CoreCryptoFfiTypes.ConversationSummary[] =
+            await CoreCryptoError.asyncMapErr(this.#cc.list_conversations());
+        return ffiSummaries.map((summary) => ({
+            id: summary.id,
+            ciphersuite: summary.ciphersuite,
+            epoch: Number(summary.epoch),
+            memberCount: summary.member_count,
+            lastPersisted: Number(summary.last_persisted),
+            parentId: summary.parent_id ?? undefined,
+        }));
     }
 
     /**
-     * Creates a new prekey with the requested ID.
+     * Lists the conversations whose own key material is older than the `keyRotationSpan` they were configured with.
+     * They should be updated with {@link CoreCrypto.updateKeyingMaterial}. Conversations without a key rotation span
+     * are never listed.
      *
-     * @param prekeyId - ID of the PreKey to generate. This cannot be bigger than a u16
-     * @returns: A CBOR-serialized version of the PreKeyBundle corresponding to the newly generated and stored PreKey
+     * @returns The IDs of the conversations due for a key update
      */
-    async proteusNewPrekey(prekeyId: number): Promise<Uint8Array> {
+    async conversationsNeedingKeyUpdate(): Promise<ConversationId[]> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_new_prekey(prekeyId)
+            this.#cc.conversations_needing_key_update()
         );
     }
 
     /**
-     * Creates a new prekey with an automatically generated ID..
+     * Lists the maintenance work currently pending, the most urgent first, so that it can be scheduled with the
+     * platform's background task facilities. Nothing is changed by listing them.
      *
-     * @returns A CBOR-serialized version of the PreKeyBundle corresponding to the newly generated and stored PreKey accompanied by its ID
+     * @returns The pending {@link MaintenanceItem}s
      */
-    async proteusNewPrekeyAuto(): Promise<ProteusAutoPrekeyBundle> {
+    async pendingMaintenanceTasks(): Promise<MaintenanceItem[]> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_new_prekey_auto()
+            this.#cc.pending_maintenance_tasks()
         );
     }
 
     /**
-     * Proteus last resort prekey stuff
+     * Updates the own key material of every conversation not updated for `maxAgeSeconds`, e.g. when the application
+     * wakes up. Conversations with a pending commit are skipped, and so are the ones whose commit could not be
+     * generated: they are still stale the next time.
      *
-     * @returns A CBOR-serialize version of the PreKeyBundle associated with the last resort PreKey (holding the last resort prekey id)
+     * **CAUTION**: every commit has to be handled like the one of {@link CoreCrypto.updateKeyingMaterial}
+     *
+     * @param maxAgeSeconds - How long the own key material of a conversation can go without being updated
+     * @returns The commit of every stale conversation, keyed by the hex encoded conversation ID
      */
-    async proteusLastResortPrekey(): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_last_resort_prekey()
-        );
+    async updateAllStale(maxAgeSeconds: number): Promise<Map<string, CommitBundle>> {
+        const ffiRet: Map<string, CoreCryptoFfiTypes.CommitBundle> =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.update_all_stale(maxAgeSeconds)
+            );
+
+        const commits = new Map<string, CommitBundle>();
+        for (const [id, commit] of ffiRet) {
+            const gi = commit.group_info;
+            commits.set(id, {
+                welcome: commit.welcome,
+                commit: commit.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            });
+        }
+        return commits;
     }
 
     /**
-     * @returns The last resort PreKey id
+     * Runs a task listed by {@link CoreCrypto.pendingMaintenanceTasks}. Running a task which is no longer pending is
+     * harmless.
+     *
+     * **CAUTION**: the commit returned for {@link MaintenanceTaskKind.UpdateKeyingMaterial} has to be handled like the
+     * one of {@link CoreCrypto.updateKeyingMaterial}
+     *
+     * @param kind - What to run
+     * @param conversationId - The conversation, required for {@link MaintenanceTaskKind.UpdateKeyingMaterial}
+     *
+     * @returns A {@link CommitBundle} for {@link MaintenanceTaskKind.UpdateKeyingMaterial}, `undefined` otherwise
      */
-    static proteusLastResortPrekeyId(): number {
-        this.#assertModuleLoaded();
-        return CoreCryptoFfi.proteus_last_resort_prekey_id();
+    async runMaintenance(
+        kind: MaintenanceTaskKind,
+        conversationId?: ConversationId
+    ): Promise<CommitBundle | undefined> {
+        try {
+            const ffiCommitBundle: CoreCryptoFfiTypes.CommitBundle | undefined =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.run_maintenance(kind, conversationId)
+                );
+
+            if (!ffiCommitBundle) {
+                return undefined;
+            }
+
+            const gi = ffiCommitBundle.group_info;
+
+            return {
+                welcome: ffiCommitBundle.welcome,
+                commit: ffiCommitBundle.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Proteus public key fingerprint
-     * It's basically the public key encoded as an hex string
+     * Creates an update commit which forces every client to update their LeafNode in the conversation
      *
-     * @returns Hex-encoded public key string
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     *
+     * @returns A {@link CommitBundle}
      */
-    async proteusFingerprint(): Promise<string> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_fingerprint()
-        );
+    async updateKeyingMaterial(
+        conversationId: ConversationId
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.update_keying_material(conversationId)
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Proteus session local fingerprint
+     * Commits the local pending proposals and returns the {@link CommitBundle} object containing what can result from this operation.
      *
-     * @param sessionId - ID of the Proteus session
-     * @returns Hex-encoded public key string
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterwards **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     *
+     * @returns A {@link CommitBundle} or `undefined` when there was no pending proposal to commit
      */
-    async proteusFingerprintLocal(sessionId: string): Promise<string> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_fingerprint_local(sessionId)
-        );
+    async commitPendingProposals(
+        conversationId: ConversationId
+    ): Promise<CommitBundle | undefined> {
+        try {
+            const ffiCommitBundle: CoreCryptoFfiTypes.CommitBundle | undefined =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.commit_pending_proposals(conversationId)
+                );
+
+            if (!ffiCommitBundle) {
+                return undefined;
+            }
+
+            const gi = ffiCommitBundle.group_info;
+
+            return {
+                welcome: ffiCommitBundle.welcome,
+                commit: ffiCommitBundle.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Proteus session remote fingerprint
+     * Adds an external pre-shared key to the keystore, so that a commit of the conversation can inject it. Every member
+     * has to add it before processing such a commit, see {@link ProposalType.PreSharedKey}
      *
-     * @param sessionId - ID of the Proteus session
-     * @returns Hex-encoded public key string
+     * @param conversationId - The ID of the conversation, whose ciphersuite the secret is used with
+     * @param pskId - Id of the key, agreed upon by the members
+     * @param secret - The key itself
      */
-    async proteusFingerprintRemote(sessionId: string): Promise<string> {
+    async addExternalPsk(
+        conversationId: ConversationId,
+        pskId: Uint8Array,
+        secret: Uint8Array
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_fingerprint_remote(sessionId)
+            this.#cc.add_external_psk(conversationId, pskId, secret)
         );
     }
 
     /**
-     * Hex-encoded fingerprint of the given prekey
+     * Creates a new proposal for the provided Conversation ID
      *
-     * @param prekey - the prekey bundle to get the fingerprint from
-     * @returns Hex-encoded public key string
-     **/
-    static proteusFingerprintPrekeybundle(prekey: Uint8Array): string {
-        try {
-            return CoreCryptoFfi.proteus_fingerprint_prekeybundle(prekey);
+     * @param proposalType - The type of proposal, see {@link ProposalType}
+     * @param args - The arguments of the proposal, see {@link ProposalArgs}, {@link AddProposalArgs}, {@link RemoveProposalArgs} or {@link PskProposalArgs}
+     *
+     * @returns A {@link ProposalBundle} containing the Proposal and its reference in order to roll it back if necessary
+     */
+    async newProposal(
+        proposalType: ProposalType,
+        args: ProposalArgs | AddProposalArgs | RemoveProposalArgs | PskProposalArgs
+    ): Promise<ProposalBundle> {
+        switch (proposalType) {
+            case ProposalType.Add: {
+                if (!(args as AddProposalArgs).kp) {
+                    throw new Error(
+                        "kp is not contained in the proposal arguments"
+                    );
+                }
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_add_proposal(
+                        args.conversationId,
+                        (args as AddProposalArgs).kp
+                    )
+                );
+            }
+            case ProposalType.Remove: {
+                if (!(args as RemoveProposalArgs).clientId) {
+                    throw new Error(
+                        "clientId is not contained in the proposal arguments"
+                    );
+                }
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_remove_proposal(
+                        args.conversationId,
+                        (args as RemoveProposalArgs).clientId
+                    )
+                );
+            }
+            case ProposalType.Update: {
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_update_proposal(args.conversationId)
+                );
+            }
+            case ProposalType.PreSharedKey: {
+                if (!(args as PskProposalArgs).pskId) {
+                    throw new Error(
+                        "pskId is not contained in the proposal arguments"
+                    );
+                }
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_external_psk_proposal(
+                        args.conversationId,
+                        (args as PskProposalArgs).pskId
+                    )
+                );
+            }
+            default:
+                throw new Error("Invalid proposal type!");
+        }
+    }
+
+    /**
+     * Creates a new external Add proposal for self client to join a conversation.
+     */
+    async newExternalProposal(
+        externalProposalType: ExternalProposalType,
+        args: ExternalAddProposalArgs
+    ): Promise<Uint8Array> {
+        switch (externalProposalType) {
+            case ExternalProposalType.Add: {
+                let addArgs = args as ExternalAddProposalArgs;
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_external_add_proposal(
+                        args.conversationId,
+                        args.epoch,
+                        addArgs.ciphersuite,
+                        addArgs.credentialType
+                    )
+                );
+            }
+            default:
+                throw new Error("Invalid external proposal type!");
+        }
+    }
+
+    /**
+     * Allows to create an external commit to "apply" to join a group through its GroupInfo.
+     *
+     * If the Delivery Service accepts the external commit, you have to {@link CoreCrypto.mergePendingGroupFromExternalCommit}
+     * in order to get back a functional MLS group. On the opposite, if it rejects it, you can either retry by just
+     * calling again {@link CoreCrypto.joinByExternalCommit}, no need to {@link CoreCrypto.clearPendingGroupFromExternalCommit}.
+     * If you want to abort the operation (too many retries or the user decided to abort), you can use
+     * {@link CoreCrypto.clearPendingGroupFromExternalCommit} in order not to bloat the user's storage but nothing
+     * bad can happen if you forget to except some storage space wasted.
+     *
+     * @param groupInfo - a TLS encoded GroupInfo fetched from the Delivery Service
+     * @param credentialType - kind of Credential to use for joining this group. If {@link CredentialType.Basic} is
+     * chosen and no Credential has been created yet for it, a new one will be generated.
+     * @param configuration - configuration of the MLS group
+     * When {@link CredentialType.X509} is chosen, it fails when no Credential has been created for the given {@link Ciphersuite}.
+     * @param expectedCiphersuite - the ciphersuite the group has to use, e.g. the one the Delivery Service advertises for
+     * the conversation. Fails early with {@link CoreCryptoErrorCode.GroupCiphersuiteMismatch} when the GroupInfo uses
+     * another one
+     * @returns see {@link ConversationInitBundle}
+     */
+    async joinByExternalCommit(
+        groupInfo: Uint8Array,
+        credentialType: CredentialType,
+        configuration: CustomConfiguration = {},
+        expectedCiphersuite?: Ciphersuite
+    ): Promise<ConversationInitBundle> {
+        try {
+            const config = customConfigurationFfi(configuration);
+            const ffiInitMessage: CoreCryptoFfiTypes.ConversationInitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.join_by_external_commit(
+                        groupInfo,
+                        config,
+                        credentialType,
+                        expectedCiphersuite
+                    )
+                );
+
+            const gi = ffiInitMessage.group_info;
+
+            const ret: ConversationInitBundle = {
+                conversationId: ffiInitMessage.conversation_id,
+                commit: ffiInitMessage.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiInitMessage.crl_new_distribution_points,
+            };
+
+            return ret;
         } catch (e) {
             throw CoreCryptoError.fromStdError(e as Error);
         }
     }
 
     /**
-     * Imports all the data stored by Cryptobox into the CoreCrypto keystore
+     * Same as {@link CoreCrypto.joinByExternalCommit} but for a GroupInfo which does not embed the ratchet tree, as
+     * delivered by some federated Delivery Services, with the ratchet tree delivered alongside it.
      *
-     * @param storeName - The name of the IndexedDB store where the data is stored
+     * The secrets of a group are only ever sent to a new member in a Welcome, hence even when an Add commit already
+     * included this client but its Welcome has been dropped, the group can only be joined with an external commit.
+     *
+     * @param groupInfo - a TLS encoded GroupInfo fetched from the Delivery Service
+     * @param ratchetTree - the TLS encoded ratchet tree of the group at the epoch of `groupInfo`
+     * @param credentialType - see {@link CoreCrypto.joinByExternalCommit}
+     * @param configuration - configuration of the MLS group
+     * @returns see {@link ConversationInitBundle}
      */
-    async proteusCryptoboxMigrate(storeName: string): Promise<void> {
+    async joinFromGroupInfo(
+        groupInfo: Uint8Array,
+        ratchetTree: Uint8Array,
+        credentialType: CredentialType,
+        configuration: CustomConfiguration = {}
+    ): Promise<ConversationInitBundle> {
+        try {
+            const config = customConfigurationFfi(configuration);
+            const ffiInitMessage: CoreCryptoFfiTypes.ConversationInitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.join_from_group_info(
+                        groupInfo,
+                        ratchetTree,
+                        config,
+                        credentialType
+                    )
+                );
+
+            const gi = ffiInitMessage.group_info;
+
+            return {
+                conversationId: ffiInitMessage.conversation_id,
+                commit: ffiInitMessage.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiInitMessage.crl_new_distribution_points,
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Recovers a conversation whose local state is unrecoverable, e.g. when decrypting keeps failing with
+     * {@link CoreCryptoErrorCode.WrongEpoch}. The conversation is wiped locally then joined again by external commit
+     * with the same configuration and the same kind of credential.
+     *
+     * As with {@link CoreCrypto.joinByExternalCommit}, the commit has to be sent to the Delivery Service and then
+     * {@link CoreCrypto.mergePendingGroupFromExternalCommit} called once it is accepted.
+     *
+     * @param conversationId - The ID of the conversation
+     * @param groupInfo - a TLS encoded GroupInfo of the conversation fetched from the Delivery Service
+     * @returns see {@link ConversationInitBundle}
+     */
+    async recoverConversation(
+        conversationId: ConversationId,
+        groupInfo: Uint8Array
+    ): Promise<ConversationInitBundle> {
+        try {
+            const ffiInitMessage: CoreCryptoFfiTypes.ConversationInitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.recover_conversation(conversationId, groupInfo)
+                );
+
+            const gi = ffiInitMessage.group_info;
+
+            const ret: ConversationInitBundle = {
+                conversationId: ffiInitMessage.conversation_id,
+                commit: ffiInitMessage.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiInitMessage.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Joins many conversations by external commit at once, e.g. after restoring a backup made on another device, which
+     * holds the conversation ids but no usable MLS state. The external commits are generated concurrently and a
+     * conversation failing to be joined does not prevent the others from being joined.
+     *
+     * As with {@link CoreCrypto.joinByExternalCommit}, every commit has to be sent to the Delivery Service and then
+     * {@link CoreCrypto.mergePendingGroupFromExternalCommit} called once it is accepted.
+     *
+     * @param groupInfos - the conversations along with their current TLS encoded GroupInfo fetched from the Delivery Service
+     * @param credentialType - see {@link CoreCrypto.joinByExternalCommit}
+     * @param configuration - configuration of the MLS groups
+     * @returns the outcome of every conversation, in the order of `groupInfos`
+     */
+    async rejoinAllFromGroupInfos(
+        groupInfos: { conversationId: ConversationId; groupInfo: Uint8Array }[],
+        credentialType: CredentialType,
+        configuration: CustomConfiguration = {}
+    ): Promise<RejoinResult[]> {
+        try {
+            const config = customConfigurationFfi(configuration);
+            const results: {
+                conversation_id: ConversationId;
+                bundle?: CoreCryptoFfiTypes.ConversationInitBundle;
+                error_code?: CoreCryptoErrorCode;
+            }[] = await CoreCryptoError.asyncMapErr(
+                this.#cc.rejoin_all_from_group_infos(
+                    groupInfos.map((gi) => gi.conversationId),
+                    groupInfos.map((gi) => gi.groupInfo),
+                    config,
+                    credentialType
+                )
+            );
+
+            return results.map(({ conversation_id, bundle, error_code }) => ({
+                conversationId: conversation_id,
+                bundle: bundle && {
+                    conversationId: bundle.conversation_id,
+                    commit: bundle.commit,
+                    groupInfo: {
+                        encryptionType: bundle.group_info.encryption_type,
+                        ratchetTreeType: bundle.group_info.ratchet_tree_type,
+                        payload: bundle.group_info.payload,
+                    },
+                    crlNewDistributionPoints: bundle.crl_new_distribution_points,
+                },
+                errorCode: error_code,
+            }));
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * This merges the commit generated by {@link CoreCrypto.joinByExternalCommit}, persists the group permanently
+     * and deletes the temporary one. This step makes the group operational and ready to encrypt/decrypt message
+     *
+     * @param conversationId - The ID of the conversation
+     * @returns eventually decrypted buffered messages if any
+     */
+    async mergePendingGroupFromExternalCommit(
+        conversationId: ConversationId
+    ): Promise<BufferedDecryptedMessage[] | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.merge_pending_group_from_external_commit(conversationId)
+        );
+    }
+
+    /**
+     * In case the external commit generated by {@link CoreCrypto.joinByExternalCommit} is rejected by the Delivery Service, and we
+     * want to abort this external commit once for all, we can wipe out the pending group from the keystore in order
+     * not to waste space
+     *
+     * @param conversationId - The ID of the conversation
+     */
+    async clearPendingGroupFromExternalCommit(
+        conversationId: ConversationId
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_group_from_external_commit(conversationId)
+        );
+    }
+
+    /**
+     * Allows to mark the latest commit produced as "accepted" and be able to safely merge it into the local group state
+     *
+     * @param conversationId - The group's ID
+     * @returns the messages from current epoch which had been buffered, if any
+     */
+    async commitAccepted(
+        conversationId: ConversationId
+    ): Promise<BufferedDecryptedMessage[] | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.commit_accepted(conversationId)
+        );
+    }
+
+    /**
+     * Lists the proposals currently pending in a conversation
+     *
+     * @param conversationId - The group's ID
+     * @returns a description of every pending proposal
+     */
+    async pendingProposals(
+        conversationId: ConversationId
+    ): Promise<ProposalInfo[]> {
+        const ffiInfos: CoreCryptoFfiTypes.ProposalInfo[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.pending_proposals(conversationId)
+            );
+        return ffiInfos.map((info) => ({
+            proposalType: info.proposal_type,
+            proposer: info.proposer,
+            affectedClients: info.affected_clients,
+            proposalRef: info.proposal_ref,
+        }));
+    }
+
+    /**
+     * Allows to remove a pending proposal (rollback). Use this when backend rejects the proposal you just sent e.g. if permissions have changed meanwhile.
+     *
+     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
+     * e.g. 403 or 409. Do not use otherwise e.g. 5xx responses, timeout etc…
+     *
+     * @param conversationId - The group's ID
+     * @param proposalRef - A reference to the proposal to delete. You get one when using {@link CoreCrypto.newProposal}
+     */
+    async clearPendingProposal(
+        conversationId: ConversationId,
+        proposalRef: ProposalRef
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_proposal(conversationId, proposalRef)
+        );
+    }
+
+    /**
+     * Allows to remove a pending commit (rollback). Use this when backend rejects the commit you just sent e.g. if permissions have changed meanwhile.
+     *
+     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
+     * e.g. 403. Do not use otherwise e.g. 5xx responses, timeout etc..
+     * **DO NOT** use when Delivery Service responds 409, pending state will be renewed
+     * in {@link CoreCrypto.decryptMessage}
+     *
+     * @param conversationId - The group's ID
+     */
+    async clearPendingCommit(conversationId: ConversationId): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_commit(conversationId)
+        );
+    }
+
+    /**
+     * Returns the epoch authenticator of the current epoch of a conversation, e.g. for the calling service to
+     * authenticate the participants of a conference call. It is the same for every member and changes with every epoch.
+     *
+     * It proves membership in the epoch: treat it as a secret, only hand it to the party authenticating the members and
+     * do not log or persist it. JavaScript can't zeroize the returned buffer, so drop it as soon as possible
+     *
+     * @param conversationId - The group's ID
+     *
+     * @returns the epoch authenticator
+     */
+    async epochAuthenticator(conversationId: ConversationId): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.epoch_authenticator(conversationId));
+    }
+
+    /**
+     * Derives a new key from the group
+     *
+     * @param conversationId - The group's ID
+     * @param keyLength - the length of the key to be derived. If the value is higher than the
+     * bounds of `u16` or the context hash * 255, an error will be returned
+     *
+     * @returns A `Uint8Array` representing the derived key
+     */
+    async exportSecretKey(
+        conversationId: ConversationId,
+        keyLength: number
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.export_secret_key(conversationId, keyLength)
+        );
+    }
+
+    /**
+     * Returns the raw public key of the single external sender present in this group.
+     * This should be used to initialize a subconversation
+     *
+     * @param conversationId - The group's ID
+     *
+     * @returns A `Uint8Array` representing the external sender raw public key
+     */
+    async getExternalSender(conversationId: ConversationId): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.get_external_sender(conversationId));
+    }
+
+    /**
+     * Returns the external senders of a conversation, i.e. the Delivery Service keys allowed to send it external
+     * proposals, in the order of its ExternalSenders extension
+     *
+     * @param conversationId - The group's ID
+     *
+     * @returns see {@link ExternalSender}, empty when the conversation has none
+     */
+    async conversationExternalSenders(conversationId: ConversationId): Promise<ExternalSender[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_external_senders(conversationId)
+        );
+    }
+
+    /**
+     * Checks whether a raw signature, e.g. sent by the Delivery Service along with a message, has been made by one of
+     * the external senders of a conversation. The signature scheme of the conversation's ciphersuite is used
+     *
+     * @param conversationId - The group's ID
+     * @param payload - the signed bytes
+     * @param signature - the raw signature of `payload`
+     *
+     * @returns the index of the external sender who made the signature, unset when none of them did
+     */
+    async verifyExternalSenderSignature(
+        conversationId: ConversationId,
+        payload: Uint8Array,
+        signature: Uint8Array
+    ): Promise<number | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.verify_external_sender_signature(conversationId, payload, signature)
+        );
+    }
+
+    /**
+     * Returns all clients from group's members
+     *
+     * @param conversationId - The group's ID
+     *
+     * @returns A list of clients from the members of the group
+     */
+    async getClientIds(conversationId: ConversationId): Promise<ClientId[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.get_client_ids(conversationId)
+        );
+    }
+
+    /**
+     * Payload of the QR code of the security verification screen for a conversation with another member. It is based
+     * on the epoch authenticator, hence changes with every commit, and is the same for both members, on every platform.
+     *
+     * @param conversationId - The group's ID
+     * @param clientId - The member to verify
+     *
+     * @returns The payload to encode in the QR code
+     */
+    async mlsFingerprintQr(conversationId: ConversationId, clientId: ClientId): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.mls_fingerprint_qr(conversationId, clientId)
+        );
+    }
+
+    /**
+     * Allows {@link CoreCrypto} to act as a CSPRNG provider
+     * @note The underlying CSPRNG algorithm is ChaCha20 and takes in account the external seed provider either at init time or provided with {@link CoreCrypto.reseedRng}
+     *
+     * @param length - The number of bytes to be returned in the `Uint8Array`
+     *
+     * @returns A `Uint8Array` buffer that contains `length` cryptographically-secure random bytes
+     */
+    async randomBytes(length: number): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.random_bytes(length));
+    }
+
+    /**
+     * Allows to reseed {@link CoreCrypto}'s internal CSPRNG with a new seed.
+     *
+     * @param seed - **exactly 32** bytes buffer seed
+     */
+    async reseedRng(seed: Uint8Array): Promise<void> {
+        if (seed.length !== 32) {
+            throw new Error(
+                `The seed length needs to be exactly 32 bytes. ${seed.length} bytes provided.`
+            );
+        }
+
+        return await CoreCryptoError.asyncMapErr(this.#cc.reseed_rng(seed));
+    }
+
+    /**
+     * Initializes the proteus client
+     */
+    async proteusInit(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.proteus_init());
+    }
+
+    /**
+     * Create a Proteus session using a prekey
+     *
+     * @param sessionId - ID of the Proteus session
+     * @param prekey - CBOR-encoded Proteus prekey of the other client
+     */
+    async proteusSessionFromPrekey(
+        sessionId: string,
+        prekey: Uint8Array
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_session_from_prekey(sessionId, prekey)
+        );
+    }
+
+    /**
+     * Create a Proteus session from a handshake message
+     *
+     * @param sessionId - ID of the Proteus session
+     * @param envelope - CBOR-encoded Proteus message
+     *
+     * @returns A `Uint8Array` containing the message that was sent along with the session handshake
+     */
+    async proteusSessionFromMessage(
+        sessionId: string,
+        envelope: Uint8Array
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_session_from_message(sessionId, envelope)
+        );
+    }
+
+    /**
+     * Locally persists a session to the keystore
+     *
+     * **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+     *
+     * @param sessionId - ID of the Proteus session
+     */
+    async proteusSessionSave(sessionId: string): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_session_save(sessionId)
+        );
+    }
+
+    /**
+     * Deletes a session
+     * Note: this also deletes the persisted data within the keystore
+     *
+     * @param sessionId - ID of the Proteus session
+     */
+    async proteusSessionDelete(sessionId: string): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_session_delete(sessionId)
+        );
+    }
+
+    /**
+     * Reloads the Proteus sessions from the keystore, discarding their in-memory state. To be called when another
+     * process sharing the keystore (e.g. a notification extension) has used them, see
+     * {@link CoreCrypto.proteusStaleSessions}
+     */
+    async proteusReloadSessions(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_reload_sessions()
+        );
+    }
+
+    /**
+     * Sets whether the Proteus sessions are persisted right after every successful encryption or decryption, which is
+     * the default. When disabled, they have to be persisted with {@link CoreCrypto.proteusSessionSave}.
+     *
+     * Whatever the setting, a session used by another instance sharing the database in the meantime is not used
+     * anymore: the call fails with {@link CoreCryptoErrorCode.ProteusSessionOutOfSync} and retrying it uses the latest
+     * state of the session
+     *
+     * @param enabled - whether to persist sessions automatically
+     */
+    async proteusSetAutoPersist(enabled: boolean): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_set_auto_persist(enabled)
+        );
+    }
+
+    /**
+     * Cheaply checks whether the Proteus sessions loaded in memory are still in sync with the keystore. If this is
+     * not empty, another process sharing the keystore has used them and {@link CoreCrypto.proteusReloadSessions} has
+     * to be called before decrypting anything
+     *
+     * @returns The IDs of the out of sync sessions
+     */
+    async proteusStaleSessions(): Promise<string[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_stale_sessions()
+        );
+    }
+
+    /**
+     * Checks if a session exists
+     *
+     * @param sessionId - ID of the Proteus session
+     *
+     * @returns whether the session exists or not
+     */
+    async proteusSessionExists(sessionId: string): Promise<boolean> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_session_exists(sessionId)
+        );
+    }
+
+    /**
+     * Decrypt an incoming message for an existing Proteus session
+     *
+     * @param sessionId - ID of the Proteus session
+     * @param ciphertext - CBOR encoded, encrypted proteus message
+     * @returns The decrypted payload contained within the message
+     */
+    async proteusDecrypt(
+        sessionId: string,
+        ciphertext: Uint8Array
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_decrypt(sessionId, ciphertext)
+        );
+    }
+
+    /**
+     * Decrypt several incoming messages for an existing Proteus session, in order. The updated session is only
+     * persisted once at the end, which makes it much cheaper than calling {@link CoreCrypto.proteusDecrypt} for each
+     * of them, e.g. when catching up from a notification extension.
+     * If any message cannot be decrypted, the session is left untouched and the error is thrown.
+     *
+     * @param sessionId - ID of the Proteus session
+     * @param ciphertexts - CBOR encoded, encrypted proteus messages
+     * @returns The decrypted payloads, in the same order as the messages
+     */
+    async proteusDecryptBatched(
+        sessionId: string,
+        ciphertexts: Uint8Array[]
+    ): Promise<Uint8Array[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_decrypt_batched(sessionId, ciphertexts)
+        );
+    }
+
+    /**
+     * Same as {@link CoreCrypto.proteusDecryptBatched} but for several Proteus sessions at once.
+     * If any message cannot be decrypted, all the sessions are left untouched and the error is thrown.
+     *
+     * @param messages - A map indexed by Proteus session ID of the CBOR encoded, encrypted messages of each session
+     * @returns A map indexed by each session ID and the decrypted payloads of this session, in the same order as the messages
+     */
+    async proteusDecryptBatchedMulti(
+        messages: Map<string, Uint8Array[]>
+    ): Promise<Map<string, Uint8Array[]>> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_decrypt_batched_multi(messages)
+        );
+    }
+
+    /**
+     * Encrypt a message for a given Proteus session
+     *
+     * @param sessionId - ID of the Proteus session
+     * @param plaintext - payload to encrypt
+     * @returns The CBOR-serialized encrypted message
+     */
+    async proteusEncrypt(
+        sessionId: string,
+        plaintext: Uint8Array
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_encrypt(sessionId, plaintext)
+        );
+    }
+
+    /**
+     * Batch encryption for proteus messages
+     * This is used to minimize FFI roundtrips when used in the context of a multi-client session (i.e. conversation)
+     *
+     * @param sessions - List of Proteus session IDs to encrypt the message for
+     * @param plaintext - payload to encrypt
+     * @returns A map indexed by each session ID and the corresponding CBOR-serialized encrypted message for this session
+     */
+    async proteusEncryptBatched(
+        sessions: string[],
+        plaintext: Uint8Array
+    ): Promise<Map<string, Uint8Array>> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_encrypt_batched(sessions, plaintext)
+        );
+    }
+
+    /**
+     * Creates a new prekey with the requested ID.
+     *
+     * @param prekeyId - ID of the PreKey to generate. This cannot be bigger than a u16
+     * @returns: A CBOR-serialized version of the PreKeyBundle corresponding to the newly generated and stored PreKey
+     */
+    async proteusNewPrekey(prekeyId: number): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_new_prekey(prekeyId)
+        );
+    }
+
+    /**
+     * Creates a new prekey with an automatically generated ID..
+     *
+     * @returns A CBOR-serialized version of the PreKeyBundle corresponding to the newly generated and stored PreKey accompanied by its ID
+     */
+    async proteusNewPrekeyAuto(): Promise<ProteusAutoPrekeyBundle> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_new_prekey_auto()
+        );
+    }
+
+    /**
+     * Proteus last resort prekey stuff
+     *
+     * @returns A CBOR-serialize version of the PreKeyBundle associated with the last resort PreKey (holding the last resort prekey id)
+     */
+    async proteusLastResortPrekey(): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_last_resort_prekey()
+        );
+    }
+
+    /**
+     * @returns The last resort PreKey id
+     */
+    static proteusLastResortPrekeyId(): number {
+        this.#assertModuleLoaded();
+        return CoreCryptoFfi.proteus_last_resort_prekey_id();
+    }
+
+    /**
+     * Proteus public key fingerprint
+     * It's basically the public key encoded as an hex string
+     *
+     * @returns Hex-encoded public key string
+     */
+    async proteusFingerprint(): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_fingerprint()
+        );
+    }
+
+    /**
+     * Redacted overview of the Proteus identity and sessions, meant to be attached to support tickets.
+     * It only holds counters and never any key material
+     *
+     * @returns see {@link ProteusDiagnostics}
+     */
+    async proteusDiagnostics(): Promise<ProteusDiagnostics> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_diagnostics()
+        );
+    }
+
+    /**
+     * Proteus session local fingerprint
+     *
+     * @param sessionId - ID of the Proteus session
+     * @returns Hex-encoded public key string
+     */
+    async proteusFingerprintLocal(sessionId: string): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_fingerprint_local(sessionId)
+        );
+    }
+
+    /**
+     * Proteus session remote fingerprint
+     *
+     * @param sessionId - ID of the Proteus session
+     * @returns Hex-encoded public key string
+     */
+    async proteusFingerprintRemote(sessionId: string): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_fingerprint_remote(sessionId)
+        );
+    }
+
+    /**
+     * Payload of the QR code of the security verification screen for a Proteus session. It combines the fingerprints
+     * of both identities and is the same on both ends of the session, on every platform.
+     *
+     * @param sessionId - ID of the Proteus session
+     * @returns The payload to encode in the QR code
+     */
+    async proteusFingerprintQr(sessionId: string): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_fingerprint_qr(sessionId)
+        );
+    }
+
+    /**
+     * Binds the Proteus identity and the most recent MLS credential of the given type: each key signs the fingerprint
+     * of the other one along with the client id. Both Proteus and MLS have to be initialized.
+     *
+     * @param ciphersuite - whose signature scheme the MLS credential uses
+     * @param credentialType - of the MLS credential to bind
+     * @returns The serialized binding, to be sent to remote clients
+     */
+    async generateIdentityBinding(ciphersuite: Ciphersuite, credentialType: CredentialType): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.generate_identity_binding(ciphersuite, credentialType)
+        );
+    }
+
+    /**
+     * Verifies the signatures of a binding generated by a remote client with {@link CoreCrypto.generateIdentityBinding}.
+     * It only proves that both keys belong to the same client: the client id and either key still have to be checked
+     * against the expected ones, e.g. the Proteus fingerprint against the one of a verified session.
+     *
+     * @param binding - the serialized binding
+     * @returns The verified binding
+     * @throws {@link CoreCryptoErrorCode.InvalidIdentityBinding} when the binding is malformed or a signature is invalid
+     */
+    async verifyIdentityBinding(binding: Uint8Array): Promise<IdentityBinding> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.verify_identity_binding(binding)
+        );
+    }
+
+    /**
+     * Signs some data, e.g. a backup, with an identity key of the client without the private key leaving CoreCrypto:
+     * the signature key of the most recent MLS credential of the given type, or the Proteus identity when no
+     * ciphersuite is given. Proteus then has to be initialized.
+     *
+     * @param data - what to sign
+     * @param ciphersuite - whose signature scheme the MLS credential uses, none to sign with the Proteus identity
+     * @param credentialType - of the MLS credential to sign with
+     * @returns The serialized signature, to be verified with {@link CoreCrypto.verifyWithIdentity}
+     */
+    async signWithIdentity(
+        data: Uint8Array,
+        ciphersuite?: Ciphersuite,
+        credentialType: CredentialType = CredentialType.Basic
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.sign_with_identity(data, ciphersuite, credentialType)
+        );
+    }
+
+    /**
+     * Verifies that a signature generated with {@link CoreCrypto.signWithIdentity} has been made over the data by the
+     * given identity key. The public key has to come from a trusted source, e.g. the credential of the client in a
+     * conversation or the fingerprint of a verified Proteus session.
+     *
+     * @param publicKey - the identity key expected to have signed
+     * @param data - what has been signed
+     * @param signature - the serialized signature
+     * @throws {@link CoreCryptoErrorCode.InvalidIdentitySignature} when the signature is malformed or was not made by
+     * this key over this data
+     */
+    async verifyWithIdentity(publicKey: Uint8Array, data: Uint8Array, signature: Uint8Array): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.verify_with_identity(publicKey, data, signature)
+        );
+    }
+
+    /**
+     * Bootstraps the MLS conversation taking over an existing Proteus session. The Proteus session is left untouched
+     * so that it can still be used until the peer has joined the MLS conversation. Retrying is harmless.
+     *
+     * @param sessionId - ID of the Proteus session to migrate
+     * @param conversationId - The ID of the MLS conversation to create
+     * @param creatorCredentialType - kind of credential the creator wants to create the group with
+     * @param configuration - configuration of the MLS conversation
+     * @returns A report of the migration
+     */
+    async migrateProteusConversation(
+        sessionId: string,
+        conversationId: ConversationId,
+        creatorCredentialType: CredentialType,
+        configuration: ConversationConfiguration = {}
+    ): Promise<ProteusMigrationReport> {
+        try {
+            const config = conversationConfigurationFfi(configuration);
+            const ffiReport: CoreCryptoFfiTypes.ProteusMigrationReport =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.migrate_proteus_conversation(
+                        sessionId,
+                        conversationId,
+                        creatorCredentialType,
+                        config
+                    )
+                );
+            return {
+                sessionId: ffiReport.session_id,
+                conversationId: ffiReport.conversation_id,
+                remoteFingerprint: ffiReport.remote_fingerprint,
+                conversationCreated: ffiReport.conversation_created,
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Migrates several Proteus sessions at once, see {@link CoreCrypto.migrateProteusConversation}. A session failing
+     * to be migrated does not prevent the others from being migrated, so the whole batch can simply be retried.
+     *
+     * @param migrations - the Proteus sessions along with the ID of the MLS conversation to create for each of them
+     * @param creatorCredentialType - kind of credential the creator wants to create the groups with
+     * @param configuration - configuration of the MLS conversations
+     * @returns the outcome of every session, in the order of `migrations`
+     */
+    async migrateProteusConversations(
+        migrations: { sessionId: string; conversationId: ConversationId }[],
+        creatorCredentialType: CredentialType,
+        configuration: ConversationConfiguration = {}
+    ): Promise<ProteusMigrationResult[]> {
+        try {
+            const config = conversationConfigurationFfi(configuration);
+            const results: {
+                session_id: string;
+                report?: CoreCryptoFfiTypes.ProteusMigrationReport;
+                error_code?: CoreCryptoErrorCode;
+            }[] = await CoreCryptoError.asyncMapErr(
+                this.#cc.migrate_proteus_conversations(
+                    migrations.map((m) => m.sessionId),
+                    migrations.map((m) => m.conversationId),
+                    creatorCredentialType,
+                    config
+                )
+            );
+
+            return results.map(({ session_id, report, error_code }) => ({
+                sessionId: session_id,
+                report: report && {
+                    sessionId: report.session_id,
+                    conversationId: report.conversation_id,
+                    remoteFingerprint: report.remote_fingerprint,
+                    conversationCreated: report.conversation_created,
+                },
+                errorCode: error_code,
+            }));
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Hex-encoded fingerprint of the given prekey
+     *
+     * @param prekey - the prekey bundle to get the fingerprint from
+     * @returns Hex-encoded public key string
+     **/
+    static proteusFingerprintPrekeybundle(prekey: Uint8Array): string {
+        try {
+            return CoreCryptoFfi.proteus_fingerprint_prekeybundle(prekey);
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Imports all the data stored by Cryptobox into the CoreCrypto keystore
+     *
+     * @param storeName - The name of the IndexedDB store where the data is stored
+     */
+    async proteusCryptoboxMigrate(storeName: string): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.proteus_cryptobox_migrate(storeName)
+        );
+    }
+
+    /**
+     * Note: this call clears out the code and resets it to 0 (aka no error)
+     * @returns the last proteus error code that occured.
+     */
+    async proteusLastErrorCode(): Promise<number> {
+        return await this.#cc.proteus_last_error_code();
+    }
+
+    /**
+     * Creates an enrollment instance with private key material you can use in order to fetch
+     * a new x509 certificate from the acme server.
+     *
+     * @param clientId - client identifier e.g. `b7ac11a4-8f01-4527-af88-1c30885a7931:6add501bacd1d90e@example.com`
+     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
+     * @param handle - user handle e.g. `alice.smith.qa@example.com`
+     * @param expirySec - generated x509 certificate expiry
+     * @param ciphersuite - for generating signing key material
+     * @param team - name of the Wire team a user belongs to
+     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiMlsInitOnly}
+     */
+    async e2eiNewEnrollment(
+        clientId: string,
+        displayName: string,
+        handle: string,
+        expirySec: number,
+        ciphersuite: Ciphersuite,
+        team?: string
+    ): Promise<E2eiEnrollment> {
+        const e2ei = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_new_enrollment(
+                clientId,
+                displayName,
+                handle,
+                team,
+                expirySec,
+                ciphersuite
+            )
+        );
+        return new E2eiEnrollment(e2ei);
+    }
+
+    /**
+     * Generates an E2EI enrollment instance for a "regular" client (with a Basic credential) willing to migrate to E2EI.
+     * Once the enrollment is finished, use the instance in {@link CoreCrypto.e2eiRotateAll} to do the rotation.
+     *
+     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
+     * @param handle - user handle e.g. `alice.smith.qa@example.com`
+     * @param expirySec - generated x509 certificate expiry
+     * @param ciphersuite - for generating signing key material
+     * @param team - name of the Wire team a user belongs to
+     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiRotateAll}
+     */
+    async e2eiNewActivationEnrollment(
+        displayName: string,
+        handle: string,
+        expirySec: number,
+        ciphersuite: Ciphersuite,
+        team?: string
+    ): Promise<E2eiEnrollment> {
+        const e2ei = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_new_activation_enrollment(
+                displayName,
+                handle,
+                team,
+                expirySec,
+                ciphersuite
+            )
+        );
+        return new E2eiEnrollment(e2ei);
+    }
+
+    /**
+     * Generates an E2EI enrollment instance for a E2EI client (with a X509 certificate credential)
+     * having to change/rotate their credential, either because the former one is expired or it
+     * has been revoked. It lets you change the DisplayName or the handle
+     * if you need to. Once the enrollment is finished, use the instance in {@link CoreCrypto.e2eiRotateAll} to do the rotation.
+     *
+     * @param expirySec - generated x509 certificate expiry
+     * @param ciphersuite - for generating signing key material
+     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
+     * @param handle - user handle e.g. `alice.smith.qa@example.com`
+     * @param team - name of the Wire team a user belongs to
+     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiRotateAll}
+     */
+    async e2eiNewRotateEnrollment(
+        expirySec: number,
+        ciphersuite: Ciphersuite,
+        displayName?: string,
+        handle?: string,
+        team?: string
+    ): Promise<E2eiEnrollment> {
+        const e2ei = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_new_rotate_enrollment(
+                displayName,
+                handle,
+                team,
+                expirySec,
+                ciphersuite
+            )
+        );
+        return new E2eiEnrollment(e2ei);
+    }
+
+    /**
+     * Generates an E2EI enrollment instance for renewing the certificate of a E2EI client before it expires. The
+     * certificate is requested for the current signature keypair and identity. Once the enrollment is finished, use
+     * the instance in {@link CoreCrypto.e2eiRenew} to install the renewed certificate.
+     *
+     * @param expirySec - generated x509 certificate expiry
+     * @param ciphersuite - of the credential to renew
+     * @param team - name of the Wire team a user belongs to
+     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiRenew}
+     */
+    async e2eiNewRenewalEnrollment(
+        expirySec: number,
+        ciphersuite: Ciphersuite,
+        team?: string
+    ): Promise<E2eiEnrollment> {
+        const e2ei = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_new_renewal_enrollment(team, expirySec, ciphersuite)
+        );
+        return new E2eiEnrollment(e2ei);
+    }
+
+    /**
+     * Use this method to initialize end-to-end identity when a client signs up and the grace period is already expired ;
+     * that means he cannot initialize with a Basic credential
+     *
+     * @param enrollment - the enrollment instance used to fetch the certificates
+     * @param certificateChain - the raw response from ACME server
+     * @param nbKeyPackage - number of initial KeyPackage to create when initializing the client
+     * @returns a MlsClient initialized with only a x509 credential
+     */
+    async e2eiMlsInitOnly(
+        enrollment: E2eiEnrollment,
+        certificateChain: string,
+        nbKeyPackage?: number
+    ): Promise<string[] | undefined> {
+        return await this.#cc.e2ei_mls_init_only(
+            enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity,
+            certificateChain,
+            nbKeyPackage
+        );
+    }
+
+    /**
+    * Registers a Root Trust Anchor CA for the use in E2EI processing.
+    *
+    * Please note that without a Root Trust Anchor, all validations *will* fail;
+    * So this is the first step to perform after initializing your E2EI client
+    *
+    * @param trustAnchorPEM - PEM certificate to anchor as a Trust Root
+    */
+    async e2eiRegisterAcmeCA(trustAnchorPEM: string): Promise<void> {
+        return await this.#cc.e2ei_register_acme_ca(trustAnchorPEM);
+    }
+
+    /**
+    * Registers an Intermediate CA for the use in E2EI processing.
+    *
+    * Please note that a Root Trust Anchor CA is needed to validate Intermediate CAs;
+    * You **need** to have a Root CA registered before calling this
+    *
+    * @param certPEM - PEM certificate to register as an Intermediate CA
+    */
+    async e2eiRegisterIntermediateCA(certPEM: string): Promise<string[] | undefined> {
+        return await this.#cc.e2ei_register_intermediate_ca(certPEM);
+    }
+
+    /**
+    * Registers a CRL for the use in E2EI processing.
+    *
+    * Please note that a Root Trust Anchor CA is needed to validate CRLs;
+    * You **need** to have a Root CA registered before calling this
+    *
+    * @param crlDP - CRL Distribution Point; Basically the URL you fetched it from
+    * @param crlDER - DER representation of the CRL
+    *
+    * @returns a {@link CRLRegistration} with the dirty state of the new CRL (see struct) and its expiration timestamp
+    */
+    async e2eiRegisterCRL(crlDP: string, crlDER: Uint8Array): Promise<CRLRegistration> {
+        return await this.#cc.e2ei_register_crl(crlDP, crlDER);
+    }
+
+    /**
+     * Creates a commit in all local conversations for changing the credential. Requires first
+     * having enrolled a new X509 certificate with either {@link CoreCrypto.e2eiNewActivationEnrollment}
+     * or {@link CoreCrypto.e2eiNewRotateEnrollment}
+     *
+     * @param enrollment - the enrollment instance used to fetch the certificates
+     * @param certificateChain - the raw response from ACME server
+     * @param newKeyPackageCount - number of KeyPackages with new identity to generate
+     * @returns a {@link RotateBundle} with commits to fan-out to other group members, KeyPackages to upload and old ones to delete
+     */
+    async e2eiRotateAll(
+        enrollment: E2eiEnrollment,
+        certificateChain: string,
+        newKeyPackageCount: number
+    ): Promise<RotateBundle> {
+        const ffiRet: CoreCryptoFfiTypes.RotateBundle =
+            await this.#cc.e2ei_rotate_all(
+                enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity,
+                certificateChain,
+                newKeyPackageCount
+            );
+
+        const ret: RotateBundle = {
+            commits: ffiRet.commits,
+            newKeyPackages: ffiRet.new_key_packages,
+            keyPackageRefsToRemove: ffiRet.key_package_refs_to_remove,
+            crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+        };
+
+        return ret;
+    }
+
+    /**
+     * Installs the certificate renewed with an enrollment from {@link CoreCrypto.e2eiNewRenewalEnrollment}. Like
+     * {@link CoreCrypto.e2eiRotateAll}, it creates a commit in all local conversations for changing the credential but
+     * the signature key stays the same. Fails when the certificate was issued for another key
+     *
+     * @param enrollment - the enrollment instance used to fetch the certificates
+     * @param certificateChain - the raw response from ACME server
+     * @param newKeyPackageCount - number of KeyPackages with the renewed credential to generate
+     * @returns a {@link RotateBundle} with commits to fan-out to other group members, KeyPackages to upload and old ones to delete
+     */
+    async e2eiRenew(
+        enrollment: E2eiEnrollment,
+        certificateChain: string,
+        newKeyPackageCount: number
+    ): Promise<RotateBundle> {
+        const ffiRet: CoreCryptoFfiTypes.RotateBundle =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.e2ei_renew(
+                    enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity,
+                    certificateChain,
+                    newKeyPackageCount
+                )
+            );
+
+        const ret: RotateBundle = {
+            commits: ffiRet.commits,
+            newKeyPackages: ffiRet.new_key_packages,
+            keyPackageRefsToRemove: ffiRet.key_package_refs_to_remove,
+            crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+        };
+
+        return ret;
+    }
+
+    /**
+     * Allows persisting an active enrollment (for example while redirecting the user during OAuth) in order to resume
+     * it later with {@link e2eiEnrollmentStashPop}
+     *
+     * @param enrollment the enrollment instance to persist
+     * @returns a handle to fetch the enrollment later with {@link e2eiEnrollmentStashPop}
+     */
+    async e2eiEnrollmentStash(enrollment: E2eiEnrollment): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_enrollment_stash(
+                enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity
+            )
+        );
+    }
+
+    /**
+     * Fetches the persisted enrollment and deletes it from the keystore
+     *
+     * @param handle returned by {@link e2eiEnrollmentStash}
+     * @returns the persisted enrollment instance
+     */
+    async e2eiEnrollmentStashPop(handle: Uint8Array): Promise<E2eiEnrollment> {
+        const e2ei = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_enrollment_stash_pop(handle)
+        );
+        return new E2eiEnrollment(e2ei);
+    }
+
+    /**
+     * Indicates when to mark a conversation as not verified i.e. when not all its members have a X509.
+     * Credential generated by Wire's end-to-end identity enrollment
+     *
+     * @param conversationId The group's ID
+     * @returns the conversation state given current members
+     */
+    async e2eiConversationState(
+        conversationId: ConversationId
+    ): Promise<E2eiConversationState> {
+        let state = await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_conversation_state(conversationId)
+        );
+        // @ts-ignore
+        return E2eiConversationState[E2eiConversationState[state]];
+    }
+
+    /**
+     * Returns true when end-to-end-identity is enabled for the given Ciphersuite
+     *
+     * @param ciphersuite of the credential to check
+     * @returns true if end-to-end identity is enabled for the given ciphersuite
+     */
+    async e2eiIsEnabled(ciphersuite: Ciphersuite): Promise<boolean> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.e2ei_is_enabled(ciphersuite)
+        );
+    }
+
+    /**
+     * From a given conversation, get the identity of the members supplied. Identity is only present for members with a
+     * Certificate Credential (after turning on end-to-end identity).
+     *
+     * @param conversationId - identifier of the conversation
+     * @param deviceIds - identifiers of the devices
+     * @returns identities or if no member has a x509 certificate, it will return an empty List
+     */
+    async getDeviceIdentities(
+        conversationId: ConversationId,
+        deviceIds: ClientId[]
+    ): Promise<WireIdentity[]> {
+        return (await CoreCryptoError.asyncMapErr(
+            this.#cc.get_device_identities(conversationId, deviceIds)
+        )).map(mapWireIdentity);
+    }
+
+    /**
+     * From a given conversation, get the identity of the users (device holders) supplied.
+     * Identity is only present for devices with a Certificate Credential (after turning on end-to-end identity).
+     * If no member has a x509 certificate, it will return an empty Vec.
+     *
+     * @param conversationId - identifier of the conversation
+     * @param userIds - user identifiers hyphenated UUIDv4 e.g. 'bd4c7053-1c5a-4020-9559-cd7bf7961954'
+     * @returns a Map with all the identities for a given users. Consumers are then recommended to reduce those identities to determine the actual status of a user.
+     */
+    async getUserIdentities(
+        conversationId: ConversationId,
+        userIds: string[]
+    ): Promise<Map<string, WireIdentity[]>> {
+        const map: Map<string, CoreCryptoFfiTypes.WireIdentity[]> = await CoreCryptoError.asyncMapErr(
+            this.#cc.get_user_identities(conversationId, userIds)
+        );
+
+        const mapFixed: Map<string, WireIdentity[]> = new Map();
+
+        for (const [userId, identities] of map) {
+            const mappedIdentities = identities.flatMap(identity => {
+                const mappedIdentity = mapWireIdentity(identity);
+                return mappedIdentity ? [mappedIdentity] : [];
+            });
+            mapFixed.set(userId, mappedIdentities);
+        }
+
+        return mapFixed;
+    }
+
+    /**
+     * Gets the e2ei conversation state from a `GroupInfo`. Useful to check if the group has e2ei
+     * turned on or not before joining it.
+     *
+     * @param groupInfo - a TLS encoded GroupInfo fetched from the Delivery Service
+     * @param credentialType - kind of Credential to check usage of. Defaults to X509 for now as no other value will give any result.
+     * @returns see {@link E2eiConversationState}
+     */
+    async getCredentialInUse(groupInfo: Uint8Array, credentialType: CredentialType = CredentialType.X509): Promise<E2eiConversationState> {
+        let state = await CoreCryptoError.asyncMapErr(this.#cc.get_credential_in_use(groupInfo, credentialType));
+        // @ts-ignore
+        return E2eiConversationState[E2eiConversationState[state]];
+    }
+
+    /**
+     * Returns the current version of {@link CoreCrypto}
+     *
+     * @returns The `core-crypto` version as defined in its `Cargo.toml` file
+     */
+    static version(): string {
+        this.#assertModuleLoaded();
+        return CoreCryptoFfi.version();
+    }
+
+    /**
+     * Describes how this copy of {@link CoreCrypto} has been built, to be attached to bug reports
+     *
+     * @returns see {@link BuildMetadata}
+     */
+    static buildMetadata(): BuildMetadata {
+        this.#assertModuleLoaded();
+        return CoreCryptoFfi.build_metadata();
+    }
+
+    /**
+     * Registers the logger receiving the issues CoreCrypto recovered from internally, replacing the previous one.
+     * There is a single logger for the whole module, shared by every {@link CoreCrypto} instance. To get what
+     * happens while initializing, pass it as {@link CoreCryptoDeferredParams.logger} instead
+     *
+     * @param logger - {@link CoreCryptoLogger}. Pass `undefined` to unregister it
+     */
+    static setLogger(logger?: CoreCryptoLogger) {
+        this.#assertModuleLoaded();
+        CoreCryptoFfi.set_logger(
+            logger &&
+                ((level: CoreCryptoLogLevel, message: string, context: string) =>
+                    logger.log(level, message, context))
+        );
+    }
+}
+
+/**
+ * Tags the messages exchanged by {@link CoreCryptoWorkerClient} and {@link CoreCryptoWorkerHost} so that they can
+ * share the worker with the messages of the application
+ * @hidden
+ */
+const WORKER_CALL = "core-crypto-worker-call";
+/** @hidden */
+const WORKER_RESULT = "core-crypto-worker-result";
+
+/** @hidden */
+interface CoreCryptoWorkerRequest {
+    kind: typeof WORKER_CALL;
+    id: number;
+    method: string;
+    args: unknown[];
+}
+
+/** @hidden */
+interface CoreCryptoWorkerResponse {
+    kind: typeof WORKER_RESULT;
+    id: number;
+    result?: unknown;
+    error?: {
+        message: string;
+        richError?: CoreCryptoRichError;
+    };
+}
+
+/**
+ * Methods of {@link CoreCrypto} which can be called through a {@link CoreCryptoWorkerClient}, i.e. the asynchronous
+ * ones
+ */
+export type CoreCryptoWorkerMethod = {
+    [K in keyof CoreCrypto]: CoreCrypto[K] extends (...args: any[]) => Promise<any> ? K : never;
+}[keyof CoreCrypto];
+
+/**
+ * Either end of the channel between the main thread and the worker, e.g. a
+ * {@link https://developer.mozilla.org/en-US/docs/Web/API/Worker | Worker} on the main thread and `self` in the worker
+ */
+export interface CoreCryptoWorkerEndpoint {
+    postMessage(message: any): void;
+    addEventListener(type: "message", listener: (event: MessageEvent) => void): void;
+}
+
+/**
+ * Serves, in a Web Worker, the calls of a {@link CoreCryptoWorkerClient} on the instance opened with
+ * {@link CoreCrypto.fromTransferHandle}
+ *
+ * @example
+ * ```ts
+ * // worker.ts
+ * self.addEventListener("message", async ({ data }) => {
+ *     if (data.databaseName) {
+ *         const cc = await CoreCrypto.fromTransferHandle(data, logger);
+ *         await cc.registerCallbacks(callbacks);
+ *         CoreCryptoWorkerHost.serve(cc, self);
+ *         self.postMessage("ready");
+ *     }
+ * });
+ * ```
+ */
+export class CoreCryptoWorkerHost {
+    /**
+     * Answers every call posted by a {@link CoreCryptoWorkerClient} to `endpoint`. Other messages are ignored
+     *
+     * @param cc - the instance the calls are made on
+     * @param endpoint - usually `self` in the worker
+     */
+    static serve(cc: CoreCrypto, endpoint: CoreCryptoWorkerEndpoint) {
+        endpoint.addEventListener("message", async ({ data }: MessageEvent) => {
+            if (data?.kind !== WORKER_CALL) {
+                return;
+            }
+            const { id, method, args } = data as CoreCryptoWorkerRequest;
+            const response: CoreCryptoWorkerResponse = { kind: WORKER_RESULT, id };
+            try {
+                const fn = (cc as any)[method];
+                if (typeof fn !== "function") {
+                    throw new Error(`CoreCrypto has no method named ${method}`);
+                }
+                response.result = await fn.apply(cc, args);
+            } catch (e) {
+                response.error = { message: (e as Error).message };
+                if (e instanceof CoreCryptoError) {
+                    response.error.richError = {
+                        errorName: e.name,
+                        message: e.message,
+                        rustStackTrace: e.rustStackTrace,
+                        proteusErrorCode: e.proteusErrorCode,
+                        errorCode: e.errorCode,
+                        context: e.context,
+                    };
+                }
+            }
+            endpoint.postMessage(response);
+        });
+    }
+}
+
+/**
+ * Calls, from the main thread, the {@link CoreCrypto} instance served by a {@link CoreCryptoWorkerHost} in a Web
+ * Worker. Every call runs in the worker, hence the CPU-heavy ones no longer block the main thread. Those are mostly:
+ * - {@link CoreCrypto.processWelcomeMessage} and {@link CoreCrypto.joinByExternalCommit} in large conversations
+ * - {@link CoreCrypto.decryptMessage} of a commit, which has to update the whole ratchet tree
+ * - {@link CoreCrypto.addClientsToConversation}, {@link CoreCrypto.removeClientsFromConversation},
+ *   {@link CoreCrypto.updateKeyingMaterial} and {@link CoreCrypto.commitPendingProposals}
+ * - {@link CoreCrypto.e2eiRotateAll}, which commits in every conversation
+ *
+ * Arguments and results are copied with the structured clone algorithm. Methods taking callbacks or returning
+ * objects backed by the wasm instance (e.g. {@link E2eiEnrollment}) can't be called this way and have to be called
+ * in the worker.
+ *
+ * @example
+ * ```ts
+ * const worker = new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+ * worker.postMessage(await cc.transferToWorker());
+ * // once the worker answered "ready"
+ * const client = new CoreCryptoWorkerClient(worker);
+ * const decrypted = await client.call("decryptMessage", conversationId, payload);
+ * ```
+ */
+export class CoreCryptoWorkerClient {
+    /** @hidden */
+    #endpoint: CoreCryptoWorkerEndpoint;
+    /** @hidden */
+    #nextId = 0;
+    /** @hidden */
+    #pending = new Map<number, { resolve: (result: any) => void; reject: (error: Error) => void }>();
+
+    /**
+     * @param endpoint - usually the {@link https://developer.mozilla.org/en-US/docs/Web/API/Worker | Worker} running
+     * the {@link CoreCryptoWorkerHost}
+     */
+    constructor(endpoint: CoreCryptoWorkerEndpoint) {
+        this.#endpoint = endpoint;
+        endpoint.addEventListener("message", ({ data }: MessageEvent) => {
+            if (data?.kind !== WORKER_RESULT) {
+                return;
+            }
+            const { id, result, error } = data as CoreCryptoWorkerResponse;
+            const pending = this.#pending.get(id);
+            if (!pending) {
+                return;
+            }
+            this.#pending.delete(id);
+            if (!error) {
+                pending.resolve(result);
+            } else if (error.richError) {
+                pending.reject(
+                    CoreCryptoError.build(`${error.message}\n\n${JSON.stringify(error.richError)}`)
+                );
+            } else {
+                pending.reject(new Error(error.message));
+            }
+        });
+    }
+
+    /**
+     * Calls a method of the {@link CoreCrypto} instance in the worker
+     *
+     * @param method - name of the method e.g. `"decryptMessage"`
+     * @param args - arguments of the method
+     * @returns what the method returned in the worker
+     */
+    async call<K extends CoreCryptoWorkerMethod>(
+        method: K,
+        ...args: CoreCrypto[K] extends (...args: infer A) => Promise<any> ? A : never
+    ): Promise<CoreCrypto[K] extends (...args: any[]) => Promise<infer R> ? R : never> {
+        const id = this.#nextId++;
+        const request: CoreCryptoWorkerRequest = { kind: WORKER_CALL, id, method, args };
+        return await new Promise((resolve, reject) => {
+            this.#pending.set(id, { resolve, reject });
+            try {
+                this.#endpoint.postMessage(request);
+            } catch (e) {
+                this.#pending.delete(id);
+                reject(e);
+            }
+        });
+    }
+}
+
+type JsonRawData = Uint8Array;
+
+export class E2eiEnrollment {
+    /** @hidden */
+    #enrollment: CoreCryptoFfiTypes.FfiWireE2EIdentity;
+
+    /** @hidden */
+    constructor(e2ei: unknown) {
+        this.#enrollment = e2ei as CoreCryptoFfiTypes.FfiWireE2EIdentity;
+    }
+
+    free() {
+        this.#enrollment.free();
+    }
+
+    /**
+     * Should only be used internally
+     */
+    inner(): unknown {
+        return this.#enrollment as CoreCryptoFfiTypes.FfiWireE2EIdentity;
+    }
+
+    /**
+     * Parses the response from `GET /acme/{provisioner-name}/directory`.
+     * Use this {@link AcmeDirectory} in the next step to fetch the first nonce from the acme server. Use
+     * {@link AcmeDirectory.newNonce}.
+     *
+     * @param directory HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.1.1
+     */
+    async directoryResponse(directory: JsonRawData): Promise<AcmeDirectory> {
+        const ffiRet: CoreCryptoFfiTypes.AcmeDirectory = await CoreCryptoError.asyncMapErr(
+            this.#enrollment.directory_response(directory)
+        );
+
+        return {
+            newNonce: ffiRet.new_nonce,
+            newAccount: ffiRet.new_account,
+            newOrder: ffiRet.new_order,
+            revokeCert: ffiRet.revoke_cert,
+        };
+    }
+
+    /**
+     * For creating a new acme account. This returns a signed JWS-alike request body to send to
+     * `POST /acme/{provisioner-name}/new-account`.
+     *
+     * @param previousNonce you got from calling `HEAD {@link AcmeDirectory.newNonce}`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.3
+     */
+    async newAccountRequest(previousNonce: string): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_account_request(previousNonce)
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/new-account`.
+     * @param account HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.3
+     */
+    async newAccountResponse(account: JsonRawData): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_account_response(account)
+        );
+    }
+
+    /**
+     * Creates a new acme order for the handle (userId + display name) and the clientId.
+     *
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/new-account`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     */
+    async newOrderRequest(previousNonce: string): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_order_request(previousNonce)
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/new-order`.
+     *
+     * @param order HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     */
+    async newOrderResponse(order: JsonRawData): Promise<NewAcmeOrder> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_order_response(order)
+        );
+    }
+
+    /**
+     * Creates a new authorization request.
+     *
+     * @param url one of the URL in new order's authorizations (use {@link NewAcmeOrder.authorizations} from {@link newOrderResponse})
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/new-order` (or from the
+     * previous to this method if you are creating the second authorization)
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
+     */
+    async newAuthzRequest(
+        url: string,
+        previousNonce: string
+    ): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_authz_request(url, previousNonce)
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/authz/{authz-id}`
+     *
+     * @param authz HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
+     */
+    async newAuthzResponse(authz: JsonRawData): Promise<NewAcmeAuthz> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_authz_response(authz)
+        );
+    }
+
+    /**
+     * Generates a new client Dpop JWT token. It demonstrates proof of possession of the nonces
+     * (from wire-server & acme server) and will be verified by the acme server when verifying the
+     * challenge (in order to deliver a certificate).
+     *
+     * Then send it to `POST /clients/{id}/access-token`
+     * {@link https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/post_clients__cid__access_token} on wire-server.
+     *
+     * @param expirySecs of the client Dpop JWT. This should be equal to the grace period set in Team Management
+     * @param backendNonce you get by calling `GET /clients/token/nonce` on wire-server as defined here {@link https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/get_clients__client__nonce}
+     */
+    async createDpopToken(
+        expirySecs: number,
+        backendNonce: string
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.create_dpop_token(expirySecs, backendNonce)
+        );
+    }
+
+    /**
+     * Creates a new challenge request for Wire Dpop challenge.
+     *
+     * @param accessToken returned by wire-server from https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/post_clients__cid__access_token
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/authz/{authz-id}`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     */
+    async newDpopChallengeRequest(
+        accessToken: string,
+        previousNonce: string
+    ): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_dpop_challenge_request(
+                accessToken,
+                previousNonce
+            )
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/challenge/{challenge-id}` for the DPoP challenge.
+     *
+     * @param challenge HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     */
+    async newDpopChallengeResponse(challenge: JsonRawData): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_dpop_challenge_response(challenge)
+        );
+    }
+
+    /**
+     * Creates a new challenge request for Wire Oidc challenge.
+     *
+     * @param idToken you get back from Identity Provider
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/authz/{authz-id}`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     */
+    async newOidcChallengeRequest(
+        idToken: string,
+        previousNonce: string
+    ): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_oidc_challenge_request(
+                idToken,
+                previousNonce
+            )
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/challenge/{challenge-id}` for the OIDC challenge.
+     *
+     * @param cc the CoreCrypto instance
+     * @param challenge HTTP response body
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     */
+    async newOidcChallengeResponse(challenge: JsonRawData): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.new_oidc_challenge_response(challenge)
+        );
+    }
+
+    /**
+     * Verifies that the previous challenge has been completed.
+     *
+     * @param orderUrl `location` header from http response you got from {@link newOrderResponse}
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/challenge/{challenge-id}`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     */
+    async checkOrderRequest(
+        orderUrl: string,
+        previousNonce: string
+    ): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.check_order_request(orderUrl, previousNonce)
+        );
+    }
+
+    /**
+     * Parses the response from `POST /acme/{provisioner-name}/order/{order-id}`.
+     *
+     * @param order HTTP response body
+     * @return finalize url to use with {@link finalizeRequest}
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     */
+    async checkOrderResponse(order: JsonRawData): Promise<string> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.proteus_cryptobox_migrate(storeName)
+            this.#enrollment.check_order_response(order)
         );
     }
 
     /**
-     * Note: this call clears out the code and resets it to 0 (aka no error)
-     * @returns the last proteus error code that occured.
+     * Final step before fetching the certificate.
+     *
+     * @param previousNonce - `replay-nonce` response header from `POST /acme/{provisioner-name}/order/{order-id}`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
      */
-    async proteusLastErrorCode(): Promise<number> {
-        return await this.#cc.proteus_last_error_code();
+    async finalizeRequest(previousNonce: string): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.finalize_request(previousNonce)
+        );
     }
 
     /**
-     * Creates an enrollment instance with private key material you can use in order to fetch
-     * a new x509 certificate from the acme server.
+     * Parses the response from `POST /acme/{provisioner-name}/order/{order-id}/finalize`.
      *
-     * @param clientId - client identifier e.g. `b7ac11a4-8f01-4527-af88-1c30885a7931:6add501bacd1d90e@example.com`
-     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
-     * @param handle - user handle e.g. `alice.smith.qa@example.com`
-     * @param expirySec - generated x509 certificate expiry
-     * @param ciphersuite - for generating signing key material
-     * @param team - name of the Wire team a user belongs to
-     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiMlsInitOnly}
+     * @param finalize HTTP response body
+     * @return the certificate url to use with {@link certificateRequest}
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
      */
-    async e2eiNewEnrollment(
-        clientId: string,
-        displayName: string,
-        handle: string,
-        expirySec: number,
-        ciphersuite: Ciphersuite,
-        team?: string
-    ): Promise<E2eiEnrollment> {
-        const e2ei = await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_new_enrollment(
-                clientId,
-                displayName,
-                handle,
-                team,
-                expirySec,
-                ciphersuite
-            )
+    async finalizeResponse(finalize: JsonRawData): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.finalize_response(finalize)
         );
-        return new E2eiEnrollment(e2ei);
     }
 
     /**
-     * Generates an E2EI enrollment instance for a "regular" client (with a Basic credential) willing to migrate to E2EI.
-     * Once the enrollment is finished, use the instance in {@link CoreCrypto.e2eiRotateAll} to do the rotation.
+     * Creates a request for finally fetching the x509 certificate.
      *
-     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
-     * @param handle - user handle e.g. `alice.smith.qa@example.com`
-     * @param expirySec - generated x509 certificate expiry
-     * @param ciphersuite - for generating signing key material
-     * @param team - name of the Wire team a user belongs to
-     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiRotateAll}
+     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/order/{order-id}/finalize`
+     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4.2
      */
-    async e2eiNewActivationEnrollment(
-        displayName: string,
-        handle: string,
-        expirySec: number,
-        ciphersuite: Ciphersuite,
-        team?: string
-    ): Promise<E2eiEnrollment> {
-        const e2ei = await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_new_activation_enrollment(
-                displayName,
-                handle,
-                team,
-                expirySec,
-                ciphersuite
-            )
+    async certificateRequest(previousNonce: string): Promise<JsonRawData> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#enrollment.certificate_request(previousNonce)
         );
-        return new E2eiEnrollment(e2ei);
     }
+}
 
+/**
+ * Health of a conversation based on the number of consecutive messages which could not be decrypted
+ */
+export enum ConversationHealthState {
     /**
-     * Generates an E2EI enrollment instance for a E2EI client (with a X509 certificate credential)
-     * having to change/rotate their credential, either because the former one is expired or it
-     * has been revoked. It lets you change the DisplayName or the handle
-     * if you need to. Once the enrollment is finished, use the instance in {@link CoreCrypto.e2eiRotateAll} to do the rotation.
-     *
-     * @param expirySec - generated x509 certificate expiry
-     * @param ciphersuite - for generating signing key material
-     * @param displayName - human-readable name displayed in the application e.g. `Smith, Alice M (QA)`
-     * @param handle - user handle e.g. `alice.smith.qa@example.com`
-     * @param team - name of the Wire team a user belongs to
-     * @returns The new {@link E2eiEnrollment} enrollment instance to use with {@link CoreCrypto.e2eiRotateAll}
+     * The last message could be decrypted
      */
-    async e2eiNewRotateEnrollment(
-        expirySec: number,
-        ciphersuite: Ciphersuite,
-        displayName?: string,
-        handle?: string,
-        team?: string
-    ): Promise<E2eiEnrollment> {
-        const e2ei = await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_new_rotate_enrollment(
-                displayName,
-                handle,
-                team,
-                expirySec,
-                ciphersuite
-            )
-        );
-        return new E2eiEnrollment(e2ei);
-    }
+    Healthy = 0x0001,
+    /**
+     * Some messages in a row could not be decrypted
+     */
+    Suspect = 0x0002,
+    /**
+     * Too many messages in a row could not be decrypted, the conversation should be rejoined
+     */
+    Broken = 0x0003,
+}
 
+/**
+ * see {@link CoreCrypto.buildMetadata}
+ */
+export interface BuildMetadata {
     /**
-     * Use this method to initialize end-to-end identity when a client signs up and the grace period is already expired ;
-     * that means he cannot initialize with a Basic credential
-     *
-     * @param enrollment - the enrollment instance used to fetch the certificates
-     * @param certificateChain - the raw response from ACME server
-     * @param nbKeyPackage - number of initial KeyPackage to create when initializing the client
-     * @returns a MlsClient initialized with only a x509 credential
+     * Version as defined in `Cargo.toml` e.g. `1.0.0-rc.42`
+     */
+    version: string;
+    /**
+     * Hash of the git commit built, `unknown` when not built from a git checkout
+     */
+    gitHash: string;
+    /**
+     * Target triple e.g. `wasm32-unknown-unknown`
+     */
+    target: string;
+    /**
+     * Cargo profile e.g. `release`
+     */
+    profile: string;
+    /**
+     * Enabled optional features e.g. `proteus`
+     */
+    features: string[];
+}
+
+/**
+ * see {@link CoreCrypto.epochHistory}
+ */
+export interface EpochHistoryEntry {
+    /**
+     * Epoch number
+     */
+    epoch: number;
+    /**
+     * Hash of the epoch authenticator. Members agree on it as long as they share the same group state
+     */
+    authenticatorHash: Uint8Array;
+    /**
+     * Unix timestamp (in seconds) of when the epoch was entered locally
+     */
+    timestamp: number;
+    /**
+     * Id of the client whose commit created the epoch. Unknown when joining by Welcome
+     */
+    commitSender?: ClientId;
+}
+
+/**
+ * see {@link CoreCrypto.seenCredentials}
+ */
+export interface CredentialFirstSeen {
+    /**
+     * Id of the client presenting the credential
+     */
+    clientId: ClientId;
+    /**
+     * TLS-serialized credential
+     */
+    credential: Uint8Array;
+    /**
+     * Unix timestamp (in seconds) of the first time the credential was seen in the conversation
+     */
+    firstSeen: number;
+}
+
+/**
+ * see {@link CoreCrypto.clientPublicKeyBundle}
+ */
+export interface PublicKeyBundle {
+    /**
+     * IANA identifier of the signature scheme of the key
+     */
+    signatureScheme: number;
+    /**
+     * Type of the credential the key belongs to
+     */
+    credentialType: CredentialType;
+    /**
+     * Public signature key
+     */
+    publicKey: Uint8Array;
+    /**
+     * DER-encoded certificate chain, leaf first. Empty for a Basic credential
+     */
+    certificateChain: Uint8Array[];
+}
+
+/**
+ * Durations of the calls to an operation, see {@link MetricsSnapshot}
+ */
+export interface OperationMetrics {
+    /**
+     * Number of calls, successful or not
+     */
+    calls: number;
+    /**
+     * Cumulated duration of the calls in microseconds
+     */
+    totalMicros: number;
+    /**
+     * Duration of the slowest call in microseconds
+     */
+    maxMicros: number;
+}
+
+/**
+ * Redacted view of a Proteus session, see {@link ProteusDiagnostics}
+ */
+export interface ProteusSessionDebugInfo {
+    /**
+     * ID of the Proteus session
+     */
+    sessionId: string;
+    /**
+     * Hex-encoded tag of the current session state, the same on both ends of the session
+     */
+    sessionTag: string;
+    /**
+     * Whether nothing was received on this session yet
+     */
+    pendingPrekey: boolean;
+    /**
+     * Number of session states kept, the current one included
+     */
+    sessionStates: number;
+    /**
+     * Number of messages sent on the current sending chain
+     */
+    sendChainCounter: number;
+    /**
+     * Number of messages sent on the previous sending chain
+     */
+    previousSendChainCounter: number;
+    /**
+     * Index of the next message expected on each receiving chain
+     */
+    recvChainCounters: number[];
+    /**
+     * Number of message keys kept aside to decrypt delayed messages
+     */
+    skippedMessageKeys: number;
+}
+
+/**
+ * see {@link CoreCrypto.proteusDiagnostics}
+ */
+export interface ProteusDiagnostics {
+    /**
+     * Hex-encoded public key of the local Proteus identity
+     */
+    identityFingerprint: string;
+    /**
+     * Every session of the keystore
+     */
+    sessions: ProteusSessionDebugInfo[];
+}
+
+/**
+ * see {@link CoreCrypto.previewCommit}
+ */
+export interface CommitPreview {
+    /**
+     * Members once the commit merged: the remaining ones, then the added ones
+     */
+    members: ClientId[];
+    /**
+     * Epoch once the commit merged
+     */
+    epoch: number;
+    /**
+     * Changes made by the commit, pending proposals included
+     */
+    delta: CommitDelta;
+    /**
+     * Size in bytes of the commit
+     */
+    commitSize: number;
+    /**
+     * Size in bytes of the Welcome, absent when nobody is added
+     */
+    welcomeSize?: number;
+    /**
+     * Size in bytes of the GroupInfo
+     */
+    groupInfoSize: number;
+}
+
+/**
+ * see {@link CoreCrypto.verifyIdentityBinding}
+ */
+export interface IdentityBinding {
+    /**
+     * Client both keys belong to
+     */
+    clientId: ClientId;
+    /**
+     * Hex-encoded public key of the Proteus identity, to be compared with {@link CoreCrypto.proteusFingerprintRemote}
+     */
+    proteusFingerprint: string;
+    /**
+     * Public signature key of the MLS credential
+     */
+    mlsPublicKey: Uint8Array;
+}
+
+/**
+ * see {@link CoreCrypto.metricsSnapshot}
+ */
+export interface MetricsSnapshot {
+    /**
+     * Application messages encrypted
+     */
+    messagesEncrypted: number;
+    /**
+     * Messages decrypted, handshake ones included
+     */
+    messagesDecrypted: number;
+    /**
+     * Commits merged, be they own, incoming or external ones
+     */
+    epochsAdvanced: number;
+    /**
+     * Entities saved in the keystore
+     */
+    keystoreWrites: number;
+    /**
+     * Bytes of MLS group states and key material written in the keystore, before encryption
+     */
+    keystoreBytesWritten: number;
+    /**
+     * see {@link CoreCrypto.encryptMessage}
+     */
+    encrypt: OperationMetrics;
+    /**
+     * see {@link CoreCrypto.decryptMessage}
+     */
+    decrypt: OperationMetrics;
+    /**
+     * Creating a commit e.g. {@link CoreCrypto.updateKeyingMaterial}
+     */
+    commit: OperationMetrics;
+    /**
+     * see {@link CoreCrypto.commitAccepted}
      */
-    async e2eiMlsInitOnly(
-        enrollment: E2eiEnrollment,
-        certificateChain: string,
-        nbKeyPackage?: number
-    ): Promise<string[] | undefined> {
-        return await this.#cc.e2ei_mls_init_only(
-            enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity,
-            certificateChain,
-            nbKeyPackage
-        );
-    }
+    merge: OperationMetrics;
+}
 
+/**
+ * see {@link CoreCrypto.conversationStats}
+ */
+export interface ConversationStats {
     /**
-    * Registers a Root Trust Anchor CA for the use in E2EI processing.
-    *
-    * Please note that without a Root Trust Anchor, all validations *will* fail;
-    * So this is the first step to perform after initializing your E2EI client
-    *
-    * @param trustAnchorPEM - PEM certificate to anchor as a Trust Root
-    */
-    async e2eiRegisterAcmeCA(trustAnchorPEM: string): Promise<void> {
-        return await this.#cc.e2ei_register_acme_ca(trustAnchorPEM);
-    }
-
+     * Application messages encrypted
+     */
+    messagesSent: number;
     /**
-    * Registers an Intermediate CA for the use in E2EI processing.
-    *
-    * Please note that a Root Trust Anchor CA is needed to validate Intermediate CAs;
-    * You **need** to have a Root CA registered before calling this
-    *
-    * @param certPEM - PEM certificate to register as an Intermediate CA
-    */
-    async e2eiRegisterIntermediateCA(certPEM: string): Promise<string[] | undefined> {
-        return await this.#cc.e2ei_register_intermediate_ca(certPEM);
-    }
-
+     * Application messages decrypted
+     */
+    messagesReceived: number;
     /**
-    * Registers a CRL for the use in E2EI processing.
-    *
-    * Please note that a Root Trust Anchor CA is needed to validate CRLs;
-    * You **need** to have a Root CA registered before calling this
-    *
-    * @param crlDP - CRL Distribution Point; Basically the URL you fetched it from
-    * @param crlDER - DER representation of the CRL
-    *
-    * @returns a {@link CRLRegistration} with the dirty state of the new CRL (see struct) and its expiration timestamp
-    */
-    async e2eiRegisterCRL(crlDP: string, crlDER: Uint8Array): Promise<CRLRegistration> {
-        return await this.#cc.e2ei_register_crl(crlDP, crlDER);
-    }
-
+     * Own commits merged, including joining by external commit
+     */
+    commitsSent: number;
     /**
-     * Creates a commit in all local conversations for changing the credential. Requires first
-     * having enrolled a new X509 certificate with either {@link CoreCrypto.e2eiNewActivationEnrollment}
-     * or {@link CoreCrypto.e2eiNewRotateEnrollment}
-     *
-     * @param enrollment - the enrollment instance used to fetch the certificates
-     * @param certificateChain - the raw response from ACME server
-     * @param newKeyPackageCount - number of KeyPackages with new identity to generate
-     * @returns a {@link RotateBundle} with commits to fan-out to other group members, KeyPackages to upload and old ones to delete
+     * Incoming commits merged
      */
-    async e2eiRotateAll(
-        enrollment: E2eiEnrollment,
-        certificateChain: string,
-        newKeyPackageCount: number
-    ): Promise<RotateBundle> {
-        const ffiRet: CoreCryptoFfiTypes.RotateBundle =
-            await this.#cc.e2ei_rotate_all(
-                enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity,
-                certificateChain,
-                newKeyPackageCount
-            );
-
-        const ret: RotateBundle = {
-            commits: ffiRet.commits,
-            newKeyPackages: ffiRet.new_key_packages,
-            keyPackageRefsToRemove: ffiRet.key_package_refs_to_remove,
-            crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
-        };
-
-        return ret;
-    }
-
+    commitsReceived: number;
     /**
-     * Allows persisting an active enrollment (for example while redirecting the user during OAuth) in order to resume
-     * it later with {@link e2eiEnrollmentStashPop}
-     *
-     * @param enrollment the enrollment instance to persist
-     * @returns a handle to fetch the enrollment later with {@link e2eiEnrollmentStashPop}
+     * Epoch of the conversation when one of the above last happened, unset when nothing has been counted yet
      */
-    async e2eiEnrollmentStash(enrollment: E2eiEnrollment): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_enrollment_stash(
-                enrollment.inner() as CoreCryptoFfiTypes.FfiWireE2EIdentity
-            )
-        );
-    }
-
+    lastActivityEpoch?: number;
     /**
-     * Fetches the persisted enrollment and deletes it from the keystore
-     *
-     * @param handle returned by {@link e2eiEnrollmentStash}
-     * @returns the persisted enrollment instance
+     * Unix timestamp (in seconds) of when one of the above last happened, unset when nothing has been counted yet
      */
-    async e2eiEnrollmentStashPop(handle: Uint8Array): Promise<E2eiEnrollment> {
-        const e2ei = await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_enrollment_stash_pop(handle)
-        );
-        return new E2eiEnrollment(e2ei);
-    }
+    lastActivity?: number;
+}
 
+/**
+ * see {@link CoreCrypto.ratchetDiagnostics}
+ */
+export interface RatchetDiagnostics {
     /**
-     * Indicates when to mark a conversation as not verified i.e. when not all its members have a X509.
-     * Credential generated by Wire's end-to-end identity enrollment
-     *
-     * @param conversationId The group's ID
-     * @returns the conversation state given current members
+     * Current epoch of the conversation
      */
-    async e2eiConversationState(
-        conversationId: ConversationId
-    ): Promise<E2eiConversationState> {
-        let state = await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_conversation_state(conversationId)
-        );
-        // @ts-ignore
-        return E2eiConversationState[E2eiConversationState[state]];
-    }
-
+    epoch: number;
     /**
-     * Returns true when end-to-end-identity is enabled for the given Ciphersuite
-     *
-     * @param ciphersuite of the credential to check
-     * @returns true if end-to-end identity is enabled for the given ciphersuite
+     * Generation of the next application message this client encrypts, i.e. how many it encrypted in the epoch. Only
+     * counted while the conversation is loaded, hence a lower bound after a restart
      */
-    async e2eiIsEnabled(ciphersuite: Ciphersuite): Promise<boolean> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.e2ei_is_enabled(ciphersuite)
-        );
-    }
+    ownGeneration: number;
+    /**
+     * see {@link CustomConfiguration.outOfOrderTolerance}
+     */
+    outOfOrderTolerance: number;
+    /**
+     * see {@link CustomConfiguration.maximumForwardDistance}
+     */
+    maximumForwardDistance: number;
+    /**
+     * Past epochs whose secrets are kept to decrypt the messages arriving late, fewer when this client entered the
+     * conversation recently
+     */
+    retainedPastEpochs: number;
+    /**
+     * How many more application messages this client can encrypt in the epoch before a member who missed them all can
+     * no longer decrypt the next one, assuming it has the same {@link RatchetDiagnostics.maximumForwardDistance}
+     */
+    forwardDistanceRemaining: number;
+}
 
+/**
+ * see {@link CoreCrypto.restoreNextGroups}
+ */
+export interface GroupRestoreProgress {
     /**
-     * From a given conversation, get the identity of the members supplied. Identity is only present for members with a
-     * Certificate Credential (after turning on end-to-end identity).
-     *
-     * @param conversationId - identifier of the conversation
-     * @param deviceIds - identifiers of the devices
-     * @returns identities or if no member has a x509 certificate, it will return an empty List
+     * Conversations restored so far, the ones already loaded when first used included
      */
-    async getDeviceIdentities(
-        conversationId: ConversationId,
-        deviceIds: ClientId[]
-    ): Promise<WireIdentity[]> {
-        return (await CoreCryptoError.asyncMapErr(
-            this.#cc.get_device_identities(conversationId, deviceIds)
-        )).map(mapWireIdentity);
-    }
+    restored: number;
+    /**
+     * Conversations persisted when the restoration started
+     */
+    total: number;
+}
 
+/**
+ * see {@link CoreCrypto.conversationCacheStats}
+ */
+export interface ConversationCacheStats {
     /**
-     * From a given conversation, get the identity of the users (device holders) supplied.
-     * Identity is only present for devices with a Certificate Credential (after turning on end-to-end identity).
-     * If no member has a x509 certificate, it will return an empty Vec.
-     *
-     * @param conversationId - identifier of the conversation
-     * @param userIds - user identifiers hyphenated UUIDv4 e.g. 'bd4c7053-1c5a-4020-9559-cd7bf7961954'
-     * @returns a Map with all the identities for a given users. Consumers are then recommended to reduce those identities to determine the actual status of a user.
+     * Conversations found in memory
      */
-    async getUserIdentities(
-        conversationId: ConversationId,
-        userIds: string[]
-    ): Promise<Map<string, WireIdentity[]>> {
-        const map: Map<string, CoreCryptoFfiTypes.WireIdentity[]> = await CoreCryptoError.asyncMapErr(
-            this.#cc.get_user_identities(conversationId, userIds)
-        );
+    hits: number;
+    /**
+     * Conversations which had to be loaded from the database
+     */
+    misses: number;
+    /**
+     * Conversations currently held in memory
+     */
+    cached: number;
+}
 
-        const mapFixed: Map<string, WireIdentity[]> = new Map();
+/**
+ * Maintenance work listed by {@link CoreCrypto.pendingMaintenanceTasks}
+ */
+export enum MaintenanceTaskKind {
+    /**
+     * Deletes the expired KeyPackages along with their private keys
+     */
+    PruneExpiredKeyPackages = 0x0001,
+    /**
+     * Rotates the own key material of a conversation whose `keyRotationSpan` elapsed
+     */
+    UpdateKeyingMaterial = 0x0002,
+    /**
+     * Discards the messages buffered for conversations which no longer exist locally
+     */
+    ExpireBufferedMessages = 0x0003,
+    /**
+     * Gives the storage left over by deleted entities back to the filesystem
+     */
+    VacuumKeystore = 0x0004,
+    /**
+     * Deletes the transient entities left behind for too long, e.g. the pending group of an external commit which was
+     * neither merged nor cleared
+     */
+    PurgeExpiredEntities = 0x0005,
+}
 
-        for (const [userId, identities] of map) {
-            const mappedIdentities = identities.flatMap(identity => {
-                const mappedIdentity = mapWireIdentity(identity);
-                return mappedIdentity ? [mappedIdentity] : [];
-            });
-            mapFixed.set(userId, mappedIdentities);
-        }
+/**
+ * How soon a maintenance task should run
+ */
+export enum MaintenancePriority {
+    /**
+     * Whenever the device is idle
+     */
+    Low = 0x0001,
+    /**
+     * In the next background slot
+     */
+    Normal = 0x0002,
+    /**
+     * As soon as possible, e.g. it affects the security of conversations
+     */
+    High = 0x0003,
+}
 
-        return mapFixed;
-    }
+/**
+ * see {@link CoreCrypto.pendingMaintenanceTasks}
+ */
+export interface MaintenanceItem {
+    /**
+     * What to run, see {@link CoreCrypto.runMaintenance}
+     */
+    kind: MaintenanceTaskKind;
+    /**
+     * The conversation of {@link MaintenanceTaskKind.UpdateKeyingMaterial}
+     */
+    conversationId?: ConversationId;
+    /**
+     * How soon
+     */
+    priority: MaintenancePriority;
+}
 
+/**
+ * see {@link CoreCrypto.validateKeypackage}
+ */
+export interface KeyPackageValidationReport {
     /**
-     * Gets the e2ei conversation state from a `GroupInfo`. Useful to check if the group has e2ei
-     * turned on or not before joining it.
-     *
-     * @param groupInfo - a TLS encoded GroupInfo fetched from the Delivery Service
-     * @param credentialType - kind of Credential to check usage of. Defaults to X509 for now as no other value will give any result.
-     * @returns see {@link E2eiConversationState}
+     * Whether the KeyPackage can be used to add its owner to a conversation
      */
-    async getCredentialInUse(groupInfo: Uint8Array, credentialType: CredentialType = CredentialType.X509): Promise<E2eiConversationState> {
-        let state = await CoreCryptoError.asyncMapErr(this.#cc.get_credential_in_use(groupInfo, credentialType));
-        // @ts-ignore
-        return E2eiConversationState[E2eiConversationState[state]];
-    }
+    isValid: boolean;
+    /**
+     * Ciphersuite of the KeyPackage, unset when it is unknown to this client
+     */
+    ciphersuite?: Ciphersuite;
+    /**
+     * Whether this client supports the ciphersuite of the KeyPackage
+     */
+    ciphersuiteSupported: boolean;
+    /**
+     * Type of the credential of the KeyPackage
+     */
+    credentialType: CredentialType;
+    /**
+     * Whether now lies within the lifetime of the KeyPackage. Also false when the KeyPackage has been rejected for
+     * another reason since its lifetime can't be trusted then
+     */
+    lifetimeValid: boolean;
+    /**
+     * Whether the signatures of the KeyPackage and of its LeafNode are valid
+     */
+    signatureValid: boolean;
+    /**
+     * Why the KeyPackage has been rejected, unset when it has been accepted
+     */
+    failure?: string;
+}
 
+/**
+ * see {@link CoreCrypto.keypackagesUploadPayload}
+ */
+export interface KeyPackageUploadPayload {
+    /**
+     * Base64 encoded TLS serialized KeyPackages
+     */
+    keyPackages: string[];
     /**
-     * Returns the current version of {@link CoreCrypto}
-     *
-     * @returns The `core-crypto-ffi` version as defined in its `Cargo.toml` file
+     * References of the KeyPackages in the same order, e.g. to delete them later with {@link CoreCrypto.deleteKeypackages}
      */
-    static version(): string {
-        this.#assertModuleLoaded();
-        return CoreCryptoFfi.version();
-    }
+    refs: Uint8Array[];
+    /**
+     * Body of the upload request i.e. `{"key_packages":["<base64>",...]}`
+     */
+    json: string;
 }
 
-type JsonRawData = Uint8Array;
-
-export class E2eiEnrollment {
-    /** @hidden */
-    #enrollment: CoreCryptoFfiTypes.FfiWireE2EIdentity;
-
-    /** @hidden */
-    constructor(e2ei: unknown) {
-        this.#enrollment = e2ei as CoreCryptoFfiTypes.FfiWireE2EIdentity;
-    }
-
-    free() {
-        this.#enrollment.free();
-    }
-
+/**
+ * see {@link CoreCrypto.conversationExternalSenders}
+ */
+export interface ExternalSender {
     /**
-     * Should only be used internally
+     * Raw public signature key
      */
-    inner(): unknown {
-        return this.#enrollment as CoreCryptoFfiTypes.FfiWireE2EIdentity;
-    }
-
+    signatureKey: Uint8Array;
     /**
-     * Parses the response from `GET /acme/{provisioner-name}/directory`.
-     * Use this {@link AcmeDirectory} in the next step to fetch the first nonce from the acme server. Use
-     * {@link AcmeDirectory.newNonce}.
-     *
-     * @param directory HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.1.1
+     * Identity of the credential of the external sender
      */
-    async directoryResponse(directory: JsonRawData): Promise<AcmeDirectory> {
-        const ffiRet: CoreCryptoFfiTypes.AcmeDirectory = await CoreCryptoError.asyncMapErr(
-            this.#enrollment.directory_response(directory)
-        );
-
-        return {
-            newNonce: ffiRet.new_nonce,
-            newAccount: ffiRet.new_account,
-            newOrder: ffiRet.new_order,
-            revokeCert: ffiRet.revoke_cert,
-        };
-    }
+    identity: Uint8Array;
+}
 
+/**
+ * see {@link CoreCrypto.epochCleanupMetrics}
+ */
+export interface EpochCleanupMetrics {
     /**
-     * For creating a new acme account. This returns a signed JWS-alike request body to send to
-     * `POST /acme/{provisioner-name}/new-account`.
-     *
-     * @param previousNonce you got from calling `HEAD {@link AcmeDirectory.newNonce}`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.3
+     * Number of times the cleanup ran, i.e. once per merged commit
      */
-    async newAccountRequest(previousNonce: string): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_account_request(previousNonce)
-        );
-    }
-
+    runs: number;
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/new-account`.
-     * @param account HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.3
+     * Number of epoch encryption keypairs deleted
      */
-    async newAccountResponse(account: JsonRawData): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_account_response(account)
-        );
-    }
+    deletedKeypairs: number;
+}
 
+/**
+ * How the identifiers (client id, conversation ids) appear in a report of {@link CoreCrypto.generateDiagnosticReport}
+ */
+export enum RedactionLevel {
     /**
-     * Creates a new acme order for the handle (userId + display name) and the clientId.
-     *
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/new-account`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * Identifiers appear as is, hex encoded
      */
-    async newOrderRequest(previousNonce: string): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_order_request(previousNonce)
-        );
-    }
-
+    None = 1,
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/new-order`.
-     *
-     * @param order HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * Identifiers are replaced by the beginning of their SHA-256 hash. Reports of different clients can still be
+     * matched but the identifiers can't be read back
      */
-    async newOrderResponse(order: JsonRawData): Promise<NewAcmeOrder> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_order_response(order)
-        );
-    }
-
+    Hashed = 2,
     /**
-     * Creates a new authorization request.
-     *
-     * @param url one of the URL in new order's authorizations (use {@link NewAcmeOrder.authorizations} from {@link newOrderResponse})
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/new-order` (or from the
-     * previous to this method if you are creating the second authorization)
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
+     * Identifiers are left out
      */
-    async newAuthzRequest(
-        url: string,
-        previousNonce: string
-    ): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_authz_request(url, previousNonce)
-        );
-    }
+    Full = 3,
+}
 
+/**
+ * What a check of {@link CoreCrypto.selfTest} exercises
+ */
+export enum SelfTestCheckKind {
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/authz/{authz-id}`
-     *
-     * @param authz HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
+     * Key generation, signature and verification with the signature scheme of a ciphersuite
      */
-    async newAuthzResponse(authz: JsonRawData): Promise<NewAcmeAuthz> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_authz_response(authz)
-        );
-    }
-
+    Signature = 0x0001,
     /**
-     * Generates a new client Dpop JWT token. It demonstrates proof of possession of the nonces
-     * (from wire-server & acme server) and will be verified by the acme server when verifying the
-     * challenge (in order to deliver a certificate).
-     *
-     * Then send it to `POST /clients/{id}/access-token`
-     * {@link https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/post_clients__cid__access_token} on wire-server.
-     *
-     * @param expirySecs of the client Dpop JWT. This should be equal to the grace period set in Team Management
-     * @param backendNonce you get by calling `GET /clients/token/nonce` on wire-server as defined here {@link https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/get_clients__client__nonce}
+     * HPKE seal then open with the HPKE configuration of a ciphersuite
      */
-    async createDpopToken(
-        expirySecs: number,
-        backendNonce: string
-    ): Promise<Uint8Array> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.create_dpop_token(expirySecs, backendNonce)
-        );
-    }
-
+    Hpke = 0x0002,
     /**
-     * Creates a new challenge request for Wire Dpop challenge.
-     *
-     * @param accessToken returned by wire-server from https://staging-nginz-https.zinfra.io/api/swagger-ui/#/default/post_clients__cid__access_token
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/authz/{authz-id}`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     * AEAD encryption then decryption with the AEAD of a ciphersuite
      */
-    async newDpopChallengeRequest(
-        accessToken: string,
-        previousNonce: string
-    ): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_dpop_challenge_request(
-                accessToken,
-                previousNonce
-            )
-        );
-    }
-
+    Aead = 0x0003,
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/challenge/{challenge-id}` for the DPoP challenge.
-     *
-     * @param challenge HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     * Writing, reading and removing a record in the keystore
      */
-    async newDpopChallengeResponse(challenge: JsonRawData): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_dpop_challenge_response(challenge)
-        );
-    }
+    Keystore = 0x0004,
+}
 
+/**
+ * Outcome of a check of {@link CoreCrypto.selfTest}
+ */
+export interface SelfTestCheck {
     /**
-     * Creates a new challenge request for Wire Oidc challenge.
-     *
-     * @param idToken you get back from Identity Provider
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/authz/{authz-id}`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     * see {@link SelfTestCheckKind}
      */
-    async newOidcChallengeRequest(
-        idToken: string,
-        previousNonce: string
-    ): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_oidc_challenge_request(
-                idToken,
-                previousNonce
-            )
-        );
-    }
-
+    kind: SelfTestCheckKind;
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/challenge/{challenge-id}` for the OIDC challenge.
-     *
-     * @param cc the CoreCrypto instance
-     * @param challenge HTTP response body
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+     * Ciphersuite whose primitives have been checked, none for the keystore
      */
-    async newOidcChallengeResponse(challenge: JsonRawData): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.new_oidc_challenge_response(challenge)
-        );
-    }
-
+    ciphersuite?: Ciphersuite;
     /**
-     * Verifies that the previous challenge has been completed.
-     *
-     * @param orderUrl `location` header from http response you got from {@link newOrderResponse}
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/challenge/{challenge-id}`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * Why the check failed, none when it passed
      */
-    async checkOrderRequest(
-        orderUrl: string,
-        previousNonce: string
-    ): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.check_order_request(orderUrl, previousNonce)
-        );
-    }
+    failure?: string;
+}
 
+/**
+ * see {@link CoreCrypto.selfTest}
+ */
+export interface SelfTestReport {
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/order/{order-id}`.
-     *
-     * @param order HTTP response body
-     * @return finalize url to use with {@link finalizeRequest}
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * Whether every check passed
      */
-    async checkOrderResponse(order: JsonRawData): Promise<string> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.check_order_response(order)
-        );
-    }
-
+    passed: boolean;
     /**
-     * Final step before fetching the certificate.
-     *
-     * @param previousNonce - `replay-nonce` response header from `POST /acme/{provisioner-name}/order/{order-id}`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * Every check which has been run
      */
-    async finalizeRequest(previousNonce: string): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.finalize_request(previousNonce)
-        );
-    }
+    checks: SelfTestCheck[];
+}
 
+/**
+ * see {@link CoreCrypto.conversationHealth}
+ */
+export interface ConversationHealth {
     /**
-     * Parses the response from `POST /acme/{provisioner-name}/order/{order-id}/finalize`.
-     *
-     * @param finalize HTTP response body
-     * @return the certificate url to use with {@link certificateRequest}
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+     * see {@link ConversationHealthState}
      */
-    async finalizeResponse(finalize: JsonRawData): Promise<string> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.finalize_response(finalize)
-        );
-    }
-
+    state: ConversationHealthState;
     /**
-     * Creates a request for finally fetching the x509 certificate.
-     *
-     * @param previousNonce `replay-nonce` response header from `POST /acme/{provisioner-name}/order/{order-id}/finalize`
-     * @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4.2
+     * Number of consecutive decryption failures
      */
-    async certificateRequest(previousNonce: string): Promise<JsonRawData> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#enrollment.certificate_request(previousNonce)
-        );
-    }
+    failures: number;
 }
 
 /**
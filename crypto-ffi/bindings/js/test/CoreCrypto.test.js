@@ -471,6 +471,148 @@ test("encrypt message", async () => {
   await ctx.close();
 });
 
+test("transaction", async () => {
+  const [ctx, page] = await initBrowser();
+
+  const [committed, rolledBack, rethrown] = await page.evaluate(async () => {
+    const { CoreCrypto, Ciphersuite, CredentialType } = await import("./corecrypto.js");
+
+    const ciphersuite = Ciphersuite.MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    const credentialType = CredentialType.Basic;
+    const cc = await CoreCrypto.init({
+      databaseName: "transaction",
+      key: "test",
+      ciphersuites: [ciphersuite],
+      clientId: "test",
+    });
+
+    const encoder = new TextEncoder();
+    const committedId = encoder.encode("committedConversation");
+    const rolledBackId = encoder.encode("rolledBackConversation");
+
+    await cc.transaction(async (ctx) => {
+      await ctx.createConversation(committedId, credentialType);
+    });
+
+    let rethrown = false;
+    try {
+      await cc.transaction(async (ctx) => {
+        await ctx.createConversation(rolledBackId, credentialType);
+        throw new Error("abort");
+      });
+    } catch (e) {
+      rethrown = e.message === "abort";
+    }
+
+    const committed = await cc.conversationExists(committedId);
+    const rolledBack = await cc.conversationExists(rolledBackId);
+
+    await cc.wipe();
+
+    return [committed, rolledBack, rethrown];
+  });
+
+  expect(committed).toBe(true);
+  expect(rolledBack).toBe(false);
+  expect(rethrown).toBe(true);
+
+  await page.close();
+  await ctx.close();
+});
+
+test("transfer to a worker", async () => {
+  const [ctx, page] = await initBrowser();
+
+  const [handle, epoch, errorCode, expectedErrorCode] = await page.evaluate(async () => {
+    const {
+      CoreCrypto,
+      CoreCryptoWorkerClient,
+      Ciphersuite,
+      CredentialType,
+      CoreCryptoErrorCode,
+    } = await import("./corecrypto.js");
+
+    const ciphersuite = Ciphersuite.MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    const cc = await CoreCrypto.init({
+      databaseName: "transfer to a worker",
+      key: "test",
+      ciphersuites: [ciphersuite],
+      clientId: "test",
+      nbKeyPackage: 5,
+      takeOverStore: true,
+      lazyGroupRestore: true,
+      disableGroupCache: true,
+      clockSkewLeewaySeconds: 60,
+    });
+    await cc.setClockSkewLeeway(120);
+
+    const encoder = new TextEncoder();
+    const conversationId = encoder.encode("testConversation");
+    await cc.createConversation(conversationId, CredentialType.Basic);
+    const handle = await cc.transferToWorker();
+
+    const workerSource = `
+      import { CoreCrypto, CoreCryptoWorkerHost } from "${location.origin}/corecrypto.js";
+      self.addEventListener("message", async ({ data }) => {
+        if (data.databaseName) {
+          const cc = await CoreCrypto.fromTransferHandle(data);
+          CoreCryptoWorkerHost.serve(cc, self);
+          self.postMessage("ready");
+        }
+      });
+    `;
+    const worker = new Worker(
+      URL.createObjectURL(new Blob([workerSource], { type: "text/javascript" })),
+      { type: "module" },
+    );
+    const ready = new Promise((resolve) => worker.addEventListener("message", ({ data }) => {
+      if (data === "ready") {
+        resolve();
+      }
+    }));
+    worker.postMessage(handle);
+    await ready;
+
+    const client = new CoreCryptoWorkerClient(worker);
+    const epoch = await client.call("conversationEpoch", conversationId);
+    let errorCode;
+    try {
+      await client.call("conversationEpoch", encoder.encode("unknownConversation"));
+    } catch (e) {
+      errorCode = e.errorCode;
+    }
+
+    await client.call("wipe");
+    worker.terminate();
+
+    return [
+      { ...handle, clientId: new TextDecoder().decode(handle.clientId) },
+      epoch,
+      errorCode,
+      CoreCryptoErrorCode.ConversationNotFound,
+    ];
+  });
+
+  // every option of the instance is handed over to the worker
+  expect(handle).toEqual({
+    databaseName: "transfer to a worker",
+    key: "test",
+    clientId: "test",
+    ciphersuites: [1],
+    nbKeyPackage: 5,
+    takeOverStore: true,
+    lazyGroupRestore: true,
+    disableGroupCache: true,
+    clockSkewLeewaySeconds: 120,
+  });
+  // the worker opened the same store and answers the calls of the main thread
+  expect(epoch).toBe(0);
+  expect(errorCode).toBe(expectedErrorCode);
+
+  await page.close();
+  await ctx.close();
+});
+
 test("roundtrip message", async () => {
   const [ctx, page] = await initBrowser();
   const [ctx2, page2] = await initBrowser();
@@ -570,7 +712,8 @@ test("roundtrip message", async () => {
 
     const decryptedMessage = await cc2.decryptMessage(welcomeConversationId, encryptedMessage);
 
-    if (!decryptedMessage.message) {
+    // byte buffers are handed over as typed arrays, not as arrays of numbers
+    if (!(decryptedMessage.message instanceof Uint8Array)) {
       return false;
     }
 
@@ -617,6 +760,12 @@ test("callbacks default to false when not async", async () => {
       clientIsExistingGroupUser(conversationId, clientId, existingClients, parentConversationIds) {
         return true;
       },
+      memberChangeAuthorize(conversationId, committer, addedClients, removedClients) {
+        return true;
+      },
+      proteusIdentityChanged(sessionId, previousFingerprint, newFingerprint) {
+        return true;
+      },
     };
 
     const cc = await CoreCrypto.init(client1Config);
@@ -708,6 +857,12 @@ test("ext commits|proposals & callbacks", async () => {
         callbacksResults.clientIsExistingGroupUser = true;
         return true;
       },
+      async memberChangeAuthorize(conversationId, committer, addedClients, removedClients) {
+        return true;
+      },
+      async proteusIdentityChanged(sessionId, previousFingerprint, newFingerprint) {
+        return true;
+      },
     };
 
     const cc = await CoreCrypto.init(client1Config);
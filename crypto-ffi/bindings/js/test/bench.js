@@ -0,0 +1,117 @@
+// Measures the cost of moving MLS application messages of increasing size across the wasm boundary.
+// Run it from `crypto-ffi` once the package has been built with `cargo make wasm`:
+//
+//     bun bindings/js/test/bench.js
+//
+// Compare the output against the one of another revision to see the effect of a change on the bindings.
+const puppeteer = require("puppeteer");
+import { rm } from "node:fs/promises";
+
+const SIZES = [1024, 64 * 1024, 1024 * 1024, 4 * 1024 * 1024];
+const ITERATIONS = 20;
+
+await Bun.write("../platforms/web/index.html", `
+<!DOCTYPE html>
+<html>
+  <head>
+    <title>CoreCrypto Bench</title>
+  </head>
+  <body>
+    <script type="module" src="corecrypto.js"></script>
+  </body>
+</html>
+`);
+
+const server = Bun.serve({
+  port: 3000,
+  hostname: "127.0.0.1",
+  fetch(req) {
+    const url = new URL(req.url);
+    const filename = url.pathname === "/" ? "/index.html" : url.pathname;
+    const file = Bun.file(Bun.resolveSync(`../platforms/web${filename}`, process.cwd()));
+    if (file.size === 0) {
+      return new Response("Not Found", { status: 404 });
+    }
+    return new Response(file);
+  },
+});
+
+const browser = await puppeteer.launch({ headless: "new" });
+
+try {
+  const page = await browser.newPage();
+  await page.goto("http://localhost:3000");
+
+  const results = await page.evaluate(async (sizes, iterations) => {
+    const { CoreCrypto, Ciphersuite, CredentialType } = await import("./corecrypto.js");
+
+    const ciphersuite = Ciphersuite.MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    const credentialType = CredentialType.Basic;
+    const encoder = new TextEncoder();
+    const conversationId = encoder.encode("benchConversation");
+
+    const alice = await CoreCrypto.init({
+      databaseName: "bench alice",
+      key: "alice",
+      clientId: "alice",
+      ciphersuites: [ciphersuite],
+    });
+    const bob = await CoreCrypto.init({
+      databaseName: "bench bob",
+      key: "bob",
+      clientId: "bob",
+      ciphersuites: [ciphersuite],
+    });
+
+    const [kp] = await bob.clientKeypackages(ciphersuite, credentialType, 1);
+    await alice.createConversation(conversationId, credentialType);
+    const { welcome } = await alice.addClientsToConversation(conversationId, [kp]);
+    await alice.commitAccepted(conversationId);
+    await bob.processWelcomeMessage(welcome);
+
+    const results = [];
+    for (const size of sizes) {
+      const plaintext = new Uint8Array(size);
+      // getRandomValues is limited to 64KiB per call
+      for (let offset = 0; offset < size; offset += 65536) {
+        crypto.getRandomValues(plaintext.subarray(offset, offset + 65536));
+      }
+
+      let encryptMs = 0;
+      let decryptMs = 0;
+      for (let i = 0; i < iterations; i++) {
+        let start = performance.now();
+        const ciphertext = await alice.encryptMessage(conversationId, plaintext);
+        encryptMs += performance.now() - start;
+
+        start = performance.now();
+        const { message } = await bob.decryptMessage(conversationId, ciphertext);
+        decryptMs += performance.now() - start;
+
+        if (!(message instanceof Uint8Array) || message.length !== size) {
+          throw new Error(`unexpected decrypted message for a ${size} bytes payload`);
+        }
+      }
+
+      const mibPerSecond = (ms) => ((size * iterations) / (1024 * 1024) / (ms / 1000)).toFixed(2);
+      results.push({
+        size,
+        "encrypt (ms)": (encryptMs / iterations).toFixed(3),
+        "encrypt (MiB/s)": mibPerSecond(encryptMs),
+        "decrypt (ms)": (decryptMs / iterations).toFixed(3),
+        "decrypt (MiB/s)": mibPerSecond(decryptMs),
+      });
+    }
+
+    await alice.wipe();
+    await bob.wipe();
+
+    return results;
+  }, SIZES, ITERATIONS);
+
+  console.table(results);
+} finally {
+  await browser.close();
+  server.stop();
+  await rm("../platforms/web/index.html");
+}
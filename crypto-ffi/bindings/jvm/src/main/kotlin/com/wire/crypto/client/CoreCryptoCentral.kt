@@ -21,6 +21,19 @@ private class Callbacks : CoreCryptoCallbacks {
         existingClients: List<ByteArray>,
         parentConversationClients: List<ByteArray>?
     ): Boolean = true
+
+    override fun memberChangeAuthorize(
+        conversationId: ByteArray,
+        committer: ByteArray,
+        addedClients: List<ByteArray>,
+        removedClients: List<ByteArray>
+    ): Boolean = true
+
+    override fun proteusIdentityChanged(
+        sessionId: String,
+        previousFingerprint: String,
+        newFingerprint: String
+    ): Boolean = true
 }
 
 @Suppress("TooManyFunctions")
@@ -246,11 +259,15 @@ class CoreCryptoCentral private constructor(private val cc: CoreCrypto, private
         suspend operator fun invoke(
             rootDir: String,
             databaseKey: String,
-            ciphersuites: Ciphersuites = Ciphersuites.DEFAULT
+            ciphersuites: Ciphersuites = Ciphersuites.DEFAULT,
+            allowIdentityExport: Boolean = false,
+            takeOverStore: Boolean = false,
+            lazyGroupRestore: Boolean = false,
+            disableGroupCache: Boolean = false
         ): CoreCryptoCentral {
             val path = "$rootDir/$KEYSTORE_NAME"
             File(rootDir).mkdirs()
-            val cc = coreCryptoDeferredInit(path, databaseKey, ciphersuites.lower(), DEFAULT_NB_KEY_PACKAGE)
+            val cc = coreCryptoDeferredInit(path, databaseKey, ciphersuites.lower(), DEFAULT_NB_KEY_PACKAGE, allowIdentityExport, takeOverStore, lazyGroupRestore, disableGroupCache, null, null)
             cc.setCallbacks(Callbacks())
             return CoreCryptoCentral(cc, rootDir)
         }
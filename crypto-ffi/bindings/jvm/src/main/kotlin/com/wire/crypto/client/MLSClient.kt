@@ -30,7 +30,11 @@ class MLSClient(private val cc: com.wire.crypto.CoreCrypto) {
         private val keyRotationDuration: Duration = 30.toDuration(DurationUnit.DAYS)
         private val defaultGroupConfiguration = com.wire.crypto.CustomConfiguration(
             java.time.Duration.ofDays(keyRotationDuration.inWholeDays),
-            com.wire.crypto.MlsWirePolicy.PLAINTEXT
+            com.wire.crypto.MlsWirePolicy.PLAINTEXT,
+            null,
+            null,
+            null,
+            null
         )
     }
 
@@ -167,15 +171,29 @@ class MLSClient(private val cc: com.wire.crypto.CoreCrypto) {
      *
      * @param groupInfo a TLS encoded GroupInfo fetched from the Delivery Service
      * @param credentialType to join the group with
+     * @param expectedCiphersuite the ciphersuite the group has to use, fails early when the GroupInfo uses another one
      */
     suspend fun joinByExternalCommit(
         groupInfo: GroupInfo,
         credentialType: CredentialType = CredentialType.DEFAULT,
         configuration: com.wire.crypto.CustomConfiguration = defaultGroupConfiguration,
+        expectedCiphersuite: Ciphersuite? = null,
     ): CommitBundle {
         // cannot be tested since the groupInfo required is not wrapped in a MlsMessage whereas the one returned
         // in Commit Bundles is... because that's the API the backend imposed
-        return cc.joinByExternalCommit(groupInfo.lower(), configuration, credentialType.lower()).lift()
+        return cc.joinByExternalCommit(groupInfo.lower(), configuration, credentialType.lower(), expectedCiphersuite?.lower()).lift()
+    }
+
+    /**
+     * Recovers a conversation whose local state is unrecoverable, e.g. when decrypting keeps failing with a wrong
+     * epoch. The conversation is wiped locally then joined again by external commit with the same configuration and
+     * the same kind of credential. Then proceed as with [joinByExternalCommit].
+     *
+     * @param id conversation identifier
+     * @param groupInfo a TLS encoded GroupInfo of the conversation fetched from the Delivery Service
+     */
+    suspend fun recoverConversation(id: MLSGroupId, groupInfo: GroupInfo): CommitBundle {
+        return cc.recoverConversation(id.lower(), groupInfo.lower()).lift()
     }
 
     /**
@@ -217,6 +235,9 @@ class MLSClient(private val cc: com.wire.crypto.CoreCrypto) {
             ciphersuite.lower(),
             externalSenders.map { it.lower() },
             defaultGroupConfiguration,
+            null,
+            null,
+            null,
         )
 
         cc.createConversation(id.lower(), creatorCredentialType.lower(), cfg)
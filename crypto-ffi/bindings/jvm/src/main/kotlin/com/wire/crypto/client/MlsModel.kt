@@ -311,6 +311,10 @@ data class DecryptedMessage(
      * New CRL distribution points that appeared by the introduction of a new credential
      */
     val crlNewDistributionPoints: CrlDistributionPoints?,
+    /**
+     * Only set when the decrypted message is a commit. Describes the proposals it applied
+     */
+    val commitDelta: CommitDelta?,
 ) {
 
     override fun equals(other: Any?): Boolean {
@@ -330,6 +334,7 @@ data class DecryptedMessage(
         if (hasEpochChanged != other.hasEpochChanged) return false
         if (identity != other.identity) return false
         if (crlNewDistributionPoints != other.crlNewDistributionPoints) return false
+        if (commitDelta != other.commitDelta) return false
 
         return true
     }
@@ -343,6 +348,7 @@ data class DecryptedMessage(
         result = 31 * result + hasEpochChanged.hashCode()
         result = 31 * result + (identity?.hashCode() ?: 0)
         result = 31 * result + (crlNewDistributionPoints?.hashCode() ?: 0)
+        result = 31 * result + (commitDelta?.hashCode() ?: 0)
         return result
     }
 }
@@ -356,7 +362,8 @@ fun com.wire.crypto.DecryptedMessage.lift() = DecryptedMessage(
     hasEpochChanged,
     identity?.lift(),
     bufferedMessages?.map { it.lift() },
-    crlNewDistributionPoints?.toCrlDistributionPoint()
+    crlNewDistributionPoints?.toCrlDistributionPoint(),
+    commitDelta?.lift()
 )
 
 /**
@@ -379,6 +386,8 @@ data class BufferedDecryptedMessage(
     val identity: WireIdentity?,
     /** @see DecryptedMessage.crlNewDistributionPoints */
     val crlNewDistributionPoints: CrlDistributionPoints?,
+    /** @see DecryptedMessage.commitDelta */
+    val commitDelta: CommitDelta?,
 ) {
 
     override fun equals(other: Any?): Boolean {
@@ -398,6 +407,7 @@ data class BufferedDecryptedMessage(
         if (hasEpochChanged != other.hasEpochChanged) return false
         if (identity != other.identity) return false
         if (crlNewDistributionPoints != other.crlNewDistributionPoints) return false
+        if (commitDelta != other.commitDelta) return false
 
         return true
     }
@@ -411,6 +421,7 @@ data class BufferedDecryptedMessage(
         result = 31 * result + hasEpochChanged.hashCode()
         result = 31 * result + (identity?.hashCode() ?: 0)
         result = 31 * result + (crlNewDistributionPoints?.hashCode() ?: 0)
+        result = 31 * result + (commitDelta?.hashCode() ?: 0)
         return result
     }
 }
@@ -423,7 +434,37 @@ fun com.wire.crypto.BufferedDecryptedMessage.lift() = BufferedDecryptedMessage(
     senderClientId?.toClientId(),
     hasEpochChanged,
     identity?.lift(),
-    crlNewDistributionPoints?.toCrlDistributionPoint()
+    crlNewDistributionPoints?.toCrlDistributionPoint(),
+    commitDelta?.lift()
+)
+
+/**
+ * What a commit changed in a conversation
+ */
+data class CommitDelta(
+    /**
+     * Clients added by the commit, including the committer of an external commit
+     */
+    val added: List<ClientId>,
+    /**
+     * Clients removed by the commit
+     */
+    val removed: List<ClientId>,
+    /**
+     * Clients whose leaf was updated, either by an Update proposal or, for the committer, by the commit itself
+     */
+    val updated: List<ClientId>,
+    /**
+     * Whether the leaf of this client was updated, either by one of its Update proposals or because it is the committer
+     */
+    val selfUpdated: Boolean,
+)
+
+fun com.wire.crypto.CommitDelta.lift() = CommitDelta(
+    added.map { it.toClientId() },
+    removed.map { it.toClientId() },
+    updated.map { it.toClientId() },
+    selfUpdated
 )
 
 /**
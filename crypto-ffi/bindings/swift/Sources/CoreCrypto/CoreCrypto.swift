@@ -70,7 +70,8 @@ extension CoreCryptoSwift.DecryptedMessage {
             hasEpochChanged: self.hasEpochChanged,
             identity: self.identity?.convertTo(),
             bufferedMessages: self.bufferedMessages.map({ (bm) -> BufferedDecryptedMessage in return bm.convertTo() }),
-            crlNewDistributionPoints: self.crlNewDistributionPoints
+            crlNewDistributionPoints: self.crlNewDistributionPoints,
+            commitDelta: self.commitDelta?.convertTo()
         )
     }
 }
@@ -85,11 +86,24 @@ extension CoreCryptoSwift.BufferedDecryptedMessage {
             senderClientId: self.senderClientId,
             hasEpochChanged: self.hasEpochChanged,
             identity: self.identity?.convertTo(),
-            crlNewDistributionPoints: self.crlNewDistributionPoints
+            crlNewDistributionPoints: self.crlNewDistributionPoints,
+            commitDelta: self.commitDelta?.convertTo()
         )
     }
 }
 
+extension CoreCryptoSwift.CommitDelta {
+    func convertTo() -> CommitDelta {
+        return CommitDelta(added: self.added, removed: self.removed, updated: self.updated, selfUpdated: self.selfUpdated)
+    }
+}
+
+extension CoreCryptoSwift.BuildMetadata {
+    func convertTo() -> BuildMetadata {
+        return BuildMetadata(version: self.version, gitHash: self.gitHash, target: self.target, profile: self.profile, features: self.features)
+    }
+}
+
 extension CoreCryptoSwift.WireIdentity {
     func convertTo() -> WireIdentity {
         return WireIdentity(clientId: self.clientId, handle: self.handle, displayName: self.displayName, domain: self.domain, thumbprint: self.thumbprint, serialNumber: self.serialNumber, notBefore: self.notBefore, notAfter: self.notAfter)
@@ -218,7 +232,7 @@ public struct ProteusAutoPrekeyBundle: ConvertToInner {
 public struct ConversationConfiguration: ConvertToInner {
     typealias Inner = CoreCryptoSwift.ConversationConfiguration
     func convert() -> Inner {
-        return CoreCryptoSwift.ConversationConfiguration(ciphersuite: self.ciphersuite, externalSenders: self.externalSenders, custom: self.custom.convert())
+        return CoreCryptoSwift.ConversationConfiguration(ciphersuite: self.ciphersuite, externalSenders: self.externalSenders, custom: self.custom.convert(), requiredExtensionTypes: self.requiredExtensionTypes, requiredProposalTypes: self.requiredProposalTypes, requiredCredentialTypes: self.requiredCredentialTypes?.map { $0.convert() })
     }
 
     /// Conversation ciphersuite
@@ -227,11 +241,20 @@ public struct ConversationConfiguration: ConvertToInner {
     public var externalSenders: [[UInt8]]
     /// Implementation specific configuration
     public var custom: CustomConfiguration
-
-    public init(ciphersuite: UInt16, externalSenders: [[UInt8]], custom: CustomConfiguration) {
+    /// Non-default extension types every member has to support. Empty by default
+    public var requiredExtensionTypes: [UInt16]?
+    /// Non-default proposal types every member has to support. Empty by default
+    public var requiredProposalTypes: [UInt16]?
+    /// Credential types every member has to support. Both Basic and X509 by default
+    public var requiredCredentialTypes: [MlsCredentialType]?
+
+    public init(ciphersuite: UInt16, externalSenders: [[UInt8]], custom: CustomConfiguration, requiredExtensionTypes: [UInt16]? = nil, requiredProposalTypes: [UInt16]? = nil, requiredCredentialTypes: [MlsCredentialType]? = nil) {
         self.ciphersuite = ciphersuite
         self.externalSenders = externalSenders
         self.custom = custom
+        self.requiredExtensionTypes = requiredExtensionTypes
+        self.requiredProposalTypes = requiredProposalTypes
+        self.requiredCredentialTypes = requiredCredentialTypes
     }
 }
 
@@ -258,7 +281,7 @@ private extension WirePolicy {
 public struct CustomConfiguration: ConvertToInner {
     typealias Inner = CoreCryptoSwift.CustomConfiguration
     func convert() -> Inner {
-        return CoreCryptoSwift.CustomConfiguration(keyRotationSpan: self.keyRotationSpan, wirePolicy: self.wirePolicy?.convert())
+        return CoreCryptoSwift.CustomConfiguration(keyRotationSpan: self.keyRotationSpan, wirePolicy: self.wirePolicy?.convert(), outOfOrderTolerance: self.outOfOrderTolerance, maximumForwardDistance: self.maximumForwardDistance, allowedCiphersuites: self.allowedCiphersuites, requireX509Credentials: self.requireX509Credentials)
     }
 
     /// Duration in seconds after which we will automatically force a self_update commit
@@ -267,10 +290,24 @@ public struct CustomConfiguration: ConvertToInner {
     /// Defines if handshake messages are encrypted or not
     /// Note: Ciphertext is not currently supported by wire-server
     public var wirePolicy: WirePolicy?
-
-    public init(keyRotationSpan: TimeInterval?, wirePolicy: WirePolicy?) {
+    /// How many application messages of a sender, older than the latest one received from it in the epoch, can still be decrypted.
+    /// Keeping their secrets weakens forward secrecy within an epoch. Defaults to 2
+    public var outOfOrderTolerance: UInt32?
+    /// How many application messages of a sender can be skipped. Deriving the skipped secrets costs time so a large value lets a
+    /// malicious member slow the client down with a single message. Defaults to 1000
+    public var maximumForwardDistance: UInt32?
+    /// Ciphersuites a conversation joined by Welcome is allowed to use. Any is accepted when empty or not set
+    public var allowedCiphersuites: [UInt16]?
+    /// Reject Welcomes for conversations in which a member has a Basic credential e.g. when end-to-end identity is mandated
+    public var requireX509Credentials: Bool?
+
+    public init(keyRotationSpan: TimeInterval?, wirePolicy: WirePolicy?, outOfOrderTolerance: UInt32? = nil, maximumForwardDistance: UInt32? = nil, allowedCiphersuites: [UInt16]? = nil, requireX509Credentials: Bool? = nil) {
         self.keyRotationSpan = keyRotationSpan
         self.wirePolicy = wirePolicy
+        self.outOfOrderTolerance = outOfOrderTolerance
+        self.maximumForwardDistance = maximumForwardDistance
+        self.allowedCiphersuites = allowedCiphersuites
+        self.requireX509Credentials = requireX509Credentials
     }
 }
 
@@ -346,8 +383,10 @@ public struct DecryptedMessage: ConvertToInner {
     public var bufferedMessages: [BufferedDecryptedMessage]?
     /// New CRL distribution points that appeared by the introduction of a new credential
     public var crlNewDistributionPoints: [String]?
+    /// Only set when the decrypted message is a commit. Describes the proposals it applied
+    public var commitDelta: CommitDelta?
 
-    public init(message: [UInt8]?, proposals: [ProposalBundle], isActive: Bool, commitDelay: UInt64?, senderClientId: ClientId?, hasEpochChanged: Bool, identity: WireIdentity?, bufferedMessages: [BufferedDecryptedMessage]?, crlNewDistributionPoints: [String]?) {
+    public init(message: [UInt8]?, proposals: [ProposalBundle], isActive: Bool, commitDelay: UInt64?, senderClientId: ClientId?, hasEpochChanged: Bool, identity: WireIdentity?, bufferedMessages: [BufferedDecryptedMessage]?, crlNewDistributionPoints: [String]?, commitDelta: CommitDelta?) {
         self.message = message
         self.proposals = proposals
         self.isActive = isActive
@@ -357,6 +396,7 @@ public struct DecryptedMessage: ConvertToInner {
         self.identity = identity
         self.bufferedMessages = bufferedMessages
         self.crlNewDistributionPoints = crlNewDistributionPoints
+        self.commitDelta = commitDelta
     }
 
     func convert() -> Inner {
@@ -369,7 +409,8 @@ public struct DecryptedMessage: ConvertToInner {
             hasEpochChanged: self.hasEpochChanged,
             identity: self.identity?.convert(),
             bufferedMessages: self.bufferedMessages.map({ (bm) -> CoreCryptoSwift.DecryptedMessage in bm.convert() }),
-            crlNewDistributionPoints: self.crlNewDistributionPoints
+            crlNewDistributionPoints: self.crlNewDistributionPoints,
+            commitDelta: self.commitDelta?.convert()
         )
     }
 }
@@ -393,9 +434,11 @@ public struct BufferedDecryptedMessage: ConvertToInner {
     public var identity: WireIdentity?
     /// see ```DecryptedMessage.crlNewDistributionPoints```
     public var crlNewDistributionPoints: [String]?
+    /// see ```DecryptedMessage.commitDelta```
+    public var commitDelta: CommitDelta?
 
 
-    public init(message: [UInt8]?, proposals: [ProposalBundle], isActive: Bool, commitDelay: UInt64?, senderClientId: ClientId?, hasEpochChanged: Bool, identity: WireIdentity?, crlNewDistributionPoints: [String]?) {
+    public init(message: [UInt8]?, proposals: [ProposalBundle], isActive: Bool, commitDelay: UInt64?, senderClientId: ClientId?, hasEpochChanged: Bool, identity: WireIdentity?, crlNewDistributionPoints: [String]?, commitDelta: CommitDelta?) {
         self.message = message
         self.proposals = proposals
         self.isActive = isActive
@@ -404,6 +447,7 @@ public struct BufferedDecryptedMessage: ConvertToInner {
         self.hasEpochChanged = hasEpochChanged
         self.identity = identity
         self.crlNewDistributionPoints = crlNewDistributionPoints
+        self.commitDelta = commitDelta
     }
 
     func convert() -> Inner {
@@ -415,11 +459,58 @@ public struct BufferedDecryptedMessage: ConvertToInner {
             senderClientId: self.senderClientId,
             hasEpochChanged: self.hasEpochChanged,
             identity: self.identity?.convert(),
-            crlNewDistributionPoints: self.crlNewDistributionPoints
+            crlNewDistributionPoints: self.crlNewDistributionPoints,
+            commitDelta: self.commitDelta?.convert()
         )
     }
 }
 
+/// What a commit changed in a conversation
+public struct CommitDelta: ConvertToInner {
+    typealias Inner = CoreCryptoSwift.CommitDelta
+    /// Clients added by the commit, including the committer of an external commit
+    public var added: [ClientId]
+    /// Clients removed by the commit
+    public var removed: [ClientId]
+    /// Clients whose leaf was updated, either by an Update proposal or, for the committer, by the commit itself
+    public var updated: [ClientId]
+    /// Whether the leaf of this client was updated, either by one of its Update proposals or because it is the committer
+    public var selfUpdated: Bool
+
+    public init(added: [ClientId], removed: [ClientId], updated: [ClientId], selfUpdated: Bool) {
+        self.added = added
+        self.removed = removed
+        self.updated = updated
+        self.selfUpdated = selfUpdated
+    }
+
+    func convert() -> Inner {
+        return CoreCryptoSwift.CommitDelta(added: self.added, removed: self.removed, updated: self.updated, selfUpdated: self.selfUpdated)
+    }
+}
+
+/// How CoreCrypto has been built
+public struct BuildMetadata {
+    /// Version as defined in `Cargo.toml` e.g. `1.0.0-rc.42`
+    public var version: String
+    /// Hash of the git commit built, `unknown` when not built from a git checkout
+    public var gitHash: String
+    /// Target triple e.g. `aarch64-apple-ios`
+    public var target: String
+    /// Cargo profile e.g. `release`
+    public var profile: String
+    /// Enabled optional features e.g. `proteus`
+    public var features: [String]
+
+    public init(version: String, gitHash: String, target: String, profile: String, features: [String]) {
+        self.version = version
+        self.gitHash = gitHash
+        self.target = target
+        self.profile = profile
+        self.features = features
+    }
+}
+
 /// Represents the identity claims identifying a client. Those claims are verifiable by any member in the group
 public struct WireIdentity: ConvertToInner {
     typealias Inner = CoreCryptoSwift.WireIdentity
@@ -721,6 +812,13 @@ public class CoreCryptoWrapper {
         return try await self.coreCrypto.restoreFromDisk()
     }
 
+    /// Only one `CoreCrypto` instance can use a keystore at a time. An extension opening it with `takeOverStore`
+    /// makes every write of the app instance fail until the app calls this method, which takes the keystore back
+    /// and fetches the MLS groups from it like ``CoreCryptoWrapper/restoreFromDisk``.
+    public func takeOverStore() async throws {
+        return try await self.coreCrypto.takeOverStore()
+    }
+
     /// Sets the callback interface, required by some operations from `CoreCrypto`
     ///
     /// - parameter callbacks: the object that implements the ``CoreCryptoCallbacks`` interface
@@ -830,6 +928,43 @@ public class CoreCryptoWrapper {
         try await self.coreCrypto.markConversationAsChildOf(childId: childId, parentId: parentId)
     }
 
+    /// Creates the sub-conversation of a conversation for a given purpose e.g. a conference call, with the same
+    /// ciphersuite and kind of credential as its parent. Every member of the parent derives the same id for it
+    ///
+    /// - parameter parentId: conversation identifier of the parent conversation
+    /// - parameter purpose: what the sub-conversation is for e.g. `conference`
+    /// - returns: the conversation identifier of the sub-conversation
+    public func createSubConversation(parentId: ConversationId, purpose: String) async throws -> ConversationId {
+        try await self.coreCrypto.createSubConversation(parentId: parentId, purpose: purpose)
+    }
+
+    /// Lists the sub-conversations of a conversation, without loading them
+    ///
+    /// - parameter parentId: conversation identifier of the parent conversation
+    /// - returns: the conversation identifiers of the sub-conversations
+    public func subConversations(parentId: ConversationId) async throws -> [ConversationId] {
+        try await self.coreCrypto.subConversations(parentId: parentId)
+    }
+
+    /// Removes from a sub-conversation the clients which are not members of its parent anymore
+    ///
+    /// **CAUTION**: ``CoreCryptoWrapper/commitAccepted`` **HAS TO** be called afterwards **ONLY IF** the Delivery Service responds
+    /// '200 OK' to the ``CommitBundle`` upload.
+    ///
+    /// - parameter conversationId: conversation identifier of the sub-conversation
+    /// - returns: A ``CommitBundle`` to fan out to the Delivery Service, nothing when every member is still a member of the parent
+    public func syncSubConversationMembers(conversationId: ConversationId) async throws -> CommitBundle? {
+        try await self.coreCrypto.syncSubConversationMembers(conversationId: conversationId)?.convertTo()
+    }
+
+    /// Leaves a sub-conversation by destroying it locally. The Delivery Service still has to be told so that the
+    /// remaining members remove this client
+    ///
+    /// - parameter conversationId: conversation identifier of the sub-conversation
+    public func leaveSubConversation(conversationId: ConversationId) async throws {
+        try await self.coreCrypto.leaveSubConversation(conversationId: conversationId)
+    }
+
     /// Self updates the KeyPackage and automatically commits. Pending proposals will be commited.
     ///
     /// The returned ``CommitBundle`` is a TLS struct that needs to be fanned out to Delivery Service in order to validate the commit.
@@ -1343,6 +1478,11 @@ public class CoreCryptoWrapper {
     public static func version() -> String {
         return CoreCryptoSwift.version()
     }
+
+    /// - returns: How this copy of CoreCrypto has been built, to be attached to bug reports
+    public static func buildMetadata() -> BuildMetadata {
+        return CoreCryptoSwift.buildMetadata().convertTo()
+    }
 }
 
 /// Instance for enrolling a certificate with the ACME server
@@ -0,0 +1,770 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Plain C ABI for consumers which cannot use the UniFFI bindings, for example C or C++ services. It is built with the
+//! `capi` feature and its header is generated by cbindgen with `cargo make capi-header`.
+//!
+//! Conventions:
+//! * every function returns a [CoreCryptoStatus]. On [CoreCryptoStatus::Error], details are given by
+//!   [core_crypto_last_error_code] and [core_crypto_last_error_message] on the same thread
+//! * results are written to the `out` pointers only on success
+//! * returned buffers, lists and strings are owned by the caller who has to release them with the matching `_free`
+//!   function. Input buffers are only borrowed for the duration of the call
+//! * a handle is obtained from [core_crypto_init] and released with [core_crypto_close]. It can be shared across
+//!   threads, calls on the same handle are serialized
+//! * calls block the calling thread until CoreCrypto is done
+
+#![cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::AssertUnwindSafe,
+};
+
+use futures_executor::block_on;
+use tls_codec::{Deserialize, Serialize};
+
+use core_crypto::{
+    prelude::{
+        CiphersuiteName, ClientId, CryptoError, KeyPackageIn, MlsCentral, MlsCentralConfiguration, MlsCiphersuite,
+        MlsConversationConfiguration, MlsCredentialType, MlsCustomConfiguration,
+    },
+    CoreCryptoErrorCode, MlsError,
+};
+
+/// Outcome of a call to the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreCryptoStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// CoreCrypto returned an error, see [core_crypto_last_error_code]
+    Error = 1,
+    /// A required pointer was null or a string was not valid UTF-8
+    InvalidArgument = 2,
+    /// CoreCrypto panicked. The handle should not be used anymore, except to close it
+    Panic = 3,
+}
+
+/// Opaque CoreCrypto instance
+pub struct CoreCryptoHandle(async_lock::Mutex<core_crypto::CoreCrypto>);
+
+/// Byte buffer allocated by CoreCrypto, to be released with [core_crypto_buffer_free]. `data` is null when empty
+#[repr(C)]
+#[derive(Debug)]
+pub struct CoreCryptoBuffer {
+    /// Start of the buffer
+    pub data: *mut u8,
+    /// Length of the buffer in bytes
+    pub len: usize,
+}
+
+impl CoreCryptoBuffer {
+    const EMPTY: Self = Self {
+        data: std::ptr::null_mut(),
+        len: 0,
+    };
+
+    /// # Safety
+    /// The buffer must have been allocated by CoreCrypto and not been released yet
+    unsafe fn release(self) {
+        if !self.data.is_null() {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.data, self.len)));
+        }
+    }
+}
+
+impl From<Vec<u8>> for CoreCryptoBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        if bytes.is_empty() {
+            return Self::EMPTY;
+        }
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+impl From<Option<Vec<u8>>> for CoreCryptoBuffer {
+    fn from(bytes: Option<Vec<u8>>) -> Self {
+        bytes.map(Self::from).unwrap_or(Self::EMPTY)
+    }
+}
+
+/// List of byte buffers allocated by CoreCrypto, to be released with [core_crypto_buffer_list_free]
+#[repr(C)]
+#[derive(Debug)]
+pub struct CoreCryptoBufferList {
+    /// Start of the array of buffers
+    pub data: *mut CoreCryptoBuffer,
+    /// Number of buffers
+    pub len: usize,
+}
+
+impl From<Vec<Vec<u8>>> for CoreCryptoBufferList {
+    fn from(buffers: Vec<Vec<u8>>) -> Self {
+        let buffers = buffers
+            .into_iter()
+            .map(CoreCryptoBuffer::from)
+            .collect::<Vec<_>>()
+            .into_boxed_slice();
+        let len = buffers.len();
+        let data = Box::into_raw(buffers) as *mut CoreCryptoBuffer;
+        Self { data, len }
+    }
+}
+
+/// Messages to send after adding clients to a conversation, to be released with [core_crypto_member_added_free]
+#[repr(C)]
+#[derive(Debug)]
+pub struct CoreCryptoMemberAdded {
+    /// Welcome message for the new clients
+    pub welcome: CoreCryptoBuffer,
+    /// Commit message for the existing members
+    pub commit: CoreCryptoBuffer,
+    /// Plaintext GroupInfo of the new epoch
+    pub group_info: CoreCryptoBuffer,
+}
+
+/// Outcome of decrypting a MLS message, to be released with [core_crypto_decrypted_message_free]
+#[repr(C)]
+#[derive(Debug)]
+pub struct CoreCryptoDecryptedMessage {
+    /// Decrypted application message, empty for handshake messages
+    pub message: CoreCryptoBuffer,
+    /// Id of the client who sent the message, when known
+    pub sender_client_id: CoreCryptoBuffer,
+    /// False when the message removed the local client from the conversation
+    pub is_active: bool,
+    /// True when the message was a commit moving the conversation to a new epoch
+    pub has_epoch_changed: bool,
+}
+
+struct LastError {
+    code: CoreCryptoErrorCode,
+    proteus_code: u32,
+    message: CString,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: &CryptoError) {
+    let message = CString::new(error.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = Some(LastError {
+            code: error.error_code(),
+            proteus_code: error.proteus_error_code(),
+            message,
+        })
+    });
+}
+
+/// Failure of a call before or while reaching CoreCrypto
+enum CallError {
+    InvalidArgument,
+    Crypto(CryptoError),
+}
+
+impl From<CryptoError> for CallError {
+    fn from(e: CryptoError) -> Self {
+        Self::Crypto(e)
+    }
+}
+
+impl From<MlsError> for CallError {
+    fn from(e: MlsError) -> Self {
+        Self::Crypto(e.into())
+    }
+}
+
+type CallResult<T> = Result<T, CallError>;
+
+/// Runs the body, catching panics and recording the error on the calling thread
+fn ffi_call(body: impl FnOnce() -> CallResult<()>) -> CoreCryptoStatus {
+    LAST_ERROR.with(|last| last.borrow_mut().take());
+    match std::panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => CoreCryptoStatus::Ok,
+        Ok(Err(CallError::InvalidArgument)) => CoreCryptoStatus::InvalidArgument,
+        Ok(Err(CallError::Crypto(e))) => {
+            set_last_error(&e);
+            CoreCryptoStatus::Error
+        }
+        Err(_) => CoreCryptoStatus::Panic,
+    }
+}
+
+/// Locks a handle, blocking until the calls already running on it are done
+///
+/// # Safety
+/// `handle` must be null or come from [core_crypto_init] and not have been closed yet
+unsafe fn central<'a>(
+    handle: *const CoreCryptoHandle,
+) -> CallResult<async_lock::MutexGuard<'a, core_crypto::CoreCrypto>> {
+    let handle = handle.as_ref().ok_or(CallError::InvalidArgument)?;
+    Ok(handle.0.lock_blocking())
+}
+
+/// # Safety
+/// `data` must be null or point to `len` readable bytes
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> CallResult<&'a [u8]> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(CallError::InvalidArgument),
+        (false, _) => Ok(std::slice::from_raw_parts(data, len)),
+    }
+}
+
+/// # Safety
+/// `s` must be null or point to a nul-terminated string
+unsafe fn string<'a>(s: *const c_char) -> CallResult<&'a str> {
+    if s.is_null() {
+        return Err(CallError::InvalidArgument);
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| CallError::InvalidArgument)
+}
+
+/// # Safety
+/// `out` must be null or valid for writes
+unsafe fn write<T>(out: *mut T, value: T) -> CallResult<()> {
+    if out.is_null() {
+        return Err(CallError::InvalidArgument);
+    }
+    out.write(value);
+    Ok(())
+}
+
+fn parse_ciphersuite(ciphersuite: u16) -> CallResult<MlsCiphersuite> {
+    CiphersuiteName::try_from(ciphersuite)
+        .map(Into::into)
+        .map_err(|_| CallError::InvalidArgument)
+}
+
+/// Error code of the last call which returned [CoreCryptoStatus::Error] on the calling thread, 0 if there was none.
+/// See `CoreCryptoErrorCode` for the possible values
+#[no_mangle]
+pub extern "C" fn core_crypto_last_error_code() -> u16 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map(|e| e.code as u16).unwrap_or_default())
+}
+
+/// Detailed Proteus error code of the last call which returned [CoreCryptoStatus::Error] on the calling thread, 0 if
+/// it was not a Proteus error
+#[no_mangle]
+pub extern "C" fn core_crypto_last_error_proteus_code() -> u32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map(|e| e.proteus_code).unwrap_or_default())
+}
+
+/// Description of the last error on the calling thread, null if there was none. The string is owned by CoreCrypto and
+/// only valid until the next call on the same thread
+#[no_mangle]
+pub extern "C" fn core_crypto_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|e| e.message.as_ptr())
+            .unwrap_or(std::ptr::null())
+    })
+}
+
+/// Releases a buffer returned by CoreCrypto
+///
+/// # Safety
+/// The buffer must come from CoreCrypto and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_buffer_free(buffer: CoreCryptoBuffer) {
+    buffer.release();
+}
+
+/// Releases a list of buffers returned by CoreCrypto, along with all of its buffers
+///
+/// # Safety
+/// The list must come from CoreCrypto and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_buffer_list_free(list: CoreCryptoBufferList) {
+    if list.data.is_null() {
+        return;
+    }
+    let buffers = Box::from_raw(std::ptr::slice_from_raw_parts_mut(list.data, list.len));
+    for buffer in buffers.into_vec() {
+        buffer.release();
+    }
+}
+
+/// Releases a string returned by CoreCrypto
+///
+/// # Safety
+/// The string must come from CoreCrypto and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Releases the messages returned by [core_crypto_add_clients_to_conversation]
+///
+/// # Safety
+/// The messages must come from CoreCrypto and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_member_added_free(messages: CoreCryptoMemberAdded) {
+    messages.welcome.release();
+    messages.commit.release();
+    messages.group_info.release();
+}
+
+/// Releases a message returned by [core_crypto_decrypt_message]
+///
+/// # Safety
+/// The message must come from CoreCrypto and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_decrypted_message_free(message: CoreCryptoDecryptedMessage) {
+    message.message.release();
+    message.sender_client_id.release();
+}
+
+/// Opens or creates the keystore at `path` and initializes a MLS client with a Basic credential
+///
+/// # Arguments
+/// * `path` - path of the keystore, a nul-terminated string
+/// * `key` - key of the keystore, a nul-terminated string
+/// * `client_id` - id of the client, `client_id_len` bytes
+/// * `ciphersuite` - ciphersuite of the client, see the `CiphersuiteName` of MLS
+/// * `out` - receives the handle, to be released with [core_crypto_close]
+///
+/// # Safety
+/// The pointers must be valid for the given lengths
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_init(
+    path: *const c_char,
+    key: *const c_char,
+    client_id: *const u8,
+    client_id_len: usize,
+    ciphersuite: u16,
+    out: *mut *mut CoreCryptoHandle,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let configuration = MlsCentralConfiguration::try_new(
+            string(path)?.to_string(),
+            string(key)?.to_string(),
+            Some(ClientId::from(bytes(client_id, client_id_len)?)),
+            vec![parse_ciphersuite(ciphersuite)?],
+            None,
+            None,
+        )?;
+        let central = block_on(MlsCentral::try_new(configuration))?;
+        let handle = CoreCryptoHandle(core_crypto::CoreCrypto::from(central).into());
+        write(out, Box::into_raw(Box::new(handle)))
+    })
+}
+
+/// Persists everything and releases the handle, which must not be used afterwards
+///
+/// # Safety
+/// `handle` must come from [core_crypto_init] and no call may be running on it
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_close(handle: *mut CoreCryptoHandle) -> CoreCryptoStatus {
+    ffi_call(|| {
+        if handle.is_null() {
+            return Err(CallError::InvalidArgument);
+        }
+        let handle = Box::from_raw(handle);
+        Ok(block_on(handle.0.into_inner().take().close())?)
+    })
+}
+
+/// Public signature key of the client for the given ciphersuite
+///
+/// # Safety
+/// `handle` must be a live handle and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_client_public_key(
+    handle: *const CoreCryptoHandle,
+    ciphersuite: u16,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let cs = parse_ciphersuite(ciphersuite)?;
+        let key = central(handle)?.client_public_key(cs, MlsCredentialType::Basic)?;
+        write(out, key.into())
+    })
+}
+
+/// Returns `amount` TLS-serialized KeyPackages of the client, creating them if needed
+///
+/// # Safety
+/// `handle` must be a live handle and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_client_keypackages(
+    handle: *const CoreCryptoHandle,
+    ciphersuite: u16,
+    amount: u32,
+    out: *mut CoreCryptoBufferList,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let cs = parse_ciphersuite(ciphersuite)?;
+        let kps =
+            block_on(central(handle)?.get_or_create_client_keypackages(cs, MlsCredentialType::Basic, amount as usize))?;
+        let kps = kps
+            .into_iter()
+            .map(|kp| kp.tls_serialize_detached().map_err(MlsError::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        write(out, kps.into())
+    })
+}
+
+/// Creates a conversation with the local client as its only member
+///
+/// # Safety
+/// `handle` must be a live handle and the pointers valid for the given lengths
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_create_conversation(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+    ciphersuite: u16,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        let config = MlsConversationConfiguration {
+            ciphersuite: parse_ciphersuite(ciphersuite)?,
+            ..Default::default()
+        };
+        Ok(block_on(central(handle)?.new_conversation(
+            &id,
+            MlsCredentialType::Basic,
+            config,
+        ))?)
+    })
+}
+
+/// Whether the conversation exists
+///
+/// # Safety
+/// `handle` must be a live handle, the pointers valid for the given lengths and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_conversation_exists(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+    out: *mut bool,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        let exists = block_on(central(handle)?.conversation_exists(&id));
+        write(out, exists)
+    })
+}
+
+/// Adds clients to a conversation given their TLS-serialized KeyPackages. The returned commit has to be accepted by
+/// the Delivery Service before calling [core_crypto_commit_accepted]
+///
+/// # Arguments
+/// * `key_packages` - array of `key_packages_count` KeyPackages
+/// * `key_packages_len` - array of `key_packages_count` lengths of the KeyPackages
+///
+/// # Safety
+/// `handle` must be a live handle, the pointers valid for the given lengths and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_add_clients_to_conversation(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+    key_packages: *const *const u8,
+    key_packages_len: *const usize,
+    key_packages_count: usize,
+    out: *mut CoreCryptoMemberAdded,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        if key_packages_count > 0 && (key_packages.is_null() || key_packages_len.is_null()) {
+            return Err(CallError::InvalidArgument);
+        }
+        let key_packages = (0..key_packages_count)
+            .map(|i| {
+                let mut kp = bytes(*key_packages.add(i), *key_packages_len.add(i))?;
+                KeyPackageIn::tls_deserialize(&mut kp).map_err(|e| CallError::from(CryptoError::MlsError(e.into())))
+            })
+            .collect::<CallResult<Vec<_>>>()?;
+        let messages = block_on(central(handle)?.add_members_to_conversation(&id, key_packages))?;
+        let (welcome, commit, group_info, _) = messages.to_bytes()?;
+        write(
+            out,
+            CoreCryptoMemberAdded {
+                welcome: welcome.into(),
+                commit: commit.into(),
+                group_info: group_info.payload.bytes().into(),
+            },
+        )
+    })
+}
+
+/// Merges the pending commit of a conversation once the Delivery Service accepted it
+///
+/// # Safety
+/// `handle` must be a live handle and the pointers valid for the given lengths
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_commit_accepted(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        block_on(central(handle)?.commit_accepted(&id))?;
+        Ok(())
+    })
+}
+
+/// Joins a conversation with a TLS-serialized Welcome message and returns the id of the conversation
+///
+/// # Safety
+/// `handle` must be a live handle, the pointers valid for the given lengths and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_process_welcome_message(
+    handle: *const CoreCryptoHandle,
+    welcome: *const u8,
+    welcome_len: usize,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let welcome = bytes(welcome, welcome_len)?.to_vec();
+        let bundle =
+            block_on(central(handle)?.process_raw_welcome_message(welcome, MlsCustomConfiguration::default()))?;
+        write(out, bundle.id.into())
+    })
+}
+
+/// Encrypts an application message for a conversation
+///
+/// # Safety
+/// `handle` must be a live handle, the pointers valid for the given lengths and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_encrypt_message(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+    message: *const u8,
+    message_len: usize,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        let message = bytes(message, message_len)?;
+        let encrypted = block_on(central(handle)?.encrypt_message(&id, message))?;
+        write(out, encrypted.into())
+    })
+}
+
+/// Decrypts a MLS message of a conversation. Messages buffered by CoreCrypto and decrypted as a side effect are not
+/// returned
+///
+/// # Safety
+/// `handle` must be a live handle, the pointers valid for the given lengths and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_decrypt_message(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+    message: *const u8,
+    message_len: usize,
+    out: *mut CoreCryptoDecryptedMessage,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        let message = bytes(message, message_len)?;
+        let decrypted = block_on(central(handle)?.decrypt_message(&id, message))?;
+        write(
+            out,
+            CoreCryptoDecryptedMessage {
+                message: decrypted.app_msg.into(),
+                sender_client_id: decrypted.sender_client_id.map(|id| id.to_vec()).into(),
+                is_active: decrypted.is_active,
+                has_epoch_changed: decrypted.has_epoch_changed,
+            },
+        )
+    })
+}
+
+/// Destroys a conversation along with everything related to it
+///
+/// # Safety
+/// `handle` must be a live handle and the pointers valid for the given lengths
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_wipe_conversation(
+    handle: *const CoreCryptoHandle,
+    conversation_id: *const u8,
+    conversation_id_len: usize,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        let id = bytes(conversation_id, conversation_id_len)?.to_vec();
+        block_on(central(handle)?.wipe_conversation(&id))?;
+        Ok(())
+    })
+}
+
+/// Initializes the Proteus client, creating its identity if needed
+///
+/// # Safety
+/// `handle` must be a live handle
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_init(handle: *const CoreCryptoHandle) -> CoreCryptoStatus {
+    ffi_call(|| proteus_impl!({ Ok(block_on(central(handle)?.proteus_init())?) }))
+}
+
+/// Creates a CBOR-serialized prekey bundle with the given id
+///
+/// # Safety
+/// `handle` must be a live handle and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_new_prekey(
+    handle: *const CoreCryptoHandle,
+    prekey_id: u16,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let prekey = block_on(central(handle)?.proteus_new_prekey(prekey_id))?;
+            write(out, prekey.into())
+        })
+    })
+}
+
+/// Hex-encoded fingerprint of the local identity, to be released with [core_crypto_string_free]
+///
+/// # Safety
+/// `handle` must be a live handle and `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_fingerprint(
+    handle: *const CoreCryptoHandle,
+    out: *mut *mut c_char,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let fingerprint = central(handle)?.proteus_fingerprint()?;
+            let fingerprint = CString::new(fingerprint).map_err(|_| CallError::InvalidArgument)?;
+            write(out, fingerprint.into_raw())
+        })
+    })
+}
+
+/// Creates a session from the CBOR-serialized prekey bundle of the remote client
+///
+/// # Safety
+/// `handle` must be a live handle, `session_id` a nul-terminated string and the pointers valid for the given lengths
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_session_from_prekey(
+    handle: *const CoreCryptoHandle,
+    session_id: *const c_char,
+    prekey: *const u8,
+    prekey_len: usize,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let session_id = string(session_id)?;
+            let prekey = bytes(prekey, prekey_len)?;
+            block_on(central(handle)?.proteus_session_from_prekey(session_id, prekey))?;
+            Ok(())
+        })
+    })
+}
+
+/// Creates a session from the first message of the remote client and returns the decrypted message
+///
+/// # Safety
+/// `handle` must be a live handle, `session_id` a nul-terminated string, the pointers valid for the given lengths and
+/// `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_session_from_message(
+    handle: *const CoreCryptoHandle,
+    session_id: *const c_char,
+    envelope: *const u8,
+    envelope_len: usize,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let session_id = string(session_id)?;
+            let envelope = bytes(envelope, envelope_len)?;
+            let (_, message) = block_on(central(handle)?.proteus_session_from_message(session_id, envelope))?;
+            write(out, message.into())
+        })
+    })
+}
+
+/// Persists a session in the keystore
+///
+/// # Safety
+/// `handle` must be a live handle and `session_id` a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_session_save(
+    handle: *const CoreCryptoHandle,
+    session_id: *const c_char,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let session_id = string(session_id)?;
+            Ok(block_on(central(handle)?.proteus_session_save(session_id))?)
+        })
+    })
+}
+
+/// Encrypts a message for a session
+///
+/// # Safety
+/// `handle` must be a live handle, `session_id` a nul-terminated string, the pointers valid for the given lengths and
+/// `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_encrypt(
+    handle: *const CoreCryptoHandle,
+    session_id: *const c_char,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let session_id = string(session_id)?;
+            let plaintext = bytes(plaintext, plaintext_len)?;
+            let encrypted = block_on(central(handle)?.proteus_encrypt(session_id, plaintext))?;
+            write(out, encrypted.into())
+        })
+    })
+}
+
+/// Decrypts a message of a session
+///
+/// # Safety
+/// `handle` must be a live handle, `session_id` a nul-terminated string, the pointers valid for the given lengths and
+/// `out` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn core_crypto_proteus_decrypt(
+    handle: *const CoreCryptoHandle,
+    session_id: *const c_char,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    out: *mut CoreCryptoBuffer,
+) -> CoreCryptoStatus {
+    ffi_call(|| {
+        proteus_impl!({
+            let session_id = string(session_id)?;
+            let ciphertext = bytes(ciphertext, ciphertext_len)?;
+            let decrypted = block_on(central(handle)?.proteus_decrypt(session_id, ciphertext))?;
+            write(out, decrypted.into())
+        })
+    })
+}
@@ -21,11 +21,12 @@ use tls_codec::{Deserialize, Serialize};
 pub use core_crypto::prelude::ConversationId;
 use core_crypto::{
     prelude::{
-        ClientIdentifier, CryptoError, E2eIdentityError, EntropySeed, KeyPackageIn, KeyPackageRef,
-        MlsBufferedConversationDecryptMessage, MlsCentral, MlsCentralConfiguration, MlsCiphersuite, MlsCommitBundle,
-        MlsConversationConfiguration, MlsConversationCreationMessage, MlsConversationDecryptMessage,
-        MlsConversationInitBundle, MlsCustomConfiguration, MlsGroupInfoBundle, MlsProposalBundle, MlsRotateBundle,
-        VerifiableGroupInfo,
+        ClaimedKeyPackageBundle, ClientIdentifier, CryptoError, E2eIdentityError, EntropySeed, KeyPackageIn,
+        KeyPackageRef, MlsBufferedConversationDecryptMessage, MlsCentral, MlsCentralConfiguration, MlsCiphersuite,
+        MlsCommitBundle, MlsCommitDelta, MlsCommitPreview, MlsConversationConfiguration,
+        MlsConversationCreationMessage, MlsConversationDecryptMessage, MlsConversationInitBundle,
+        MlsConversationSummary, MlsConversationWipeReport, MlsCustomConfiguration, MlsGroupInfoBundle, MlsJoinPolicy,
+        MlsProposal, MlsProposalBundle, MlsProposalInfo, MlsRequiredCapabilities, MlsRotateBundle, RatchetTreeIn,
     },
     MlsError,
 };
@@ -37,23 +38,77 @@ pub(crate) const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[uniffi::export]
 pub fn version() -> String {
-    VERSION.to_string()
+    core_crypto::CoreCrypto::version().to_string()
+}
+
+/// See [core_crypto::prelude::BuildMetadata]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct BuildMetadata {
+    pub version: String,
+    pub git_hash: String,
+    pub target: String,
+    pub profile: String,
+    pub features: Vec<String>,
+}
+
+impl From<core_crypto::prelude::BuildMetadata> for BuildMetadata {
+    fn from(metadata: core_crypto::prelude::BuildMetadata) -> Self {
+        Self {
+            version: metadata.version.to_string(),
+            git_hash: metadata.git_hash.to_string(),
+            target: metadata.target.to_string(),
+            profile: metadata.profile.to_string(),
+            features: metadata.features.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+/// How this copy of CoreCrypto has been built, to be attached to bug reports
+#[uniffi::export]
+pub fn build_metadata() -> BuildMetadata {
+    core_crypto::CoreCrypto::build_metadata().into()
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 pub enum CoreCryptoError {
-    #[error(transparent)]
+    #[error("{error}")]
     CryptoError {
-        #[from]
         error: CryptoError,
+        /// Flattened code to switch on, see [core_crypto::CoreCryptoErrorCode]
+        code: core_crypto::CoreCryptoErrorCode,
+        /// Detailed description including all the nested errors
+        context: String,
     },
-    #[error(transparent)]
+    #[error("{error}")]
     E2eIdentityError {
-        #[from]
         error: E2eIdentityError,
+        /// Flattened code to switch on, see [core_crypto::CoreCryptoErrorCode]
+        code: core_crypto::CoreCryptoErrorCode,
+        /// Detailed description including all the nested errors
+        context: String,
     },
 }
 
+impl From<CryptoError> for CoreCryptoError {
+    fn from(error: CryptoError) -> Self {
+        Self::CryptoError {
+            code: error.error_code(),
+            context: error.context(),
+            error,
+        }
+    }
+}
+
+impl From<E2eIdentityError> for CoreCryptoError {
+    fn from(error: E2eIdentityError) -> Self {
+        Self::E2eIdentityError {
+            code: (&error).into(),
+            context: format!("{error:?}"),
+            error,
+        }
+    }
+}
+
 type CoreCryptoResult<T> = Result<T, CoreCryptoError>;
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
@@ -208,6 +263,110 @@ pub struct ProteusAutoPrekeyBundle {
     pub pkb: Vec<u8>,
 }
 
+#[derive(Debug, Clone, uniffi::Record)]
+/// see [core_crypto::proteus::ProteusMigrationReport]
+pub struct ProteusMigrationReport {
+    pub session_id: String,
+    pub conversation_id: Vec<u8>,
+    pub remote_fingerprint: String,
+    pub conversation_created: bool,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// A Proteus session to migrate along with the MLS conversation standing for it, see [CoreCrypto::migrate_proteus_conversations]
+pub struct ProteusMigration {
+    pub session_id: String,
+    pub conversation_id: Vec<u8>,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// Outcome of migrating a Proteus session, see [core_crypto::CoreCrypto::migrate_proteus_conversations]
+pub struct ProteusMigrationResult {
+    pub session_id: String,
+    /// None when the session could not be migrated
+    pub report: Option<ProteusMigrationReport>,
+    /// Why the session could not be migrated
+    pub error: Option<core_crypto::CoreCryptoErrorCode>,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// see [core_crypto::identity_binding::IdentityBinding]
+pub struct IdentityBinding {
+    pub client_id: ClientId,
+    /// Hex-encoded public key of the Proteus identity, as returned by `proteus_fingerprint`
+    pub proteus_fingerprint: String,
+    pub mls_public_key: Vec<u8>,
+}
+
+#[cfg(feature = "proteus")]
+impl From<core_crypto::identity_binding::IdentityBinding> for IdentityBinding {
+    fn from(binding: core_crypto::identity_binding::IdentityBinding) -> Self {
+        Self {
+            proteus_fingerprint: binding.proteus_fingerprint(),
+            client_id: ClientId(binding.client_id),
+            mls_public_key: binding.mls_public_key,
+        }
+    }
+}
+
+/// See [core_crypto::identity_signature::IdentitySignatureScheme]
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum IdentitySignatureScheme {
+    Mls {
+        ciphersuite: Ciphersuite,
+        credential_type: MlsCredentialType,
+    },
+    Proteus,
+}
+
+impl From<IdentitySignatureScheme> for core_crypto::identity_signature::IdentitySignatureScheme {
+    fn from(scheme: IdentitySignatureScheme) -> Self {
+        match scheme {
+            IdentitySignatureScheme::Mls {
+                ciphersuite,
+                credential_type,
+            } => Self::Mls(ciphersuite.into(), credential_type.into()),
+            IdentitySignatureScheme::Proteus => Self::Proteus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// see [core_crypto::proteus::ProteusSessionDebugInfo]
+pub struct ProteusSessionDebugInfo {
+    pub session_id: String,
+    pub session_tag: String,
+    pub pending_prekey: bool,
+    pub session_states: u64,
+    pub send_chain_counter: u32,
+    pub previous_send_chain_counter: u32,
+    pub recv_chain_counters: Vec<u32>,
+    pub skipped_message_keys: u64,
+}
+
+#[cfg(feature = "proteus")]
+impl From<core_crypto::proteus::ProteusSessionDebugInfo> for ProteusSessionDebugInfo {
+    fn from(info: core_crypto::proteus::ProteusSessionDebugInfo) -> Self {
+        Self {
+            session_id: info.session_id,
+            session_tag: info.session_tag,
+            pending_prekey: info.pending_prekey,
+            session_states: info.session_states as u64,
+            send_chain_counter: info.send_chain_counter,
+            previous_send_chain_counter: info.previous_send_chain_counter,
+            recv_chain_counters: info.recv_chain_counters,
+            skipped_message_keys: info.skipped_message_keys as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// see [core_crypto::proteus::ProteusDiagnostics]
+pub struct ProteusDiagnostics {
+    pub identity_fingerprint: String,
+    pub sessions: Vec<ProteusSessionDebugInfo>,
+}
+
 #[derive(Debug, uniffi::Record)]
 /// see [core_crypto::prelude::MlsConversationCreationMessage]
 pub struct MemberAddedMessages {
@@ -392,6 +551,98 @@ impl TryFrom<MlsProposalBundle> for ProposalBundle {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+#[repr(u8)]
+pub enum PendingProposalType {
+    Add = core_crypto::prelude::MlsProposalType::Add as u8,
+    Update = core_crypto::prelude::MlsProposalType::Update as u8,
+    Remove = core_crypto::prelude::MlsProposalType::Remove as u8,
+    PreSharedKey = core_crypto::prelude::MlsProposalType::PreSharedKey as u8,
+    ReInit = core_crypto::prelude::MlsProposalType::ReInit as u8,
+    ExternalInit = core_crypto::prelude::MlsProposalType::ExternalInit as u8,
+    GroupContextExtensions = core_crypto::prelude::MlsProposalType::GroupContextExtensions as u8,
+    Other = core_crypto::prelude::MlsProposalType::Other as u8,
+}
+
+impl From<core_crypto::prelude::MlsProposalType> for PendingProposalType {
+    fn from(value: core_crypto::prelude::MlsProposalType) -> Self {
+        match value {
+            core_crypto::prelude::MlsProposalType::Add => Self::Add,
+            core_crypto::prelude::MlsProposalType::Update => Self::Update,
+            core_crypto::prelude::MlsProposalType::Remove => Self::Remove,
+            core_crypto::prelude::MlsProposalType::PreSharedKey => Self::PreSharedKey,
+            core_crypto::prelude::MlsProposalType::ReInit => Self::ReInit,
+            core_crypto::prelude::MlsProposalType::ExternalInit => Self::ExternalInit,
+            core_crypto::prelude::MlsProposalType::GroupContextExtensions => Self::GroupContextExtensions,
+            core_crypto::prelude::MlsProposalType::Other => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// See [core_crypto::prelude::MlsProposalInfo]
+pub struct ProposalInfo {
+    pub proposal_type: PendingProposalType,
+    pub proposer: Option<ClientId>,
+    pub affected_clients: Vec<ClientId>,
+    pub proposal_ref: Vec<u8>,
+}
+
+impl From<MlsProposalInfo> for ProposalInfo {
+    fn from(info: MlsProposalInfo) -> Self {
+        Self {
+            proposal_type: info.proposal_type.into(),
+            proposer: info.proposer.map(ClientId),
+            affected_clients: info.affected_clients.into_iter().map(ClientId).collect(),
+            proposal_ref: info.proposal_ref.to_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// See [core_crypto::prelude::MlsConversationWipeReport]
+pub struct ConversationWipeReport {
+    pub pending_proposals: u32,
+    pub encryption_keypairs: u32,
+    pub buffered_messages: u32,
+    pub pending_group: bool,
+}
+
+impl From<MlsConversationWipeReport> for ConversationWipeReport {
+    fn from(report: MlsConversationWipeReport) -> Self {
+        Self {
+            pending_proposals: report.pending_proposals as u32,
+            encryption_keypairs: report.encryption_keypairs as u32,
+            buffered_messages: report.buffered_messages as u32,
+            pending_group: report.pending_group,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// See [core_crypto::prelude::MlsConversationSummary]
+pub struct ConversationSummary {
+    pub id: Vec<u8>,
+    pub ciphersuite: Ciphersuite,
+    pub epoch: u64,
+    pub member_count: u32,
+    pub last_persisted: u64,
+    pub parent_id: Option<Vec<u8>>,
+}
+
+impl From<MlsConversationSummary> for ConversationSummary {
+    fn from(summary: MlsConversationSummary) -> Self {
+        Self {
+            id: summary.id,
+            ciphersuite: core_crypto::prelude::CiphersuiteName::from(summary.ciphersuite).into(),
+            epoch: summary.epoch,
+            member_count: summary.member_count,
+            last_persisted: summary.last_persisted,
+            parent_id: summary.parent_id,
+        }
+    }
+}
+
 #[derive(Debug, uniffi::Record)]
 pub struct ConversationInitBundle {
     pub conversation_id: Vec<u8>,
@@ -415,6 +666,24 @@ impl TryFrom<MlsConversationInitBundle> for ConversationInitBundle {
     }
 }
 
+#[derive(Debug, uniffi::Record)]
+/// A conversation to rejoin along with its current GroupInfo, see [CoreCrypto::rejoin_all_from_group_infos]
+pub struct ConversationGroupInfo {
+    pub conversation_id: Vec<u8>,
+    /// TLS serialized GroupInfo fetched from the Delivery Service
+    pub group_info: Vec<u8>,
+}
+
+#[derive(Debug, uniffi::Record)]
+/// Outcome of rejoining a conversation, see [core_crypto::mls::MlsCentral::rejoin_all_from_group_infos]
+pub struct RejoinResult {
+    pub conversation_id: Vec<u8>,
+    /// The external commit to send, none when the conversation could not be joined
+    pub bundle: Option<ConversationInitBundle>,
+    /// Why the conversation could not be joined
+    pub error: Option<core_crypto::CoreCryptoErrorCode>,
+}
+
 #[derive(Debug, uniffi::Record)]
 /// See [core_crypto::prelude::decrypt::MlsConversationDecryptMessage]
 pub struct DecryptedMessage {
@@ -427,6 +696,7 @@ pub struct DecryptedMessage {
     pub identity: Option<WireIdentity>,
     pub buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
     pub crl_new_distribution_points: Option<Vec<String>>,
+    pub commit_delta: Option<CommitDelta>,
 }
 
 #[derive(Debug, uniffi::Record)]
@@ -440,6 +710,51 @@ pub struct BufferedDecryptedMessage {
     pub has_epoch_changed: bool,
     pub identity: Option<WireIdentity>,
     pub crl_new_distribution_points: Option<Vec<String>>,
+    pub commit_delta: Option<CommitDelta>,
+}
+
+#[derive(Debug, uniffi::Record)]
+/// See [core_crypto::prelude::MlsCommitDelta]
+pub struct CommitDelta {
+    pub added: Vec<ClientId>,
+    pub removed: Vec<ClientId>,
+    pub updated: Vec<ClientId>,
+    pub self_updated: bool,
+}
+
+impl From<MlsCommitDelta> for CommitDelta {
+    fn from(delta: MlsCommitDelta) -> Self {
+        Self {
+            added: delta.added.into_iter().map(ClientId).collect(),
+            removed: delta.removed.into_iter().map(ClientId).collect(),
+            updated: delta.updated.into_iter().map(ClientId).collect(),
+            self_updated: delta.self_updated,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// See [core_crypto::prelude::MlsCommitPreview]
+pub struct CommitPreview {
+    pub members: Vec<ClientId>,
+    pub epoch: u64,
+    pub delta: CommitDelta,
+    pub commit_size: u64,
+    pub welcome_size: Option<u64>,
+    pub group_info_size: u64,
+}
+
+impl From<MlsCommitPreview> for CommitPreview {
+    fn from(preview: MlsCommitPreview) -> Self {
+        Self {
+            members: preview.members.into_iter().map(ClientId).collect(),
+            epoch: preview.epoch,
+            delta: preview.delta.into(),
+            commit_size: preview.commit_size as u64,
+            welcome_size: preview.welcome_size.map(|s| s as u64),
+            group_info_size: preview.group_info_size as u64,
+        }
+    }
 }
 
 impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
@@ -472,6 +787,7 @@ impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
             identity: from.identity.map(Into::into),
             buffered_messages,
             crl_new_distribution_points: from.crl_new_distribution_points,
+            commit_delta: from.commit_delta.map(Into::into),
         })
     }
 }
@@ -495,6 +811,7 @@ impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.map(Into::into),
             crl_new_distribution_points: from.crl_new_distribution_points,
+            commit_delta: from.commit_delta.map(Into::into),
         })
     }
 }
@@ -558,14 +875,29 @@ pub struct ConversationConfiguration {
     pub ciphersuite: Ciphersuite,
     pub external_senders: Vec<Vec<u8>>,
     pub custom: CustomConfiguration,
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::extension_types]
+    pub required_extension_types: Option<Vec<u16>>,
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::proposal_types]
+    pub required_proposal_types: Option<Vec<u16>>,
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::credential_types]
+    pub required_credential_types: Option<Vec<MlsCredentialType>>,
 }
 
 impl TryInto<MlsConversationConfiguration> for ConversationConfiguration {
     type Error = CoreCryptoError;
     fn try_into(self) -> CoreCryptoResult<MlsConversationConfiguration> {
+        let required_credential_types = match self.required_credential_types {
+            Some(cts) => cts.into_iter().map(Into::into).collect(),
+            None => MlsRequiredCapabilities::default().credential_types,
+        };
         let mut cfg = MlsConversationConfiguration {
             custom: self.custom.into(),
             ciphersuite: self.ciphersuite.into(),
+            required_capabilities: MlsRequiredCapabilities::try_from_raw(
+                &self.required_extension_types.unwrap_or_default(),
+                &self.required_proposal_types.unwrap_or_default(),
+                required_credential_types,
+            )?,
             ..Default::default()
         };
         cfg.set_raw_external_senders(self.external_senders);
@@ -601,23 +933,214 @@ impl From<MlsWirePolicy> for core_crypto::prelude::MlsWirePolicy {
     }
 }
 
-#[derive(Debug, Clone, uniffi::Record)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, uniffi::Enum)]
+#[repr(u8)]
+pub enum MlsEpochFence {
+    /// The message is encrypted for the current epoch, as if there was no pending commit
+    #[default]
+    Disabled = core_crypto::prelude::MlsEpochFence::Disabled as u8,
+    /// Encrypting fails with a PendingCommitInFlight error
+    FailFast = core_crypto::prelude::MlsEpochFence::FailFast as u8,
+    /// The message is queued and encrypted once the pending commit is resolved
+    Queue = core_crypto::prelude::MlsEpochFence::Queue as u8,
+}
+
+impl From<MlsEpochFence> for core_crypto::prelude::MlsEpochFence {
+    fn from(value: MlsEpochFence) -> core_crypto::prelude::MlsEpochFence {
+        match value {
+            MlsEpochFence::Disabled => core_crypto::prelude::MlsEpochFence::Disabled,
+            MlsEpochFence::FailFast => core_crypto::prelude::MlsEpochFence::FailFast,
+            MlsEpochFence::Queue => core_crypto::prelude::MlsEpochFence::Queue,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, uniffi::Record)]
 /// See [core_crypto::prelude::MlsCustomConfiguration]
 pub struct CustomConfiguration {
     pub key_rotation_span: Option<std::time::Duration>,
     pub wire_policy: Option<MlsWirePolicy>,
+    pub out_of_order_tolerance: Option<u32>,
+    pub maximum_forward_distance: Option<u32>,
+    pub max_application_message_epoch_age: Option<u32>,
+    pub epoch_fence: Option<MlsEpochFence>,
+    /// See [core_crypto::prelude::MlsJoinPolicy::allowed_ciphersuites]
+    pub allowed_ciphersuites: Option<Ciphersuites>,
+    /// See [core_crypto::prelude::MlsJoinPolicy::require_x509_credentials]
+    pub require_x509_credentials: Option<bool>,
 }
 
 impl From<CustomConfiguration> for MlsCustomConfiguration {
     fn from(cfg: CustomConfiguration) -> Self {
+        let default = MlsCustomConfiguration::default();
         Self {
             key_rotation_span: cfg.key_rotation_span,
             wire_policy: cfg.wire_policy.unwrap_or_default().into(),
-            ..Default::default()
+            out_of_order_tolerance: cfg.out_of_order_tolerance.unwrap_or(default.out_of_order_tolerance),
+            maximum_forward_distance: cfg.maximum_forward_distance.unwrap_or(default.maximum_forward_distance),
+            max_application_message_epoch_age: cfg.max_application_message_epoch_age,
+            epoch_fence: cfg.epoch_fence.unwrap_or_default().into(),
+            join_policy: MlsJoinPolicy {
+                allowed_ciphersuites: cfg.allowed_ciphersuites.as_ref().map(Into::into).unwrap_or_default(),
+                require_x509_credentials: cfg.require_x509_credentials.unwrap_or_default(),
+            },
+            ..default
         }
     }
 }
 
+#[derive(Debug, uniffi::Object)]
+/// Builds a [ConversationConfiguration] which is checked up front with
+/// [core_crypto::prelude::MlsConversationConfiguration::validate], rather than failing once the conversation is
+/// created or a member is added. Errors name the invalid field as in
+/// [core_crypto::prelude::MlsConversationConfiguration]
+pub struct ConversationConfigurationBuilder(std::sync::Mutex<ConversationConfiguration>);
+
+#[uniffi::export]
+impl ConversationConfigurationBuilder {
+    #[uniffi::constructor]
+    pub fn new(ciphersuite: Ciphersuite) -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self(std::sync::Mutex::new(ConversationConfiguration {
+            ciphersuite,
+            external_senders: vec![],
+            custom: CustomConfiguration::default(),
+            required_extension_types: None,
+            required_proposal_types: None,
+            required_credential_types: None,
+        })))
+    }
+
+    /// See [core_crypto::prelude::MlsConversationConfiguration::set_raw_external_senders]
+    pub fn external_senders(self: std::sync::Arc<Self>, external_senders: Vec<Vec<u8>>) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.external_senders = external_senders)
+    }
+
+    /// See [CustomConfigurationBuilder]
+    pub fn custom(self: std::sync::Arc<Self>, custom: CustomConfiguration) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.custom = custom)
+    }
+
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::extension_types]
+    pub fn required_extension_types(self: std::sync::Arc<Self>, extension_types: Vec<u16>) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.required_extension_types = Some(extension_types))
+    }
+
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::proposal_types]
+    pub fn required_proposal_types(self: std::sync::Arc<Self>, proposal_types: Vec<u16>) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.required_proposal_types = Some(proposal_types))
+    }
+
+    /// See [core_crypto::prelude::MlsRequiredCapabilities::credential_types]
+    pub fn required_credential_types(
+        self: std::sync::Arc<Self>,
+        credential_types: Vec<MlsCredentialType>,
+    ) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.required_credential_types = Some(credential_types))
+    }
+
+    /// Fails with [CryptoError::InvalidConfiguration] naming the first invalid field when the configuration is not
+    /// consistent for a conversation created with a `creator_credential_type` credential
+    pub fn build(&self, creator_credential_type: MlsCredentialType) -> CoreCryptoResult<ConversationConfiguration> {
+        let cfg = self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone();
+        // unknown proposal types are the only reason for the conversion to fail
+        let mls_cfg: MlsConversationConfiguration =
+            cfg.clone().try_into().map_err(|_| CryptoError::InvalidConfiguration {
+                field: "required_capabilities.proposal_types".to_string(),
+                reason: "contains an unknown proposal type".to_string(),
+            })?;
+        mls_cfg.validate(creator_credential_type.into())?;
+        Ok(cfg)
+    }
+}
+
+impl ConversationConfigurationBuilder {
+    fn update(self: std::sync::Arc<Self>, f: impl FnOnce(&mut ConversationConfiguration)) -> std::sync::Arc<Self> {
+        f(&mut self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner));
+        self
+    }
+}
+
+#[derive(Debug, Default, uniffi::Object)]
+/// Builds a [CustomConfiguration] which is checked up front with
+/// [core_crypto::prelude::MlsCustomConfiguration::validate]. Errors name the invalid field as in
+/// [core_crypto::prelude::MlsCustomConfiguration]
+pub struct CustomConfigurationBuilder(std::sync::Mutex<CustomConfiguration>);
+
+#[uniffi::export]
+impl CustomConfigurationBuilder {
+    #[uniffi::constructor]
+    pub fn new() -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self::default())
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::key_rotation_span]
+    pub fn key_rotation_span(
+        self: std::sync::Arc<Self>,
+        key_rotation_span: std::time::Duration,
+    ) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.key_rotation_span = Some(key_rotation_span))
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::wire_policy]
+    pub fn wire_policy(self: std::sync::Arc<Self>, wire_policy: MlsWirePolicy) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.wire_policy = Some(wire_policy))
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::out_of_order_tolerance]
+    pub fn out_of_order_tolerance(self: std::sync::Arc<Self>, out_of_order_tolerance: u32) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.out_of_order_tolerance = Some(out_of_order_tolerance))
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::maximum_forward_distance]
+    pub fn maximum_forward_distance(self: std::sync::Arc<Self>, maximum_forward_distance: u32) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.maximum_forward_distance = Some(maximum_forward_distance))
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::max_application_message_epoch_age]
+    pub fn max_application_message_epoch_age(
+        self: std::sync::Arc<Self>,
+        max_application_message_epoch_age: u32,
+    ) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.max_application_message_epoch_age = Some(max_application_message_epoch_age))
+    }
+
+    /// See [core_crypto::prelude::MlsCustomConfiguration::epoch_fence]
+    pub fn epoch_fence(self: std::sync::Arc<Self>, epoch_fence: MlsEpochFence) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.epoch_fence = Some(epoch_fence))
+    }
+
+    /// See [core_crypto::prelude::MlsJoinPolicy::allowed_ciphersuites]
+    pub fn allowed_ciphersuites(
+        self: std::sync::Arc<Self>,
+        allowed_ciphersuites: Ciphersuites,
+    ) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.allowed_ciphersuites = Some(allowed_ciphersuites))
+    }
+
+    /// See [core_crypto::prelude::MlsJoinPolicy::require_x509_credentials]
+    pub fn require_x509_credentials(
+        self: std::sync::Arc<Self>,
+        require_x509_credentials: bool,
+    ) -> std::sync::Arc<Self> {
+        self.update(|cfg| cfg.require_x509_credentials = Some(require_x509_credentials))
+    }
+
+    /// Fails with [CryptoError::InvalidConfiguration] naming the first invalid field when the configuration is not
+    /// consistent
+    pub fn build(&self) -> CoreCryptoResult<CustomConfiguration> {
+        let cfg = self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone();
+        MlsCustomConfiguration::from(cfg.clone()).validate()?;
+        Ok(cfg)
+    }
+}
+
+impl CustomConfigurationBuilder {
+    fn update(self: std::sync::Arc<Self>, f: impl FnOnce(&mut CustomConfiguration)) -> std::sync::Arc<Self> {
+        f(&mut self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner));
+        self
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, uniffi::Enum)]
 #[repr(u8)]
 pub enum MlsCredentialType {
@@ -681,6 +1204,29 @@ impl core_crypto::prelude::CoreCryptoCallbacks for CoreCryptoCallbacksWrapper {
             parent_conversation_clients.map(|pccs| pccs.into_iter().map(ClientId).collect()),
         )
     }
+    async fn member_change_authorize(
+        &self,
+        conversation_id: Vec<u8>,
+        committer: core_crypto::prelude::ClientId,
+        added_clients: Vec<core_crypto::prelude::ClientId>,
+        removed_clients: Vec<core_crypto::prelude::ClientId>,
+    ) -> bool {
+        self.0.member_change_authorize(
+            conversation_id,
+            ClientId(committer),
+            added_clients.into_iter().map(ClientId).collect(),
+            removed_clients.into_iter().map(ClientId).collect(),
+        )
+    }
+    async fn proteus_identity_changed(
+        &self,
+        session_id: String,
+        previous_fingerprint: String,
+        new_fingerprint: String,
+    ) -> bool {
+        self.0
+            .proteus_identity_changed(session_id, previous_fingerprint, new_fingerprint)
+    }
 }
 
 /// This only exists to create a sync interface to our internal async callback interface
@@ -701,79 +1247,777 @@ pub trait CoreCryptoCallbacks: std::fmt::Debug + Send + Sync {
         existing_clients: Vec<ClientId>,
         parent_conversation_clients: Option<Vec<ClientId>>,
     ) -> bool;
+    fn member_change_authorize(
+        &self,
+        conversation_id: Vec<u8>,
+        committer: ClientId,
+        added_clients: Vec<ClientId>,
+        removed_clients: Vec<ClientId>,
+    ) -> bool;
+    fn proteus_identity_changed(
+        &self,
+        session_id: String,
+        previous_fingerprint: String,
+        new_fingerprint: String,
+    ) -> bool;
 }
 
-#[derive(Debug, uniffi::Object)]
-pub struct CoreCrypto {
-    central: async_lock::Mutex<core_crypto::CoreCrypto>,
-    proteus_last_error_code: std::sync::atomic::AtomicU32,
+/// See [core_crypto::prelude::MlsConversationHealth]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ConversationHealth {
+    Healthy,
+    Suspect { failures: u32 },
+    Broken { failures: u32 },
 }
 
-#[uniffi::export]
-/// See [core_crypto::mls::MlsCentral::try_new]
-pub async fn core_crypto_new(
-    path: String,
-    key: String,
-    client_id: ClientId,
-    ciphersuites: Ciphersuites,
-    nb_key_package: Option<u32>,
-) -> CoreCryptoResult<std::sync::Arc<CoreCrypto>> {
-    let nb_key_package = nb_key_package
-        .map(usize::try_from)
-        .transpose()
-        .map_err(CryptoError::from)?;
-    let configuration = MlsCentralConfiguration::try_new(
-        path,
-        key,
-        Some(client_id.0.clone()),
-        (&ciphersuites).into(),
-        None,
-        nb_key_package,
-    )?;
-
-    let central = MlsCentral::try_new(configuration).await?;
-    let central = core_crypto::CoreCrypto::from(central).into();
-    Ok(CoreCrypto {
-        central,
-        proteus_last_error_code: std::sync::atomic::AtomicU32::new(0),
+impl From<core_crypto::prelude::MlsConversationHealth> for ConversationHealth {
+    fn from(health: core_crypto::prelude::MlsConversationHealth) -> Self {
+        match health {
+            core_crypto::prelude::MlsConversationHealth::Healthy => Self::Healthy,
+            core_crypto::prelude::MlsConversationHealth::Suspect { failures } => Self::Suspect { failures },
+            core_crypto::prelude::MlsConversationHealth::Broken { failures } => Self::Broken { failures },
+        }
     }
-    .into())
 }
 
-#[uniffi::export]
-/// Similar to [core_crypto_new] but defers MLS initialization. It can be initialized later
-/// with [CoreCrypto::mls_init].
-pub async fn core_crypto_deferred_init(
-    path: String,
-    key: String,
-    ciphersuites: Ciphersuites,
-    nb_key_package: Option<u32>,
-) -> CoreCryptoResult<std::sync::Arc<CoreCrypto>> {
-    let nb_key_package = nb_key_package
-        .map(usize::try_from)
-        .transpose()
-        .map_err(CryptoError::from)?;
-    let configuration =
-        MlsCentralConfiguration::try_new(path, key, None, (&ciphersuites).into(), None, nb_key_package)?;
+/// See [core_crypto::prelude::SelfTestCheckKind]
+#[cfg(feature = "self-test")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SelfTestCheckKind {
+    Signature,
+    Hpke,
+    Aead,
+    Keystore,
+}
 
-    let central = MlsCentral::try_new(configuration).await?;
-    let central = core_crypto::CoreCrypto::from(central).into();
-    Ok(CoreCrypto {
-        central,
-        proteus_last_error_code: std::sync::atomic::AtomicU32::new(0),
+#[cfg(feature = "self-test")]
+impl From<core_crypto::prelude::SelfTestCheckKind> for SelfTestCheckKind {
+    fn from(kind: core_crypto::prelude::SelfTestCheckKind) -> Self {
+        match kind {
+            core_crypto::prelude::SelfTestCheckKind::Signature => Self::Signature,
+            core_crypto::prelude::SelfTestCheckKind::Hpke => Self::Hpke,
+            core_crypto::prelude::SelfTestCheckKind::Aead => Self::Aead,
+            core_crypto::prelude::SelfTestCheckKind::Keystore => Self::Keystore,
+        }
     }
-    .into())
 }
 
-#[allow(dead_code, unused_variables)]
-#[uniffi::export]
-impl CoreCrypto {
-    /// See [core_crypto::mls::MlsCentral::mls_init]
-    pub async fn mls_init(
-        &self,
-        client_id: ClientId,
-        ciphersuites: Ciphersuites,
-        nb_key_package: Option<u32>,
+/// See [core_crypto::prelude::SelfTestCheck]
+#[cfg(feature = "self-test")]
+#[derive(Debug, uniffi::Record)]
+pub struct SelfTestCheck {
+    pub kind: SelfTestCheckKind,
+    pub ciphersuite: Option<Ciphersuite>,
+    pub failure: Option<String>,
+}
+
+/// See [core_crypto::prelude::SelfTestReport]
+#[cfg(feature = "self-test")]
+#[derive(Debug, uniffi::Record)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+#[cfg(feature = "self-test")]
+impl From<core_crypto::prelude::SelfTestReport> for SelfTestReport {
+    fn from(report: core_crypto::prelude::SelfTestReport) -> Self {
+        Self {
+            passed: report.passed(),
+            checks: report
+                .checks
+                .into_iter()
+                .map(|check| SelfTestCheck {
+                    kind: check.kind.into(),
+                    ciphersuite: check
+                        .ciphersuite
+                        .map(|cs| core_crypto::prelude::CiphersuiteName::from(cs).into()),
+                    failure: check.failure,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ConversationHealthObserverWrapper(Box<dyn ConversationHealthObserver>);
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl core_crypto::prelude::MlsConversationHealthObserver for ConversationHealthObserverWrapper {
+    async fn health_changed(&self, conversation_id: Vec<u8>, health: core_crypto::prelude::MlsConversationHealth) {
+        self.0.health_changed(conversation_id, health.into())
+    }
+}
+
+/// See [core_crypto::prelude::MlsConversationHealthObserver]
+#[uniffi::export(callback_interface)]
+pub trait ConversationHealthObserver: std::fmt::Debug + Send + Sync {
+    fn health_changed(&self, conversation_id: Vec<u8>, health: ConversationHealth);
+}
+
+/// See [core_crypto::prelude::MlsCredentialFirstSeen]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CredentialFirstSeen {
+    pub client_id: ClientId,
+    pub credential: Vec<u8>,
+    pub first_seen: u64,
+}
+
+impl From<core_crypto::prelude::MlsCredentialFirstSeen> for CredentialFirstSeen {
+    fn from(seen: core_crypto::prelude::MlsCredentialFirstSeen) -> Self {
+        Self {
+            client_id: ClientId(seen.client_id),
+            credential: seen.credential,
+            first_seen: seen.first_seen,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsExternalSender]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ExternalSender {
+    pub signature_key: Vec<u8>,
+    pub identity: Vec<u8>,
+}
+
+impl From<core_crypto::prelude::MlsExternalSender> for ExternalSender {
+    fn from(sender: core_crypto::prelude::MlsExternalSender) -> Self {
+        Self {
+            signature_key: sender.signature_key,
+            identity: sender.identity,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsKeyPackageUploadPayload]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct KeyPackageUploadPayload {
+    pub key_packages: Vec<String>,
+    pub refs: Vec<Vec<u8>>,
+    /// Body of the upload request
+    pub json: String,
+}
+
+impl From<core_crypto::prelude::MlsKeyPackageUploadPayload> for KeyPackageUploadPayload {
+    fn from(payload: core_crypto::prelude::MlsKeyPackageUploadPayload) -> Self {
+        Self {
+            json: payload.to_json(),
+            refs: payload.refs.iter().map(|r| r.as_slice().to_vec()).collect(),
+            key_packages: payload.key_packages,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::KeyPackageValidationReport]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct KeyPackageValidationReport {
+    pub is_valid: bool,
+    pub ciphersuite: Option<Ciphersuite>,
+    pub ciphersuite_supported: bool,
+    pub credential_type: MlsCredentialType,
+    pub lifetime_valid: bool,
+    pub signature_valid: bool,
+    pub failure: Option<String>,
+}
+
+impl From<core_crypto::prelude::KeyPackageValidationReport> for KeyPackageValidationReport {
+    fn from(report: core_crypto::prelude::KeyPackageValidationReport) -> Self {
+        Self {
+            is_valid: report.is_valid(),
+            ciphersuite: report
+                .ciphersuite
+                .map(|cs| core_crypto::prelude::CiphersuiteName::from(cs).into()),
+            ciphersuite_supported: report.ciphersuite_supported,
+            credential_type: report.credential_type.into(),
+            lifetime_valid: report.lifetime_valid,
+            signature_valid: report.signature_valid,
+            failure: report.failure,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsEpochHistoryEntry]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct EpochHistoryEntry {
+    pub epoch: u64,
+    pub authenticator_hash: Vec<u8>,
+    pub timestamp: u64,
+    pub commit_sender: Option<ClientId>,
+}
+
+impl From<core_crypto::prelude::MlsEpochHistoryEntry> for EpochHistoryEntry {
+    fn from(entry: core_crypto::prelude::MlsEpochHistoryEntry) -> Self {
+        Self {
+            epoch: entry.epoch,
+            authenticator_hash: entry.authenticator_hash,
+            timestamp: entry.timestamp,
+            commit_sender: entry.commit_sender.map(ClientId),
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsConversationStats]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ConversationStats {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub commits_sent: u64,
+    pub commits_received: u64,
+    pub last_activity_epoch: Option<u64>,
+    pub last_activity: Option<u64>,
+}
+
+impl From<core_crypto::prelude::MlsConversationStats> for ConversationStats {
+    fn from(stats: core_crypto::prelude::MlsConversationStats) -> Self {
+        Self {
+            messages_sent: stats.messages_sent,
+            messages_received: stats.messages_received,
+            commits_sent: stats.commits_sent,
+            commits_received: stats.commits_received,
+            last_activity_epoch: stats.last_activity_epoch,
+            last_activity: stats.last_activity,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsRatchetDiagnostics]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RatchetDiagnostics {
+    pub epoch: u64,
+    pub own_generation: u32,
+    pub out_of_order_tolerance: u32,
+    pub maximum_forward_distance: u32,
+    pub retained_past_epochs: u32,
+    /// See [core_crypto::prelude::MlsRatchetDiagnostics::forward_distance_remaining]
+    pub forward_distance_remaining: u32,
+}
+
+impl From<core_crypto::prelude::MlsRatchetDiagnostics> for RatchetDiagnostics {
+    fn from(diagnostics: core_crypto::prelude::MlsRatchetDiagnostics) -> Self {
+        Self {
+            epoch: diagnostics.epoch,
+            own_generation: diagnostics.own_generation,
+            out_of_order_tolerance: diagnostics.out_of_order_tolerance,
+            maximum_forward_distance: diagnostics.maximum_forward_distance,
+            retained_past_epochs: diagnostics.retained_past_epochs,
+            forward_distance_remaining: diagnostics.forward_distance_remaining(),
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsCommitOperation]
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum CommitOperation {
+    AddClients { key_packages: Vec<Vec<u8>> },
+    RemoveClients { clients: Vec<ClientId> },
+    UpdateKeyingMaterial,
+    CommitPendingProposals,
+}
+
+impl TryFrom<CommitOperation> for core_crypto::prelude::MlsCommitOperation {
+    type Error = CoreCryptoError;
+
+    fn try_from(operation: CommitOperation) -> CoreCryptoResult<Self> {
+        Ok(match operation {
+            CommitOperation::AddClients { key_packages } => Self::AddMembers(
+                key_packages
+                    .into_iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.as_slice())
+                            .map_err(|e| CoreCryptoError::from(CryptoError::MlsError(e.into())))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?,
+            ),
+            CommitOperation::RemoveClients { clients } => {
+                Self::RemoveMembers(clients.into_iter().map(|c| c.0).collect())
+            }
+            CommitOperation::UpdateKeyingMaterial => Self::UpdateKeyingMaterial,
+            CommitOperation::CommitPendingProposals => Self::CommitPendingProposals,
+        })
+    }
+}
+
+/// See [core_crypto::prelude::MlsIdempotentCommitBundle]
+#[derive(Debug, uniffi::Record)]
+pub struct IdempotentCommitBundle {
+    pub commit: Vec<u8>,
+    pub welcome: Option<Vec<u8>>,
+    pub group_info: GroupInfoBundle,
+    pub crl_new_distribution_points: Option<Vec<String>>,
+    pub replayed: bool,
+}
+
+impl From<core_crypto::prelude::MlsIdempotentCommitBundle> for IdempotentCommitBundle {
+    fn from(bundle: core_crypto::prelude::MlsIdempotentCommitBundle) -> Self {
+        Self {
+            commit: bundle.commit,
+            welcome: bundle.welcome,
+            group_info: bundle.group_info.into(),
+            crl_new_distribution_points: bundle.crl_new_distribution_points,
+            replayed: bundle.replayed,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsConversationCacheStats]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ConversationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub cached: u64,
+}
+
+impl From<core_crypto::prelude::MlsConversationCacheStats> for ConversationCacheStats {
+    fn from(stats: core_crypto::prelude::MlsConversationCacheStats) -> Self {
+        Self {
+            hits: stats.hits,
+            misses: stats.misses,
+            cached: stats.cached,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsGroupRestoreProgress]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct GroupRestoreProgress {
+    pub restored: u32,
+    pub total: u32,
+}
+
+impl From<core_crypto::prelude::MlsGroupRestoreProgress> for GroupRestoreProgress {
+    fn from(progress: core_crypto::prelude::MlsGroupRestoreProgress) -> Self {
+        Self {
+            restored: progress.restored as u32,
+            total: progress.total as u32,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsMaintenanceTask]
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum MaintenanceTask {
+    PruneExpiredKeyPackages,
+    UpdateKeyingMaterial { conversation_id: Vec<u8> },
+    ExpireBufferedMessages,
+    VacuumKeystore,
+    PurgeExpiredEntities,
+}
+
+impl From<core_crypto::prelude::MlsMaintenanceTask> for MaintenanceTask {
+    fn from(task: core_crypto::prelude::MlsMaintenanceTask) -> Self {
+        match task {
+            core_crypto::prelude::MlsMaintenanceTask::PruneExpiredKeyPackages => Self::PruneExpiredKeyPackages,
+            core_crypto::prelude::MlsMaintenanceTask::UpdateKeyingMaterial(conversation_id) => {
+                Self::UpdateKeyingMaterial { conversation_id }
+            }
+            core_crypto::prelude::MlsMaintenanceTask::ExpireBufferedMessages => Self::ExpireBufferedMessages,
+            core_crypto::prelude::MlsMaintenanceTask::VacuumKeystore => Self::VacuumKeystore,
+            core_crypto::prelude::MlsMaintenanceTask::PurgeExpiredEntities => Self::PurgeExpiredEntities,
+        }
+    }
+}
+
+impl From<MaintenanceTask> for core_crypto::prelude::MlsMaintenanceTask {
+    fn from(task: MaintenanceTask) -> Self {
+        match task {
+            MaintenanceTask::PruneExpiredKeyPackages => Self::PruneExpiredKeyPackages,
+            MaintenanceTask::UpdateKeyingMaterial { conversation_id } => Self::UpdateKeyingMaterial(conversation_id),
+            MaintenanceTask::ExpireBufferedMessages => Self::ExpireBufferedMessages,
+            MaintenanceTask::VacuumKeystore => Self::VacuumKeystore,
+            MaintenanceTask::PurgeExpiredEntities => Self::PurgeExpiredEntities,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsMaintenancePriority]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum MaintenancePriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl From<core_crypto::prelude::MlsMaintenancePriority> for MaintenancePriority {
+    fn from(priority: core_crypto::prelude::MlsMaintenancePriority) -> Self {
+        match priority {
+            core_crypto::prelude::MlsMaintenancePriority::Low => Self::Low,
+            core_crypto::prelude::MlsMaintenancePriority::Normal => Self::Normal,
+            core_crypto::prelude::MlsMaintenancePriority::High => Self::High,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsMaintenanceItem]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct MaintenanceItem {
+    pub task: MaintenanceTask,
+    pub priority: MaintenancePriority,
+}
+
+impl From<core_crypto::prelude::MlsMaintenanceItem> for MaintenanceItem {
+    fn from(item: core_crypto::prelude::MlsMaintenanceItem) -> Self {
+        Self {
+            task: item.task.into(),
+            priority: item.priority.into(),
+        }
+    }
+}
+
+/// See [core_crypto::prelude::PublicKeyBundle]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PublicKeyBundle {
+    pub signature_scheme: u16,
+    pub credential_type: MlsCredentialType,
+    pub public_key: Vec<u8>,
+    pub certificate_chain: Vec<Vec<u8>>,
+}
+
+impl From<core_crypto::prelude::PublicKeyBundle> for PublicKeyBundle {
+    fn from(bundle: core_crypto::prelude::PublicKeyBundle) -> Self {
+        Self {
+            signature_scheme: bundle.signature_scheme as u16,
+            credential_type: bundle.credential_type.into(),
+            public_key: bundle.public_key,
+            certificate_chain: bundle.certificate_chain,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsOperationMetrics]
+#[derive(Debug, Clone, Copy, uniffi::Record)]
+pub struct OperationMetrics {
+    pub calls: u64,
+    pub total_micros: u64,
+    pub max_micros: u64,
+}
+
+impl From<core_crypto::prelude::MlsOperationMetrics> for OperationMetrics {
+    fn from(metrics: core_crypto::prelude::MlsOperationMetrics) -> Self {
+        Self {
+            calls: metrics.calls,
+            total_micros: metrics.total_micros,
+            max_micros: metrics.max_micros,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsMetricsSnapshot]
+#[derive(Debug, Clone, Copy, uniffi::Record)]
+pub struct MetricsSnapshot {
+    pub messages_encrypted: u64,
+    pub messages_decrypted: u64,
+    pub epochs_advanced: u64,
+    pub keystore_writes: u64,
+    pub keystore_bytes_written: u64,
+    pub encrypt: OperationMetrics,
+    pub decrypt: OperationMetrics,
+    pub commit: OperationMetrics,
+    pub merge: OperationMetrics,
+}
+
+impl From<core_crypto::prelude::MlsMetricsSnapshot> for MetricsSnapshot {
+    fn from(snapshot: core_crypto::prelude::MlsMetricsSnapshot) -> Self {
+        Self {
+            messages_encrypted: snapshot.messages_encrypted,
+            messages_decrypted: snapshot.messages_decrypted,
+            epochs_advanced: snapshot.epochs_advanced,
+            keystore_writes: snapshot.keystore_writes,
+            keystore_bytes_written: snapshot.keystore_bytes_written,
+            encrypt: snapshot.encrypt.into(),
+            decrypt: snapshot.decrypt.into(),
+            commit: snapshot.commit.into(),
+            merge: snapshot.merge.into(),
+        }
+    }
+}
+
+/// See [core_crypto::prelude::MlsEpochCleanupMetrics]
+#[derive(Debug, Clone, Copy, uniffi::Record)]
+pub struct EpochCleanupMetrics {
+    pub runs: u64,
+    pub deleted_keypairs: u64,
+}
+
+impl From<core_crypto::prelude::MlsEpochCleanupMetrics> for EpochCleanupMetrics {
+    fn from(metrics: core_crypto::prelude::MlsEpochCleanupMetrics) -> Self {
+        Self {
+            runs: metrics.runs,
+            deleted_keypairs: metrics.deleted_keypairs,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CredentialObserverWrapper(Box<dyn CredentialObserver>);
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl core_crypto::prelude::MlsCredentialObserver for CredentialObserverWrapper {
+    async fn on_new_credential(
+        &self,
+        conversation_id: Vec<u8>,
+        client_id: core_crypto::prelude::ClientId,
+        credential: Vec<u8>,
+    ) {
+        self.0
+            .on_new_credential(conversation_id, ClientId(client_id), credential)
+    }
+}
+
+/// See [core_crypto::prelude::MlsCredentialObserver]
+#[uniffi::export(callback_interface)]
+pub trait CredentialObserver: std::fmt::Debug + Send + Sync {
+    fn on_new_credential(&self, conversation_id: Vec<u8>, client_id: ClientId, credential: Vec<u8>);
+}
+
+/// Notified when a conversation could not be persisted, see [core_crypto::mls::MlsCentral::set_persistence_failure_callback]
+#[uniffi::export(callback_interface)]
+pub trait PersistenceFailureCallback: std::fmt::Debug + Send + Sync {
+    fn on_persistence_failure(&self, conversation_id: Vec<u8>, error: String);
+}
+
+/// See [core_crypto::prelude::RedactionLevel]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum RedactionLevel {
+    None,
+    Hashed,
+    Full,
+}
+
+impl From<RedactionLevel> for core_crypto::prelude::RedactionLevel {
+    fn from(level: RedactionLevel) -> Self {
+        match level {
+            RedactionLevel::None => Self::None,
+            RedactionLevel::Hashed => Self::Hashed,
+            RedactionLevel::Full => Self::Full,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::CoreCryptoLogLevel]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum CoreCryptoLogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<core_crypto::prelude::CoreCryptoLogLevel> for CoreCryptoLogLevel {
+    fn from(level: core_crypto::prelude::CoreCryptoLogLevel) -> Self {
+        match level {
+            core_crypto::prelude::CoreCryptoLogLevel::Debug => Self::Debug,
+            core_crypto::prelude::CoreCryptoLogLevel::Info => Self::Info,
+            core_crypto::prelude::CoreCryptoLogLevel::Warn => Self::Warn,
+            core_crypto::prelude::CoreCryptoLogLevel::Error => Self::Error,
+        }
+    }
+}
+
+/// See [core_crypto::prelude::CoreCryptoLogger]
+#[uniffi::export(callback_interface)]
+pub trait CoreCryptoLogger: std::fmt::Debug + Send + Sync {
+    fn log(&self, level: CoreCryptoLogLevel, message: String, context: String);
+}
+
+#[derive(Debug)]
+struct CoreCryptoLoggerWrapper(Box<dyn CoreCryptoLogger>);
+
+impl core_crypto::prelude::CoreCryptoLogger for CoreCryptoLoggerWrapper {
+    fn log(&self, level: core_crypto::prelude::CoreCryptoLogLevel, message: &str, context: &str) {
+        self.0.log(level.into(), message.to_string(), context.to_string())
+    }
+}
+
+/// Registers the logger receiving the issues CoreCrypto recovered from, see [core_crypto::prelude::set_logger]. To
+/// be called before [core_crypto_new] in order to also get what happens while restoring the persisted state
+#[uniffi::export]
+pub fn set_logger(logger: Box<dyn CoreCryptoLogger>) {
+    core_crypto::prelude::set_logger(Some(std::sync::Arc::new(CoreCryptoLoggerWrapper(logger))));
+}
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+/// See [core_crypto::prelude::FileProtectionClass]
+pub enum FileProtectionClass {
+    /// `NSFileProtectionComplete`
+    Complete,
+    /// `NSFileProtectionCompleteUnlessOpen`
+    CompleteUnlessOpen,
+    /// `NSFileProtectionCompleteUntilFirstUserAuthentication`
+    CompleteUntilFirstUserAuthentication,
+    /// `NSFileProtectionNone`
+    None,
+}
+
+impl From<FileProtectionClass> for core_crypto::prelude::FileProtectionClass {
+    fn from(value: FileProtectionClass) -> Self {
+        match value {
+            FileProtectionClass::Complete => Self::Complete,
+            FileProtectionClass::CompleteUnlessOpen => Self::CompleteUnlessOpen,
+            FileProtectionClass::CompleteUntilFirstUserAuthentication => Self::CompleteUntilFirstUserAuthentication,
+            FileProtectionClass::None => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Object)]
+pub struct CoreCrypto {
+    central: std::sync::Arc<async_lock::Mutex<core_crypto::CoreCrypto>>,
+    proteus_last_error_code: std::sync::atomic::AtomicU32,
+}
+
+#[uniffi::export]
+/// See [core_crypto::mls::MlsCentral::try_new], or [core_crypto::mls::MlsCentral::try_new_with_progress] with
+/// `lazy_group_restore`
+pub async fn core_crypto_new(
+    path: String,
+    key: String,
+    client_id: ClientId,
+    ciphersuites: Ciphersuites,
+    nb_key_package: Option<u32>,
+    allow_identity_export: Option<bool>,
+    take_over_store: Option<bool>,
+    lazy_group_restore: Option<bool>,
+    disable_group_cache: Option<bool>,
+    file_protection: Option<FileProtectionClass>,
+    clock_skew_leeway_seconds: Option<u64>,
+) -> CoreCryptoResult<std::sync::Arc<CoreCrypto>> {
+    let nb_key_package = nb_key_package
+        .map(usize::try_from)
+        .transpose()
+        .map_err(CryptoError::from)?;
+    let mut configuration = MlsCentralConfiguration::try_new(
+        path,
+        key,
+        Some(client_id.0.clone()),
+        (&ciphersuites).into(),
+        None,
+        nb_key_package,
+    )?;
+    configuration.set_allow_identity_export(allow_identity_export.unwrap_or_default());
+    configuration.set_take_over_store(take_over_store.unwrap_or_default());
+    configuration.set_disable_group_cache(disable_group_cache.unwrap_or_default());
+    if let Some(file_protection) = file_protection {
+        configuration.set_file_protection(file_protection.into());
+    }
+    if let Some(leeway_seconds) = clock_skew_leeway_seconds {
+        configuration.set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds));
+    }
+
+    let central = if lazy_group_restore.unwrap_or_default() {
+        MlsCentral::try_new_with_progress(configuration).await?
+    } else {
+        MlsCentral::try_new(configuration).await?
+    };
+    let central = std::sync::Arc::new(core_crypto::CoreCrypto::from(central).into());
+    Ok(CoreCrypto {
+        central,
+        proteus_last_error_code: std::sync::atomic::AtomicU32::new(0),
+    }
+    .into())
+}
+
+#[uniffi::export]
+/// Similar to [core_crypto_new] but defers MLS initialization. It can be initialized later
+/// with [CoreCrypto::mls_init].
+pub async fn core_crypto_deferred_init(
+    path: String,
+    key: String,
+    ciphersuites: Ciphersuites,
+    nb_key_package: Option<u32>,
+    allow_identity_export: Option<bool>,
+    take_over_store: Option<bool>,
+    lazy_group_restore: Option<bool>,
+    disable_group_cache: Option<bool>,
+    file_protection: Option<FileProtectionClass>,
+    clock_skew_leeway_seconds: Option<u64>,
+) -> CoreCryptoResult<std::sync::Arc<CoreCrypto>> {
+    let nb_key_package = nb_key_package
+        .map(usize::try_from)
+        .transpose()
+        .map_err(CryptoError::from)?;
+    let mut configuration =
+        MlsCentralConfiguration::try_new(path, key, None, (&ciphersuites).into(), None, nb_key_package)?;
+    configuration.set_allow_identity_export(allow_identity_export.unwrap_or_default());
+    configuration.set_take_over_store(take_over_store.unwrap_or_default());
+    configuration.set_disable_group_cache(disable_group_cache.unwrap_or_default());
+    if let Some(file_protection) = file_protection {
+        configuration.set_file_protection(file_protection.into());
+    }
+    if let Some(leeway_seconds) = clock_skew_leeway_seconds {
+        configuration.set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds));
+    }
+
+    let central = if lazy_group_restore.unwrap_or_default() {
+        MlsCentral::try_new_with_progress(configuration).await?
+    } else {
+        MlsCentral::try_new(configuration).await?
+    };
+    let central = std::sync::Arc::new(core_crypto::CoreCrypto::from(central).into());
+    Ok(CoreCrypto {
+        central,
+        proteus_last_error_code: std::sync::atomic::AtomicU32::new(0),
+    }
+    .into())
+}
+
+#[derive(Debug, Default, uniffi::Object)]
+/// See [core_crypto::prelude::CancellationToken]
+pub struct CancellationToken(core_crypto::prelude::CancellationToken);
+
+#[uniffi::export]
+impl CancellationToken {
+    #[uniffi::constructor]
+    pub fn new() -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self::default())
+    }
+
+    /// See [core_crypto::prelude::CancellationToken::cancel]
+    pub fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    /// See [core_crypto::prelude::CancellationToken::is_cancelled]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl CoreCrypto {
+    /// Restores the MLS groups, then reloads the Proteus sessions
+    async fn restore(&self, cancellation: Option<&core_crypto::prelude::CancellationToken>) -> CoreCryptoResult<()> {
+        let mut central = self.central.lock().await;
+
+        match cancellation {
+            Some(cancellation) => central.restore_from_disk_cancellable(cancellation).await?,
+            None => central.restore_from_disk().await?,
+        }
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "proteus")] {
+                central.proteus_reload_sessions().await.map_err(|e|{
+                    let errcode = e.proteus_error_code();
+                    if errcode > 0 {
+                        self.proteus_last_error_code.store(errcode, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    e
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(dead_code, unused_variables)]
+#[uniffi::export]
+impl CoreCrypto {
+    /// See [core_crypto::mls::MlsCentral::mls_init]
+    pub async fn mls_init(
+        &self,
+        client_id: ClientId,
+        ciphersuites: Ciphersuites,
+        nb_key_package: Option<u32>,
     ) -> CoreCryptoResult<()> {
         let nb_key_package = nb_key_package
             .map(usize::try_from)
@@ -791,6 +2035,26 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::export_identity]
+    pub async fn export_identity(&self, backup_key: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.export_identity(&backup_key).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::import_identity]
+    pub async fn import_identity(
+        &self,
+        backup: Vec<u8>,
+        backup_key: Vec<u8>,
+        ciphersuites: Ciphersuites,
+    ) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .import_identity(&backup, &backup_key, (&ciphersuites).into())
+            .await?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::mls_generate_keypairs]
     pub async fn mls_generate_keypairs(&self, ciphersuites: Ciphersuites) -> CoreCryptoResult<Vec<ClientId>> {
         Ok(self
@@ -823,29 +2087,48 @@ impl CoreCrypto {
 
     /// See [core_crypto::mls::MlsCentral::restore_from_disk]
     pub async fn restore_from_disk(&self) -> CoreCryptoResult<()> {
-        let mut central = self.central.lock().await;
+        self.restore(None).await
+    }
 
-        central.restore_from_disk().await?;
-        cfg_if::cfg_if! {
-            if #[cfg(feature = "proteus")] {
-                central.proteus_reload_sessions().await.map_err(|e|{
-                    let errcode = e.proteus_error_code();
-                    if errcode > 0 {
-                        self.proteus_last_error_code.store(errcode, std::sync::atomic::Ordering::SeqCst);
-                    }
-                    e
-                })?;
-            }
-        }
+    /// See [core_crypto::mls::MlsCentral::restore_from_disk_cancellable]
+    pub async fn restore_from_disk_cancellable(
+        &self,
+        cancellation: std::sync::Arc<CancellationToken>,
+    ) -> CoreCryptoResult<()> {
+        self.restore(Some(&cancellation.0)).await
+    }
 
-        Ok(())
+    /// See [core_crypto::mls::MlsCentral::take_over_store]
+    pub async fn take_over_store(&self) -> CoreCryptoResult<()> {
+        self.central.lock().await.take_over_store().await?;
+        // the proteus sessions have to be reloaded as well
+        self.restore_from_disk().await
+    }
+
+    /// Starts a transaction grouping several operations: their keystore writes are only persisted when
+    /// [CoreCryptoContext::finish] is called and are discarded by [CoreCryptoContext::abort].
+    /// Other calls on this instance are blocked until the context is ended. A context dropped without being
+    /// ended is aborted when the next one starts, along with whatever other calls wrote in the meantime.
+    ///
+    /// See [core_crypto::CoreCrypto::context]
+    pub async fn context(&self) -> CoreCryptoResult<std::sync::Arc<CoreCryptoContext>> {
+        let mut central = self.central.lock_arc().await;
+        // left behind by a context dropped without being ended
+        if central.transaction_in_progress() {
+            CoreCryptoContext::rollback(&mut central).await?;
+        }
+        central.begin_transaction().await?;
+        Ok(CoreCryptoContext {
+            central: Some(central).into(),
+        }
+        .into())
     }
 
     /// See [core_crypto::mls::MlsCentral::close]
     pub async fn unload(self: std::sync::Arc<Self>) -> CoreCryptoResult<()> {
-        if let Some(cc) = std::sync::Arc::into_inner(self) {
-            let central = cc.central.into_inner();
-            central.take().close().await?;
+        // fails as well while a [CoreCryptoContext] is alive since it holds the lock
+        if let Some(central) = std::sync::Arc::into_inner(self).and_then(|cc| std::sync::Arc::into_inner(cc.central)) {
+            central.into_inner().take().close().await?;
             Ok(())
         } else {
             Err(CryptoError::LockPoisonError.into())
@@ -854,9 +2137,9 @@ impl CoreCrypto {
 
     /// See [core_crypto::mls::MlsCentral::wipe]
     pub async fn wipe(self: std::sync::Arc<Self>) -> CoreCryptoResult<()> {
-        if let Some(cc) = std::sync::Arc::into_inner(self) {
-            let central = cc.central.into_inner();
-            central.take().wipe().await?;
+        // fails as well while a [CoreCryptoContext] is alive since it holds the lock
+        if let Some(central) = std::sync::Arc::into_inner(self).and_then(|cc| std::sync::Arc::into_inner(cc.central)) {
+            central.into_inner().take().wipe().await?;
             Ok(())
         } else {
             Err(CryptoError::LockPoisonError.into())
@@ -872,6 +2155,29 @@ impl CoreCrypto {
         Ok(())
     }
 
+    /// See [core_crypto::mls::MlsCentral::set_persistence_failure_callback]
+    pub async fn set_persistence_failure_callback(
+        &self,
+        callback: Box<dyn PersistenceFailureCallback>,
+    ) -> CoreCryptoResult<()> {
+        let callback = Box::new(
+            move |conversation_id: &[u8], error: &core_crypto::prelude::CryptoKeystoreError| {
+                callback.on_persistence_failure(conversation_id.to_vec(), error.to_string())
+            },
+        ) as core_crypto::prelude::PersistenceFailureCallback;
+        self.central
+            .lock()
+            .await
+            .set_persistence_failure_callback(Some(callback))
+            .await;
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::dirty_conversations]
+    pub async fn dirty_conversations(&self) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        Ok(self.central.lock().await.dirty_conversations().await)
+    }
+
     /// See [core_crypto::mls::MlsCentral::client_public_key]
     pub async fn client_public_key(
         &self,
@@ -885,6 +2191,20 @@ impl CoreCrypto {
             .client_public_key(ciphersuite.into(), credential_type.into())?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::client_public_key_bundle]
+    pub async fn client_public_key_bundle(
+        &self,
+        ciphersuite: Ciphersuite,
+        credential_type: MlsCredentialType,
+    ) -> CoreCryptoResult<PublicKeyBundle> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .client_public_key_bundle(ciphersuite.into(), credential_type.into())?
+            .into())
+    }
+
     /// See [core_crypto::mls::MlsCentral::get_or_create_client_keypackages]
     pub async fn client_keypackages(
         &self,
@@ -909,6 +2229,33 @@ impl CoreCrypto {
             .collect::<CoreCryptoResult<Vec<Vec<u8>>>>()
     }
 
+    /// See [core_crypto::mls::MlsCentral::keypackages_upload_payload]
+    pub async fn keypackages_upload_payload(
+        &self,
+        ciphersuite: Ciphersuite,
+        credential_type: MlsCredentialType,
+        count: u32,
+    ) -> CoreCryptoResult<KeyPackageUploadPayload> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .keypackages_upload_payload(ciphersuite.into(), credential_type.into(), count as usize)
+            .await?
+            .into())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::validate_keypackage]
+    pub async fn validate_keypackage(&self, key_package: Vec<u8>) -> CoreCryptoResult<KeyPackageValidationReport> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .validate_keypackage(&key_package)
+            .await?
+            .into())
+    }
+
     /// See [core_crypto::mls::MlsCentral::client_valid_key_packages_count]
     pub async fn client_valid_keypackages_count(
         &self,
@@ -925,6 +2272,14 @@ impl CoreCrypto {
         Ok(count.try_into().unwrap_or(0))
     }
 
+    /// See [core_crypto::mls::MlsCentral::set_clock_skew_leeway]
+    pub async fn set_clock_skew_leeway(&self, leeway_seconds: u64) {
+        self.central
+            .lock()
+            .await
+            .set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds))
+    }
+
     /// See [core_crypto::mls::MlsCentral::delete_keypackages]
     pub async fn delete_keypackages(&self, refs: Vec<Vec<u8>>) -> CoreCryptoResult<()> {
         let refs = refs
@@ -935,6 +2290,21 @@ impl CoreCrypto {
         Ok(self.central.lock().await.delete_keypackages(&refs[..]).await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::prune_keypackages_not_in]. The remaining counts are keyed by ciphersuite
+    pub async fn prune_keypackages_not_in(&self, ciphersuites: Ciphersuites) -> CoreCryptoResult<HashMap<u16, u64>> {
+        let remaining = self
+            .central
+            .lock()
+            .await
+            .prune_keypackages_not_in(&Vec::<MlsCiphersuite>::from(&ciphersuites))
+            .await?;
+
+        Ok(remaining
+            .into_iter()
+            .map(|(cs, count)| (cs.into(), count.try_into().unwrap_or(0)))
+            .collect())
+    }
+
     /// See [core_crypto::mls::MlsCentral::new_conversation]
     pub async fn create_conversation(
         &self,
@@ -950,6 +2320,35 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::new_conversation_with_members]
+    pub async fn create_conversation_with_members(
+        &self,
+        conversation_id: Vec<u8>,
+        creator_credential_type: MlsCredentialType,
+        config: ConversationConfiguration,
+        key_packages: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice())
+                    .map_err(|e| CoreCryptoError::from(CryptoError::MlsError(e.into())))
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+        self.central
+            .lock()
+            .await
+            .new_conversation_with_members(
+                &conversation_id,
+                creator_credential_type.into(),
+                config.try_into()?,
+                key_packages,
+            )
+            .await?
+            .try_into()
+    }
+
     /// See [core_crypto::mls::MlsCentral::conversation_epoch]
     pub async fn conversation_epoch(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<u64> {
         Ok(self.central.lock().await.conversation_epoch(&conversation_id).await?)
@@ -970,6 +2369,21 @@ impl CoreCrypto {
             .into())
     }
 
+    /// See [core_crypto::mls::MlsCentral::process_raw_welcome_message_replacing]
+    pub async fn process_welcome_message_replacing(
+        &self,
+        welcome_message: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+    ) -> CoreCryptoResult<WelcomeBundle> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .process_raw_welcome_message_replacing(welcome_message, custom_configuration.into())
+            .await?
+            .into())
+    }
+
     /// See [core_crypto::mls::MlsCentral::add_members_to_conversation]
     pub async fn add_clients_to_conversation(
         &self,
@@ -979,9 +2393,8 @@ impl CoreCrypto {
         let key_packages = key_packages
             .into_iter()
             .map(|kp| {
-                KeyPackageIn::tls_deserialize(&mut kp.as_slice()).map_err(|e| CoreCryptoError::CryptoError {
-                    error: CryptoError::MlsError(e.into()),
-                })
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice())
+                    .map_err(|e| CoreCryptoError::from(CryptoError::MlsError(e.into())))
             })
             .collect::<CoreCryptoResult<Vec<_>>>()?;
 
@@ -993,6 +2406,25 @@ impl CoreCrypto {
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::add_claimed_members_to_conversation]
+    ///
+    /// `claim_response` is the JSON response of the KeyPackage claim endpoint, see
+    /// [core_crypto::prelude::ClaimedKeyPackageBundle::from_claim_response]
+    pub async fn add_claimed_clients_to_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        claim_response: Vec<u8>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let claimed_key_packages = ClaimedKeyPackageBundle::from_claim_response(&claim_response)?;
+
+        self.central
+            .lock()
+            .await
+            .add_claimed_members_to_conversation(&conversation_id, claimed_key_packages)
+            .await?
+            .try_into()
+    }
+
     /// See [core_crypto::mls::MlsCentral::remove_members_from_conversation]
     pub async fn remove_clients_from_conversation(
         &self,
@@ -1008,14 +2440,170 @@ impl CoreCrypto {
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::preview_commit]
+    ///
+    /// `key_packages` are the clients to add, `clients_to_remove` the ones to remove and `update` whether to update
+    /// the own leaf
+    pub async fn preview_commit(
+        &self,
+        conversation_id: Vec<u8>,
+        key_packages: Vec<Vec<u8>>,
+        clients_to_remove: Vec<ClientId>,
+        update: bool,
+    ) -> CoreCryptoResult<CommitPreview> {
+        let mut intents = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice())
+                    .map(|kp| MlsProposal::Add(kp.into()))
+                    .map_err(|e| CoreCryptoError::from(CryptoError::MlsError(e.into())))
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+        intents.extend(clients_to_remove.into_iter().map(|c| MlsProposal::Remove(c.0)));
+        if update {
+            intents.push(MlsProposal::Update);
+        }
+
+        Ok(self
+            .central
+            .lock()
+            .await
+            .preview_commit(&conversation_id, intents)
+            .await?
+            .into())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_conversation_admins]
+    pub async fn set_conversation_admins(
+        &self,
+        conversation_id: Vec<u8>,
+        admins: Vec<ClientId>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        let admins = admins.into_iter().map(|c| c.0).collect();
+        self.central
+            .lock()
+            .await
+            .set_conversation_admins(&conversation_id, admins)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_admins]
+    pub async fn conversation_admins(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<ClientId>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .conversation_admins(&conversation_id)
+            .await?
+            .into_iter()
+            .map(ClientId)
+            .collect())
+    }
+
     /// See [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
     pub async fn mark_conversation_as_child_of(&self, child_id: Vec<u8>, parent_id: Vec<u8>) -> CoreCryptoResult<()> {
         Ok(self
             .central
             .lock()
             .await
-            .mark_conversation_as_child_of(&child_id, &parent_id)
-            .await?)
+            .mark_conversation_as_child_of(&child_id, &parent_id)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::create_sub_conversation]
+    pub async fn create_sub_conversation(&self, parent_id: Vec<u8>, purpose: String) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .create_sub_conversation(&parent_id, &purpose)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::sub_conversations]
+    pub async fn sub_conversations(&self, parent_id: Vec<u8>) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        Ok(self.central.lock().await.sub_conversations(&parent_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::sync_sub_conversation_members]
+    pub async fn sync_sub_conversation_members(
+        &self,
+        conversation_id: Vec<u8>,
+    ) -> CoreCryptoResult<Option<CommitBundle>> {
+        self.central
+            .lock()
+            .await
+            .sync_sub_conversation_members(&conversation_id)
+            .await
+            .transpose()
+            .map(|r| r?.try_into())
+            .transpose()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::leave_sub_conversation]
+    pub async fn leave_sub_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ConversationWipeReport> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .leave_sub_conversation(&conversation_id)
+            .await?
+            .into())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::list_conversations]
+    pub async fn list_conversations(&self) -> CoreCryptoResult<Vec<ConversationSummary>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .list_conversations()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversations_needing_key_update]
+    pub async fn conversations_needing_key_update(&self) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        Ok(self.central.lock().await.conversations_needing_key_update().await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::update_all_stale]. The commits are keyed by the hex encoded conversation id
+    pub async fn update_all_stale(&self, max_age_seconds: u64) -> CoreCryptoResult<HashMap<String, CommitBundle>> {
+        self.central
+            .lock()
+            .await
+            .update_all_stale(std::time::Duration::from_secs(max_age_seconds))
+            .await?
+            .into_iter()
+            .map(|(id, commit)| Ok((hex::encode(id), commit.try_into()?)))
+            .collect()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::pending_maintenance_tasks]
+    pub async fn pending_maintenance_tasks(&self) -> CoreCryptoResult<Vec<MaintenanceItem>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .pending_maintenance_tasks()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::run_maintenance]
+    pub async fn run_maintenance(&self, task: MaintenanceTask) -> CoreCryptoResult<Option<CommitBundle>> {
+        self.central
+            .lock()
+            .await
+            .run_maintenance(&task.into())
+            .await?
+            .map(TryInto::try_into)
+            .transpose()
     }
 
     /// See [core_crypto::mls::MlsCentral::update_keying_material]
@@ -1041,8 +2629,14 @@ impl CoreCrypto {
     }
 
     /// see [core_crypto::mls::MlsCentral::wipe_conversation]
-    pub async fn wipe_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<()> {
-        Ok(self.central.lock().await.wipe_conversation(&conversation_id).await?)
+    pub async fn wipe_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ConversationWipeReport> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .wipe_conversation(&conversation_id)
+            .await?
+            .into())
     }
 
     /// See [core_crypto::mls::MlsCentral::decrypt_message]
@@ -1073,11 +2667,197 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::take_fenced_messages]
+    pub async fn take_fenced_messages(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        Ok(self.central.lock().await.take_fenced_messages(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::CoreCrypto::self_test]
+    #[cfg(feature = "self-test")]
+    pub async fn self_test(&self) -> SelfTestReport {
+        self.central.lock().await.self_test().await.into()
+    }
+
+    /// See [core_crypto::CoreCrypto::generate_diagnostic_report]
+    pub async fn generate_diagnostic_report(&self, redaction: RedactionLevel) -> CoreCryptoResult<String> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .generate_diagnostic_report(redaction.into())
+            .await?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::conversation_exists]
     pub async fn conversation_exists(&self, conversation_id: Vec<u8>) -> bool {
         self.central.lock().await.conversation_exists(&conversation_id).await
     }
 
+    /// See [core_crypto::mls::MlsCentral::conversation_health]
+    pub async fn conversation_health(&self, conversation_id: Vec<u8>) -> ConversationHealth {
+        self.central.lock().await.conversation_health(&conversation_id).into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_message_limits]
+    pub async fn set_message_limits(
+        &self,
+        max_message_size: u32,
+        max_welcome_size: u32,
+        max_group_info_size: u32,
+    ) -> CoreCryptoResult<()> {
+        self.central
+            .lock()
+            .await
+            .set_message_limits(core_crypto::prelude::MlsMessageLimits {
+                max_message_size: max_message_size as usize,
+                max_welcome_size: max_welcome_size as usize,
+                max_group_info_size: max_group_info_size as usize,
+            });
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_conversation_health_thresholds]
+    pub async fn set_conversation_health_thresholds(&self, suspect: u32, broken: u32) -> CoreCryptoResult<()> {
+        self.central.lock().await.set_conversation_health_thresholds(
+            core_crypto::prelude::MlsConversationHealthThresholds { suspect, broken },
+        );
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_health_observer]
+    pub async fn set_conversation_health_observer(
+        &self,
+        observer: Box<dyn ConversationHealthObserver>,
+    ) -> CoreCryptoResult<()> {
+        self.central
+            .lock()
+            .await
+            .conversation_health_observer(Box::new(ConversationHealthObserverWrapper(observer)));
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::credential_observer]
+    pub async fn set_credential_observer(&self, observer: Box<dyn CredentialObserver>) -> CoreCryptoResult<()> {
+        self.central
+            .lock()
+            .await
+            .credential_observer(Box::new(CredentialObserverWrapper(observer)));
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::seen_credentials]
+    pub async fn seen_credentials(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<CredentialFirstSeen>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .seen_credentials(&conversation_id)
+            .await?
+            .into_iter()
+            .map(CredentialFirstSeen::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::epoch_history]
+    pub async fn epoch_history(
+        &self,
+        conversation_id: Vec<u8>,
+        limit: u32,
+    ) -> CoreCryptoResult<Vec<EpochHistoryEntry>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .epoch_history(&conversation_id, limit as usize)
+            .await?
+            .into_iter()
+            .map(EpochHistoryEntry::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::epoch_cleanup_metrics]
+    pub async fn epoch_cleanup_metrics(&self) -> EpochCleanupMetrics {
+        self.central.lock().await.epoch_cleanup_metrics().into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_conversation_stats_enabled]
+    pub async fn set_conversation_stats_enabled(&self, enabled: bool) -> CoreCryptoResult<()> {
+        self.central.lock().await.set_conversation_stats_enabled(enabled);
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_stats]
+    pub async fn conversation_stats(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ConversationStats> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .conversation_stats(&conversation_id)
+            .await?
+            .into())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::ratchet_diagnostics]
+    pub async fn ratchet_diagnostics(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<RatchetDiagnostics> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .ratchet_diagnostics(&conversation_id)
+            .await?
+            .into())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::commit_with_operation_key]
+    pub async fn commit_with_operation_key(
+        &self,
+        conversation_id: Vec<u8>,
+        operation_key: Vec<u8>,
+        operation: CommitOperation,
+    ) -> CoreCryptoResult<Option<IdempotentCommitBundle>> {
+        let operation = operation.try_into()?;
+        Ok(self
+            .central
+            .lock()
+            .await
+            .commit_with_operation_key(&conversation_id, &operation_key, operation)
+            .await?
+            .map(Into::into))
+    }
+
+    /// See [core_crypto::mls::MlsCentral::prefetch_conversations]
+    pub async fn prefetch_conversations(&self, conversation_ids: Vec<Vec<u8>>) -> CoreCryptoResult<u32> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .prefetch_conversations(&conversation_ids)
+            .await? as u32)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_cache_stats]
+    pub async fn conversation_cache_stats(&self) -> ConversationCacheStats {
+        self.central.lock().await.conversation_cache_stats().into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::restore_next_groups]
+    pub async fn restore_next_groups(&self) -> CoreCryptoResult<Option<GroupRestoreProgress>> {
+        Ok(self.central.lock().await.restore_next_groups().await?.map(Into::into))
+    }
+
+    /// See [core_crypto::mls::MlsCentral::metrics_snapshot]
+    pub async fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.central.lock().await.metrics_snapshot().into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_keystore_slow_operation_threshold]
+    pub async fn set_keystore_slow_operation_threshold(&self, threshold_ms: Option<u32>) {
+        self.central
+            .lock()
+            .await
+            .set_keystore_slow_operation_threshold(threshold_ms.map(|ms| std::time::Duration::from_millis(ms.into())));
+    }
+
     /// See [core_crypto::mls::MlsCentral::new_add_proposal]
     pub async fn new_add_proposal(
         &self,
@@ -1095,6 +2875,35 @@ impl CoreCrypto {
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::new_external_psk_proposal]
+    pub async fn new_external_psk_proposal(
+        &self,
+        conversation_id: Vec<u8>,
+        psk_id: Vec<u8>,
+    ) -> CoreCryptoResult<ProposalBundle> {
+        self.central
+            .lock()
+            .await
+            .new_external_psk_proposal(&conversation_id, psk_id)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::add_external_psk]
+    pub async fn add_external_psk(
+        &self,
+        conversation_id: Vec<u8>,
+        psk_id: Vec<u8>,
+        secret: Vec<u8>,
+    ) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .add_external_psk(&conversation_id, psk_id, secret)
+            .await?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::new_update_proposal]
     pub async fn new_update_proposal(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ProposalBundle> {
         self.central
@@ -1143,24 +2952,106 @@ impl CoreCrypto {
             .map_err(CryptoError::from)?)
     }
 
-    /// See [core_crypto::mls::MlsCentral::join_by_external_commit]
+    /// See [core_crypto::mls::MlsCentral::join_by_external_commit], or
+    /// [core_crypto::mls::MlsCentral::join_by_external_commit_pinned] with `expected_ciphersuite`
     pub async fn join_by_external_commit(
         &self,
         group_info: Vec<u8>,
         custom_configuration: CustomConfiguration,
         credential_type: MlsCredentialType,
+        expected_ciphersuite: Option<Ciphersuite>,
+    ) -> CoreCryptoResult<ConversationInitBundle> {
+        let mut central = self.central.lock().await;
+        let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+        if let Some(expected_ciphersuite) = expected_ciphersuite {
+            central
+                .join_by_external_commit_pinned(
+                    group_info,
+                    custom_configuration.into(),
+                    credential_type.into(),
+                    expected_ciphersuite.into(),
+                )
+                .await?
+                .try_into()
+        } else {
+            central
+                .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+                .await?
+                .try_into()
+        }
+    }
+
+    /// See [core_crypto::mls::MlsCentral::join_from_group_info]
+    pub async fn join_from_group_info(
+        &self,
+        group_info: Vec<u8>,
+        ratchet_tree: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+        credential_type: MlsCredentialType,
     ) -> CoreCryptoResult<ConversationInitBundle> {
-        let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_slice())
+        let mut central = self.central.lock().await;
+        let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+        let ratchet_tree = RatchetTreeIn::tls_deserialize(&mut ratchet_tree.as_slice())
             .map_err(MlsError::from)
             .map_err(CryptoError::from)?;
-        self.central
-            .lock()
-            .await
-            .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+        central
+            .join_from_group_info(
+                group_info,
+                ratchet_tree,
+                custom_configuration.into(),
+                credential_type.into(),
+            )
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::recover_conversation]
+    pub async fn recover_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        group_info: Vec<u8>,
+    ) -> CoreCryptoResult<ConversationInitBundle> {
+        let mut central = self.central.lock().await;
+        let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+        central
+            .recover_conversation(&conversation_id, group_info)
             .await?
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::rejoin_all_from_group_infos]
+    pub async fn rejoin_all_from_group_infos(
+        &self,
+        group_infos: Vec<ConversationGroupInfo>,
+        custom_configuration: CustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CoreCryptoResult<Vec<RejoinResult>> {
+        let mut central = self.central.lock().await;
+        let group_infos = group_infos
+            .into_iter()
+            .map(|gi| {
+                let group_info = central.message_limits().deserialize_group_info(&gi.group_info)?;
+                CoreCryptoResult::Ok((gi.conversation_id, group_info))
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+        central
+            .rejoin_all_from_group_infos(group_infos, custom_configuration.into(), credential_type.into())
+            .await?
+            .into_iter()
+            .map(|(conversation_id, result)| {
+                let (bundle, error) = match result {
+                    Ok(bundle) => (Some(bundle.try_into()?), None),
+                    Err(e) => (None, Some(e.error_code())),
+                };
+                Ok(RejoinResult {
+                    conversation_id,
+                    bundle,
+                    error,
+                })
+            })
+            .collect()
+    }
+
     /// See [core_crypto::mls::MlsCentral::merge_pending_group_from_external_commit]
     pub async fn merge_pending_group_from_external_commit(
         &self,
@@ -1229,6 +3120,17 @@ impl CoreCrypto {
         Ok(None)
     }
 
+    /// See [core_crypto::mls::MlsCentral::pending_proposals]
+    pub async fn pending_proposals(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<ProposalInfo>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .pending_proposals(&conversation_id)
+            .await
+            .map(|infos| infos.into_iter().map(Into::into).collect())?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::clear_pending_proposal]
     pub async fn clear_pending_proposal(
         &self,
@@ -1259,6 +3161,16 @@ impl CoreCrypto {
             .map(|cids| cids.into_iter().map(ClientId).collect())?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::mls_fingerprint_qr]
+    pub async fn mls_fingerprint_qr(&self, conversation_id: Vec<u8>, client_id: ClientId) -> CoreCryptoResult<String> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .mls_fingerprint_qr(&conversation_id, &client_id.0)
+            .await?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::export_secret_key]
     pub async fn export_secret_key(&self, conversation_id: Vec<u8>, key_length: u32) -> CoreCryptoResult<Vec<u8>> {
         Ok(self
@@ -1269,10 +3181,53 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::epoch_authenticator]
+    pub async fn epoch_authenticator(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .epoch_authenticator(&conversation_id)
+            .await?
+            .to_vec())
+    }
+
     /// See [core_crypto::mls::MlsCentral::get_external_sender]
     pub async fn get_external_sender(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
         Ok(self.central.lock().await.get_external_sender(&conversation_id).await?)
     }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_external_senders]
+    pub async fn conversation_external_senders(
+        &self,
+        conversation_id: Vec<u8>,
+    ) -> CoreCryptoResult<Vec<ExternalSender>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .conversation_external_senders(&conversation_id)
+            .await?
+            .into_iter()
+            .map(ExternalSender::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::verify_external_sender_signature]
+    pub async fn verify_external_sender_signature(
+        &self,
+        conversation_id: Vec<u8>,
+        payload: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> CoreCryptoResult<Option<u32>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .verify_external_sender_signature(&conversation_id, &payload, &signature)
+            .await?
+            .map(|index| index as u32))
+    }
 }
 
 #[derive(Debug, Copy, Clone, uniffi::Enum)]
@@ -1365,24 +3320,85 @@ impl CoreCrypto {
         }}
     }
 
+    /// See [core_crypto::CoreCrypto::proteus_reload_sessions]
+    pub async fn proteus_reload_sessions(&self) -> CoreCryptoResult<()> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_reload_sessions()
+                .await?)
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::proteus_set_auto_persist]
+    pub async fn proteus_set_auto_persist(&self, enabled: bool) -> CoreCryptoResult<()> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_set_auto_persist(enabled)?)
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::proteus_stale_sessions]
+    pub async fn proteus_stale_sessions(&self) -> CoreCryptoResult<Vec<String>> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_stale_sessions()
+                .await?)
+        }}
+    }
+
     /// See [core_crypto::proteus::ProteusCentral::session_exists]
     pub async fn proteus_session_exists(&self, session_id: String) -> CoreCryptoResult<bool> {
         proteus_impl! { self.proteus_last_error_code => {
             Ok(self.central
                 .lock()
                 .await
-                .proteus_session_exists(&session_id)
+                .proteus_session_exists(&session_id)
+                .await?)
+        }}
+    }
+
+    /// See [core_crypto::proteus::ProteusCentral::decrypt]
+    pub async fn proteus_decrypt(&self, session_id: String, ciphertext: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_decrypt(&session_id, &ciphertext)
+                .await?)
+        }}
+    }
+
+    /// See [core_crypto::proteus::ProteusCentral::decrypt_batched]
+    pub async fn proteus_decrypt_batched(
+        &self,
+        session_id: String,
+        ciphertexts: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_decrypt_batched(&session_id, ciphertexts.as_slice())
                 .await?)
         }}
     }
 
-    /// See [core_crypto::proteus::ProteusCentral::decrypt]
-    pub async fn proteus_decrypt(&self, session_id: String, ciphertext: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+    /// See [core_crypto::proteus::ProteusCentral::decrypt_batched_multi]
+    pub async fn proteus_decrypt_batched_multi(
+        &self,
+        messages: std::collections::HashMap<String, Vec<Vec<u8>>>,
+    ) -> CoreCryptoResult<std::collections::HashMap<String, Vec<Vec<u8>>>> {
         proteus_impl! { self.proteus_last_error_code => {
             Ok(self.central
                 .lock()
                 .await
-                .proteus_decrypt(&session_id, &ciphertext)
+                .proteus_decrypt_batched_multi(&messages)
                 .await?)
         }}
     }
@@ -1463,6 +3479,17 @@ impl CoreCrypto {
         }}
     }
 
+    /// See [core_crypto::CoreCrypto::proteus_diagnostics]
+    pub async fn proteus_diagnostics(&self) -> CoreCryptoResult<ProteusDiagnostics> {
+        proteus_impl! { self.proteus_last_error_code => {
+            let diagnostics = self.central.lock().await.proteus_diagnostics().await?;
+            CoreCryptoResult::Ok(ProteusDiagnostics {
+                identity_fingerprint: diagnostics.identity_fingerprint,
+                sessions: diagnostics.sessions.into_iter().map(Into::into).collect(),
+            })
+        }}
+    }
+
     /// See [core_crypto::proteus::ProteusCentral::fingerprint_local]
     pub async fn proteus_fingerprint_local(&self, session_id: String) -> CoreCryptoResult<String> {
         proteus_impl! { self.proteus_last_error_code => {
@@ -1485,6 +3512,132 @@ impl CoreCrypto {
         }}
     }
 
+    /// See [core_crypto::CoreCrypto::proteus_fingerprint_qr]
+    pub async fn proteus_fingerprint_qr(&self, session_id: String) -> CoreCryptoResult<String> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .proteus_fingerprint_qr(&session_id)
+                .await?)
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::generate_identity_binding]. Returns the serialized binding
+    pub async fn generate_identity_binding(
+        &self,
+        ciphersuite: Ciphersuite,
+        credential_type: MlsCredentialType,
+    ) -> CoreCryptoResult<Vec<u8>> {
+        proteus_impl! { self.proteus_last_error_code => {
+            Ok(self.central
+                .lock()
+                .await
+                .generate_identity_binding(ciphersuite.into(), credential_type.into())?
+                .to_bytes()?)
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::verify_identity_binding]. Returns the verified binding, whose client id and
+    /// keys still have to be checked against the expected ones
+    pub async fn verify_identity_binding(&self, binding: Vec<u8>) -> CoreCryptoResult<IdentityBinding> {
+        proteus_impl! { self.proteus_last_error_code => {
+            let binding = core_crypto::identity_binding::IdentityBinding::try_from_bytes(&binding)?;
+            self.central.lock().await.verify_identity_binding(&binding)?;
+            CoreCryptoResult::Ok(binding.into())
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::sign_with_identity]. Returns the serialized signature
+    pub async fn sign_with_identity(
+        &self,
+        data: Vec<u8>,
+        scheme: IdentitySignatureScheme,
+    ) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .sign_with_identity(&data, scheme.into())?
+            .to_bytes()?)
+    }
+
+    /// See [core_crypto::CoreCrypto::verify_with_identity]
+    pub async fn verify_with_identity(
+        &self,
+        public_key: Vec<u8>,
+        data: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> CoreCryptoResult<()> {
+        let signature = core_crypto::identity_signature::IdentitySignature::try_from_bytes(&signature)?;
+        Ok(self
+            .central
+            .lock()
+            .await
+            .verify_with_identity(&public_key, &data, &signature)?)
+    }
+
+    /// See [core_crypto::CoreCrypto::migrate_proteus_conversation]
+    pub async fn migrate_proteus_conversation(
+        &self,
+        session_id: String,
+        conversation_id: Vec<u8>,
+        creator_credential_type: MlsCredentialType,
+        config: ConversationConfiguration,
+    ) -> CoreCryptoResult<ProteusMigrationReport> {
+        proteus_impl! { self.proteus_last_error_code => {
+            let report = self.central
+                .lock()
+                .await
+                .migrate_proteus_conversation(&session_id, &conversation_id, creator_credential_type.into(), config.try_into()?)
+                .await?;
+
+            CoreCryptoResult::Ok(ProteusMigrationReport {
+                session_id: report.session_id,
+                conversation_id: report.conversation_id,
+                remote_fingerprint: report.remote_fingerprint,
+                conversation_created: report.conversation_created,
+            })
+        }}
+    }
+
+    /// See [core_crypto::CoreCrypto::migrate_proteus_conversations]
+    pub async fn migrate_proteus_conversations(
+        &self,
+        migrations: Vec<ProteusMigration>,
+        creator_credential_type: MlsCredentialType,
+        config: ConversationConfiguration,
+    ) -> CoreCryptoResult<Vec<ProteusMigrationResult>> {
+        proteus_impl! { self.proteus_last_error_code => {
+            let migrations = migrations.into_iter().map(|m| (m.session_id, m.conversation_id)).collect();
+            let results = self.central
+                .lock()
+                .await
+                .migrate_proteus_conversations(migrations, creator_credential_type.into(), config.try_into()?)
+                .await?
+                .into_iter()
+                .map(|(session_id, result)| {
+                    let (report, error) = match result {
+                        Ok(report) => (Some(ProteusMigrationReport {
+                            session_id: report.session_id,
+                            conversation_id: report.conversation_id,
+                            remote_fingerprint: report.remote_fingerprint,
+                            conversation_created: report.conversation_created,
+                        }), None),
+                        Err(e) => (None, Some(e.error_code())),
+                    };
+                    ProteusMigrationResult {
+                        session_id,
+                        report,
+                        error,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            CoreCryptoResult::Ok(results)
+        }}
+    }
+
     /// See [core_crypto::proteus::ProteusCentral::fingerprint_prekeybundle]
     /// NOTE: uniffi doesn't support associated functions, so we have to have the self here
     pub fn proteus_fingerprint_prekeybundle(&self, prekey: Vec<u8>) -> CoreCryptoResult<String> {
@@ -1502,6 +3655,26 @@ impl CoreCrypto {
         }}
     }
 
+    /// See [core_crypto::CoreCrypto::proteus_cryptobox_migrate_with_options]
+    pub async fn proteus_cryptobox_migrate_cancellable(
+        &self,
+        path: String,
+        cancellation: std::sync::Arc<CancellationToken>,
+    ) -> CoreCryptoResult<()> {
+        proteus_impl! { self.proteus_last_error_code => {
+            let options = core_crypto::proteus::CryptoboxMigrationOptions {
+                cancellation: Some(&cancellation.0),
+                ..Default::default()
+            };
+            self.central
+                .lock()
+                .await
+                .proteus_cryptobox_migrate_with_options(&path, options)
+                .await?;
+            Ok(())
+        }}
+    }
+
     /// Returns the latest proteus error code. If 0, no error has occured
     ///
     /// NOTE: This will clear the last error code.
@@ -1584,6 +3757,25 @@ impl CoreCrypto {
             .map(std::sync::Arc::new)?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::e2ei_new_renewal_enrollment]
+    pub async fn e2ei_new_renewal_enrollment(
+        &self,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: Ciphersuite,
+    ) -> CoreCryptoResult<std::sync::Arc<E2eiEnrollment>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .e2ei_new_renewal_enrollment(team, expiry_sec, ciphersuite.into())
+            .await
+            .map(async_lock::RwLock::new)
+            .map(std::sync::Arc::new)
+            .map(E2eiEnrollment)
+            .map(std::sync::Arc::new)?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::e2ei_register_acme_ca]
     pub async fn e2ei_register_acme_ca(&self, trust_anchor_pem: String) -> CoreCryptoResult<()> {
         Ok(self
@@ -1676,6 +3868,66 @@ impl CoreCrypto {
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::e2ei_rotate_all_cancellable]
+    pub async fn e2ei_rotate_all_cancellable(
+        &self,
+        enrollment: std::sync::Arc<E2eiEnrollment>,
+        certificate_chain: String,
+        new_key_packages_count: u32,
+        cancellation: std::sync::Arc<CancellationToken>,
+    ) -> CoreCryptoResult<RotateBundle> {
+        if std::sync::Arc::strong_count(&enrollment) > 1 {
+            unsafe {
+                // it is required because in order to pass the enrollment to Rust, uniffi lowers it by cloning the Arc
+                // hence here the Arc has a strong_count of 2. We decrement it manually then drop it with `try_unwrap`.
+                // We have to do this since this instance contains private keys that have to be zeroed once dropped.
+                std::sync::Arc::decrement_strong_count(std::sync::Arc::as_ptr(&enrollment));
+            }
+        }
+        let e2ei = std::sync::Arc::into_inner(enrollment).ok_or_else(|| CryptoError::LockPoisonError)?;
+        let e2ei = std::sync::Arc::into_inner(e2ei.0)
+            .ok_or_else(|| CryptoError::LockPoisonError)?
+            .into_inner();
+
+        self.central
+            .lock()
+            .await
+            .e2ei_rotate_all_cancellable(
+                e2ei,
+                certificate_chain,
+                new_key_packages_count as usize,
+                &cancellation.0,
+            )
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::e2ei_renew]
+    pub async fn e2ei_renew(
+        &self,
+        enrollment: std::sync::Arc<E2eiEnrollment>,
+        certificate_chain: String,
+        new_key_packages_count: u32,
+    ) -> CoreCryptoResult<RotateBundle> {
+        if std::sync::Arc::strong_count(&enrollment) > 1 {
+            unsafe {
+                // see [CoreCrypto::e2ei_rotate_all]
+                std::sync::Arc::decrement_strong_count(std::sync::Arc::as_ptr(&enrollment));
+            }
+        }
+        let e2ei = std::sync::Arc::into_inner(enrollment).ok_or_else(|| CryptoError::LockPoisonError)?;
+        let e2ei = std::sync::Arc::into_inner(e2ei.0)
+            .ok_or_else(|| CryptoError::LockPoisonError)?
+            .into_inner();
+
+        self.central
+            .lock()
+            .await
+            .e2ei_renew(e2ei, certificate_chain, new_key_packages_count as usize)
+            .await?
+            .try_into()
+    }
+
     /// See [core_crypto::mls::MlsCentral::e2ei_enrollment_stash]
     pub async fn e2ei_enrollment_stash(&self, enrollment: std::sync::Arc<E2eiEnrollment>) -> CoreCryptoResult<Vec<u8>> {
         let enrollment = std::sync::Arc::into_inner(enrollment).ok_or_else(|| CryptoError::LockPoisonError)?;
@@ -1759,13 +4011,9 @@ impl CoreCrypto {
         group_info: Vec<u8>,
         credential_type: MlsCredentialType,
     ) -> CoreCryptoResult<E2eiConversationState> {
-        let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_slice())
-            .map_err(MlsError::from)
-            .map_err(CryptoError::from)?;
-        Ok(self
-            .central
-            .lock()
-            .await
+        let central = self.central.lock().await;
+        let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+        Ok(central
             .get_credential_in_use(group_info, credential_type.into())
             .await?
             .into())
@@ -2014,3 +4262,145 @@ impl From<AcmeChallenge> for core_crypto::prelude::E2eiAcmeChallenge {
         }
     }
 }
+
+#[derive(Debug, uniffi::Object)]
+/// Operations performed within a transaction, see [CoreCrypto::context]
+pub struct CoreCryptoContext {
+    central: async_lock::Mutex<Option<async_lock::MutexGuardArc<core_crypto::CoreCrypto>>>,
+}
+
+impl CoreCryptoContext {
+    fn central(
+        central: &mut Option<async_lock::MutexGuardArc<core_crypto::CoreCrypto>>,
+    ) -> CoreCryptoResult<&mut core_crypto::CoreCrypto> {
+        // the context cannot be used anymore once ended
+        Ok(central.as_deref_mut().ok_or(CryptoError::ConsumerError)?)
+    }
+
+    async fn rollback(central: &mut core_crypto::CoreCrypto) -> CoreCryptoResult<()> {
+        central.rollback_transaction().await?;
+        #[cfg(feature = "proteus")]
+        central.proteus_reload_sessions().await?;
+        Ok(())
+    }
+}
+
+impl Drop for CoreCryptoContext {
+    fn drop(&mut self) {
+        // the transaction cannot be rolled back without awaiting, it is when the next context starts
+        if let Some(central) = self.central.get_mut().take() {
+            central.abandon_transaction();
+        }
+    }
+}
+
+#[uniffi::export]
+impl CoreCryptoContext {
+    /// See [CoreCrypto::create_conversation]
+    pub async fn create_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        creator_credential_type: MlsCredentialType,
+        config: ConversationConfiguration,
+    ) -> CoreCryptoResult<()> {
+        let mut central = self.central.lock().await;
+        Ok(Self::central(&mut central)?
+            .new_conversation(&conversation_id, creator_credential_type.into(), config.try_into()?)
+            .await?)
+    }
+
+    /// See [CoreCrypto::add_clients_to_conversation]
+    pub async fn add_clients_to_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        key_packages: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice())
+                    .map_err(|e| CoreCryptoError::from(CryptoError::MlsError(e.into())))
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+        let mut central = self.central.lock().await;
+        Self::central(&mut central)?
+            .add_members_to_conversation(&conversation_id, key_packages)
+            .await?
+            .try_into()
+    }
+
+    /// See [CoreCrypto::remove_clients_from_conversation]
+    pub async fn remove_clients_from_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        clients: Vec<ClientId>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        let clients: Vec<core_crypto::prelude::ClientId> = clients.into_iter().map(|c| c.0).collect();
+        let mut central = self.central.lock().await;
+        Self::central(&mut central)?
+            .remove_members_from_conversation(&conversation_id, &clients)
+            .await?
+            .try_into()
+    }
+
+    /// See [CoreCrypto::update_keying_material]
+    pub async fn update_keying_material(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<CommitBundle> {
+        let mut central = self.central.lock().await;
+        Self::central(&mut central)?
+            .update_keying_material(&conversation_id)
+            .await?
+            .try_into()
+    }
+
+    /// See [CoreCrypto::commit_accepted]
+    pub async fn commit_accepted(
+        &self,
+        conversation_id: Vec<u8>,
+    ) -> CoreCryptoResult<Option<Vec<BufferedDecryptedMessage>>> {
+        let mut central = self.central.lock().await;
+        if let Some(decrypted_messages) = Self::central(&mut central)?.commit_accepted(&conversation_id).await? {
+            return Ok(Some(
+                decrypted_messages
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<CoreCryptoResult<Vec<_>>>()?,
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// See [CoreCrypto::commit_pending_proposals]
+    pub async fn commit_pending_proposals(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Option<CommitBundle>> {
+        let mut central = self.central.lock().await;
+        Self::central(&mut central)?
+            .commit_pending_proposals(&conversation_id)
+            .await
+            .transpose()
+            .map(|r| r?.try_into())
+            .transpose()
+    }
+
+    /// Persists every change made within this context and gives the [CoreCrypto] instance back
+    ///
+    /// See [core_crypto::mls::MlsCentral::commit_transaction]
+    pub async fn finish(&self) -> CoreCryptoResult<()> {
+        let mut central = self.central.lock().await.take().ok_or(CryptoError::ConsumerError)?;
+        if let Err(e) = central.commit_transaction().await {
+            // the MLS state is rolled back along with the transaction, the Proteus sessions have to follow
+            #[cfg(feature = "proteus")]
+            central.proteus_reload_sessions().await?;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Discards every change made within this context and gives the [CoreCrypto] instance back
+    ///
+    /// See [core_crypto::mls::MlsCentral::rollback_transaction]
+    pub async fn abort(&self) -> CoreCryptoResult<()> {
+        let mut central = self.central.lock().await.take().ok_or(CryptoError::ConsumerError)?;
+        Self::rollback(&mut central).await
+    }
+}
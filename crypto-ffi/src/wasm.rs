@@ -60,6 +60,8 @@ struct CoreCryptoJsRichError {
     message: String,
     rust_stack_trace: String,
     proteus_error_code: u32,
+    error_code: u16,
+    context: String,
 }
 
 impl<'a> From<&'a CoreCryptoError> for CoreCryptoJsRichError {
@@ -75,6 +77,8 @@ impl<'a> From<&'a CoreCryptoError> for CoreCryptoJsRichError {
             message: e.0.to_string(),
             rust_stack_trace: format!("{:?}", e.0),
             proteus_error_code: e.proteus_error_code(),
+            error_code: e.error_code() as u16,
+            context: e.context(),
         }
     }
 }
@@ -90,6 +94,23 @@ impl CoreCryptoError {
 
         e.proteus_error_code()
     }
+
+    fn error_code(&self) -> core_crypto::CoreCryptoErrorCode {
+        match &self.0 {
+            WasmError::CryptoError(e) => e.error_code(),
+            WasmError::E2eError(e) => e.into(),
+            WasmError::SerializationError(_) | WasmError::EnumError => {
+                core_crypto::CoreCryptoErrorCode::SerializationError
+            }
+        }
+    }
+
+    fn context(&self) -> String {
+        match &self.0 {
+            WasmError::CryptoError(e) => e.context(),
+            e => format!("{e:?}"),
+        }
+    }
 }
 
 impl std::fmt::Display for CoreCryptoError {
@@ -226,7 +247,7 @@ fn lower_ciphersuites(ciphersuites: &[u16]) -> WasmCryptoResult<Vec<MlsCiphersui
 
 #[allow(non_camel_case_types)]
 #[wasm_bindgen]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum::FromRepr)]
 #[repr(u16)]
 /// see [core_crypto::prelude::MlsCredentialType]
 pub enum CredentialType {
@@ -260,7 +281,9 @@ pub type FfiClientId = Box<[u8]>;
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 /// see [core_crypto::prelude::MlsConversationCreationMessage]
 pub struct MemberAddedMessages {
+    #[serde(with = "serde_bytes")]
     welcome: Vec<u8>,
+    #[serde(with = "serde_bytes")]
     commit: Vec<u8>,
     group_info: GroupInfoBundle,
     crl_new_distribution_points: Option<Vec<String>>,
@@ -312,13 +335,16 @@ impl TryFrom<MlsConversationCreationMessage> for MemberAddedMessages {
 pub struct ProteusAutoPrekeyBundle {
     pub id: u16,
     #[wasm_bindgen(getter_with_clone)]
+    #[serde(with = "serde_bytes")]
     pub pkb: Vec<u8>,
 }
 
 #[wasm_bindgen]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CommitBundle {
+    #[serde(with = "serde_bytes")]
     commit: Vec<u8>,
+    #[serde(with = "serde_bytes")]
     welcome: Option<Vec<u8>>,
     group_info: GroupInfoBundle,
 }
@@ -358,11 +384,52 @@ impl TryFrom<MlsCommitBundle> for CommitBundle {
     }
 }
 
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[repr(u8)]
+/// see [core_crypto::prelude::MlsCommitOperation]
+pub enum CommitOperationType {
+    /// Adds the clients whose key packages are given
+    AddClients = 0x01,
+    /// Removes the clients whose ids are given
+    RemoveClients = 0x02,
+    /// Updates the own leaf
+    UpdateKeyingMaterial = 0x03,
+    /// Commits the pending proposals, if any
+    CommitPendingProposals = 0x04,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsIdempotentCommitBundle]
+pub struct IdempotentCommitBundle {
+    #[serde(with = "serde_bytes")]
+    commit: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    welcome: Option<Vec<u8>>,
+    group_info: GroupInfoBundle,
+    crl_new_distribution_points: Option<Vec<String>>,
+    replayed: bool,
+}
+
+impl From<MlsIdempotentCommitBundle> for IdempotentCommitBundle {
+    fn from(bundle: MlsIdempotentCommitBundle) -> Self {
+        Self {
+            commit: bundle.commit,
+            welcome: bundle.welcome,
+            group_info: bundle.group_info.into(),
+            crl_new_distribution_points: bundle.crl_new_distribution_points,
+            replayed: bundle.replayed,
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GroupInfoBundle {
     encryption_type: u8,
     ratchet_tree_type: u8,
+    #[serde(with = "serde_bytes")]
     payload: Vec<u8>,
 }
 
@@ -398,7 +465,9 @@ impl From<MlsGroupInfoBundle> for GroupInfoBundle {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RotateBundle {
     commits: HashMap<String, CommitBundle>,
+    #[serde(with = "byte_arrays")]
     new_key_packages: Vec<Vec<u8>>,
+    #[serde(with = "byte_arrays")]
     key_package_refs_to_remove: Vec<Vec<u8>>,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
@@ -469,8 +538,10 @@ impl TryFrom<MlsRotateBundle> for RotateBundle {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProposalBundle {
     /// TLS-serialized MLS proposal that needs to be fanned out to other (existing) members of the conversation
+    #[serde(with = "serde_bytes")]
     proposal: Vec<u8>,
     /// Unique identifier of a proposal. Use this in {@link CoreCrypto.clearPendingProposal} to roll back (delete) the proposal
+    #[serde(with = "serde_bytes")]
     proposal_ref: Vec<u8>,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
@@ -511,10 +582,251 @@ impl TryFrom<MlsProposalBundle> for ProposalBundle {
     }
 }
 
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[repr(u8)]
+/// see [core_crypto::prelude::MlsProposalType]
+pub enum PendingProposalType {
+    Add = 0x01,
+    Update = 0x02,
+    Remove = 0x03,
+    PreSharedKey = 0x04,
+    ReInit = 0x05,
+    ExternalInit = 0x06,
+    GroupContextExtensions = 0x07,
+    Other = 0xFF,
+}
+
+impl From<core_crypto::prelude::MlsProposalType> for PendingProposalType {
+    fn from(value: core_crypto::prelude::MlsProposalType) -> Self {
+        match value {
+            core_crypto::prelude::MlsProposalType::Add => Self::Add,
+            core_crypto::prelude::MlsProposalType::Update => Self::Update,
+            core_crypto::prelude::MlsProposalType::Remove => Self::Remove,
+            core_crypto::prelude::MlsProposalType::PreSharedKey => Self::PreSharedKey,
+            core_crypto::prelude::MlsProposalType::ReInit => Self::ReInit,
+            core_crypto::prelude::MlsProposalType::ExternalInit => Self::ExternalInit,
+            core_crypto::prelude::MlsProposalType::GroupContextExtensions => Self::GroupContextExtensions,
+            core_crypto::prelude::MlsProposalType::Other => Self::Other,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsProposalInfo]
+pub struct ProposalInfo {
+    /// Kind of the pending proposal
+    proposal_type: PendingProposalType,
+    /// Client who authored the proposal, if it is a member or a joiner
+    #[serde(with = "serde_bytes")]
+    proposer: Option<Vec<u8>>,
+    /// Clients added, removed or updated by this proposal
+    #[serde(with = "byte_arrays")]
+    affected_clients: Vec<Vec<u8>>,
+    /// Unique identifier of a proposal. Use this in {@link CoreCrypto.clearPendingProposal} to roll back (delete) the proposal
+    #[serde(with = "serde_bytes")]
+    proposal_ref: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ProposalInfo {
+    #[wasm_bindgen(getter)]
+    pub fn proposal_type(&self) -> PendingProposalType {
+        self.proposal_type
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn proposer(&self) -> Option<Uint8Array> {
+        self.proposer.as_deref().map(Uint8Array::from)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn affected_clients(&self) -> Vec<Uint8Array> {
+        self.affected_clients.iter().map(|c| c.as_slice().into()).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn proposal_ref(&self) -> Uint8Array {
+        Uint8Array::from(&*self.proposal_ref)
+    }
+}
+
+impl From<MlsProposalInfo> for ProposalInfo {
+    fn from(info: MlsProposalInfo) -> Self {
+        Self {
+            proposal_type: info.proposal_type.into(),
+            proposer: info.proposer.map(ClientId::into),
+            affected_clients: info.affected_clients.into_iter().map(ClientId::into).collect(),
+            proposal_ref: info.proposal_ref.to_bytes(),
+        }
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::proteus::ProteusMigrationReport]
+pub struct ProteusMigrationReport {
+    /// Proteus session the conversation has been migrated from
+    pub session_id: String,
+    /// MLS conversation now standing for the Proteus session
+    #[serde(with = "serde_bytes")]
+    pub conversation_id: Vec<u8>,
+    /// Fingerprint of the remote identity of the Proteus session
+    pub remote_fingerprint: String,
+    /// Whether the MLS conversation has been created. `false` when it already existed
+    pub conversation_created: bool,
+}
+
+/// Outcome of migrating a Proteus session, see [core_crypto::CoreCrypto::migrate_proteus_conversations]
+#[derive(Debug, Clone, serde::Serialize)]
+#[cfg_attr(not(feature = "proteus"), allow(dead_code))]
+pub struct ProteusMigrationResult {
+    session_id: String,
+    /// None when the session could not be migrated
+    report: Option<ProteusMigrationReport>,
+    /// Why the session could not be migrated, see [core_crypto::CoreCryptoErrorCode]
+    error_code: Option<u16>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "proteus"), allow(dead_code))]
+/// see [core_crypto::proteus::ProteusSessionDebugInfo]
+struct ProteusSessionDebugInfo {
+    session_id: String,
+    session_tag: String,
+    pending_prekey: bool,
+    session_states: u32,
+    send_chain_counter: u32,
+    previous_send_chain_counter: u32,
+    recv_chain_counters: Vec<u32>,
+    skipped_message_keys: u32,
+}
+
+#[cfg(feature = "proteus")]
+impl From<core_crypto::proteus::ProteusSessionDebugInfo> for ProteusSessionDebugInfo {
+    fn from(info: core_crypto::proteus::ProteusSessionDebugInfo) -> Self {
+        Self {
+            session_id: info.session_id,
+            session_tag: info.session_tag,
+            pending_prekey: info.pending_prekey,
+            session_states: info.session_states as u32,
+            send_chain_counter: info.send_chain_counter,
+            previous_send_chain_counter: info.previous_send_chain_counter,
+            recv_chain_counters: info.recv_chain_counters,
+            skipped_message_keys: info.skipped_message_keys as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "proteus"), allow(dead_code))]
+/// see [core_crypto::identity_binding::IdentityBinding]
+struct IdentityBinding {
+    #[serde(with = "serde_bytes")]
+    client_id: Vec<u8>,
+    proteus_fingerprint: String,
+    #[serde(with = "serde_bytes")]
+    mls_public_key: Vec<u8>,
+}
+
+#[cfg(feature = "proteus")]
+impl From<core_crypto::identity_binding::IdentityBinding> for IdentityBinding {
+    fn from(binding: core_crypto::identity_binding::IdentityBinding) -> Self {
+        Self {
+            proteus_fingerprint: binding.proteus_fingerprint(),
+            client_id: binding.client_id.to_vec(),
+            mls_public_key: binding.mls_public_key,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(feature = "proteus"), allow(dead_code))]
+/// see [core_crypto::proteus::ProteusDiagnostics]
+struct ProteusDiagnostics {
+    identity_fingerprint: String,
+    sessions: Vec<ProteusSessionDebugInfo>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsConversationSummary]
+pub struct ConversationSummary {
+    /// Conversation id
+    #[serde(with = "serde_bytes")]
+    pub id: Vec<u8>,
+    /// Ciphersuite of the conversation
+    pub ciphersuite: Ciphersuite,
+    /// Epoch of the conversation when it was last persisted
+    pub epoch: u64,
+    /// Number of members of the conversation when it was last persisted
+    pub member_count: u32,
+    /// Unix timestamp (in seconds) of the last time the conversation was persisted
+    pub last_persisted: u64,
+    /// Id of the parent conversation, for a sub-conversation
+    #[serde(with = "serde_bytes")]
+    pub parent_id: Option<Vec<u8>>,
+}
+
+impl From<MlsConversationSummary> for ConversationSummary {
+    fn from(summary: MlsConversationSummary) -> Self {
+        Self {
+            id: summary.id,
+            ciphersuite: summary.ciphersuite.into(),
+            epoch: summary.epoch,
+            member_count: summary.member_count,
+            last_persisted: summary.last_persisted,
+            parent_id: summary.parent_id,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsConversationWipeReport]
+pub struct ConversationWipeReport {
+    /// Proposals which were not committed yet
+    pub pending_proposals: u32,
+    /// Encryption keypairs of the own leaf node and of the current epoch
+    pub encryption_keypairs: u32,
+    /// Messages buffered because they were received before the commit creating their epoch
+    pub buffered_messages: u32,
+    /// Whether a pending group created when joining by external commit existed
+    pub pending_group: bool,
+}
+
+impl From<MlsConversationWipeReport> for ConversationWipeReport {
+    fn from(report: MlsConversationWipeReport) -> Self {
+        Self {
+            pending_proposals: report.pending_proposals as u32,
+            encryption_keypairs: report.encryption_keypairs as u32,
+            buffered_messages: report.buffered_messages as u32,
+            pending_group: report.pending_group,
+        }
+    }
+}
+
+/// Outcome of rejoining a conversation, see [core_crypto::mls::MlsCentral::rejoin_all_from_group_infos]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RejoinResult {
+    #[serde(with = "serde_bytes")]
+    conversation_id: ConversationId,
+    /// The external commit to send, none when the conversation could not be joined
+    bundle: Option<ConversationInitBundle>,
+    /// Why the conversation could not be joined, see [core_crypto::CoreCryptoErrorCode]
+    error_code: Option<u16>,
+}
+
 #[wasm_bindgen(skip_jsdoc, getter_with_clone)]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConversationInitBundle {
+    #[serde(with = "serde_bytes")]
     conversation_id: ConversationId,
+    #[serde(with = "serde_bytes")]
     commit: Vec<u8>,
     group_info: GroupInfoBundle,
     /// New CRL Distribution of members of this group
@@ -568,6 +880,7 @@ impl TryFrom<MlsConversationInitBundle> for ConversationInitBundle {
 /// see [core_crypto::prelude::WelcomeBundle]
 pub struct WelcomeBundle {
     /// Identifier of the joined conversation
+    #[serde(with = "serde_bytes")]
     id: ConversationId,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
@@ -601,12 +914,14 @@ impl From<core_crypto::prelude::WelcomeBundle> for WelcomeBundle {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 /// see [core_crypto::prelude::decrypt::MlsConversationDecryptMessage]
 pub struct DecryptedMessage {
+    #[serde(with = "serde_bytes")]
     message: Option<Vec<u8>>,
     proposals: Vec<ProposalBundle>,
     /// It is set to false if ingesting this MLS message has resulted in the client being removed from the group (i.e. a Remove commit)
     is_active: bool,
     /// Commit delay hint (in milliseconds) to prevent clients from hammering the server with epoch changes
     commit_delay: Option<u32>,
+    #[serde(with = "serde_bytes")]
     sender_client_id: Option<Vec<u8>>,
     /// true when the decrypted message resulted in an epoch change i.e. it was a commit
     has_epoch_changed: bool,
@@ -614,6 +929,8 @@ pub struct DecryptedMessage {
     buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
+    /// What the commit changed, only for commits
+    commit_delta: Option<CommitDelta>,
 }
 
 impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
@@ -652,6 +969,7 @@ impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
             identity: from.identity.map(Into::into),
             buffered_messages,
             crl_new_distribution_points: from.crl_new_distribution_points,
+            commit_delta: from.commit_delta.map(Into::into),
         })
     }
 }
@@ -718,21 +1036,116 @@ impl DecryptedMessage {
             .clone()
             .map(|crl_dp| crl_dp.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn commit_delta(&self) -> Option<CommitDelta> {
+        self.commit_delta.clone()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsCommitDelta]
+pub struct CommitDelta {
+    added: Vec<Vec<u8>>,
+    removed: Vec<Vec<u8>>,
+    updated: Vec<Vec<u8>>,
+    /// Whether the leaf of this client was updated by the commit
+    pub self_updated: bool,
+}
+
+impl From<MlsCommitDelta> for CommitDelta {
+    fn from(delta: MlsCommitDelta) -> Self {
+        Self {
+            added: delta.added.into_iter().map(ClientId::into).collect(),
+            removed: delta.removed.into_iter().map(ClientId::into).collect(),
+            updated: delta.updated.into_iter().map(ClientId::into).collect(),
+            self_updated: delta.self_updated,
+        }
+    }
+}
+
+fn client_ids_to_js(ids: &[Vec<u8>]) -> js_sys::Array {
+    ids.iter()
+        .map(|id| Uint8Array::from(id.as_slice()))
+        .map(JsValue::from)
+        .collect::<js_sys::Array>()
+}
+
+#[wasm_bindgen]
+impl CommitDelta {
+    /// Clients added by the commit
+    #[wasm_bindgen(getter)]
+    pub fn added(&self) -> js_sys::Array {
+        client_ids_to_js(&self.added)
+    }
+
+    /// Clients removed by the commit
+    #[wasm_bindgen(getter)]
+    pub fn removed(&self) -> js_sys::Array {
+        client_ids_to_js(&self.removed)
+    }
+
+    /// Clients whose leaf was updated by the commit
+    #[wasm_bindgen(getter)]
+    pub fn updated(&self) -> js_sys::Array {
+        client_ids_to_js(&self.updated)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsCommitPreview]
+struct CommitPreview {
+    members: Vec<serde_bytes::ByteBuf>,
+    epoch: u64,
+    added: Vec<serde_bytes::ByteBuf>,
+    removed: Vec<serde_bytes::ByteBuf>,
+    updated: Vec<serde_bytes::ByteBuf>,
+    self_updated: bool,
+    commit_size: u32,
+    welcome_size: Option<u32>,
+    group_info_size: u32,
+}
+
+impl From<MlsCommitPreview> for CommitPreview {
+    fn from(preview: MlsCommitPreview) -> Self {
+        let to_js = |ids: Vec<ClientId>| {
+            ids.into_iter()
+                .map(|id| serde_bytes::ByteBuf::from(id.to_vec()))
+                .collect()
+        };
+        Self {
+            members: to_js(preview.members),
+            epoch: preview.epoch,
+            added: to_js(preview.delta.added),
+            removed: to_js(preview.delta.removed),
+            updated: to_js(preview.delta.updated),
+            self_updated: preview.delta.self_updated,
+            commit_size: preview.commit_size as u32,
+            welcome_size: preview.welcome_size.map(|s| s as u32),
+            group_info_size: preview.group_info_size as u32,
+        }
+    }
 }
 
 #[wasm_bindgen]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 /// to avoid recursion
 pub struct BufferedDecryptedMessage {
+    #[serde(with = "serde_bytes")]
     message: Option<Vec<u8>>,
     proposals: Vec<ProposalBundle>,
     is_active: bool,
     commit_delay: Option<u32>,
+    #[serde(with = "serde_bytes")]
     sender_client_id: Option<Vec<u8>>,
     has_epoch_changed: bool,
     identity: Option<WireIdentity>,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
+    /// What the commit changed, only for commits
+    commit_delta: Option<CommitDelta>,
 }
 
 impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage {
@@ -760,6 +1173,7 @@ impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.map(Into::into),
             crl_new_distribution_points: from.crl_new_distribution_points,
+            commit_delta: from.commit_delta.map(Into::into),
         })
     }
 }
@@ -819,6 +1233,11 @@ impl BufferedDecryptedMessage {
             .clone()
             .map(|crl_dp| crl_dp.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn commit_delta(&self) -> Option<CommitDelta> {
+        self.commit_delta.clone()
+    }
 }
 
 #[wasm_bindgen(skip_jsdoc, getter_with_clone)]
@@ -882,6 +1301,9 @@ pub struct ConversationConfiguration {
     ciphersuite: Option<Ciphersuite>,
     external_senders: Vec<Vec<u8>>,
     custom: CustomConfiguration,
+    required_extension_types: Option<Vec<u16>>,
+    required_proposal_types: Option<Vec<u16>>,
+    required_credential_types: Option<Vec<CredentialType>>,
 }
 
 #[wasm_bindgen]
@@ -892,16 +1314,55 @@ impl ConversationConfiguration {
         external_senders: Option<Vec<Uint8Array>>,
         key_rotation_span: Option<u32>,
         wire_policy: Option<WirePolicy>,
+        out_of_order_tolerance: Option<u32>,
+        maximum_forward_distance: Option<u32>,
+        max_application_message_epoch_age: Option<u32>,
+        epoch_fence: Option<EpochFence>,
+        required_extension_types: Option<Box<[u16]>>,
+        required_proposal_types: Option<Box<[u16]>>,
+        required_credential_types: Option<Box<[u16]>>,
+        allowed_ciphersuites: Option<Box<[u16]>>,
+        require_x509_credentials: Option<bool>,
     ) -> WasmCryptoResult<ConversationConfiguration> {
         let external_senders = external_senders
             .map(|exs| exs.iter().cloned().map(|jsv| jsv.to_vec()).collect())
             .unwrap_or_default();
+        let required_credential_types = required_credential_types
+            .map(|cts| {
+                cts.iter()
+                    .map(|&ct| CredentialType::from_repr(ct).ok_or(WasmError::EnumError))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
         Ok(Self {
             ciphersuite,
             external_senders,
-            custom: CustomConfiguration::new(key_rotation_span, wire_policy),
+            custom: CustomConfiguration::new(
+                key_rotation_span,
+                wire_policy,
+                out_of_order_tolerance,
+                maximum_forward_distance,
+                max_application_message_epoch_age,
+                epoch_fence,
+                allowed_ciphersuites,
+                require_x509_credentials,
+            )?,
+            required_extension_types: required_extension_types.map(Into::into),
+            required_proposal_types: required_proposal_types.map(Into::into),
+            required_credential_types,
         })
     }
+
+    /// see [core_crypto::prelude::MlsConversationConfiguration::validate]
+    pub fn validate(&self, creator_credential_type: CredentialType) -> WasmCryptoResult<()> {
+        // unknown proposal types are the only reason for the conversion to fail
+        let cfg: MlsConversationConfiguration =
+            self.clone().try_into().map_err(|_| CryptoError::InvalidConfiguration {
+                field: "required_capabilities.proposal_types".to_string(),
+                reason: "contains an unknown proposal type".to_string(),
+            })?;
+        Ok(cfg.validate(creator_credential_type.into())?)
+    }
 }
 
 impl TryInto<MlsConversationConfiguration> for ConversationConfiguration {
@@ -914,6 +1375,20 @@ impl TryInto<MlsConversationConfiguration> for ConversationConfiguration {
 
         cfg.set_raw_external_senders(self.external_senders);
 
+        if self.required_extension_types.is_some()
+            || self.required_proposal_types.is_some()
+            || self.required_credential_types.is_some()
+        {
+            cfg.required_capabilities = MlsRequiredCapabilities::try_from_raw(
+                &self.required_extension_types.take().unwrap_or_default(),
+                &self.required_proposal_types.take().unwrap_or_default(),
+                self.required_credential_types
+                    .take()
+                    .map(|cts| cts.into_iter().map(Into::into).collect())
+                    .unwrap_or(cfg.required_capabilities.credential_types),
+            )?;
+        }
+
         if let Some(ciphersuite) = self.ciphersuite.take() {
             let mls_ciphersuite: CiphersuiteName = ciphersuite.into();
             cfg.ciphersuite = mls_ciphersuite.into();
@@ -929,16 +1404,43 @@ impl TryInto<MlsConversationConfiguration> for ConversationConfiguration {
 pub struct CustomConfiguration {
     key_rotation_span: Option<u32>,
     wire_policy: Option<WirePolicy>,
+    out_of_order_tolerance: Option<u32>,
+    maximum_forward_distance: Option<u32>,
+    max_application_message_epoch_age: Option<u32>,
+    epoch_fence: Option<EpochFence>,
+    allowed_ciphersuites: Option<Vec<MlsCiphersuite>>,
+    require_x509_credentials: Option<bool>,
 }
 
 #[wasm_bindgen]
 impl CustomConfiguration {
     #[wasm_bindgen(constructor)]
-    pub fn new(key_rotation_span: Option<u32>, wire_policy: Option<WirePolicy>) -> Self {
-        Self {
+    pub fn new(
+        key_rotation_span: Option<u32>,
+        wire_policy: Option<WirePolicy>,
+        out_of_order_tolerance: Option<u32>,
+        maximum_forward_distance: Option<u32>,
+        max_application_message_epoch_age: Option<u32>,
+        epoch_fence: Option<EpochFence>,
+        allowed_ciphersuites: Option<Box<[u16]>>,
+        require_x509_credentials: Option<bool>,
+    ) -> WasmCryptoResult<CustomConfiguration> {
+        let allowed_ciphersuites = allowed_ciphersuites.map(|cs| lower_ciphersuites(&cs)).transpose()?;
+        Ok(Self {
             key_rotation_span,
             wire_policy,
-        }
+            out_of_order_tolerance,
+            maximum_forward_distance,
+            max_application_message_epoch_age,
+            epoch_fence,
+            allowed_ciphersuites,
+            require_x509_credentials,
+        })
+    }
+
+    /// see [core_crypto::prelude::MlsCustomConfiguration::validate]
+    pub fn validate(&self) -> WasmCryptoResult<()> {
+        Ok(MlsCustomConfiguration::from(self.clone()).validate()?)
     }
 }
 
@@ -946,19 +1448,34 @@ impl Drop for CustomConfiguration {
     fn drop(&mut self) {
         let _ = self.key_rotation_span.take();
         let _ = self.wire_policy.take();
+        let _ = self.out_of_order_tolerance.take();
+        let _ = self.maximum_forward_distance.take();
+        let _ = self.max_application_message_epoch_age.take();
+        let _ = self.epoch_fence.take();
+        let _ = self.allowed_ciphersuites.take();
+        let _ = self.require_x509_credentials.take();
     }
 }
 
 impl From<CustomConfiguration> for MlsCustomConfiguration {
-    fn from(cfg: CustomConfiguration) -> Self {
+    fn from(mut cfg: CustomConfiguration) -> Self {
         let key_rotation_span = cfg
             .key_rotation_span
             .map(|span| std::time::Duration::from_secs(span as u64));
         let wire_policy = cfg.wire_policy.map(WirePolicy::into).unwrap_or_default();
+        let default = MlsCustomConfiguration::default();
         Self {
             key_rotation_span,
             wire_policy,
-            ..Default::default()
+            out_of_order_tolerance: cfg.out_of_order_tolerance.unwrap_or(default.out_of_order_tolerance),
+            maximum_forward_distance: cfg.maximum_forward_distance.unwrap_or(default.maximum_forward_distance),
+            max_application_message_epoch_age: cfg.max_application_message_epoch_age,
+            epoch_fence: cfg.epoch_fence.map(EpochFence::into).unwrap_or_default(),
+            join_policy: MlsJoinPolicy {
+                allowed_ciphersuites: cfg.allowed_ciphersuites.take().unwrap_or_default(),
+                require_x509_credentials: cfg.require_x509_credentials.unwrap_or_default(),
+            },
+            ..default
         }
     }
 }
@@ -983,6 +1500,52 @@ impl From<WirePolicy> for MlsWirePolicy {
     }
 }
 
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[repr(u16)]
+/// see [core_crypto::prelude::MlsEpochFence]
+pub enum EpochFence {
+    /// The message is encrypted for the current epoch, as if there was no pending commit
+    Disabled = 0x0000,
+    /// Encrypting fails with a PendingCommitInFlight error
+    FailFast = 0x0001,
+    /// The message is queued and encrypted once the pending commit is resolved
+    Queue = 0x0002,
+}
+
+impl From<EpochFence> for MlsEpochFence {
+    fn from(fence: EpochFence) -> Self {
+        match fence {
+            EpochFence::Disabled => Self::Disabled,
+            EpochFence::FailFast => Self::FailFast,
+            EpochFence::Queue => Self::Queue,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[repr(u8)]
+/// see [core_crypto::prelude::RedactionLevel]
+pub enum RedactionLevel {
+    /// Identifiers appear as is, hex encoded
+    None = 1,
+    /// Identifiers are replaced by the beginning of their SHA-256 hash
+    Hashed = 2,
+    /// Identifiers are left out
+    Full = 3,
+}
+
+impl From<RedactionLevel> for core_crypto::prelude::RedactionLevel {
+    fn from(level: RedactionLevel) -> Self {
+        match level {
+            RedactionLevel::None => Self::None,
+            RedactionLevel::Hashed => Self::Hashed,
+            RedactionLevel::Full => Self::Full,
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 /// see [core_crypto::prelude::CoreCryptoCallbacks]
@@ -990,6 +1553,8 @@ pub struct CoreCryptoWasmCallbacks {
     authorize: std::sync::Arc<async_lock::RwLock<js_sys::Function>>,
     user_authorize: std::sync::Arc<async_lock::RwLock<js_sys::Function>>,
     client_is_existing_group_user: std::sync::Arc<async_lock::RwLock<js_sys::Function>>,
+    member_change_authorize: std::sync::Arc<async_lock::RwLock<js_sys::Function>>,
+    proteus_identity_changed: std::sync::Arc<async_lock::RwLock<js_sys::Function>>,
     ctx: std::sync::Arc<async_lock::RwLock<JsValue>>,
 }
 
@@ -1000,6 +1565,8 @@ impl CoreCryptoWasmCallbacks {
         authorize: js_sys::Function,
         user_authorize: js_sys::Function,
         client_is_existing_group_user: js_sys::Function,
+        member_change_authorize: js_sys::Function,
+        proteus_identity_changed: js_sys::Function,
         ctx: JsValue,
     ) -> Self {
         #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
@@ -1007,6 +1574,8 @@ impl CoreCryptoWasmCallbacks {
             authorize: std::sync::Arc::new(authorize.into()),
             user_authorize: std::sync::Arc::new(user_authorize.into()),
             client_is_existing_group_user: std::sync::Arc::new(client_is_existing_group_user.into()),
+            member_change_authorize: std::sync::Arc::new(member_change_authorize.into()),
+            proteus_identity_changed: std::sync::Arc::new(proteus_identity_changed.into()),
             ctx: std::sync::Arc::new(ctx.into()),
         }
     }
@@ -1109,6 +1678,56 @@ impl CoreCryptoCallbacks for CoreCryptoWasmCallbacks {
         .await
         .unwrap_or_default()
     }
+
+    async fn member_change_authorize(
+        &self,
+        conversation_id: ConversationId,
+        committer: ClientId,
+        added_clients: Vec<ClientId>,
+        removed_clients: Vec<ClientId>,
+    ) -> bool {
+        let member_change_authorize = self.member_change_authorize.read().await;
+        let this = self.ctx.read().await;
+        let added = added_clients
+            .into_iter()
+            .map(|client| js_sys::Uint8Array::from(client.as_slice()))
+            .collect::<js_sys::Array>();
+        let removed = removed_clients
+            .into_iter()
+            .map(|client| js_sys::Uint8Array::from(client.as_slice()))
+            .collect::<js_sys::Array>();
+
+        Self::drive_js_func_call(member_change_authorize.apply(
+            &this,
+            &js_sys::Array::of4(
+                &js_sys::Uint8Array::from(conversation_id.as_slice()).into(),
+                &js_sys::Uint8Array::from(committer.as_slice()).into(),
+                &added.into(),
+                &removed.into(),
+            ),
+        ))
+        .await
+        .unwrap_or_default()
+    }
+
+    async fn proteus_identity_changed(
+        &self,
+        session_id: String,
+        previous_fingerprint: String,
+        new_fingerprint: String,
+    ) -> bool {
+        let proteus_identity_changed = self.proteus_identity_changed.read().await;
+        let this = self.ctx.read().await;
+
+        Self::drive_js_func_call(proteus_identity_changed.call3(
+            &this,
+            &JsValue::from_str(&session_id),
+            &JsValue::from_str(&previous_fingerprint),
+            &JsValue::from_str(&new_fingerprint),
+        ))
+        .await
+        .unwrap_or_default()
+    }
 }
 
 #[derive(Debug)]
@@ -1122,10 +1741,29 @@ pub struct CoreCrypto {
 impl CoreCrypto {
     /// Returns the current version of CoreCrypto
     pub fn version() -> String {
-        crate::VERSION.into()
+        core_crypto::CoreCrypto::version().into()
     }
 
-    /// see [core_crypto::mls::MlsCentral::try_new]
+    /// Returns: [`WasmCryptoResult<BuildMetadata>`]
+    ///
+    /// see [core_crypto::CoreCrypto::build_metadata]
+    pub fn build_metadata() -> WasmCryptoResult<JsValue> {
+        let metadata = BuildMetadata::from(core_crypto::CoreCrypto::build_metadata());
+        Ok(serde_wasm_bindgen::to_value(&metadata)?)
+    }
+
+    /// see [core_crypto::prelude::set_logger]. The logger is invoked with the level, the message and the context as
+    /// a JSON string. Passing `undefined` unregisters it
+    pub fn set_logger(logger: Option<js_sys::Function>) {
+        let logger = logger.map(|logger| {
+            std::sync::Arc::new(CoreCryptoWasmLogger(logger))
+                as std::sync::Arc<dyn core_crypto::prelude::CoreCryptoLogger>
+        });
+        core_crypto::prelude::set_logger(logger);
+    }
+
+    /// see [core_crypto::mls::MlsCentral::try_new], or [core_crypto::mls::MlsCentral::try_new_with_progress] with
+    /// `lazy_group_restore`
     pub async fn _internal_new(
         path: String,
         key: String,
@@ -1133,14 +1771,19 @@ impl CoreCrypto {
         ciphersuites: Box<[u16]>,
         entropy_seed: Option<Box<[u8]>>,
         nb_key_package: Option<u32>,
+        allow_identity_export: Option<bool>,
+        take_over_store: Option<bool>,
+        lazy_group_restore: Option<bool>,
+        disable_group_cache: Option<bool>,
+        clock_skew_leeway_seconds: Option<u32>,
     ) -> WasmCryptoResult<CoreCrypto> {
         let ciphersuites = lower_ciphersuites(&ciphersuites)?;
-        let entropy_seed = entropy_seed.map(|s| s.to_vec());
+        let entropy_seed = entropy_seed.map(<[u8]>::into_vec);
         let nb_key_package = nb_key_package
             .map(usize::try_from)
             .transpose()
             .map_err(CryptoError::from)?;
-        let configuration = MlsCentralConfiguration::try_new(
+        let mut configuration = MlsCentralConfiguration::try_new(
             path,
             key,
             Some(client_id.into()),
@@ -1149,10 +1792,19 @@ impl CoreCrypto {
             nb_key_package,
         )
         .map_err(CoreCryptoError::from)?;
+        configuration.set_allow_identity_export(allow_identity_export.unwrap_or_default());
+        configuration.set_take_over_store(take_over_store.unwrap_or_default());
+        configuration.set_disable_group_cache(disable_group_cache.unwrap_or_default());
+        if let Some(leeway_seconds) = clock_skew_leeway_seconds {
+            configuration.set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds.into()));
+        }
 
-        let central = MlsCentral::try_new(configuration)
-            .await
-            .map_err(CoreCryptoError::from)?;
+        let central = if lazy_group_restore.unwrap_or_default() {
+            MlsCentral::try_new_with_progress(configuration).await
+        } else {
+            MlsCentral::try_new(configuration).await
+        }
+        .map_err(CoreCryptoError::from)?;
         Ok(CoreCrypto {
             inner: async_lock::RwLock::new(central.into()).into(),
             proteus_last_error_code: async_lock::RwLock::new(0).into(),
@@ -1166,20 +1818,34 @@ impl CoreCrypto {
         ciphersuites: Box<[u16]>,
         entropy_seed: Option<Box<[u8]>>,
         nb_key_package: Option<u32>,
+        allow_identity_export: Option<bool>,
+        take_over_store: Option<bool>,
+        lazy_group_restore: Option<bool>,
+        disable_group_cache: Option<bool>,
+        clock_skew_leeway_seconds: Option<u32>,
     ) -> WasmCryptoResult<CoreCrypto> {
         let ciphersuites = lower_ciphersuites(&ciphersuites)?;
-        let entropy_seed = entropy_seed.map(|s| s.to_vec());
+        let entropy_seed = entropy_seed.map(<[u8]>::into_vec);
         let nb_key_package = nb_key_package
             .map(usize::try_from)
             .transpose()
             .map_err(CryptoError::from)?;
-        let configuration =
+        let mut configuration =
             MlsCentralConfiguration::try_new(path, key, None, ciphersuites, entropy_seed, nb_key_package)
                 .map_err(CoreCryptoError::from)?;
+        configuration.set_allow_identity_export(allow_identity_export.unwrap_or_default());
+        configuration.set_take_over_store(take_over_store.unwrap_or_default());
+        configuration.set_disable_group_cache(disable_group_cache.unwrap_or_default());
+        if let Some(leeway_seconds) = clock_skew_leeway_seconds {
+            configuration.set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds.into()));
+        }
 
-        let central = MlsCentral::try_new(configuration)
-            .await
-            .map_err(CoreCryptoError::from)?;
+        let central = if lazy_group_restore.unwrap_or_default() {
+            MlsCentral::try_new_with_progress(configuration).await
+        } else {
+            MlsCentral::try_new(configuration).await
+        }
+        .map_err(CoreCryptoError::from)?;
 
         Ok(CoreCrypto {
             inner: async_lock::RwLock::new(central.into()).into(),
@@ -1212,41 +1878,77 @@ impl CoreCrypto {
         )
     }
 
-    /// Returns [`WasmCryptoResult<Vec<u8>>`]
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// See [core_crypto::mls::MlsCentral::mls_generate_keypair]
-    pub fn mls_generate_keypair(&self, ciphersuites: Box<[u16]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::export_identity]
+    pub fn export_identity(&self, backup_key: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let ciphersuites = lower_ciphersuites(&ciphersuites)?;
-                let central = this.read().await;
-                let pks = central
-                    .mls_generate_keypairs(ciphersuites)
+                let backup = this
+                    .read()
+                    .await
+                    .export_identity(&backup_key)
                     .await
                     .map_err(CoreCryptoError::from)?;
-
-                let js_pks = js_sys::Array::from_iter(
-                    pks.into_iter()
-                        .map(|kp| js_sys::Uint8Array::from(kp.as_slice()))
-                        .map(JsValue::from),
-                );
-
-                WasmCryptoResult::Ok(js_pks.into())
+                WasmCryptoResult::Ok(Uint8Array::from(backup.as_slice()).into())
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<()>`]
-    ///
-    /// See [core_crypto::mls::MlsCentral::mls_init_with_client_id]
-    pub fn mls_init_with_client_id(
-        &self,
-        client_id: FfiClientId,
-        signature_public_keys: Box<[Uint8Array]>,
-        ciphersuites: Box<[u16]>,
+    /// see [core_crypto::mls::MlsCentral::import_identity]
+    pub fn import_identity(&self, backup: Box<[u8]>, backup_key: Box<[u8]>, ciphersuites: Box<[u16]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let ciphersuites = lower_ciphersuites(&ciphersuites)?;
+                this.write()
+                    .await
+                    .import_identity(&backup, &backup_key, ciphersuites)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// See [core_crypto::mls::MlsCentral::mls_generate_keypair]
+    pub fn mls_generate_keypair(&self, ciphersuites: Box<[u16]>) -> Promise {
+        let this = self.inner.clone();
+
+        future_to_promise(
+            async move {
+                let ciphersuites = lower_ciphersuites(&ciphersuites)?;
+                let central = this.read().await;
+                let pks = central
+                    .mls_generate_keypairs(ciphersuites)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                let js_pks = js_sys::Array::from_iter(
+                    pks.into_iter()
+                        .map(|kp| js_sys::Uint8Array::from(kp.as_slice()))
+                        .map(JsValue::from),
+                );
+
+                WasmCryptoResult::Ok(js_pks.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<()>`]
+    ///
+    /// See [core_crypto::mls::MlsCentral::mls_init_with_client_id]
+    pub fn mls_init_with_client_id(
+        &self,
+        client_id: FfiClientId,
+        signature_public_keys: Box<[Uint8Array]>,
+        ciphersuites: Box<[u16]>,
     ) -> Promise {
         let this = self.inner.clone();
 
@@ -1366,6 +2068,82 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// Registers a callback invoked with the size of a write and the space left (when known), both in bytes, when
+    /// the write would exceed the storage quota of the origin. Passing `undefined` unregisters it
+    pub fn set_storage_pressure_callback(&self, callback: Option<js_sys::Function>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let callback = callback.map(|callback| {
+                    Box::new(move |required: usize, available: Option<u64>| {
+                        let available = available.map(|a| JsValue::from(a as f64)).unwrap_or(JsValue::UNDEFINED);
+                        if let Err(e) = callback.call2(&JsValue::NULL, &JsValue::from(required as f64), &available) {
+                            web_sys::console::error_1(&e);
+                        }
+                    }) as Box<dyn Fn(usize, Option<u64>)>
+                });
+                this.read()
+                    .await
+                    .provider()
+                    .borrow_keystore()
+                    .set_storage_pressure_callback(callback)
+                    .await;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::set_persistence_failure_callback]. The callback is invoked with the
+    /// conversation id and the error message. Passing `undefined` unregisters it
+    pub fn set_persistence_failure_callback(&self, callback: Option<js_sys::Function>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let callback = callback.map(|callback| {
+                    Box::new(move |conversation_id: &[u8], error: &CryptoKeystoreError| {
+                        let conversation_id = Uint8Array::from(conversation_id);
+                        let error = JsValue::from(error.to_string());
+                        if let Err(e) = callback.call2(&JsValue::NULL, &conversation_id, &error) {
+                            web_sys::console::error_1(&e);
+                        }
+                    }) as PersistenceFailureCallback
+                });
+                this.read().await.set_persistence_failure_callback(callback).await;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<js_sys::Uint8Array>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::dirty_conversations]
+    pub fn dirty_conversations(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let conversations = this
+                    .read()
+                    .await
+                    .dirty_conversations()
+                    .await
+                    .iter()
+                    .map(|id| JsValue::from(Uint8Array::from(id.as_slice())))
+                    .collect::<js_sys::Array>();
+
+                WasmCryptoResult::Ok(conversations.into())
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns:: [`WasmCryptoResult<js_sys::Uint8Array>`]
     ///
     /// see [core_crypto::mls::MlsCentral::client_public_key]
@@ -1384,6 +2162,43 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<Option<js_sys::Uint8Array>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::client_id]. None when MLS has not been initialized yet
+    pub fn client_id(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let client_id = this.read().await.client_id().ok();
+                WasmCryptoResult::Ok(
+                    client_id
+                        .map(|id| Uint8Array::from(id.as_slice()).into())
+                        .unwrap_or(JsValue::UNDEFINED),
+                )
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<PublicKeyBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::client_public_key_bundle]
+    pub fn client_public_key_bundle(&self, ciphersuite: Ciphersuite, credential_type: CredentialType) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let bundle = this
+                    .read()
+                    .await
+                    .client_public_key_bundle(ciphersuite.into(), credential_type.into())
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&PublicKeyBundle::from(bundle))?)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns: [`WasmCryptoResult<js_sys::Array<js_sys::Uint8Array>>`]
     ///
     /// see [core_crypto::mls::MlsCentral::client_keypackages]
@@ -1428,6 +2243,50 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<KeyPackageValidationReport>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::validate_keypackage]
+    pub fn validate_keypackage(&self, key_package: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let report = this
+                    .read()
+                    .await
+                    .validate_keypackage(&key_package)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&KeyPackageValidationReport::from(report))?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<KeyPackageUploadPayload>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::keypackages_upload_payload]
+    pub fn keypackages_upload_payload(
+        &self,
+        ciphersuite: Ciphersuite,
+        credential_type: CredentialType,
+        count: u32,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let payload = this
+                    .read()
+                    .await
+                    .keypackages_upload_payload(ciphersuite.into(), credential_type.into(), count as usize)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&KeyPackageUploadPayload::from(payload))?)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns: [`WasmCryptoResult<usize>`]
     ///
     /// see [core_crypto::mls::MlsCentral::client_valid_keypackages_count]
@@ -1449,6 +2308,22 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::set_clock_skew_leeway]
+    pub fn set_clock_skew_leeway(&self, leeway_seconds: u32) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .set_clock_skew_leeway(std::time::Duration::from_secs(leeway_seconds.into()));
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns: [`WasmCryptoResult<usize>`]
     ///
     /// see [core_crypto::mls::MlsCentral::delete_keypackages]
@@ -1475,6 +2350,29 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<Map<u16, usize>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::prune_keypackages_not_in]. The remaining counts are keyed by ciphersuite
+    pub fn prune_keypackages_not_in(&self, ciphersuites: Box<[u16]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let ciphersuites = lower_ciphersuites(&ciphersuites)?;
+                let remaining = this
+                    .read()
+                    .await
+                    .prune_keypackages_not_in(&ciphersuites)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(|(cs, count)| (u16::from(cs), count))
+                    .collect::<HashMap<_, _>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&remaining)?)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns: [`WasmCryptoResult<()>`]
     ///
     /// see [core_crypto::mls::MlsCentral::new_conversation]
@@ -1489,11 +2387,7 @@ impl CoreCrypto {
             async move {
                 this.write()
                     .await
-                    .new_conversation(
-                        &conversation_id.to_vec(),
-                        creator_credential_type.into(),
-                        config.try_into()?,
-                    )
+                    .new_conversation(&conversation_id, creator_credential_type.into(), config.try_into()?)
                     .await
                     .map_err(CoreCryptoError::from)?;
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
@@ -1502,6 +2396,43 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_conversation_with_members]
+    pub fn create_conversation_with_members(
+        &self,
+        conversation_id: ConversationId,
+        creator_credential_type: CredentialType,
+        config: ConversationConfiguration,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+                let mut central = this.write().await;
+                let creation = central
+                    .new_conversation_with_members(
+                        &conversation_id,
+                        creator_credential_type.into(),
+                        config.try_into()?,
+                        key_packages,
+                    )
+                    .await?;
+                let creation: MemberAddedMessages = creation.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&creation)?)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns [`WasmCryptoResult<u64>`]
     ///
     /// see [core_crypto::mls::MlsCentral::conversation_epoch]
@@ -1539,88 +2470,72 @@ impl CoreCrypto {
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<SelfTestReport>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::process_raw_welcome_message]
-    pub fn process_welcome_message(
-        &self,
-        welcome_message: Box<[u8]>,
-        custom_configuration: CustomConfiguration,
-    ) -> Promise {
+    /// see [core_crypto::CoreCrypto::self_test]
+    #[cfg(feature = "self-test")]
+    pub fn self_test(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let bundle = this
+                let report = SelfTestReport::from(this.read().await.self_test().await);
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&report)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::CoreCrypto::generate_diagnostic_report]
+    pub fn generate_diagnostic_report(&self, redaction: RedactionLevel) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let report = this
                     .write()
                     .await
-                    .process_raw_welcome_message(welcome_message.into(), custom_configuration.into())
+                    .generate_diagnostic_report(redaction.into())
                     .await
                     .map_err(CoreCryptoError::from)?;
-                let bundle: WelcomeBundle = bundle.into();
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
+                WasmCryptoResult::Ok(report.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Option<MemberAddedMessages>>`]
+    /// Returns: [`WasmCryptoResult<ConversationHealth>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::add_members_to_conversation]
-    pub fn add_clients_to_conversation(
-        &self,
-        conversation_id: ConversationId,
-        key_packages: Box<[Uint8Array]>,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversation_health]
+    pub fn conversation_health(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let key_packages = key_packages
-                    .iter()
-                    .map(|kp| {
-                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
-                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
-                    })
-                    .collect::<CoreCryptoResult<Vec<_>>>()?;
-
-                let mut central = this.write().await;
-                let commit = central
-                    .add_members_to_conversation(&conversation_id, key_packages)
-                    .await?;
-                let commit: MemberAddedMessages = commit.try_into()?;
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+                let health = ConversationHealth::from(this.read().await.conversation_health(&conversation_id));
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&health)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Option<js_sys::Uint8Array>>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::remove_members_from_conversation]
-    pub fn remove_clients_from_conversation(
+    /// see [core_crypto::mls::MlsCentral::set_message_limits]
+    pub fn set_message_limits(
         &self,
-        conversation_id: ConversationId,
-        clients: Box<[Uint8Array]>,
+        max_message_size: u32,
+        max_welcome_size: u32,
+        max_group_info_size: u32,
     ) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let clients = clients
-                    .iter()
-                    .cloned()
-                    .map(|c| c.to_vec().into())
-                    .collect::<Vec<ClientId>>();
-
-                let mut central = this.write().await;
-                let commit = central
-                    .remove_members_from_conversation(&conversation_id, &clients)
-                    .await
-                    .map_err(CoreCryptoError::from)?;
-
-                let commit: CommitBundle = commit.try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+                this.write().await.set_message_limits(MlsMessageLimits {
+                    max_message_size: max_message_size as usize,
+                    max_welcome_size: max_welcome_size as usize,
+                    max_group_info_size: max_group_info_size as usize,
+                });
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
@@ -1628,1280 +2543,2748 @@ impl CoreCrypto {
 
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
-    pub fn mark_conversation_as_child_of(&self, child_id: Box<[u8]>, parent_id: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_conversation_health_thresholds]
+    pub fn set_conversation_health_thresholds(&self, suspect: u32, broken: u32) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let mut central = this.write().await;
-                central
-                    .mark_conversation_as_child_of(&child_id.into(), &parent_id.into())
+                this.write()
                     .await
-                    .map_err(CoreCryptoError::from)?;
-
+                    .set_conversation_health_thresholds(MlsConversationHealthThresholds { suspect, broken });
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<CommitBundle>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::update_keying_material]
-    pub fn update_keying_material(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversation_health_observer]. The callback is invoked with the
+    /// conversation id and its new health
+    pub fn set_conversation_health_observer(&self, callback: js_sys::Function) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let mut central = this.write().await;
-                let commit = central
-                    .update_keying_material(&conversation_id)
-                    .await
-                    .map_err(CoreCryptoError::from)?;
-
-                let commit: CommitBundle = commit.try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+                let observer = ConversationHealthWasmObserver(callback);
+                this.write().await.conversation_health_observer(Box::new(observer));
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::commit_pending_proposals]
-    pub fn commit_pending_proposals(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::credential_observer]. The callback is invoked with the conversation id,
+    /// the client id and the TLS-serialized credential
+    pub fn set_credential_observer(&self, callback: js_sys::Function) -> Promise {
         let this = self.inner.clone();
-
         future_to_promise(
             async move {
-                let mut central = this.write().await;
-                let commit: Option<CommitBundle> = central
-                    .commit_pending_proposals(&conversation_id)
-                    .await?
-                    .map(|c| c.try_into())
-                    .transpose()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+                let observer = CredentialWasmObserver(callback);
+                this.write().await.credential_observer(Box::new(observer));
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Vec<CredentialFirstSeen>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
-    pub fn wipe_conversation(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::seen_credentials]
+    pub fn seen_credentials(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let mut central = this.write().await;
-                central
-                    .wipe_conversation(&conversation_id)
+                let seen = this
+                    .read()
                     .await
-                    .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                    .seen_credentials(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(CredentialFirstSeen::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&seen)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<DecryptedMessage>`]
+    /// Returns: [`WasmCryptoResult<Vec<EpochHistoryEntry>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::decrypt_message]
-    pub fn decrypt_message(&self, conversation_id: ConversationId, payload: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::epoch_history]
+    pub fn epoch_history(&self, conversation_id: ConversationId, limit: u32) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let raw_decrypted_message = this
+                let history = this
                     .write()
                     .await
-                    .decrypt_message(&conversation_id.to_vec(), payload)
+                    .epoch_history(&conversation_id, limit as usize)
                     .await
-                    .map_err(CoreCryptoError::from)?;
-
-                let decrypted_message = DecryptedMessage::try_from(raw_decrypted_message)?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&decrypted_message)?)
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(EpochHistoryEntry::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&history)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<EpochCleanupMetrics>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::encrypt_message]
-    pub fn encrypt_message(&self, conversation_id: ConversationId, message: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::epoch_cleanup_metrics]
+    pub fn epoch_cleanup_metrics(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let ciphertext = this
-                    .write()
-                    .await
-                    .encrypt_message(&conversation_id.to_vec(), message)
-                    .await
-                    .map(|ciphertext| Uint8Array::from(ciphertext.as_slice()))
-                    .map_err(CoreCryptoError::from)?;
-
-                WasmCryptoResult::Ok(ciphertext.into())
+                let metrics = EpochCleanupMetrics::from(this.read().await.epoch_cleanup_metrics());
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&metrics)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_add_proposal]
-    pub fn new_add_proposal(&self, conversation_id: ConversationId, keypackage: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_conversation_stats_enabled]
+    pub fn set_conversation_stats_enabled(&self, enabled: bool) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
-                    .map_err(CoreCryptoError::from)?;
-
-                let proposal: ProposalBundle = this
-                    .write()
-                    .await
-                    .new_add_proposal(&conversation_id.to_vec(), kp.into())
-                    .await
-                    .map_err(CoreCryptoError::from)?
-                    .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                this.write().await.set_conversation_stats_enabled(enabled);
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<ConversationStats>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_update_proposal]
-    pub fn new_update_proposal(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversation_stats]
+    pub fn conversation_stats(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let proposal: ProposalBundle = this
-                    .write()
-                    .await
-                    .new_update_proposal(&conversation_id.to_vec())
-                    .await?
-                    .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                let stats = ConversationStats::from(
+                    this.write()
+                        .await
+                        .conversation_stats(&conversation_id)
+                        .await
+                        .map_err(CoreCryptoError::from)?,
+                );
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&stats)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<RatchetDiagnostics>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_remove_proposal]
-    pub fn new_remove_proposal(&self, conversation_id: ConversationId, client_id: FfiClientId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::ratchet_diagnostics]
+    pub fn ratchet_diagnostics(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let proposal: ProposalBundle = this
-                    .write()
-                    .await
-                    .new_remove_proposal(&conversation_id.to_vec(), client_id.into())
-                    .await
-                    .map_err(CoreCryptoError::from)?
-                    .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                let diagnostics = RatchetDiagnostics::from(
+                    this.write()
+                        .await
+                        .ratchet_diagnostics(&conversation_id)
+                        .await
+                        .map_err(CoreCryptoError::from)?,
+                );
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&diagnostics)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<u32>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_external_add_proposal]
-    pub fn new_external_add_proposal(
-        &self,
-        conversation_id: ConversationId,
-        epoch: u32,
-        ciphersuite: Ciphersuite,
-        credential_type: CredentialType,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::prefetch_conversations]
+    #[allow(clippy::boxed_local)]
+    pub fn prefetch_conversations(&self, conversation_ids: Box<[Uint8Array]>) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        let conversation_ids = conversation_ids.iter().map(|id| id.to_vec()).collect::<Vec<_>>();
         future_to_promise(
             async move {
-                let proposal_bytes = this
+                let loaded = this
                     .write()
                     .await
-                    .new_external_add_proposal(
-                        conversation_id.to_vec(),
-                        u64::from(epoch).into(),
-                        ciphersuite.into(),
-                        credential_type.into(),
-                    )
+                    .prefetch_conversations(&conversation_ids)
                     .await
-                    .map_err(CoreCryptoError::from)?
-                    .to_bytes()
-                    .map(|bytes| Uint8Array::from(bytes.as_slice()))
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
                     .map_err(CoreCryptoError::from)?;
-
-                WasmCryptoResult::Ok(proposal_bytes.into())
+                WasmCryptoResult::Ok((loaded as u32).into())
             }
             .err_into(),
         )
     }
 
-    #[allow(clippy::boxed_local)]
-    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    /// Returns: [`WasmCryptoResult<ConversationCacheStats>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::join_by_external_commit]
-    pub fn join_by_external_commit(
-        &self,
-        group_info: Box<[u8]>,
-        custom_configuration: CustomConfiguration,
-        credential_type: CredentialType,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversation_cache_stats]
+    pub fn conversation_cache_stats(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
-                    .map_err(CoreCryptoError::from)?;
-
-                let result: ConversationInitBundle = this
-                    .write()
-                    .await
-                    .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
-                    .await
-                    .map_err(CoreCryptoError::from)?
-                    .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
+                let stats = ConversationCacheStats::from(this.read().await.conversation_cache_stats());
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&stats)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Option<GroupRestoreProgress>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::merge_pending_group_from_external_commit]
-    pub fn merge_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::restore_next_groups]
+    pub fn restore_next_groups(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                if let Some(decrypted_messages) = this
+                let progress = this
                     .write()
                     .await
-                    .merge_pending_group_from_external_commit(&conversation_id)
+                    .restore_next_groups()
                     .await
                     .map_err(CoreCryptoError::from)?
-                {
-                    let messages = decrypted_messages
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
-
-                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
-                }
-
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                    .map(GroupRestoreProgress::from);
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&progress)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<MetricsSnapshot>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::clear_pending_group_from_external_commit]
-    pub fn clear_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::metrics_snapshot]
+    pub fn metrics_snapshot(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                this.write()
-                    .await
-                    .clear_pending_group_from_external_commit(&conversation_id)
-                    .await
-                    .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let snapshot = MetricsSnapshot::from(this.read().await.metrics_snapshot());
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&snapshot)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::commit_accepted]
-    pub fn commit_accepted(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::set_keystore_slow_operation_threshold]
+    pub fn set_keystore_slow_operation_threshold(&self, threshold_ms: Option<u32>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                if let Some(decrypted_messages) = this
-                    .write()
-                    .await
-                    .commit_accepted(&conversation_id)
-                    .await
-                    .map_err(CoreCryptoError::from)?
-                {
-                    let messages = decrypted_messages
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
-
-                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
-                }
-
+                this.read().await.set_keystore_slow_operation_threshold(
+                    threshold_ms.map(|ms| std::time::Duration::from_millis(ms.into())),
+                );
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::clear_pending_proposal]
-    pub fn clear_pending_proposal(&self, conversation_id: ConversationId, proposal_ref: Box<[u8]>) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::take_over_store]
+    pub fn take_over_store(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
                 this.write()
                     .await
-                    .clear_pending_proposal(&conversation_id.to_vec(), proposal_ref.to_vec().into())
+                    .take_over_store()
                     .await
                     .map_err(CoreCryptoError::from)?;
-
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::clear_pending_commit]
-    pub fn clear_pending_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<any>`] the value returned by `callback`
+    ///
+    /// Runs `callback` within a transaction: it is handed a [CoreCryptoContext] whose keystore writes are all
+    /// persisted once the callback resolves and all discarded, along with the in-memory changes, when it throws or
+    /// rejects. Other calls on this instance wait until the transaction is over.
+    ///
+    /// see [core_crypto::CoreCrypto::context]
+    pub fn transaction(&self, callback: js_sys::Function) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(async move {
+            let mut central = this.write_arc().await;
+            central.begin_transaction().await.map_err(CoreCryptoError::from)?;
+
+            let state = std::rc::Rc::new(async_lock::Mutex::new(Some(central)));
+            let context = CoreCryptoContext { central: state.clone() };
+            let result = match callback.call1(&JsValue::NULL, &context.into()) {
+                Ok(value) => match value.dyn_into::<Promise>() {
+                    Ok(promise) => wasm_bindgen_futures::JsFuture::from(promise).await,
+                    Err(value) => Ok(value),
+                },
+                Err(e) => Err(e),
+            };
+
+            // the context cannot be used anymore past this point
+            let mut central = state
+                .lock()
+                .await
+                .take()
+                .ok_or(CoreCryptoError::from(CryptoError::ConsumerError))?;
+            let result = match result {
+                // rolls the MLS state back on its own when failing
+                Ok(value) => central
+                    .commit_transaction()
+                    .await
+                    .map(|_| value)
+                    .map_err(|e| CoreCryptoError::from(e).into()),
+                Err(e) => {
+                    central.rollback_transaction().await.map_err(CoreCryptoError::from)?;
+                    Err(e)
+                }
+            };
+            #[cfg(feature = "proteus")]
+            if result.is_err() {
+                central.proteus_reload_sessions().await.map_err(CoreCryptoError::from)?;
+            }
+            result
+        })
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::process_raw_welcome_message]
+    pub fn process_welcome_message(
+        &self,
+        welcome_message: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                this.write()
+                let bundle = this
+                    .write()
                     .await
-                    .clear_pending_commit(&conversation_id.to_vec())
+                    .process_raw_welcome_message(welcome_message.into(), custom_configuration.into())
                     .await
                     .map_err(CoreCryptoError::from)?;
-
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let bundle: WelcomeBundle = bundle.into();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::random_bytes]
-    pub fn random_bytes(&self, len: usize) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::process_raw_welcome_message_replacing]
+    pub fn process_welcome_message_replacing(
+        &self,
+        welcome_message: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let bytes = this.read().await.random_bytes(len).map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(bytes.as_slice()).into())
+                let bundle = this
+                    .write()
+                    .await
+                    .process_raw_welcome_message_replacing(welcome_message.into(), custom_configuration.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let bundle: WelcomeBundle = bundle.into();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
         )
     }
 
-    #[allow(rustdoc::broken_intra_doc_links)]
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Option<MemberAddedMessages>>`]
     ///
-    /// see [mls_crypto_provider::MlsCryptoProvider::reseed]
-    pub fn reseed_rng(&self, seed: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::add_members_to_conversation]
+    pub fn add_clients_to_conversation(
+        &self,
+        conversation_id: ConversationId,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
+
         future_to_promise(
             async move {
-                let seed = EntropySeed::try_from_slice(&seed)
-                    .map_err(CryptoError::from)
-                    .map_err(CoreCryptoError::from)?;
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
 
-                this.write().await.provider_mut().reseed(Some(seed));
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let mut central = this.write().await;
+                let commit = central
+                    .add_members_to_conversation(&conversation_id, key_packages)
+                    .await?;
+                let commit: MemberAddedMessages = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::try_new]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_init(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::add_claimed_members_to_conversation]
+    pub fn add_claimed_clients_to_conversation(
+        &self,
+        conversation_id: ConversationId,
+        claim_response: Box<[u8]>,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_init().await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let claimed_key_packages =
+                    ClaimedKeyPackageBundle::from_claim_response(&claim_response).map_err(CoreCryptoError::from)?;
+
+                let mut central = this.write().await;
+                let commit = central
+                    .add_claimed_members_to_conversation(&conversation_id, claimed_key_packages)
+                    .await?;
+                let commit: MemberAddedMessages = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Option<js_sys::Uint8Array>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_from_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_from_prekey(&self, session_id: String, prekey: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::remove_members_from_conversation]
+    pub fn remove_clients_from_conversation(
+        &self,
+        conversation_id: ConversationId,
+        clients: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_from_prekey(&session_id, &prekey).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let clients = clients
+                    .iter()
+                    .cloned()
+                    .map(|c| c.to_vec().into())
+                    .collect::<Vec<ClientId>>();
+
+                let mut central = this.write().await;
+                let commit = central
+                    .remove_members_from_conversation(&conversation_id, &clients)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                let commit: CommitBundle = commit.try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Option<IdempotentCommitBundle>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_from_message]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_from_message(&self, session_id: String, envelope: Box<[u8]>) -> Promise {
+    /// `members` are the key packages of the clients to add or the ids of the clients to remove, depending on
+    /// `operation`
+    ///
+    /// see [core_crypto::mls::MlsCentral::commit_with_operation_key]
+    pub fn commit_with_operation_key(
+        &self,
+        conversation_id: ConversationId,
+        operation_key: Box<[u8]>,
+        operation: CommitOperationType,
+        members: Option<Box<[Uint8Array]>>,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let (_, payload) = this.write().await.proteus_session_from_message(&session_id, &envelope).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(payload.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
+                let members = members.unwrap_or_default();
+                let operation = match operation {
+                    CommitOperationType::AddClients => MlsCommitOperation::AddMembers(
+                        members
+                            .iter()
+                            .map(|kp| {
+                                KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice()).map_err(|e| {
+                                    CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into())))
+                                })
+                            })
+                            .collect::<CoreCryptoResult<Vec<_>>>()?,
+                    ),
+                    CommitOperationType::RemoveClients => {
+                        MlsCommitOperation::RemoveMembers(members.iter().map(|c| c.to_vec().into()).collect())
+                    }
+                    CommitOperationType::UpdateKeyingMaterial => MlsCommitOperation::UpdateKeyingMaterial,
+                    CommitOperationType::CommitPendingProposals => MlsCommitOperation::CommitPendingProposals,
+                };
+
+                let bundle = this
+                    .write()
+                    .await
+                    .commit_with_operation_key(&conversation_id, &operation_key, operation)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .map(IdempotentCommitBundle::from);
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
-    ///
-    /// **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+    /// Returns: [`WasmCryptoResult<CommitPreview>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_save]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_save(&self, session_id: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::preview_commit]
+    pub fn preview_commit(
+        &self,
+        conversation_id: ConversationId,
+        key_packages: Box<[Uint8Array]>,
+        clients_to_remove: Box<[Uint8Array]>,
+        update: bool,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_save(&session_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let mut intents = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map(|kp| MlsProposal::Add(kp.into()))
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
+                intents.extend(clients_to_remove.iter().map(|c| MlsProposal::Remove(c.to_vec().into())));
+                if update {
+                    intents.push(MlsProposal::Update);
+                }
+
+                let mut central = this.write().await;
+                let preview = central
+                    .preview_commit(&conversation_id, intents)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&CommitPreview::from(preview))?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_delete]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_delete(&self, session_id: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_conversation_admins]
+    #[allow(clippy::boxed_local)]
+    pub fn set_conversation_admins(&self, conversation_id: ConversationId, admins: Box<[Uint8Array]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+        let admins = admins
+            .iter()
+            .cloned()
+            .map(|c| c.to_vec().into())
+            .collect::<Vec<ClientId>>();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_delete(&session_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let commit: CommitBundle = this
+                    .write()
+                    .await
+                    .set_conversation_admins(&conversation_id, admins)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<bool>`]
+    /// Returns: [`WasmCryptoResult<Vec<ClientId>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_exists]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_exists(&self, session_id: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversation_admins]
+    pub fn conversation_admins(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let exists = this.write().await.proteus_session_exists(&session_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::from_bool(exists))
-                } or throw WasmCryptoResult<_> }
+                let admins = this
+                    .write()
+                    .await
+                    .conversation_admins(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let js_admins = js_sys::Array::from_iter(
+                    admins
+                        .into_iter()
+                        .map(|id| js_sys::Uint8Array::from(id.as_slice()))
+                        .map(JsValue::from),
+                );
+                WasmCryptoResult::Ok(js_admins.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::decrypt]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_decrypt(&self, session_id: String, ciphertext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
+    pub fn mark_conversation_as_child_of(&self, child_id: Box<[u8]>, parent_id: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
 
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let cleartext = this.write().await.proteus_decrypt(&session_id, &ciphertext).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(cleartext.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
+                let mut central = this.write().await;
+                central
+                    .mark_conversation_as_child_of(&child_id.into(), &parent_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<ConversationId>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::encrypt]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_encrypt(&self, session_id: String, plaintext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::create_sub_conversation]
+    pub fn create_sub_conversation(&self, parent_id: ConversationId, purpose: String) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let encrypted = this.write().await.proteus_encrypt(&session_id, &plaintext).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(encrypted.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
+                let id = this
+                    .write()
+                    .await
+                    .create_sub_conversation(&parent_id, &purpose)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(id.as_slice()).into())
+            }
+            .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Map<string, Uint8Array>>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<js_sys::Uint8Array>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::encrypt_batched]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_encrypt_batched(&self, sessions: Box<[js_sys::JsString]>, plaintext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::sub_conversations]
+    pub fn sub_conversations(&self, parent_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let session_ids: Vec<String> = sessions.iter().map(String::from).collect();
-                    let batch = this.write().await.proteus_encrypt_batched(session_ids.as_slice(), &plaintext).await.map_err(CoreCryptoError::from)?;
-                    let js_obj = js_sys::Map::new();
-                    for (key, payload) in batch.into_iter() {
-                        js_obj.set(&js_sys::JsString::from(key).into(), &Uint8Array::from(payload.as_slice()));
-                    }
-                    WasmCryptoResult::Ok(js_obj.into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
+                let ids = this
+                    .read()
+                    .await
+                    .sub_conversations(&parent_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                let js_ids = js_sys::Array::from_iter(
+                    ids.into_iter()
+                        .map(|id| js_sys::Uint8Array::from(id.as_slice()))
+                        .map(JsValue::from),
+                );
+
+                WasmCryptoResult::Ok(js_ids.into())
+            }
+            .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<Option<CommitBundle>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_new_prekey(&self, prekey_id: u16) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::sync_sub_conversation_members]
+    pub fn sync_sub_conversation_members(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let prekey_raw = this.read().await.proteus_new_prekey(prekey_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(prekey_raw.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
-        )
-    }
-
-    /// Returns: [`WasmCryptoResult<ProteusAutoPrekeyBundle>`]
+                let mut central = this.write().await;
+                let commit: Option<CommitBundle> = central
+                    .sync_sub_conversation_members(&conversation_id)
+                    .await?
+                    .map(|c| c.try_into())
+                    .transpose()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<ConversationWipeReport>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_new_prekey_auto(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::leave_sub_conversation]
+    pub fn leave_sub_conversation(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let (id, pkb) = this.read().await.proteus_new_prekey_auto().await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(ProteusAutoPrekeyBundle { id, pkb }.into())
-                } or throw WasmCryptoResult<_> }
+                let mut central = this.write().await;
+                let report: ConversationWipeReport = central
+                    .leave_sub_conversation(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into();
+                WasmCryptoResult::Ok(report.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<ConversationSummary>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey]
-    pub fn proteus_last_resort_prekey(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::list_conversations]
+    pub fn list_conversations(&self) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                let summaries = this
+                    .read()
+                    .await
+                    .list_conversations()
+                    .await
+                    .map_err(CoreCryptoError::from)?;
 
-        future_to_promise(async move {
-            proteus_impl! { errcode_dest => {
-                let last_resort_pkbundle = this.read().await.proteus_last_resort_prekey().await.map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(last_resort_pkbundle.as_slice()).into())
-            } or throw WasmCryptoResult<_> }
-        }.err_into())
-    }
+                let js_summaries =
+                    js_sys::Array::from_iter(summaries.into_iter().map(ConversationSummary::from).map(JsValue::from));
 
-    /// Returns: [`WasmCryptoResult<u16>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey_id]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_last_resort_prekey_id() -> WasmCryptoResult<u16> {
-        proteus_impl! {{
-            Ok(core_crypto::CoreCrypto::proteus_last_resort_prekey_id())
-        } or throw WasmCryptoResult<_> }
+                WasmCryptoResult::Ok(js_summaries.into())
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<String>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<js_sys::Uint8Array>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint(&self) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
+    /// see [core_crypto::mls::MlsCentral::conversations_needing_key_update]
+    pub fn conversations_needing_key_update(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let ids = this
+                    .read()
+                    .await
+                    .conversations_needing_key_update()
+                    .await
+                    .map_err(CoreCryptoError::from)?;
 
-        proteus_impl! { errcode_dest => {
-            self.inner.read().await.proteus_fingerprint().map_err(CoreCryptoError::from).map(Into::into)
-        } or throw WasmCryptoResult<_> }
+                let js_ids = js_sys::Array::from_iter(
+                    ids.into_iter()
+                        .map(|id| js_sys::Uint8Array::from(id.as_slice()))
+                        .map(JsValue::from),
+                );
+
+                WasmCryptoResult::Ok(js_ids.into())
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<String>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Map<String, CommitBundle>>`], keyed by the hex encoded conversation id
     ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint_local]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint_local(&self, session_id: String) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        proteus_impl! { errcode_dest => {
-            self.inner
-                .write()
-                .await
-                .proteus_fingerprint_local(&session_id)
-                .await
-                .map_err(CoreCryptoError::from)
-                .map(Into::into)
-        } or throw WasmCryptoResult<_> }
+    /// see [core_crypto::mls::MlsCentral::update_all_stale]
+    pub fn update_all_stale(&self, max_age_seconds: u32) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let commits = this
+                    .write()
+                    .await
+                    .update_all_stale(std::time::Duration::from_secs(max_age_seconds.into()))
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let js_commits = js_sys::Map::new();
+                for (id, commit) in commits {
+                    let commit: CommitBundle = commit.try_into()?;
+                    js_commits.set(
+                        &js_sys::JsString::from(hex::encode(id)).into(),
+                        &serde_wasm_bindgen::to_value(&commit)?,
+                    );
+                }
+                WasmCryptoResult::Ok(js_commits.into())
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<String>`]
+    /// Returns: [`WasmCryptoResult<Vec<MaintenanceItem>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint_remote]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint_remote(&self, session_id: String) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        proteus_impl! { errcode_dest => {
-            self.inner.write().await.proteus_fingerprint_remote(&session_id).await
-                .map_err(CoreCryptoError::from).map(Into::into)
-        } or throw WasmCryptoResult<_> }
+    /// see [core_crypto::mls::MlsCentral::pending_maintenance_tasks]
+    pub fn pending_maintenance_tasks(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let items = this
+                    .read()
+                    .await
+                    .pending_maintenance_tasks()
+                    .await?
+                    .into_iter()
+                    .map(MaintenanceItem::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&items)?)
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<String>`]
+    /// Returns: [`WasmCryptoResult<Option<CommitBundle>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCproteus_fingerprint_prekeybundle]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_fingerprint_prekeybundle(prekey: Box<[u8]>) -> WasmCryptoResult<String> {
-        proteus_impl!({
-            core_crypto::proteus::ProteusCentral::fingerprint_prekeybundle(&prekey)
-                .map_err(Into::into).map(Into::into)
-        } or throw WasmCryptoResult<_>)
+    /// see [core_crypto::mls::MlsCentral::run_maintenance]
+    pub fn run_maintenance(&self, kind: u8, conversation_id: Option<Box<[u8]>>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let task = match (kind, conversation_id) {
+                    (1, _) => MlsMaintenanceTask::PruneExpiredKeyPackages,
+                    (2, Some(id)) => MlsMaintenanceTask::UpdateKeyingMaterial(id.into()),
+                    (3, _) => MlsMaintenanceTask::ExpireBufferedMessages,
+                    (4, _) => MlsMaintenanceTask::VacuumKeystore,
+                    (5, _) => MlsMaintenanceTask::PurgeExpiredEntities,
+                    _ => return Err(CryptoError::ConsumerError.into()),
+                };
+                let commit: Option<CommitBundle> = this
+                    .write()
+                    .await
+                    .run_maintenance(&task)
+                    .await?
+                    .map(|c| c.try_into())
+                    .transpose()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::cryptobox_migrate]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_cryptobox_migrate(&self, path: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::update_keying_material]
+    pub fn update_keying_material(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.read().await.proteus_cryptobox_migrate(&path).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let mut central = this.write().await;
+                let commit = central
+                    .update_keying_material(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                let commit: CommitBundle = commit.try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<u32>`]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_last_error_code(&self) -> Promise {
-        let errcode = self.proteus_last_error_code.clone();
+    /// see [core_crypto::mls::MlsCentral::commit_pending_proposals]
+    pub fn commit_pending_proposals(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+
         future_to_promise(
             async move {
-                proteus_impl! {{
-                    let prev_value: u32 = *(errcode.read().await);
-                    let mut errcode_val = errcode.write().await;
-                    *errcode_val = 0;
+                let mut central = this.write().await;
+                let commit: Option<CommitBundle> = central
+                    .commit_pending_proposals(&conversation_id)
+                    .await?
+                    .map(|c| c.try_into())
+                    .transpose()?;
 
-                    WasmCryptoResult::Ok(prev_value.into())
-                } or throw WasmCryptoResult<_> }
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    /// Returns: [`WasmCryptoResult<ConversationWipeReport>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::export_secret_key]
-    pub fn export_secret_key(&self, conversation_id: ConversationId, key_length: usize) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
+    pub fn wipe_conversation(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let key = this
+                let mut central = this.write().await;
+                let report: ConversationWipeReport = central
+                    .wipe_conversation(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into();
+                WasmCryptoResult::Ok(report.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<DecryptedMessage>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::decrypt_message]
+    pub fn decrypt_message(&self, conversation_id: ConversationId, payload: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let raw_decrypted_message = this
                     .write()
                     .await
-                    .export_secret_key(&conversation_id.to_vec(), key_length)
+                    .decrypt_message(&conversation_id, payload)
                     .await
                     .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(key.as_slice()).into())
+
+                let decrypted_message = DecryptedMessage::try_from(raw_decrypted_message)?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&decrypted_message)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::get_external_sender]
-    pub fn get_external_sender(&self, id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::encrypt_message]
+    pub fn encrypt_message(&self, conversation_id: ConversationId, message: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let ext_sender = this
+                let ciphertext = this
                     .write()
                     .await
-                    .get_external_sender(&id.to_vec())
+                    .encrypt_message(&conversation_id, message)
                     .await
+                    .map(|ciphertext| Uint8Array::from(ciphertext.as_slice()))
                     .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(ext_sender.as_slice()).into())
+
+                WasmCryptoResult::Ok(ciphertext.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Box<[js_sys::Uint8Array]>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<js_sys::Uint8Array>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::get_client_ids]
-    pub fn get_client_ids(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::take_fenced_messages]
+    pub fn take_fenced_messages(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let clients = this
+                let messages = this
                     .write()
                     .await
-                    .get_client_ids(&conversation_id.to_vec())
+                    .take_fenced_messages(&conversation_id)
                     .await
                     .map_err(CoreCryptoError::from)?;
-                let clients = js_sys::Array::from_iter(
-                    clients
+                let js_messages = js_sys::Array::from_iter(
+                    messages
                         .into_iter()
-                        .map(|client| Uint8Array::from(client.as_slice()))
+                        .map(|m| js_sys::Uint8Array::from(m.as_slice()))
                         .map(JsValue::from),
                 );
-                WasmCryptoResult::Ok(clients.into())
+
+                WasmCryptoResult::Ok(js_messages.into())
             }
             .err_into(),
         )
     }
-}
 
-// End-to-end identity methods
-#[wasm_bindgen]
-impl CoreCrypto {
-    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::e2ei_new_enrollment]
-    pub fn e2ei_new_enrollment(
-        &self,
-        client_id: String,
-        display_name: String,
-        handle: String,
-        team: Option<String>,
-        expiry_sec: u32,
-        ciphersuite: Ciphersuite,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::new_add_proposal]
+    pub fn new_add_proposal(&self, conversation_id: ConversationId, keypackage: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let enrollment = this
-                    .e2ei_new_enrollment(
-                        client_id.into_bytes().into(),
-                        display_name,
-                        handle,
-                        team,
-                        expiry_sec,
-                        ciphersuite.into(),
-                    )
-                    .map(async_lock::RwLock::new)
-                    .map(std::sync::Arc::new)
-                    .map(E2eiEnrollment)
+                let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_ref())
+                    .map_err(MlsError::from)
+                    .map_err(CryptoError::from)
                     .map_err(CoreCryptoError::from)?;
 
-                WasmCryptoResult::Ok(enrollment.into())
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_add_proposal(&conversation_id, kp.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::e2ei_new_activation_enrollment]
-    pub fn e2ei_new_activation_enrollment(
-        &self,
-        display_name: String,
-        handle: String,
-        team: Option<String>,
-        expiry_sec: u32,
-        ciphersuite: Ciphersuite,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::new_update_proposal]
+    pub fn new_update_proposal(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let enrollment = this
-                    .e2ei_new_activation_enrollment(display_name, handle, team, expiry_sec, ciphersuite.into())
-                    .map(async_lock::RwLock::new)
-                    .map(std::sync::Arc::new)
-                    .map(E2eiEnrollment)
-                    .map_err(CoreCryptoError::from)?;
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_update_proposal(&conversation_id)
+                    .await?
+                    .try_into()?;
 
-                WasmCryptoResult::Ok(enrollment.into())
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::e2ei_new_rotate_enrollment]
-    pub fn e2ei_new_rotate_enrollment(
-        &self,
-        display_name: Option<String>,
-        handle: Option<String>,
-        team: Option<String>,
-        expiry_sec: u32,
-        ciphersuite: Ciphersuite,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::new_remove_proposal]
+    pub fn new_remove_proposal(&self, conversation_id: ConversationId, client_id: FfiClientId) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let enrollment = this
-                    .e2ei_new_rotate_enrollment(display_name, handle, team, expiry_sec, ciphersuite.into())
+                let proposal: ProposalBundle = this
+                    .write()
                     .await
-                    .map(async_lock::RwLock::new)
-                    .map(std::sync::Arc::new)
-                    .map(E2eiEnrollment)
-                    .map_err(CoreCryptoError::from)?;
+                    .new_remove_proposal(&conversation_id, client_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
 
-                WasmCryptoResult::Ok(enrollment.into())
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::mls::MlsCentral::e2ei_register_acme_ca]
-    pub async fn e2ei_register_acme_ca(&self, trust_anchor_pem: String) -> Promise {
+    /// Returns: [`WasmCryptoResult<ProposalBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_external_psk_proposal]
+    pub fn new_external_psk_proposal(&self, conversation_id: ConversationId, psk_id: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                this.e2ei_register_acme_ca(trust_anchor_pem).await?;
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_external_psk_proposal(&conversation_id, psk_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::mls::MlsCentral::e2ei_register_intermediate_ca]
-    pub async fn e2ei_register_intermediate_ca(&self, cert_pem: String) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::add_external_psk]
+    pub fn add_external_psk(&self, conversation_id: ConversationId, psk_id: Box<[u8]>, secret: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let crls = this.e2ei_register_intermediate_ca_pem(cert_pem).await?;
-
-                let crls = if let Some(crls) = crls {
-                    js_sys::Array::from_iter(crls.into_iter().map(JsValue::from))
-                } else {
-                    js_sys::Array::new()
-                };
-                WasmCryptoResult::Ok(crls.into())
+                this.write()
+                    .await
+                    .add_external_psk(&conversation_id, psk_id.into(), secret.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::mls::MlsCentral::e2ei_register_crl]
-    pub async fn e2ei_register_crl(&self, crl_dp: String, crl_der: Box<[u8]>) -> Promise {
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_external_add_proposal]
+    pub fn new_external_add_proposal(
+        &self,
+        conversation_id: ConversationId,
+        epoch: u32,
+        ciphersuite: Ciphersuite,
+        credential_type: CredentialType,
+    ) -> Promise {
         let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let cc_registration = this.e2ei_register_crl(crl_dp, crl_der.to_vec()).await?;
-                let registration: CrlRegistration = cc_registration.into();
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&registration)?)
+                let proposal_bytes = this
+                    .write()
+                    .await
+                    .new_external_add_proposal(
+                        conversation_id,
+                        u64::from(epoch).into(),
+                        ciphersuite.into(),
+                        credential_type.into(),
+                    )
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .to_bytes()
+                    .map(|bytes| Uint8Array::from(bytes.as_slice()))
+                    .map_err(MlsError::from)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(proposal_bytes.into())
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::e2ei_mls_init_only]
-    pub fn e2ei_mls_init_only(
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::join_by_external_commit], or
+    /// [core_crypto::mls::MlsCentral::join_by_external_commit_pinned] with `expected_ciphersuite`
+    pub fn join_by_external_commit(
         &self,
-        enrollment: E2eiEnrollment,
-        certificate_chain: String,
-        nb_key_package: Option<u32>,
+        group_info: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+        credential_type: CredentialType,
+        expected_ciphersuite: Option<Ciphersuite>,
     ) -> Promise {
         let this = self.inner.clone();
+        let expected_ciphersuite = expected_ciphersuite.map(CiphersuiteName::from);
         future_to_promise(
             async move {
-                let mut this = this.write().await;
-                let nb_key_package = nb_key_package
-                    .map(usize::try_from)
-                    .transpose()
-                    .map_err(CryptoError::from)?;
-
-                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
-                    .map_err(|_| CryptoError::LockPoisonError)?
-                    .into_inner();
-                let crls = this
-                    .e2ei_mls_init_only(enrollment, certificate_chain, nb_key_package)
-                    .await?;
-
-                let crls = if let Some(crls) = crls {
-                    js_sys::Array::from_iter(crls.into_iter().map(JsValue::from))
+                let mut central = this.write().await;
+                let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+
+                let result: ConversationInitBundle = if let Some(expected_ciphersuite) = expected_ciphersuite {
+                    central
+                        .join_by_external_commit_pinned(
+                            group_info,
+                            custom_configuration.into(),
+                            credential_type.into(),
+                            expected_ciphersuite.into(),
+                        )
+                        .await
                 } else {
-                    js_sys::Array::new()
-                };
-                WasmCryptoResult::Ok(crls.into())
+                    central
+                        .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+                        .await
+                }
+                .map_err(CoreCryptoError::from)?
+                .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::e2ei_rotate_all]
-    pub fn e2ei_rotate_all(
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::join_from_group_info]
+    pub fn join_from_group_info(
         &self,
-        enrollment: E2eiEnrollment,
-        certificate_chain: String,
-        new_key_packages_count: u32,
+        group_info: Box<[u8]>,
+        ratchet_tree: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+        credential_type: CredentialType,
     ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let mut this = this.write().await;
-
-                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
-                    .map_err(|_| CryptoError::LockPoisonError)?
-                    .into_inner();
+                let mut central = this.write().await;
+                let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+                let ratchet_tree = RatchetTreeIn::tls_deserialize(&mut ratchet_tree.as_ref())
+                    .map_err(MlsError::from)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
 
-                let rotate_bundle: RotateBundle = this
-                    .e2ei_rotate_all(enrollment, certificate_chain, new_key_packages_count as usize)
-                    .await?
+                let result: ConversationInitBundle = central
+                    .join_from_group_info(
+                        group_info,
+                        ratchet_tree,
+                        custom_configuration.into(),
+                        credential_type.into(),
+                    )
+                    .await
+                    .map_err(CoreCryptoError::from)?
                     .try_into()?;
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&rotate_bundle)?)
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::e2ei_enrollment_stash]
-    pub fn e2ei_enrollment_stash(&self, enrollment: E2eiEnrollment) -> Promise {
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::recover_conversation]
+    pub fn recover_conversation(&self, conversation_id: ConversationId, group_info: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let this = this.write().await;
-                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
-                    .map_err(|_| CryptoError::LockPoisonError)?
-                    .into_inner();
-                let handle = this.e2ei_enrollment_stash(enrollment).await?;
-                WasmCryptoResult::Ok(Uint8Array::from(handle.as_slice()).into())
+                let mut central = this.write().await;
+                let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+
+                let result: ConversationInitBundle = central
+                    .recover_conversation(&conversation_id, group_info)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::e2ei_enrollment_stash_pop]
-    pub fn e2ei_enrollment_stash_pop(&self, handle: Box<[u8]>) -> Promise {
+    /// Returns: [`WasmCryptoResult<Vec<RejoinResult>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::rejoin_all_from_group_infos]. `group_infos` are the ones of the
+    /// conversations in `conversation_ids`, in the same order
+    pub fn rejoin_all_from_group_infos(
+        &self,
+        conversation_ids: Box<[Uint8Array]>,
+        group_infos: Box<[Uint8Array]>,
+        custom_configuration: CustomConfiguration,
+        credential_type: CredentialType,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let enrollment = this
-                    .e2ei_enrollment_stash_pop(handle.to_vec())
+                if conversation_ids.len() != group_infos.len() {
+                    return Err(CryptoError::ConsumerError.into());
+                }
+                let mut central = this.write().await;
+                let group_infos = conversation_ids
+                    .iter()
+                    .zip(group_infos.iter())
+                    .map(|(id, gi)| {
+                        let group_info = central.message_limits().deserialize_group_info(&gi.to_vec())?;
+                        WasmCryptoResult::Ok((id.to_vec(), group_info))
+                    })
+                    .collect::<WasmCryptoResult<Vec<_>>>()?;
+
+                let results = central
+                    .rejoin_all_from_group_infos(group_infos, custom_configuration.into(), credential_type.into())
                     .await
-                    .map(async_lock::RwLock::new)
-                    .map(std::sync::Arc::new)
-                    .map(E2eiEnrollment)
-                    .map_err(CoreCryptoError::from)?;
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(|(conversation_id, result)| {
+                        let (bundle, error_code) = match result {
+                            Ok(bundle) => (Some(bundle.try_into()?), None),
+                            Err(e) => (None, Some(e.error_code() as u16)),
+                        };
+                        WasmCryptoResult::Ok(RejoinResult {
+                            conversation_id,
+                            bundle,
+                            error_code,
+                        })
+                    })
+                    .collect::<WasmCryptoResult<Vec<_>>>()?;
 
-                WasmCryptoResult::Ok(enrollment.into())
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&results)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<u8>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::e2ei_conversation_state]
-    pub fn e2ei_conversation_state(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::merge_pending_group_from_external_commit]
+    pub fn merge_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let state: E2eiConversationState = this
+                if let Some(decrypted_messages) = this
                     .write()
                     .await
-                    .e2ei_conversation_state(&conversation_id)
+                    .merge_pending_group_from_external_commit(&conversation_id)
                     .await
                     .map_err(CoreCryptoError::from)?
-                    .into();
-                WasmCryptoResult::Ok((state as u8).into())
+                {
+                    let messages = decrypted_messages
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
+
+                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
+                }
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<bool>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::e2ei_is_enabled]
-    pub fn e2ei_is_enabled(&self, ciphersuite: Ciphersuite) -> Promise {
-        let sc = MlsCiphersuite::from(ciphersuite).signature_algorithm();
+    /// see [core_crypto::mls::MlsCentral::clear_pending_group_from_external_commit]
+    pub fn clear_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let is_enabled = this
-                    .write()
+                this.write()
                     .await
-                    .e2ei_is_enabled(sc)
-                    .map_err(CoreCryptoError::from)?
-                    .into();
-                WasmCryptoResult::Ok(is_enabled)
+                    .clear_pending_group_from_external_commit(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<Vec<WireIdentity>>`]
-    ///
-    /// see [core_crypto::mls::MlsCentral::get_device_identities]
-    pub fn get_device_identities(&self, conversation_id: ConversationId, device_ids: Box<[Uint8Array]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::commit_accepted]
+    pub fn commit_accepted(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let device_ids = device_ids.iter().map(|c| c.to_vec().into()).collect::<Vec<ClientId>>();
-                let identities = this
+                if let Some(decrypted_messages) = this
                     .write()
                     .await
-                    .get_device_identities(&conversation_id, &device_ids[..])
+                    .commit_accepted(&conversation_id)
                     .await
                     .map_err(CoreCryptoError::from)?
-                    .into_iter()
-                    .map(Into::into)
-                    .collect::<Vec<WireIdentity>>();
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&identities)?)
+                {
+                    let messages = decrypted_messages
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
+
+                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
+                }
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<HashMap<String, Vec<WireIdentity>>>`]
+    /// Returns: [`WasmCryptoResult<Vec<ProposalInfo>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::get_user_identities]
-    pub fn get_user_identities(&self, conversation_id: ConversationId, user_ids: Box<[String]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::pending_proposals]
+    pub fn pending_proposals(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let identities = this
+                let infos = this
                     .write()
                     .await
-                    .get_user_identities(&conversation_id, user_ids.deref())
+                    .pending_proposals(&conversation_id)
                     .await
                     .map_err(CoreCryptoError::from)?
                     .into_iter()
-                    .map(|(k, v)| (k, v.into_iter().map(Into::into).collect()))
-                    .collect::<HashMap<String, Vec<WireIdentity>>>();
-                let js_obj = js_sys::Map::new();
-                for (uid, identities) in identities.into_iter() {
-                    let uid = js_sys::JsString::from(uid).into();
-                    let identities = serde_wasm_bindgen::to_value(&identities)?;
-                    js_obj.set(&uid, &identities);
-                }
-                WasmCryptoResult::Ok(js_obj.into())
+                    .map(ProposalInfo::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&infos)?)
             }
             .err_into(),
         )
     }
 
-    #[allow(clippy::boxed_local)]
-    /// Returns: [`WasmCryptoResult<u8>`]
-    ///
-    /// see [core_crypto::mls::MlsCentral::get_credential_in_use]
-    pub fn get_credential_in_use(&self, group_info: Box<[u8]>, credential_type: CredentialType) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::clear_pending_proposal]
+    pub fn clear_pending_proposal(&self, conversation_id: ConversationId, proposal_ref: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
-                    .map_err(CoreCryptoError::from)?;
-
-                let state: E2eiConversationState = this
-                    .write()
+                this.write()
                     .await
-                    .get_credential_in_use(group_info, credential_type.into())
+                    .clear_pending_proposal(&conversation_id, proposal_ref.into_vec().into())
                     .await
-                    .map(Into::into)
                     .map_err(CoreCryptoError::from)?;
 
-                WasmCryptoResult::Ok((state as u8).into())
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
-}
-
-#[derive(Debug)]
-#[wasm_bindgen(js_name = FfiWireE2EIdentity)]
-#[repr(transparent)]
-pub struct E2eiEnrollment(std::sync::Arc<async_lock::RwLock<core_crypto::prelude::E2eiEnrollment>>);
 
-#[wasm_bindgen(js_class = FfiWireE2EIdentity)]
-impl E2eiEnrollment {
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::directory_response]
-    pub fn directory_response(&mut self, directory: Vec<u8>) -> Promise {
-        let this = self.0.clone();
+    /// see [core_crypto::mls::MlsCentral::clear_pending_commit]
+    pub fn clear_pending_commit(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                let mut this = this.write().await;
-                let directory: AcmeDirectory = this.directory_response(directory)?.into();
-                WasmCryptoResult::Ok(directory.into())
+                this.write()
+                    .await
+                    .clear_pending_commit(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_account_request]
-    pub fn new_account_request(&self, previous_nonce: String) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::random_bytes]
+    pub fn random_bytes(&self, len: usize) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let new_account: Vec<u8> = this.new_account_request(previous_nonce)?;
-                WasmCryptoResult::Ok(Uint8Array::from(new_account.as_slice()).into())
+                let bytes = this.read().await.random_bytes(len).map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(bytes.as_slice()).into())
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_account_response]
-    pub fn new_account_response(&mut self, account: Uint8Array) -> Promise {
-        let this = self.0.clone();
+    #[allow(rustdoc::broken_intra_doc_links)]
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [mls_crypto_provider::MlsCryptoProvider::reseed]
+    pub fn reseed_rng(&self, seed: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                let mut this = this.write().await;
-                this.new_account_response(account.to_vec())?;
+                let seed = EntropySeed::try_from_slice(&seed)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
+
+                this.write().await.provider_mut().reseed(Some(seed));
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_order_request]
-    pub fn new_order_request(&self, previous_nonce: String) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::try_new]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_init(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let new_order = this.new_order_request(previous_nonce)?;
-                WasmCryptoResult::Ok(Uint8Array::from(new_order.as_slice()).into())
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_init().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_order_response]
-    pub fn new_order_response(&self, order: Uint8Array) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_from_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_from_prekey(&self, session_id: String, prekey: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let order: NewAcmeOrder = this.new_order_response(order.to_vec())?.into();
-                WasmCryptoResult::Ok(order.into())
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_session_from_prekey(&session_id, &prekey).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_authz_request]
-    pub fn new_authz_request(&self, url: String, previous_nonce: String) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_from_message]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_from_message(&self, session_id: String, envelope: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let new_authz = this.new_authz_request(url, previous_nonce)?;
-                WasmCryptoResult::Ok(Uint8Array::from(new_authz.as_slice()).into())
+                proteus_impl! { errcode_dest => {
+                    let (_, payload) = this.write().await.proteus_session_from_message(&session_id, &envelope).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(payload.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_authz_response]
-    pub fn new_authz_response(&mut self, authz: Uint8Array) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_save]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_save(&self, session_id: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let mut this = this.write().await;
-                let authz: NewAcmeAuthz = this.new_authz_response(authz.to_vec())?.into();
-                WasmCryptoResult::Ok(authz.into())
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_session_save(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::create_dpop_token]
-    pub fn create_dpop_token(&self, expiry_secs: u32, backend_nonce: String) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_delete]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_delete(&self, session_id: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let dpop_token = this.create_dpop_token(expiry_secs, backend_nonce)?;
-                WasmCryptoResult::Ok(Uint8Array::from(dpop_token.as_bytes()).into())
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_session_delete(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_dpop_challenge_request]
-    pub fn new_dpop_challenge_request(&self, access_token: String, previous_nonce: String) -> Promise {
-        let this = self.0.clone();
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::CoreCrypto::proteus_reload_sessions]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_reload_sessions(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
         future_to_promise(
             async move {
-                let this = this.read().await;
-                let chall = this.new_dpop_challenge_request(access_token, previous_nonce)?;
-                WasmCryptoResult::Ok(Uint8Array::from(chall.as_slice()).into())
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_reload_sessions().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
             }
             .err_into(),
         )
     }
 
-    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_dpop_challenge_response]
-    pub fn new_dpop_challenge_response(&self, challenge: Uint8Array) -> Promise {
-        let this = self.0.clone();
-        future_to_promise(
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::CoreCrypto::proteus_set_auto_persist]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_set_auto_persist(&self, enabled: bool) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_set_auto_persist(enabled).map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<String>>`]
+    ///
+    /// see [core_crypto::CoreCrypto::proteus_stale_sessions]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_stale_sessions(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let stale = this.read().await.proteus_stale_sessions().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&stale)?)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<bool>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_exists]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_exists(&self, session_id: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let exists = this.write().await.proteus_session_exists(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::from_bool(exists))
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::decrypt]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_decrypt(&self, session_id: String, ciphertext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let cleartext = this.write().await.proteus_decrypt(&session_id, &ciphertext).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(cleartext.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Array<js_sys::Uint8Array>>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::decrypt_batched]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_decrypt_batched(&self, session_id: String, ciphertexts: Box<[Uint8Array]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let ciphertexts = ciphertexts.iter().map(Uint8Array::to_vec).collect::<Vec<_>>();
+                    let cleartexts = this.write().await.proteus_decrypt_batched(&session_id, ciphertexts.as_slice()).await.map_err(CoreCryptoError::from)?;
+                    let js_array = js_sys::Array::new();
+                    for cleartext in cleartexts {
+                        js_array.push(&Uint8Array::from(cleartext.as_slice()));
+                    }
+                    WasmCryptoResult::Ok(js_array.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Map<string, Array<Uint8Array>>>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::decrypt_batched_multi]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_decrypt_batched_multi(&self, messages: js_sys::Map) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let mut batch = std::collections::HashMap::new();
+                    messages.for_each(&mut |ciphertexts, session_id| {
+                        if let Some(session_id) = session_id.as_string() {
+                            let ciphertexts = js_sys::Array::from(&ciphertexts)
+                                .iter()
+                                .map(|c| Uint8Array::new(&c).to_vec())
+                                .collect::<Vec<_>>();
+                            batch.insert(session_id, ciphertexts);
+                        }
+                    });
+
+                    let cleartexts = this.write().await.proteus_decrypt_batched_multi(&batch).await.map_err(CoreCryptoError::from)?;
+                    let js_obj = js_sys::Map::new();
+                    for (key, payloads) in cleartexts.into_iter() {
+                        let js_array = js_sys::Array::new();
+                        for payload in payloads {
+                            js_array.push(&Uint8Array::from(payload.as_slice()));
+                        }
+                        js_obj.set(&js_sys::JsString::from(key).into(), &js_array);
+                    }
+                    WasmCryptoResult::Ok(js_obj.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::encrypt]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_encrypt(&self, session_id: String, plaintext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let encrypted = this.write().await.proteus_encrypt(&session_id, &plaintext).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(encrypted.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Map<string, Uint8Array>>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::encrypt_batched]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_encrypt_batched(&self, sessions: Box<[js_sys::JsString]>, plaintext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let session_ids: Vec<String> = sessions.iter().map(String::from).collect();
+                    let batch = this.write().await.proteus_encrypt_batched(session_ids.as_slice(), &plaintext).await.map_err(CoreCryptoError::from)?;
+                    let js_obj = js_sys::Map::new();
+                    for (key, payload) in batch.into_iter() {
+                        js_obj.set(&js_sys::JsString::from(key).into(), &Uint8Array::from(payload.as_slice()));
+                    }
+                    WasmCryptoResult::Ok(js_obj.into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_new_prekey(&self, prekey_id: u16) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let prekey_raw = this.read().await.proteus_new_prekey(prekey_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(prekey_raw.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<ProteusAutoPrekeyBundle>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_new_prekey_auto(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let (id, pkb) = this.read().await.proteus_new_prekey_auto().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(ProteusAutoPrekeyBundle { id, pkb }.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey]
+    pub fn proteus_last_resort_prekey(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(async move {
+            proteus_impl! { errcode_dest => {
+                let last_resort_pkbundle = this.read().await.proteus_last_resort_prekey().await.map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(last_resort_pkbundle.as_slice()).into())
+            } or throw WasmCryptoResult<_> }
+        }.err_into())
+    }
+
+    /// Returns: [`WasmCryptoResult<u16>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey_id]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_last_resort_prekey_id() -> WasmCryptoResult<u16> {
+        proteus_impl! {{
+            Ok(core_crypto::CoreCrypto::proteus_last_resort_prekey_id())
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint(&self) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner.read().await.proteus_fingerprint().map_err(CoreCryptoError::from).map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<ProteusDiagnostics>`]
+    ///
+    /// see [core_crypto::CoreCrypto::proteus_diagnostics]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_diagnostics(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let diagnostics = this.read().await.proteus_diagnostics().await.map_err(CoreCryptoError::from)?;
+                    let diagnostics = ProteusDiagnostics {
+                        identity_fingerprint: diagnostics.identity_fingerprint,
+                        sessions: diagnostics.sessions.into_iter().map(Into::into).collect(),
+                    };
+                    WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&diagnostics)?)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint_local]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint_local(&self, session_id: String) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner
+                .write()
+                .await
+                .proteus_fingerprint_local(&session_id)
+                .await
+                .map_err(CoreCryptoError::from)
+                .map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint_remote]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint_remote(&self, session_id: String) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner.write().await.proteus_fingerprint_remote(&session_id).await
+                .map_err(CoreCryptoError::from).map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::CoreCrypto::proteus_fingerprint_qr]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint_qr(&self, session_id: String) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner.write().await.proteus_fingerprint_qr(&session_id).await
+                .map_err(CoreCryptoError::from).map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::CoreCrypto::generate_identity_binding]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn generate_identity_binding(
+        &self,
+        ciphersuite: Ciphersuite,
+        credential_type: CredentialType,
+    ) -> WasmCryptoResult<Uint8Array> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+
+        proteus_impl! { errcode_dest => {
+            let binding = self.inner.read().await
+                .generate_identity_binding(ciphersuite.into(), credential_type.into())
+                .and_then(|binding| binding.to_bytes())
+                .map_err(CoreCryptoError::from)?;
+            WasmCryptoResult::Ok(Uint8Array::from(binding.as_slice()))
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<IdentityBinding>`]
+    ///
+    /// see [core_crypto::CoreCrypto::verify_identity_binding]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn verify_identity_binding(&self, binding: Box<[u8]>) -> WasmCryptoResult<JsValue> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            let binding = core_crypto::identity_binding::IdentityBinding::try_from_bytes(&binding)
+                .map_err(CoreCryptoError::from)?;
+            self.inner.read().await.verify_identity_binding(&binding).map_err(CoreCryptoError::from)?;
+            WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&IdentityBinding::from(binding))?)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::CoreCrypto::sign_with_identity]. Signs with the Proteus identity when no ciphersuite is
+    /// given, the credential type is then ignored
+    pub async fn sign_with_identity(
+        &self,
+        data: Box<[u8]>,
+        ciphersuite: Option<Ciphersuite>,
+        credential_type: CredentialType,
+    ) -> WasmCryptoResult<Uint8Array> {
+        use core_crypto::identity_signature::IdentitySignatureScheme;
+        let scheme = match ciphersuite {
+            Some(ciphersuite) => {
+                let ciphersuite: CiphersuiteName = ciphersuite.into();
+                IdentitySignatureScheme::Mls(ciphersuite.into(), credential_type.into())
+            }
+            None => IdentitySignatureScheme::Proteus,
+        };
+        let signature = self
+            .inner
+            .read()
+            .await
+            .sign_with_identity(&data, scheme)
+            .and_then(|signature| signature.to_bytes())
+            .map_err(CoreCryptoError::from)?;
+        WasmCryptoResult::Ok(Uint8Array::from(signature.as_slice()))
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::CoreCrypto::verify_with_identity]
+    pub async fn verify_with_identity(
+        &self,
+        public_key: Box<[u8]>,
+        data: Box<[u8]>,
+        signature: Box<[u8]>,
+    ) -> WasmCryptoResult<()> {
+        let signature = core_crypto::identity_signature::IdentitySignature::try_from_bytes(&signature)
+            .map_err(CoreCryptoError::from)?;
+        self.inner
+            .read()
+            .await
+            .verify_with_identity(&public_key, &data, &signature)
+            .map_err(CoreCryptoError::from)?;
+        WasmCryptoResult::Ok(())
+    }
+
+    /// Returns: [`WasmCryptoResult<ProteusMigrationReport>`]
+    ///
+    /// see [core_crypto::CoreCrypto::migrate_proteus_conversation]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn migrate_proteus_conversation(
+        &self,
+        session_id: String,
+        conversation_id: ConversationId,
+        creator_credential_type: CredentialType,
+        config: ConversationConfiguration,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let report = this.write().await
+                        .migrate_proteus_conversation(&session_id, &conversation_id, creator_credential_type.into(), config.try_into()?)
+                        .await
+                        .map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(ProteusMigrationReport {
+                        session_id: report.session_id,
+                        conversation_id: report.conversation_id,
+                        remote_fingerprint: report.remote_fingerprint,
+                        conversation_created: report.conversation_created,
+                    }.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<ProteusMigrationResult>>`]
+    ///
+    /// see [core_crypto::CoreCrypto::migrate_proteus_conversations]. `conversation_ids` are the ones standing for
+    /// the sessions in `session_ids`, in the same order
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn migrate_proteus_conversations(
+        &self,
+        session_ids: Box<[js_sys::JsString]>,
+        conversation_ids: Box<[Uint8Array]>,
+        creator_credential_type: CredentialType,
+        config: ConversationConfiguration,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    if session_ids.len() != conversation_ids.len() {
+                        return Err(CryptoError::ConsumerError.into());
+                    }
+                    let migrations = session_ids
+                        .iter()
+                        .zip(conversation_ids.iter())
+                        .map(|(session_id, id)| (String::from(session_id), id.to_vec()))
+                        .collect();
+                    let results = this.write().await
+                        .migrate_proteus_conversations(migrations, creator_credential_type.into(), config.try_into()?)
+                        .await
+                        .map_err(CoreCryptoError::from)?
+                        .into_iter()
+                        .map(|(session_id, result)| {
+                            let (report, error_code) = match result {
+                                Ok(report) => (Some(ProteusMigrationReport {
+                                    session_id: report.session_id,
+                                    conversation_id: report.conversation_id,
+                                    remote_fingerprint: report.remote_fingerprint,
+                                    conversation_created: report.conversation_created,
+                                }), None),
+                                Err(e) => (None, Some(e.error_code() as u16)),
+                            };
+                            ProteusMigrationResult {
+                                session_id,
+                                report,
+                                error_code,
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&results)?)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCproteus_fingerprint_prekeybundle]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_fingerprint_prekeybundle(prekey: Box<[u8]>) -> WasmCryptoResult<String> {
+        proteus_impl!({
+            core_crypto::proteus::ProteusCentral::fingerprint_prekeybundle(&prekey)
+                .map_err(Into::into).map(Into::into)
+        } or throw WasmCryptoResult<_>)
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::cryptobox_migrate]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_cryptobox_migrate(&self, path: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.read().await.proteus_cryptobox_migrate(&path).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<u32>`]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_last_error_code(&self) -> Promise {
+        let errcode = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! {{
+                    let prev_value: u32 = *(errcode.read().await);
+                    let mut errcode_val = errcode.write().await;
+                    *errcode_val = 0;
+
+                    WasmCryptoResult::Ok(prev_value.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::export_secret_key]
+    pub fn export_secret_key(&self, conversation_id: ConversationId, key_length: usize) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let key = this
+                    .write()
+                    .await
+                    .export_secret_key(&conversation_id, key_length)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(key.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::epoch_authenticator]
+    pub fn epoch_authenticator(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let authenticator = this
+                    .write()
+                    .await
+                    .epoch_authenticator(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(authenticator.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_external_sender]
+    pub fn get_external_sender(&self, id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let ext_sender = this
+                    .write()
+                    .await
+                    .get_external_sender(&id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(ext_sender.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<ExternalSender>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::conversation_external_senders]
+    pub fn conversation_external_senders(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let senders = this
+                    .write()
+                    .await
+                    .conversation_external_senders(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(ExternalSender::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&senders)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Option<u32>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::verify_external_sender_signature]
+    pub fn verify_external_sender_signature(
+        &self,
+        conversation_id: ConversationId,
+        payload: Box<[u8]>,
+        signature: Box<[u8]>,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let index = this
+                    .write()
+                    .await
+                    .verify_external_sender_signature(&conversation_id, &payload, &signature)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(index.map(|i| JsValue::from(i as u32)).unwrap_or(JsValue::UNDEFINED))
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Box<[js_sys::Uint8Array]>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_client_ids]
+    pub fn get_client_ids(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let clients = this
+                    .write()
+                    .await
+                    .get_client_ids(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let clients = js_sys::Array::from_iter(
+                    clients
+                        .into_iter()
+                        .map(|client| Uint8Array::from(client.as_slice()))
+                        .map(JsValue::from),
+                );
+                WasmCryptoResult::Ok(clients.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::mls_fingerprint_qr]
+    pub fn mls_fingerprint_qr(&self, conversation_id: ConversationId, client_id: FfiClientId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let payload = this
+                    .write()
+                    .await
+                    .mls_fingerprint_qr(&conversation_id, &client_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(payload.into())
+            }
+            .err_into(),
+        )
+    }
+}
+
+// End-to-end identity methods
+#[wasm_bindgen]
+impl CoreCrypto {
+    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_new_enrollment]
+    pub fn e2ei_new_enrollment(
+        &self,
+        client_id: String,
+        display_name: String,
+        handle: String,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let enrollment = this
+                    .e2ei_new_enrollment(
+                        client_id.into_bytes().into(),
+                        display_name,
+                        handle,
+                        team,
+                        expiry_sec,
+                        ciphersuite.into(),
+                    )
+                    .map(async_lock::RwLock::new)
+                    .map(std::sync::Arc::new)
+                    .map(E2eiEnrollment)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(enrollment.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_new_activation_enrollment]
+    pub fn e2ei_new_activation_enrollment(
+        &self,
+        display_name: String,
+        handle: String,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let enrollment = this
+                    .e2ei_new_activation_enrollment(display_name, handle, team, expiry_sec, ciphersuite.into())
+                    .map(async_lock::RwLock::new)
+                    .map(std::sync::Arc::new)
+                    .map(E2eiEnrollment)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(enrollment.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_new_rotate_enrollment]
+    pub fn e2ei_new_rotate_enrollment(
+        &self,
+        display_name: Option<String>,
+        handle: Option<String>,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let enrollment = this
+                    .e2ei_new_rotate_enrollment(display_name, handle, team, expiry_sec, ciphersuite.into())
+                    .await
+                    .map(async_lock::RwLock::new)
+                    .map(std::sync::Arc::new)
+                    .map(E2eiEnrollment)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(enrollment.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<E2eiEnrollment>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_new_renewal_enrollment]
+    pub fn e2ei_new_renewal_enrollment(
+        &self,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let enrollment = this
+                    .e2ei_new_renewal_enrollment(team, expiry_sec, ciphersuite.into())
+                    .await
+                    .map(async_lock::RwLock::new)
+                    .map(std::sync::Arc::new)
+                    .map(E2eiEnrollment)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(enrollment.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::mls::MlsCentral::e2ei_register_acme_ca]
+    pub async fn e2ei_register_acme_ca(&self, trust_anchor_pem: String) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                this.e2ei_register_acme_ca(trust_anchor_pem).await?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::mls::MlsCentral::e2ei_register_intermediate_ca]
+    pub async fn e2ei_register_intermediate_ca(&self, cert_pem: String) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let crls = this.e2ei_register_intermediate_ca_pem(cert_pem).await?;
+
+                let crls = if let Some(crls) = crls {
+                    js_sys::Array::from_iter(crls.into_iter().map(JsValue::from))
+                } else {
+                    js_sys::Array::new()
+                };
+                WasmCryptoResult::Ok(crls.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::mls::MlsCentral::e2ei_register_crl]
+    pub async fn e2ei_register_crl(&self, crl_dp: String, crl_der: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let cc_registration = this.e2ei_register_crl(crl_dp, crl_der.into_vec()).await?;
+                let registration: CrlRegistration = cc_registration.into();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&registration)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::e2ei_mls_init_only]
+    pub fn e2ei_mls_init_only(
+        &self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        nb_key_package: Option<u32>,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+                let nb_key_package = nb_key_package
+                    .map(usize::try_from)
+                    .transpose()
+                    .map_err(CryptoError::from)?;
+
+                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
+                    .map_err(|_| CryptoError::LockPoisonError)?
+                    .into_inner();
+                let crls = this
+                    .e2ei_mls_init_only(enrollment, certificate_chain, nb_key_package)
+                    .await?;
+
+                let crls = if let Some(crls) = crls {
+                    js_sys::Array::from_iter(crls.into_iter().map(JsValue::from))
+                } else {
+                    js_sys::Array::new()
+                };
+                WasmCryptoResult::Ok(crls.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::e2ei_rotate_all]
+    pub fn e2ei_rotate_all(
+        &self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        new_key_packages_count: u32,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+
+                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
+                    .map_err(|_| CryptoError::LockPoisonError)?
+                    .into_inner();
+
+                let rotate_bundle: RotateBundle = this
+                    .e2ei_rotate_all(enrollment, certificate_chain, new_key_packages_count as usize)
+                    .await?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&rotate_bundle)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::e2ei_renew]
+    pub fn e2ei_renew(
+        &self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        new_key_packages_count: u32,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+
+                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
+                    .map_err(|_| CryptoError::LockPoisonError)?
+                    .into_inner();
+
+                let rotate_bundle: RotateBundle = this
+                    .e2ei_renew(enrollment, certificate_chain, new_key_packages_count as usize)
+                    .await?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&rotate_bundle)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::e2ei_enrollment_stash]
+    pub fn e2ei_enrollment_stash(&self, enrollment: E2eiEnrollment) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let this = this.write().await;
+                let enrollment = std::sync::Arc::try_unwrap(enrollment.0)
+                    .map_err(|_| CryptoError::LockPoisonError)?
+                    .into_inner();
+                let handle = this.e2ei_enrollment_stash(enrollment).await?;
+                WasmCryptoResult::Ok(Uint8Array::from(handle.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::e2ei_enrollment_stash_pop]
+    pub fn e2ei_enrollment_stash_pop(&self, handle: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let enrollment = this
+                    .e2ei_enrollment_stash_pop(handle.into_vec())
+                    .await
+                    .map(async_lock::RwLock::new)
+                    .map(std::sync::Arc::new)
+                    .map(E2eiEnrollment)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(enrollment.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<u8>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_conversation_state]
+    pub fn e2ei_conversation_state(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let state: E2eiConversationState = this
+                    .write()
+                    .await
+                    .e2ei_conversation_state(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into();
+                WasmCryptoResult::Ok((state as u8).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<bool>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::e2ei_is_enabled]
+    pub fn e2ei_is_enabled(&self, ciphersuite: Ciphersuite) -> Promise {
+        let sc = MlsCiphersuite::from(ciphersuite).signature_algorithm();
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let is_enabled = this
+                    .write()
+                    .await
+                    .e2ei_is_enabled(sc)
+                    .map_err(CoreCryptoError::from)?
+                    .into();
+                WasmCryptoResult::Ok(is_enabled)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<Vec<WireIdentity>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_device_identities]
+    pub fn get_device_identities(&self, conversation_id: ConversationId, device_ids: Box<[Uint8Array]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let device_ids = device_ids.iter().map(|c| c.to_vec().into()).collect::<Vec<ClientId>>();
+                let identities = this
+                    .write()
+                    .await
+                    .get_device_identities(&conversation_id, &device_ids[..])
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<WireIdentity>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&identities)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<HashMap<String, Vec<WireIdentity>>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_user_identities]
+    pub fn get_user_identities(&self, conversation_id: ConversationId, user_ids: Box<[String]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let identities = this
+                    .write()
+                    .await
+                    .get_user_identities(&conversation_id, user_ids.deref())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into_iter().map(Into::into).collect()))
+                    .collect::<HashMap<String, Vec<WireIdentity>>>();
+                let js_obj = js_sys::Map::new();
+                for (uid, identities) in identities.into_iter() {
+                    let uid = js_sys::JsString::from(uid).into();
+                    let identities = serde_wasm_bindgen::to_value(&identities)?;
+                    js_obj.set(&uid, &identities);
+                }
+                WasmCryptoResult::Ok(js_obj.into())
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<u8>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_credential_in_use]
+    pub fn get_credential_in_use(&self, group_info: Box<[u8]>, credential_type: CredentialType) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let central = this.read().await;
+                let group_info = central.message_limits().deserialize_group_info(&group_info)?;
+
+                let state: E2eiConversationState = central
+                    .get_credential_in_use(group_info, credential_type.into())
+                    .await
+                    .map(Into::into)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok((state as u8).into())
+            }
+            .err_into(),
+        )
+    }
+}
+
+#[derive(Debug)]
+#[wasm_bindgen(js_name = FfiWireE2EIdentity)]
+#[repr(transparent)]
+pub struct E2eiEnrollment(std::sync::Arc<async_lock::RwLock<core_crypto::prelude::E2eiEnrollment>>);
+
+#[wasm_bindgen(js_class = FfiWireE2EIdentity)]
+impl E2eiEnrollment {
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::directory_response]
+    pub fn directory_response(&mut self, directory: Vec<u8>) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+                let directory: AcmeDirectory = this.directory_response(directory)?.into();
+                WasmCryptoResult::Ok(directory.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_account_request]
+    pub fn new_account_request(&self, previous_nonce: String) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let new_account: Vec<u8> = this.new_account_request(previous_nonce)?;
+                WasmCryptoResult::Ok(Uint8Array::from(new_account.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_account_response]
+    pub fn new_account_response(&mut self, account: Uint8Array) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+                this.new_account_response(account.to_vec())?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_order_request]
+    pub fn new_order_request(&self, previous_nonce: String) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let new_order = this.new_order_request(previous_nonce)?;
+                WasmCryptoResult::Ok(Uint8Array::from(new_order.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_order_response]
+    pub fn new_order_response(&self, order: Uint8Array) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let order: NewAcmeOrder = this.new_order_response(order.to_vec())?.into();
+                WasmCryptoResult::Ok(order.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_authz_request]
+    pub fn new_authz_request(&self, url: String, previous_nonce: String) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let new_authz = this.new_authz_request(url, previous_nonce)?;
+                WasmCryptoResult::Ok(Uint8Array::from(new_authz.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_authz_response]
+    pub fn new_authz_response(&mut self, authz: Uint8Array) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let mut this = this.write().await;
+                let authz: NewAcmeAuthz = this.new_authz_response(authz.to_vec())?.into();
+                WasmCryptoResult::Ok(authz.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::create_dpop_token]
+    pub fn create_dpop_token(&self, expiry_secs: u32, backend_nonce: String) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let dpop_token = this.create_dpop_token(expiry_secs, backend_nonce)?;
+                WasmCryptoResult::Ok(Uint8Array::from(dpop_token.as_bytes()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_dpop_challenge_request]
+    pub fn new_dpop_challenge_request(&self, access_token: String, previous_nonce: String) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
+            async move {
+                let this = this.read().await;
+                let chall = this.new_dpop_challenge_request(access_token, previous_nonce)?;
+                WasmCryptoResult::Ok(Uint8Array::from(chall.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// See [core_crypto::e2e_identity::WireE2eIdentity::new_dpop_challenge_response]
+    pub fn new_dpop_challenge_response(&self, challenge: Uint8Array) -> Promise {
+        let this = self.0.clone();
+        future_to_promise(
             async move {
                 let this = this.read().await;
                 this.new_dpop_challenge_response(challenge.to_vec())?;
@@ -3020,146 +5403,616 @@ pub struct AcmeDirectory {
     pub revoke_cert: String,
 }
 
-impl From<core_crypto::prelude::E2eiAcmeDirectory> for AcmeDirectory {
-    fn from(directory: core_crypto::prelude::E2eiAcmeDirectory) -> Self {
+impl From<core_crypto::prelude::E2eiAcmeDirectory> for AcmeDirectory {
+    fn from(directory: core_crypto::prelude::E2eiAcmeDirectory) -> Self {
+        Self {
+            new_nonce: directory.new_nonce,
+            new_account: directory.new_account,
+            new_order: directory.new_order,
+            revoke_cert: directory.revoke_cert,
+        }
+    }
+}
+
+impl From<AcmeDirectory> for core_crypto::prelude::E2eiAcmeDirectory {
+    fn from(directory: AcmeDirectory) -> Self {
+        Self {
+            new_nonce: directory.new_nonce,
+            new_account: directory.new_account,
+            new_order: directory.new_order,
+            revoke_cert: directory.revoke_cert,
+        }
+    }
+}
+
+/// Result of an order creation.
+/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
+#[wasm_bindgen(skip_jsdoc)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewAcmeOrder {
+    /// Contains raw JSON data of this order. This is parsed by the underlying Rust library hence should not be accessed
+    #[wasm_bindgen(readonly, getter_with_clone)]
+    pub delegate: Vec<u8>,
+    authorizations: ArrayOfByteArray,
+}
+
+#[wasm_bindgen]
+impl NewAcmeOrder {
+    #[wasm_bindgen(getter)]
+    pub fn authorizations(&self) -> Vec<Uint8Array> {
+        self.authorizations.clone().into()
+    }
+}
+
+impl From<core_crypto::prelude::E2eiNewAcmeOrder> for NewAcmeOrder {
+    fn from(new_order: core_crypto::prelude::E2eiNewAcmeOrder) -> Self {
+        Self {
+            delegate: new_order.delegate,
+            authorizations: new_order
+                .authorizations
+                .into_iter()
+                .map(String::into_bytes)
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+}
+
+impl TryFrom<NewAcmeOrder> for core_crypto::prelude::E2eiNewAcmeOrder {
+    type Error = CoreCryptoError;
+
+    fn try_from(new_order: NewAcmeOrder) -> WasmCryptoResult<Self> {
+        Ok(Self {
+            delegate: new_order.delegate,
+            authorizations: new_order
+                .authorizations
+                .0
+                .into_iter()
+                .map(|a| String::from_utf8(a).map_err(CryptoError::from))
+                .collect::<CryptoResult<Vec<String>>>()?,
+        })
+    }
+}
+
+/// Result of an authorization creation.
+/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
+#[wasm_bindgen(skip_jsdoc, getter_with_clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NewAcmeAuthz {
+    /// DNS entry associated with those challenge
+    #[wasm_bindgen(readonly)]
+    pub identifier: String,
+    /// ACME challenge + ACME key thumbprint
+    #[wasm_bindgen(readonly)]
+    pub keyauth: Option<String>,
+    /// Associated ACME Challenge
+    #[wasm_bindgen(readonly)]
+    pub challenge: AcmeChallenge,
+}
+
+impl From<core_crypto::prelude::E2eiNewAcmeAuthz> for NewAcmeAuthz {
+    fn from(authz: core_crypto::prelude::E2eiNewAcmeAuthz) -> Self {
+        Self {
+            identifier: authz.identifier,
+            keyauth: authz.keyauth,
+            challenge: authz.challenge.into(),
+        }
+    }
+}
+
+impl From<NewAcmeAuthz> for core_crypto::prelude::E2eiNewAcmeAuthz {
+    fn from(authz: NewAcmeAuthz) -> Self {
+        Self {
+            identifier: authz.identifier,
+            keyauth: authz.keyauth,
+            challenge: authz.challenge.into(),
+        }
+    }
+}
+
+/// For creating a challenge.
+/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
+#[wasm_bindgen(skip_jsdoc, getter_with_clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AcmeChallenge {
+    /// Contains raw JSON data of this challenge. This is parsed by the underlying Rust library hence should not be accessed
+    #[wasm_bindgen(readonly)]
+    pub delegate: Vec<u8>,
+    /// URL of this challenge
+    #[wasm_bindgen(readonly)]
+    pub url: String,
+    /// Non-standard, Wire specific claim. Indicates the consumer from where it should get the challenge proof.
+    /// Either from wire-server "/access-token" endpoint in case of a DPoP challenge, or from an OAuth token endpoint for an OIDC challenge
+    #[wasm_bindgen(readonly)]
+    pub target: String,
+}
+
+impl From<core_crypto::prelude::E2eiAcmeChallenge> for AcmeChallenge {
+    fn from(chall: core_crypto::prelude::E2eiAcmeChallenge) -> Self {
+        Self {
+            delegate: chall.delegate,
+            url: chall.url,
+            target: chall.target,
+        }
+    }
+}
+
+impl From<AcmeChallenge> for core_crypto::prelude::E2eiAcmeChallenge {
+    fn from(chall: AcmeChallenge) -> Self {
+        Self {
+            delegate: chall.delegate,
+            url: chall.url,
+            target: chall.target,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+/// see [core_crypto::prelude::MlsConversationHealth]
+struct ConversationHealth {
+    /// 1 when healthy, 2 when suspect, 3 when broken
+    state: u8,
+    /// number of consecutive decryption failures
+    failures: u32,
+}
+
+impl From<MlsConversationHealth> for ConversationHealth {
+    fn from(health: MlsConversationHealth) -> Self {
+        let (state, failures) = match health {
+            MlsConversationHealth::Healthy => (1, 0),
+            MlsConversationHealth::Suspect { failures } => (2, failures),
+            MlsConversationHealth::Broken { failures } => (3, failures),
+        };
+        Self { state, failures }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsMaintenanceItem]
+struct MaintenanceItem {
+    /// 1 to prune expired KeyPackages, 2 to update keying material, 3 to expire buffered messages, 4 to vacuum the
+    /// keystore
+    kind: u8,
+    /// conversation whose keying material has to be updated
+    #[serde(with = "serde_bytes")]
+    conversation_id: Option<Vec<u8>>,
+    /// 1 when low, 2 when normal, 3 when high
+    priority: u8,
+}
+
+impl From<MlsMaintenanceItem> for MaintenanceItem {
+    fn from(item: MlsMaintenanceItem) -> Self {
+        let (kind, conversation_id) = match item.task {
+            MlsMaintenanceTask::PruneExpiredKeyPackages => (1, None),
+            MlsMaintenanceTask::UpdateKeyingMaterial(id) => (2, Some(id)),
+            MlsMaintenanceTask::ExpireBufferedMessages => (3, None),
+            MlsMaintenanceTask::VacuumKeystore => (4, None),
+            MlsMaintenanceTask::PurgeExpiredEntities => (5, None),
+        };
+        let priority = match item.priority {
+            MlsMaintenancePriority::Low => 1,
+            MlsMaintenancePriority::Normal => 2,
+            MlsMaintenancePriority::High => 3,
+        };
+        Self {
+            kind,
+            conversation_id,
+            priority,
+        }
+    }
+}
+
+#[cfg(feature = "self-test")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::SelfTestCheck]
+struct SelfTestCheck {
+    /// 1 for signatures, 2 for HPKE, 3 for AEAD, 4 for the keystore
+    kind: u8,
+    ciphersuite: Option<u16>,
+    failure: Option<String>,
+}
+
+#[cfg(feature = "self-test")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::SelfTestReport]
+struct SelfTestReport {
+    passed: bool,
+    checks: Vec<SelfTestCheck>,
+}
+
+#[cfg(feature = "self-test")]
+impl From<core_crypto::prelude::SelfTestReport> for SelfTestReport {
+    fn from(report: core_crypto::prelude::SelfTestReport) -> Self {
+        Self {
+            passed: report.passed(),
+            checks: report
+                .checks
+                .into_iter()
+                .map(|check| SelfTestCheck {
+                    kind: match check.kind {
+                        SelfTestCheckKind::Signature => 1,
+                        SelfTestCheckKind::Hpke => 2,
+                        SelfTestCheckKind::Aead => 3,
+                        SelfTestCheckKind::Keystore => 4,
+                    },
+                    ciphersuite: check.ciphersuite.map(u16::from),
+                    failure: check.failure,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::MlsConversationHealthObserver]
+struct ConversationHealthWasmObserver(js_sys::Function);
+
+// SAFETY: WASM is single-threaded, the function is never shared across threads
+unsafe impl Send for ConversationHealthWasmObserver {}
+unsafe impl Sync for ConversationHealthWasmObserver {}
+
+#[async_trait::async_trait(?Send)]
+impl MlsConversationHealthObserver for ConversationHealthWasmObserver {
+    async fn health_changed(&self, conversation_id: ConversationId, health: MlsConversationHealth) {
+        let health = match serde_wasm_bindgen::to_value(&ConversationHealth::from(health)) {
+            Ok(health) => health,
+            Err(e) => return web_sys::console::error_1(&JsValue::from(e)),
+        };
+        let conversation_id = Uint8Array::from(conversation_id.as_slice());
+        if let Err(e) = self.0.call2(&JsValue::NULL, &conversation_id, &health) {
+            web_sys::console::error_1(&e);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsCredentialFirstSeen]
+struct CredentialFirstSeen {
+    #[serde(with = "serde_bytes")]
+    client_id: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    credential: Vec<u8>,
+    first_seen: u64,
+}
+
+impl From<MlsCredentialFirstSeen> for CredentialFirstSeen {
+    fn from(seen: MlsCredentialFirstSeen) -> Self {
+        Self {
+            client_id: seen.client_id.to_vec(),
+            credential: seen.credential,
+            first_seen: seen.first_seen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsKeyPackageUploadPayload]
+struct KeyPackageUploadPayload {
+    key_packages: Vec<String>,
+    #[serde(with = "byte_arrays")]
+    refs: Vec<Vec<u8>>,
+    json: String,
+}
+
+impl From<core_crypto::prelude::MlsKeyPackageUploadPayload> for KeyPackageUploadPayload {
+    fn from(payload: core_crypto::prelude::MlsKeyPackageUploadPayload) -> Self {
+        Self {
+            json: payload.to_json(),
+            refs: payload.refs.iter().map(|r| r.as_slice().to_vec()).collect(),
+            key_packages: payload.key_packages,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::KeyPackageValidationReport]
+struct KeyPackageValidationReport {
+    is_valid: bool,
+    ciphersuite: Option<u16>,
+    ciphersuite_supported: bool,
+    credential_type: u8,
+    lifetime_valid: bool,
+    signature_valid: bool,
+    failure: Option<String>,
+}
+
+impl From<core_crypto::prelude::KeyPackageValidationReport> for KeyPackageValidationReport {
+    fn from(report: core_crypto::prelude::KeyPackageValidationReport) -> Self {
+        Self {
+            is_valid: report.is_valid(),
+            ciphersuite: report.ciphersuite.map(u16::from),
+            ciphersuite_supported: report.ciphersuite_supported,
+            credential_type: report.credential_type as u8,
+            lifetime_valid: report.lifetime_valid,
+            signature_valid: report.signature_valid,
+            failure: report.failure,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsExternalSender]
+struct ExternalSender {
+    #[serde(with = "serde_bytes")]
+    signature_key: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    identity: Vec<u8>,
+}
+
+impl From<MlsExternalSender> for ExternalSender {
+    fn from(sender: MlsExternalSender) -> Self {
+        Self {
+            signature_key: sender.signature_key,
+            identity: sender.identity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::BuildMetadata]
+struct BuildMetadata {
+    version: String,
+    git_hash: String,
+    target: String,
+    profile: String,
+    features: Vec<String>,
+}
+
+impl From<core_crypto::prelude::BuildMetadata> for BuildMetadata {
+    fn from(metadata: core_crypto::prelude::BuildMetadata) -> Self {
+        Self {
+            version: metadata.version.to_string(),
+            git_hash: metadata.git_hash.to_string(),
+            target: metadata.target.to_string(),
+            profile: metadata.profile.to_string(),
+            features: metadata.features.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsEpochHistoryEntry]
+struct EpochHistoryEntry {
+    epoch: u64,
+    #[serde(with = "serde_bytes")]
+    authenticator_hash: Vec<u8>,
+    timestamp: u64,
+    #[serde(with = "serde_bytes")]
+    commit_sender: Option<Vec<u8>>,
+}
+
+impl From<MlsEpochHistoryEntry> for EpochHistoryEntry {
+    fn from(entry: MlsEpochHistoryEntry) -> Self {
+        Self {
+            epoch: entry.epoch,
+            authenticator_hash: entry.authenticator_hash,
+            timestamp: entry.timestamp,
+            commit_sender: entry.commit_sender.map(|c| c.to_vec()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::PublicKeyBundle]
+struct PublicKeyBundle {
+    signature_scheme: u16,
+    /// see [CredentialType]
+    credential_type: u16,
+    #[serde(with = "serde_bytes")]
+    public_key: Vec<u8>,
+    #[serde(with = "byte_arrays")]
+    certificate_chain: Vec<Vec<u8>>,
+}
+
+impl From<core_crypto::prelude::PublicKeyBundle> for PublicKeyBundle {
+    fn from(bundle: core_crypto::prelude::PublicKeyBundle) -> Self {
         Self {
-            new_nonce: directory.new_nonce,
-            new_account: directory.new_account,
-            new_order: directory.new_order,
-            revoke_cert: directory.revoke_cert,
+            signature_scheme: bundle.signature_scheme as u16,
+            credential_type: CredentialType::from(bundle.credential_type) as u16,
+            public_key: bundle.public_key,
+            certificate_chain: bundle.certificate_chain,
         }
     }
 }
 
-impl From<AcmeDirectory> for core_crypto::prelude::E2eiAcmeDirectory {
-    fn from(directory: AcmeDirectory) -> Self {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsOperationMetrics]
+struct OperationMetrics {
+    calls: u64,
+    total_micros: u64,
+    max_micros: u64,
+}
+
+impl From<MlsOperationMetrics> for OperationMetrics {
+    fn from(metrics: MlsOperationMetrics) -> Self {
         Self {
-            new_nonce: directory.new_nonce,
-            new_account: directory.new_account,
-            new_order: directory.new_order,
-            revoke_cert: directory.revoke_cert,
+            calls: metrics.calls,
+            total_micros: metrics.total_micros,
+            max_micros: metrics.max_micros,
         }
     }
 }
 
-/// Result of an order creation.
-/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.4
-#[wasm_bindgen(skip_jsdoc)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct NewAcmeOrder {
-    /// Contains raw JSON data of this order. This is parsed by the underlying Rust library hence should not be accessed
-    #[wasm_bindgen(readonly, getter_with_clone)]
-    pub delegate: Vec<u8>,
-    authorizations: ArrayOfByteArray,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsMetricsSnapshot]
+struct MetricsSnapshot {
+    messages_encrypted: u64,
+    messages_decrypted: u64,
+    epochs_advanced: u64,
+    keystore_writes: u64,
+    keystore_bytes_written: u64,
+    encrypt: OperationMetrics,
+    decrypt: OperationMetrics,
+    commit: OperationMetrics,
+    merge: OperationMetrics,
 }
 
-#[wasm_bindgen]
-impl NewAcmeOrder {
-    #[wasm_bindgen(getter)]
-    pub fn authorizations(&self) -> Vec<Uint8Array> {
-        self.authorizations.clone().into()
+impl From<MlsMetricsSnapshot> for MetricsSnapshot {
+    fn from(snapshot: MlsMetricsSnapshot) -> Self {
+        Self {
+            messages_encrypted: snapshot.messages_encrypted,
+            messages_decrypted: snapshot.messages_decrypted,
+            epochs_advanced: snapshot.epochs_advanced,
+            keystore_writes: snapshot.keystore_writes,
+            keystore_bytes_written: snapshot.keystore_bytes_written,
+            encrypt: snapshot.encrypt.into(),
+            decrypt: snapshot.decrypt.into(),
+            commit: snapshot.commit.into(),
+            merge: snapshot.merge.into(),
+        }
     }
 }
 
-impl From<core_crypto::prelude::E2eiNewAcmeOrder> for NewAcmeOrder {
-    fn from(new_order: core_crypto::prelude::E2eiNewAcmeOrder) -> Self {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsConversationStats]
+struct ConversationStats {
+    messages_sent: u64,
+    messages_received: u64,
+    commits_sent: u64,
+    commits_received: u64,
+    last_activity_epoch: Option<u64>,
+    last_activity: Option<u64>,
+}
+
+impl From<MlsConversationStats> for ConversationStats {
+    fn from(stats: MlsConversationStats) -> Self {
         Self {
-            delegate: new_order.delegate,
-            authorizations: new_order
-                .authorizations
-                .into_iter()
-                .map(String::into_bytes)
-                .collect::<Vec<_>>()
-                .into(),
+            messages_sent: stats.messages_sent,
+            messages_received: stats.messages_received,
+            commits_sent: stats.commits_sent,
+            commits_received: stats.commits_received,
+            last_activity_epoch: stats.last_activity_epoch,
+            last_activity: stats.last_activity,
         }
     }
 }
 
-impl TryFrom<NewAcmeOrder> for core_crypto::prelude::E2eiNewAcmeOrder {
-    type Error = CoreCryptoError;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsRatchetDiagnostics]
+struct RatchetDiagnostics {
+    epoch: u64,
+    own_generation: u32,
+    out_of_order_tolerance: u32,
+    maximum_forward_distance: u32,
+    retained_past_epochs: u32,
+    forward_distance_remaining: u32,
+}
 
-    fn try_from(new_order: NewAcmeOrder) -> WasmCryptoResult<Self> {
-        Ok(Self {
-            delegate: new_order.delegate,
-            authorizations: new_order
-                .authorizations
-                .0
-                .into_iter()
-                .map(|a| String::from_utf8(a).map_err(CryptoError::from))
-                .collect::<CryptoResult<Vec<String>>>()?,
-        })
+impl From<MlsRatchetDiagnostics> for RatchetDiagnostics {
+    fn from(diagnostics: MlsRatchetDiagnostics) -> Self {
+        Self {
+            epoch: diagnostics.epoch,
+            own_generation: diagnostics.own_generation,
+            out_of_order_tolerance: diagnostics.out_of_order_tolerance,
+            maximum_forward_distance: diagnostics.maximum_forward_distance,
+            retained_past_epochs: diagnostics.retained_past_epochs,
+            forward_distance_remaining: diagnostics.forward_distance_remaining(),
+        }
     }
 }
 
-/// Result of an authorization creation.
-/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5
-#[wasm_bindgen(skip_jsdoc, getter_with_clone)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct NewAcmeAuthz {
-    /// DNS entry associated with those challenge
-    #[wasm_bindgen(readonly)]
-    pub identifier: String,
-    /// ACME challenge + ACME key thumbprint
-    #[wasm_bindgen(readonly)]
-    pub keyauth: Option<String>,
-    /// Associated ACME Challenge
-    #[wasm_bindgen(readonly)]
-    pub challenge: AcmeChallenge,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsConversationCacheStats]
+struct ConversationCacheStats {
+    hits: u64,
+    misses: u64,
+    cached: u64,
 }
 
-impl From<core_crypto::prelude::E2eiNewAcmeAuthz> for NewAcmeAuthz {
-    fn from(authz: core_crypto::prelude::E2eiNewAcmeAuthz) -> Self {
+impl From<MlsConversationCacheStats> for ConversationCacheStats {
+    fn from(stats: MlsConversationCacheStats) -> Self {
         Self {
-            identifier: authz.identifier,
-            keyauth: authz.keyauth,
-            challenge: authz.challenge.into(),
+            hits: stats.hits,
+            misses: stats.misses,
+            cached: stats.cached,
         }
     }
 }
 
-impl From<NewAcmeAuthz> for core_crypto::prelude::E2eiNewAcmeAuthz {
-    fn from(authz: NewAcmeAuthz) -> Self {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsGroupRestoreProgress]
+struct GroupRestoreProgress {
+    restored: u32,
+    total: u32,
+}
+
+impl From<MlsGroupRestoreProgress> for GroupRestoreProgress {
+    fn from(progress: MlsGroupRestoreProgress) -> Self {
         Self {
-            identifier: authz.identifier,
-            keyauth: authz.keyauth,
-            challenge: authz.challenge.into(),
+            restored: progress.restored as u32,
+            total: progress.total as u32,
         }
     }
 }
 
-/// For creating a challenge.
-/// @see https://www.rfc-editor.org/rfc/rfc8555.html#section-7.5.1
-#[wasm_bindgen(skip_jsdoc, getter_with_clone)]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct AcmeChallenge {
-    /// Contains raw JSON data of this challenge. This is parsed by the underlying Rust library hence should not be accessed
-    #[wasm_bindgen(readonly)]
-    pub delegate: Vec<u8>,
-    /// URL of this challenge
-    #[wasm_bindgen(readonly)]
-    pub url: String,
-    /// Non-standard, Wire specific claim. Indicates the consumer from where it should get the challenge proof.
-    /// Either from wire-server "/access-token" endpoint in case of a DPoP challenge, or from an OAuth token endpoint for an OIDC challenge
-    #[wasm_bindgen(readonly)]
-    pub target: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+/// see [core_crypto::prelude::MlsEpochCleanupMetrics]
+struct EpochCleanupMetrics {
+    runs: u64,
+    deleted_keypairs: u64,
 }
 
-impl From<core_crypto::prelude::E2eiAcmeChallenge> for AcmeChallenge {
-    fn from(chall: core_crypto::prelude::E2eiAcmeChallenge) -> Self {
+impl From<MlsEpochCleanupMetrics> for EpochCleanupMetrics {
+    fn from(metrics: MlsEpochCleanupMetrics) -> Self {
         Self {
-            delegate: chall.delegate,
-            url: chall.url,
-            target: chall.target,
+            runs: metrics.runs,
+            deleted_keypairs: metrics.deleted_keypairs,
         }
     }
 }
 
-impl From<AcmeChallenge> for core_crypto::prelude::E2eiAcmeChallenge {
-    fn from(chall: AcmeChallenge) -> Self {
-        Self {
-            delegate: chall.delegate,
-            url: chall.url,
-            target: chall.target,
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::CoreCryptoLogger]
+struct CoreCryptoWasmLogger(js_sys::Function);
+
+// SAFETY: WASM is single-threaded, the function is never shared across threads
+unsafe impl Send for CoreCryptoWasmLogger {}
+unsafe impl Sync for CoreCryptoWasmLogger {}
+
+impl core_crypto::prelude::CoreCryptoLogger for CoreCryptoWasmLogger {
+    fn log(&self, level: core_crypto::prelude::CoreCryptoLogLevel, message: &str, context: &str) {
+        let level = JsValue::from(level as u8);
+        let message = JsValue::from(message);
+        let context = JsValue::from(context);
+        if let Err(e) = self.0.call3(&JsValue::NULL, &level, &message, &context) {
+            web_sys::console::error_1(&e);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::MlsCredentialObserver]
+struct CredentialWasmObserver(js_sys::Function);
+
+// SAFETY: WASM is single-threaded, the function is never shared across threads
+unsafe impl Send for CredentialWasmObserver {}
+unsafe impl Sync for CredentialWasmObserver {}
+
+#[async_trait::async_trait(?Send)]
+impl MlsCredentialObserver for CredentialWasmObserver {
+    async fn on_new_credential(&self, conversation_id: ConversationId, client_id: ClientId, credential: Vec<u8>) {
+        let conversation_id = Uint8Array::from(conversation_id.as_slice());
+        let client_id = Uint8Array::from(client_id.as_slice());
+        let credential = Uint8Array::from(credential.as_slice());
+        if let Err(e) = self.0.call3(&JsValue::NULL, &conversation_id, &client_id, &credential) {
+            web_sys::console::error_1(&e);
         }
     }
 }
@@ -3185,6 +6038,175 @@ impl From<core_crypto::prelude::E2eiConversationState> for E2eiConversationState
     }
 }
 
+type TransactionState =
+    std::rc::Rc<async_lock::Mutex<Option<async_lock::RwLockWriteGuardArc<core_crypto::CoreCrypto>>>>;
+
+#[wasm_bindgen]
+#[derive(Debug)]
+/// Operations performed within a transaction, see [CoreCrypto::transaction]
+pub struct CoreCryptoContext {
+    central: TransactionState,
+}
+
+impl CoreCryptoContext {
+    fn central(
+        central: &mut Option<async_lock::RwLockWriteGuardArc<core_crypto::CoreCrypto>>,
+    ) -> WasmCryptoResult<&mut core_crypto::CoreCrypto> {
+        // the context cannot be used anymore once the transaction is over
+        Ok(central.as_deref_mut().ok_or(CryptoError::ConsumerError)?)
+    }
+}
+
+#[wasm_bindgen]
+impl CoreCryptoContext {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [CoreCrypto::create_conversation]
+    pub fn create_conversation(
+        &self,
+        conversation_id: ConversationId,
+        creator_credential_type: CredentialType,
+        config: ConversationConfiguration,
+    ) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let mut central = this.lock().await;
+                Self::central(&mut central)?
+                    .new_conversation(&conversation_id, creator_credential_type.into(), config.try_into()?)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
+    ///
+    /// see [CoreCrypto::add_clients_to_conversation]
+    pub fn add_clients_to_conversation(
+        &self,
+        conversation_id: ConversationId,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+                let mut central = this.lock().await;
+                let commit: MemberAddedMessages = Self::central(&mut central)?
+                    .add_members_to_conversation(&conversation_id, key_packages)
+                    .await?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
+    ///
+    /// see [CoreCrypto::remove_clients_from_conversation]
+    pub fn remove_clients_from_conversation(
+        &self,
+        conversation_id: ConversationId,
+        clients: Box<[Uint8Array]>,
+    ) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let clients = clients
+                    .iter()
+                    .cloned()
+                    .map(|c| c.to_vec().into())
+                    .collect::<Vec<ClientId>>();
+
+                let mut central = this.lock().await;
+                let commit: CommitBundle = Self::central(&mut central)?
+                    .remove_members_from_conversation(&conversation_id, &clients)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
+    ///
+    /// see [CoreCrypto::update_keying_material]
+    pub fn update_keying_material(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let mut central = this.lock().await;
+                let commit: CommitBundle = Self::central(&mut central)?
+                    .update_keying_material(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Option<Vec<BufferedDecryptedMessage>>>`]
+    ///
+    /// see [CoreCrypto::commit_accepted]
+    pub fn commit_accepted(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let mut central = this.lock().await;
+                if let Some(decrypted_messages) = Self::central(&mut central)?
+                    .commit_accepted(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                {
+                    let messages = decrypted_messages
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
+
+                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
+                }
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Option<CommitBundle>>`]
+    ///
+    /// see [CoreCrypto::commit_pending_proposals]
+    pub fn commit_pending_proposals(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.central.clone();
+        future_to_promise(
+            async move {
+                let mut central = this.lock().await;
+                let commit: Option<CommitBundle> = Self::central(&mut central)?
+                    .commit_pending_proposals(&conversation_id)
+                    .await?
+                    .map(|c| c.try_into())
+                    .transpose()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+            }
+            .err_into(),
+        )
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[repr(u8)]
@@ -1,5 +1,5 @@
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ArrayOfByteArray(pub Vec<Vec<u8>>);
+pub struct ArrayOfByteArray(#[serde(with = "byte_arrays")] pub Vec<Vec<u8>>);
 
 impl From<Vec<Vec<u8>>> for ArrayOfByteArray {
     fn from(value: Vec<Vec<u8>>) -> Self {
@@ -34,3 +34,18 @@ impl From<ArrayOfByteArray> for Vec<js_sys::Uint8Array> {
             .collect::<Vec<_>>()
     }
 }
+
+/// Serializes a list of byte buffers as an array of `Uint8Array` instead of an array of arrays of numbers, for use with
+/// `#[serde(with = "byte_arrays")]`. The counterpart of `serde_bytes` for a single buffer
+pub mod byte_arrays {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(buffers: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(buffers.iter().map(|b| serde_bytes::Bytes::new(b)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+        let buffers = Vec::<serde_bytes::ByteBuf>::deserialize(deserializer)?;
+        Ok(buffers.into_iter().map(serde_bytes::ByteBuf::into_vec).collect())
+    }
+}
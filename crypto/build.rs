@@ -25,7 +25,31 @@ fn main() {
     #[cfg(target_os = "android")]
     println!("cargo:rustc-cfg=android");
 
-    if let Ok(profile) = std::env::var("PROFILE") {
+    let profile = std::env::var("PROFILE").unwrap_or_default();
+    if !profile.is_empty() {
         println!("cargo:rustc-cfg=build=\"{profile}\"");
     }
+
+    // Build metadata, see `BuildMetadata`
+    println!("cargo:rustc-env=CORE_CRYPTO_PROFILE={profile}");
+    println!(
+        "cargo:rustc-env=CORE_CRYPTO_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+    let git_hash = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=CORE_CRYPTO_GIT_HASH={git_hash}");
+    // only rebuilt when the checked out commit changes, when built from a git checkout
+    println!("cargo:rerun-if-changed=build.rs");
+    for git_ref in ["../.git/HEAD", "../.git/refs/heads"] {
+        if std::path::Path::new(git_ref).exists() {
+            println!("cargo:rerun-if-changed={git_ref}");
+        }
+    }
 }
@@ -0,0 +1,9 @@
+#![no_main]
+
+use core_crypto::prelude::MlsMessageLimits;
+use libfuzzer_sys::fuzz_target;
+
+// Entry point of `MlsCentral::process_raw_welcome_message` for untrusted bytes
+fuzz_target!(|data: &[u8]| {
+    let _ = MlsMessageLimits::default().deserialize_welcome(data);
+});
@@ -0,0 +1,127 @@
+//! Byte buffers reused across serializations on hot paths such as decryption, where group states are serialized
+//! over and over: growing a fresh buffer to the size of the group every time weighs a lot more than the serialization
+//! itself. Buffers are kept in a per-thread pool so that no synchronization is needed.
+
+use std::cell::RefCell;
+
+/// Number of idle buffers kept per thread
+const MAX_POOLED_BUFFERS: usize = 16;
+
+/// Buffers which grew larger than this are freed instead of being pooled, so that a single huge group does not keep
+/// its memory around forever
+const MAX_POOLED_CAPACITY: usize = 1024 * 1024;
+
+thread_local! {
+    static POOL: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A buffer taken from the pool of the current thread, given back to the pool of the thread it's dropped on
+pub(crate) struct PooledBuffer(Vec<u8>);
+
+impl PooledBuffer {
+    /// Takes an empty buffer from the pool or allocates a new one when it's empty
+    pub(crate) fn take() -> Self {
+        Self(POOL.with(|pool| pool.borrow_mut().pop()).unwrap_or_default())
+    }
+}
+
+impl Default for PooledBuffer {
+    fn default() -> Self {
+        Self::take()
+    }
+}
+
+/// Adopts an existing buffer, which joins the pool when dropped
+impl From<Vec<u8>> for PooledBuffer {
+    fn from(buf: Vec<u8>) -> Self {
+        Self(buf)
+    }
+}
+
+impl std::ops::Deref for PooledBuffer {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::fmt::Debug for PooledBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledBuffer")
+            .field("len", &self.0.len())
+            .field("capacity", &self.0.capacity())
+            .finish()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let mut buf = std::mem::take(&mut self.0);
+        if buf.capacity() == 0 || buf.capacity() > MAX_POOLED_CAPACITY {
+            return;
+        }
+        buf.clear();
+        // the pool might already be gone when the thread is exiting
+        let _ = POOL.try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < MAX_POOLED_BUFFERS {
+                pool.push(buf);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn pooled() -> usize {
+        POOL.with(|pool| pool.borrow().len())
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn should_reuse_buffers() {
+        POOL.with(|pool| pool.borrow_mut().clear());
+
+        let mut buf = PooledBuffer::take();
+        buf.extend_from_slice(&[42; 1000]);
+        let capacity = buf.capacity();
+        drop(buf);
+        assert_eq!(pooled(), 1);
+
+        let buf = PooledBuffer::take();
+        assert!(buf.is_empty());
+        assert_eq!(buf.capacity(), capacity);
+        assert_eq!(pooled(), 0);
+        drop(buf);
+
+        // oversized buffers are freed
+        let mut buf = PooledBuffer::take();
+        buf.reserve(MAX_POOLED_CAPACITY + 1);
+        drop(buf);
+        assert_eq!(pooled(), 0);
+
+        // and so are buffers beyond the pool's limit
+        let bufs = (0..MAX_POOLED_BUFFERS + 1)
+            .map(|_| {
+                let mut buf = PooledBuffer::take();
+                buf.push(0);
+                buf
+            })
+            .collect::<Vec<_>>();
+        drop(bufs);
+        assert_eq!(pooled(), MAX_POOLED_BUFFERS);
+    }
+}
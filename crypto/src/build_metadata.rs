@@ -0,0 +1,78 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Describes how this copy of CoreCrypto has been built, so that bug reports can tell exactly which code is running.
+//! The git hash, target and profile are collected by the build script
+
+/// Version of CoreCrypto as defined in its `Cargo.toml`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How this copy of CoreCrypto has been built, see [crate::CoreCrypto::build_metadata]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildMetadata {
+    /// Version as defined in `Cargo.toml` e.g. `1.0.0-rc.42`
+    pub version: &'static str,
+    /// Hash of the git commit built, `unknown` when not built from a git checkout
+    pub git_hash: &'static str,
+    /// Target triple e.g. `aarch64-linux-android`
+    pub target: &'static str,
+    /// Cargo profile e.g. `release`
+    pub profile: &'static str,
+    /// Enabled optional features e.g. `proteus`
+    pub features: Vec<&'static str>,
+}
+
+impl BuildMetadata {
+    /// Metadata of the running build
+    pub fn current() -> Self {
+        let features = [
+            ("proteus", cfg!(feature = "proteus")),
+            ("cryptobox-migrate", cfg!(feature = "cryptobox-migrate")),
+            ("pq-ciphersuites", cfg!(feature = "pq-ciphersuites")),
+            ("tracing", cfg!(feature = "tracing")),
+            ("self-test", cfg!(feature = "self-test")),
+        ];
+        Self {
+            version: VERSION,
+            git_hash: env!("CORE_CRYPTO_GIT_HASH"),
+            target: env!("CORE_CRYPTO_TARGET"),
+            profile: env!("CORE_CRYPTO_PROFILE"),
+            features: features
+                .into_iter()
+                .filter_map(|(feature, enabled)| enabled.then_some(feature))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn should_describe_build() {
+        let metadata = BuildMetadata::current();
+        assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
+        assert!(!metadata.git_hash.is_empty());
+        assert!(!metadata.target.is_empty());
+        assert_eq!(metadata.features.contains(&"proteus"), cfg!(feature = "proteus"));
+    }
+}
@@ -0,0 +1,46 @@
+//! Long-running operations, e.g. [crate::prelude::MlsCentral::restore_from_disk_cancellable] or
+//! [crate::prelude::MlsCentral::e2ei_rotate_all_cancellable], can take tens of seconds on a large keystore. They check
+//! a [CancellationToken] between two steps and stop with [CryptoError::OperationCancelled] once it is cancelled, the
+//! step in progress being completed first so that the state is left consistent.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{CryptoError, CryptoResult};
+
+/// Lets the caller cancel a long-running operation, e.g. when the user leaves the screen. Clones share the same state,
+/// so that the token can be cancelled from another thread than the one running the operation.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token which is not cancelled yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the operations using this token to stop. They do at their next step, cancelling is definitive
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [CancellationToken::cancel] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [CryptoError::OperationCancelled] when cancelled, to be called between two steps of an operation
+    pub(crate) fn check(&self) -> CryptoResult<()> {
+        if self.is_cancelled() {
+            return Err(CryptoError::OperationCancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Same as [CancellationToken::check] for the operations whose token is optional
+pub(crate) fn check_cancelled(token: Option<&CancellationToken>) -> CryptoResult<()> {
+    token.map(CancellationToken::check).unwrap_or(Ok(()))
+}
@@ -0,0 +1,236 @@
+use crate::{
+    mls::{client::Client, MlsCentral},
+    CoreCrypto, CryptoResult,
+};
+
+// futures are not `Send` on wasm
+#[cfg(not(target_family = "wasm"))]
+type ContextFuture<'a, T> = futures_util::future::BoxFuture<'a, CryptoResult<T>>;
+#[cfg(target_family = "wasm")]
+type ContextFuture<'a, T> = futures_util::future::LocalBoxFuture<'a, CryptoResult<T>>;
+
+/// In-memory state captured when a transaction begins so that it can be put back when the transaction is rolled back
+#[derive(Debug)]
+pub(crate) struct TransactionSnapshot {
+    mls_client: Option<Client>,
+}
+
+impl MlsCentral {
+    /// Starts a transaction: every keystore write from now on is only persisted once [MlsCentral::commit_transaction]
+    /// is called and is discarded by [MlsCentral::rollback_transaction]. Only one transaction can be in progress at
+    /// a time.
+    ///
+    /// Prefer [CoreCrypto::context] which takes care of committing or rolling back
+    pub async fn begin_transaction(&mut self) -> CryptoResult<()> {
+        self.mls_backend.key_store().begin_transaction().await?;
+        self.transaction_snapshot = Some(TransactionSnapshot {
+            mls_client: self.mls_client.clone(),
+        });
+        Ok(())
+    }
+
+    /// Whether a transaction is in progress, see [MlsCentral::begin_transaction]
+    pub fn transaction_in_progress(&self) -> bool {
+        self.transaction_snapshot.is_some()
+    }
+
+    /// Reports that the transaction in progress will not be ended by whoever began it, e.g. a context of the bindings
+    /// dropped without being finished nor aborted. It has to be rolled back before another one can begin
+    pub fn abandon_transaction(&self) {
+        if self.transaction_in_progress() {
+            crate::logger::warn(
+                "A transaction was abandoned without being committed nor rolled back",
+                serde_json::json!({}),
+            );
+        }
+    }
+
+    /// Persists every keystore write made since [MlsCentral::begin_transaction]. When they cannot be persisted, the
+    /// transaction is rolled back, see [MlsCentral::rollback_transaction], and the error is returned
+    pub async fn commit_transaction(&mut self) -> CryptoResult<()> {
+        // the keystore discards the transaction on its own when failing to commit it
+        if let Err(e) = self.mls_backend.key_store().commit_transaction().await {
+            self.restore_transaction_snapshot().await?;
+            return Err(e.into());
+        }
+        self.transaction_snapshot = None;
+        Ok(())
+    }
+
+    /// Discards every keystore write made since [MlsCentral::begin_transaction] and restores the in-memory state
+    /// (client & groups) as it was then
+    pub async fn rollback_transaction(&mut self) -> CryptoResult<()> {
+        self.mls_backend.key_store().rollback_transaction().await?;
+        self.restore_transaction_snapshot().await
+    }
+
+    /// Puts the in-memory state back as it was when the transaction began
+    async fn restore_transaction_snapshot(&mut self) -> CryptoResult<()> {
+        if let Some(snapshot) = self.transaction_snapshot.take() {
+            self.mls_client = snapshot.mls_client;
+        }
+        self.restore_from_disk().await
+    }
+
+    /// Runs `f` within a transaction so that its keystore writes are persisted all at once or not at all. When a
+    /// transaction is already in progress, they simply become part of it. The in-memory state is restored when `f` or
+    /// the commit fails
+    pub(crate) async fn within_transaction<T, F>(&mut self, f: F) -> CryptoResult<T>
+    where
+        F: for<'a> FnOnce(&'a mut MlsCentral) -> ContextFuture<'a, T>,
+    {
+        if self.transaction_snapshot.is_some() {
+            return f(self).await;
+        }
+        self.begin_transaction().await?;
+        match f(self).await {
+            Ok(value) => {
+                self.commit_transaction().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.rollback_transaction().await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Same as [MlsCentral::within_transaction] for operations which put the conversation back in memory on their own
+    /// when they fail, e.g. merging a commit, see [crate::prelude::MlsConversation::rollback]: only the keystore writes
+    /// are discarded then. The conversations are restored from the keystore only when the transaction cannot be
+    /// committed
+    pub(crate) async fn within_keystore_transaction<T, F>(&mut self, f: F) -> CryptoResult<T>
+    where
+        F: for<'a> FnOnce(&'a mut MlsCentral) -> ContextFuture<'a, T>,
+    {
+        if self.transaction_snapshot.is_some() {
+            return f(self).await;
+        }
+        self.begin_transaction().await?;
+        let value = match f(self).await {
+            Ok(value) => value,
+            Err(e) => {
+                self.transaction_snapshot = None;
+                self.mls_backend.key_store().rollback_transaction().await?;
+                return Err(e);
+            }
+        };
+        // rolls everything back when failing
+        self.commit_transaction().await?;
+        Ok(value)
+    }
+
+    /// Fails when a test set [MlsCentral::failing_step] to `step`, e.g. to check that an operation interrupted between
+    /// two writes leaves nothing behind
+    #[cfg(test)]
+    pub(crate) fn fail_at(&mut self, step: &'static str) -> CryptoResult<()> {
+        if self.failing_step == Some(step) {
+            self.failing_step = None;
+            return Err(crate::CryptoError::ImplementationError);
+        }
+        Ok(())
+    }
+}
+
+impl CoreCrypto {
+    /// Runs `f` within a transaction: every keystore write it makes is persisted at once when it succeeds and they
+    /// are all discarded, along with the in-memory changes, when it fails.
+    ///
+    /// # Example
+    /// ```ignore
+    /// cc.context(|ctx| Box::pin(async move {
+    ///     ctx.new_conversation(&id, MlsCredentialType::Basic, config).await?;
+    ///     ctx.add_members_to_conversation(&id, key_packages).await
+    /// })).await?;
+    /// ```
+    ///
+    /// # Errors
+    /// If a transaction is already in progress or if `f` fails, in which case its error is returned
+    pub async fn context<T, F>(&mut self, f: F) -> CryptoResult<T>
+    where
+        F: for<'a> FnOnce(&'a mut CoreCrypto) -> ContextFuture<'a, T>,
+    {
+        self.begin_transaction().await?;
+        let result = match f(self).await {
+            // rolls the MLS state back on its own when failing
+            Ok(value) => self.commit_transaction().await.map(|_| value),
+            Err(e) => self.rollback_transaction().await.and(Err(e)),
+        };
+        #[cfg(feature = "proteus")]
+        if result.is_err() {
+            self.proteus_reload_sessions().await?;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{prelude::*, test_utils::*, CoreCrypto};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn context_should_persist_on_success(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice", "bob"], move |[alice_central, bob_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                let mut alice_cc = CoreCrypto::from(alice_central.mls_central);
+
+                let cfg = case.cfg.clone();
+                let id2 = id.clone();
+                alice_cc
+                    .context(move |ctx| {
+                        Box::pin(async move {
+                            ctx.new_conversation(&id2, case.credential_type, cfg).await?;
+                            ctx.add_members_to_conversation(&id2, vec![bob_kp]).await?;
+                            ctx.commit_accepted(&id2).await
+                        })
+                    })
+                    .await
+                    .unwrap();
+
+                // state survives a restart
+                alice_cc.restore_from_disk().await.unwrap();
+                assert_eq!(alice_cc.members_count(&id).await, 2);
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn context_should_rollback_on_failure(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let mut alice_cc = CoreCrypto::from(alice_central.mls_central);
+                let cfg = case.cfg.clone();
+                let id2 = id.clone();
+                let result = alice_cc
+                    .context(move |ctx| {
+                        Box::pin(async move {
+                            ctx.new_conversation(&id2, case.credential_type, cfg).await?;
+                            // fails since there's no such conversation
+                            ctx.commit_accepted(&b"unknown".to_vec()).await
+                        })
+                    })
+                    .await;
+                assert!(matches!(result, Err(CryptoError::ConversationNotFound(_))));
+
+                // nothing got persisted nor remains in memory
+                assert!(!alice_cc.conversation_exists(&id).await);
+                alice_cc.restore_from_disk().await.unwrap();
+                assert!(!alice_cc.conversation_exists(&id).await);
+
+                // another transaction can be started
+                alice_cc.begin_transaction().await.unwrap();
+                alice_cc.commit_transaction().await.unwrap();
+            })
+        })
+        .await
+    }
+}
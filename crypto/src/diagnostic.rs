@@ -0,0 +1,401 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! A report of the local state to attach to a support ticket, typically when a user can't decrypt the messages of a
+//! conversation anymore. It only describes the state (epochs, member counts, pending proposals, number of records in
+//! the keystore...) and never contains key material: no private key, no secret, no serialized group. Identifiers are
+//! redacted according to a [RedactionLevel] and the number of conversations described is bounded so that the report
+//! stays small enough to be sent.
+
+use core_crypto_keystore::entities::{
+    E2eiEnrollment, MlsCredential, MlsEncryptionKeyPair, MlsEpochEncryptionKeyPair, MlsHpkePrivateKey, MlsKeyPackage,
+    MlsPendingMessage, MlsPskBundle, MlsSignatureKeyPair, PersistedMlsGroup, PersistedMlsPendingGroup,
+};
+use openmls_traits::{crypto::OpenMlsCrypto, types::HashType, OpenMlsCryptoProvider};
+use serde_json::{json, Value};
+
+use crate::{
+    prelude::{CryptoResult, MlsConversationHealth, MlsConversationSummary, MlsError},
+    CoreCrypto,
+};
+
+/// Maximum number of conversations described in a report, the others are only counted
+pub const DIAGNOSTIC_REPORT_MAX_CONVERSATIONS: usize = 256;
+
+/// Length, in bytes, of the hashes replacing the identifiers with [RedactionLevel::Hashed]
+const REDACTED_ID_LEN: usize = 8;
+
+/// How the identifiers (client id, conversation ids) appear in a diagnostic report, see
+/// [CoreCrypto::generate_diagnostic_report]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RedactionLevel {
+    /// Identifiers appear as is, hex encoded
+    None = 1,
+    /// Identifiers are replaced by the beginning of their SHA-256 hash. Reports of different clients can still be
+    /// matched, e.g. the ones of every member of a broken conversation, but the identifiers can't be read back
+    #[default]
+    Hashed = 2,
+    /// Identifiers are left out
+    Full = 3,
+}
+
+impl CoreCrypto {
+    /// Describes the local state to help support figure out why a conversation can't be decrypted anymore: epoch,
+    /// member count, pending proposals and commit and health of every conversation along with the number of records
+    /// of each kind in the keystore, and the slow keystore operations when they are logged (see
+    /// [crate::prelude::MlsCentral::set_keystore_slow_operation_threshold]). Key material never appears in the report. At most
+    /// [DIAGNOSTIC_REPORT_MAX_CONVERSATIONS] conversations are described, the `conversations_truncated` field tells
+    /// when there were more.
+    ///
+    /// # Arguments
+    /// * `redaction` - how identifiers appear in the report
+    ///
+    /// # Return type
+    /// The report, as a JSON object
+    ///
+    /// # Errors
+    /// KeyStore errors. A conversation which can't be restored does not fail the report, the error is described in
+    /// its place instead
+    pub async fn generate_diagnostic_report(&mut self, redaction: RedactionLevel) -> CryptoResult<String> {
+        let client_id = match self.mls.client_id() {
+            Ok(id) => self.redact(redaction, &id)?,
+            Err(_) => Value::Null,
+        };
+
+        let summaries = self.mls.list_conversations().await?;
+        let mut conversations = Vec::with_capacity(summaries.len().min(DIAGNOSTIC_REPORT_MAX_CONVERSATIONS));
+        for summary in summaries.iter().take(DIAGNOSTIC_REPORT_MAX_CONVERSATIONS) {
+            conversations.push(self.describe_conversation(redaction, summary).await?);
+        }
+
+        let report = json!({
+            "version": crate::build_metadata::VERSION,
+            "redaction": redaction as u8,
+            "client_id": client_id,
+            "conversations": conversations,
+            "conversations_total": summaries.len(),
+            "conversations_truncated": summaries.len() > DIAGNOSTIC_REPORT_MAX_CONVERSATIONS,
+            "keystore": self.count_keystore_entities().await?,
+            "keystore_slow_operations": self.describe_keystore_slow_operations(),
+            "proteus": self.describe_proteus().await?,
+        });
+        Ok(report.to_string())
+    }
+
+    async fn describe_conversation(
+        &mut self,
+        redaction: RedactionLevel,
+        summary: &MlsConversationSummary,
+    ) -> CryptoResult<Value> {
+        let mut description = json!({
+            "id": self.redact(redaction, &summary.id)?,
+            "parent_id": match &summary.parent_id {
+                Some(parent_id) => self.redact(redaction, parent_id)?,
+                None => Value::Null,
+            },
+            "ciphersuite": u16::from(summary.ciphersuite),
+            "last_persisted": summary.last_persisted,
+            "health": match self.mls.conversation_health(&summary.id) {
+                MlsConversationHealth::Healthy => json!({ "status": "healthy" }),
+                MlsConversationHealth::Suspect { failures } => json!({ "status": "suspect", "failures": failures }),
+                MlsConversationHealth::Broken { failures } => json!({ "status": "broken", "failures": failures }),
+            },
+        });
+        match self.mls.get_conversation(&summary.id).await {
+            Ok(conversation) => {
+                let conversation = conversation.read().await;
+                description["epoch"] = conversation.group.epoch().as_u64().into();
+                description["member_count"] = conversation.group.members().count().into();
+                description["pending_proposals"] = conversation.group.pending_proposals().count().into();
+                description["pending_commit"] = conversation.group.pending_commit().is_some().into();
+            }
+            Err(e) => {
+                // fall back on what has been indexed when it was last persisted
+                description["epoch"] = summary.epoch.into();
+                description["member_count"] = summary.member_count.into();
+                description["error"] = e.to_string().into();
+            }
+        }
+        Ok(description)
+    }
+
+    async fn count_keystore_entities(&self) -> CryptoResult<Value> {
+        let keystore = self.mls.mls_backend.key_store();
+        Ok(json!({
+            "credential": keystore.count::<MlsCredential>().await?,
+            "encryption_keypair": keystore.count::<MlsEncryptionKeyPair>().await?,
+            "epoch_encryption_keypair": keystore.count::<MlsEpochEncryptionKeyPair>().await?,
+            "enrollment": keystore.count::<E2eiEnrollment>().await?,
+            "group": keystore.count::<PersistedMlsGroup>().await?,
+            "hpke_private_key": keystore.count::<MlsHpkePrivateKey>().await?,
+            "key_package": keystore.count::<MlsKeyPackage>().await?,
+            "pending_group": keystore.count::<PersistedMlsPendingGroup>().await?,
+            "pending_messages": keystore.count::<MlsPendingMessage>().await?,
+            "psk_bundle": keystore.count::<MlsPskBundle>().await?,
+            "signature_keypair": keystore.count::<MlsSignatureKeyPair>().await?,
+        }))
+    }
+
+    /// See [crate::prelude::MlsCentral::set_keystore_slow_operation_threshold]
+    fn describe_keystore_slow_operations(&self) -> Value {
+        self.mls
+            .mls_backend
+            .key_store()
+            .slow_operations()
+            .into_iter()
+            .map(|op| {
+                json!({
+                    "entity": op.entity,
+                    "operation": format!("{:?}", op.operation),
+                    "duration_micros": op.duration.as_micros() as u64,
+                    "entities": op.entities,
+                    "bytes": op.bytes,
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "proteus")]
+    async fn describe_proteus(&self) -> CryptoResult<Value> {
+        use core_crypto_keystore::entities::{ProteusPrekey, ProteusSession};
+
+        let keystore = self.mls.mls_backend.key_store();
+        Ok(json!({
+            "initialized": self.proteus.is_some(),
+            "sessions": keystore.count::<ProteusSession>().await?,
+            "prekeys": keystore.count::<ProteusPrekey>().await?,
+        }))
+    }
+
+    #[cfg(not(feature = "proteus"))]
+    async fn describe_proteus(&self) -> CryptoResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn redact(&self, redaction: RedactionLevel, id: &[u8]) -> CryptoResult<Value> {
+        Ok(match redaction {
+            RedactionLevel::None => hex::encode(id).into(),
+            RedactionLevel::Hashed => {
+                let hash = self
+                    .mls
+                    .mls_backend
+                    .crypto()
+                    .hash(HashType::Sha2_256, id)
+                    .map_err(MlsError::from)?;
+                hex::encode(&hash[..REDACTED_ID_LEN]).into()
+            }
+            RedactionLevel::Full => Value::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use base64::Engine as _;
+    use core_crypto_keystore::entities::{MlsEncryptionKeyPair, MlsHpkePrivateKey, MlsSignatureKeyPair};
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*, CoreCrypto};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Every encoding a secret could be leaked with in a JSON report
+    fn encodings(secret: &[u8]) -> Vec<String> {
+        vec![
+            hex::encode(secret),
+            hex::encode_upper(secret),
+            base64::prelude::BASE64_STANDARD.encode(secret),
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(secret),
+            serde_json::to_string(secret)
+                .unwrap()
+                .trim_matches(['[', ']'])
+                .to_string(),
+        ]
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn diagnostic_report_should_describe_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.new_update_proposal(&id).await.unwrap();
+
+                    let mut cc = CoreCrypto::from(alice_central.mls_central);
+                    let report = cc.generate_diagnostic_report(RedactionLevel::None).await.unwrap();
+                    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+                    assert_eq!(report["conversations_total"], 1);
+                    assert_eq!(report["conversations_truncated"], false);
+                    let conversation = &report["conversations"][0];
+                    assert_eq!(conversation["id"], hex::encode(&id));
+                    assert_eq!(conversation["epoch"], 1);
+                    assert_eq!(conversation["member_count"], 2);
+                    assert_eq!(conversation["pending_proposals"], 1);
+                    assert_eq!(conversation["pending_commit"], false);
+                    assert_eq!(conversation["health"]["status"], "healthy");
+                    assert_eq!(report["keystore"]["group"], 1);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn diagnostic_report_should_redact_identifiers(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let mut cc = CoreCrypto::from(alice_central.mls_central);
+                cc.new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                let client_id = cc.client_id().unwrap();
+
+                let hashed = cc.generate_diagnostic_report(RedactionLevel::Hashed).await.unwrap();
+                let full = cc.generate_diagnostic_report(RedactionLevel::Full).await.unwrap();
+                for report in [&hashed, &full] {
+                    for identifier in [id.as_slice(), client_id.as_slice()] {
+                        assert!(!report.contains(&hex::encode(identifier)));
+                    }
+                }
+
+                let hashed: serde_json::Value = serde_json::from_str(&hashed).unwrap();
+                assert_eq!(hashed["conversations"][0]["id"].as_str().unwrap().len(), 16);
+                // the same conversation has the same pseudonym in every report
+                let again = cc.generate_diagnostic_report(RedactionLevel::Hashed).await.unwrap();
+                let again: serde_json::Value = serde_json::from_str(&again).unwrap();
+                assert_eq!(hashed["conversations"][0]["id"], again["conversations"][0]["id"]);
+
+                let full: serde_json::Value = serde_json::from_str(&full).unwrap();
+                assert!(full["client_id"].is_null());
+                assert!(full["conversations"][0]["id"].is_null());
+                assert_eq!(full["conversations"][0]["epoch"], 0);
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn diagnostic_report_should_list_slow_keystore_operations(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let mut cc = CoreCrypto::from(alice_central.mls_central);
+                let report = cc.generate_diagnostic_report(RedactionLevel::None).await.unwrap();
+                let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+                assert!(report["keystore_slow_operations"].as_array().unwrap().is_empty());
+
+                cc.set_keystore_slow_operation_threshold(Some(std::time::Duration::ZERO));
+                cc.new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                let report = cc.generate_diagnostic_report(RedactionLevel::None).await.unwrap();
+                let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+                let operations = report["keystore_slow_operations"].as_array().unwrap();
+                let group_write = operations
+                    .iter()
+                    .find(|op| op["entity"] == "PersistedMlsGroup" && op["operation"] == "Save")
+                    .unwrap();
+                assert_eq!(group_write["entities"], 1);
+                assert!(group_write["bytes"].as_u64().unwrap() > 0);
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn diagnostic_report_should_not_contain_secrets(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .get_or_create_client_keypackages(case.ciphersuite(), case.credential_type, 2)
+                        .await
+                        .unwrap();
+
+                    let keystore = alice_central.mls_central.mls_backend.key_store();
+                    let mut secrets = vec![];
+                    for kp in keystore
+                        .find_all::<MlsSignatureKeyPair>(Default::default())
+                        .await
+                        .unwrap()
+                    {
+                        secrets.push(kp.keypair.clone());
+                    }
+                    for kp in keystore
+                        .find_all::<MlsEncryptionKeyPair>(Default::default())
+                        .await
+                        .unwrap()
+                    {
+                        secrets.push(kp.sk.clone());
+                    }
+                    for kp in keystore
+                        .find_all::<MlsHpkePrivateKey>(Default::default())
+                        .await
+                        .unwrap()
+                    {
+                        secrets.push(kp.sk.clone());
+                    }
+                    let exporter = alice_central.mls_central.export_secret_key(&id, 32).await.unwrap();
+                    secrets.push(exporter);
+                    assert!(secrets.len() > 3);
+
+                    let mut cc = CoreCrypto::from(alice_central.mls_central);
+                    for redaction in [RedactionLevel::None, RedactionLevel::Hashed, RedactionLevel::Full] {
+                        let report = cc.generate_diagnostic_report(redaction).await.unwrap();
+                        for secret in &secrets {
+                            for encoded in encodings(secret) {
+                                assert!(!report.contains(&encoded), "a secret leaked in {report}");
+                            }
+                        }
+                    }
+                })
+            },
+        )
+        .await
+    }
+}
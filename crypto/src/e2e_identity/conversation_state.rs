@@ -1,5 +1,5 @@
 use crate::{
-    mls::credential::ext::CredentialExt,
+    mls::{client::key_package::unix_now, credential::ext::CredentialExt},
     prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsCredentialType},
     MlsError,
 };
@@ -36,7 +36,7 @@ impl MlsCentral {
 
         let conversation_lock = conversation.read().await;
 
-        Ok(conversation_lock.e2ei_conversation_state(&self.mls_backend))
+        Ok(conversation_lock.e2ei_conversation_state(&self.mls_backend, self.clock_skew_leeway()))
     }
 
     /// Gets the e2ei conversation state from a `GroupInfo`. Useful to check if the group has e2ei
@@ -66,22 +66,38 @@ impl MlsCentral {
             Some(Node::LeafNode(ln)) => Some(ln.credential()),
             _ => None,
         });
-        Ok(compute_state(credentials, &self.mls_backend, credential_type))
+        Ok(compute_state(
+            credentials,
+            &self.mls_backend,
+            credential_type,
+            self.clock_skew_leeway(),
+        ))
     }
 }
 
 impl MlsConversation {
-    fn e2ei_conversation_state(&self, backend: &MlsCryptoProvider) -> E2eiConversationState {
-        compute_state(self.group.members_credentials(), backend, MlsCredentialType::X509)
+    fn e2ei_conversation_state(
+        &self,
+        backend: &MlsCryptoProvider,
+        leeway: std::time::Duration,
+    ) -> E2eiConversationState {
+        compute_state(
+            self.group.members_credentials(),
+            backend,
+            MlsCredentialType::X509,
+            leeway,
+        )
     }
 }
 
 /// _credential_type will be used in the future to get the usage of VC Credentials, even Basics one.
 /// Right now though, we do not need anything other than X509 so let's keep things simple.
+/// `leeway` is the clock skew tolerated when checking the validity period of certificates
 pub(crate) fn compute_state<'a>(
     credentials: impl Iterator<Item = &'a Credential>,
     backend: &MlsCryptoProvider,
     _credential_type: MlsCredentialType,
+    leeway: std::time::Duration,
 ) -> E2eiConversationState {
     let mut is_e2ei = false;
 
@@ -92,9 +108,7 @@ pub(crate) fn compute_state<'a>(
             let invalid_identity = cert.extract_identity().is_err();
 
             // TODO: this is incomplete and has to be applied to the whole cert chain
-            use openmls_x509_credential::X509Ext as _;
-            let is_time_valid = cert.is_time_valid().unwrap_or(false);
-            let is_time_invalid = !is_time_valid;
+            let is_time_invalid = !is_cert_time_valid(&cert, leeway);
             let is_revoked_or_invalid = backend
                 .authentication_service()
                 .borrow()
@@ -124,6 +138,17 @@ pub(crate) fn compute_state<'a>(
     }
 }
 
+/// Verifies that now lies within the certificate's validity period, widened by `leeway` on both sides
+fn is_cert_time_valid(cert: &x509_cert::Certificate, leeway: std::time::Duration) -> bool {
+    let Ok(now) = unix_now() else {
+        return false;
+    };
+    let validity = &cert.tbs_certificate.validity;
+    let not_before = validity.not_before.to_unix_duration();
+    let not_after = validity.not_after.to_unix_duration();
+    now + leeway >= not_before && now <= not_after + leeway
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::e2e_identity::rotate::tests::all::failsafe_ctx;
@@ -292,6 +317,13 @@ pub mod tests {
                             .invite_all(&case, &id, [&mut bob_central.mls_central])
                             .await
                             .unwrap();
+                        // do not tolerate any clock skew, otherwise the certificate would be considered valid
+                        alice_central
+                            .mls_central
+                            .set_clock_skew_leeway(core::time::Duration::ZERO);
+                        bob_central
+                            .mls_central
+                            .set_clock_skew_leeway(core::time::Duration::ZERO);
 
                         let expiration_time = core::time::Duration::from_secs(14);
                         let start = fluvio_wasm_timer::Instant::now();
@@ -369,6 +401,10 @@ pub mod tests {
                         .new_conversation(&id, case.credential_type, case.cfg.clone())
                         .await
                         .unwrap();
+                    // do not tolerate any clock skew, otherwise the certificate would be considered valid
+                    alice_central
+                        .mls_central
+                        .set_clock_skew_leeway(core::time::Duration::ZERO);
 
                     let expiration_time = core::time::Duration::from_secs(14);
                     let start = fluvio_wasm_timer::Instant::now();
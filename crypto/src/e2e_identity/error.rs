@@ -26,6 +26,9 @@ pub enum E2eIdentityError {
     /// Failed converting the MLS signature key for the e2ei enrollment
     #[error("Failed converting the MLS signature key for the e2ei enrollment")]
     InvalidSignatureKey,
+    /// The renewed certificate does not certify the current signature key
+    #[error("The renewed certificate was issued for another signature key than the current one")]
+    RenewalKeyMismatch,
     /// Enrollment methods are called out of order
     #[error("Enrollment methods are called out of order: {0}")]
     OutOfOrderEnrollment(&'static str),
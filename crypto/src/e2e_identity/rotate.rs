@@ -1,13 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use openmls::prelude::{KeyPackage, KeyPackageRef, MlsCredentialType as OpenMlsCredential};
 use openmls_traits::OpenMlsCryptoProvider;
 
-use core_crypto_keystore::{entities::MlsKeyPackage, CryptoKeystoreMls};
+use core_crypto_keystore::{
+    entities::{EntityFindParams, MlsKeyPackage, MlsPendingRotation},
+    CryptoKeystoreMls,
+};
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
-    mls::credential::{ext::CredentialExt, x509::CertificatePrivateKey, CredentialBundle},
+    cancellation::{check_cancelled, CancellationToken},
+    mls::{
+        conversation::batch_commit::{commit_concurrently, DEFAULT_COMMIT_CONCURRENCY},
+        credential::{ext::CredentialExt, x509::CertificatePrivateKey, CredentialBundle},
+    },
     prelude::{
         CertificateBundle, Client, ConversationId, CryptoError, CryptoResult, E2eIdentityError, E2eiEnrollment,
         MlsCentral, MlsCiphersuite, MlsCommitBundle, MlsConversation, MlsCredentialType,
@@ -96,19 +103,114 @@ impl MlsCentral {
         )
     }
 
+    /// Generates an E2EI enrollment instance for renewing the X509 certificate of a E2EI client before it expires.
+    /// Unlike [MlsCentral::e2ei_new_rotate_enrollment], the identity in the certificate is kept as is: the CSR is
+    /// issued over the current signature keypair with the current display name and handle. Once the enrollment is
+    /// finished, use the instance in [MlsCentral::e2ei_renew] to install the renewed certificate.
+    pub async fn e2ei_new_renewal_enrollment(
+        &self,
+        team: Option<String>,
+        expiry_sec: u32,
+        ciphersuite: MlsCiphersuite,
+    ) -> CryptoResult<E2eiEnrollment> {
+        self.e2ei_new_rotate_enrollment(None, None, team, expiry_sec, ciphersuite)
+            .await
+    }
+
+    /// Installs the certificate renewed with an enrollment from [MlsCentral::e2ei_new_renewal_enrollment]. Like
+    /// [MlsCentral::e2ei_rotate_all], it creates a commit in all local conversations for changing the credential and
+    /// replaces the KeyPackages, but the leaf signature key stays the same.
+    ///
+    /// # Errors
+    /// [E2eIdentityError::RenewalKeyMismatch] when the renewed certificate was issued for another key than the current
+    /// one, in which case nothing is changed
+    pub async fn e2ei_renew(
+        &mut self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        new_key_packages_count: usize,
+    ) -> CryptoResult<MlsRotateBundle> {
+        let signature_scheme = enrollment.ciphersuite.signature_algorithm();
+        let cb = self
+            .mls_client()?
+            .find_most_recent_credential_bundle(signature_scheme, MlsCredentialType::X509)
+            .ok_or(E2eIdentityError::MissingExistingClient(MlsCredentialType::X509))?;
+        let current_public_key = cb.signature_key.public().to_vec();
+
+        self.rotate_credential(
+            enrollment,
+            certificate_chain,
+            new_key_packages_count,
+            Some(current_public_key),
+            None,
+        )
+        .await
+    }
+
     /// Creates a commit in all local conversations for changing the credential. Requires first
     /// having enrolled a new X509 certificate with either [MlsCentral::e2ei_new_activation_enrollment]
     /// or [MlsCentral::e2ei_new_rotate_enrollment]
+    ///
+    /// Once [MlsCentral::commit_accepted] has been called for every one of those commits, the superseded KeyPackages
+    /// are deleted locally, even after a restart. Without any conversation there is nothing telling when they can be,
+    /// hence they are kept: use [MlsCentral::delete_keypackages] once they have been deleted remotely
     pub async fn e2ei_rotate_all(
         &mut self,
         enrollment: E2eiEnrollment,
         certificate_chain: String,
         new_key_packages_count: usize,
     ) -> CryptoResult<MlsRotateBundle> {
+        self.rotate_credential(enrollment, certificate_chain, new_key_packages_count, None, None)
+            .await
+    }
+
+    /// Same as [MlsCentral::e2ei_rotate_all] but stops before the next conversation once `cancellation` is cancelled,
+    /// failing with [CryptoError::OperationCancelled]. The commits created so far are then cleared, hence no
+    /// conversation is left with a pending commit. The new credential is kept though, it is the most recent one from
+    /// now on and the next rotation supersedes it
+    pub async fn e2ei_rotate_all_cancellable(
+        &mut self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        new_key_packages_count: usize,
+        cancellation: &CancellationToken,
+    ) -> CryptoResult<MlsRotateBundle> {
+        self.rotate_credential(
+            enrollment,
+            certificate_chain,
+            new_key_packages_count,
+            None,
+            Some(cancellation),
+        )
+        .await
+    }
+
+    /// Rotates the credential to the given certificate, which has to certify `expected_public_key` if any
+    async fn rotate_credential(
+        &mut self,
+        enrollment: E2eiEnrollment,
+        certificate_chain: String,
+        new_key_packages_count: usize,
+        expected_public_key: Option<Vec<u8>>,
+        cancellation: Option<&CancellationToken>,
+    ) -> CryptoResult<MlsRotateBundle> {
+        check_cancelled(cancellation)?;
         let sk = enrollment.get_sign_key_for_mls()?;
         let cs = enrollment.ciphersuite;
         let certificate_chain = enrollment.certificate_response(certificate_chain).await?;
 
+        if let Some(expected_public_key) = expected_public_key {
+            use wire_e2e_identity::prelude::WireIdentityReader as _;
+            let leaf = certificate_chain.first().ok_or(CryptoError::InvalidIdentity)?;
+            let public_key = leaf
+                .as_slice()
+                .extract_public_key()
+                .map_err(|_| CryptoError::InvalidIdentity)?;
+            if public_key != expected_public_key {
+                return Err(E2eIdentityError::RenewalKeyMismatch.into());
+            }
+        }
+
         let private_key = CertificatePrivateKey {
             value: sk,
             signature_scheme: cs.signature_algorithm(),
@@ -128,7 +230,7 @@ impl MlsCentral {
             .save_new_x509_credential_bundle(&self.mls_backend, cs.signature_algorithm(), cert_bundle)
             .await?;
 
-        let commits = self.e2ei_update_all(&new_cb).await?;
+        let commits = self.e2ei_update_all(&new_cb, cancellation).await?;
 
         let key_package_refs_to_remove = self.find_key_packages_to_remove(&new_cb).await?;
 
@@ -137,6 +239,16 @@ impl MlsCentral {
             .generate_new_keypackages(&self.mls_backend, cs, &new_cb, new_key_packages_count)
             .await?;
 
+        if !commits.is_empty() {
+            // keyed by a random id so that another rotation started in the meantime does not replace this one
+            let rotation = PendingRotation {
+                conversations: commits.keys().cloned().collect(),
+                key_package_refs_to_remove: key_package_refs_to_remove.clone(),
+            };
+            let id = self.random_bytes(16)?;
+            self.mls_backend.key_store().save(rotation.to_entity(id)?).await?;
+        }
+
         Ok(MlsRotateBundle {
             commits,
             new_key_packages,
@@ -145,6 +257,52 @@ impl MlsCentral {
         })
     }
 
+    /// Tracks the progress of the pending rotations and deletes the KeyPackages superseded by one once the commit of
+    /// each of its conversations has been accepted
+    pub(crate) async fn rotation_commit_accepted(&mut self, id: &ConversationId) -> CryptoResult<()> {
+        let rotations = self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsPendingRotation>(EntityFindParams::default())
+            .await?;
+        for entity in rotations {
+            let mut rotation = PendingRotation::try_from(&entity)?;
+            if !rotation.conversations.remove(id) {
+                continue;
+            }
+            let rotation_id = entity.id.clone();
+            if !rotation.conversations.is_empty() {
+                self.mls_backend
+                    .key_store()
+                    .save(rotation.to_entity(rotation_id)?)
+                    .await?;
+                continue;
+            }
+
+            let refs = rotation.key_package_refs_to_remove;
+            self.within_transaction(|central| {
+                Box::pin(async move {
+                    central
+                        .mls_backend
+                        .key_store()
+                        .remove::<MlsPendingRotation, _>(&rotation_id)
+                        .await?;
+                    if refs.is_empty() {
+                        return Ok(());
+                    }
+                    central
+                        .mls_client
+                        .as_mut()
+                        .ok_or(CryptoError::MlsNotInitialized)?
+                        .prune_keypackages_and_credential(&central.mls_backend, &refs)
+                        .await
+                })
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn find_key_packages_to_remove(&self, cb: &CredentialBundle) -> CryptoResult<Vec<KeyPackageRef>> {
         let nb_kp = self.mls_backend.key_store().count::<MlsKeyPackage>().await?;
         let kps: Vec<KeyPackage> = self.mls_backend.key_store().mls_fetch_keypackages(nb_kp as u32).await?;
@@ -176,17 +334,31 @@ impl MlsCentral {
     async fn e2ei_update_all(
         &mut self,
         cb: &CredentialBundle,
+        cancellation: Option<&CancellationToken>,
     ) -> CryptoResult<HashMap<ConversationId, MlsCommitBundle>> {
         let all_conversations = self.get_all_conversations().await?;
 
-        let mut commits = HashMap::with_capacity(all_conversations.len());
-        for conv in all_conversations {
-            let mut conv = conv.write().await;
-            let id = conv.id().clone();
-            let commit = conv.e2ei_rotate(&self.mls_backend, self.mls_client()?, cb).await?;
-            let _ = commits.insert(id, commit);
+        let client = self.mls_client()?;
+        let backend = &self.mls_backend;
+        let commits = commit_concurrently(all_conversations, DEFAULT_COMMIT_CONCURRENCY, |mut conv| async move {
+            check_cancelled(cancellation)?;
+            conv.e2ei_rotate(backend, client, cb).await
+        })
+        .await;
+
+        if cancellation.is_some_and(CancellationToken::is_cancelled) {
+            for (id, commit) in commits {
+                if commit.is_ok() {
+                    self.clear_pending_commit(&id).await?;
+                }
+            }
+            return Err(CryptoError::OperationCancelled);
         }
-        Ok(commits)
+
+        commits
+            .into_iter()
+            .map(|(id, commit)| commit.map(|commit| (id, commit)))
+            .collect()
     }
 
     #[cfg(test)]
@@ -219,6 +391,46 @@ impl MlsConversation {
     }
 }
 
+/// Rotation started by [MlsCentral::e2ei_rotate_all] whose commits have not all been accepted yet, persisted as a
+/// [MlsPendingRotation]
+#[derive(Debug, Clone)]
+struct PendingRotation {
+    /// Conversations whose rotation commit has not been accepted yet
+    conversations: HashSet<ConversationId>,
+    /// KeyPackages to delete once it's the case for all of them
+    key_package_refs_to_remove: Vec<KeyPackageRef>,
+}
+
+impl PendingRotation {
+    fn to_entity(&self, id: Vec<u8>) -> CryptoResult<MlsPendingRotation> {
+        let key_package_refs = self
+            .key_package_refs_to_remove
+            .iter()
+            .map(|r| r.as_slice())
+            .collect::<Vec<_>>();
+        Ok(MlsPendingRotation {
+            id,
+            conversation_ids: serde_json::to_vec(&self.conversations)
+                .map_err(MlsError::MlsKeystoreSerializationError)?,
+            key_package_refs: serde_json::to_vec(&key_package_refs).map_err(MlsError::MlsKeystoreSerializationError)?,
+        })
+    }
+}
+
+impl TryFrom<&MlsPendingRotation> for PendingRotation {
+    type Error = CryptoError;
+
+    fn try_from(entity: &MlsPendingRotation) -> CryptoResult<Self> {
+        let key_package_refs: Vec<Vec<u8>> =
+            serde_json::from_slice(&entity.key_package_refs).map_err(MlsError::MlsKeystoreSerializationError)?;
+        Ok(Self {
+            conversations: serde_json::from_slice(&entity.conversation_ids)
+                .map_err(MlsError::MlsKeystoreSerializationError)?,
+            key_package_refs_to_remove: key_package_refs.iter().map(|r| KeyPackageRef::from_slice(r)).collect(),
+        })
+    }
+}
+
 /// Result returned after rotating the Credential of the current client in all the local conversations
 #[derive(Debug, Clone)]
 pub struct MlsRotateBundle {
@@ -226,7 +438,8 @@ pub struct MlsRotateBundle {
     pub commits: HashMap<ConversationId, MlsCommitBundle>,
     /// Fresh KeyPackages with the new Credential
     pub new_key_packages: Vec<KeyPackage>,
-    /// All the now deprecated KeyPackages. Once deleted remotely, delete them locally with [MlsCentral::delete_keypackages]
+    /// All the now deprecated KeyPackages, to be deleted remotely. They are deleted locally once all the commits have
+    /// been accepted
     pub key_package_refs_to_remove: Vec<KeyPackageRef>,
     /// New CRL distribution points that appeared by the introduction of a new credential
     pub crl_new_distribution_points: Option<Vec<String>>,
@@ -287,7 +500,7 @@ pub mod tests {
     use tls_codec::Deserialize;
     use wasm_bindgen_test::*;
 
-    use core_crypto_keystore::entities::{EntityFindParams, MlsCredential};
+    use core_crypto_keystore::entities::{EntityFindParams, MlsCredential, MlsPendingRotation};
 
     use crate::{
         e2e_identity::tests::*,
@@ -448,21 +661,6 @@ pub mod tests {
                         // and a new Credential has been persisted in the keystore
                         assert_eq!(after_rotate.credential - before_rotate.credential, 1);
 
-                        for (id, commit) in rotate_bundle.commits.into_iter() {
-                            let decrypted = bob_central
-                                .mls_central
-                                .decrypt_message(&id, commit.commit.to_bytes().unwrap())
-                                .await
-                                .unwrap();
-                            alice_central.mls_central.verify_sender_identity(&case, &decrypted);
-
-                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
-                            alice_central
-                                .mls_central
-                                .verify_local_credential_rotated(&id, NEW_HANDLE, NEW_DISPLAY_NAME)
-                                .await;
-                        }
-
                         // Verify that all the new KeyPackages contain the new identity
                         let new_credentials = rotate_bundle
                             .new_key_packages
@@ -475,9 +673,9 @@ pub mod tests {
                             assert_eq!(identity.handle, format!("wireapp://%40{NEW_HANDLE}@world.com"));
                         }
 
-                        // Alice has to delete her old KeyPackages
+                        // Alice's old KeyPackages are deleted once all the commits are accepted
 
-                        // But first let's verify the previous credential material is present
+                        // But until then the previous credential material is present
                         assert!(alice_central
                             .mls_central
                             .find_credential_bundle(
@@ -505,13 +703,22 @@ pub mod tests {
                             .await
                             .is_some());
 
-                        // Checks are done, now let's delete ALL the deprecated KeyPackages.
+                        // Checks are done, accepting ALL the commits deletes the deprecated KeyPackages.
                         // This should have the consequence to purge the previous credential material as well.
-                        alice_central
-                            .mls_central
-                            .delete_keypackages(&rotate_bundle.key_package_refs_to_remove[..])
-                            .await
-                            .unwrap();
+                        for (id, commit) in rotate_bundle.commits.into_iter() {
+                            let decrypted = bob_central
+                                .mls_central
+                                .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                                .await
+                                .unwrap();
+                            alice_central.mls_central.verify_sender_identity(&case, &decrypted);
+
+                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                            alice_central
+                                .mls_central
+                                .verify_local_credential_rotated(&id, NEW_HANDLE, NEW_DISPLAY_NAME)
+                                .await;
+                        }
 
                         // Alice should just have the number of X509 KeyPackages she requested
                         let nb_x509_kp = alice_central
@@ -569,6 +776,98 @@ pub mod tests {
             .await
         }
 
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn cancelled_rotation_should_change_nothing(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let x509_test_chain_arc =
+                            failsafe_ctx(&mut [&mut alice_central, &mut bob_central], case.signature_scheme()).await;
+                        let x509_test_chain = x509_test_chain_arc.as_ref().as_ref().unwrap();
+
+                        let mut ids = vec![];
+                        for _ in 0..3 {
+                            let id = conversation_id();
+                            alice_central
+                                .mls_central
+                                .new_conversation(&id, case.credential_type, case.cfg.clone())
+                                .await
+                                .unwrap();
+                            alice_central
+                                .mls_central
+                                .invite_all(&case, &id, [&mut bob_central.mls_central])
+                                .await
+                                .unwrap();
+                            ids.push(id)
+                        }
+
+                        fn init(wrapper: E2eiInitWrapper) -> InitFnReturn<'_> {
+                            Box::pin(async move {
+                                let E2eiInitWrapper { cc, case } = wrapper;
+                                let cs = case.ciphersuite();
+                                match case.credential_type {
+                                    MlsCredentialType::Basic => cc.e2ei_new_activation_enrollment(
+                                        E2EI_DISPLAY_NAME.to_string(),
+                                        E2EI_HANDLE.to_string(),
+                                        Some(TEAM.to_string()),
+                                        E2EI_EXPIRY,
+                                        cs,
+                                    ),
+                                    MlsCredentialType::X509 => {
+                                        cc.e2ei_new_rotate_enrollment(
+                                            Some(E2EI_DISPLAY_NAME.to_string()),
+                                            Some(E2EI_HANDLE.to_string()),
+                                            Some(TEAM.to_string()),
+                                            E2EI_EXPIRY,
+                                            cs,
+                                        )
+                                        .await
+                                    }
+                                }
+                            })
+                        }
+
+                        let is_renewal = case.credential_type == MlsCredentialType::X509;
+                        let (enrollment, cert) = e2ei_enrollment(
+                            &mut alice_central,
+                            &case,
+                            x509_test_chain,
+                            None,
+                            is_renewal,
+                            init,
+                            noop_restore,
+                        )
+                        .await
+                        .unwrap();
+
+                        let before = alice_central.mls_central.count_entities().await;
+                        let cancellation = CancellationToken::new();
+                        cancellation.cancel();
+                        let error = alice_central
+                            .mls_central
+                            .e2ei_rotate_all_cancellable(enrollment, cert, 5, &cancellation)
+                            .await
+                            .unwrap_err();
+                        assert!(matches!(error, CryptoError::OperationCancelled));
+
+                        assert_eq!(alice_central.mls_central.count_entities().await, before);
+                        for id in &ids {
+                            assert!(alice_central.mls_central.pending_commit(id).await.is_none());
+                        }
+                        assert!(alice_central
+                            .mls_central
+                            .try_talk_to(&ids[0], &mut bob_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
         #[apply(all_cred_cipher)]
         #[wasm_bindgen_test]
         pub async fn should_restore_credentials_in_order(case: TestCase) {
@@ -645,8 +944,6 @@ pub mod tests {
                         .await
                         .unwrap();
 
-                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
-
                     // So alice has a new Credential as expected
                     let cb = alice_central
                         .mls_central
@@ -657,7 +954,7 @@ pub mod tests {
                     assert_eq!(identity.display_name, NEW_DISPLAY_NAME);
                     assert_eq!(identity.handle, format!("wireapp://%40{NEW_HANDLE}@world.com"));
 
-                    // but keeps her old one since it's referenced from some KeyPackages
+                    // but keeps her old one since it's referenced from some KeyPackages until the commit is accepted
                     let old_spk = SignaturePublicKey::from(old_cb.signature_key.public());
                     let old_cb_found = alice_central
                         .mls_central
@@ -718,11 +1015,283 @@ pub mod tests {
                             .count(),
                         old_nb_identities
                     );
+
+                    // accepting the commit finally purges the old Credential
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let nb_credentials = alice_central
+                        .mls_central
+                        .mls_backend
+                        .key_store()
+                        .count::<MlsCredential>()
+                        .await
+                        .unwrap();
+                    assert_eq!(nb_credentials, 1);
+                })
+            })
+            .await
+        }
+
+        const ROTATED_HANDLE: &str = "new_alice_wire";
+        const ROTATED_DISPLAY_NAME: &str = "New Alice Smith";
+
+        /// Rotates the credential of `ctx` in all its conversations, renewing its certificate when it has one already
+        async fn rotate_all(
+            ctx: &mut ClientContext,
+            case: &TestCase,
+            x509_test_chain: &X509TestChain,
+            is_renewal: bool,
+        ) -> MlsRotateBundle {
+            fn init(wrapper: E2eiInitWrapper) -> InitFnReturn<'_> {
+                Box::pin(async move {
+                    let E2eiInitWrapper { cc, case } = wrapper;
+                    cc.e2ei_new_activation_enrollment(
+                        ROTATED_DISPLAY_NAME.to_string(),
+                        ROTATED_HANDLE.to_string(),
+                        Some(TEAM.to_string()),
+                        E2EI_EXPIRY,
+                        case.ciphersuite(),
+                    )
+                })
+            }
+            fn renew(wrapper: E2eiInitWrapper) -> InitFnReturn<'_> {
+                Box::pin(async move {
+                    let E2eiInitWrapper { cc, case } = wrapper;
+                    cc.e2ei_new_rotate_enrollment(
+                        Some(ROTATED_DISPLAY_NAME.to_string()),
+                        Some(ROTATED_HANDLE.to_string()),
+                        Some(TEAM.to_string()),
+                        E2EI_EXPIRY,
+                        case.ciphersuite(),
+                    )
+                    .await
+                })
+            }
+
+            let init: fn(E2eiInitWrapper) -> InitFnReturn<'_> = if is_renewal { renew } else { init };
+            let (enrollment, cert) = e2ei_enrollment(ctx, case, x509_test_chain, None, is_renewal, init, noop_restore)
+                .await
+                .unwrap();
+            ctx.mls_central.e2ei_rotate_all(enrollment, cert, 10).await.unwrap()
+        }
+
+        async fn count_pending_rotations(central: &MlsCentral) -> usize {
+            central
+                .mls_backend
+                .key_store()
+                .count::<MlsPendingRotation>()
+                .await
+                .unwrap()
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn pending_rotation_should_survive_restart(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let x509_test_chain_arc =
+                            failsafe_ctx(&mut [&mut alice_central, &mut bob_central], case.signature_scheme()).await;
+                        let x509_test_chain = x509_test_chain_arc.as_ref().as_ref().unwrap();
+
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let old_cb = alice_central
+                            .mls_central
+                            .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                            .await
+                            .unwrap()
+                            .clone();
+
+                        let is_renewal = case.credential_type == MlsCredentialType::X509;
+                        let rotate_bundle = rotate_all(&mut alice_central, &case, x509_test_chain, is_renewal).await;
+                        assert_eq!(count_pending_rotations(&alice_central.mls_central).await, 1);
+
+                        // Let's simulate an app restart, nothing but the keystore is left
+                        alice_central.mls_central.restore_from_disk().await.unwrap();
+                        let cid = alice_central.mls_central.client_id().unwrap();
+                        let all_credentials = alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .find_all::<MlsCredential>(EntityFindParams::default())
+                            .await
+                            .unwrap()
+                            .into_iter()
+                            .map(|c| {
+                                let credential =
+                                    openmls::prelude::Credential::tls_deserialize(&mut c.credential.as_slice())
+                                        .unwrap();
+                                (credential, c.created_at)
+                            })
+                            .collect::<Vec<_>>();
+                        let scs = HashSet::from([case.signature_scheme()]);
+                        let client = Client::load(&alice_central.mls_central.mls_backend, &cid, all_credentials, scs)
+                            .await
+                            .unwrap();
+                        alice_central.mls_central.mls_client = Some(client);
+
+                        let commit = rotate_bundle.commits.get(&id).unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                        // the superseded KeyPackages and the old Credential are gone nonetheless
+                        assert_eq!(count_pending_rotations(&alice_central.mls_central).await, 0);
+                        assert!(alice_central
+                            .mls_central
+                            .find_credential_from_keystore(&old_cb)
+                            .await
+                            .is_none());
+                        let nb_old_kp = alice_central
+                            .mls_central
+                            .count_key_package(case.ciphersuite(), Some(case.credential_type))
+                            .await;
+                        let expected = if is_renewal { 10 } else { 0 };
+                        assert_eq!(nb_old_kp, expected);
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn rotation_without_conversation_should_keep_old_key_packages(case: TestCase) {
+            run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+                Box::pin(async move {
+                    let x509_test_chain_arc = failsafe_ctx(&mut [&mut alice_central], case.signature_scheme()).await;
+                    let x509_test_chain = x509_test_chain_arc.as_ref().as_ref().unwrap();
+
+                    let old_cb = alice_central
+                        .mls_central
+                        .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                        .await
+                        .unwrap()
+                        .clone();
+                    let before_rotate = alice_central.mls_central.count_entities().await;
+
+                    let is_renewal = case.credential_type == MlsCredentialType::X509;
+                    let rotate_bundle = rotate_all(&mut alice_central, &case, x509_test_chain, is_renewal).await;
+                    assert!(rotate_bundle.commits.is_empty());
+
+                    // nothing tells when the superseded KeyPackages have been deleted remotely, hence they are kept...
+                    assert_eq!(count_pending_rotations(&alice_central.mls_central).await, 0);
+                    let after_rotate = alice_central.mls_central.count_entities().await;
+                    assert_eq!(after_rotate.key_package - before_rotate.key_package, 10);
+                    assert!(alice_central
+                        .mls_central
+                        .find_credential_from_keystore(&old_cb)
+                        .await
+                        .is_some());
+
+                    // ...until they are explicitly deleted
+                    alice_central
+                        .mls_central
+                        .delete_keypackages(&rotate_bundle.key_package_refs_to_remove)
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .find_credential_from_keystore(&old_cb)
+                        .await
+                        .is_none());
                 })
             })
             .await
         }
 
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn second_rotation_should_not_replace_pending_one(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let x509_test_chain_arc =
+                            failsafe_ctx(&mut [&mut alice_central, &mut bob_central], case.signature_scheme()).await;
+                        let x509_test_chain = x509_test_chain_arc.as_ref().as_ref().unwrap();
+
+                        let (id1, id2) = (conversation_id(), conversation_id());
+                        for id in [&id1, &id2] {
+                            alice_central
+                                .mls_central
+                                .new_conversation(id, case.credential_type, case.cfg.clone())
+                                .await
+                                .unwrap();
+                            alice_central
+                                .mls_central
+                                .invite_all(&case, id, [&mut bob_central.mls_central])
+                                .await
+                                .unwrap();
+                        }
+                        let old_cb = alice_central
+                            .mls_central
+                            .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                            .await
+                            .unwrap()
+                            .clone();
+
+                        // the first rotation is only accepted in one of the conversations...
+                        let is_renewal = case.credential_type == MlsCredentialType::X509;
+                        let first = rotate_all(&mut alice_central, &case, x509_test_chain, is_renewal).await;
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id1, first.commits.get(&id1).unwrap().commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        alice_central.mls_central.commit_accepted(&id1).await.unwrap();
+                        alice_central.mls_central.clear_pending_commit(&id2).await.unwrap();
+
+                        // ...so a second one is started, which does not replace the first one
+                        async_std::task::sleep(core::time::Duration::from_secs(1)).await;
+                        let second = rotate_all(&mut alice_central, &case, x509_test_chain, true).await;
+                        assert_eq!(count_pending_rotations(&alice_central.mls_central).await, 2);
+
+                        for id in [&id1, &id2] {
+                            bob_central
+                                .mls_central
+                                .decrypt_message(id, second.commits.get(id).unwrap().commit.to_bytes().unwrap())
+                                .await
+                                .unwrap();
+                            alice_central.mls_central.commit_accepted(id).await.unwrap();
+                        }
+
+                        // both are then completed: only the most recent Credential is left
+                        assert_eq!(count_pending_rotations(&alice_central.mls_central).await, 0);
+                        assert!(alice_central
+                            .mls_central
+                            .find_credential_from_keystore(&old_cb)
+                            .await
+                            .is_none());
+                        let nb_credentials = alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .count::<MlsCredential>()
+                            .await
+                            .unwrap();
+                        assert_eq!(nb_credentials, 1);
+                    })
+                },
+            )
+            .await
+        }
+
         #[apply(all_cred_cipher)]
         #[wasm_bindgen_test]
         pub async fn rotate_should_roundtrip(case: TestCase) {
@@ -881,6 +1450,144 @@ pub mod tests {
             )
             .await
         }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn renewal_should_keep_signature_key(case: TestCase) {
+            if case.is_x509() {
+                run_test_with_client_ids(
+                    case.clone(),
+                    ["alice", "bob"],
+                    move |[mut alice_central, mut bob_central]| {
+                        Box::pin(async move {
+                            let id = conversation_id();
+                            alice_central
+                                .mls_central
+                                .new_conversation(&id, case.credential_type, case.cfg.clone())
+                                .await
+                                .unwrap();
+                            alice_central
+                                .mls_central
+                                .invite_all(&case, &id, [&mut bob_central.mls_central])
+                                .await
+                                .unwrap();
+
+                            let x509_test_chain_arc =
+                                failsafe_ctx(&mut [&mut alice_central, &mut bob_central], case.signature_scheme())
+                                    .await;
+                            let x509_test_chain = x509_test_chain_arc.as_ref().as_ref().unwrap();
+
+                            let old_credential = alice_central
+                                .mls_central
+                                .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                                .await
+                                .unwrap()
+                                .clone();
+                            let before_renewal = alice_central.mls_central.count_entities().await;
+
+                            // a certificate issued for a fresh keypair cannot be used for a renewal
+                            fn fresh_init(wrapper: E2eiInitWrapper) -> InitFnReturn<'_> {
+                                Box::pin(async move {
+                                    let E2eiInitWrapper { cc, case } = wrapper;
+                                    cc.e2ei_new_enrollment(
+                                        cc.get_client_id(),
+                                        E2EI_DISPLAY_NAME.to_string(),
+                                        E2EI_HANDLE.to_string(),
+                                        Some(TEAM.to_string()),
+                                        E2EI_EXPIRY,
+                                        case.ciphersuite(),
+                                    )
+                                })
+                            }
+                            let (enrollment, cert) = e2ei_enrollment(
+                                &mut alice_central,
+                                &case,
+                                x509_test_chain,
+                                None,
+                                false,
+                                fresh_init,
+                                noop_restore,
+                            )
+                            .await
+                            .unwrap();
+                            let result = alice_central
+                                .mls_central
+                                .e2ei_renew(enrollment, cert, INITIAL_KEYING_MATERIAL_COUNT)
+                                .await;
+                            assert!(matches!(
+                                result.unwrap_err(),
+                                CryptoError::E2eiError(E2eIdentityError::RenewalKeyMismatch)
+                            ));
+                            let after_mismatch = alice_central.mls_central.count_entities().await;
+                            assert_eq!(after_mismatch.credential, before_renewal.credential);
+                            assert_eq!(after_mismatch.key_package, before_renewal.key_package);
+
+                            fn init(wrapper: E2eiInitWrapper) -> InitFnReturn<'_> {
+                                Box::pin(async move {
+                                    let E2eiInitWrapper { cc, case } = wrapper;
+                                    cc.e2ei_new_renewal_enrollment(
+                                        Some(TEAM.to_string()),
+                                        E2EI_EXPIRY,
+                                        case.ciphersuite(),
+                                    )
+                                    .await
+                                })
+                            }
+                            let (enrollment, cert) = e2ei_enrollment(
+                                &mut alice_central,
+                                &case,
+                                x509_test_chain,
+                                None,
+                                true,
+                                init,
+                                noop_restore,
+                            )
+                            .await
+                            .unwrap();
+                            let renew_bundle = alice_central
+                                .mls_central
+                                .e2ei_renew(enrollment, cert, INITIAL_KEYING_MATERIAL_COUNT)
+                                .await
+                                .unwrap();
+
+                            // the renewed credential is a new one over the same signature key
+                            let new_credential = alice_central
+                                .mls_central
+                                .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                                .await
+                                .unwrap()
+                                .clone();
+                            assert_ne!(new_credential.credential, old_credential.credential);
+                            assert_eq!(
+                                new_credential.signature_key.public(),
+                                old_credential.signature_key.public()
+                            );
+                            for kp in &renew_bundle.new_key_packages {
+                                assert_eq!(
+                                    kp.leaf_node().signature_key().as_slice(),
+                                    old_credential.signature_key.public()
+                                );
+                            }
+
+                            let commit = renew_bundle.commits.get(&id).unwrap();
+                            let decrypted = bob_central
+                                .mls_central
+                                .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                                .await
+                                .unwrap();
+                            alice_central.mls_central.verify_sender_identity(&case, &decrypted);
+                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                            alice_central
+                                .mls_central
+                                .try_talk_to(&id, &mut bob_central.mls_central)
+                                .await
+                                .unwrap();
+                        })
+                    },
+                )
+                .await
+            }
+        }
     }
 
     pub mod one {
@@ -76,6 +76,24 @@ pub enum CryptoError {
     /// The consumer of this library has misused it
     #[error("The consumer of this library has misused it")]
     ConsumerError,
+    /// The ciphersuite is not supported by this build e.g. a hybrid one without the `pq-ciphersuites` feature
+    #[error("Ciphersuite {0:#06x} is not supported by this build")]
+    UnsupportedCiphersuite(u16),
+    /// The operation was cancelled through its [crate::prelude::CancellationToken]. What it had done so far is either
+    /// kept in a consistent state or rolled back, see the cancelled operation
+    #[error("The operation has been cancelled")]
+    OperationCancelled,
+    /// Another instance holds the store, see [crate::prelude::MlsCentralConfiguration::set_take_over_store]
+    #[error("The store is already in use by another instance")]
+    StoreAlreadyInUse,
+    /// The store can't be opened until the device is unlocked, see
+    /// [crate::prelude::MlsCentralConfiguration::set_file_protection]
+    #[error("The store cannot be accessed while the device is locked")]
+    DeviceLocked,
+    /// The browser evicted the store, losing the keying material it held: the device has to be enrolled again. Only in
+    /// browsers
+    #[error("The store has been evicted by the browser")]
+    StoreEvicted,
     /// Errors that are sent by our MLS Provider
     #[error(transparent)]
     MlsProviderError(#[from] mls_crypto_provider::MlsProviderError),
@@ -122,6 +140,12 @@ pub enum CryptoError {
     /// External Commit sender was not authorized to perform such
     #[error("External Commit sender was not authorized to perform such")]
     UnauthorizedExternalCommit,
+    /// External Remove Proposal Validation failed
+    #[error("External remove proposal validation failed: it has to be issued by an external sender of the group for one of its members")]
+    UnauthorizedExternalRemoveProposal,
+    /// The committer was not authorized to add or remove the members of the commit
+    #[error("The committer was not authorized to add or remove these members")]
+    UnauthorizedMemberChange,
     /// A supplied [`openmls::ciphersuite::hash_ref::HashReference`] is not of the expected size: 16
     #[error("A supplied reference is not of the expected size: 16")]
     InvalidHashReference,
@@ -146,6 +170,19 @@ pub enum CryptoError {
     /// CoreCrypto hasn't been built with the `proteus` feature enabled, meaning proteus isn't built in
     #[error("CoreCrypto hasn't been built with Proteus support enabled; The feature `{0}` isn't enabled")]
     ProteusSupportNotEnabled(String),
+    /// The persisted state of a Proteus session is not the one it has been loaded from, i.e. another process sharing
+    /// the keystore used it in the meantime. The session in memory has been discarded, retrying uses the persisted one
+    #[error("The Proteus session {0} is out of sync with the keystore")]
+    ProteusSessionOutOfSync(String),
+    /// The remote identity of a Proteus session being created is not the one previously recorded for it, e.g. the
+    /// remote client has been reinstalled or someone is impersonating it, and the change has not been confirmed
+    /// through [CoreCryptoCallbacks::proteus_identity_changed](crate::CoreCryptoCallbacks::proteus_identity_changed)
+    #[error("The remote identity of the Proteus session {0} has changed")]
+    ProteusIdentityChanged(String),
+    /// The signatures of a [crate::identity_binding::IdentityBinding] do not match its client id and keys, i.e. the
+    /// Proteus identity and the MLS credential it binds do not belong to the same client
+    #[error("The binding between the Proteus identity and the MLS credential is invalid")]
+    InvalidIdentityBinding,
     /// A MLS operation was requested but MLS hasn't been initialized on this instance
     #[error("A MLS operation was requested but MLS hasn't been initialized on this instance")]
     MlsNotInitialized,
@@ -192,6 +229,47 @@ pub enum CryptoError {
     clears this pending commit and creates another commit. This is triggered when the client tries to decrypt the original commit.\
     This means something is very wrong in the client's code and has to be fixed immediately")]
     ClearingPendingCommitError,
+    /// The incoming message has been rejected before being processed: it is not a MLS 1.0 message, is of an
+    /// unexpected kind or could not be parsed at all
+    #[error("Malformed message: {0}")]
+    MalformedMessage(String),
+    /// The incoming message is from a later epoch than the next one, meaning the commits of the epochs in between
+    /// have not been delivered. The missing range should be requested again from the DS
+    #[error("Message from epoch {received} whereas epoch {expected} was expected, some messages are missing")]
+    MessageEpochGap {
+        /// The epoch the next message was expected in
+        expected: u64,
+        /// The epoch of the incoming message
+        received: u64,
+    },
+    /// The incoming application message is from the current epoch but more messages of its sender than the ratchet
+    /// window allows (see
+    /// [MlsCustomConfiguration::maximum_forward_distance](crate::prelude::MlsCustomConfiguration::maximum_forward_distance))
+    /// have not been delivered before it. The missing messages of this epoch should be requested again from the DS
+    #[error("Message too far ahead in epoch {epoch}, some messages of its sender are missing")]
+    MessageGenerationGap {
+        /// The current epoch of the conversation, which is also the one of the incoming message
+        epoch: u64,
+    },
+    /// The incoming application message could have been decrypted but is from an epoch older than the conversation
+    /// accepts (see
+    /// [MlsCustomConfiguration::max_application_message_epoch_age](crate::prelude::MlsCustomConfiguration::max_application_message_epoch_age))
+    #[error("Application message from epoch {received} is too old to be accepted in epoch {current}")]
+    ApplicationMessageTooOld {
+        /// The current epoch of the conversation
+        current: u64,
+        /// The epoch of the incoming message
+        received: u64,
+    },
+    /// The incoming message, Welcome or GroupInfo is larger than allowed (see
+    /// [MlsMessageLimits](crate::prelude::MlsMessageLimits)) hence has been rejected before being parsed
+    #[error("The input of {size} bytes exceeds the maximum of {max} bytes")]
+    InputTooLarge {
+        /// Size of the input in bytes
+        size: usize,
+        /// Maximum size allowed in bytes
+        max: usize,
+    },
     /// Tried to decrypt a commit created by self which is likely to have been replayed by the DS
     #[error("Tried to decrypt a commit created by self which is likely to have been replayed by the DS")]
     SelfCommitIgnored,
@@ -246,6 +324,72 @@ pub enum CryptoError {
     /// The group lacks an ExternalSender extension whereas it should have at least one
     #[error("The group lacks an ExternalSender extension whereas it should have at least one")]
     MissingExternalSenderExtension,
+    /// The merged conversation could not be persisted, so its in-memory state has been put back as it was before the
+    /// merge. The operation can be retried, e.g. once the underlying keystore error has been solved
+    #[error("The merged conversation could not be persisted and has been rolled back: {0}")]
+    MergeRolledBack(Box<CryptoError>),
+    /// The KeyPackage of a client being added to a conversation does not support every capability required by the
+    /// conversation, see [crate::prelude::MlsRequiredCapabilities]
+    #[error("Client {0} does not support the capabilities required by the conversation")]
+    MissingRequiredCapabilities(crate::prelude::ClientId),
+    /// The conversation has a pending commit and its epoch fence does not let application messages be encrypted
+    /// until it is resolved, see [crate::prelude::MlsEpochFence::FailFast]
+    #[error("A commit of the conversation is in flight, the message would be encrypted for a stale epoch")]
+    PendingCommitInFlight,
+    /// Exporting or importing the client identity has not been allowed in the [crate::prelude::MlsCentralConfiguration]
+    #[error("Exporting or importing the client identity has not been allowed in the configuration")]
+    IdentityExportNotAllowed,
+    /// The keystore already contains an identity which is not the one being imported
+    #[error("The keystore already contains a different identity")]
+    IdentityConflict,
+    /// The identity backup could not be decrypted with the supplied key or its content is malformed
+    #[error("The identity backup could not be decrypted or is malformed")]
+    MalformedIdentityBackup,
+    /// The conversation described by the Welcome does not comply with the
+    /// [crate::prelude::MlsJoinPolicy] of the [crate::prelude::MlsCustomConfiguration] it was processed with
+    #[error("The conversation does not comply with the join policy: {0}")]
+    JoinPolicyViolation(String),
+    /// A KeyPackage does not belong to the client it has been claimed for, see
+    /// [crate::prelude::ClaimedKeyPackageBundle]
+    #[error("The KeyPackage claimed for client {0} belongs to another client")]
+    ClaimedKeyPackageMismatch(crate::prelude::ClientId),
+    /// A client id does not follow Wire's qualified format `user-uuid:device-hex@domain`, see
+    /// [crate::prelude::QualifiedClientId]. Tells which part is malformed
+    #[error("Malformed qualified client id: {0}")]
+    MalformedClientId(&'static str),
+    /// A group uses a GroupContext or GroupInfo extension this version of CoreCrypto does not understand, hence can't
+    /// be joined. Holds the extension type as registered by the IANA
+    #[error("The group uses the unsupported extension {0:#06x}, the application has to be updated to join it")]
+    UnsupportedGroupExtension(u16),
+    /// A client which is not an admin of the conversation tried to add or remove members. Holds its client id
+    #[error("The client {0} is not an admin of the conversation, hence can't add or remove members")]
+    NotConversationAdmin(crate::prelude::ClientId),
+    /// A [crate::identity_signature::IdentitySignature] was not made over the data by the expected identity key, or
+    /// is malformed
+    #[error("The signature was not made by the expected identity key")]
+    InvalidIdentitySignature,
+    /// The GroupInfo of a group to join by external commit uses another ciphersuite than the expected one, see
+    /// [crate::prelude::MlsCentral::join_by_external_commit_pinned]
+    #[error("The group uses the ciphersuite {actual:#06x} whereas {expected:#06x} was expected")]
+    GroupCiphersuiteMismatch {
+        /// The ciphersuite the group was expected to use
+        expected: u16,
+        /// The ciphersuite of the GroupInfo
+        actual: u16,
+    },
+    /// A configuration is inconsistent, see [crate::prelude::MlsConversationConfiguration::validate] and
+    /// [crate::prelude::MlsCustomConfiguration::validate]
+    #[error("Invalid configuration, {field} {reason}")]
+    InvalidConfiguration {
+        /// Path of the invalid field within the configuration, e.g. `custom.key_rotation_span`
+        field: String,
+        /// Why its value is invalid
+        reason: String,
+    },
+    /// A proposal this version of CoreCrypto can't follow has been received, e.g. a ReInit since the successor group
+    /// can't be created
+    #[error("The {0:?} proposal is not supported")]
+    UnsupportedProposal(crate::prelude::MlsProposalType),
 }
 
 impl From<MlsError> for CryptoError {
@@ -268,6 +412,323 @@ impl From<MlsError> for CryptoError {
 /// A simpler definition for Result types that the Error is a [CryptoError]
 pub type CryptoResult<T> = Result<T, CryptoError>;
 
+/// Flattened error code for consumers across the FFI, who cannot inspect nested errors.
+///
+/// Codes are grouped by hundreds: conversation state (1xx), messages (2xx), identity & authorization (3xx),
+/// end-to-end identity & certificates (4xx), setup & misuse (5xx), Proteus (6xx), keystore (7xx),
+/// MLS internals (8xx) and encoding (9xx). Values are stable and must not be reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::IntoStaticStr)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[repr(u16)]
+pub enum CoreCryptoErrorCode {
+    /// Not classified, see the error context
+    Other = 0,
+    /// see [CryptoError::ConversationNotFound]
+    ConversationNotFound = 100,
+    /// see [CryptoError::ConversationAlreadyExists]
+    ConversationAlreadyExists = 101,
+    /// see [CryptoError::ParentGroupNotFound]
+    ParentGroupNotFound = 102,
+    /// see [CryptoError::PendingProposalNotFound]
+    PendingProposalNotFound = 103,
+    /// see [CryptoError::PendingCommitNotFound]
+    PendingCommitNotFound = 104,
+    /// see [CryptoError::ClientNotFound]
+    ClientNotFound = 105,
+    /// see [CryptoError::UnmergedPendingGroup]
+    UnmergedPendingGroup = 106,
+    /// see [CryptoError::OrphanWelcome]
+    OrphanWelcome = 107,
+    /// see [CryptoError::InternalMlsError]
+    InternalMlsError = 108,
+    /// see [CryptoError::MissingExternalSenderExtension]
+    MissingExternalSenderExtension = 109,
+    /// see [CryptoError::MergeRolledBack]
+    MergeRolledBack = 110,
+    /// see [CryptoError::MissingRequiredCapabilities]
+    MissingRequiredCapabilities = 111,
+    /// see [CryptoError::PendingCommitInFlight]
+    PendingCommitInFlight = 112,
+    /// see [CryptoError::WrongEpoch]
+    WrongEpoch = 200,
+    /// see [CryptoError::DecryptionError]
+    DecryptionError = 201,
+    /// see [CryptoError::BufferedFutureMessage]
+    BufferedFutureMessage = 202,
+    /// see [CryptoError::MessageEpochTooOld]
+    MessageEpochTooOld = 203,
+    /// see [CryptoError::DuplicateMessage]
+    DuplicateMessage = 204,
+    /// see [CryptoError::SelfCommitIgnored]
+    SelfCommitIgnored = 205,
+    /// see [CryptoError::StaleCommit]
+    StaleCommit = 206,
+    /// see [CryptoError::StaleProposal]
+    StaleProposal = 207,
+    /// see [CryptoError::ClearingPendingCommitError]
+    ClearingPendingCommitError = 208,
+    /// see [CryptoError::MalformedMessage]
+    MalformedMessage = 209,
+    /// see [CryptoError::MessageEpochGap]
+    MessageEpochGap = 210,
+    /// see [CryptoError::ApplicationMessageTooOld]
+    ApplicationMessageTooOld = 211,
+    /// see [CryptoError::InputTooLarge]
+    InputTooLarge = 212,
+    /// see [CryptoError::MessageGenerationGap]
+    MessageGenerationGap = 213,
+    /// see [CryptoError::Unauthorized]
+    Unauthorized = 300,
+    /// see [CryptoError::UnauthorizedExternalAddProposal]
+    UnauthorizedExternalAddProposal = 301,
+    /// see [CryptoError::UnauthorizedExternalCommit]
+    UnauthorizedExternalCommit = 302,
+    /// see [CryptoError::InvalidIdentity]
+    InvalidIdentity = 303,
+    /// see [CryptoError::InvalidKeyPackage]
+    InvalidKeyPackage = 304,
+    /// see [CryptoError::InvalidClientId]
+    InvalidClientId = 305,
+    /// see [CryptoError::MalformedIdentifier]
+    MalformedIdentifier = 306,
+    /// see [CryptoError::CredentialNotFound]
+    CredentialNotFound = 307,
+    /// see [CryptoError::CredentialBundleConflict]
+    CredentialBundleConflict = 308,
+    /// see [CryptoError::IdentityAlreadyPresent]
+    IdentityAlreadyPresent = 309,
+    /// see [CryptoError::NoProvisionalIdentityFound]
+    NoProvisionalIdentityFound = 310,
+    /// see [CryptoError::TooManyIdentitiesPresent]
+    TooManyIdentitiesPresent = 311,
+    /// see [CryptoError::ClientSignatureNotFound]
+    ClientSignatureNotFound = 312,
+    /// see [CryptoError::IdentityInitializationError]
+    IdentityInitializationError = 313,
+    /// see [CryptoError::UnauthorizedExternalRemoveProposal]
+    UnauthorizedExternalRemoveProposal = 314,
+    /// see [CryptoError::UnauthorizedMemberChange]
+    UnauthorizedMemberChange = 315,
+    /// see [CryptoError::IdentityExportNotAllowed]
+    IdentityExportNotAllowed = 316,
+    /// see [CryptoError::IdentityConflict]
+    IdentityConflict = 317,
+    /// see [CryptoError::MalformedIdentityBackup]
+    MalformedIdentityBackup = 318,
+    /// see [CryptoError::JoinPolicyViolation]
+    JoinPolicyViolation = 319,
+    /// see [CryptoError::ClaimedKeyPackageMismatch]
+    ClaimedKeyPackageMismatch = 320,
+    /// see [CryptoError::MalformedClientId]
+    MalformedClientId = 321,
+    /// see [CryptoError::UnsupportedGroupExtension]
+    UnsupportedGroupExtension = 322,
+    /// see [CryptoError::NotConversationAdmin]
+    NotConversationAdmin = 323,
+    /// see [CryptoError::InvalidIdentitySignature]
+    InvalidIdentitySignature = 324,
+    /// see [CryptoError::GroupCiphersuiteMismatch]
+    GroupCiphersuiteMismatch = 325,
+    /// see [CryptoError::InvalidConfiguration]
+    InvalidConfiguration = 326,
+    /// see [CryptoError::UnsupportedProposal]
+    UnsupportedProposal = 327,
+    /// see [CryptoError::E2eiEnrollmentNotDone]
+    E2eiEnrollmentNotDone = 400,
+    /// see [E2eIdentityError]
+    E2eIdentityError = 401,
+    /// see [CryptoError::InvalidCertificateChain]
+    InvalidCertificateChain = 402,
+    /// see [CryptoError::DomainNameNotFound]
+    DomainNameNotFound = 403,
+    /// see [CryptoError::DomainNamesDontMatch]
+    DomainNamesDontMatch = 404,
+    /// see [CryptoError::DuplicateDomainName]
+    DuplicateDomainName = 405,
+    /// see [CryptoError::EmptyTrustAnchorUpdate]
+    EmptyTrustAnchorUpdate = 406,
+    /// see [CryptoError::DuplicateCertificateChain]
+    DuplicateCertificateChain = 407,
+    /// A certificate could not be parsed, see [CryptoError::X509CertDerError] and [CryptoError::PemError]
+    CertificateParsingError = 408,
+    /// see [CryptoError::MlsNotInitialized]
+    MlsNotInitialized = 500,
+    /// see [CryptoError::ProteusNotInitialized]
+    ProteusNotInitialized = 501,
+    /// see [CryptoError::ProteusSupportNotEnabled]
+    ProteusSupportNotEnabled = 502,
+    /// see [CryptoError::CallbacksNotSet]
+    CallbacksNotSet = 503,
+    /// see [CryptoError::ConsumerError]
+    ConsumerError = 504,
+    /// see [CryptoError::UnsupportedCiphersuite]
+    UnsupportedCiphersuite = 505,
+    /// A bug on our side, see [CryptoError::ImplementationError] and [CryptoError::LockPoisonError]
+    ImplementationError = 506,
+    /// see [CryptoError::OperationCancelled]
+    OperationCancelled = 507,
+    /// see [CryptoError::ProteusError]. The detailed code is given by [CryptoError::proteus_error_code]
+    ProteusError = 600,
+    /// see [CryptoError::CryptoboxMigrationError]
+    CryptoboxMigrationError = 601,
+    /// see [CryptoError::ProteusSessionOutOfSync]
+    ProteusSessionOutOfSync = 602,
+    /// see [CryptoError::ProteusIdentityChanged]
+    ProteusIdentityChanged = 603,
+    /// see [CryptoError::InvalidIdentityBinding]
+    InvalidIdentityBinding = 604,
+    /// see [CryptoError::KeyStoreError]
+    KeyStoreError = 700,
+    /// The requested entity is not in the keystore
+    MissingKeyInStore = 701,
+    /// There is not enough storage left to persist the data
+    StorageQuotaExceeded = 702,
+    /// A keystore transaction was started while another one is in progress or ended while none is
+    TransactionError = 703,
+    /// Another instance holds the store, see [CryptoError::StoreAlreadyInUse]
+    StoreAlreadyInUse = 704,
+    /// The store can't be accessed until the device is unlocked, see [CryptoError::DeviceLocked]
+    DeviceLocked = 705,
+    /// The store has been evicted by the browser, see [CryptoError::StoreEvicted]
+    StoreEvicted = 706,
+    /// see [CryptoError::MlsError]
+    MlsError = 800,
+    /// see [CryptoError::MlsProviderError]
+    MlsProviderError = 801,
+    /// Some data could not be (de)serialized or converted
+    SerializationError = 900,
+    /// see [CryptoError::IoError]
+    IoError = 901,
+}
+
+impl From<&core_crypto_keystore::CryptoKeystoreError> for CoreCryptoErrorCode {
+    fn from(e: &core_crypto_keystore::CryptoKeystoreError) -> Self {
+        use core_crypto_keystore::CryptoKeystoreError as E;
+        match e {
+            E::MissingKeyInStore(_) => Self::MissingKeyInStore,
+            E::StorageQuotaExceeded(_) => Self::StorageQuotaExceeded,
+            E::TransactionInProgress | E::NoTransactionInProgress => Self::TransactionError,
+            E::StoreAlreadyInUse => Self::StoreAlreadyInUse,
+            E::DeviceLocked => Self::DeviceLocked,
+            E::StoreEvicted => Self::StoreEvicted,
+            _ => Self::KeyStoreError,
+        }
+    }
+}
+
+impl From<&E2eIdentityError> for CoreCryptoErrorCode {
+    fn from(e: &E2eIdentityError) -> Self {
+        match e {
+            E2eIdentityError::KeyStoreError(e) => e.into(),
+            _ => Self::E2eIdentityError,
+        }
+    }
+}
+
+impl From<&CryptoError> for CoreCryptoErrorCode {
+    fn from(e: &CryptoError) -> Self {
+        use CryptoError as E;
+        match e {
+            E::ConversationNotFound(_) => Self::ConversationNotFound,
+            E::ConversationAlreadyExists(_) => Self::ConversationAlreadyExists,
+            E::ParentGroupNotFound => Self::ParentGroupNotFound,
+            E::PendingProposalNotFound(_) => Self::PendingProposalNotFound,
+            E::PendingCommitNotFound => Self::PendingCommitNotFound,
+            E::ClientNotFound(_) => Self::ClientNotFound,
+            E::UnmergedPendingGroup => Self::UnmergedPendingGroup,
+            E::OrphanWelcome => Self::OrphanWelcome,
+            E::InternalMlsError => Self::InternalMlsError,
+            E::MissingExternalSenderExtension => Self::MissingExternalSenderExtension,
+            E::MergeRolledBack(_) => Self::MergeRolledBack,
+            E::MissingRequiredCapabilities(_) => Self::MissingRequiredCapabilities,
+            E::PendingCommitInFlight => Self::PendingCommitInFlight,
+            E::WrongEpoch => Self::WrongEpoch,
+            E::DecryptionError => Self::DecryptionError,
+            E::BufferedFutureMessage => Self::BufferedFutureMessage,
+            E::MessageEpochTooOld => Self::MessageEpochTooOld,
+            E::DuplicateMessage => Self::DuplicateMessage,
+            E::SelfCommitIgnored => Self::SelfCommitIgnored,
+            E::StaleCommit => Self::StaleCommit,
+            E::StaleProposal => Self::StaleProposal,
+            E::ClearingPendingCommitError => Self::ClearingPendingCommitError,
+            E::MalformedMessage(_) => Self::MalformedMessage,
+            E::MessageEpochGap { .. } => Self::MessageEpochGap,
+            E::ApplicationMessageTooOld { .. } => Self::ApplicationMessageTooOld,
+            E::InputTooLarge { .. } => Self::InputTooLarge,
+            E::MessageGenerationGap { .. } => Self::MessageGenerationGap,
+            E::Unauthorized => Self::Unauthorized,
+            E::UnauthorizedExternalAddProposal => Self::UnauthorizedExternalAddProposal,
+            E::UnauthorizedExternalCommit => Self::UnauthorizedExternalCommit,
+            E::UnauthorizedExternalRemoveProposal => Self::UnauthorizedExternalRemoveProposal,
+            E::UnauthorizedMemberChange => Self::UnauthorizedMemberChange,
+            E::IdentityExportNotAllowed => Self::IdentityExportNotAllowed,
+            E::IdentityConflict => Self::IdentityConflict,
+            E::MalformedIdentityBackup => Self::MalformedIdentityBackup,
+            E::JoinPolicyViolation(_) => Self::JoinPolicyViolation,
+            E::ClaimedKeyPackageMismatch(_) => Self::ClaimedKeyPackageMismatch,
+            E::MalformedClientId(_) => Self::MalformedClientId,
+            E::UnsupportedGroupExtension(_) => Self::UnsupportedGroupExtension,
+            E::NotConversationAdmin(_) => Self::NotConversationAdmin,
+            E::InvalidIdentitySignature => Self::InvalidIdentitySignature,
+            E::GroupCiphersuiteMismatch { .. } => Self::GroupCiphersuiteMismatch,
+            E::InvalidConfiguration { .. } => Self::InvalidConfiguration,
+            E::UnsupportedProposal(_) => Self::UnsupportedProposal,
+            E::InvalidIdentity => Self::InvalidIdentity,
+            E::InvalidKeyPackage => Self::InvalidKeyPackage,
+            E::InvalidClientId => Self::InvalidClientId,
+            E::MalformedIdentifier(_) => Self::MalformedIdentifier,
+            E::CredentialNotFound(_) => Self::CredentialNotFound,
+            E::CredentialBundleConflict => Self::CredentialBundleConflict,
+            E::IdentityAlreadyPresent => Self::IdentityAlreadyPresent,
+            E::NoProvisionalIdentityFound => Self::NoProvisionalIdentityFound,
+            E::TooManyIdentitiesPresent => Self::TooManyIdentitiesPresent,
+            E::ClientSignatureNotFound => Self::ClientSignatureNotFound,
+            E::IdentityInitializationError => Self::IdentityInitializationError,
+            E::E2eiEnrollmentNotDone => Self::E2eiEnrollmentNotDone,
+            E::E2eiError(e) => e.into(),
+            E::InvalidCertificateChain => Self::InvalidCertificateChain,
+            E::DomainNameNotFound => Self::DomainNameNotFound,
+            E::DomainNamesDontMatch => Self::DomainNamesDontMatch,
+            E::DuplicateDomainName => Self::DuplicateDomainName,
+            E::EmptyTrustAnchorUpdate => Self::EmptyTrustAnchorUpdate,
+            E::DuplicateCertificateChain => Self::DuplicateCertificateChain,
+            E::X509CertDerError(_) | E::PemError(_) => Self::CertificateParsingError,
+            E::MlsNotInitialized => Self::MlsNotInitialized,
+            E::ProteusNotInitialized => Self::ProteusNotInitialized,
+            E::ProteusSupportNotEnabled(_) => Self::ProteusSupportNotEnabled,
+            E::CallbacksNotSet => Self::CallbacksNotSet,
+            E::ConsumerError => Self::ConsumerError,
+            E::UnsupportedCiphersuite(_) => Self::UnsupportedCiphersuite,
+            E::ImplementationError | E::LockPoisonError => Self::ImplementationError,
+            E::OperationCancelled => Self::OperationCancelled,
+            E::ProteusError(_) => Self::ProteusError,
+            E::CryptoboxMigrationError(_) => Self::CryptoboxMigrationError,
+            E::ProteusSessionOutOfSync(_) => Self::ProteusSessionOutOfSync,
+            E::ProteusIdentityChanged(_) => Self::ProteusIdentityChanged,
+            E::InvalidIdentityBinding => Self::InvalidIdentityBinding,
+            E::StoreAlreadyInUse => Self::StoreAlreadyInUse,
+            E::DeviceLocked => Self::DeviceLocked,
+            E::StoreEvicted => Self::StoreEvicted,
+            E::KeyStoreError(e) => e.into(),
+            E::MlsError(_) => Self::MlsError,
+            E::MlsProviderError(_) => Self::MlsProviderError,
+            E::Utf8Error(_)
+            | E::StringUtf8Error(_)
+            | E::ParseIntError(_)
+            | E::ConvertIntError(_)
+            | E::HexDecodeError(_)
+            | E::InvalidByteArrayError(_)
+            | E::InvalidHashReference
+            | E::JsonError(_) => Self::SerializationError,
+            E::IoError(_) => Self::IoError,
+            #[cfg(test)]
+            E::UuidError(_) => Self::SerializationError,
+            #[cfg(test)]
+            E::LeakEntities | E::NoEntityCreated => Self::ImplementationError,
+        }
+    }
+}
+
 impl CryptoError {
     /// Returns the proteus error code
     pub fn proteus_error_code(&self) -> u32 {
@@ -276,6 +737,16 @@ impl CryptoError {
         };
         e.error_code()
     }
+
+    /// Returns the flattened error code, for consumers who cannot inspect nested errors
+    pub fn error_code(&self) -> CoreCryptoErrorCode {
+        self.into()
+    }
+
+    /// Detailed description of the error including all the nested ones, meant for logs and bug reports
+    pub fn context(&self) -> String {
+        format!("{self:?}")
+    }
 }
 
 /// MLS-specific error wrapper - see github.com/openmls/openmls for details
@@ -328,6 +799,14 @@ pub enum MlsError {
     /// Propose remove members error
     #[error(transparent)]
     ProposeRemoveMemberError(#[from] openmls::prelude::ProposeRemoveMemberError),
+    /// Update group context extensions error
+    #[error(transparent)]
+    MlsUpdateExtensionsError(
+        #[from] openmls::prelude::UpdateExtensionsError<core_crypto_keystore::CryptoKeystoreError>,
+    ),
+    /// Invalid set of extensions error
+    #[error(transparent)]
+    MlsInvalidExtensionError(#[from] openmls::prelude::InvalidExtensionError),
     /// Commit to pending proposals error
     #[error(transparent)]
     MlsCommitToPendingProposalsError(
@@ -389,6 +868,12 @@ pub enum MlsError {
     /// OpenMLS GroupInfo error
     #[error(transparent)]
     GroupInfoError(#[from] openmls::messages::group_info::GroupInfoError),
+    /// OpenMLS generic proposal error
+    #[error(transparent)]
+    ProposalError(#[from] openmls::prelude::ProposalError<core_crypto_keystore::CryptoKeystoreError>),
+    /// OpenMLS pre-shared key error
+    #[error(transparent)]
+    PskError(#[from] openmls::prelude::PskError),
 }
 
 #[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
@@ -463,3 +948,175 @@ pub enum CryptoboxMigrationError {
     /// Error when inspecting a Cryptobox store that doesn't contain an Identity
     IdentityNotFound(String),
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use core_crypto_keystore::{CryptoKeystoreError, MissingKeyErrorKind};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn error_codes_should_be_stable() {
+        let table = [
+            (CryptoError::ConversationNotFound(vec![]), 100),
+            (CryptoError::ConversationAlreadyExists(vec![]), 101),
+            (CryptoError::ParentGroupNotFound, 102),
+            (CryptoError::PendingCommitNotFound, 104),
+            (CryptoError::ClientNotFound(vec![].into()), 105),
+            (CryptoError::UnmergedPendingGroup, 106),
+            (CryptoError::OrphanWelcome, 107),
+            (CryptoError::InternalMlsError, 108),
+            (CryptoError::MissingExternalSenderExtension, 109),
+            (
+                CryptoError::MergeRolledBack(Box::new(CryptoError::ImplementationError)),
+                110,
+            ),
+            (
+                CryptoError::MissingRequiredCapabilities(crate::prelude::ClientId::from(vec![])),
+                111,
+            ),
+            (CryptoError::PendingCommitInFlight, 112),
+            (CryptoError::WrongEpoch, 200),
+            (CryptoError::DecryptionError, 201),
+            (CryptoError::BufferedFutureMessage, 202),
+            (CryptoError::MessageEpochTooOld, 203),
+            (CryptoError::DuplicateMessage, 204),
+            (CryptoError::SelfCommitIgnored, 205),
+            (CryptoError::StaleCommit, 206),
+            (CryptoError::StaleProposal, 207),
+            (CryptoError::ClearingPendingCommitError, 208),
+            (CryptoError::MalformedMessage("truncated header".to_string()), 209),
+            (
+                CryptoError::MessageEpochGap {
+                    expected: 1,
+                    received: 3,
+                },
+                210,
+            ),
+            (
+                CryptoError::ApplicationMessageTooOld {
+                    current: 5,
+                    received: 2,
+                },
+                211,
+            ),
+            (CryptoError::InputTooLarge { size: 2, max: 1 }, 212),
+            (CryptoError::MessageGenerationGap { epoch: 1 }, 213),
+            (CryptoError::Unauthorized, 300),
+            (CryptoError::UnauthorizedExternalAddProposal, 301),
+            (CryptoError::UnauthorizedExternalCommit, 302),
+            (CryptoError::InvalidIdentity, 303),
+            (CryptoError::InvalidKeyPackage, 304),
+            (CryptoError::InvalidClientId, 305),
+            (CryptoError::MalformedIdentifier("id"), 306),
+            (CryptoError::CredentialNotFound(MlsCredentialType::Basic), 307),
+            (CryptoError::CredentialBundleConflict, 308),
+            (CryptoError::IdentityAlreadyPresent, 309),
+            (CryptoError::NoProvisionalIdentityFound, 310),
+            (CryptoError::TooManyIdentitiesPresent, 311),
+            (CryptoError::ClientSignatureNotFound, 312),
+            (CryptoError::IdentityInitializationError, 313),
+            (CryptoError::UnauthorizedExternalRemoveProposal, 314),
+            (CryptoError::UnauthorizedMemberChange, 315),
+            (CryptoError::IdentityExportNotAllowed, 316),
+            (CryptoError::IdentityConflict, 317),
+            (CryptoError::MalformedIdentityBackup, 318),
+            (CryptoError::JoinPolicyViolation("".to_string()), 319),
+            (
+                CryptoError::ClaimedKeyPackageMismatch(crate::prelude::ClientId::from(vec![])),
+                320,
+            ),
+            (CryptoError::MalformedClientId("domain"), 321),
+            (CryptoError::UnsupportedGroupExtension(0xF000), 322),
+            (
+                CryptoError::NotConversationAdmin(crate::prelude::ClientId::from(vec![])),
+                323,
+            ),
+            (CryptoError::InvalidIdentitySignature, 324),
+            (
+                CryptoError::GroupCiphersuiteMismatch {
+                    expected: 0x0001,
+                    actual: 0x0002,
+                },
+                325,
+            ),
+            (
+                CryptoError::InvalidConfiguration {
+                    field: "".to_string(),
+                    reason: "".to_string(),
+                },
+                326,
+            ),
+            (
+                CryptoError::UnsupportedProposal(crate::prelude::MlsProposalType::ReInit),
+                327,
+            ),
+            (CryptoError::E2eiEnrollmentNotDone, 400),
+            (CryptoError::E2eiError(E2eIdentityError::InvalidRefreshToken), 401),
+            (CryptoError::InvalidCertificateChain, 402),
+            (CryptoError::DomainNameNotFound, 403),
+            (CryptoError::DomainNamesDontMatch, 404),
+            (CryptoError::DuplicateDomainName, 405),
+            (CryptoError::EmptyTrustAnchorUpdate, 406),
+            (CryptoError::DuplicateCertificateChain, 407),
+            (CryptoError::MlsNotInitialized, 500),
+            (CryptoError::ProteusNotInitialized, 501),
+            (CryptoError::ProteusSupportNotEnabled("proteus".into()), 502),
+            (CryptoError::CallbacksNotSet, 503),
+            (CryptoError::ConsumerError, 504),
+            (CryptoError::UnsupportedCiphersuite(0xf031), 505),
+            (CryptoError::ImplementationError, 506),
+            (CryptoError::LockPoisonError, 506),
+            (CryptoError::OperationCancelled, 507),
+            (
+                CryptoError::CryptoboxMigrationError(CryptoboxMigrationError::IdentityNotFound("".into())),
+                601,
+            ),
+            (CryptoError::ProteusSessionOutOfSync("".into()), 602),
+            (CryptoError::ProteusIdentityChanged("".into()), 603),
+            (CryptoError::InvalidIdentityBinding, 604),
+            (CryptoError::KeyStoreError(CryptoKeystoreError::NotImplemented), 700),
+            (
+                CryptoError::KeyStoreError(CryptoKeystoreError::MissingKeyInStore(MissingKeyErrorKind::MlsGroup)),
+                701,
+            ),
+            (
+                CryptoError::KeyStoreError(CryptoKeystoreError::StorageQuotaExceeded(42)),
+                702,
+            ),
+            (
+                CryptoError::KeyStoreError(CryptoKeystoreError::TransactionInProgress),
+                703,
+            ),
+            (
+                CryptoError::KeyStoreError(CryptoKeystoreError::NoTransactionInProgress),
+                703,
+            ),
+            (CryptoError::StoreAlreadyInUse, 704),
+            (CryptoError::KeyStoreError(CryptoKeystoreError::StoreAlreadyInUse), 704),
+            (CryptoError::DeviceLocked, 705),
+            (CryptoError::KeyStoreError(CryptoKeystoreError::DeviceLocked), 705),
+            (CryptoError::StoreEvicted, 706),
+            (CryptoError::KeyStoreError(CryptoKeystoreError::StoreEvicted), 706),
+            (
+                CryptoError::E2eiError(E2eIdentityError::KeyStoreError(CryptoKeystoreError::NotImplemented)),
+                700,
+            ),
+            (
+                CryptoError::MlsError(MlsError::MlsTlsCodecError(tls_codec::Error::EndOfStream)),
+                800,
+            ),
+            (CryptoError::InvalidByteArrayError(32), 900),
+            (CryptoError::InvalidHashReference, 900),
+            (CryptoError::IoError(std::io::ErrorKind::NotFound.into()), 901),
+        ];
+
+        for (error, code) in table {
+            assert_eq!(error.error_code() as u16, code, "{error:?}");
+            assert!(error.context().contains(<&'static str>::from(&error)));
+        }
+    }
+}
@@ -15,7 +15,6 @@
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
 use crate::prelude::{CryptoResult, MlsConversation};
-use core_crypto_keystore::entities::EntityFindParams;
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
@@ -28,16 +27,24 @@ pub(crate) trait GroupStoreEntity: std::fmt::Debug {
     async fn fetch_from_id(
         id: &[u8],
         identity: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> CryptoResult<Option<Self>>
     where
         Self: Sized;
 
-    async fn fetch_all(
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>>
+    async fn fetch_all(keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
     where
         Self: Sized;
+
+    async fn fetch_many(ids: &[Vec<u8>], keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
+    where
+        Self: Sized;
+
+    /// Whether the value holds state which is not in the keystore, in which case an uncached [GroupStore] keeps it in
+    /// memory until it no longer does
+    fn holds_unpersisted_state(&self) -> bool {
+        false
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -53,36 +60,53 @@ impl GroupStoreEntity for MlsConversation {
     async fn fetch_from_id(
         id: &[u8],
         _: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> crate::CryptoResult<Option<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
-        let Some(store_value) = Self::RawStoreValue::find_one(keystore, &id.into()).await? else {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        // not going through the entity directly since the latest state might not have been persisted yet
+        let Some((parent_id, state)) = keystore.mls_group_load(id).await? else {
             return Ok(None);
         };
 
-        let conversation = Self::from_serialized_state(store_value.state.clone(), store_value.parent_id.clone())?;
+        let mut conversation = Self::from_serialized_state(state, parent_id)?;
         // If the conversation is not active, pretend it doesn't exist
-        Ok(if conversation.group.is_active() {
-            Some(conversation)
-        } else {
-            None
-        })
+        if !conversation.group.is_active() {
+            return Ok(None);
+        }
+        conversation.restore_metadata(keystore).await?;
+        Ok(Some(conversation))
     }
 
-    async fn fetch_all(
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
+    async fn fetch_all(keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
 
-        let all_conversations = Self::RawStoreValue::find_all(keystore, EntityFindParams::default());
-        Ok(all_conversations
-            .await?
-            .iter()
-            .filter_map(|c| {
-                let conversation = Self::from_serialized_state(c.state.clone(), c.parent_id.clone()).unwrap();
-                conversation.group.is_active().then_some(conversation)
-            })
-            .collect::<Vec<_>>())
+        let mut conversations = vec![];
+        for (parent_id, state) in keystore.mls_groups_restore().await?.into_values() {
+            let mut conversation = Self::from_serialized_state(state, parent_id).unwrap();
+            if conversation.group.is_active() {
+                conversation.restore_metadata(keystore).await?;
+                conversations.push(conversation);
+            }
+        }
+        Ok(conversations)
+    }
+
+    async fn fetch_many(ids: &[Vec<u8>], keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+
+        let mut conversations = vec![];
+        for (parent_id, state) in keystore.mls_groups_load(ids).await?.into_values() {
+            let mut conversation = Self::from_serialized_state(state, parent_id)?;
+            if conversation.group.is_active() {
+                conversation.restore_metadata(keystore).await?;
+                conversations.push(conversation);
+            }
+        }
+        Ok(conversations)
+    }
+
+    fn holds_unpersisted_state(&self) -> bool {
+        self.group.state_changed() == openmls::group::InnerState::Changed || self.holds_fenced_messages()
     }
 }
 
@@ -100,10 +124,9 @@ impl GroupStoreEntity for crate::proteus::ProteusConversationSession {
     async fn fetch_from_id(
         id: &[u8],
         identity: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> crate::CryptoResult<Option<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
-        let Some(store_value) = Self::RawStoreValue::find_one(keystore, &id.into()).await? else {
+        let Some(store_value) = keystore.find::<Self::RawStoreValue>(id).await? else {
             return Ok(None);
         };
 
@@ -117,12 +140,18 @@ impl GroupStoreEntity for crate::proteus::ProteusConversationSession {
         Ok(Some(Self {
             identifier: store_value.id.clone(),
             session,
+            synced: Some(store_value.session.clone().into()),
         }))
     }
 
-    async fn fetch_all(
-        _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>>
+    async fn fetch_all(_keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        unreachable!()
+    }
+
+    async fn fetch_many(_ids: &[Vec<u8>], _keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
     where
         Self: Sized,
     {
@@ -137,13 +166,35 @@ pub(crate) type LruMap<V> = schnellru::LruMap<Vec<u8>, GroupStoreValue<V>, Hybri
 /// LRU-cache based group/session store
 /// Uses a hybrid memory limiter based on both amount of elements and total memory usage
 /// As with all LRU caches, eviction is based on oldest elements
-pub(crate) struct GroupStore<V: GroupStoreEntity>(LruMap<V>);
+pub(crate) struct GroupStore<V: GroupStoreEntity>(LruMap<V>, GroupStoreStats, GroupStoreMode);
+
+/// Whether a [GroupStore] keeps the values in memory once they have been used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum GroupStoreMode {
+    /// Values stay in memory until evicted by the limiter
+    #[default]
+    Cached,
+    /// Values are fetched from the keystore for every operation. The ones no longer in use are dropped on the next
+    /// access to the store, unless they hold state which is not in the keystore yet
+    Uncached,
+}
+
+/// Lookups of a [GroupStore], telling how often values had to be fetched from the keystore
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct GroupStoreStats {
+    /// Values found in memory
+    pub(crate) hits: u64,
+    /// Values which had to be looked up in the keystore
+    pub(crate) misses: u64,
+}
 
 impl<V: GroupStoreEntity> std::fmt::Debug for GroupStore<V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GroupStore")
             .field("length", &self.0.len())
             .field("memory_usage", &self.0.memory_usage())
+            .field("stats", &self.1)
+            .field("mode", &self.2)
             .field(
                 "entries",
                 &self
@@ -159,7 +210,11 @@ impl<V: GroupStoreEntity> std::fmt::Debug for GroupStore<V> {
 
 impl<V: GroupStoreEntity> Default for GroupStore<V> {
     fn default() -> Self {
-        Self(schnellru::LruMap::default())
+        Self(
+            schnellru::LruMap::default(),
+            GroupStoreStats::default(),
+            GroupStoreMode::default(),
+        )
     }
 }
 
@@ -184,14 +239,26 @@ impl<V: GroupStoreEntity> GroupStore<V> {
     pub(crate) fn new_with_limit(len: u32) -> Self {
         let limiter = HybridMemoryLimiter::new(Some(len), None);
         let store = schnellru::LruMap::new(limiter);
-        Self(store)
+        Self(store, GroupStoreStats::default(), GroupStoreMode::default())
     }
 
     #[allow(dead_code)]
     pub(crate) fn new(count: Option<u32>, memory: Option<usize>) -> Self {
         let limiter = HybridMemoryLimiter::new(count, memory);
         let store = schnellru::LruMap::new(limiter);
-        Self(store)
+        Self(store, GroupStoreStats::default(), GroupStoreMode::default())
+    }
+
+    /// Store which does not keep the values in memory, see [GroupStoreMode::Uncached]. The values in use are never
+    /// evicted, hence it is not limited
+    pub(crate) fn uncached() -> Self {
+        let limiter = HybridMemoryLimiter::new(Some(u32::MAX), Some(usize::MAX));
+        let store = schnellru::LruMap::new(limiter);
+        Self(store, GroupStoreStats::default(), GroupStoreMode::Uncached)
+    }
+
+    pub(crate) fn mode(&self) -> GroupStoreMode {
+        self.2
     }
 
     #[allow(dead_code)]
@@ -205,18 +272,16 @@ impl<V: GroupStoreEntity> GroupStore<V> {
         keystore: &mut core_crypto_keystore::Connection,
         identity: Option<V::IdentityType>,
     ) -> crate::CryptoResult<Option<GroupStoreValue<V>>> {
+        self.evict_unused();
         // Optimistic cache lookup
         if let Some(value) = self.0.get(k) {
+            self.1.hits += 1;
             return Ok(Some(value.clone()));
         }
 
-        let mut keystore_connection = keystore
-            .borrow_conn()
-            .await
-            .map_err(|_| crate::CryptoError::LockPoisonError)?;
-
         // Not in store, fetch the thing in the keystore
-        let mut value = V::fetch_from_id(k, identity, &mut keystore_connection).await?;
+        self.1.misses += 1;
+        let mut value = V::fetch_from_id(k, identity, keystore).await?;
         if let Some(value) = value.take() {
             let value_to_insert = std::sync::Arc::new(async_lock::RwLock::new(value));
             self.insert_prepped(k.to_vec(), value_to_insert.clone());
@@ -231,12 +296,8 @@ impl<V: GroupStoreEntity> GroupStore<V> {
         &mut self,
         keystore: &mut core_crypto_keystore::Connection,
     ) -> CryptoResult<Vec<GroupStoreValue<V>>> {
-        let mut keystore_connection = keystore
-            .borrow_conn()
-            .await
-            .map_err(|_| crate::CryptoError::LockPoisonError)?;
-
-        let all = V::fetch_all(&mut keystore_connection)
+        self.evict_unused();
+        let all = V::fetch_all(keystore)
             .await?
             .into_iter()
             .map(|g| {
@@ -249,16 +310,55 @@ impl<V: GroupStoreEntity> GroupStore<V> {
         Ok(all)
     }
 
+    /// Loads the values which are not in memory yet in a single keystore read, so that later lookups are hits.
+    /// Returns how many were loaded, values missing from the keystore being ignored. Not counted in [GroupStoreStats].
+    /// Does nothing for an uncached store, which would drop them right away
+    pub(crate) async fn prefetch(
+        &mut self,
+        ids: &[Vec<u8>],
+        keystore: &mut core_crypto_keystore::Connection,
+    ) -> CryptoResult<usize> {
+        if self.2 == GroupStoreMode::Uncached {
+            return Ok(0);
+        }
+        let missing = ids
+            .iter()
+            .filter(|id| self.0.peek(id.as_slice()).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+
+        let fetched = V::fetch_many(&missing, keystore).await?;
+        let count = fetched.len();
+        for value in fetched {
+            let id = value.id().to_vec();
+            self.insert_prepped(id, std::sync::Arc::new(async_lock::RwLock::new(value)));
+        }
+        Ok(count)
+    }
+
+    pub(crate) fn stats(&self) -> GroupStoreStats {
+        self.1
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
     fn insert_prepped(&mut self, k: Vec<u8>, prepped_entity: GroupStoreValue<V>) {
         self.0.insert(k, prepped_entity);
     }
 
     pub(crate) fn insert(&mut self, k: Vec<u8>, entity: V) {
+        self.evict_unused();
         let value_to_insert = std::sync::Arc::new(async_lock::RwLock::new(entity));
         self.insert_prepped(k, value_to_insert)
     }
 
     pub(crate) fn try_insert(&mut self, k: Vec<u8>, entity: V) -> Result<(), V> {
+        self.evict_unused();
         let value_to_insert = std::sync::Arc::new(async_lock::RwLock::new(entity));
 
         if self.0.try_insert(k, value_to_insert.clone()) {
@@ -269,6 +369,26 @@ impl<V: GroupStoreEntity> GroupStore<V> {
         }
     }
 
+    /// Drops the values of an uncached store which are neither used by an operation nor hold unpersisted state
+    fn evict_unused(&mut self) {
+        if self.2 == GroupStoreMode::Cached {
+            return;
+        }
+        let unused = self
+            .0
+            .iter()
+            .filter(|(_, v)| {
+                // nobody else holding the value, it can't be locked either
+                std::sync::Arc::strong_count(v) == 1
+                    && v.try_read().is_some_and(|value| !value.holds_unpersisted_state())
+            })
+            .map(|(k, _)| k.clone())
+            .collect::<Vec<_>>();
+        for k in unused {
+            self.0.remove(&k);
+        }
+    }
+
     pub(crate) fn remove(&mut self, k: &[u8]) -> Option<GroupStoreValue<V>> {
         self.0.remove(k)
     }
@@ -276,6 +396,12 @@ impl<V: GroupStoreEntity> GroupStore<V> {
     pub(crate) fn get(&mut self, k: &[u8]) -> Option<&mut GroupStoreValue<V>> {
         self.0.get(k)
     }
+
+    /// Values currently held in memory. Unlike [GroupStore::get], it does not affect their eviction order
+    #[allow(dead_code)]
+    pub(crate) fn values(&self) -> impl Iterator<Item = &GroupStoreValue<V>> {
+        self.0.iter().map(|(_, v)| v)
+    }
 }
 
 pub(crate) struct HybridMemoryLimiter {
@@ -373,15 +499,17 @@ mod tests {
         async fn fetch_from_id(
             id: &[u8],
             _identity: Option<Self::IdentityType>,
-            _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+            _keystore: &core_crypto_keystore::Connection,
         ) -> crate::CryptoResult<Option<Self>> {
             let id = std::str::from_utf8(id)?;
             Ok(Some(id.into()))
         }
 
-        async fn fetch_all(
-            _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-        ) -> CryptoResult<Vec<Self>> {
+        async fn fetch_all(_keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
+            unreachable!()
+        }
+
+        async fn fetch_many(_ids: &[Vec<u8>], _keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
             unreachable!()
         }
     }
@@ -441,6 +569,24 @@ mod tests {
         assert_eq!(store.len(), 2);
     }
 
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn group_store_uncached_drops_unused_values() {
+        let mut store = TestGroupStore::uncached();
+        assert_eq!(store.mode(), GroupStoreMode::Uncached);
+        store.insert(b"1".to_vec(), "1".into());
+        let in_use = store.get(b"1").unwrap().clone();
+        store.insert(b"2".to_vec(), "2".into());
+        assert!(store.contains_key(b"1"));
+        assert_eq!(store.len(), 2);
+
+        drop(in_use);
+        store.insert(b"3".to_vec(), "3".into());
+        assert!(!store.contains_key(b"1"));
+        assert!(!store.contains_key(b"2"));
+        assert_eq!(store.len(), 1);
+    }
+
     #[async_std::test]
     #[wasm_bindgen_test]
     async fn group_store_operations_mem_limiter() {
@@ -0,0 +1,244 @@
+//! A client having both a Proteus identity and a MLS credential can prove they belong together with an
+//! [IdentityBinding]: each key signs the fingerprint of the other one along with the client id. A remote client which
+//! already trusts either key, e.g. the Proteus identity it has a verified session with, can then trust the other one
+//! once the binding has been verified.
+//!
+//! The content signed by either key is `WIRE:IDENTITY-BINDING:1:<signer>:<client id>:<fingerprint>`, where the signer
+//! is `PROTEUS` or `MLS` and the fingerprint is the one of the other key. Binary fields are hex-encoded, the signer
+//! being part of the content prevents a signature from being replayed as the one of the other key.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme, OpenMlsCryptoProvider};
+use zeroize::Zeroizing;
+
+use crate::{
+    prelude::{ClientId, CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsError},
+    CoreCrypto,
+};
+
+const BINDING_PREFIX: &str = "WIRE:IDENTITY-BINDING:1";
+
+/// Statement binding the Proteus identity and the MLS credential of a client, signed by both keys.
+/// See [CoreCrypto::generate_identity_binding] and [CoreCrypto::verify_identity_binding]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IdentityBinding {
+    /// Client both keys belong to
+    pub client_id: ClientId,
+    /// Ciphersuite whose signature scheme the MLS signature key uses
+    pub ciphersuite: MlsCiphersuite,
+    /// Public key of the Proteus identity
+    pub proteus_public_key: Vec<u8>,
+    /// Public signature key of the MLS credential
+    pub mls_public_key: Vec<u8>,
+    /// Signature of the MLS public key fingerprint by the Proteus identity
+    pub proteus_signature: Vec<u8>,
+    /// Signature of the Proteus public key fingerprint by the MLS signature key
+    pub mls_signature: Vec<u8>,
+}
+
+impl IdentityBinding {
+    /// Hex-encoded fingerprint of the Proteus identity, to be compared with the one of a Proteus session e.g.
+    /// [CoreCrypto::proteus_fingerprint_remote]
+    pub fn proteus_fingerprint(&self) -> String {
+        hex::encode(&self.proteus_public_key)
+    }
+
+    /// Serializes the binding to be sent to a remote client
+    pub fn to_bytes(&self) -> CryptoResult<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Parses a binding received from a remote client. It still has to be verified with
+    /// [CoreCrypto::verify_identity_binding]
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidIdentityBinding] when the binding is malformed
+    pub fn try_from_bytes(bytes: &[u8]) -> CryptoResult<Self> {
+        serde_json::from_slice(bytes).map_err(|_| CryptoError::InvalidIdentityBinding)
+    }
+}
+
+/// Content signed by the `signer` key, `other_public_key` being the public key of the other one
+fn to_be_signed(signer: &str, client_id: &ClientId, other_public_key: &[u8]) -> Vec<u8> {
+    format!(
+        "{BINDING_PREFIX}:{signer}:{}:{}",
+        hex::encode(client_id.as_slice()),
+        hex::encode(other_public_key)
+    )
+    .into_bytes()
+}
+
+impl CoreCrypto {
+    /// Creates a binding between the Proteus identity and the most recent MLS credential of the given type, each key
+    /// signing the fingerprint of the other one along with the client id.
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first and the MLS one
+    /// as well, or an error will be returned
+    ///
+    /// # Arguments
+    /// * `ciphersuite` - whose signature scheme the MLS credential uses
+    /// * `credential_type` - of the MLS credential to bind
+    ///
+    /// # Errors
+    /// [CryptoError::ClientSignatureNotFound] when the client has no such credential
+    pub fn generate_identity_binding(
+        &self,
+        ciphersuite: MlsCiphersuite,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<IdentityBinding> {
+        let proteus_identity = self.proteus_identity()?;
+        let client = self.mls.mls_client()?;
+        let cb = client
+            .find_most_recent_credential_bundle(ciphersuite.signature_algorithm(), credential_type)
+            .ok_or(CryptoError::ClientSignatureNotFound)?;
+        let client_id = client.id().clone();
+
+        let proteus_public_key = proteus_identity.public_key.public_key.as_slice().to_vec();
+        let mls_public_key = cb.signature_key.to_public_vec();
+
+        let crypto = self.mls.mls_backend.crypto();
+        let proteus_secret_key = Zeroizing::new(proteus_identity.secret_key.to_keypair_bytes());
+        let proteus_signature = crypto
+            .sign(
+                SignatureScheme::ED25519,
+                &to_be_signed("PROTEUS", &client_id, &mls_public_key),
+                proteus_secret_key.as_slice(),
+            )
+            .map_err(MlsError::from)?;
+        let mls_signature = crypto
+            .sign(
+                ciphersuite.signature_algorithm(),
+                &to_be_signed("MLS", &client_id, &proteus_public_key),
+                cb.signature_key.private(),
+            )
+            .map_err(MlsError::from)?;
+
+        Ok(IdentityBinding {
+            client_id,
+            ciphersuite,
+            proteus_public_key,
+            mls_public_key,
+            proteus_signature,
+            mls_signature,
+        })
+    }
+
+    /// Verifies the signatures of a binding received from a remote client. It only proves that both keys belong to
+    /// the same client: the caller still has to check that the client id and either key are the expected ones, e.g.
+    /// the Proteus identity against the remote fingerprint of a verified session.
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidIdentityBinding] when either signature is invalid
+    pub fn verify_identity_binding(&self, binding: &IdentityBinding) -> CryptoResult<()> {
+        let crypto = self.mls.mls_backend.crypto();
+        let proteus_valid = crypto
+            .verify_signature(
+                SignatureScheme::ED25519,
+                &to_be_signed("PROTEUS", &binding.client_id, &binding.mls_public_key),
+                &binding.proteus_public_key,
+                &binding.proteus_signature,
+            )
+            .is_ok();
+        let mls_valid = crypto
+            .verify_signature(
+                binding.ciphersuite.signature_algorithm(),
+                &to_be_signed("MLS", &binding.client_id, &binding.proteus_public_key),
+                &binding.mls_public_key,
+                &binding.mls_signature,
+            )
+            .is_ok();
+
+        if proteus_valid && mls_valid {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidIdentityBinding)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{
+        prelude::{MlsCentral, MlsCentralConfiguration, INITIAL_KEYING_MATERIAL_COUNT},
+        test_utils::*,
+    };
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn new_core_crypto(case: &TestCase, path: String, client_id: &str) -> CoreCrypto {
+        let cfg = MlsCentralConfiguration::try_new(
+            path,
+            "test".to_string(),
+            Some(client_id.into()),
+            vec![case.ciphersuite()],
+            None,
+            Some(INITIAL_KEYING_MATERIAL_COUNT),
+        )
+        .unwrap();
+        let mut cc: CoreCrypto = MlsCentral::try_new(cfg).await.unwrap().into();
+        cc.proteus_init().await.unwrap();
+        cc
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    async fn can_generate_and_verify_identity_binding(case: TestCase) {
+        #[cfg(not(target_family = "wasm"))]
+        let (alice_path, alice_db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (alice_path, _) = tmp_db_file();
+        #[cfg(not(target_family = "wasm"))]
+        let (bob_path, bob_db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (bob_path, _) = tmp_db_file();
+        let alice = new_core_crypto(&case, alice_path, "alice").await;
+        let bob = new_core_crypto(&case, bob_path, "bob").await;
+
+        let binding = alice
+            .generate_identity_binding(case.ciphersuite(), MlsCredentialType::Basic)
+            .unwrap();
+        assert_eq!(binding.client_id, alice.client_id().unwrap());
+        assert_eq!(binding.proteus_fingerprint(), alice.proteus_fingerprint().unwrap());
+        assert_eq!(
+            binding.mls_public_key,
+            alice
+                .client_public_key(case.ciphersuite(), MlsCredentialType::Basic)
+                .unwrap()
+        );
+
+        // verified by a remote client after going over the wire
+        let binding = IdentityBinding::try_from_bytes(&binding.to_bytes().unwrap()).unwrap();
+        bob.verify_identity_binding(&binding).unwrap();
+
+        // claiming to be another client
+        let mut tampered = binding.clone();
+        tampered.client_id = bob.client_id().unwrap();
+        let error = bob.verify_identity_binding(&tampered).unwrap_err();
+        assert!(matches!(error, CryptoError::InvalidIdentityBinding));
+
+        // binding the Proteus identity of alice with the MLS credential of bob
+        let mut tampered = binding.clone();
+        tampered.mls_public_key = bob
+            .client_public_key(case.ciphersuite(), MlsCredentialType::Basic)
+            .unwrap();
+        let error = bob.verify_identity_binding(&tampered).unwrap_err();
+        assert!(matches!(error, CryptoError::InvalidIdentityBinding));
+
+        // a signature can't be replayed as the one of the other key
+        let mut tampered = binding;
+        tampered.mls_signature = tampered.proteus_signature.clone();
+        let error = bob.verify_identity_binding(&tampered).unwrap_err();
+        assert!(matches!(error, CryptoError::InvalidIdentityBinding));
+
+        let error = IdentityBinding::try_from_bytes(b"not a binding").unwrap_err();
+        assert!(matches!(error, CryptoError::InvalidIdentityBinding));
+        #[cfg(not(target_family = "wasm"))]
+        {
+            drop(alice_db_file);
+            drop(bob_db_file);
+        }
+    }
+}
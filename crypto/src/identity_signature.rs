@@ -0,0 +1,224 @@
+//! Signatures of arbitrary data, e.g. a backup file, by the identity keys of the client: the signature key of a MLS
+//! credential or the Proteus identity. The private keys never leave CoreCrypto, a remote client only needs the public
+//! key it already trusts to verify an [IdentitySignature].
+//!
+//! The content signed is `WIRE:IDENTITY-SIGNATURE:1:` followed by the data, so that such a signature can't be replayed
+//! as one of the MLS or Proteus protocols made by the same key.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme, OpenMlsCryptoProvider};
+
+use crate::{
+    prelude::{CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsError},
+    CoreCrypto,
+};
+
+const SIGNATURE_PREFIX: &[u8] = b"WIRE:IDENTITY-SIGNATURE:1:";
+
+/// Identity key signing with [CoreCrypto::sign_with_identity]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentitySignatureScheme {
+    /// The signature key of the most recent MLS credential of the given type, with the signature scheme of the
+    /// ciphersuite
+    Mls(MlsCiphersuite, MlsCredentialType),
+    /// The Proteus identity, signing with Ed25519
+    Proteus,
+}
+
+/// Signature of some data by an identity key of a client, see [CoreCrypto::sign_with_identity] and
+/// [CoreCrypto::verify_with_identity]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IdentitySignature {
+    /// Ciphersuite whose signature scheme the MLS signature key uses, none for the Proteus identity
+    pub ciphersuite: Option<MlsCiphersuite>,
+    /// Public key of the signer
+    pub public_key: Vec<u8>,
+    /// Signature of the data
+    pub signature: Vec<u8>,
+}
+
+impl IdentitySignature {
+    /// Serializes the signature, e.g. to be stored along with a backup
+    pub fn to_bytes(&self) -> CryptoResult<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Parses a serialized signature. It still has to be verified with [CoreCrypto::verify_with_identity]
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidIdentitySignature] when the signature is malformed
+    pub fn try_from_bytes(bytes: &[u8]) -> CryptoResult<Self> {
+        serde_json::from_slice(bytes).map_err(|_| CryptoError::InvalidIdentitySignature)
+    }
+
+    fn signature_scheme(&self) -> SignatureScheme {
+        self.ciphersuite
+            .map(|ciphersuite| ciphersuite.signature_algorithm())
+            .unwrap_or(SignatureScheme::ED25519)
+    }
+}
+
+fn to_be_signed(data: &[u8]) -> Vec<u8> {
+    [SIGNATURE_PREFIX, data].concat()
+}
+
+impl CoreCrypto {
+    /// Signs `data` with an identity key of the client, without the private key ever leaving CoreCrypto.
+    ///
+    /// Warning: Signing with the Proteus identity requires the Proteus client to be initialized with
+    /// [CoreCrypto::proteus_init] first, or an error will be returned
+    ///
+    /// # Arguments
+    /// * `data` - what to sign, e.g. the content of a backup
+    /// * `scheme` - the identity key to sign with
+    ///
+    /// # Errors
+    /// [CryptoError::ClientSignatureNotFound] when the client has no such MLS credential and
+    /// [CryptoError::ProteusSupportNotEnabled] when signing with the Proteus identity without the `proteus` feature
+    pub fn sign_with_identity(&self, data: &[u8], scheme: IdentitySignatureScheme) -> CryptoResult<IdentitySignature> {
+        let crypto = self.mls.mls_backend.crypto();
+        let to_be_signed = to_be_signed(data);
+        match scheme {
+            IdentitySignatureScheme::Mls(ciphersuite, credential_type) => {
+                let cb = self
+                    .mls
+                    .mls_client()?
+                    .find_most_recent_credential_bundle(ciphersuite.signature_algorithm(), credential_type)
+                    .ok_or(CryptoError::ClientSignatureNotFound)?;
+                let signature = crypto
+                    .sign(
+                        ciphersuite.signature_algorithm(),
+                        &to_be_signed,
+                        cb.signature_key.private(),
+                    )
+                    .map_err(MlsError::from)?;
+                Ok(IdentitySignature {
+                    ciphersuite: Some(ciphersuite),
+                    public_key: cb.signature_key.to_public_vec(),
+                    signature,
+                })
+            }
+            #[cfg(feature = "proteus")]
+            IdentitySignatureScheme::Proteus => {
+                let identity = self.proteus_identity()?;
+                let secret_key = zeroize::Zeroizing::new(identity.secret_key.to_keypair_bytes());
+                let signature = crypto
+                    .sign(SignatureScheme::ED25519, &to_be_signed, secret_key.as_slice())
+                    .map_err(MlsError::from)?;
+                Ok(IdentitySignature {
+                    ciphersuite: None,
+                    public_key: identity.public_key.public_key.as_slice().to_vec(),
+                    signature,
+                })
+            }
+            #[cfg(not(feature = "proteus"))]
+            IdentitySignatureScheme::Proteus => Err(CryptoError::ProteusSupportNotEnabled("proteus".into())),
+        }
+    }
+
+    /// Verifies that `signature` has been made over `data` by the identity key `public_key`. The public key has to
+    /// come from a trusted source, e.g. the credential of the client in a conversation or the remote fingerprint of a
+    /// verified Proteus session: the one the signature carries is not trusted. Neither MLS nor Proteus have to be
+    /// initialized.
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidIdentitySignature] when the signature was not made by this key over this data
+    pub fn verify_with_identity(
+        &self,
+        public_key: &[u8],
+        data: &[u8],
+        signature: &IdentitySignature,
+    ) -> CryptoResult<()> {
+        if signature.public_key != public_key {
+            return Err(CryptoError::InvalidIdentitySignature);
+        }
+        self.mls
+            .mls_backend
+            .crypto()
+            .verify_signature(
+                signature.signature_scheme(),
+                &to_be_signed(data),
+                public_key,
+                &signature.signature,
+            )
+            .map_err(|_| CryptoError::InvalidIdentitySignature)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_sign_and_verify_with_identity(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice", "bob"], move |[alice_central, bob_central]| {
+            Box::pin(async move {
+                let alice = CoreCrypto::from(alice_central.mls_central);
+                let bob = CoreCrypto::from(bob_central.mls_central);
+                let backup = b"some backup".to_vec();
+
+                let scheme = IdentitySignatureScheme::Mls(case.ciphersuite(), case.credential_type);
+                let signature = alice.sign_with_identity(&backup, scheme).unwrap();
+                let public_key = alice
+                    .client_public_key(case.ciphersuite(), case.credential_type)
+                    .unwrap();
+                assert_eq!(signature.public_key, public_key);
+
+                // verified after being stored along with the backup
+                let signature = IdentitySignature::try_from_bytes(&signature.to_bytes().unwrap()).unwrap();
+                bob.verify_with_identity(&public_key, &backup, &signature).unwrap();
+
+                // tampered backup
+                let error = bob
+                    .verify_with_identity(&public_key, b"another backup", &signature)
+                    .unwrap_err();
+                assert!(matches!(error, CryptoError::InvalidIdentitySignature));
+
+                // signed by someone else claiming to be alice
+                let forged = bob.sign_with_identity(&backup, scheme).unwrap();
+                let error = bob.verify_with_identity(&public_key, &backup, &forged).unwrap_err();
+                assert!(matches!(error, CryptoError::InvalidIdentitySignature));
+                let mut forged = forged;
+                forged.public_key = public_key.clone();
+                let error = bob.verify_with_identity(&public_key, &backup, &forged).unwrap_err();
+                assert!(matches!(error, CryptoError::InvalidIdentitySignature));
+
+                let error = IdentitySignature::try_from_bytes(b"not a signature").unwrap_err();
+                assert!(matches!(error, CryptoError::InvalidIdentitySignature));
+            })
+        })
+        .await
+    }
+
+    #[cfg(feature = "proteus")]
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_sign_and_verify_with_proteus_identity(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[alice_central]| {
+            Box::pin(async move {
+                let mut alice = CoreCrypto::from(alice_central.mls_central);
+                let backup = b"some backup".to_vec();
+                let error = alice
+                    .sign_with_identity(&backup, IdentitySignatureScheme::Proteus)
+                    .unwrap_err();
+                assert!(matches!(error, CryptoError::ProteusNotInitialized));
+
+                alice.proteus_init().await.unwrap();
+                let signature = alice
+                    .sign_with_identity(&backup, IdentitySignatureScheme::Proteus)
+                    .unwrap();
+                assert_eq!(hex::encode(&signature.public_key), alice.proteus_fingerprint().unwrap());
+                alice
+                    .verify_with_identity(&signature.public_key, &backup, &signature)
+                    .unwrap();
+            })
+        })
+        .await
+    }
+}
@@ -50,21 +50,61 @@ pub mod e2e_identity;
 /// Proteus Abstraction
 pub mod proteus;
 
+#[cfg(feature = "proteus")]
+/// Binding between the Proteus identity and the MLS credential of a client
+pub mod identity_binding;
+
+/// Signatures of arbitrary data by the identity keys of the client
+pub mod identity_signature;
+
+/// Payloads of the security verification screen
+pub mod verification;
+
+/// Cancellation of long-running operations
+pub mod cancellation;
+
 mod group_store;
 
+mod buffer_pool;
+
+mod build_metadata;
+
+mod diagnostic;
+
+/// Reporting of the issues recovered from internally
+pub mod logger;
+
+#[cfg(feature = "self-test")]
+mod self_test;
+
+mod context;
+
 /// Common imports that should be useful for most uses of the crate
 pub mod prelude {
     pub use openmls::{
         group::{MlsGroup, MlsGroupConfig},
         prelude::{
             group_info::VerifiableGroupInfo, Ciphersuite as CiphersuiteName, Credential, GroupEpoch, KeyPackage,
-            KeyPackageIn, KeyPackageRef, MlsMessageIn, Node,
+            KeyPackageIn, KeyPackageRef, MlsMessageIn, Node, RatchetTreeIn,
         },
     };
 
+    pub use core_crypto_keystore::{
+        connection::{FileProtectionClass, KeyProvider, PersistenceFailureCallback},
+        CryptoKeystoreError,
+    };
     pub use mls_crypto_provider::{EntropySeed, MlsCryptoProvider, RawEntropySeed};
 
+    #[cfg(feature = "self-test")]
+    pub use crate::self_test::{SelfTestCheck, SelfTestCheckKind, SelfTestReport};
+
+    #[cfg(feature = "rfc-export")]
+    pub use crate::mls::conversation::rfc_export::MlsRfcGroupState;
+
     pub use crate::{
+        build_metadata::BuildMetadata,
+        cancellation::CancellationToken,
+        diagnostic::{RedactionLevel, DIAGNOSTIC_REPORT_MAX_CONVERSATIONS},
         e2e_identity::{
             conversation_state::E2eiConversationState,
             device_status::DeviceStatus,
@@ -75,25 +115,55 @@ pub mod prelude {
             E2eiEnrollment,
         },
         error::*,
+        logger::{set_logger, CoreCryptoLogLevel, CoreCryptoLogger},
         mls::{
             ciphersuite::MlsCiphersuite,
-            client::id::ClientId,
+            client::id::{ClientId, QualifiedClientId},
             client::identifier::ClientIdentifier,
-            client::key_package::INITIAL_KEYING_MATERIAL_COUNT,
+            client::key_package::{
+                KeyPackageValidationReport, MlsKeyPackageUploadPayload, CLOCK_SKEW_DEFAULT_LEEWAY,
+                INITIAL_KEYING_MATERIAL_COUNT,
+            },
             client::*,
             config::MlsCentralConfiguration,
             conversation::{
+                batch_commit::DEFAULT_COMMIT_CONCURRENCY,
+                claimed_key_package::ClaimedKeyPackageBundle,
                 commit::{MlsCommitBundle, MlsConversationCreationMessage},
-                config::{MlsConversationConfiguration, MlsCustomConfiguration, MlsWirePolicy},
+                commit_delta::MlsCommitDelta,
+                config::{
+                    MlsConversationConfiguration, MlsCustomConfiguration, MlsEpochFence, MlsJoinPolicy,
+                    MlsRequiredCapabilities, MlsWirePolicy,
+                },
                 decrypt::{MlsBufferedConversationDecryptMessage, MlsConversationDecryptMessage},
+                epoch_cleanup::MlsEpochCleanupMetrics,
+                epoch_history::{MlsEpochHistoryEntry, EPOCH_HISTORY_RETENTION},
+                external_sender::MlsExternalSender,
+                group_context_extensions::MlsGroupContextExtensions,
                 group_info::{GroupInfoPayload, MlsGroupInfoBundle, MlsGroupInfoEncryptionType, MlsRatchetTreeType},
+                health::{MlsConversationHealth, MlsConversationHealthObserver, MlsConversationHealthThresholds},
+                idempotent_commit::{MlsCommitOperation, MlsIdempotentCommitBundle},
+                prefetch::MlsConversationCacheStats,
+                preview::MlsCommitPreview,
                 proposal::MlsProposalBundle,
+                ratchet_diagnostics::MlsRatchetDiagnostics,
+                registry::MlsConversationSummary,
+                seen_credential::{MlsCredentialFirstSeen, MlsCredentialObserver},
+                stats::MlsConversationStats,
                 welcome::WelcomeBundle,
+                wipe::MlsConversationWipeReport,
                 *,
             },
-            credential::{typ::MlsCredentialType, x509::CertificateBundle},
+            credential::{typ::MlsCredentialType, x509::CertificateBundle, PublicKeyBundle},
+            deserialize::{MlsMessageLimits, MAX_GROUP_INFO_SIZE, MAX_MESSAGE_SIZE, MAX_WELCOME_SIZE},
             external_commit::MlsConversationInitBundle,
-            proposal::{MlsProposal, MlsProposalRef},
+            maintenance::{
+                MlsMaintenanceItem, MlsMaintenancePriority, MlsMaintenanceTask, MAINTENANCE_VACUUM_THRESHOLD,
+            },
+            metrics::{MlsMetricsSnapshot, MlsOperationMetrics},
+            proposal::{MlsProposal, MlsProposalInfo, MlsProposalRef, MlsProposalType},
+            public_group_state::{PublicGroupStateInspector, PublicGroupStateMember},
+            restore::{MlsGroupRestoreProgress, GROUP_RESTORE_BATCH_SIZE},
             MlsCentral,
         },
         CoreCrypto, CoreCryptoCallbacks,
@@ -143,6 +213,37 @@ pub trait CoreCryptoCallbacks: std::fmt::Debug + Send + Sync {
         existing_clients: Vec<prelude::ClientId>,
         parent_conversation_clients: Option<Vec<prelude::ClientId>>,
     ) -> bool;
+    /// Function responsible for authorizing a commit, received from another member, adding or removing clients
+    /// from the group. It is only called before merging such a commit, hence rejecting it leaves the group untouched.
+    /// Returns `true` if the membership changes are authorized.
+    ///
+    /// # Arguments
+    /// * `conversation_id` - id of the group/conversation
+    /// * `committer` - id of the client who created the commit
+    /// * `added_clients` - clients added to the group by this commit
+    /// * `removed_clients` - clients removed from the group by this commit
+    async fn member_change_authorize(
+        &self,
+        conversation_id: prelude::ConversationId,
+        committer: prelude::ClientId,
+        added_clients: Vec<prelude::ClientId>,
+        removed_clients: Vec<prelude::ClientId>,
+    ) -> bool;
+    /// Function asking the user to confirm that the remote identity of a Proteus session has changed, e.g. because
+    /// the remote client has been reinstalled. It is called when a session is created with a remote identity other
+    /// than the one previously recorded for the same session id, which could also mean that someone impersonates the
+    /// remote client. Returns `true` if the new identity is trusted.
+    ///
+    /// # Arguments
+    /// * `session_id` - id of the Proteus session
+    /// * `previous_fingerprint` - hex-encoded fingerprint of the remote identity recorded so far
+    /// * `new_fingerprint` - hex-encoded fingerprint of the remote identity the session is created with
+    async fn proteus_identity_changed(
+        &self,
+        session_id: String,
+        previous_fingerprint: String,
+        new_fingerprint: String,
+    ) -> bool;
 }
 
 #[derive(Debug)]
@@ -186,6 +287,16 @@ impl CoreCrypto {
     pub fn take(self) -> mls::MlsCentral {
         self.mls
     }
+
+    /// Version of CoreCrypto as defined in its `Cargo.toml`
+    pub fn version() -> &'static str {
+        build_metadata::VERSION
+    }
+
+    /// How this copy of CoreCrypto has been built, to be attached to bug reports
+    pub fn build_metadata() -> build_metadata::BuildMetadata {
+        build_metadata::BuildMetadata::current()
+    }
 }
 
 #[cfg(feature = "uniffi")]
@@ -0,0 +1,111 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Some issues are recovered from internally instead of failing the whole operation, e.g. a Proteus session which
+//! can't be found while saving it. They are reported to the [CoreCryptoLogger] registered with [set_logger] so that
+//! they do not go unnoticed. There is a single logger for the whole process: it can be registered before CoreCrypto
+//! is initialized and hence also gets what happens while restoring the persisted state.
+
+use std::sync::{Arc, RwLock};
+
+/// Severity of a log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum CoreCryptoLogLevel {
+    /// Details only useful when debugging CoreCrypto itself
+    Debug = 1,
+    /// Normal operation worth knowing about
+    Info = 2,
+    /// Something went wrong but CoreCrypto recovered from it
+    Warn = 3,
+    /// Something went wrong and could not be recovered from
+    Error = 4,
+}
+
+/// Receives what CoreCrypto logs, see [set_logger]
+pub trait CoreCryptoLogger: std::fmt::Debug + Send + Sync {
+    /// Called for every log entry
+    ///
+    /// # Arguments
+    /// * `level` - severity of the entry
+    /// * `message` - human readable description of what happened
+    /// * `context` - JSON object with the details e.g. the conversation id, `{}` when there are none
+    fn log(&self, level: CoreCryptoLogLevel, message: &str, context: &str);
+}
+
+static LOGGER: RwLock<Option<Arc<dyn CoreCryptoLogger>>> = RwLock::new(None);
+
+/// Registers the logger of the whole process, replacing the previous one. `None` unregisters it
+pub fn set_logger(logger: Option<Arc<dyn CoreCryptoLogger>>) {
+    let mut current = LOGGER.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = logger;
+}
+
+/// Forwards an entry to the registered logger, if any
+pub(crate) fn log(level: CoreCryptoLogLevel, message: &str, context: serde_json::Value) {
+    // the lock is released before calling the logger so that it can register another one
+    let logger = LOGGER.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    if let Some(logger) = logger {
+        logger.log(level, message, &context.to_string());
+    }
+}
+
+/// Reports an issue CoreCrypto recovered from
+pub(crate) fn warn(message: &str, context: serde_json::Value) {
+    log(CoreCryptoLogLevel::Warn, message, context)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Mutex;
+
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Default)]
+    struct RecordingLogger(Mutex<Vec<(CoreCryptoLogLevel, String, String)>>);
+
+    impl CoreCryptoLogger for RecordingLogger {
+        fn log(&self, level: CoreCryptoLogLevel, message: &str, context: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((level, message.to_string(), context.to_string()));
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    pub fn should_forward_warnings_to_the_registered_logger() {
+        // other tests running concurrently may log too, hence only this test's message is looked for
+        let message = "should_forward_warnings_to_the_registered_logger";
+        let logger = Arc::new(RecordingLogger::default());
+        set_logger(Some(logger.clone()));
+        warn(message, serde_json::json!({ "answer": 42 }));
+        set_logger(None);
+        warn(message, serde_json::json!({}));
+
+        let entries = logger.0.lock().unwrap();
+        let entries = entries.iter().filter(|(_, m, _)| m == message).collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        let (level, _, context) = entries[0];
+        assert_eq!(*level, CoreCryptoLogLevel::Warn);
+        assert_eq!(context, r#"{"answer":42}"#);
+    }
+}
@@ -22,6 +22,7 @@ impl MlsCentral {
         let pending_msg = MlsPendingMessage {
             id: pending_group.id.clone(),
             message: message.as_ref().to_vec(),
+            created_at: 0,
         };
         keystore.save::<MlsPendingMessage>(pending_msg).await?;
         Err(CryptoError::UnmergedPendingGroup)
@@ -14,6 +14,24 @@ impl Default for MlsCiphersuite {
     }
 }
 
+impl MlsCiphersuite {
+    /// Whether this ciphersuite relies on a hybrid (classical + post-quantum) KEM
+    pub fn is_post_quantum(&self) -> bool {
+        matches!(
+            self.0,
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519
+        )
+    }
+
+    /// Hybrid ciphersuites are experimental and only usable with the `pq-ciphersuites` feature
+    pub(crate) fn ensure_supported(&self) -> CryptoResult<()> {
+        if self.is_post_quantum() && !cfg!(feature = "pq-ciphersuites") {
+            return Err(CryptoError::UnsupportedCiphersuite((*self).into()));
+        }
+        Ok(())
+    }
+}
+
 impl From<Ciphersuite> for MlsCiphersuite {
     fn from(value: Ciphersuite) -> Self {
         Self(value)
@@ -41,3 +59,30 @@ impl TryFrom<u16> for MlsCiphersuite {
             .into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn hybrid_ciphersuites_should_require_feature() {
+        let classical = MlsCiphersuite::default();
+        assert!(!classical.is_post_quantum());
+        assert!(classical.ensure_supported().is_ok());
+
+        let hybrid = MlsCiphersuite::from(Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519);
+        assert!(hybrid.is_post_quantum());
+        if cfg!(feature = "pq-ciphersuites") {
+            assert!(hybrid.ensure_supported().is_ok());
+        } else {
+            assert!(matches!(
+                hybrid.ensure_supported().unwrap_err(),
+                CryptoError::UnsupportedCiphersuite(0xF031)
+            ));
+        }
+    }
+}
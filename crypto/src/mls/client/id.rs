@@ -14,12 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
-use crate::CryptoError;
+use crate::{CryptoError, CryptoResult};
 
 /// A unique identifier for clients. A client is an identifier for each App a user is using, such as desktop,
 /// mobile, etc. Users can have multiple clients.
 /// More information [here](https://messaginglayersecurity.rocks/mls-architecture/draft-ietf-mls-architecture.html#name-group-members-and-clients)
-#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Deref)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, derive_more::Deref, serde::Serialize, serde::Deserialize)]
 pub struct ClientId(pub(crate) Vec<u8>);
 
 impl From<&[u8]> for ClientId {
@@ -75,3 +75,180 @@ impl std::str::FromStr for ClientId {
         ))
     }
 }
+
+impl ClientId {
+    /// Parses this client id as a [QualifiedClientId]
+    ///
+    /// # Errors
+    /// [CryptoError::MalformedClientId] when it does not follow Wire's qualified format
+    pub fn to_qualified(&self) -> CryptoResult<QualifiedClientId> {
+        self.try_into()
+    }
+}
+
+/// A client id following Wire's qualified format `user-uuid:device-hex@domain` e.g.
+/// `bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com`. Formatting it back yields its canonical form,
+/// with a lowercase user id and device id without leading zeros
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QualifiedClientId {
+    user_id: uuid::Uuid,
+    device_id: u64,
+    domain: String,
+}
+
+impl QualifiedClientId {
+    /// Builds a qualified client id from its parts
+    ///
+    /// # Errors
+    /// [CryptoError::MalformedClientId] when the domain is malformed
+    pub fn try_new(user_id: uuid::Uuid, device_id: u64, domain: impl Into<String>) -> CryptoResult<Self> {
+        let domain = domain.into();
+        Self::validate_domain(&domain)?;
+        Ok(Self {
+            user_id,
+            device_id,
+            domain,
+        })
+    }
+
+    /// Id of the user owning the client
+    pub fn user_id(&self) -> &uuid::Uuid {
+        &self.user_id
+    }
+
+    /// Id of the device of the user
+    pub fn device_id(&self) -> u64 {
+        self.device_id
+    }
+
+    /// Domain of the backend of the user
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn validate_domain(domain: &str) -> CryptoResult<()> {
+        let is_valid = !domain.is_empty()
+            && domain
+                .split('.')
+                .all(|label| !label.is_empty() && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'));
+        if !is_valid {
+            return Err(CryptoError::MalformedClientId("the domain is not a valid domain name"));
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for QualifiedClientId {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> CryptoResult<Self> {
+        let (user_id, rest) = s
+            .split_once(':')
+            .ok_or(CryptoError::MalformedClientId("the device id is missing"))?;
+        let (device_id, domain) = rest
+            .split_once('@')
+            .ok_or(CryptoError::MalformedClientId("the domain is missing"))?;
+
+        let user_id =
+            uuid::Uuid::try_parse(user_id).map_err(|_| CryptoError::MalformedClientId("the user id is not a UUID"))?;
+        // from_str_radix tolerates a sign, hence checking the digits beforehand
+        if device_id.is_empty() || device_id.len() > 16 || !device_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(CryptoError::MalformedClientId(
+                "the device id is not a 64-bit hex number",
+            ));
+        }
+        let device_id = u64::from_str_radix(device_id, 16)
+            .map_err(|_| CryptoError::MalformedClientId("the device id is not a 64-bit hex number"))?;
+
+        Self::try_new(user_id, device_id, domain)
+    }
+}
+
+impl std::fmt::Display for QualifiedClientId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{:x}@{}", self.user_id.hyphenated(), self.device_id, self.domain)
+    }
+}
+
+impl TryFrom<&ClientId> for QualifiedClientId {
+    type Error = CryptoError;
+
+    fn try_from(id: &ClientId) -> CryptoResult<Self> {
+        std::str::from_utf8(id.as_slice())
+            .map_err(|_| CryptoError::MalformedClientId("it is not valid UTF-8"))?
+            .parse()
+    }
+}
+
+impl From<&QualifiedClientId> for ClientId {
+    fn from(id: &QualifiedClientId) -> Self {
+        Self(id.to_string().into_bytes())
+    }
+}
+
+impl From<QualifiedClientId> for ClientId {
+    fn from(id: QualifiedClientId) -> Self {
+        (&id).into()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const QUALIFIED: &str = "bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com";
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    pub async fn should_parse_and_format_qualified_client_id() {
+        let id = QUALIFIED.parse::<QualifiedClientId>().unwrap();
+        assert_eq!(id.user_id().to_string(), "bd4c7053-1c5a-4020-9559-cd7bf7961954");
+        assert_eq!(id.device_id(), 0x4959bc6ab12f2846);
+        assert_eq!(id.domain(), "wire.com");
+        assert_eq!(id.to_string(), QUALIFIED);
+
+        let client_id = ClientId::from(&id);
+        assert_eq!(client_id.as_slice(), QUALIFIED.as_bytes());
+        assert_eq!(client_id.to_qualified().unwrap(), id);
+
+        // formatting yields the canonical form
+        let id = "BD4C7053-1C5A-4020-9559-CD7BF7961954:00A@wire.com"
+            .parse::<QualifiedClientId>()
+            .unwrap();
+        assert_eq!(id.to_string(), "bd4c7053-1c5a-4020-9559-cd7bf7961954:a@wire.com");
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_malformed_qualified_client_id() {
+        for malformed in [
+            "",
+            "alice",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846",
+            "not-a-uuid:4959bc6ab12f2846@wire.com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:@wire.com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:+4959bc6ab12f2846@wire.com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:14959bc6ab12f2846@wire.com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:xyz@wire.com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire..com",
+            "bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com@wire.com",
+        ] {
+            assert!(
+                matches!(
+                    malformed.parse::<QualifiedClientId>().unwrap_err(),
+                    CryptoError::MalformedClientId(_)
+                ),
+                "{malformed}"
+            );
+        }
+        assert!(matches!(
+            ClientId::from(vec![0xFF, 0xFE]).to_qualified().unwrap_err(),
+            CryptoError::MalformedClientId(_)
+        ));
+    }
+}
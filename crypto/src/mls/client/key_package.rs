@@ -16,7 +16,10 @@
 
 use std::collections::HashMap;
 
-use openmls::prelude::{Credential, CredentialWithKey, CryptoConfig, KeyPackage, KeyPackageRef, Lifetime};
+use openmls::prelude::{
+    Credential, CredentialWithKey, CryptoConfig, KeyPackage, KeyPackageIn, KeyPackageRef, KeyPackageVerifyError,
+    Lifetime, ProtocolVersion,
+};
 use openmls_traits::OpenMlsCryptoProvider;
 use tls_codec::{Deserialize, Serialize};
 
@@ -47,6 +50,55 @@ pub const INITIAL_KEYING_MATERIAL_COUNT: usize = 10;
 pub(crate) const KEYPACKAGE_DEFAULT_LIFETIME: std::time::Duration =
     std::time::Duration::from_secs(60 * 60 * 24 * 28 * 3); // ~3 months
 
+/// Maximum range between a KeyPackage's `not_before` and `not_after` tolerated by openmls
+const KEYPACKAGE_MAX_LIFETIME_RANGE: std::time::Duration =
+    std::time::Duration::from_secs(KEYPACKAGE_DEFAULT_LIFETIME.as_secs() + 60 * 60);
+
+/// KeyPackages shaped the way the Delivery Service expects them to be uploaded, see
+/// [MlsCentral::keypackages_upload_payload]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsKeyPackageUploadPayload {
+    /// Base64 encoded TLS serialization of the KeyPackages
+    pub key_packages: Vec<String>,
+    /// References of the KeyPackages in the same order, e.g. to delete them later with [MlsCentral::delete_keypackages]
+    pub refs: Vec<KeyPackageRef>,
+}
+
+impl MlsKeyPackageUploadPayload {
+    /// Body of the upload request i.e. `{"key_packages":["<base64>",...]}`
+    pub fn to_json(&self) -> String {
+        serde_json::json!({ "key_packages": self.key_packages }).to_string()
+    }
+}
+
+/// Outcome of [MlsCentral::validate_keypackage]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPackageValidationReport {
+    /// Ciphersuite of the KeyPackage, none when it is unknown to this client
+    pub ciphersuite: Option<MlsCiphersuite>,
+    /// Whether this client supports the ciphersuite of the KeyPackage
+    pub ciphersuite_supported: bool,
+    /// Type of the credential of the KeyPackage
+    pub credential_type: MlsCredentialType,
+    /// Whether now lies within the lifetime of the KeyPackage. Also false when the KeyPackage has been rejected for
+    /// another reason since its lifetime can't be trusted then
+    pub lifetime_valid: bool,
+    /// Whether the signatures of the KeyPackage and of its LeafNode are valid
+    pub signature_valid: bool,
+    /// Why openmls rejected the KeyPackage, none when it accepted it
+    pub failure: Option<String>,
+}
+
+impl KeyPackageValidationReport {
+    /// Whether the KeyPackage can be used to add its owner to a conversation
+    pub fn is_valid(&self) -> bool {
+        self.ciphersuite_supported && self.lifetime_valid && self.signature_valid && self.failure.is_none()
+    }
+}
+
+/// Default tolerance applied to time-based validations in order to cope with devices whose clock is skewed
+pub const CLOCK_SKEW_DEFAULT_LEEWAY: std::time::Duration = std::time::Duration::from_secs(60 * 60); // 1 hour
+
 impl Client {
     /// Generates a single new keypackage
     ///
@@ -61,9 +113,10 @@ impl Client {
         cs: MlsCiphersuite,
         cb: &CredentialBundle,
     ) -> CryptoResult<KeyPackage> {
+        cs.ensure_supported()?;
         let keypackage = KeyPackage::builder()
             .leaf_node_capabilities(MlsConversationConfiguration::default_leaf_capabilities())
-            .key_package_lifetime(Lifetime::new(self.keypackage_lifetime.as_secs()))
+            .key_package_lifetime(self.stamp_keypackage_lifetime()?)
             .build(
                 CryptoConfig {
                     ciphersuite: cs.into(),
@@ -82,6 +135,19 @@ impl Client {
         Ok(keypackage)
     }
 
+    /// Computes the lifetime extension of a new KeyPackage. Its start is backdated by the clock skew leeway so that
+    /// members whose clock lags behind ours do not consider it as not yet valid
+    fn stamp_keypackage_lifetime(&self) -> CryptoResult<Lifetime> {
+        let now = unix_now()?;
+        let not_before = now.saturating_sub(self.clock_skew_leeway);
+        let range = (self.keypackage_lifetime + self.clock_skew_leeway).min(KEYPACKAGE_MAX_LIFETIME_RANGE);
+        let not_after = not_before + range;
+
+        // openmls does not let us choose the start of the lifetime, so we craft its wire format instead
+        let raw = [not_before.as_secs().to_be_bytes(), not_after.as_secs().to_be_bytes()].concat();
+        Ok(Lifetime::tls_deserialize(&mut raw.as_slice()).map_err(MlsError::from)?)
+    }
+
     /// Requests `count` keying material to be present and returns
     /// a reference to it for the consumer to copy/clone.
     ///
@@ -172,7 +238,7 @@ impl Client {
                     .unwrap_or_default()
             })
             .try_fold(0usize, |mut valid_count, kp| {
-                if !Self::is_mls_keypackage_expired(&kp?) {
+                if !Self::is_mls_keypackage_expired(&kp?, self.clock_skew_leeway) {
                     valid_count += 1;
                 }
                 CryptoResult::Ok(valid_count)
@@ -181,14 +247,24 @@ impl Client {
         Ok(valid_count)
     }
 
+    /// Returns the count of expired KeyPackages in store, whatever their ciphersuite and credential type
+    pub(crate) async fn expired_keypackages_count(&self, backend: &MlsCryptoProvider) -> CryptoResult<usize> {
+        let mut conn = backend.key_store().borrow_conn().await?;
+        let kps = self.find_all_keypackages(&mut conn).await?;
+        Ok(kps
+            .iter()
+            .filter(|(_, kp)| Self::is_mls_keypackage_expired(kp, self.clock_skew_leeway))
+            .count())
+    }
+
     /// Checks if a given OpenMLS [`KeyPackage`] is expired by looking through its extensions,
-    /// finding a lifetime extension and checking if it's valid.
-    fn is_mls_keypackage_expired(kp: &KeyPackage) -> bool {
+    /// finding a lifetime extension and checking if it's valid, tolerating a clock skew of `leeway`.
+    fn is_mls_keypackage_expired(kp: &KeyPackage, leeway: std::time::Duration) -> bool {
         let Some(lifetime) = kp.leaf_node().life_time() else {
             return false;
         };
 
-        !(lifetime.has_acceptable_range() && lifetime.is_valid())
+        !(lifetime.has_acceptable_range() && is_lifetime_valid(lifetime, leeway))
     }
 
     /// Prune the provided KeyPackageRefs from the keystore
@@ -203,6 +279,32 @@ impl Client {
         Ok(())
     }
 
+    /// Deletes the KeyPackages (along with their HPKE private keys and encryption keypairs) whose ciphersuite is not
+    /// in `ciphersuites`, plus the expired ones. Returns the count of KeyPackages remaining for each of `ciphersuites`
+    pub async fn prune_keypackages_not_in(
+        &self,
+        backend: &MlsCryptoProvider,
+        ciphersuites: &[MlsCiphersuite],
+    ) -> CryptoResult<HashMap<MlsCiphersuite, usize>> {
+        let mut conn = backend.key_store().borrow_conn().await?;
+        let kps = self.find_all_keypackages(&mut conn).await?;
+
+        let stale_refs = kps
+            .iter()
+            .filter(|(_, kp)| !ciphersuites.contains(&kp.ciphersuite().into()))
+            .map(|(store_kp, _)| KeyPackageRef::from_slice(&store_kp.keypackage_ref))
+            .collect::<Vec<_>>();
+        let deleted = self._prune_keypackages(&kps, &mut conn, &stale_refs).await?;
+
+        let mut remaining = ciphersuites.iter().map(|cs| (*cs, 0)).collect::<HashMap<_, _>>();
+        for (store_kp, kp) in &kps {
+            if !deleted.contains(&store_kp.keypackage_ref.as_slice()) {
+                remaining.entry(kp.ciphersuite().into()).and_modify(|count| *count += 1);
+            }
+        }
+        Ok(remaining)
+    }
+
     pub(crate) async fn prune_keypackages_and_credential(
         &mut self,
         backend: &MlsCryptoProvider,
@@ -254,7 +356,7 @@ impl Client {
         use core_crypto_keystore::entities::EntityBase as _;
 
         let kp_to_delete = kps.iter().try_fold(vec![], |mut kp_to_delete, (store_kp, kp)| {
-            let is_expired = Self::is_mls_keypackage_expired(kp);
+            let is_expired = Self::is_mls_keypackage_expired(kp, self.clock_skew_leeway);
             let mut to_delete = is_expired;
             if !(is_expired || refs.is_empty()) {
                 // not expired and there are some refs to check
@@ -304,6 +406,44 @@ impl Client {
     pub fn set_keypackage_lifetime(&mut self, duration: std::time::Duration) {
         self.keypackage_lifetime = duration;
     }
+
+    /// Tolerance applied to time-based validations (KeyPackage lifetimes, certificate validity)
+    pub fn clock_skew_leeway(&self) -> std::time::Duration {
+        self.clock_skew_leeway
+    }
+
+    /// see [MlsCentral::set_clock_skew_leeway]
+    pub fn set_clock_skew_leeway(&mut self, leeway: std::time::Duration) {
+        self.clock_skew_leeway = leeway;
+    }
+
+    pub(crate) fn with_clock_skew_leeway(mut self, leeway: std::time::Duration) -> Self {
+        self.clock_skew_leeway = leeway;
+        self
+    }
+}
+
+/// Current time as a duration since the Unix epoch. WASM-compatible.
+pub(crate) fn unix_now() -> CryptoResult<std::time::Duration> {
+    fluvio_wasm_timer::SystemTime::now()
+        .duration_since(fluvio_wasm_timer::UNIX_EPOCH)
+        .map_err(|_| CryptoError::ImplementationError)
+}
+
+/// Verifies that now lies within the [Lifetime] bounds, widened by `leeway` on both sides
+fn is_lifetime_valid(lifetime: &Lifetime, leeway: std::time::Duration) -> bool {
+    let Ok(raw) = lifetime.tls_serialize_detached() else {
+        return false;
+    };
+    let (Ok(not_before), Ok(not_after)) = (raw[..8].try_into(), raw[8..].try_into()) else {
+        return false;
+    };
+    let (not_before, not_after) = (u64::from_be_bytes(not_before), u64::from_be_bytes(not_after));
+    let Ok(now) = unix_now() else {
+        return false;
+    };
+    let (now, leeway) = (now.as_secs(), leeway.as_secs());
+    now.saturating_add(leeway) >= not_before && now <= not_after.saturating_add(leeway)
 }
 
 impl MlsCentral {
@@ -331,6 +471,93 @@ impl MlsCentral {
             .await
     }
 
+    /// Same as [MlsCentral::get_or_create_client_keypackages] but returns the KeyPackages ready to be uploaded to the
+    /// Delivery Service, so that every platform does not have to encode them on its own
+    ///
+    /// # Arguments
+    /// * `ciphersuite` - of the KeyPackages
+    /// * `credential_type` - of the KeyPackages
+    /// * `count` - number of KeyPackages to upload
+    ///
+    /// # Errors
+    /// Errors can happen when accessing the KeyStore
+    pub async fn keypackages_upload_payload(
+        &self,
+        ciphersuite: MlsCiphersuite,
+        credential_type: MlsCredentialType,
+        count: usize,
+    ) -> CryptoResult<MlsKeyPackageUploadPayload> {
+        use base64::Engine as _;
+
+        let key_packages = self
+            .get_or_create_client_keypackages(ciphersuite, credential_type, count)
+            .await?;
+        let mut payload = MlsKeyPackageUploadPayload {
+            key_packages: Vec::with_capacity(key_packages.len()),
+            refs: Vec::with_capacity(key_packages.len()),
+        };
+        for kp in key_packages {
+            let serialized = kp.tls_serialize_detached().map_err(MlsError::from)?;
+            payload
+                .key_packages
+                .push(base64::prelude::BASE64_STANDARD.encode(serialized));
+            payload
+                .refs
+                .push(kp.hash_ref(self.mls_backend.crypto()).map_err(MlsError::from)?);
+        }
+        Ok(payload)
+    }
+
+    /// Checks a KeyPackage claimed from the Delivery Service before adding its owner to a conversation, so that a bad
+    /// one can be skipped and reported to the backend instead of failing the whole commit with an opaque error
+    ///
+    /// # Arguments
+    /// * `key_package` - TLS-serialized KeyPackage
+    ///
+    /// # Errors
+    /// When the KeyPackage is malformed i.e. can't be deserialized
+    pub async fn validate_keypackage(&self, key_package: &[u8]) -> CryptoResult<KeyPackageValidationReport> {
+        use openmls_traits::crypto::OpenMlsCrypto as _;
+
+        let key_package_in = KeyPackageIn::tls_deserialize(&mut &key_package[..]).map_err(MlsError::from)?;
+
+        // a KeyPackage starts with its protocol version followed by its ciphersuite, see RFC 9420 section 10
+        let ciphersuite = key_package
+            .get(2..4)
+            .and_then(|raw| MlsCiphersuite::try_from(u16::from_be_bytes([raw[0], raw[1]])).ok());
+        let ciphersuite_supported = ciphersuite.is_some_and(|cs| {
+            cs.ensure_supported().is_ok() && self.mls_backend.crypto().supported_ciphersuites().contains(&cs.0)
+        });
+        let mut report = KeyPackageValidationReport {
+            ciphersuite,
+            ciphersuite_supported,
+            credential_type: key_package_in.credential().credential_type().into(),
+            lifetime_valid: false,
+            signature_valid: false,
+            failure: None,
+        };
+
+        // the lifetime is checked on its own so that an expired KeyPackage can be told apart from a forged one
+        match key_package_in
+            .standalone_validate(&self.mls_backend, ProtocolVersion::Mls10, false)
+            .await
+        {
+            Ok(kp) => {
+                report.signature_valid = true;
+                report.lifetime_valid = kp.leaf_node().life_time().is_some()
+                    && !Client::is_mls_keypackage_expired(&kp, self.clock_skew_leeway);
+            }
+            Err(e) => {
+                report.signature_valid = !matches!(
+                    e,
+                    KeyPackageVerifyError::InvalidSignature | KeyPackageVerifyError::InvalidLeafNodeSignature
+                );
+                report.failure = Some(e.to_string());
+            }
+        }
+        Ok(report)
+    }
+
     /// Returns the count of valid, non-expired, unclaimed keypackages in store for the given [MlsCiphersuite] and [MlsCredentialType]
     #[cfg_attr(test, crate::idempotent)]
     pub async fn client_valid_key_packages_count(
@@ -343,8 +570,57 @@ impl MlsCentral {
             .await
     }
 
+    /// Sets the tolerance applied to time-based validations in order to cope with devices whose clock is skewed.
+    /// It is applied when:
+    /// * stamping the lifetime of newly generated KeyPackages (their start is backdated by `leeway`)
+    /// * checking whether local KeyPackages are expired
+    /// * checking the lifetime of KeyPackages received from other clients in [MlsCentral::validate_keypackage]
+    /// * checking the validity period of members' certificates in [MlsCentral::e2ei_conversation_state]
+    ///
+    /// Note that openmls still strictly checks the lifetime of the KeyPackages it adds to a conversation. It can be
+    /// set at initialization with [crate::prelude::MlsCentralConfiguration::set_clock_skew_leeway] and defaults to
+    /// [CLOCK_SKEW_DEFAULT_LEEWAY]
+    pub fn set_clock_skew_leeway(&mut self, leeway: std::time::Duration) {
+        self.clock_skew_leeway = leeway;
+        if let Some(client) = self.mls_client.as_mut() {
+            client.set_clock_skew_leeway(leeway);
+        }
+    }
+
+    /// Tolerated clock skew, see [MlsCentral::set_clock_skew_leeway]
+    pub(crate) fn clock_skew_leeway(&self) -> std::time::Duration {
+        self.clock_skew_leeway
+    }
+
+    /// Deletes the KeyPackages whose ciphersuite is not in `ciphersuites` along with their HPKE private keys, for
+    /// example after the list of supported ciphersuites changed on upgrade. Expired KeyPackages are pruned as well.
+    /// KeyPackages of dropped ciphersuites should also be deleted on the backend side, otherwise peers could still
+    /// claim them and invite this client with a Welcome it cannot process
+    ///
+    /// # Arguments
+    /// * `ciphersuites` - the ciphersuites still supported
+    ///
+    /// # Return type
+    /// The count of KeyPackages remaining in store for each of `ciphersuites`, so that the missing ones can be
+    /// generated with [MlsCentral::get_or_create_client_keypackages]
+    ///
+    /// # Errors
+    /// When `ciphersuites` is empty, when MLS has not been initialized and KeyStore errors
+    pub async fn prune_keypackages_not_in(
+        &self,
+        ciphersuites: &[MlsCiphersuite],
+    ) -> CryptoResult<HashMap<MlsCiphersuite, usize>> {
+        if ciphersuites.is_empty() {
+            return Err(CryptoError::ConsumerError);
+        }
+        self.mls_client()?
+            .prune_keypackages_not_in(&self.mls_backend, ciphersuites)
+            .await
+    }
+
     /// Prunes local KeyPackages after making sure they also have been deleted on the backend side
-    /// You should only use this after [MlsCentral::e2ei_rotate_all]
+    /// You should only need this when the commits of [MlsCentral::e2ei_rotate_all] could not all be accepted or
+    /// when there was no conversation to rotate
     #[cfg_attr(test, crate::dispotent)]
     pub async fn delete_keypackages(&mut self, refs: &[KeyPackageRef]) -> CryptoResult<()> {
         if refs.is_empty() {
@@ -357,15 +633,19 @@ impl MlsCentral {
 
 #[cfg(test)]
 pub mod tests {
-    use openmls::prelude::{KeyPackage, KeyPackageIn, KeyPackageRef, ProtocolVersion};
+    use std::collections::HashMap;
+
+    use openmls::prelude::{Ciphersuite, ExtensionType, KeyPackage, KeyPackageIn, KeyPackageRef, ProtocolVersion};
     use openmls_traits::types::VerifiableCiphersuite;
     use openmls_traits::OpenMlsCryptoProvider;
+    use tls_codec::{Deserialize as _, Serialize as _};
     use wasm_bindgen_test::*;
 
     use mls_crypto_provider::MlsCryptoProvider;
 
-    use crate::prelude::key_package::INITIAL_KEYING_MATERIAL_COUNT;
-    use crate::prelude::MlsConversationConfiguration;
+    use crate::mls::conversation::admins::ADMINS_EXTENSION_TYPE;
+    use crate::prelude::key_package::{CLOCK_SKEW_DEFAULT_LEEWAY, INITIAL_KEYING_MATERIAL_COUNT};
+    use crate::prelude::{CryptoError, MlsCiphersuite, MlsConversationConfiguration};
     use crate::test_utils::*;
 
     use super::Client;
@@ -394,16 +674,212 @@ pub mod tests {
         .await
         .unwrap();
 
+        let no_leeway = std::time::Duration::ZERO;
+
         // 90-day standard expiration
         let kp_std_exp = client.generate_one_keypackage(&backend, cs, ct).await.unwrap();
-        assert!(!Client::is_mls_keypackage_expired(&kp_std_exp));
+        assert!(!Client::is_mls_keypackage_expired(&kp_std_exp, no_leeway));
 
         // 1-second expiration
         client.set_keypackage_lifetime(std::time::Duration::from_secs(1));
         let kp_1s_exp = client.generate_one_keypackage(&backend, cs, ct).await.unwrap();
         // Sleep 2 seconds to make sure we make the kp expire
         async_std::task::sleep(std::time::Duration::from_secs(2)).await;
-        assert!(Client::is_mls_keypackage_expired(&kp_1s_exp));
+        assert!(Client::is_mls_keypackage_expired(&kp_1s_exp, no_leeway));
+        // but it is still tolerated when accounting for a clock skew
+        assert!(!Client::is_mls_keypackage_expired(
+            &kp_1s_exp,
+            CLOCK_SKEW_DEFAULT_LEEWAY
+        ));
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_backdate_keypackage_lifetime_by_leeway(case: TestCase) {
+        let (cs, ct) = (case.ciphersuite(), case.credential_type);
+        let backend = MlsCryptoProvider::try_new_in_memory("test").await.unwrap();
+        let x509_test_chain = if case.is_x509() {
+            let x509_test_chain = crate::test_utils::x509::X509TestChain::init_empty(case.signature_scheme());
+            x509_test_chain.register_with_provider(&backend).await;
+            Some(x509_test_chain)
+        } else {
+            None
+        };
+
+        let mut client = Client::random_generate(
+            &case,
+            &backend,
+            x509_test_chain.as_ref().map(|chain| chain.find_local_intermediate_ca()),
+            false,
+        )
+        .await
+        .unwrap();
+        client.set_clock_skew_leeway(std::time::Duration::from_secs(60 * 30));
+
+        let kp = client.generate_one_keypackage(&backend, cs, ct).await.unwrap();
+        let lifetime = kp.leaf_node().life_time().unwrap();
+        // still acceptable for openmls
+        assert!(lifetime.has_acceptable_range());
+        assert!(lifetime.is_valid());
+
+        // a peer whose clock lags 20 minutes behind ours still considers it valid
+        let raw = lifetime.tls_serialize_detached().unwrap();
+        let not_before = u64::from_be_bytes(raw[..8].try_into().unwrap());
+        let now = super::unix_now().unwrap().as_secs();
+        assert!(not_before <= now - 60 * 20);
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_validate_keypackages(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice", "bob"], move |[alice_central, bob_central]| {
+            Box::pin(async move {
+                let kp = bob_central
+                    .mls_central
+                    .get_or_create_client_keypackages(case.ciphersuite(), case.credential_type, 1)
+                    .await
+                    .unwrap()
+                    .remove(0);
+                let mut serialized = kp.tls_serialize_detached().unwrap();
+                let report = alice_central
+                    .mls_central
+                    .validate_keypackage(&serialized)
+                    .await
+                    .unwrap();
+                assert!(report.is_valid(), "{report:?}");
+                assert_eq!(report.ciphersuite, Some(case.ciphersuite()));
+                assert!(report.ciphersuite_supported);
+                assert_eq!(report.credential_type, case.credential_type);
+
+                // the signature of the KeyPackage comes last
+                *serialized.last_mut().unwrap() ^= 0xFF;
+                let report = alice_central
+                    .mls_central
+                    .validate_keypackage(&serialized)
+                    .await
+                    .unwrap();
+                assert!(!report.is_valid());
+                assert!(!report.signature_valid);
+                assert!(report.failure.is_some());
+
+                assert!(alice_central.mls_central.validate_keypackage(&[1, 2, 3]).await.is_err());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_validate_expired_keypackages(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, bob_central]| {
+                Box::pin(async move {
+                    let kp = bob_central
+                        .mls_central
+                        .new_keypackage(&case, openmls::prelude::Lifetime::new(1))
+                        .await;
+                    async_std::task::sleep(std::time::Duration::from_secs(2)).await;
+
+                    // expired a second ago, which is tolerated by the default clock skew leeway
+                    let serialized = kp.tls_serialize_detached().unwrap();
+                    let report = alice_central
+                        .mls_central
+                        .validate_keypackage(&serialized)
+                        .await
+                        .unwrap();
+                    assert!(report.lifetime_valid);
+
+                    alice_central
+                        .mls_central
+                        .set_clock_skew_leeway(std::time::Duration::ZERO);
+                    let report = alice_central
+                        .mls_central
+                        .validate_keypackage(&serialized)
+                        .await
+                        .unwrap();
+                    assert!(!report.is_valid());
+                    assert!(!report.lifetime_valid);
+                    assert!(report.signature_valid);
+                    assert_eq!(report.credential_type, case.credential_type);
+                })
+            },
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn should_apply_clock_skew_leeway_from_configuration() {
+        use crate::prelude::{ClientId, ClientIdentifier, MlsCentral, MlsCentralConfiguration};
+
+        let leeway = std::time::Duration::from_secs(60);
+        let configuration = |client_id: Option<&str>| {
+            let mut configuration = MlsCentralConfiguration::try_new(
+                "test".to_string(),
+                "test".to_string(),
+                client_id.map(ClientId::from),
+                vec![MlsCiphersuite::default()],
+                None,
+                Some(2),
+            )
+            .unwrap();
+            configuration.set_clock_skew_leeway(leeway);
+            configuration
+        };
+
+        let alice = MlsCentral::try_new_in_memory(configuration(Some("alice")))
+            .await
+            .unwrap();
+        assert_eq!(alice.clock_skew_leeway(), leeway);
+        assert_eq!(alice.mls_client.as_ref().unwrap().clock_skew_leeway(), leeway);
+
+        // also applies to a client initialized afterwards
+        let mut bob = MlsCentral::try_new_in_memory(configuration(None)).await.unwrap();
+        assert_eq!(bob.clock_skew_leeway(), leeway);
+        bob.mls_init(
+            ClientIdentifier::Basic("bob".into()),
+            vec![MlsCiphersuite::default()],
+            Some(2),
+        )
+        .await
+        .unwrap();
+        assert_eq!(bob.mls_client.as_ref().unwrap().clock_skew_leeway(), leeway);
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_shape_keypackages_for_upload(case: TestCase) {
+        use base64::Engine as _;
+
+        run_test_with_client_ids(case.clone(), ["alice"], move |[cc]| {
+            Box::pin(async move {
+                let payload = cc
+                    .mls_central
+                    .keypackages_upload_payload(case.ciphersuite(), case.credential_type, 3)
+                    .await
+                    .unwrap();
+                assert_eq!(payload.key_packages.len(), 3);
+                assert_eq!(payload.refs.len(), 3);
+
+                let json: serde_json::Value = serde_json::from_str(&payload.to_json()).unwrap();
+                let uploaded = json["key_packages"].as_array().unwrap();
+                assert_eq!(uploaded.len(), 3);
+                for (uploaded, kp_ref) in uploaded.iter().zip(payload.refs.iter()) {
+                    let bytes = base64::prelude::BASE64_STANDARD
+                        .decode(uploaded.as_str().unwrap())
+                        .unwrap();
+                    let kp = KeyPackageIn::tls_deserialize(&mut bytes.as_slice())
+                        .unwrap()
+                        .standalone_validate(&cc.mls_central.mls_backend, ProtocolVersion::Mls10, true)
+                        .await
+                        .unwrap();
+                    assert_eq!(&kp.hash_ref(cc.mls_central.mls_backend.crypto()).unwrap(), kp_ref);
+                }
+            })
+        })
+        .await
     }
 
     #[apply(all_cred_cipher)]
@@ -489,6 +965,44 @@ pub mod tests {
         .await
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_prune_keypackages_of_dropped_ciphersuites(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[cc]| {
+            Box::pin(async move {
+                let cs = case.ciphersuite();
+                let other = if cs == MlsCiphersuite::default() {
+                    Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519.into()
+                } else {
+                    MlsCiphersuite::default()
+                };
+
+                // an empty list would wipe everything
+                assert!(matches!(
+                    cc.mls_central.prune_keypackages_not_in(&[]).await.unwrap_err(),
+                    CryptoError::ConsumerError
+                ));
+
+                // nothing to prune when the ciphersuite is still supported
+                let remaining = cc.mls_central.prune_keypackages_not_in(&[cs]).await.unwrap();
+                assert_eq!(remaining, HashMap::from([(cs, INITIAL_KEYING_MATERIAL_COUNT)]));
+                let before = cc.mls_central.count_entities().await;
+                assert_eq!(before.key_package, INITIAL_KEYING_MATERIAL_COUNT);
+
+                // once dropped, its KeyPackages and their keys are gone but not the Credential
+                let remaining = cc.mls_central.prune_keypackages_not_in(&[other]).await.unwrap();
+                assert_eq!(remaining, HashMap::from([(other, 0)]));
+                let after = cc.mls_central.count_entities().await;
+                assert_eq!(after.key_package, 0);
+                assert_eq!(after.hpke_private_key, 0);
+                assert_eq!(after.encryption_keypair, 0);
+                assert_eq!(after.credential, before.credential);
+                assert_eq!(after.signature_keypair, before.signature_keypair);
+            })
+        })
+        .await
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn automatically_prunes_lifetime_expired_keypackages(case: TestCase) {
@@ -523,8 +1037,9 @@ pub mod tests {
         assert_eq!(len, unexpired_kpbs.len());
         assert_eq!(len, UNEXPIRED_COUNT);
 
-        // Set the keypackage expiration to be in 2 seconds
+        // Set the keypackage expiration to be in 2 seconds, without tolerating any clock skew
         client.set_keypackage_lifetime(std::time::Duration::from_secs(2));
+        client.set_clock_skew_leeway(std::time::Duration::ZERO);
 
         // Generate new keypackages that are normally partially expired 2s after they're requested
         let partially_expired_kpbs = client
@@ -598,7 +1113,11 @@ pub mod tests {
                         .collect::<Vec<_>>()
                 );
                 assert!(kp.leaf_node().capabilities().proposals().is_empty());
-                assert!(kp.leaf_node().capabilities().extensions().is_empty());
+                // only the admins extension is not a default one
+                assert_eq!(
+                    kp.leaf_node().capabilities().extensions(),
+                    &[ExtensionType::from(ADMINS_EXTENSION_TYPE)]
+                );
                 assert_eq!(
                     kp.leaf_node().capabilities().credentials(),
                     MlsConversationConfiguration::DEFAULT_SUPPORTED_CREDENTIALS
@@ -23,8 +23,10 @@ pub(crate) mod user_id;
 use crate::{
     mls::{credential::ext::CredentialExt, credential::CredentialBundle},
     prelude::{
-        identifier::ClientIdentifier, key_package::KEYPACKAGE_DEFAULT_LIFETIME, CertificateBundle, ClientId,
-        CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsCredentialType, MlsError,
+        identifier::ClientIdentifier,
+        key_package::{CLOCK_SKEW_DEFAULT_LEEWAY, KEYPACKAGE_DEFAULT_LIFETIME},
+        CertificateBundle, ClientId, CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsCredentialType,
+        MlsError,
     },
 };
 use core_crypto_keystore::CryptoKeystoreError;
@@ -34,7 +36,7 @@ use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme, OpenMlsCrypt
 use std::collections::HashSet;
 use tls_codec::{Deserialize, Serialize};
 
-use core_crypto_keystore::entities::{EntityBase, EntityFindParams, MlsCredential, MlsSignatureKeyPair};
+use core_crypto_keystore::entities::{EntityFindParams, MlsCredential, MlsSignatureKeyPair};
 use identities::ClientIdentities;
 use mls_crypto_provider::MlsCryptoProvider;
 
@@ -53,6 +55,7 @@ pub struct Client {
     id: ClientId,
     pub(crate) identities: ClientIdentities,
     keypackage_lifetime: std::time::Duration,
+    clock_skew_leeway: std::time::Duration,
 }
 
 impl Client {
@@ -191,6 +194,7 @@ impl Client {
             id: client_id.clone(),
             identities: ClientIdentities::new(stored_skp.len()),
             keypackage_lifetime: KEYPACKAGE_DEFAULT_LIFETIME,
+            clock_skew_leeway: CLOCK_SKEW_DEFAULT_LEEWAY,
         };
 
         let id = &client_id;
@@ -248,6 +252,7 @@ impl Client {
             id: id.into_owned(),
             identities: ClientIdentities::new(signature_schemes.len()),
             keypackage_lifetime: KEYPACKAGE_DEFAULT_LIFETIME,
+            clock_skew_leeway: CLOCK_SKEW_DEFAULT_LEEWAY,
         };
 
         let identities = identifier.generate_credential_bundles(backend, signature_schemes)?;
@@ -329,6 +334,7 @@ impl Client {
             id: id.clone(),
             identities,
             keypackage_lifetime: KEYPACKAGE_DEFAULT_LIFETIME,
+            clock_skew_leeway: CLOCK_SKEW_DEFAULT_LEEWAY,
         })
     }
 
@@ -357,7 +363,7 @@ impl Client {
         sc: SignatureScheme,
         mut cb: CredentialBundle,
     ) -> CryptoResult<CredentialBundle> {
-        let mut conn = backend.key_store().borrow_conn().await?;
+        let keystore = backend.key_store();
 
         let id = id.unwrap_or_else(|| self.id());
 
@@ -367,7 +373,7 @@ impl Client {
             credential,
             created_at: 0,
         };
-        let created_at = credential.insert(&mut conn).await?;
+        let created_at = keystore.insert(credential).await?;
 
         let sign_kp = MlsSignatureKeyPair::new(
             sc,
@@ -375,7 +381,8 @@ impl Client {
             cb.signature_key.tls_serialize_detached().map_err(MlsError::from)?,
             id.clone().into(),
         );
-        sign_kp.save(&mut conn).await.map_err(|e| match e {
+        // saved through the keystore, which checksums the keypair along with it
+        keystore.save(sign_kp).await.map_err(|e| match e {
             CryptoKeystoreError::AlreadyExists => CryptoError::CredentialBundleConflict,
             _ => e.into(),
         })?;
@@ -524,6 +531,55 @@ pub mod tests {
         .unwrap();
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_checksum_signature_keypairs(case: TestCase) {
+        use core_crypto_keystore::{connection::CorruptedEntry, entities::EntityBase as _};
+
+        let backend = MlsCryptoProvider::try_new_in_memory("test").await.unwrap();
+        let x509_test_chain = if case.is_x509() {
+            let x509_test_chain = crate::test_utils::x509::X509TestChain::init_empty(case.signature_scheme());
+            x509_test_chain.register_with_provider(&backend).await;
+            Some(x509_test_chain)
+        } else {
+            None
+        };
+        let _ = Client::random_generate(
+            &case,
+            &backend,
+            x509_test_chain.as_ref().map(|chain| chain.find_local_intermediate_ca()),
+            false,
+        )
+        .await
+        .unwrap();
+        let keystore = backend.key_store();
+        assert!(keystore.integrity_check().await.unwrap().is_empty());
+
+        // the keypair changes behind the back of its checksum, as a filesystem corruption would do
+        let mut keypair = keystore
+            .find_all::<MlsSignatureKeyPair>(EntityFindParams::default())
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        {
+            let mut conn = keystore.borrow_conn().await.unwrap();
+            MlsSignatureKeyPair::delete(&mut conn, &[keypair.pk.as_slice().into()])
+                .await
+                .unwrap();
+            keypair.keypair = b"garbage".to_vec();
+            keypair.save(&mut conn).await.unwrap();
+        }
+
+        assert_eq!(
+            keystore.integrity_check().await.unwrap(),
+            vec![CorruptedEntry {
+                collection: "mls_signature_keypairs",
+                id: keypair.pk.clone(),
+            }]
+        );
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn can_externally_generate_client(case: TestCase) {
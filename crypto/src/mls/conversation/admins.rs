@@ -0,0 +1,453 @@
+//! A conversation with admins only lets them add or remove members and change who the admins are. MLS itself has no
+//! notion of roles, hence they are listed in a group context extension from the private use range: like any other
+//! extension of the group context, all members share it and only a commit can change it. Every member enforces them on
+//! the commits and proposals it decrypts. Proposals from the external senders of the conversation, e.g. the Delivery
+//! Service removing a client which left, and members proposing their own removal are always accepted.
+
+use std::collections::HashMap;
+
+use openmls::prelude::{
+    Extension, Extensions, LeafNodeIndex, Proposal, QueuedProposal, Sender, StagedCommit, UnknownExtension,
+};
+use tls_codec::{Deserialize as _, Serialize as _, VLBytes};
+
+use crate::prelude::{
+    ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation, MlsError,
+};
+
+/// Type of the group context extension holding the client ids of the admins
+pub(crate) const ADMINS_EXTENSION_TYPE: u16 = 0xF0AD;
+
+/// Admins listed in `extensions`, empty when there is no admins extension
+pub(crate) fn admins_from_extensions(extensions: &Extensions) -> CryptoResult<Vec<ClientId>> {
+    let Some(data) = extensions.iter().find_map(|e| match e {
+        Extension::Unknown(ADMINS_EXTENSION_TYPE, UnknownExtension(data)) => Some(data),
+        _ => None,
+    }) else {
+        return Ok(vec![]);
+    };
+    let admins = Vec::<VLBytes>::tls_deserialize(&mut data.as_slice()).map_err(MlsError::from)?;
+    Ok(admins.iter().map(|admin| ClientId::from(admin.as_slice())).collect())
+}
+
+/// Copy of `extensions` listing `admins` instead of the current ones, without any admins extension when empty
+pub(crate) fn with_admins(extensions: &Extensions, admins: &[ClientId]) -> CryptoResult<Extensions> {
+    let mut extensions = extensions
+        .iter()
+        .filter(|e| u16::from(e.extension_type()) != ADMINS_EXTENSION_TYPE)
+        .cloned()
+        .collect::<Vec<_>>();
+    if !admins.is_empty() {
+        let admins = admins
+            .iter()
+            .map(|admin| VLBytes::new(admin.to_vec()))
+            .collect::<Vec<_>>();
+        let data = admins.tls_serialize_detached().map_err(MlsError::from)?;
+        extensions.push(Extension::Unknown(ADMINS_EXTENSION_TYPE, UnknownExtension(data)));
+    }
+    Ok(Extensions::from_vec(extensions).map_err(MlsError::from)?)
+}
+
+impl MlsConversation {
+    /// Admins of the conversation, empty when anyone can add or remove members
+    pub(crate) fn admins(&self) -> CryptoResult<Vec<ClientId>> {
+        admins_from_extensions(self.group.export_group_context().extensions())
+    }
+
+    /// Fails with [CryptoError::NotConversationAdmin] when the conversation has admins and the client isn't one of
+    /// them
+    pub(crate) fn ensure_admin(&self, client_id: &ClientId) -> CryptoResult<()> {
+        let admins = self.admins()?;
+        if admins.is_empty() || admins.contains(client_id) {
+            Ok(())
+        } else {
+            Err(CryptoError::NotConversationAdmin(client_id.clone()))
+        }
+    }
+
+    /// Checks that every member adding or removing other members or changing the admins in a commit, either by value
+    /// or by reference, is an admin of the conversation. The commit is not merged yet, hence both the admins and the
+    /// proposers are those of the epoch it ends, including the members it removes.
+    pub(crate) fn enforce_admins_in_commit(&self, commit: &StagedCommit) -> CryptoResult<()> {
+        let admins = self.admins()?;
+        if admins.is_empty() {
+            return Ok(());
+        }
+        let members = self.members_by_leaf();
+        commit
+            .queued_proposals()
+            .try_for_each(|proposal| enforce_admins(&admins, &members, proposal))
+    }
+
+    /// Checks that the member proposing to add or remove another member or to change the admins is an admin of the
+    /// conversation
+    pub(crate) fn enforce_admins_in_proposal(&self, proposal: &QueuedProposal) -> CryptoResult<()> {
+        let admins = self.admins()?;
+        if admins.is_empty() {
+            return Ok(());
+        }
+        enforce_admins(&admins, &self.members_by_leaf(), proposal)
+    }
+
+    fn members_by_leaf(&self) -> HashMap<LeafNodeIndex, ClientId> {
+        self.group
+            .members()
+            .map(|m| (m.index, ClientId::from(m.credential.identity())))
+            .collect()
+    }
+}
+
+fn enforce_admins(
+    admins: &[ClientId],
+    members: &HashMap<LeafNodeIndex, ClientId>,
+    proposal: &QueuedProposal,
+) -> CryptoResult<()> {
+    let Sender::Member(proposer) = proposal.sender() else {
+        return Ok(());
+    };
+    let is_restricted = match proposal.proposal() {
+        Proposal::Add(_) => true,
+        Proposal::Remove(remove) => remove.removed() != *proposer,
+        Proposal::GroupContextExtensions(gce) => admins_from_extensions(gce.extensions())? != admins,
+        _ => false,
+    };
+    if !is_restricted {
+        return Ok(());
+    }
+
+    let proposer = members.get(proposer).ok_or(CryptoError::UnauthorizedMemberChange)?;
+    if admins.contains(proposer) {
+        Ok(())
+    } else {
+        Err(CryptoError::NotConversationAdmin(proposer.clone()))
+    }
+}
+
+impl MlsCentral {
+    /// Replaces the admins of a conversation, i.e. the only clients allowed to add or remove members and to change
+    /// the admins, and automatically commits. Anyone can when empty. Pending proposals will be committed
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    /// * `admins` - client ids of the admins
+    ///
+    /// # Return type
+    /// A struct containing a welcome (optional, will be present only if there's pending add proposals in the store),
+    /// a message with the commit to fan out to other clients and the group info will be returned on successful call.
+    ///
+    /// # Errors
+    /// If the conversation can't be found, [CryptoError::NotConversationAdmin] when the conversation has admins and
+    /// this client isn't one of them. Other errors are originating from OpenMls and the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn set_conversation_admins(
+        &mut self,
+        id: &ConversationId,
+        admins: Vec<ClientId>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let conversation = self.get_conversation(id).await?;
+        let client = self.mls_client()?;
+        let mut conversation = conversation.write().await;
+        conversation.ensure_admin(client.id())?;
+        let extensions = with_admins(conversation.group.export_group_context().extensions(), &admins)?;
+        conversation
+            .update_group_context_extensions(client, &self.mls_backend, extensions)
+            .await
+    }
+
+    /// Admins of a conversation, empty when anyone can add or remove members.
+    /// See [MlsCentral::set_conversation_admins]
+    ///
+    /// # Errors
+    /// If the conversation can't be found or its admins extension can't be decoded
+    pub async fn conversation_admins(&mut self, id: &ConversationId) -> CryptoResult<Vec<ClientId>> {
+        self.get_conversation(id).await?.read().await.admins()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn only_admins_can_add_or_remove_members(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.client_id().unwrap();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.admins = vec![alice_id.clone()];
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    // bob shares the admins of the group context without being told about them
+                    assert_eq!(
+                        bob_central.mls_central.conversation_admins(&id).await.unwrap(),
+                        vec![alice_id.clone()]
+                    );
+
+                    // bob is not an admin
+                    let charlie = charlie_central.mls_central.rand_key_package(&case).await;
+                    let error = bob_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![charlie.clone()])
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(_)));
+                    let error = bob_central
+                        .mls_central
+                        .remove_members_from_conversation(&id, &[alice_id.clone()])
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(_)));
+
+                    // a client not enforcing the admins can still create such a commit, it is rejected by the others
+                    let conversation = bob_central.mls_central.get_conversation(&id).await.unwrap();
+                    let MlsConversationCreationMessage { commit, .. } = conversation
+                        .write()
+                        .await
+                        .add_members(
+                            bob_central.mls_central.mls_client().unwrap(),
+                            vec![charlie],
+                            &bob_central.mls_central.mls_backend,
+                        )
+                        .await
+                        .unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let error = alice_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap_err();
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(client_id) if client_id == bob_id));
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                    bob_central.mls_central.clear_pending_commit(&id).await.unwrap();
+
+                    // same goes for proposals
+                    let charlie = charlie_central.mls_central.get_one_key_package(&case).await;
+                    let proposal = bob_central
+                        .mls_central
+                        .new_add_proposal(&id, charlie)
+                        .await
+                        .unwrap()
+                        .proposal;
+                    let error = alice_central
+                        .mls_central
+                        .decrypt_message(&id, proposal.to_bytes().unwrap())
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(_)));
+
+                    // whereas admins can
+                    let charlie = charlie_central.mls_central.rand_key_package(&case).await;
+                    let MlsConversationCreationMessage { commit, .. } = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![charlie])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn only_admins_can_change_the_admins(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.client_id().unwrap();
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.admins = vec![alice_id.clone()];
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // bob can't make himself an admin
+                    let error = bob_central
+                        .mls_central
+                        .set_conversation_admins(&id, vec![bob_id.clone()])
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(_)));
+
+                    // nor can a client not enforcing the admins
+                    let conversation = bob_central.mls_central.get_conversation(&id).await.unwrap();
+                    let MlsCommitBundle { commit, .. } = {
+                        let mut conversation = conversation.write().await;
+                        let extensions = super::with_admins(
+                            conversation.group.export_group_context().extensions(),
+                            &[bob_id.clone()],
+                        )
+                        .unwrap();
+                        conversation
+                            .update_group_context_extensions(
+                                bob_central.mls_central.mls_client().unwrap(),
+                                &bob_central.mls_central.mls_backend,
+                                extensions,
+                            )
+                            .await
+                            .unwrap()
+                    };
+                    let error = alice_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::NotConversationAdmin(client_id) if client_id == bob_id));
+                    bob_central.mls_central.clear_pending_commit(&id).await.unwrap();
+
+                    // whereas admins can, and every member ends up with the same ones
+                    let MlsCommitBundle { commit, .. } = alice_central
+                        .mls_central
+                        .set_conversation_admins(&id, vec![alice_id.clone(), bob_id.clone()])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let admins = vec![alice_id.clone(), bob_id.clone()];
+                    assert_eq!(
+                        alice_central.mls_central.conversation_admins(&id).await.unwrap(),
+                        admins
+                    );
+                    assert_eq!(bob_central.mls_central.conversation_admins(&id).await.unwrap(), admins);
+
+                    // changing the other extensions keeps the admins
+                    let MlsCommitBundle { commit, .. } = bob_central
+                        .mls_central
+                        .update_group_context_extensions(&id, MlsGroupContextExtensions::default())
+                        .await
+                        .unwrap();
+                    bob_central.mls_central.commit_accepted(&id).await.unwrap();
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        alice_central.mls_central.conversation_admins(&id).await.unwrap(),
+                        admins
+                    );
+                    assert_eq!(bob_central.mls_central.conversation_admins(&id).await.unwrap(), admins);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_accept_proposals_of_admins_removed_by_the_same_commit(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie", "dave"],
+            move |[mut alice_central, mut bob_central, mut charlie_central, dave_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.client_id().unwrap();
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.admins = vec![alice_id.clone(), bob_id.clone()];
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(
+                            &case,
+                            &id,
+                            [&mut bob_central.mls_central, &mut charlie_central.mls_central],
+                        )
+                        .await
+                        .unwrap();
+
+                    // bob proposes to add dave, then alice removes bob while committing his proposal
+                    let dave = dave_central.mls_central.get_one_key_package(&case).await;
+                    let add = bob_central
+                        .mls_central
+                        .new_add_proposal(&id, dave)
+                        .await
+                        .unwrap()
+                        .proposal;
+                    for central in [&mut alice_central.mls_central, &mut charlie_central.mls_central] {
+                        central.decrypt_message(&id, add.to_bytes().unwrap()).await.unwrap();
+                    }
+                    let remove = alice_central
+                        .mls_central
+                        .new_remove_proposal(&id, bob_id.clone())
+                        .await
+                        .unwrap()
+                        .proposal;
+                    charlie_central
+                        .mls_central
+                        .decrypt_message(&id, remove.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // bob was still an admin when he made the proposal
+                    charlie_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let members = charlie_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .members();
+                    assert_eq!(members.len(), 3);
+                    assert!(!members.contains_key(bob_id.as_slice()));
+                })
+            },
+        )
+        .await
+    }
+}
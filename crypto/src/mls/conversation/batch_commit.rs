@@ -0,0 +1,173 @@
+//! Jobs such as a credential rotation have to generate a commit in every conversation of the client, which takes
+//! minutes when done one conversation after the other. [MlsCentral::update_keying_material_many] generates them
+//! concurrently instead, with a bounded number of conversations in flight. Each conversation stays behind its own
+//! lock while its commit is generated and persisted, hence the keystore never sees interleaved writes for a given
+//! conversation.
+
+use std::{collections::HashSet, future::Future};
+
+use async_lock::RwLockWriteGuardArc;
+use futures_util::StreamExt;
+
+use crate::{
+    group_store::GroupStoreValue,
+    prelude::{ConversationId, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation},
+};
+
+/// Default number of conversations committed at once by [MlsCentral::update_keying_material_many]
+pub const DEFAULT_COMMIT_CONCURRENCY: usize = 8;
+
+/// Runs `commit` on every conversation with at most `max_concurrency` of them in flight, and returns the results in
+/// the order of the conversations. A conversation is locked for writing until its commit is done
+pub(crate) async fn commit_concurrently<F, Fut>(
+    conversations: Vec<GroupStoreValue<MlsConversation>>,
+    max_concurrency: usize,
+    commit: F,
+) -> Vec<(ConversationId, CryptoResult<MlsCommitBundle>)>
+where
+    F: Fn(RwLockWriteGuardArc<MlsConversation>) -> Fut,
+    Fut: Future<Output = CryptoResult<MlsCommitBundle>>,
+{
+    let commit = &commit;
+    futures_util::stream::iter(conversations)
+        .map(|conversation| async move {
+            let conversation = conversation.write_arc().await;
+            let id = conversation.id().clone();
+            (id, commit(conversation).await)
+        })
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await
+}
+
+impl MlsCentral {
+    /// Self updates the KeyPackage and commits in many conversations at once, see
+    /// [MlsCentral::update_keying_material]. Conversations are processed concurrently, at most `max_concurrency` at
+    /// a time, and a conversation listed more than once is only committed once.
+    ///
+    /// # Arguments
+    /// * `ids` - the conversations to commit in
+    /// * `max_concurrency` - how many conversations can be in flight at once, e.g. [DEFAULT_COMMIT_CONCURRENCY]
+    ///
+    /// # Return type
+    /// The commit of every conversation, or why it could not be generated, in the order of `ids`. As with
+    /// [MlsCentral::update_keying_material], every commit has to be accepted or cleared once the Delivery Service
+    /// answered
+    ///
+    /// # Errors
+    /// If one of the conversations can't be found, in which case no commit is generated at all
+    pub async fn update_keying_material_many(
+        &mut self,
+        ids: &[ConversationId],
+        max_concurrency: usize,
+    ) -> CryptoResult<Vec<(ConversationId, CryptoResult<MlsCommitBundle>)>> {
+        let mut seen = HashSet::with_capacity(ids.len());
+        let mut conversations = Vec::with_capacity(ids.len());
+        for id in ids.iter().filter(|id| seen.insert(*id)) {
+            conversations.push(self.get_conversation(id).await?);
+        }
+
+        let client = self.mls_client()?;
+        let backend = &self.mls_backend;
+        Ok(
+            commit_concurrently(conversations, max_concurrency, |mut conversation| async move {
+                conversation.update_keying_material(client, backend, None, None).await
+            })
+            .await,
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_update_keying_material_of_many_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let ids = (0..5).map(|_| conversation_id()).collect::<Vec<_>>();
+                    for id in &ids {
+                        alice_central
+                            .mls_central
+                            .new_conversation(id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                    }
+
+                    // duplicates are only committed once
+                    let mut requested = ids.clone();
+                    requested.push(ids[0].clone());
+                    let commits = alice_central
+                        .mls_central
+                        .update_keying_material_many(&requested, 2)
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        commits.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>(),
+                        ids,
+                        "results are in the order of the request"
+                    );
+
+                    for (id, commit) in commits {
+                        let commit = commit.unwrap().commit;
+                        let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(
+                            alice_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                            epoch + 1
+                        );
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .try_talk_to(&id, &mut bob_central.mls_central)
+                            .await
+                            .unwrap();
+                    }
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_commit_when_a_conversation_is_missing(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                let result = alice_central
+                    .mls_central
+                    .update_keying_material_many(&[id.clone(), conversation_id()], DEFAULT_COMMIT_CONCURRENCY)
+                    .await;
+                assert!(matches!(result.unwrap_err(), CryptoError::ConversationNotFound(_)));
+                // no pending commit has been created
+                let conversation = alice_central.mls_central.get_conversation_unchecked(&id).await;
+                assert!(conversation.group.pending_commit().is_none());
+            })
+        })
+        .await
+    }
+}
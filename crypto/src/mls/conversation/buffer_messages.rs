@@ -26,6 +26,7 @@ impl MlsCentral {
         let pending_msg = MlsPendingMessage {
             id: id.clone(),
             message: message.as_ref().to_vec(),
+            created_at: 0,
         };
         keystore.save::<MlsPendingMessage>(pending_msg).await?;
         Err(CryptoError::BufferedFutureMessage)
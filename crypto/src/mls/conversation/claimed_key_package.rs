@@ -0,0 +1,217 @@
+//! When adding clients to a conversation, their KeyPackages are claimed from the backend which tells, for each of
+//! them, which client it has been claimed for. Checking that the KeyPackage actually belongs to that client before
+//! committing prevents a misbehaving backend from slipping another client into the conversation.
+
+use openmls::prelude::KeyPackageIn;
+use tls_codec::Deserialize as _;
+
+use crate::prelude::{
+    ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversationCreationMessage, MlsError,
+};
+
+/// A KeyPackage claimed from the backend for a client, as found in the response of the KeyPackage claim endpoint
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ClaimedKeyPackageBundle {
+    /// Id of the user owning the client (hyphenated UUID)
+    pub user: String,
+    /// Id of the client the KeyPackage has been claimed for
+    pub client: String,
+    /// Domain of the user
+    pub domain: String,
+    /// TLS-serialized KeyPackage
+    #[serde(with = "base64_bytes")]
+    pub key_package: Vec<u8>,
+    /// Reference of the KeyPackage
+    #[serde(with = "base64_bytes")]
+    pub key_package_ref: Vec<u8>,
+}
+
+/// Shape of the response of the KeyPackage claim endpoint
+#[derive(serde::Deserialize)]
+struct KeyPackageClaimResponse {
+    key_packages: Vec<ClaimedKeyPackageBundle>,
+}
+
+mod base64_bytes {
+    use base64::Engine as _;
+
+    pub fn serialize<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::prelude::BASE64_STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = <String as serde::Deserialize>::deserialize(deserializer)?;
+        base64::prelude::BASE64_STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl ClaimedKeyPackageBundle {
+    /// Parses the JSON response of the KeyPackage claim endpoint
+    /// e.g. `{"key_packages": [{"user": "…", "client": "…", "domain": "…", "key_package": "…", "key_package_ref": "…"}]}`
+    /// where the KeyPackage and its reference are base64 encoded
+    ///
+    /// # Errors
+    /// When the response is not valid JSON or does not have the expected shape
+    pub fn from_claim_response(response: &[u8]) -> CryptoResult<Vec<Self>> {
+        Ok(serde_json::from_slice::<KeyPackageClaimResponse>(response)?.key_packages)
+    }
+
+    /// Qualified id of the client the KeyPackage has been claimed for e.g.
+    /// 'bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com'
+    pub fn client_id(&self) -> ClientId {
+        format!("{}:{}@{}", self.user, self.client, self.domain)
+            .into_bytes()
+            .into()
+    }
+
+    /// Deserializes the KeyPackage, making sure it belongs to the client it has been claimed for
+    fn verified_key_package(&self) -> CryptoResult<KeyPackageIn> {
+        let key_package = KeyPackageIn::tls_deserialize(&mut self.key_package.as_slice()).map_err(MlsError::from)?;
+        let client_id = self.client_id();
+        if key_package.credential().identity() != client_id.as_slice() {
+            return Err(CryptoError::ClaimedKeyPackageMismatch(client_id));
+        }
+        Ok(key_package)
+    }
+}
+
+impl MlsCentral {
+    /// Adds the clients KeyPackages have been claimed for to the group/conversation. Same as
+    /// [MlsCentral::add_members_to_conversation] except that each KeyPackage is first checked to belong to the client
+    /// it has been claimed for
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `claimed_key_packages` - KeyPackages claimed from the backend, see
+    /// [ClaimedKeyPackageBundle::from_claim_response]
+    ///
+    /// # Errors
+    /// [CryptoError::ClaimedKeyPackageMismatch] when a KeyPackage belongs to another client than the one it has been
+    /// claimed for, in which case nothing is committed. Otherwise the same errors as
+    /// [MlsCentral::add_members_to_conversation]
+    pub async fn add_claimed_members_to_conversation(
+        &mut self,
+        id: &ConversationId,
+        claimed_key_packages: Vec<ClaimedKeyPackageBundle>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        let key_packages = claimed_key_packages
+            .iter()
+            .map(ClaimedKeyPackageBundle::verified_key_package)
+            .collect::<CryptoResult<Vec<_>>>()?;
+        self.add_members_to_conversation(id, key_packages).await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use base64::Engine as _;
+    use openmls::prelude::TlsSerializeTrait as _;
+    use openmls_traits::OpenMlsCryptoProvider as _;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Mimics the backend's response when claiming a KeyPackage of `owner` for `claimed_for`
+    async fn claim_response(owner: &ClientContext, claimed_for: &ClientId, case: &TestCase) -> Vec<u8> {
+        let key_package = owner
+            .mls_central
+            .get_or_create_client_keypackages(case.ciphersuite(), case.credential_type, 1)
+            .await
+            .unwrap()
+            .remove(0);
+        let claimed_for = String::from_utf8(claimed_for.to_vec()).unwrap();
+        let (user, client_and_domain) = claimed_for.split_once(':').unwrap();
+        let (client, domain) = client_and_domain.split_once('@').unwrap();
+        let b64 = |bytes: &[u8]| base64::prelude::BASE64_STANDARD.encode(bytes);
+        serde_json::json!({
+            "key_packages": [{
+                "user": user,
+                "client": client,
+                "domain": domain,
+                "key_package": b64(&key_package.tls_serialize_detached().unwrap()),
+                "key_package_ref": b64(key_package.hash_ref(owner.mls_central.mls_backend.crypto()).unwrap().as_slice()),
+            }]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_add_claimed_members_only_when_key_packages_match(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    let charlie_id = charlie_central.mls_central.get_client_id();
+
+                    // the backend hands out Charlie's KeyPackage when claiming Bob's
+                    let response = claim_response(&charlie_central, &bob_id, &case).await;
+                    let claimed = ClaimedKeyPackageBundle::from_claim_response(&response).unwrap();
+                    assert_eq!(claimed.len(), 1);
+                    assert_eq!(claimed[0].client_id(), bob_id);
+                    let add = alice_central
+                        .mls_central
+                        .add_claimed_members_to_conversation(&id, claimed)
+                        .await;
+                    assert!(matches!(add.unwrap_err(), CryptoError::ClaimedKeyPackageMismatch(c) if c == bob_id));
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .get_conversation_unchecked(&id)
+                            .await
+                            .members()
+                            .len(),
+                        1
+                    );
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+
+                    let response = claim_response(&bob_central, &bob_id, &case).await;
+                    let claimed = ClaimedKeyPackageBundle::from_claim_response(&response).unwrap();
+                    let welcome = alice_central
+                        .mls_central
+                        .add_claimed_members_to_conversation(&id, claimed)
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                    assert!(!alice_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .members()
+                        .contains_key(charlie_id.as_slice()));
+
+                    // malformed responses are rejected
+                    assert!(matches!(
+                        ClaimedKeyPackageBundle::from_claim_response(b"{\"key_packages\": [{}]}").unwrap_err(),
+                        CryptoError::JsonError(_)
+                    ));
+                })
+            },
+        )
+        .await
+    }
+}
@@ -5,13 +5,19 @@
 //! | 0 pend. Proposal       | ✅              | ❌              |
 //! | 1+ pend. Proposal      | ✅              | ❌              |
 
-use openmls::prelude::{KeyPackageIn, LeafNode, LeafNodeIndex, MlsMessageOut};
+use openmls::prelude::{KeyPackage, KeyPackageIn, LeafNode, LeafNodeIndex, MlsMessageOut};
 
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
-    mls::credential::{crl::extract_dp, CredentialBundle},
-    prelude::{Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError, MlsGroupInfoBundle},
+    mls::{
+        credential::{crl::extract_dp, CredentialBundle},
+        metrics::MlsOperation,
+    },
+    prelude::{
+        Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError, MlsGroupInfoBundle,
+        MlsRequiredCapabilities,
+    },
 };
 
 use super::MlsConversation;
@@ -30,23 +36,26 @@ impl MlsCentral {
     ///
     /// # Errors
     /// If the authorisation callback is set, an error can be caused when the authorization fails.
+    /// [CryptoError::NotConversationAdmin] when the conversation has admins and this client isn't one of them.
     /// Other errors are KeyStore and OpenMls errors:
     #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn add_members_to_conversation(
         &mut self,
         id: &ConversationId,
         key_packages: Vec<KeyPackageIn>,
     ) -> CryptoResult<MlsConversationCreationMessage> {
+        let _timer = self.metrics.start(MlsOperation::Commit);
         if let Some(callbacks) = self.callbacks.as_ref() {
             let client_id = self.mls_client()?.id().clone();
             if !callbacks.authorize(id.clone(), client_id).await {
                 return Err(CryptoError::Unauthorized);
             }
         }
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        conversation.ensure_admin(self.mls_client()?.id())?;
+        conversation
             .add_members(self.mls_client()?, key_packages, &self.mls_backend)
             .await
     }
@@ -63,23 +72,27 @@ impl MlsCentral {
     /// the group info will be returned on successful call.
     ///
     /// # Errors
-    /// If the authorisation callback is set, an error can be caused when the authorization fails. Other errors are KeyStore and OpenMls errors.
+    /// If the authorisation callback is set, an error can be caused when the authorization fails.
+    /// [CryptoError::NotConversationAdmin] when the conversation has admins and this client isn't one of them.
+    /// Other errors are KeyStore and OpenMls errors.
     #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn remove_members_from_conversation(
         &mut self,
         id: &ConversationId,
         clients: &[ClientId],
     ) -> CryptoResult<MlsCommitBundle> {
+        let _timer = self.metrics.start(MlsOperation::Commit);
         if let Some(callbacks) = self.callbacks.as_ref() {
             let client_id = self.mls_client()?.id().clone();
             if !callbacks.authorize(id.clone(), client_id).await {
                 return Err(CryptoError::Unauthorized);
             }
         }
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        conversation.ensure_admin(self.mls_client()?.id())?;
+        conversation
             .remove_members(self.mls_client()?, clients, &self.mls_backend)
             .await
     }
@@ -98,7 +111,9 @@ impl MlsCentral {
     /// If the conversation can't be found, an error will be returned. Other errors are originating
     /// from OpenMls and the KeyStore
     #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn update_keying_material(&mut self, id: &ConversationId) -> CryptoResult<MlsCommitBundle> {
+        let _timer = self.metrics.start(MlsOperation::Commit);
         self.get_conversation(id)
             .await?
             .write()
@@ -113,12 +128,15 @@ impl MlsCentral {
     /// * `backend` - the KeyStore to persist group changes
     ///
     /// # Return type
-    /// A tuple containing the commit message and a possible welcome (in the case `Add` proposals were pending within the internal MLS Group)
+    /// A tuple containing the commit message and a possible welcome (in the case `Add` proposals were pending within the internal MLS Group).
+    /// `None` when there are no pending proposals, in which case no commit is created and the epoch stays the same
     ///
     /// # Errors
     /// Errors can be originating from the KeyStore and OpenMls
     #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn commit_pending_proposals(&mut self, id: &ConversationId) -> CryptoResult<Option<MlsCommitBundle>> {
+        let _timer = self.metrics.start(MlsOperation::Commit);
         self.get_conversation(id)
             .await?
             .write()
@@ -144,6 +162,12 @@ impl MlsConversation {
             .ok_or(CryptoError::IdentityInitializationError)?
             .signature_key;
 
+        if let Some(required) = self.group.group_context_extensions().required_capabilities() {
+            for key_package in &key_packages {
+                MlsRequiredCapabilities::check_key_package(required, &KeyPackage::from(key_package.clone()))?;
+            }
+        }
+
         // No need to also check pending proposals since they should already have been scanned while decrypting the proposal message
         let crl_new_distribution_points = key_packages
             .iter()
@@ -1094,7 +1118,11 @@ pub mod tests {
                             .new_add_proposal(&id, bob_central.mls_central.get_one_key_package(&case).await)
                             .await
                             .unwrap();
-                        assert!(!alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(!alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             alice_central
                                 .mls_central
@@ -1148,13 +1176,22 @@ pub mod tests {
                         .new_conversation(&id, case.credential_type, case.cfg.clone())
                         .await
                         .unwrap();
-                    assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                    assert!(alice_central
+                        .mls_central
+                        .pending_proposals_unchecked(&id)
+                        .await
+                        .is_empty());
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
                     assert!(alice_central
                         .mls_central
                         .commit_pending_proposals(&id)
                         .await
                         .unwrap()
                         .is_none());
+
+                    // no empty commit got created, hence nothing to merge and the epoch did not move
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
                 })
             })
             .await;
@@ -1184,7 +1221,11 @@ pub mod tests {
                             .new_add_proposal(&id, charlie_central.mls_central.get_one_key_package(&case).await)
                             .await
                             .unwrap();
-                        assert!(!bob_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(!bob_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             bob_central
                                 .mls_central
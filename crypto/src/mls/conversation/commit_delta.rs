@@ -0,0 +1,203 @@
+//! When a commit is merged, the application needs to know what it changed in the conversation e.g. to update its UI
+//! or tell the backend. Rather than diffing the members before and after, the applied proposals are described by a
+//! [MlsCommitDelta], computed from the staged commit before it is merged while removed members can still be found.
+
+use openmls::{
+    group::{MlsGroup, StagedCommit},
+    prelude::{Proposal, Sender},
+};
+
+use crate::prelude::ClientId;
+
+/// What a commit changed in a conversation, see [MlsConversationDecryptMessage::commit_delta](crate::prelude::MlsConversationDecryptMessage::commit_delta)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MlsCommitDelta {
+    /// Clients added by the commit, including the committer of an external commit
+    pub added: Vec<ClientId>,
+    /// Clients removed by the commit
+    pub removed: Vec<ClientId>,
+    /// Clients whose leaf was updated, either by an Update proposal or, for the committer, by the commit itself
+    pub updated: Vec<ClientId>,
+    /// Whether the leaf of this client was updated, either by one of its Update proposals or because it is the
+    /// committer
+    pub self_updated: bool,
+    /// Client who created the commit, unless it is an external commit
+    pub(crate) committer: Option<ClientId>,
+}
+
+impl MlsCommitDelta {
+    /// Describes a commit which is about to be merged in the group
+    ///
+    /// # Arguments
+    /// * `group` - the group before merging the commit
+    /// * `commit` - the staged commit
+    /// * `sender` - who created the commit
+    pub(crate) fn new(group: &MlsGroup, commit: &StagedCommit, sender: &Sender) -> Self {
+        let own_index = group.own_leaf_index();
+        let is_own_commit = matches!(sender, Sender::Member(i) if *i == own_index);
+        let committer = match sender {
+            Sender::Member(index) => group
+                .members()
+                .find(|m| m.index == *index)
+                .map(|m| m.credential.identity().into()),
+            _ => None,
+        };
+        let is_external = commit
+            .queued_proposals()
+            .any(|p| matches!(p.proposal(), Proposal::ExternalInit(_)));
+
+        let mut added = commit
+            .add_proposals()
+            .map(|p| {
+                p.add_proposal()
+                    .key_package()
+                    .leaf_node()
+                    .credential()
+                    .identity()
+                    .into()
+            })
+            .collect::<Vec<ClientId>>();
+        let removed = commit
+            .remove_proposals()
+            .filter_map(|p| {
+                let removed = p.remove_proposal().removed();
+                group
+                    .members()
+                    .find(|m| m.index == removed)
+                    .map(|m| m.credential.identity().into())
+            })
+            .collect::<Vec<ClientId>>();
+        let mut updated = commit
+            .update_proposals()
+            .map(|p| p.update_proposal().leaf_node().credential().identity().into())
+            .collect::<Vec<ClientId>>();
+        let mut self_updated = commit
+            .update_proposals()
+            .any(|p| matches!(p.sender(), Sender::Member(i) if *i == own_index));
+
+        if let Some(committer) = commit.get_update_path_leaf_node() {
+            let committer = committer.credential().identity().into();
+            if is_external {
+                added.push(committer);
+            } else {
+                updated.push(committer);
+                self_updated |= is_own_commit;
+            }
+        }
+
+        Self {
+            added,
+            removed,
+            updated,
+            self_updated,
+            committer,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_describe_applied_proposals(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let alice_id = alice_central.mls_central.get_client_id();
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    let charlie_id = charlie_central.mls_central.get_client_id();
+
+                    // adding Charlie
+                    let charlie_kp = charlie_central.mls_central.rand_key_package(&case).await;
+                    let commit = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![charlie_kp])
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let delta = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap()
+                        .commit_delta
+                        .unwrap();
+                    assert_eq!(delta.added, vec![charlie_id.clone()]);
+                    assert!(delta.removed.is_empty());
+                    assert!(!delta.self_updated);
+
+                    // removing Charlie
+                    let commit = alice_central
+                        .mls_central
+                        .remove_members_from_conversation(&id, &[charlie_id.clone()])
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let delta = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap()
+                        .commit_delta
+                        .unwrap();
+                    assert!(delta.added.is_empty());
+                    assert_eq!(delta.removed, vec![charlie_id]);
+                    assert_eq!(delta.updated, vec![alice_id.clone()]);
+                    assert!(!delta.self_updated);
+
+                    // Bob's update proposal committed by Alice
+                    let proposal = bob_central.mls_central.new_update_proposal(&id).await.unwrap().proposal;
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, proposal.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let decrypted = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let delta = decrypted.commit_delta.unwrap();
+                    assert!(delta.added.is_empty() && delta.removed.is_empty());
+                    assert!(delta.updated.contains(&bob_id) && delta.updated.contains(&alice_id));
+                    assert!(delta.self_updated);
+
+                    // only commits have a delta
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, msg).await.unwrap();
+                    assert!(decrypted.commit_delta.is_none());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -20,23 +20,25 @@
 //! when joining one by Welcome or external commit
 
 use openmls::prelude::{
-    Capabilities, Credential, CredentialType, ExternalSender, ProtocolVersion, RequiredCapabilitiesExtension,
-    SenderRatchetConfiguration, SignaturePublicKey, WireFormatPolicy, PURE_CIPHERTEXT_WIRE_FORMAT_POLICY,
-    PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+    Capabilities, Credential, CredentialType, ExtensionType, Extensions, ExternalSender, KeyPackage, LeafNode,
+    ProposalType, ProtocolVersion, RequiredCapabilitiesExtension, SenderRatchetConfiguration, SignaturePublicKey,
+    WireFormatPolicy, PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
 };
 use openmls_traits::types::Ciphersuite;
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{CryptoResult, MlsCiphersuite};
+use crate::{
+    mls::conversation::admins::ADMINS_EXTENSION_TYPE,
+    prelude::{ClientId, CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType},
+};
 
 /// Sets the config in OpenMls for the oldest possible epoch(past current) that a message can be decrypted
 pub(crate) const MAX_PAST_EPOCHS: usize = 3;
 
-/// Window for which decryption secrets are kept within an epoch. Use this with caution since this affects forward secrecy within an epoch.
-/// Use this when the Delivery Service cannot guarantee application messages order
+/// Default of [MlsCustomConfiguration::out_of_order_tolerance]
 pub(crate) const OUT_OF_ORDER_TOLERANCE: u32 = 2;
 
-/// How many application messages can be skipped. Use this when the Delivery Service can drop application messages
+/// Default of [MlsCustomConfiguration::maximum_forward_distance]
 pub(crate) const MAXIMUM_FORWARD_DISTANCE: u32 = 1000;
 
 /// The configuration parameters for a group/conversation
@@ -46,6 +48,8 @@ pub struct MlsConversationConfiguration {
     pub ciphersuite: MlsCiphersuite,
     /// Delivery service public signature key and credential
     pub external_senders: Vec<ExternalSender>,
+    /// Capabilities every member of the conversation has to support
+    pub required_capabilities: MlsRequiredCapabilities,
     /// Implementation specific configuration
     pub custom: MlsCustomConfiguration,
 }
@@ -72,6 +76,12 @@ impl MlsConversationConfiguration {
         Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519,
     ];
 
+    /// GroupContext & GroupInfo extensions understood by this version, as registered by the IANA: application_id,
+    /// ratchet_tree, required_capabilities, external_pub and external_senders. Along with the private one listing the
+    /// admins of the conversation
+    pub const SUPPORTED_GROUP_EXTENSIONS: &'static [u16] =
+        &[0x0001, 0x0002, 0x0003, 0x0004, 0x0005, ADMINS_EXTENSION_TYPE];
+
     /// Not used at the moment
     const NUMBER_RESUMPTION_PSK: usize = 1;
 
@@ -87,8 +97,8 @@ impl MlsConversationConfiguration {
             .max_past_epochs(MAX_PAST_EPOCHS)
             .padding_size(Self::PADDING_SIZE)
             .number_of_resumption_psks(Self::NUMBER_RESUMPTION_PSK)
-            .leaf_capabilities(Self::default_leaf_capabilities())
-            .required_capabilities(self.default_required_capabilities())
+            .leaf_capabilities(self.creator_leaf_capabilities())
+            .required_capabilities(self.required_capabilities.extension())
             .sender_ratchet_configuration(SenderRatchetConfiguration::new(
                 self.custom.out_of_order_tolerance,
                 self.custom.maximum_forward_distance,
@@ -99,19 +109,37 @@ impl MlsConversationConfiguration {
             .build())
     }
 
+    /// Non-default extensions every leaf supports: the one listing the admins of the conversation
+    fn default_leaf_extensions() -> [ExtensionType; 1] {
+        [ExtensionType::from(ADMINS_EXTENSION_TYPE)]
+    }
+
     /// Default capabilities for every generated [openmls::prelude::KeyPackage]
     pub fn default_leaf_capabilities() -> Capabilities {
         Capabilities::new(
             Some(&[Self::DEFAULT_PROTOCOL_VERSION]),
             Some(Self::DEFAULT_SUPPORTED_CIPHERSUITES),
-            Some(&[]),
+            Some(&Self::default_leaf_extensions()),
             Some(&[]),
             Some(Self::DEFAULT_SUPPORTED_CREDENTIALS),
         )
     }
 
-    fn default_required_capabilities(&self) -> RequiredCapabilitiesExtension {
-        RequiredCapabilitiesExtension::new(&[], &[], Self::DEFAULT_SUPPORTED_CREDENTIALS)
+    /// Capabilities of the creator's leaf, which has to support the non-default extensions and proposals it requires
+    fn creator_leaf_capabilities(&self) -> Capabilities {
+        let mut extension_types = self.required_capabilities.extension_types.clone();
+        for et in Self::default_leaf_extensions() {
+            if !extension_types.contains(&et) {
+                extension_types.push(et);
+            }
+        }
+        Capabilities::new(
+            Some(&[Self::DEFAULT_PROTOCOL_VERSION]),
+            Some(Self::DEFAULT_SUPPORTED_CIPHERSUITES),
+            Some(&extension_types),
+            Some(&self.required_capabilities.proposal_types),
+            Some(Self::DEFAULT_SUPPORTED_CREDENTIALS),
+        )
     }
 
     /// Parses supplied key from Delivery Service in order to build back an [ExternalSender]
@@ -128,23 +156,219 @@ impl MlsConversationConfiguration {
             })
             .collect();
     }
+
+    /// Checks that the configuration is consistent before creating a conversation with it, rather than having it fail
+    /// later on e.g. when the first member is added:
+    /// * the ciphersuite is supported by this version and allowed by the [MlsJoinPolicy] of [Self::custom]
+    /// * the creator's credential is a X509 one when the join policy requires it, or nobody could join by Welcome
+    /// * the keys of the external senders are not empty
+    /// * [Self::custom] is valid, see [MlsCustomConfiguration::validate]
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidConfiguration] naming the first invalid field
+    pub fn validate(&self, creator_credential_type: MlsCredentialType) -> CryptoResult<()> {
+        if !Self::is_supported(self.ciphersuite) {
+            return Err(invalid_configuration("ciphersuite", "is not supported"));
+        }
+        let join_policy = &self.custom.join_policy;
+        if join_policy.check_ciphersuite(self.ciphersuite).is_err() {
+            return Err(invalid_configuration(
+                "custom.join_policy.allowed_ciphersuites",
+                format!("does not contain the ciphersuite {:#06x}", u16::from(self.ciphersuite)),
+            ));
+        }
+        if join_policy.require_x509_credentials && creator_credential_type != MlsCredentialType::X509 {
+            return Err(invalid_configuration(
+                "custom.join_policy.require_x509_credentials",
+                "is set whereas the creator does not have a X509 credential",
+            ));
+        }
+        if let Some(i) = self
+            .external_senders
+            .iter()
+            .position(|sender| sender.signature_key().as_slice().is_empty())
+        {
+            return Err(invalid_configuration(
+                "external_senders",
+                format!("has an empty key at index {i}"),
+            ));
+        }
+        self.custom.validate().map_err(|e| match e {
+            CryptoError::InvalidConfiguration { field, reason } => CryptoError::InvalidConfiguration {
+                field: format!("custom.{field}"),
+                reason,
+            },
+            e => e,
+        })
+    }
+
+    fn is_supported(ciphersuite: MlsCiphersuite) -> bool {
+        Self::DEFAULT_SUPPORTED_CIPHERSUITES.contains(&ciphersuite.into()) && ciphersuite.ensure_supported().is_ok()
+    }
+
+    /// Fails with [CryptoError::UnsupportedGroupExtension] on the first extension of a group which is not in
+    /// [Self::SUPPORTED_GROUP_EXTENSIONS]
+    pub(crate) fn check_group_extensions(extensions: &Extensions) -> CryptoResult<()> {
+        match extensions
+            .iter()
+            .map(|e| u16::from(e.extension_type()))
+            .find(|et| !Self::SUPPORTED_GROUP_EXTENSIONS.contains(et))
+        {
+            Some(et) => Err(CryptoError::UnsupportedGroupExtension(et)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Capabilities every member of a conversation has to support. They end up in the RequiredCapabilities extension of
+/// the group context when the conversation is created, and the KeyPackages of the clients added to it are checked
+/// against them. See <https://www.rfc-editor.org/rfc/rfc9420.html#section-11.1>
+///
+/// Beware that the KeyPackages generated by this library only support the default extensions and proposals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsRequiredCapabilities {
+    /// Non-default extension types. Empty by default
+    pub extension_types: Vec<ExtensionType>,
+    /// Non-default proposal types. Empty by default
+    pub proposal_types: Vec<ProposalType>,
+    /// Credential types. Both Basic and X509 by default
+    pub credential_types: Vec<MlsCredentialType>,
+}
+
+impl Default for MlsRequiredCapabilities {
+    fn default() -> Self {
+        Self {
+            extension_types: vec![],
+            proposal_types: vec![],
+            credential_types: MlsConversationConfiguration::DEFAULT_SUPPORTED_CREDENTIALS
+                .iter()
+                .map(|&ct| ct.into())
+                .collect(),
+        }
+    }
+}
+
+impl MlsRequiredCapabilities {
+    /// Builds the required capabilities out of the extension & proposal types' values as registered by the IANA
+    ///
+    /// # Errors
+    /// [CryptoError::ConsumerError] when a proposal type is unknown
+    pub fn try_from_raw(
+        extension_types: &[u16],
+        proposal_types: &[u16],
+        credential_types: Vec<MlsCredentialType>,
+    ) -> CryptoResult<Self> {
+        Ok(Self {
+            extension_types: extension_types.iter().map(|&et| ExtensionType::from(et)).collect(),
+            proposal_types: proposal_types
+                .iter()
+                .map(|&pt| ProposalType::try_from(pt).map_err(|_| CryptoError::ConsumerError))
+                .collect::<CryptoResult<_>>()?,
+            credential_types,
+        })
+    }
+
+    pub(crate) fn extension(&self) -> RequiredCapabilitiesExtension {
+        let credential_types = self
+            .credential_types
+            .iter()
+            .map(|&ct| ct.into())
+            .collect::<Vec<CredentialType>>();
+        RequiredCapabilitiesExtension::new(&self.extension_types, &self.proposal_types, &credential_types)
+    }
+
+    /// Fails with [CryptoError::MissingRequiredCapabilities] when the leaf of the KeyPackage does not support every
+    /// required capability of a group
+    pub(crate) fn check_key_package(
+        required: &RequiredCapabilitiesExtension,
+        key_package: &KeyPackage,
+    ) -> CryptoResult<()> {
+        Self::check_leaf_node(required, key_package.leaf_node())
+    }
+
+    /// Fails with [CryptoError::MissingRequiredCapabilities] when a leaf does not support every required capability
+    /// of a group
+    pub(crate) fn check_leaf_node(required: &RequiredCapabilitiesExtension, leaf_node: &LeafNode) -> CryptoResult<()> {
+        let capabilities = leaf_node.capabilities();
+        let supported = required
+            .extension_types()
+            .iter()
+            .all(|et| capabilities.extensions().contains(et))
+            && required
+                .proposal_types()
+                .iter()
+                .all(|pt| capabilities.proposals().contains(pt))
+            && required
+                .credential_types()
+                .iter()
+                .all(|ct| capabilities.credentials().contains(ct));
+        if supported {
+            Ok(())
+        } else {
+            let identity = leaf_node.credential().identity();
+            Err(CryptoError::MissingRequiredCapabilities(identity.into()))
+        }
+    }
 }
 
 /// The configuration parameters for a group/conversation which are not handled natively by openmls
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MlsCustomConfiguration {
-    // TODO: Not implemented yet
-    /// Duration in seconds after which we will automatically force a self_update commit
+    /// Duration after which the own key material should be rotated with a self update commit.
+    /// See [MlsCentral::conversations_needing_key_update](crate::prelude::MlsCentral::conversations_needing_key_update)
     pub key_rotation_span: Option<std::time::Duration>,
     /// Defines if handshake messages are encrypted or not
     pub wire_policy: MlsWirePolicy,
-    /// Window for which decryption secrets are kept within an epoch. Use this with caution since
-    /// this affects forward secrecy within an epoch. Use this when the Delivery Service cannot
-    /// guarantee application messages order.
+    /// How many application messages of a sender, older than the latest one received from it in the
+    /// epoch, can still be decrypted. Raise it when the Delivery Service or the network reorders
+    /// application messages, since messages outside of this window can never be decrypted.
+    ///
+    /// Their decryption secrets have to be kept until they arrive, which weakens forward secrecy
+    /// within an epoch: whoever compromises the client can decrypt up to this many messages per
+    /// sender which have been sent but not received yet. Defaults to 2.
     pub out_of_order_tolerance: u32,
-    /// How many application messages can be skipped. Use this when the Delivery Service can drop
-    /// application messages
+    /// How many application messages of a sender can be skipped, i.e. how far ahead of the latest
+    /// one received from it a message can be and still be decrypted. Raise it when the Delivery
+    /// Service can drop application messages or when a client can be offline for a long time.
+    ///
+    /// The decryption secrets of the skipped messages have to be derived one after the other when
+    /// such a message arrives, hence a large value lets a malicious member make the client spend
+    /// a lot of time with a single message. Defaults to 1000. A message further ahead fails with
+    /// [CryptoError::MessageGenerationGap](crate::prelude::CryptoError::MessageGenerationGap).
+    ///
+    /// Both this and [Self::out_of_order_tolerance] are only taken into account when creating or
+    /// joining a conversation, they are then stored along with it.
     pub maximum_forward_distance: u32,
+    /// How many epochs an application message can be behind the current epoch of the conversation and still be
+    /// accepted. Unlike [MAX_PAST_EPOCHS], which bounds what can technically be
+    /// decrypted, this is a policy: older messages are rejected with
+    /// [CryptoError::ApplicationMessageTooOld](crate::prelude::CryptoError::ApplicationMessageTooOld) even though
+    /// they could be decrypted, e.g. so that messages delayed past a membership change are not shown. Any message
+    /// which can be decrypted is accepted when unset.
+    ///
+    /// It is stored along with the conversation when creating or joining it.
+    #[serde(default)]
+    pub max_application_message_epoch_age: Option<u32>,
+    /// How [MlsCentral::encrypt_message](crate::prelude::MlsCentral::encrypt_message) behaves while a commit created
+    /// by this client is in flight, so that application messages are not encrypted for the epoch the commit is about
+    /// to end. Disabled by default.
+    ///
+    /// It is stored along with the conversation when creating or joining it.
+    #[serde(default)]
+    pub epoch_fence: MlsEpochFence,
+    /// Restrictions on the conversations which can be joined by Welcome. Only taken into account by
+    /// [MlsCentral::process_welcome_message](crate::prelude::MlsCentral::process_welcome_message)
+    #[serde(default)]
+    pub join_policy: MlsJoinPolicy,
+    /// Clients allowed to add or remove members of the conversation. Commits and proposals of other members doing so
+    /// are rejected when decrypted, and so are their attempts at creating such commits. Anyone can when empty, which
+    /// is the default.
+    ///
+    /// Only taken into account when creating the conversation: the admins end up in its group context, hence are
+    /// shared by every member, and can be changed afterwards with
+    /// [MlsCentral::set_conversation_admins](crate::prelude::MlsCentral::set_conversation_admins).
+    #[serde(default)]
+    pub admins: Vec<ClientId>,
 }
 
 impl Default for MlsCustomConfiguration {
@@ -154,7 +378,96 @@ impl Default for MlsCustomConfiguration {
             key_rotation_span: Default::default(),
             out_of_order_tolerance: OUT_OF_ORDER_TOLERANCE,
             maximum_forward_distance: MAXIMUM_FORWARD_DISTANCE,
+            max_application_message_epoch_age: None,
+            epoch_fence: Default::default(),
+            join_policy: Default::default(),
+            admins: vec![],
+        }
+    }
+}
+
+impl MlsCustomConfiguration {
+    /// Checks that the configuration is consistent before creating or joining a conversation with it:
+    /// * [Self::key_rotation_span] is not zero, which would have the key material rotated all the time
+    /// * [Self::max_application_message_epoch_age] is not above [MAX_PAST_EPOCHS], since older messages can't be
+    ///   decrypted anyway
+    /// * the ciphersuites of the [MlsJoinPolicy] are supported by this version
+    ///
+    /// # Errors
+    /// [CryptoError::InvalidConfiguration] naming the first invalid field
+    pub fn validate(&self) -> CryptoResult<()> {
+        if self.key_rotation_span.is_some_and(|span| span.is_zero()) {
+            return Err(invalid_configuration("key_rotation_span", "must not be zero"));
+        }
+        if self
+            .max_application_message_epoch_age
+            .is_some_and(|age| age as usize > MAX_PAST_EPOCHS)
+        {
+            return Err(invalid_configuration(
+                "max_application_message_epoch_age",
+                format!("must not be above {MAX_PAST_EPOCHS} since older messages can't be decrypted"),
+            ));
         }
+        if let Some(ciphersuite) = self
+            .join_policy
+            .allowed_ciphersuites
+            .iter()
+            .find(|&&cs| !MlsConversationConfiguration::is_supported(cs))
+        {
+            return Err(invalid_configuration(
+                "join_policy.allowed_ciphersuites",
+                format!("contains the unsupported ciphersuite {:#06x}", u16::from(*ciphersuite)),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Policy a conversation has to comply with to be joined by Welcome, e.g. to enforce an enterprise's requirements.
+/// It is checked before the conversation is persisted.
+///
+/// The default policy accepts any conversation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MlsJoinPolicy {
+    /// Ciphersuites the conversation is allowed to use. Any ciphersuite is accepted when empty
+    pub allowed_ciphersuites: Vec<MlsCiphersuite>,
+    /// Reject conversations in which a member, the creator included, has a Basic credential e.g. when end-to-end
+    /// identity is mandated
+    pub require_x509_credentials: bool,
+}
+
+impl MlsJoinPolicy {
+    /// Fails with [CryptoError::JoinPolicyViolation] when the ciphersuite is not in the allowlist
+    pub(crate) fn check_ciphersuite(&self, ciphersuite: MlsCiphersuite) -> CryptoResult<()> {
+        if self.allowed_ciphersuites.is_empty() || self.allowed_ciphersuites.contains(&ciphersuite) {
+            Ok(())
+        } else {
+            Err(CryptoError::JoinPolicyViolation(format!(
+                "ciphersuite {:#06x} is not allowed",
+                u16::from(ciphersuite)
+            )))
+        }
+    }
+
+    /// Fails with [CryptoError::JoinPolicyViolation] when X509 credentials are required and a member has another one
+    pub(crate) fn check_credentials<'a>(
+        &self,
+        mut credentials: impl Iterator<Item = &'a Credential>,
+    ) -> CryptoResult<()> {
+        if self.require_x509_credentials && credentials.any(|c| c.credential_type() != CredentialType::X509) {
+            Err(CryptoError::JoinPolicyViolation(
+                "a member does not have a X509 credential".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn invalid_configuration(field: &str, reason: impl Into<String>) -> CryptoError {
+    CryptoError::InvalidConfiguration {
+        field: field.to_string(),
+        reason: reason.into(),
     }
 }
 
@@ -169,6 +482,32 @@ pub enum MlsWirePolicy {
     Ciphertext = 2,
 }
 
+/// What happens to an application message encrypted while the conversation has a pending commit, i.e. one which has
+/// neither been accepted with [MlsCentral::commit_accepted](crate::prelude::MlsCentral::commit_accepted) nor cleared
+/// yet. Recipients having merged the commit may no longer be able to decrypt a message of the previous epoch.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum MlsEpochFence {
+    /// The message is encrypted for the current epoch, as if there was no pending commit
+    #[default]
+    Disabled = 0,
+    /// Encrypting fails with [CryptoError::PendingCommitInFlight]
+    FailFast = 1,
+    /// The message is queued and encrypted once the pending commit is resolved, see
+    /// [MlsCentral::take_fenced_messages](crate::prelude::MlsCentral::take_fenced_messages)
+    Queue = 2,
+}
+
+impl From<u8> for MlsEpochFence {
+    fn from(fence: u8) -> Self {
+        match fence {
+            1 => Self::FailFast,
+            2 => Self::Queue,
+            _ => Self::Disabled,
+        }
+    }
+}
+
 impl From<MlsWirePolicy> for WireFormatPolicy {
     fn from(policy: MlsWirePolicy) -> Self {
         match policy {
@@ -180,8 +519,18 @@ impl From<MlsWirePolicy> for WireFormatPolicy {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::{prelude::MlsConversationConfiguration, test_utils::*};
-    use openmls::prelude::ProtocolVersion;
+    use crate::{
+        prelude::{
+            CryptoError, MlsCiphersuite, MlsConversationConfiguration, MlsCredentialType, MlsCustomConfiguration,
+            MlsRequiredCapabilities,
+        },
+        test_utils::*,
+    };
+
+    use super::{ADMINS_EXTENSION_TYPE, MAX_PAST_EPOCHS};
+    use openmls::prelude::{
+        ApplicationIdExtension, CredentialType, Extension, ExtensionType, Extensions, ProtocolVersion, UnknownExtension,
+    };
     use openmls_traits::types::VerifiableCiphersuite;
     use wasm_bindgen_test::*;
 
@@ -215,6 +564,53 @@ pub mod tests {
         .await
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_only_add_clients_supporting_required_capabilities(mut case: TestCase) {
+        // an extension type from the private use range, which KeyPackages generated by this library do not support
+        let required = MlsRequiredCapabilities::try_from_raw(&[0xF0A0], &[], vec![case.credential_type]).unwrap();
+        case.cfg.required_capabilities = required.clone();
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    {
+                        let group = alice_central.mls_central.get_conversation_unchecked(&id).await;
+                        let capabilities = group.group.group_context_extensions().required_capabilities().unwrap();
+                        assert_eq!(capabilities.extension_types(), required.extension_types.as_slice());
+                        assert_eq!(
+                            capabilities.credential_types(),
+                            &[CredentialType::from(case.credential_type)]
+                        );
+                        // the creator has to support what it requires
+                        let creator_capabilities = group.group.own_leaf().unwrap().capabilities();
+                        assert_eq!(
+                            creator_capabilities.extensions(),
+                            &[ExtensionType::from(0xF0A0), ExtensionType::from(ADMINS_EXTENSION_TYPE)]
+                        );
+                    }
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    let add = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await;
+                    assert!(matches!(add.unwrap_err(), CryptoError::MissingRequiredCapabilities(c) if c == bob_id));
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+                })
+            },
+        )
+        .await
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn creator_leaf_node_should_have_default_capabilities(case: TestCase) {
@@ -247,8 +643,11 @@ pub mod tests {
                 // Proposals MUST be empty since we support all the default ones
                 assert!(creator_capabilities.proposals().is_empty());
 
-                // Extensions MUST only contain non-default extension (i.e. empty for now)
-                assert!(creator_capabilities.extensions().is_empty(),);
+                // Extensions MUST only contain non-default extension (i.e. only the admins one for now)
+                assert_eq!(
+                    creator_capabilities.extensions(),
+                    &[ExtensionType::from(ADMINS_EXTENSION_TYPE)]
+                );
 
                 // To prevent downgrade attacks, Credentials should just contain the current
                 assert_eq!(
@@ -259,4 +658,169 @@ pub mod tests {
         })
         .await
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn members_should_use_their_own_sender_ratchet_configuration(mut case: TestCase) {
+        case.cfg.custom.out_of_order_tolerance = 10;
+        case.cfg.custom.maximum_forward_distance = 2000;
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    let bob_cfg = MlsCustomConfiguration {
+                        out_of_order_tolerance: 50,
+                        maximum_forward_distance: 100,
+                        ..Default::default()
+                    };
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), bob_cfg)
+                        .await
+                        .unwrap();
+
+                    let alice_conv = alice_central.mls_central.get_conversation(&id).await.unwrap();
+                    let alice_conv = alice_conv.read().await;
+                    let alice_ratchet = alice_conv.group.configuration().sender_ratchet_configuration();
+                    assert_eq!(alice_ratchet.out_of_order_tolerance(), 10);
+                    assert_eq!(alice_ratchet.maximum_forward_distance(), 2000);
+
+                    let bob_conv = bob_central.mls_central.get_conversation(&id).await.unwrap();
+                    let bob_conv = bob_conv.read().await;
+                    let bob_ratchet = bob_conv.group.configuration().sender_ratchet_configuration();
+                    assert_eq!(bob_ratchet.out_of_order_tolerance(), 50);
+                    assert_eq!(bob_ratchet.maximum_forward_distance(), 100);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_unsupported_group_extensions(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // groups created by this library only use supported extensions
+                    let group = alice_central.mls_central.get_conversation_unchecked(&id).await;
+                    MlsConversationConfiguration::check_group_extensions(
+                        group.group.export_group_context().extensions(),
+                    )
+                    .unwrap();
+
+                    // an extension type from the private use range, which a newer version might put in the group
+                    let unknown = Extension::Unknown(0xF0A0, UnknownExtension(vec![0x42]));
+                    let extensions = Extensions::from_vec(vec![
+                        Extension::ApplicationId(ApplicationIdExtension::new(b"app")),
+                        unknown,
+                    ])
+                    .unwrap();
+                    assert!(matches!(
+                        MlsConversationConfiguration::check_group_extensions(&extensions).unwrap_err(),
+                        CryptoError::UnsupportedGroupExtension(0xF0A0)
+                    ));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_validate_configuration(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let field = |cfg: &MlsConversationConfiguration, creator_credential_type| match cfg
+                    .validate(creator_credential_type)
+                    .unwrap_err()
+                {
+                    CryptoError::InvalidConfiguration { field, .. } => field,
+                    e => panic!("Unexpected error {e:?}"),
+                };
+
+                case.cfg.validate(case.credential_type).unwrap();
+                let mut cfg = case.cfg.clone();
+                cfg.custom.join_policy.allowed_ciphersuites = vec![case.ciphersuite()];
+                cfg.custom.max_application_message_epoch_age = Some(MAX_PAST_EPOCHS as u32);
+                cfg.validate(case.credential_type).unwrap();
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, cfg.clone())
+                    .await
+                    .unwrap();
+
+                let other_ciphersuite = MlsConversationConfiguration::DEFAULT_SUPPORTED_CIPHERSUITES
+                    .iter()
+                    .map(|&cs| MlsCiphersuite::from(cs))
+                    .find(|&cs| cs != case.ciphersuite())
+                    .unwrap();
+                let mut invalid = cfg.clone();
+                invalid.custom.join_policy.allowed_ciphersuites = vec![other_ciphersuite];
+                assert_eq!(
+                    field(&invalid, case.credential_type),
+                    "custom.join_policy.allowed_ciphersuites"
+                );
+
+                let mut invalid = cfg.clone();
+                invalid.custom.join_policy.require_x509_credentials = true;
+                assert_eq!(
+                    field(&invalid, MlsCredentialType::Basic),
+                    "custom.join_policy.require_x509_credentials"
+                );
+                invalid.validate(MlsCredentialType::X509).unwrap();
+
+                let mut invalid = cfg.clone();
+                invalid.set_raw_external_senders(vec![vec![]]);
+                assert_eq!(field(&invalid, case.credential_type), "external_senders");
+
+                let mut invalid = cfg.clone();
+                invalid.custom.key_rotation_span = Some(std::time::Duration::ZERO);
+                assert_eq!(field(&invalid, case.credential_type), "custom.key_rotation_span");
+                assert!(matches!(
+                    invalid.custom.validate().unwrap_err(),
+                    CryptoError::InvalidConfiguration { field, .. } if field == "key_rotation_span"
+                ));
+
+                let mut invalid = cfg.clone();
+                invalid.custom.max_application_message_epoch_age = Some(MAX_PAST_EPOCHS as u32 + 1);
+                assert_eq!(
+                    field(&invalid, case.credential_type),
+                    "custom.max_application_message_epoch_age"
+                );
+            })
+        })
+        .await
+    }
 }
@@ -17,7 +17,6 @@ use openmls::{
     },
 };
 use openmls_traits::OpenMlsCryptoProvider;
-use tls_codec::Deserialize;
 
 use core_crypto_keystore::entities::MlsPendingMessage;
 use mls_crypto_provider::MlsCryptoProvider;
@@ -27,14 +26,16 @@ use crate::{
     group_store::GroupStoreValue,
     mls::{
         client::Client,
-        conversation::renew::Renew,
+        conversation::{renew::Renew, stats::MlsConversationActivity},
         credential::crl::{
             extract_crl_uris_from_proposals, extract_crl_uris_from_update_path, get_new_crl_distribution_points,
         },
         credential::ext::CredentialExt,
+        metrics::MlsOperation,
+        proposal::validate_supported_proposals,
         ClientId, ConversationId, MlsCentral, MlsConversation,
     },
-    prelude::{E2eiConversationState, MlsProposalBundle, WireIdentity},
+    prelude::{E2eiConversationState, MlsCommitDelta, MlsProposalBundle, WireIdentity},
     CoreCryptoCallbacks, CryptoError, CryptoResult, MlsError,
 };
 
@@ -67,6 +68,8 @@ pub struct MlsConversationDecryptMessage {
     pub buffered_messages: Option<Vec<MlsBufferedConversationDecryptMessage>>,
     /// New CRL distribution points that appeared by the introduction of a new credential
     pub crl_new_distribution_points: Option<Vec<String>>,
+    /// Only set when the decrypted message is a commit. Describes the proposals it applied
+    pub commit_delta: Option<MlsCommitDelta>,
 }
 
 /// Type safe recursion of [MlsConversationDecryptMessage]
@@ -88,6 +91,8 @@ pub struct MlsBufferedConversationDecryptMessage {
     pub identity: Option<WireIdentity>,
     /// see [MlsConversationDecryptMessage]
     pub crl_new_distribution_points: Option<Vec<String>>,
+    /// see [MlsConversationDecryptMessage]
+    pub commit_delta: Option<MlsCommitDelta>,
 }
 
 impl From<MlsConversationDecryptMessage> for MlsBufferedConversationDecryptMessage {
@@ -101,6 +106,7 @@ impl From<MlsConversationDecryptMessage> for MlsBufferedConversationDecryptMessa
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity,
             crl_new_distribution_points: from.crl_new_distribution_points,
+            commit_delta: from.commit_delta,
         }
     }
 }
@@ -122,15 +128,38 @@ impl MlsConversation {
         // handles the crooked case where we receive our own commits.
         // Since this would result in an error in openmls, we handle it here
         if let Some(ct) = self.maybe_self_member_commit(&message)? {
-            return self.handle_self_member_commit(backend, ct).await;
+            let decrypted = self.handle_self_member_commit(backend, ct).await?;
+            self.release_fenced_messages(client, backend).await;
+            return Ok(decrypted);
         }
 
+        // a commit is merged in memory before being persisted, keep the group aside to put it back if that fails
+        let snapshot = is_commit(&message).then(|| self.snapshot()).transpose()?;
+        self.process_message(message, parent_conv, client, backend, callbacks, restore_pending)
+            .await
+            .map_err(|e| match snapshot {
+                Some(snapshot) => self.rollback(snapshot, e),
+                None => e,
+            })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn process_message(
+        &mut self,
+        message: MlsMessageIn,
+        parent_conv: Option<&GroupStoreValue<MlsConversation>>,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+        restore_pending: bool,
+    ) -> CryptoResult<MlsConversationDecryptMessage> {
         let message = self.parse_message(backend, message).await?;
 
         let credential = message.credential();
         let identity = credential.extract_identity()?;
 
         let sender_client_id = credential.identity().into();
+        let sender = message.sender().clone();
 
         let decrypted = match message.into_content() {
             ProcessedMessageContent::ApplicationMessage(app_msg) => MlsConversationDecryptMessage {
@@ -143,8 +172,13 @@ impl MlsConversation {
                 identity,
                 buffered_messages: None,
                 crl_new_distribution_points: None,
+                commit_delta: None,
             },
             ProcessedMessageContent::ProposalMessage(proposal) => {
+                self.validate_external_remove_proposal(&proposal)?;
+                self.enforce_admins_in_proposal(&proposal)?;
+                self.validate_group_context_extensions(std::iter::once(proposal.proposal()))?;
+                validate_supported_proposals(std::iter::once(proposal.proposal()))?;
                 let crl_dps = extract_crl_uris_from_proposals(&[proposal.proposal().clone()])?;
                 let crl_new_distribution_points = get_new_crl_distribution_points(backend, crl_dps).await?;
 
@@ -160,13 +194,27 @@ impl MlsConversation {
                     identity,
                     buffered_messages: None,
                     crl_new_distribution_points,
+                    commit_delta: None,
                 }
             }
             ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
-                self.validate_external_commit(&staged_commit, sender_client_id, parent_conv, backend, callbacks)
+                let leeway = client.clock_skew_leeway();
+                self.enforce_admins_in_commit(&staged_commit)?;
+                self.validate_member_changes(&staged_commit, sender_client_id.clone(), callbacks)
                     .await?;
+                self.validate_external_commit(
+                    &staged_commit,
+                    sender_client_id,
+                    parent_conv,
+                    backend,
+                    callbacks,
+                    leeway,
+                )
+                .await?;
 
-                self.validate_commit(&staged_commit, backend)?;
+                self.validate_commit(&staged_commit, backend, leeway)?;
+                self.validate_group_context_extensions(staged_commit.queued_proposals().map(|p| p.proposal()))?;
+                validate_supported_proposals(staged_commit.queued_proposals().map(|p| p.proposal()))?;
 
                 #[allow(clippy::needless_collect)] // false positive
                 let pending_proposals = self.self_pending_proposals().cloned().collect::<Vec<_>>();
@@ -197,6 +245,8 @@ impl MlsConversation {
 
                 // getting the pending has to be done before `merge_staged_commit` otherwise it's wiped out
                 let pending_commit = self.group.pending_commit().cloned();
+                // removed members can only be found before merging
+                let commit_delta = MlsCommitDelta::new(&self.group, &staged_commit, &sender);
 
                 self.group
                     .merge_staged_commit(backend, *staged_commit.clone())
@@ -212,6 +262,10 @@ impl MlsConversation {
                 let proposals = self
                     .renew_proposals_for_current_epoch(client, backend, proposals_to_renew.into_iter(), needs_update)
                     .await?;
+                // a pending commit of ours has been superseded
+                if pending_commit.is_some() {
+                    self.release_fenced_messages(client, backend).await;
+                }
 
                 let buffered_messages = if restore_pending {
                     if let Some(pm) = self
@@ -237,6 +291,7 @@ impl MlsConversation {
                     identity,
                     buffered_messages,
                     crl_new_distribution_points,
+                    commit_delta: Some(commit_delta),
                 }
             }
             ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
@@ -256,6 +311,7 @@ impl MlsConversation {
                     identity,
                     buffered_messages: None,
                     crl_new_distribution_points,
+                    commit_delta: None,
                 }
             }
         };
@@ -289,6 +345,9 @@ impl MlsConversation {
         };
         let msg_epoch = protocol_message.epoch().as_u64();
         let group_epoch = self.group.epoch().as_u64();
+        if matches!(content_type, ContentType::Application) {
+            self.enforce_decryption_policy(msg_epoch)?;
+        }
         let processed_msg = self
             .group
             .process_message(backend, protocol_message)
@@ -311,7 +370,11 @@ impl MlsConversation {
                             ContentType::Proposal => CryptoError::StaleProposal,
                         }
                     } else {
-                        CryptoError::WrongEpoch
+                        // the commits of the epochs in between are missing
+                        CryptoError::MessageEpochGap {
+                            expected: group_epoch + 1,
+                            received: msg_epoch,
+                        }
                     }
                 }
                 ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
@@ -320,6 +383,11 @@ impl MlsConversation {
                 ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
                     MessageDecryptionError::SecretTreeError(SecretTreeError::TooDistantInThePast),
                 )) => CryptoError::MessageEpochTooOld,
+                // the generation of the message is encrypted along with its sender hence can't be told, only that
+                // more messages than the ratchet window are missing in the current epoch
+                ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+                    MessageDecryptionError::SecretTreeError(SecretTreeError::TooDistantInTheFuture),
+                )) => CryptoError::MessageGenerationGap { epoch: msg_epoch },
                 _ => CryptoError::from(MlsError::from(e)),
             })?;
         if is_duplicate {
@@ -328,7 +396,64 @@ impl MlsConversation {
         Ok(processed_msg)
     }
 
-    fn validate_commit(&self, commit: &StagedCommit, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+    /// Asks the application whether the committer is allowed to add or remove the members it does. Only done when
+    /// callbacks are set, and not for external commits which are verified by [Self::validate_external_commit]
+    async fn validate_member_changes(
+        &self,
+        commit: &StagedCommit,
+        committer: ClientId,
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+    ) -> CryptoResult<()> {
+        let Some(callbacks) = callbacks else {
+            return Ok(());
+        };
+        let is_external_init = commit
+            .queued_proposals()
+            .any(|p| matches!(p.proposal(), Proposal::ExternalInit(_)));
+        if is_external_init {
+            return Ok(());
+        }
+
+        let added_clients = commit
+            .add_proposals()
+            .map(|p| {
+                p.add_proposal()
+                    .key_package()
+                    .leaf_node()
+                    .credential()
+                    .identity()
+                    .into()
+            })
+            .collect::<Vec<ClientId>>();
+        let removed_clients = commit
+            .remove_proposals()
+            .filter_map(|p| {
+                let removed = p.remove_proposal().removed();
+                self.group
+                    .members()
+                    .find(|m| m.index == removed)
+                    .map(|m| m.credential.identity().into())
+            })
+            .collect::<Vec<ClientId>>();
+        if added_clients.is_empty() && removed_clients.is_empty() {
+            return Ok(());
+        }
+
+        if !callbacks
+            .member_change_authorize(self.id.clone(), committer, added_clients, removed_clients)
+            .await
+        {
+            return Err(CryptoError::UnauthorizedMemberChange);
+        }
+        Ok(())
+    }
+
+    fn validate_commit(
+        &self,
+        commit: &StagedCommit,
+        backend: &MlsCryptoProvider,
+        leeway: std::time::Duration,
+    ) -> CryptoResult<()> {
         if backend.authentication_service().is_env_setup() {
             let credentials: Vec<_> = commit
                 .add_proposals()
@@ -338,7 +463,12 @@ impl MlsConversation {
                     matches!(credential.credential_type(), CredentialType::X509).then(|| credential.clone())
                 })
                 .collect();
-            let state = compute_state(credentials.iter(), backend, crate::prelude::MlsCredentialType::X509);
+            let state = compute_state(
+                credentials.iter(),
+                backend,
+                crate::prelude::MlsCredentialType::X509,
+                leeway,
+            );
             if state != E2eiConversationState::Verified {
                 // FIXME: Uncomment when PKI env can be seeded - the computation is still done to assess performance and impact of the validations
                 // return Err(CryptoError::InvalidCertificateChain);
@@ -348,6 +478,14 @@ impl MlsConversation {
     }
 }
 
+fn is_commit(message: &MlsMessageIn) -> bool {
+    match message.body_as_ref() {
+        MlsMessageInBody::PublicMessage(m) => matches!(m.content_type(), ContentType::Commit),
+        MlsMessageInBody::PrivateMessage(m) => matches!(m.content_type(), ContentType::Commit),
+        _ => false,
+    }
+}
+
 impl MlsCentral {
     /// Deserializes a TLS-serialized message, then deciphers it
     ///
@@ -363,41 +501,84 @@ impl MlsCentral {
     ///
     /// # Errors
     /// If the conversation can't be found, an error will be returned. Other errors are originating
-    /// from OpenMls and the KeyStore
+    /// from OpenMls and the KeyStore. When a commit cannot be persisted once merged, the conversation is rolled back
+    /// and a [CryptoError::MergeRolledBack] is returned so that the message can be decrypted again. ReInit proposals
+    /// are rejected with [CryptoError::UnsupportedProposal] since the successor group can't be created
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn decrypt_message(
         &mut self,
         id: &ConversationId,
         message: impl AsRef<[u8]>,
     ) -> CryptoResult<MlsConversationDecryptMessage> {
-        let msg = MlsMessageIn::tls_deserialize(&mut message.as_ref()).map_err(MlsError::from)?;
+        let _timer = self.metrics.start(MlsOperation::Decrypt);
+        let msg = self.message_limits.deserialize_message(message.as_ref())?;
         let Ok(conversation) = self.get_conversation(id).await else {
             return self.handle_when_group_is_pending(id, message).await;
         };
         let parent_conversation = self.get_parent_conversation(&conversation).await?;
-        let callbacks = self.callbacks.as_ref().map(|boxed| boxed.as_ref());
-        let decrypt_message = conversation
-            .write()
+        let decrypt_message = if is_commit(&msg) {
+            // merging a commit writes the key material of the new epoch and deletes the previous one, they have to be
+            // persisted along with the group or not at all
+            let merged = conversation.clone();
+            self.within_keystore_transaction(|central| {
+                Box::pin(central.decrypt_in_conversation(merged, parent_conversation, msg))
+            })
             .await
-            .decrypt_message(
-                msg,
-                parent_conversation.as_ref(),
-                self.mls_client()?,
-                &self.mls_backend,
-                callbacks,
-                true,
-            )
-            .await;
+        } else {
+            self.decrypt_in_conversation(conversation.clone(), parent_conversation, msg)
+                .await
+        };
 
         let decrypt_message = match decrypt_message {
             Err(CryptoError::BufferedFutureMessage) => self.handle_future_message(id, message).await?,
-            _ => decrypt_message?,
+            Err(e) => {
+                self.record_decryption_failure(id, &e).await;
+                return Err(e);
+            }
+            Ok(decrypt_message) => {
+                self.reset_conversation_health(id).await;
+                decrypt_message
+            }
         };
 
         if !decrypt_message.is_active {
             self.wipe_conversation(id).await?;
+        } else if decrypt_message.has_epoch_changed {
+            self.metrics.epoch_advanced();
+            self.cleanup_epoch_key_material(&*conversation.read().await).await;
+            let committer = decrypt_message.commit_delta.as_ref().and_then(|d| d.committer.clone());
+            self.record_merged_commit(id, committer).await;
+            self.record_conversation_activity(id, MlsConversationActivity::CommitReceived)
+                .await;
         }
+        if decrypt_message.is_active && decrypt_message.app_msg.is_some() {
+            self.record_conversation_activity(id, MlsConversationActivity::MessageReceived)
+                .await;
+        }
+        self.metrics.message_decrypted();
         Ok(decrypt_message)
     }
+
+    async fn decrypt_in_conversation(
+        &self,
+        conversation: GroupStoreValue<MlsConversation>,
+        parent_conversation: Option<GroupStoreValue<MlsConversation>>,
+        message: MlsMessageIn,
+    ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let callbacks = self.callbacks.as_ref().map(|boxed| boxed.as_ref());
+        conversation
+            .write()
+            .await
+            .decrypt_message(
+                message,
+                parent_conversation.as_ref(),
+                self.mls_client()?,
+                &self.mls_backend,
+                callbacks,
+                true,
+            )
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -410,7 +591,7 @@ pub mod tests {
 
     use crate::mls::conversation::config::MAX_PAST_EPOCHS;
     use crate::{
-        prelude::{MlsCommitBundle, MlsWirePolicy},
+        prelude::{MlsCommitBundle, MlsConversationCreationMessage, MlsWirePolicy},
         test_utils::{ValidationCallbacks, *},
         CryptoError,
     };
@@ -497,6 +678,61 @@ pub mod tests {
     pub mod commit {
         use super::*;
 
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn cannot_decrypt_commit_with_unauthorized_member_changes(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob", "charlie"],
+                move |[mut alice_central, mut bob_central, charlie_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        bob_central.mls_central.callbacks(Box::new(ValidationCallbacks {
+                            member_change_authorize: false,
+                            ..Default::default()
+                        }));
+
+                        // a commit without membership changes does not need to be authorized
+                        let MlsCommitBundle { commit, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+
+                        let charlie = charlie_central.mls_central.rand_key_package(&case).await;
+                        let MlsConversationCreationMessage { commit, .. } = alice_central
+                            .mls_central
+                            .add_members_to_conversation(&id, vec![charlie])
+                            .await
+                            .unwrap();
+                        let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+                        let error = bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap_err();
+                        assert!(matches!(error, CryptoError::UnauthorizedMemberChange));
+                        // the commit has not been merged
+                        assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                    })
+                },
+            )
+            .await
+        }
+
         #[apply(all_cred_cipher)]
         #[wasm_bindgen_test]
         pub async fn decrypting_a_commit_should_succeed(case: TestCase) {
@@ -668,7 +904,10 @@ pub mod tests {
 
                         // But its proposal to add Charlie has been renewed and is also in store
                         assert!(!proposals.is_empty());
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
 
                         // Let's commit this proposal to see if it works
@@ -780,7 +1019,11 @@ pub mod tests {
                             .unwrap();
                         assert!(proposals.is_empty());
                         assert!(delay.is_none());
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert!(has_epoch_changed)
                     })
                 },
@@ -828,7 +1071,10 @@ pub mod tests {
                             .new_add_proposal(&id, charlie_kp)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // But first she receives Bob commit
                         let MlsConversationDecryptMessage { proposals, delay, .. } = alice_central
@@ -849,7 +1095,10 @@ pub mod tests {
 
                         // But its proposal to add Charlie has been renewed and is also in store
                         assert!(!proposals.is_empty());
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         let renewed_proposal = proposals.first().unwrap();
                         assert_eq!(
                             commit_epoch.as_u64() + 1,
@@ -862,7 +1111,7 @@ pub mod tests {
                             .decrypt_message(&id, renewed_proposal.proposal.to_bytes().unwrap())
                             .await
                             .unwrap();
-                        assert_eq!(bob_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(bob_central.mls_central.pending_proposals_unchecked(&id).await.len(), 1);
                         let MlsCommitBundle { commit, .. } = bob_central
                             .mls_central
                             .commit_pending_proposals(&id)
@@ -938,13 +1187,20 @@ pub mod tests {
                             )
                             .await
                             .unwrap();
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .decrypt_message(&id, ext_proposal.to_bytes().unwrap())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         let MlsCommitBundle { commit, .. } =
                             bob_central.mls_central.update_keying_material(&id).await.unwrap();
@@ -955,7 +1211,11 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         assert!(alice_renewed_proposals.is_empty());
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                     })
                 },
             )
@@ -1522,6 +1782,98 @@ pub mod tests {
             )
             .await
         }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_detect_message_gap_beyond_ratchet_window(mut case: TestCase) {
+            case.cfg.custom.maximum_forward_distance = 2;
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                        let mut messages = vec![];
+                        for i in 0..4 {
+                            let msg = format!("Hello {i}");
+                            let encrypted = alice_central.mls_central.encrypt_message(&id, &msg).await.unwrap();
+                            messages.push((msg, encrypted));
+                        }
+
+                        // the last message is beyond the ratchet window
+                        let decrypt = bob_central.mls_central.decrypt_message(&id, &messages[3].1).await;
+                        assert!(matches!(
+                            decrypt.unwrap_err(),
+                            CryptoError::MessageGenerationGap { epoch: gap_epoch } if gap_epoch == epoch
+                        ));
+
+                        // once the missing ones are redelivered, it can be decrypted
+                        for (original, encrypted) in &messages {
+                            let decrypted = bob_central
+                                .mls_central
+                                .decrypt_message(&id, encrypted)
+                                .await
+                                .unwrap()
+                                .app_msg
+                                .unwrap();
+                            assert_eq!(decrypted, original.as_bytes());
+                        }
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_detect_missing_epochs(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                        // Bob misses 2 commits
+                        for _ in 0..2 {
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        }
+
+                        let encrypted = alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                        let decrypt = bob_central.mls_central.decrypt_message(&id, encrypted).await;
+                        assert!(matches!(
+                            decrypt.unwrap_err(),
+                            CryptoError::MessageEpochGap { expected, received } if expected == epoch + 1 && received == epoch + 2
+                        ));
+                    })
+                },
+            )
+            .await
+        }
     }
 
     pub mod epoch_sync {
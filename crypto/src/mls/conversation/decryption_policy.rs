@@ -0,0 +1,94 @@
+//! Application messages of past epochs can be decrypted as long as openmls still holds their secrets. A conversation
+//! configured with a
+//! [max_application_message_epoch_age](crate::prelude::MlsCustomConfiguration::max_application_message_epoch_age)
+//! rejects the ones lagging further behind.
+
+use crate::prelude::{CryptoError, CryptoResult, MlsConversation};
+
+impl MlsConversation {
+    /// Rejects an application message from `msg_epoch` when it is older than the conversation accepts
+    pub(crate) fn enforce_decryption_policy(&self, msg_epoch: u64) -> CryptoResult<()> {
+        let current = self.group.epoch().as_u64();
+        if msg_epoch >= current {
+            return Ok(());
+        }
+
+        match self.configuration.custom.max_application_message_epoch_age {
+            Some(max_age) if current - msg_epoch > max_age as u64 => Err(CryptoError::ApplicationMessageTooOld {
+                current,
+                received: msg_epoch,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_application_messages_older_than_policy(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.max_application_message_epoch_age = Some(1);
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                    let mut encrypted = vec![];
+                    for message in [b"one", b"two", b"six"] {
+                        encrypted.push(bob_central.mls_central.encrypt_message(&id, message).await.unwrap());
+                    }
+
+                    // one epoch behind is accepted
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let decrypted = alice_central
+                        .mls_central
+                        .decrypt_message(&id, &encrypted[0])
+                        .await
+                        .unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"one");
+
+                    // two epochs behind could still be decrypted but is rejected
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let result = alice_central.mls_central.decrypt_message(&id, &encrypted[1]).await;
+                    assert!(matches!(
+                        result.unwrap_err(),
+                        CryptoError::ApplicationMessageTooOld { current, received }
+                            if current == epoch + 2 && received == epoch
+                    ));
+
+                    // the policy survives a restart
+                    alice_central.mls_central.restore_from_disk().await.unwrap();
+                    let result = alice_central.mls_central.decrypt_message(&id, &encrypted[2]).await;
+                    assert!(matches!(
+                        result.unwrap_err(),
+                        CryptoError::ApplicationMessageTooOld { .. }
+                    ));
+                })
+            },
+        )
+        .await
+    }
+}
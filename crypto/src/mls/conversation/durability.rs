@@ -14,7 +14,11 @@ impl MlsConversation {
             .await
             .map(|mut groups| groups.remove(group_id.as_slice()).unwrap())
             .unwrap();
-        let group = MlsConversation::from_serialized_state(group, parent_id).unwrap();
+        let mut group = MlsConversation::from_serialized_state(group, parent_id).unwrap();
+        group.restore_metadata(backend.key_store()).await.unwrap();
+        // the queue of the epoch fence is only held in memory
+        group.fenced_messages = std::mem::take(&mut self.fenced_messages);
+        group.released_messages = std::mem::take(&mut self.released_messages);
         *self = group;
     }
 }
@@ -32,7 +36,8 @@ impl MlsCentral {
             .await
             .map(|mut groups| groups.remove(id.as_slice()).unwrap())
             .unwrap();
-        let group = MlsConversation::from_serialized_state(group, parent_id).unwrap();
+        let mut group = MlsConversation::from_serialized_state(group, parent_id).unwrap();
+        group.restore_metadata(self.mls_backend.key_store()).await.unwrap();
         self.mls_groups.insert(id.clone(), group);
     }
 }
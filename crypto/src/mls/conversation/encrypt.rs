@@ -6,12 +6,18 @@
 //! |-------------------|----------------|----------------|
 //! | 0 pend. Proposal  | ✅              | ❌              |
 //! | 1+ pend. Proposal | ❌              | ❌              |
+//!
+//! With a pending commit, the message is encrypted for the epoch the commit is about to end unless the conversation
+//! has an [epoch fence](crate::prelude::MlsEpochFence).
 
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls::prelude::MlsMessageOutBody;
 
 use crate::prelude::Client;
-use crate::{mls::ConversationId, mls::MlsCentral, CryptoError, CryptoResult, MlsError};
+use crate::{
+    mls::{conversation::stats::MlsConversationActivity, metrics::MlsOperation, ConversationId, MlsCentral},
+    CryptoError, CryptoResult, MlsError,
+};
 
 use super::MlsConversation;
 
@@ -25,6 +31,19 @@ impl MlsConversation {
         client: &Client,
         message: impl AsRef<[u8]>,
         backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        if self.fence_message(message.as_ref())? {
+            return Ok(vec![]);
+        }
+        self.create_application_message(client, message.as_ref(), backend).await
+    }
+
+    /// Encrypts a message for the current epoch, regardless of the epoch fence
+    pub(crate) async fn create_application_message(
+        &mut self,
+        client: &Client,
+        message: &[u8],
+        backend: &MlsCryptoProvider,
     ) -> CryptoResult<Vec<u8>> {
         let signer = &self
             .find_current_credential_bundle(client)?
@@ -32,13 +51,14 @@ impl MlsConversation {
             .signature_key;
         let encrypted = self
             .group
-            .create_message(backend, signer, message.as_ref())
+            .create_message(backend, signer, message)
             .map_err(MlsError::from)?;
 
         // make sure all application messages are encrypted
         debug_assert!(matches!(encrypted.body, MlsMessageOutBody::PrivateMessage(_)));
 
         let encrypted = encrypted.to_bytes().map_err(MlsError::from)?;
+        self.increment_own_generation();
 
         self.persist_group_when_changed(backend, false).await?;
         Ok(encrypted)
@@ -53,23 +73,33 @@ impl MlsCentral {
     /// * `message` - the message as a byte array
     ///
     /// # Return type
-    /// This method will return an encrypted TLS serialized message.
+    /// This method will return an encrypted TLS serialized message. It is empty when the message has been queued by
+    /// the epoch fence of the conversation, see [MlsCentral::take_fenced_messages]
     ///
     /// # Errors
-    /// If the conversation can't be found, an error will be returned. Other errors are originating
-    /// from OpenMls and the KeyStore
+    /// If the conversation can't be found, an error will be returned. [CryptoError::PendingCommitInFlight] when the
+    /// conversation has a pending commit and fails fast, see
+    /// [MlsCustomConfiguration::epoch_fence](crate::prelude::MlsCustomConfiguration::epoch_fence). Other errors are
+    /// originating from OpenMls and the KeyStore
     #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn encrypt_message(
         &mut self,
         conversation: &ConversationId,
         message: impl AsRef<[u8]>,
     ) -> CryptoResult<Vec<u8>> {
-        self.get_conversation(conversation)
+        let _timer = self.metrics.start(MlsOperation::Encrypt);
+        let encrypted = self
+            .get_conversation(conversation)
             .await?
             .write()
             .await
             .encrypt_message(self.mls_client()?, message, &self.mls_backend)
-            .await
+            .await?;
+        self.metrics.message_encrypted();
+        self.record_conversation_activity(conversation, MlsConversationActivity::MessageSent)
+            .await;
+        Ok(encrypted)
     }
 }
 
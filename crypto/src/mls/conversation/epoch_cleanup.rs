@@ -0,0 +1,178 @@
+//! openmls stores the encryption keypairs of every epoch a client goes through and only deletes those of the previous
+//! epoch when merging a commit. Whatever it leaves behind, e.g. when a merge was interrupted or a group was rejoined,
+//! accumulates in the keystore. After every merge, the epoch encryption keypairs of the group older than the
+//! [MAX_PAST_EPOCHS] window are deleted since no message of those epochs can be processed anymore.
+
+use core_crypto_keystore::entities::{EntityFindParams, MlsEpochEncryptionKeyPair};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::{
+    mls::{conversation::config::MAX_PAST_EPOCHS, MlsCentral, MlsConversation},
+    CryptoResult,
+};
+
+/// Counters of the epoch key material cleanup, see [MlsCentral::epoch_cleanup_metrics]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MlsEpochCleanupMetrics {
+    /// Number of times the cleanup ran, i.e. once per merged commit
+    pub runs: u64,
+    /// Number of epoch encryption keypairs deleted
+    pub deleted_keypairs: u64,
+}
+
+impl MlsConversation {
+    /// Deletes the epoch encryption keypairs of the group older than [MAX_PAST_EPOCHS] epochs and returns how many
+    /// were deleted
+    async fn delete_stale_epoch_keypairs(&self, backend: &MlsCryptoProvider) -> CryptoResult<u64> {
+        let group_id = self.group.group_id().as_slice();
+        let oldest_kept = self.group.epoch().as_u64().saturating_sub(MAX_PAST_EPOCHS as u64);
+
+        let keystore = backend.key_store();
+        let stale = keystore
+            .find_all::<MlsEpochEncryptionKeyPair>(EntityFindParams::default())
+            .await?
+            .iter()
+            .filter(|kp| matches!(keypair_epoch(&kp.id, group_id), Some(epoch) if epoch < oldest_kept))
+            .map(|kp| kp.id.clone())
+            .collect::<Vec<_>>();
+        for id in &stale {
+            keystore.remove::<MlsEpochEncryptionKeyPair, _>(id).await?;
+        }
+        Ok(stale.len() as u64)
+    }
+}
+
+/// Epoch of a keypair identified by `group id || leaf index (u32) || epoch (u64)`, when it belongs to the group
+fn keypair_epoch(id: &[u8], group_id: &[u8]) -> Option<u64> {
+    let epoch = id.strip_prefix(group_id)?.get(4..)?.try_into().ok()?;
+    Some(u64::from_be_bytes(epoch))
+}
+
+impl MlsCentral {
+    /// Counters of the cleanup of epoch key material, which runs after every merged commit
+    pub fn epoch_cleanup_metrics(&self) -> MlsEpochCleanupMetrics {
+        self.epoch_cleanup_metrics
+    }
+
+    /// Deletes the stale key material of a conversation which just merged a commit. This is best effort since the
+    /// merge already succeeded: whatever could not be deleted will be on the next one
+    pub(crate) async fn cleanup_epoch_key_material(&mut self, conversation: &MlsConversation) {
+        let deleted = conversation
+            .delete_stale_epoch_keypairs(&self.mls_backend)
+            .await
+            .unwrap_or_else(|e| {
+                crate::logger::warn(
+                    "Could not delete the stale key material of a conversation",
+                    serde_json::json!({ "conversation_id": hex::encode(conversation.id()), "error": e.to_string() }),
+                );
+                0
+            });
+        self.epoch_cleanup_metrics.runs += 1;
+        self.epoch_cleanup_metrics.deleted_keypairs += deleted;
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::{EntityFindParams, MlsEpochEncryptionKeyPair};
+    use openmls_traits::OpenMlsCryptoProvider;
+    use wasm_bindgen_test::*;
+
+    use crate::{mls::conversation::config::MAX_PAST_EPOCHS, prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    #[wasm_bindgen_test]
+    pub fn should_only_parse_keypairs_of_the_group() {
+        let id = [b"group".as_slice(), &7u32.to_be_bytes(), &42u64.to_be_bytes()].concat();
+        assert_eq!(super::keypair_epoch(&id, b"group"), Some(42));
+        assert_eq!(super::keypair_epoch(&id, b"other"), None);
+        assert_eq!(super::keypair_epoch(&id, b"grou"), None);
+        assert_eq!(super::keypair_epoch(&id[..id.len() - 1], b"group"), None);
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_delete_key_material_older_than_the_epoch_window(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // leftovers of an epoch beyond the window, of the current one and of another group
+                    let group_id = id.as_slice();
+                    let leftover = |group_id: &[u8], epoch: u64| MlsEpochEncryptionKeyPair {
+                        id: [group_id, &0u32.to_be_bytes(), &epoch.to_be_bytes()].concat(),
+                        keypairs: vec![1, 2, 3],
+                    };
+                    let keystore = alice_central.mls_central.mls_backend.key_store();
+                    keystore.save(leftover(group_id, 0)).await.unwrap();
+                    keystore
+                        .save(leftover(group_id, MAX_PAST_EPOCHS as u64 + 1))
+                        .await
+                        .unwrap();
+                    keystore.save(leftover(b"another group", 0)).await.unwrap();
+
+                    let before = alice_central.mls_central.epoch_cleanup_metrics();
+                    // moves the group past the window
+                    for _ in 0..MAX_PAST_EPOCHS {
+                        let commit = alice_central
+                            .mls_central
+                            .update_keying_material(&id)
+                            .await
+                            .unwrap()
+                            .commit;
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                    }
+
+                    let after = alice_central.mls_central.epoch_cleanup_metrics();
+                    assert_eq!(after.runs, before.runs + MAX_PAST_EPOCHS as u64);
+                    assert!(after.deleted_keypairs > before.deleted_keypairs);
+                    assert!(bob_central.mls_central.epoch_cleanup_metrics().runs >= MAX_PAST_EPOCHS as u64);
+
+                    let remaining = alice_central
+                        .mls_central
+                        .mls_backend
+                        .key_store()
+                        .find_all::<MlsEpochEncryptionKeyPair>(EntityFindParams::default())
+                        .await
+                        .unwrap()
+                        .into_iter()
+                        .map(|kp| kp.id.clone())
+                        .collect::<Vec<_>>();
+                    assert!(!remaining.contains(&leftover(group_id, 0).id));
+                    assert!(remaining.contains(&leftover(group_id, MAX_PAST_EPOCHS as u64 + 1).id));
+                    assert!(remaining.contains(&leftover(b"another group", 0).id));
+
+                    // the conversation keeps working
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+}
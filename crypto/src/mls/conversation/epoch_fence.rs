@@ -0,0 +1,298 @@
+//! A commit created by this client is in flight until the Delivery Service accepts or rejects it. Application messages
+//! encrypted meanwhile are for the epoch the commit is about to end, hence recipients having merged it may be unable to
+//! decrypt them. A conversation configured with an
+//! [epoch_fence](crate::prelude::MlsCustomConfiguration::epoch_fence) either refuses to encrypt them or queues them
+//! until the pending commit is resolved: accepted with [MlsCentral::commit_accepted], cleared with
+//! [MlsCentral::clear_pending_commit] or superseded by a commit of another member. The queued messages are then encrypted
+//! for the new epoch, in order, and handed over by [MlsCentral::take_fenced_messages]. Since application messages can't
+//! be encrypted with pending proposals, those renewed from a superseded commit have to be committed first.
+//!
+//! The queue is only held in memory: the messages still queued when the application stops are lost. A conversation holding some is hence kept in memory even when the group cache is disabled, see
+//! [MlsCentralConfiguration::disable_group_cache](crate::prelude::MlsCentralConfiguration::disable_group_cache).
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{Client, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsEpochFence};
+
+impl MlsConversation {
+    /// Applies the epoch fence to a message about to be encrypted. Returns whether it has been queued, in which case
+    /// it must not be encrypted now
+    pub(crate) fn fence_message(&mut self, message: &[u8]) -> CryptoResult<bool> {
+        // messages queued before have to go first
+        if self.group.pending_commit().is_none() && self.fenced_messages.is_empty() {
+            return Ok(false);
+        }
+        match self.configuration.custom.epoch_fence {
+            MlsEpochFence::Disabled => Ok(false),
+            MlsEpochFence::FailFast if self.group.pending_commit().is_some() => Err(CryptoError::PendingCommitInFlight),
+            MlsEpochFence::FailFast => Ok(false),
+            MlsEpochFence::Queue => {
+                self.fenced_messages.push_back(message.to_vec());
+                Ok(true)
+            }
+        }
+    }
+
+    /// Whether messages are queued by the epoch fence or released but not taken yet, which are only held in memory
+    pub(crate) fn holds_fenced_messages(&self) -> bool {
+        !self.fenced_messages.is_empty() || !self.released_messages.is_empty()
+    }
+
+    /// Encrypts the queued messages once nothing is pending anymore. The pending commit has already been resolved by
+    /// then, so failing to encrypt them is only logged: the ones left stay queued until the next commit is resolved
+    pub(crate) async fn release_fenced_messages(&mut self, client: &Client, backend: &MlsCryptoProvider) {
+        if self.group.pending_commit().is_some() || self.group.pending_proposals().next().is_some() {
+            return;
+        }
+        while let Some(message) = self.fenced_messages.pop_front() {
+            match self.create_application_message(client, &message, backend).await {
+                Ok(encrypted) => self.released_messages.push(encrypted),
+                Err(e) => {
+                    self.fenced_messages.push_front(message);
+                    crate::logger::warn(
+                        "Could not encrypt the messages queued by the epoch fence",
+                        serde_json::json!({ "conversation_id": hex::encode(self.id()), "error": e.to_string() }),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl MlsCentral {
+    /// Takes the messages queued by the epoch fence of a conversation which have been encrypted since its pending
+    /// commit was resolved, see [MlsEpochFence::Queue]. They have to be sent in this order, once the commit, if
+    /// accepted, has been. Messages still waiting for the commit to be resolved are not returned.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    pub async fn take_fenced_messages(&mut self, id: &ConversationId) -> CryptoResult<Vec<Vec<u8>>> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        Ok(std::mem::take(&mut conversation.released_messages))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_fail_fast_while_commit_in_flight(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.epoch_fence = MlsEpochFence::FailFast;
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    let result = alice_central.mls_central.encrypt_message(&id, b"Hello bob").await;
+                    assert!(matches!(result.unwrap_err(), CryptoError::PendingCommitInFlight));
+
+                    // the fence survives a restart
+                    alice_central.mls_central.restore_from_disk().await.unwrap();
+                    let result = alice_central.mls_central.encrypt_message(&id, b"Hello bob").await;
+                    assert!(matches!(result.unwrap_err(), CryptoError::PendingCommitInFlight));
+
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let encrypted = alice_central
+                        .mls_central
+                        .encrypt_message(&id, b"Hello bob")
+                        .await
+                        .unwrap();
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"Hello bob");
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_queue_messages_until_commit_accepted(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.epoch_fence = MlsEpochFence::Queue;
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    for message in [b"one", b"two"] {
+                        let encrypted = alice_central.mls_central.encrypt_message(&id, message).await.unwrap();
+                        assert!(encrypted.is_empty());
+                    }
+                    assert!(alice_central
+                        .mls_central
+                        .take_fenced_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let released = alice_central.mls_central.take_fenced_messages(&id).await.unwrap();
+                    assert_eq!(released.len(), 2);
+                    assert!(alice_central
+                        .mls_central
+                        .take_fenced_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    // encrypted for the new epoch
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    for (encrypted, message) in released.into_iter().zip([b"one", b"two"]) {
+                        let decrypted = bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                        assert_eq!(decrypted.app_msg.unwrap(), message);
+                    }
+
+                    // nothing is queued without a pending commit
+                    let encrypted = alice_central.mls_central.encrypt_message(&id, b"six").await.unwrap();
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"six");
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_release_queued_messages_when_commit_cleared_or_superseded(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.epoch_fence = MlsEpochFence::Queue;
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // rejected by the Delivery Service
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.encrypt_message(&id, b"one").await.unwrap();
+                    alice_central.mls_central.clear_pending_commit(&id).await.unwrap();
+                    let released = alice_central.mls_central.take_fenced_messages(&id).await.unwrap();
+                    assert_eq!(released.len(), 1);
+                    let decrypted = bob_central
+                        .mls_central
+                        .decrypt_message(&id, &released[0])
+                        .await
+                        .unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"one");
+
+                    // bob's commit won the race
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.encrypt_message(&id, b"two").await.unwrap();
+                    let commit = bob_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    bob_central.mls_central.commit_accepted(&id).await.unwrap();
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+
+                    // not before the update it renewed is committed as well
+                    assert!(alice_central
+                        .mls_central
+                        .take_fenced_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let released = alice_central.mls_central.take_fenced_messages(&id).await.unwrap();
+                    assert_eq!(released.len(), 1);
+                    let decrypted = bob_central
+                        .mls_central
+                        .decrypt_message(&id, &released[0])
+                        .await
+                        .unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"two");
+                })
+            },
+        )
+        .await
+    }
+}
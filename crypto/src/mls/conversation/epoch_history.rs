@@ -0,0 +1,306 @@
+//! Every epoch entered by a conversation is recorded along with a hash of its epoch authenticator, the time it was
+//! entered and the client whose commit created it, so that the history of a group can be audited later on. Only the
+//! last [EPOCH_HISTORY_RETENTION] epochs of a conversation are kept and they're removed along with it.
+
+use core_crypto_keystore::entities::MlsEpochRecord;
+use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+
+use crate::{
+    mls::client::key_package::unix_now,
+    prelude::{ClientId, ConversationId, CryptoResult, MlsCentral, MlsError},
+};
+
+/// Number of epochs recorded per conversation, the oldest ones being discarded first
+pub const EPOCH_HISTORY_RETENTION: u64 = 1_000;
+
+/// An epoch entered by a conversation, see [MlsCentral::epoch_history]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsEpochHistoryEntry {
+    /// Epoch number
+    pub epoch: u64,
+    /// Hash of the epoch authenticator, using the hash algorithm of the conversation's ciphersuite. Members agree
+    /// on it as long as they share the same group state
+    pub authenticator_hash: Vec<u8>,
+    /// Unix timestamp (in seconds) of when the epoch was entered locally
+    pub timestamp: u64,
+    /// Id of the client whose commit created the epoch. Unknown when joining by Welcome
+    pub commit_sender: Option<ClientId>,
+}
+
+/// Keystore id of the record of an epoch
+pub(crate) fn epoch_record_id(id: &ConversationId, epoch: u64) -> Vec<u8> {
+    [id.as_slice(), &epoch.to_be_bytes()].concat()
+}
+
+impl MlsCentral {
+    /// The last epochs entered by a conversation, newest first
+    ///
+    /// # Arguments
+    /// * `id` - id of the group/conversation
+    /// * `limit` - maximum number of epochs to return, at most [EPOCH_HISTORY_RETENTION] are kept
+    ///
+    /// # Errors
+    /// If the conversation can't be found or KeyStore errors
+    pub async fn epoch_history(
+        &mut self,
+        id: &ConversationId,
+        limit: usize,
+    ) -> CryptoResult<Vec<MlsEpochHistoryEntry>> {
+        let epoch = self.get_conversation(id).await?.read().await.group.epoch().as_u64();
+        let ids = (0..=epoch)
+            .rev()
+            .take(limit)
+            .map(|epoch| epoch_record_id(id, epoch))
+            .collect::<Vec<_>>();
+
+        let mut history = self
+            .mls_backend
+            .key_store()
+            .find_many::<MlsEpochRecord, _>(&ids)
+            .await?
+            .iter()
+            .map(|r| MlsEpochHistoryEntry {
+                epoch: r.epoch,
+                authenticator_hash: r.authenticator_hash.clone(),
+                timestamp: r.timestamp,
+                commit_sender: r.commit_sender.as_deref().map(ClientId::from),
+            })
+            .collect::<Vec<_>>();
+        history.sort_by(|a, b| b.epoch.cmp(&a.epoch));
+        Ok(history)
+    }
+
+    /// Records the current epoch of a conversation and discards the one falling out of the retention window
+    pub(crate) async fn record_epoch(
+        &mut self,
+        id: &ConversationId,
+        commit_sender: Option<ClientId>,
+    ) -> CryptoResult<()> {
+        #[cfg(test)]
+        self.fail_at("record_epoch")?;
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let epoch = conversation.group.epoch().as_u64();
+        let authenticator_hash = self
+            .mls_backend
+            .crypto()
+            .hash(
+                conversation.ciphersuite().hash_algorithm(),
+                conversation.group.epoch_authenticator().as_slice(),
+            )
+            .map_err(MlsError::from)?;
+        drop(conversation);
+
+        let keystore = self.mls_backend.key_store();
+        keystore
+            .save(MlsEpochRecord {
+                id: epoch_record_id(id, epoch),
+                conversation_id: id.clone(),
+                epoch,
+                authenticator_hash,
+                timestamp: unix_now()?.as_secs(),
+                commit_sender: commit_sender.map(|c| c.to_vec()),
+            })
+            .await?;
+
+        if let Some(expired_epoch) = epoch.checked_sub(EPOCH_HISTORY_RETENTION) {
+            let expired_id = epoch_record_id(id, expired_epoch);
+            if keystore.find::<MlsEpochRecord>(&expired_id).await?.is_some() {
+                keystore.remove::<MlsEpochRecord, _>(&expired_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the epoch entered by merging a commit along with the credentials seen for the first time, see
+    /// [MlsCentral::record_new_credentials]. The merge is persisted by then, so failing to record them is only
+    /// reported to the logger
+    pub(crate) async fn record_merged_commit(&mut self, id: &ConversationId, commit_sender: Option<ClientId>) {
+        if let Err(e) = self.record_new_credentials(id).await {
+            crate::logger::warn(
+                "Could not record the new credentials of a conversation",
+                serde_json::json!({ "conversation_id": hex::encode(id), "error": e.to_string() }),
+            );
+        }
+        if let Err(e) = self.record_epoch(id, commit_sender).await {
+            crate::logger::warn(
+                "Could not record the epoch of a conversation",
+                serde_json::json!({ "conversation_id": hex::encode(id), "error": e.to_string() }),
+            );
+        }
+    }
+
+    /// Removes the recorded epochs of a conversation currently at the given epoch
+    pub(crate) async fn wipe_epoch_history(&self, id: &ConversationId, epoch: u64) -> CryptoResult<()> {
+        let ids = (epoch.saturating_sub(EPOCH_HISTORY_RETENTION)..=epoch)
+            .map(|epoch| epoch_record_id(id, epoch))
+            .collect::<Vec<_>>();
+
+        let keystore = self.mls_backend.key_store();
+        for record in keystore.find_many::<MlsEpochRecord, _>(&ids).await? {
+            keystore.remove::<MlsEpochRecord, _>(&record.id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::MlsEpochRecord;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_record_epoch_history(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.get_client_id();
+
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    let alice_history = alice_central.mls_central.epoch_history(&id, 10).await.unwrap();
+                    assert_eq!(alice_history.iter().map(|e| e.epoch).collect::<Vec<_>>(), vec![2, 1, 0]);
+                    assert!(alice_history
+                        .iter()
+                        .all(|e| e.commit_sender.as_ref() == Some(&alice_id) && e.timestamp > 0));
+
+                    // the joiner only knows the epochs it has been a member of
+                    let bob_history = bob_central.mls_central.epoch_history(&id, 10).await.unwrap();
+                    assert_eq!(bob_history.iter().map(|e| e.epoch).collect::<Vec<_>>(), vec![2, 1]);
+                    assert_eq!(bob_history[0].commit_sender.as_ref(), Some(&alice_id));
+                    assert_eq!(bob_history[1].commit_sender, None);
+
+                    // members agree on the authenticators
+                    for (a, b) in alice_history.iter().zip(bob_history.iter()) {
+                        assert_eq!(a.epoch, b.epoch);
+                        assert_eq!(a.authenticator_hash, b.authenticator_hash);
+                    }
+                    assert_ne!(alice_history[0].authenticator_hash, alice_history[1].authenticator_hash);
+
+                    let latest = alice_central.mls_central.epoch_history(&id, 1).await.unwrap();
+                    assert_eq!(latest, alice_history[..1]);
+
+                    // records go away with the conversation
+                    alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .count::<MlsEpochRecord>()
+                            .await
+                            .unwrap(),
+                        0
+                    );
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_record_epoch_history_of_conversation_created_with_members(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.get_client_id();
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    alice_central
+                        .mls_central
+                        .new_conversation_with_members(&id, case.credential_type, case.cfg.clone(), vec![bob])
+                        .await
+                        .unwrap();
+
+                    let history = alice_central.mls_central.epoch_history(&id, 10).await.unwrap();
+                    assert_eq!(history.iter().map(|e| e.epoch).collect::<Vec<_>>(), vec![1, 0]);
+                    assert!(history.iter().all(|e| e.commit_sender.as_ref() == Some(&alice_id)));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn failing_to_record_epoch_should_not_fail_decryption(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // the commit is merged and persisted by then, only its record is missing
+                    bob_central.mls_central.failing_step = Some("record_epoch");
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), 2);
+                    let history = bob_central.mls_central.epoch_history(&id, 10).await.unwrap();
+                    assert_eq!(history.iter().map(|e| e.epoch).collect::<Vec<_>>(), vec![1]);
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -14,9 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
-//! Primitives to export data from a group, such as derived keys and client ids.
+//! Primitives to export data from a group, such as derived keys, epoch authenticators and client ids.
 
 use mls_crypto_provider::MlsCryptoProvider;
+use zeroize::Zeroizing;
 
 use crate::mls::{
     client::id::ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsError,
@@ -35,6 +36,11 @@ impl MlsConversation {
             .map_err(CryptoError::from)
     }
 
+    /// See [MlsCentral::epoch_authenticator]
+    pub fn epoch_authenticator(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.group.epoch_authenticator().as_slice().to_vec())
+    }
+
     /// See [MlsCentral::get_client_ids]
     pub fn get_client_ids(&self) -> Vec<ClientId> {
         self.group
@@ -67,6 +73,29 @@ impl MlsCentral {
             .export_secret_key(&self.mls_backend, key_length)
     }
 
+    /// Returns the epoch authenticator of the current epoch of a conversation, e.g. for the calling service to
+    /// authenticate the participants of a conference call. It is the same for every member and changes with every
+    /// epoch, see <https://www.rfc-editor.org/rfc/rfc9420.html#name-epoch-authenticators>
+    ///
+    /// It is derived from the epoch secret and proves membership in the epoch: treat it as a secret. Only hand it to
+    /// the party authenticating the members and do not log or persist it. The returned buffer is zeroized when
+    /// dropped but copies, e.g. across the FFI, are not, so keep them as short-lived as possible
+    ///
+    /// # Arguments
+    /// * `conversation_id` - the group/conversation id
+    ///
+    /// # Errors
+    /// if the conversation can't be found
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn epoch_authenticator(&mut self, conversation_id: &ConversationId) -> CryptoResult<Zeroizing<Vec<u8>>> {
+        Ok(self
+            .get_conversation(conversation_id)
+            .await?
+            .read()
+            .await
+            .epoch_authenticator())
+    }
+
     /// Exports the clients from a conversation
     ///
     /// # Arguments
@@ -165,6 +194,58 @@ pub mod tests {
         }
     }
 
+    mod epoch_authenticator {
+        use super::*;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn members_share_the_epoch_authenticator(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let authenticator = alice_central.mls_central.epoch_authenticator(&id).await.unwrap();
+                        assert!(!authenticator.is_empty());
+                        assert_eq!(
+                            authenticator,
+                            bob_central.mls_central.epoch_authenticator(&id).await.unwrap()
+                        );
+
+                        // a new epoch, a new authenticator
+                        alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_ne!(
+                            authenticator,
+                            alice_central.mls_central.epoch_authenticator(&id).await.unwrap()
+                        );
+
+                        let unknown_id = b"not_found".to_vec();
+                        let error = alice_central
+                            .mls_central
+                            .epoch_authenticator(&unknown_id)
+                            .await
+                            .unwrap_err();
+                        assert!(matches!(error, CryptoError::ConversationNotFound(c) if c == unknown_id));
+                    })
+                },
+            )
+            .await
+        }
+    }
+
     mod get_client_ids {
         use super::*;
 
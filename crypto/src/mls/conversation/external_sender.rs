@@ -1,8 +1,22 @@
+use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+
+use mls_crypto_provider::MlsCryptoProvider;
+
 use crate::{
     prelude::{ConversationId, MlsCentral, MlsConversation},
     prelude::{CryptoError, CryptoResult},
 };
 
+/// A key of the Delivery Service allowed to send external proposals to a conversation, see
+/// [MlsCentral::conversation_external_senders]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsExternalSender {
+    /// Raw public signature key
+    pub signature_key: Vec<u8>,
+    /// Identity of the credential of the external sender
+    pub identity: Vec<u8>,
+}
+
 impl MlsCentral {
     /// Returns the raw public key of the single external sender present in this group.
     /// This should be used to initialize a subconversation
@@ -14,6 +28,42 @@ impl MlsCentral {
             .get_external_sender()
             .await
     }
+
+    /// Returns the external senders of a conversation, in the order of its ExternalSenders extension. Empty when the
+    /// conversation has none
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    pub async fn conversation_external_senders(&mut self, id: &ConversationId) -> CryptoResult<Vec<MlsExternalSender>> {
+        Ok(self.get_conversation(id).await?.read().await.external_senders())
+    }
+
+    /// Checks whether a raw signature, e.g. sent by the Delivery Service along with a message, has been made by one
+    /// of the external senders of a conversation. The signature scheme of the conversation's ciphersuite is used
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `payload` - the signed bytes
+    /// * `signature` - the raw signature of `payload`
+    ///
+    /// # Return type
+    /// The index of the external sender who made the signature, none when none of them did
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    pub async fn verify_external_sender_signature(
+        &mut self,
+        id: &ConversationId,
+        payload: &[u8],
+        signature: &[u8],
+    ) -> CryptoResult<Option<usize>> {
+        Ok(self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .verify_external_sender_signature(&self.mls_backend, payload, signature))
+    }
 }
 
 impl MlsConversation {
@@ -27,13 +77,46 @@ impl MlsConversation {
         let ext_sender_public_key = ext_sender.signature_key().as_slice().to_vec();
         Ok(ext_sender_public_key)
     }
+
+    fn external_senders(&self) -> Vec<MlsExternalSender> {
+        self.group
+            .group_context_extensions()
+            .external_senders()
+            .map(|senders| {
+                senders
+                    .iter()
+                    .map(|sender| MlsExternalSender {
+                        signature_key: sender.signature_key().as_slice().to_vec(),
+                        identity: sender.credential().identity().to_vec(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn verify_external_sender_signature(
+        &self,
+        backend: &MlsCryptoProvider,
+        payload: &[u8],
+        signature: &[u8],
+    ) -> Option<usize> {
+        let scheme = self.signature_scheme();
+        self.external_senders().iter().position(|sender| {
+            backend
+                .crypto()
+                .verify_signature(scheme, payload, &sender.signature_key, signature)
+                .is_ok()
+        })
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use openmls::prelude::{Credential, ExternalSender, SignaturePublicKey};
+    use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
     use wasm_bindgen_test::*;
 
-    use crate::test_utils::*;
+    use crate::{prelude::*, test_utils::*};
 
     wasm_bindgen_test_configure!(run_in_browser);
 
@@ -62,4 +145,90 @@ pub mod tests {
         })
         .await
     }
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_list_and_verify_external_senders(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let scheme = case.signature_scheme();
+                let crypto = alice_central.mls_central.mls_backend.crypto();
+                let (sk, pk) = crypto.signature_key_gen(scheme).unwrap();
+                let (other_sk, _) = crypto.signature_key_gen(scheme).unwrap();
+                let server = ExternalSender::new(
+                    SignaturePublicKey::from(pk.clone()),
+                    Credential::new_basic(b"server".to_vec()),
+                );
+                let payload = b"external proposal";
+                let signature = crypto.sign(scheme, payload, &sk).unwrap();
+                let other_signature = crypto.sign(scheme, payload, &other_sk).unwrap();
+
+                let mut cfg = case.cfg.clone();
+                cfg.external_senders = vec![alice_central.mls_central.rand_external_sender(&case), server];
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, cfg)
+                    .await
+                    .unwrap();
+
+                let senders = alice_central
+                    .mls_central
+                    .conversation_external_senders(&id)
+                    .await
+                    .unwrap();
+                assert_eq!(senders.len(), 2);
+                assert_eq!(
+                    senders[1],
+                    MlsExternalSender {
+                        signature_key: pk,
+                        identity: b"server".to_vec(),
+                    }
+                );
+
+                let mut tampered = signature.clone();
+                tampered[0] ^= 0xFF;
+                for (signature, expected) in [(signature, Some(1)), (other_signature, None), (tampered, None)] {
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .verify_external_sender_signature(&id, payload, &signature)
+                            .await
+                            .unwrap(),
+                        expected
+                    );
+                }
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_have_no_external_senders_by_default(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                assert!(alice_central
+                    .mls_central
+                    .conversation_external_senders(&id)
+                    .await
+                    .unwrap()
+                    .is_empty());
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .verify_external_sender_signature(&id, b"payload", b"signature")
+                        .await
+                        .unwrap(),
+                    None
+                );
+            })
+        })
+        .await
+    }
 }
@@ -0,0 +1,249 @@
+//! The extensions of the group context, e.g. its external senders or its required capabilities, are set when a
+//! conversation is created but sometimes have to change afterwards, for instance when the Delivery Service rotates
+//! its signature key. They are replaced altogether by a GroupContextExtensions proposal, which
+//! [MlsCentral::update_group_context_extensions] commits right away. The admins extension is left as is, it has its
+//! own API, see [MlsCentral::set_conversation_admins]. Incoming ones are validated while decrypting, see
+//! [MlsConversation::validate_group_context_extensions].
+
+use openmls::prelude::{Extension, Extensions, ExternalSender, Node, Proposal};
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::{
+    mls::conversation::admins::with_admins,
+    prelude::{
+        Client, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation,
+        MlsConversationConfiguration, MlsError, MlsGroupInfoBundle, MlsRequiredCapabilities,
+    },
+};
+
+/// Extensions of the group context which can be changed once the conversation exists, see
+/// [MlsCentral::update_group_context_extensions]
+#[derive(Debug, Clone, Default)]
+pub struct MlsGroupContextExtensions {
+    /// Delivery service public signature keys and credentials. None when empty
+    pub external_senders: Vec<ExternalSender>,
+    /// Capabilities every member of the conversation has to support
+    pub required_capabilities: MlsRequiredCapabilities,
+}
+
+impl MlsGroupContextExtensions {
+    fn to_extensions(&self) -> CryptoResult<Extensions> {
+        let mut extensions = vec![Extension::RequiredCapabilities(self.required_capabilities.extension())];
+        if !self.external_senders.is_empty() {
+            extensions.push(Extension::ExternalSenders(self.external_senders.clone()));
+        }
+        Ok(Extensions::from_vec(extensions).map_err(MlsError::from)?)
+    }
+}
+
+impl MlsCentral {
+    /// Replaces the extensions of the group context of a conversation and automatically commits. Pending proposals
+    /// will be committed
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    /// * `extensions` - the new extensions, replacing every current one but the admins
+    ///
+    /// # Return type
+    /// A struct containing a welcome (optional, will be present only if there's pending add proposals in the store),
+    /// a message with the commit to fan out to other clients and the group info will be returned on successful call.
+    ///
+    /// # Errors
+    /// If the conversation can't be found, with [CryptoError::MissingRequiredCapabilities] if a member does not
+    /// support the new required capabilities. Other errors are originating from OpenMls and the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn update_group_context_extensions(
+        &mut self,
+        id: &ConversationId,
+        extensions: MlsGroupContextExtensions,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let extensions = with_admins(&extensions.to_extensions()?, &conversation.admins()?)?;
+        conversation
+            .update_group_context_extensions(self.mls_client()?, &self.mls_backend, extensions)
+            .await
+    }
+}
+
+impl MlsConversation {
+    /// see [MlsCentral::update_group_context_extensions]
+    #[cfg_attr(test, crate::durable)]
+    pub(crate) async fn update_group_context_extensions(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        extensions: Extensions,
+    ) -> CryptoResult<MlsCommitBundle> {
+        MlsConversationConfiguration::check_group_extensions(&extensions)?;
+        if let Some(required) = extensions.required_capabilities() {
+            // fail with a meaningful error rather than letting OpenMls reject the commit
+            for node in self.group.export_ratchet_tree().iter() {
+                if let Some(Node::LeafNode(leaf_node)) = node {
+                    MlsRequiredCapabilities::check_leaf_node(required, leaf_node)?;
+                }
+            }
+        }
+
+        let signer = &self
+            .find_most_recent_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?
+            .signature_key;
+        let (commit, welcome, group_info) = self
+            .group
+            .update_extensions(backend, signer, extensions)
+            .await
+            .map_err(MlsError::from)?;
+
+        // We should always have ratchet tree extension turned on hence GroupInfo should always be present
+        let group_info = group_info.ok_or(CryptoError::ImplementationError)?;
+        let group_info = MlsGroupInfoBundle::try_new_full_plaintext(group_info)?;
+
+        self.persist_group_when_changed(backend, false).await?;
+
+        Ok(MlsCommitBundle {
+            welcome,
+            commit,
+            group_info,
+        })
+    }
+
+    /// Rejects the GroupContextExtensions proposals among `proposals` which would leave this client in a conversation
+    /// it can't handle i.e. with an extension it does not support or requiring a capability its leaf lacks
+    pub(crate) fn validate_group_context_extensions<'a>(
+        &self,
+        mut proposals: impl Iterator<Item = &'a Proposal>,
+    ) -> CryptoResult<()> {
+        proposals.try_for_each(|proposal| {
+            let Proposal::GroupContextExtensions(proposal) = proposal else {
+                return Ok(());
+            };
+            let extensions = proposal.extensions();
+            MlsConversationConfiguration::check_group_extensions(extensions)?;
+            match (extensions.required_capabilities(), self.group.own_leaf_node()) {
+                (Some(required), Some(own_leaf)) => MlsRequiredCapabilities::check_leaf_node(required, own_leaf),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use openmls::prelude::ExtensionType;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_update_group_context_extensions(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    assert!(bob_central.mls_central.get_external_sender(&id).await.is_err());
+
+                    let external_sender = alice_central.mls_central.rand_external_sender(&case);
+                    let extensions = MlsGroupContextExtensions {
+                        external_senders: vec![external_sender.clone()],
+                        ..Default::default()
+                    };
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .update_group_context_extensions(&id, extensions)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert_eq!(
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    let expected = external_sender.signature_key().as_slice().to_vec();
+                    assert_eq!(
+                        alice_central.mls_central.get_external_sender(&id).await.unwrap(),
+                        expected
+                    );
+                    assert_eq!(
+                        bob_central.mls_central.get_external_sender(&id).await.unwrap(),
+                        expected
+                    );
+                    alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .unwrap();
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_require_capabilities_a_member_lacks(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // no KeyPackage generated by this library supports an extension from the private use range
+                    let extensions = MlsGroupContextExtensions {
+                        required_capabilities: MlsRequiredCapabilities {
+                            extension_types: vec![ExtensionType::from(0xF0A0)],
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    };
+                    let result = alice_central
+                        .mls_central
+                        .update_group_context_extensions(&id, extensions)
+                        .await;
+                    assert!(matches!(
+                        result.unwrap_err(),
+                        CryptoError::MissingRequiredCapabilities(_)
+                    ));
+                    let conversation = alice_central.mls_central.get_conversation_unchecked(&id).await;
+                    assert!(conversation.group.pending_commit().is_none());
+                })
+            },
+        )
+        .await
+    }
+}
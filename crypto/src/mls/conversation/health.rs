@@ -0,0 +1,245 @@
+//! Consecutive decryption failures in a conversation usually mean that the local group state diverged from the one of
+//! the other members. They are counted per conversation so that the application can be told when it is time to rejoin
+//! the conversation with an external commit instead of failing every incoming message.
+
+use std::collections::HashMap;
+
+use crate::prelude::{ConversationId, CryptoError, MlsCentral};
+
+/// Health of a conversation based on the number of consecutive messages which could not be decrypted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MlsConversationHealth {
+    /// The last message could be decrypted
+    Healthy,
+    /// Some messages in a row could not be decrypted
+    Suspect {
+        /// Number of consecutive decryption failures
+        failures: u32,
+    },
+    /// Too many messages in a row could not be decrypted, the conversation should be rejoined with
+    /// [MlsCentral::join_by_external_commit]
+    Broken {
+        /// Number of consecutive decryption failures
+        failures: u32,
+    },
+}
+
+/// Number of consecutive decryption failures after which a conversation is deemed unhealthy, see
+/// [MlsCentral::set_conversation_health_thresholds]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlsConversationHealthThresholds {
+    /// The conversation becomes [MlsConversationHealth::Suspect] from this number of failures. Defaults to 1
+    pub suspect: u32,
+    /// The conversation becomes [MlsConversationHealth::Broken] from this number of failures. Defaults to 5
+    pub broken: u32,
+}
+
+impl Default for MlsConversationHealthThresholds {
+    fn default() -> Self {
+        Self { suspect: 1, broken: 5 }
+    }
+}
+
+impl MlsConversationHealthThresholds {
+    fn health(&self, failures: u32) -> MlsConversationHealth {
+        match failures {
+            f if f >= self.broken => MlsConversationHealth::Broken { failures },
+            f if f >= self.suspect && f > 0 => MlsConversationHealth::Suspect { failures },
+            _ => MlsConversationHealth::Healthy,
+        }
+    }
+}
+
+/// Notified every time the health of a conversation changes, see [MlsCentral::conversation_health_observer]
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait MlsConversationHealthObserver: std::fmt::Debug + Send + Sync {
+    /// Called when a threshold is crossed, in either direction
+    ///
+    /// # Arguments
+    /// * `conversation_id` - id of the group/conversation
+    /// * `health` - the new health of the conversation
+    async fn health_changed(&self, conversation_id: ConversationId, health: MlsConversationHealth);
+}
+
+/// Consecutive decryption failures of every conversation having some
+#[derive(Debug, Default)]
+pub(crate) struct ConversationHealthTracker {
+    failures: HashMap<ConversationId, u32>,
+    thresholds: MlsConversationHealthThresholds,
+    observer: Option<Box<dyn MlsConversationHealthObserver>>,
+}
+
+impl ConversationHealthTracker {
+    fn health(&self, id: &ConversationId) -> MlsConversationHealth {
+        self.thresholds
+            .health(self.failures.get(id).copied().unwrap_or_default())
+    }
+
+    /// Whether the error hints at a divergence of the group state rather than at an invalid or unwanted message
+    fn is_divergence(error: &CryptoError) -> bool {
+        matches!(
+            error,
+            CryptoError::DecryptionError
+                | CryptoError::WrongEpoch
+                | CryptoError::MessageEpochGap { .. }
+                | CryptoError::MessageGenerationGap { .. }
+                | CryptoError::InternalMlsError
+                | CryptoError::MlsError(_)
+        )
+    }
+}
+
+impl MlsCentral {
+    /// Health of a conversation based on the number of consecutive messages which could not be decrypted. Only
+    /// tracked in memory, hence every conversation is [MlsConversationHealth::Healthy] again after a restart
+    pub fn conversation_health(&self, id: &ConversationId) -> MlsConversationHealth {
+        self.conversation_health.health(id)
+    }
+
+    /// Sets the number of consecutive decryption failures after which a conversation is deemed unhealthy
+    pub fn set_conversation_health_thresholds(&mut self, thresholds: MlsConversationHealthThresholds) {
+        self.conversation_health.thresholds = thresholds;
+    }
+
+    /// Sets the observer notified every time the health of a conversation changes, so that a broken conversation
+    /// can be rejoined
+    pub fn conversation_health_observer(&mut self, observer: Box<dyn MlsConversationHealthObserver>) {
+        self.conversation_health.observer = Some(observer);
+    }
+
+    /// Counts a failure to decrypt a message when it hints at a divergence of the group state
+    pub(crate) async fn record_decryption_failure(&mut self, id: &ConversationId, error: &CryptoError) {
+        if !ConversationHealthTracker::is_divergence(error) {
+            return;
+        }
+        let previous = self.conversation_health.health(id);
+        *self.conversation_health.failures.entry(id.clone()).or_default() += 1;
+        self.notify_health_change(id, previous).await;
+    }
+
+    /// Resets the health of a conversation, for example once a message could be decrypted again
+    pub(crate) async fn reset_conversation_health(&mut self, id: &ConversationId) {
+        let previous = self.conversation_health.health(id);
+        if self.conversation_health.failures.remove(id).is_some() {
+            self.notify_health_change(id, previous).await;
+        }
+    }
+
+    async fn notify_health_change(&self, id: &ConversationId, previous: MlsConversationHealth) {
+        let health = self.conversation_health.health(id);
+        // in the same category, only the number of failures changed
+        if std::mem::discriminant(&previous) == std::mem::discriminant(&health) {
+            return;
+        }
+        if let Some(observer) = self.conversation_health.observer.as_ref() {
+            observer.health_changed(id.clone(), health).await;
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Default, Clone)]
+    struct RecordingObserver(Arc<Mutex<Vec<MlsConversationHealth>>>);
+
+    #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+    impl MlsConversationHealthObserver for RecordingObserver {
+        async fn health_changed(&self, _: ConversationId, health: MlsConversationHealth) {
+            self.0.lock().unwrap().push(health);
+        }
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_track_consecutive_decryption_failures(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let observer = RecordingObserver::default();
+                    bob_central
+                        .mls_central
+                        .conversation_health_observer(Box::new(observer.clone()));
+                    bob_central
+                        .mls_central
+                        .set_conversation_health_thresholds(MlsConversationHealthThresholds { suspect: 1, broken: 3 });
+                    assert_eq!(
+                        bob_central.mls_central.conversation_health(&id),
+                        MlsConversationHealth::Healthy
+                    );
+
+                    // some of Alice's messages get corrupted on their way to Bob
+                    let encrypted = alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                    bob_central.mls_central.decrypt_message(&id, &encrypted).await.unwrap();
+                    let mut fails = vec![];
+                    for _ in 0..3 {
+                        let mut tampered = alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                        let last = tampered.len() - 1;
+                        tampered[last] ^= 0xFF;
+                        fails.push(tampered);
+                    }
+
+                    // duplicates are not a sign of divergence
+                    let duplicate = bob_central.mls_central.decrypt_message(&id, &encrypted).await;
+                    assert!(matches!(duplicate.unwrap_err(), CryptoError::DuplicateMessage));
+                    assert_eq!(
+                        bob_central.mls_central.conversation_health(&id),
+                        MlsConversationHealth::Healthy
+                    );
+
+                    for (i, fail) in fails.iter().enumerate() {
+                        assert!(bob_central.mls_central.decrypt_message(&id, fail).await.is_err());
+                        let failures = i as u32 + 1;
+                        let expected = if failures < 3 {
+                            MlsConversationHealth::Suspect { failures }
+                        } else {
+                            MlsConversationHealth::Broken { failures }
+                        };
+                        assert_eq!(bob_central.mls_central.conversation_health(&id), expected);
+                    }
+                    // only notified when crossing a threshold
+                    assert_eq!(
+                        *observer.0.lock().unwrap(),
+                        vec![
+                            MlsConversationHealth::Suspect { failures: 1 },
+                            MlsConversationHealth::Broken { failures: 3 }
+                        ]
+                    );
+
+                    // decrypting a message again means the conversation recovered
+                    let encrypted = alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                    bob_central.mls_central.decrypt_message(&id, &encrypted).await.unwrap();
+                    assert_eq!(
+                        bob_central.mls_central.conversation_health(&id),
+                        MlsConversationHealth::Healthy
+                    );
+                    assert_eq!(observer.0.lock().unwrap().last(), Some(&MlsConversationHealth::Healthy));
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,260 @@
+//! A commit is pending until the Delivery Service accepted it. When the application crashes before sending it, retrying
+//! the operation would generate another commit while the first one may still get delivered, forking the conversation.
+//! [MlsCentral::commit_with_operation_key] makes such a retry safe: the artifacts of a commit generated with an
+//! operation key are stored along with it, and returned again as long as the same commit is still pending.
+//!
+//! Only the last commit generated with an operation key is kept per conversation. It no longer matches once the
+//! commit is merged, cleared or replaced by another one, hence a new commit is then generated for the same key.
+
+use core_crypto_keystore::entities::MlsPendingCommit;
+use openmls::prelude::{KeyPackageIn, TlsSerializeTrait as _};
+
+use super::{commit::MlsCommitBundle, MlsConversation};
+use crate::prelude::{ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError, MlsGroupInfoBundle};
+
+/// Commit-producing operation run by [MlsCentral::commit_with_operation_key]
+#[derive(Debug, Clone)]
+pub enum MlsCommitOperation {
+    /// See [MlsCentral::add_members_to_conversation]
+    AddMembers(Vec<KeyPackageIn>),
+    /// See [MlsCentral::remove_members_from_conversation]
+    RemoveMembers(Vec<ClientId>),
+    /// See [MlsCentral::update_keying_material]
+    UpdateKeyingMaterial,
+    /// See [MlsCentral::commit_pending_proposals]
+    CommitPendingProposals,
+}
+
+/// Serialized artifacts of a commit generated by [MlsCentral::commit_with_operation_key]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MlsIdempotentCommitBundle {
+    /// TLS serialized commit message
+    pub commit: Vec<u8>,
+    /// TLS serialized welcome message, when members are added
+    pub welcome: Option<Vec<u8>>,
+    /// `GroupInfo` if the commit is merged
+    pub group_info: MlsGroupInfoBundle,
+    /// New CRL distribution points that appeared by the introduction of a new credential
+    pub crl_new_distribution_points: Option<Vec<String>>,
+    /// Whether the commit was already generated by a previous call with the same operation key, in which case it has
+    /// to be sent as is instead of a new one
+    #[serde(skip)]
+    pub replayed: bool,
+}
+
+impl MlsConversation {
+    /// TLS serialized confirmation tag of the pending commit, if any
+    fn pending_confirmation_tag(&self) -> CryptoResult<Option<Vec<u8>>> {
+        let Some(commit) = self.group.pending_commit() else {
+            return Ok(None);
+        };
+        let confirmation_tag = commit
+            .get_confirmation_tag()
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?;
+        Ok(Some(confirmation_tag))
+    }
+}
+
+impl MlsCentral {
+    /// Runs a commit-producing operation identified by `operation_key`, see [the module documentation](self). When
+    /// the commit it previously generated with the same key is still pending, its artifacts are returned again with
+    /// [MlsIdempotentCommitBundle::replayed] set instead of generating a new one.
+    ///
+    /// # Arguments
+    /// * `id` - id of the group/conversation
+    /// * `operation_key` - chosen by the application, e.g. a random value persisted along with the operation before
+    ///   running it
+    /// * `operation` - the operation generating the commit
+    ///
+    /// # Returns
+    /// The artifacts of the commit, none when there were no pending proposals to commit
+    ///
+    /// # Errors
+    /// The ones of the operation, KeyStore errors, or if the artifacts cannot be serialized
+    pub async fn commit_with_operation_key(
+        &mut self,
+        id: &ConversationId,
+        operation_key: &[u8],
+        operation: MlsCommitOperation,
+    ) -> CryptoResult<Option<MlsIdempotentCommitBundle>> {
+        let pending_confirmation_tag = self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .pending_confirmation_tag()?;
+        if let Some(pending_confirmation_tag) = pending_confirmation_tag {
+            let stored = self.mls_backend.key_store().find::<MlsPendingCommit>(id).await?;
+            if let Some(stored) = stored.filter(|stored| {
+                stored.operation_key == operation_key && stored.confirmation_tag == pending_confirmation_tag
+            }) {
+                let mut bundle: MlsIdempotentCommitBundle = serde_json::from_slice(&stored.bundle)?;
+                bundle.replayed = true;
+                return Ok(Some(bundle));
+            }
+        }
+
+        let bundle: Option<MlsIdempotentCommitBundle> = match operation {
+            MlsCommitOperation::AddMembers(key_packages) => {
+                let (welcome, commit, group_info, crl_new_distribution_points) =
+                    self.add_members_to_conversation(id, key_packages).await?.to_bytes()?;
+                Some(MlsIdempotentCommitBundle {
+                    commit,
+                    welcome: Some(welcome),
+                    group_info,
+                    crl_new_distribution_points,
+                    replayed: false,
+                })
+            }
+            MlsCommitOperation::RemoveMembers(clients) => {
+                Some(self.remove_members_from_conversation(id, &clients).await?.try_into()?)
+            }
+            MlsCommitOperation::UpdateKeyingMaterial => Some(self.update_keying_material(id).await?.try_into()?),
+            MlsCommitOperation::CommitPendingProposals => self
+                .commit_pending_proposals(id)
+                .await?
+                .map(MlsIdempotentCommitBundle::try_from)
+                .transpose()?,
+        };
+        let Some(bundle) = bundle else {
+            return Ok(None);
+        };
+
+        let confirmation_tag = self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .pending_confirmation_tag()?
+            .unwrap_or_default();
+        self.mls_backend
+            .key_store()
+            .save(MlsPendingCommit {
+                id: id.clone(),
+                operation_key: operation_key.to_vec(),
+                confirmation_tag,
+                bundle: serde_json::to_vec(&bundle)?,
+            })
+            .await?;
+        Ok(Some(bundle))
+    }
+
+    /// Removes the commit stored for an operation key in a conversation
+    pub(crate) async fn wipe_pending_commit(&self, id: &ConversationId) -> CryptoResult<()> {
+        let keystore = self.mls_backend.key_store();
+        if keystore.find::<MlsPendingCommit>(id).await?.is_some() {
+            keystore.remove::<MlsPendingCommit, _>(id).await?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<MlsCommitBundle> for MlsIdempotentCommitBundle {
+    type Error = CryptoError;
+
+    fn try_from(bundle: MlsCommitBundle) -> CryptoResult<Self> {
+        let (welcome, commit, group_info) = bundle.to_bytes_triple()?;
+        Ok(Self {
+            commit,
+            welcome,
+            group_info,
+            crl_new_distribution_points: None,
+            replayed: false,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::MlsPendingCommit;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_replay_pending_commit_with_same_operation_key(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let first = alice_central
+                        .mls_central
+                        .commit_with_operation_key(&id, b"add-bob", MlsCommitOperation::AddMembers(vec![bob]))
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert!(!first.replayed);
+                    assert!(first.welcome.is_some());
+
+                    // the application crashed before sending the commit, then retries
+                    alice_central.mls_central.drop_and_restore(&id).await;
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let retry = alice_central
+                        .mls_central
+                        .commit_with_operation_key(&id, b"add-bob", MlsCommitOperation::AddMembers(vec![bob]))
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert!(retry.replayed);
+                    assert_eq!(retry.commit, first.commit);
+                    assert_eq!(retry.welcome, first.welcome);
+                    assert_eq!(retry.group_info.payload.bytes(), first.group_info.payload.bytes());
+
+                    // another operation key generates another commit, replacing the pending one
+                    let charlie = charlie_central.mls_central.rand_key_package(&case).await;
+                    let other = alice_central
+                        .mls_central
+                        .commit_with_operation_key(&id, b"add-charlie", MlsCommitOperation::AddMembers(vec![charlie]))
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert!(!other.replayed);
+                    assert_ne!(other.commit, first.commit);
+
+                    // once merged, the same operation key generates a new commit
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let update = alice_central
+                        .mls_central
+                        .commit_with_operation_key(&id, b"add-charlie", MlsCommitOperation::UpdateKeyingMaterial)
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert!(!update.replayed);
+                    assert_ne!(update.commit, other.commit);
+
+                    // there is no proposal to commit
+                    let none = alice_central
+                        .mls_central
+                        .commit_with_operation_key(&id, b"nothing", MlsCommitOperation::CommitPendingProposals)
+                        .await
+                        .unwrap();
+                    assert!(none.is_none());
+
+                    alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    let stored = alice_central
+                        .mls_central
+                        .mls_backend
+                        .key_store()
+                        .find::<MlsPendingCommit>(&id)
+                        .await
+                        .unwrap();
+                    assert!(stored.is_none());
+                })
+            },
+        )
+        .await
+    }
+}
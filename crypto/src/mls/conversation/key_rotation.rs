@@ -0,0 +1,287 @@
+//! Own key material has to be rotated periodically for post-compromise security. The time of the last rotation is
+//! tracked in the keystore for every conversation, so that [MlsCentral::conversations_needing_key_update] can tell
+//! which conversations configured with a
+//! [key_rotation_span](crate::prelude::MlsCustomConfiguration::key_rotation_span) are due for an update commit, and
+//! [MlsCentral::update_all_stale] update the ones not rotated for a while, even across restarts.
+
+use std::collections::HashMap;
+
+use core_crypto_keystore::entities::MlsConversationMetadata;
+use openmls_traits::OpenMlsCryptoProvider;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::{
+    mls::{
+        client::key_package::unix_now,
+        conversation::batch_commit::{commit_concurrently, DEFAULT_COMMIT_CONCURRENCY},
+    },
+    prelude::{ConversationId, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation},
+};
+
+impl MlsCentral {
+    /// Lists the conversations whose own key material is older than their configured
+    /// [key_rotation_span](crate::prelude::MlsCustomConfiguration::key_rotation_span). They should be updated with
+    /// [MlsCentral::update_keying_material], the rotation is then accounted for once the commit is accepted.
+    ///
+    /// Conversations without a key rotation span are never listed.
+    ///
+    /// # Errors
+    /// KeyStore errors, or if the system clock is before the Unix epoch
+    pub async fn conversations_needing_key_update(&self) -> CryptoResult<Vec<ConversationId>> {
+        let now = unix_now()?.as_secs();
+        Ok(self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsConversationMetadata>(Default::default())
+            .await?
+            .iter()
+            .filter(|metadata| {
+                metadata.key_rotation_span > 0
+                    && metadata.last_key_rotation.saturating_add(metadata.key_rotation_span) <= now
+            })
+            .map(|metadata| metadata.id.clone())
+            .collect())
+    }
+
+    /// Self updates the KeyPackage and commits in every conversation whose own leaf was not updated for `max_age`,
+    /// e.g. when the application wakes up. Conversations are processed concurrently, at most
+    /// [DEFAULT_COMMIT_CONCURRENCY] at a time, see [MlsCentral::update_keying_material_many]. The ones with a pending
+    /// commit are skipped since it has to be resolved first, and so are the ones whose commit could not be generated:
+    /// they are still stale the next time.
+    ///
+    /// A conversation joined before the last rotations were tracked is always considered stale.
+    ///
+    /// # Return type
+    /// The commit of every stale conversation. Each of them has to be accepted or cleared once the Delivery Service
+    /// answered
+    ///
+    /// # Errors
+    /// KeyStore errors, or if the system clock is before the Unix epoch
+    pub async fn update_all_stale(
+        &mut self,
+        max_age: std::time::Duration,
+    ) -> CryptoResult<HashMap<ConversationId, MlsCommitBundle>> {
+        let now = unix_now()?.as_secs();
+        let last_updates = self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsConversationMetadata>(Default::default())
+            .await?
+            .iter()
+            .map(|metadata| (metadata.id.clone(), metadata.last_key_rotation))
+            .collect::<HashMap<_, _>>();
+
+        let mut conversations = vec![];
+        for summary in self.list_conversations().await? {
+            let is_stale = last_updates
+                .get(&summary.id)
+                .map_or(true, |last_update| last_update.saturating_add(max_age.as_secs()) <= now);
+            if !is_stale {
+                continue;
+            }
+            let conversation = self.get_conversation(&summary.id).await?;
+            if conversation.read().await.group.pending_commit().is_some() {
+                continue;
+            }
+            conversations.push(conversation);
+        }
+
+        let client = self.mls_client()?;
+        let backend = &self.mls_backend;
+        let commits = commit_concurrently(
+            conversations,
+            DEFAULT_COMMIT_CONCURRENCY,
+            |mut conversation| async move { conversation.update_keying_material(client, backend, None, None).await },
+        )
+        .await;
+
+        Ok(commits
+            .into_iter()
+            .filter_map(|(id, commit)| match commit {
+                Ok(commit) => Some((id, commit)),
+                Err(e) => {
+                    crate::logger::warn(
+                        "Could not update the keying material of a stale conversation",
+                        serde_json::json!({ "conversation_id": hex::encode(&id), "error": e.to_string() }),
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+impl MlsConversation {
+    /// Records that the own key material has just been rotated
+    pub(crate) async fn record_key_rotation(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let now = unix_now()?.as_secs();
+        self.update_metadata(backend, |metadata| metadata.last_key_rotation = now)
+            .await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::MlsConversationMetadata;
+    use openmls_traits::OpenMlsCryptoProvider;
+    use wasm_bindgen_test::*;
+
+    use crate::test_utils::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_list_conversations_whose_key_rotation_span_elapsed(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let mut cfg = case.cfg.clone();
+                cfg.custom.key_rotation_span = Some(std::time::Duration::from_secs(3600));
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, cfg)
+                    .await
+                    .unwrap();
+
+                // no rotation span, never listed
+                let other_id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&other_id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+
+                // key material is fresh
+                assert!(alice_central
+                    .mls_central
+                    .conversations_needing_key_update()
+                    .await
+                    .unwrap()
+                    .is_empty());
+
+                // pretend the last rotation happened 2 hours ago
+                let keystore = alice_central.mls_central.mls_backend.key_store();
+                let mut metadata = keystore.find::<MlsConversationMetadata>(&id).await.unwrap().unwrap();
+                metadata.last_key_rotation -= 7200;
+                keystore.save(metadata).await.unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_needing_key_update()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+
+                // still due after a restart
+                alice_central.mls_central.restore_from_disk().await.unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_needing_key_update()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+
+                // rotation is only accounted for once the commit is merged
+                alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_needing_key_update()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+                alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                assert!(alice_central
+                    .mls_central
+                    .conversations_needing_key_update()
+                    .await
+                    .unwrap()
+                    .is_empty());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_update_all_stale_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let ids = (0..4).map(|_| conversation_id()).collect::<Vec<_>>();
+                    for id in &ids {
+                        alice_central
+                            .mls_central
+                            .new_conversation(id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                    }
+                    let max_age = std::time::Duration::from_secs(3600);
+
+                    // key material is fresh
+                    assert!(alice_central
+                        .mls_central
+                        .update_all_stale(max_age)
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    // pretend the first 3 were last rotated 2 hours ago, even without a key rotation span
+                    let keystore = alice_central.mls_central.mls_backend.key_store();
+                    for id in &ids[..3] {
+                        let mut metadata = keystore.find::<MlsConversationMetadata>(id).await.unwrap().unwrap();
+                        assert_eq!(metadata.key_rotation_span, 0);
+                        metadata.last_key_rotation -= 7200;
+                        keystore.save(metadata).await.unwrap();
+                    }
+                    // a commit is already in flight in the third one
+                    alice_central.mls_central.update_keying_material(&ids[2]).await.unwrap();
+
+                    let commits = alice_central.mls_central.update_all_stale(max_age).await.unwrap();
+                    let mut updated = commits.keys().cloned().collect::<Vec<_>>();
+                    updated.sort();
+                    let mut expected = ids[..2].to_vec();
+                    expected.sort();
+                    assert_eq!(updated, expected);
+
+                    for (id, commit) in commits {
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .try_talk_to(&id, &mut bob_central.mls_central)
+                            .await
+                            .unwrap();
+                    }
+                    alice_central.mls_central.commit_accepted(&ids[2]).await.unwrap();
+
+                    // the rotations are accounted for once merged
+                    assert!(alice_central
+                        .mls_central
+                        .update_all_stale(max_age)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                })
+            },
+        )
+        .await
+    }
+}
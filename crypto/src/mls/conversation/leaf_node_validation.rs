@@ -54,7 +54,11 @@ pub mod tests {
                                 ProposeAddMemberError::KeyPackageVerifyError(KeyPackageVerifyError::InvalidLeafNode(_))
                             ))
                         ));
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
 
                         // should fail when creating Add commits
                         let expiration_time = 14;
@@ -84,7 +88,11 @@ pub mod tests {
                                 AddMembersError::KeyPackageVerifyError(KeyPackageVerifyError::InvalidLeafNode(_))
                             ))
                         ));
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
                     })
                 },
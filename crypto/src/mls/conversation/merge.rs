@@ -18,7 +18,10 @@ use openmls_traits::OpenMlsCryptoProvider;
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
-    mls::{ConversationId, MlsCentral, MlsConversation},
+    mls::{
+        conversation::stats::MlsConversationActivity, metrics::MlsOperation, ConversationId, MlsCentral,
+        MlsConversation,
+    },
     prelude::{decrypt::MlsBufferedConversationDecryptMessage, MlsProposalRef},
     CryptoError, CryptoResult, MlsError,
 };
@@ -30,14 +33,36 @@ impl MlsConversation {
     pub async fn commit_accepted(&mut self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
         // openmls stores here all the encryption keypairs used for update proposals..
         let previous_own_leaf_nodes = self.group.own_leaf_nodes.clone();
-
-        self.group.merge_pending_commit(backend).await.map_err(MlsError::from)?;
-        self.persist_group_when_changed(backend, false).await?;
+        let rotates_own_keys = self
+            .group
+            .pending_commit()
+            .map(|c| c.get_update_path_leaf_node().is_some())
+            .unwrap_or_default();
+
+        // keep the group aside to put it back if the merge cannot be persisted
+        let snapshot = self.snapshot()?;
+        let merged: CryptoResult<()> = async {
+            self.group.merge_pending_commit(backend).await.map_err(MlsError::from)?;
+            self.persist_group_when_changed(backend, false).await?;
+            if rotates_own_keys {
+                self.record_key_rotation(backend).await?;
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = merged {
+            return Err(self.rollback(snapshot, e));
+        }
 
         // ..so if there's any, we clear them after the commit is merged
         for oln in &previous_own_leaf_nodes {
             let ek = oln.encryption_key().as_slice();
-            let _ = backend.key_store().remove::<MlsEncryptionKeyPair, _>(ek).await;
+            if let Err(e) = backend.key_store().remove::<MlsEncryptionKeyPair, _>(ek).await {
+                crate::logger::warn(
+                    "Could not delete the encryption keypair of a previous leaf node",
+                    serde_json::json!({ "conversation_id": hex::encode(self.id()), "error": e.to_string() }),
+                );
+            }
         }
 
         Ok(())
@@ -91,18 +116,38 @@ impl MlsCentral {
     /// to be used for the new epoch.
     /// We can now safely "merge" it (effectively apply the commit to the group) and update it
     /// in the keystore. The previous can be discarded to respect Forward Secrecy.
+    ///
+    /// # Errors
+    /// When the merged group cannot be persisted, it is rolled back in memory and a [CryptoError::MergeRolledBack] is
+    /// returned: the commit is still pending and this can be retried
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn commit_accepted(
         &mut self,
         id: &ConversationId,
     ) -> CryptoResult<Option<Vec<MlsBufferedConversationDecryptMessage>>> {
+        let _timer = self.metrics.start(MlsOperation::Merge);
         let conv = self.get_conversation(id).await?;
+        // the group is persisted along with the key material of the new epoch and the deletion of the previous one
+        let merged = conv.clone();
+        self.within_keystore_transaction(|central| {
+            Box::pin(async move { merged.write().await.commit_accepted(&central.mls_backend).await })
+        })
+        .await?;
         let mut conv = conv.write().await;
-        conv.commit_accepted(&self.mls_backend).await?;
+        conv.release_fenced_messages(self.mls_client()?, &self.mls_backend)
+            .await;
+        self.metrics.epoch_advanced();
+        self.cleanup_epoch_key_material(&conv).await;
 
         let pending_messages = self.restore_pending_messages(&mut conv, false).await?;
         if pending_messages.is_some() {
             self.mls_backend.key_store().remove::<MlsPendingMessage, _>(id).await?;
         }
+        drop(conv);
+        self.record_merged_commit(id, Some(self.client_id()?)).await;
+        self.record_conversation_activity(id, MlsConversationActivity::CommitSent)
+            .await;
+        self.rotation_commit_accepted(id).await?;
         Ok(pending_messages)
     }
 
@@ -148,12 +193,13 @@ impl MlsCentral {
     /// When the conversation is not found or there is no pending commit
     #[cfg_attr(test, crate::idempotent)]
     pub async fn clear_pending_commit(&mut self, conversation_id: &ConversationId) -> CryptoResult<()> {
-        self.get_conversation(conversation_id)
-            .await?
-            .write()
-            .await
-            .clear_pending_commit(&self.mls_backend)
-            .await
+        let conversation = self.get_conversation(conversation_id).await?;
+        let mut conversation = conversation.write().await;
+        conversation.clear_pending_commit(&self.mls_backend).await?;
+        conversation
+            .release_fenced_messages(self.mls_client()?, &self.mls_backend)
+            .await;
+        Ok(())
     }
 }
 
@@ -250,11 +296,19 @@ pub mod tests {
                             .add_members_to_conversation(&id, vec![bob])
                             .await
                             .unwrap();
-                        assert!(!alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(!alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_some());
                         alice_central.mls_central.commit_accepted(&id).await.unwrap();
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                     })
                 },
             )
@@ -315,7 +369,11 @@ pub mod tests {
                             .invite_all(&case, &id, [&mut bob_central.mls_central])
                             .await
                             .unwrap();
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
 
                         let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
                         let add_ref = alice_central
@@ -339,16 +397,22 @@ pub mod tests {
                             .unwrap()
                             .proposal_ref;
 
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 3);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            3
+                        );
                         alice_central
                             .mls_central
                             .clear_pending_proposal(&id, add_ref)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 2);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            2
+                        );
                         assert!(!alice_central
                             .mls_central
-                            .pending_proposals(&id)
+                            .pending_proposals_unchecked(&id)
                             .await
                             .into_iter()
                             .any(|p| matches!(p.proposal(), Proposal::Add(_))));
@@ -358,10 +422,13 @@ pub mod tests {
                             .clear_pending_proposal(&id, remove_ref)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert!(!alice_central
                             .mls_central
-                            .pending_proposals(&id)
+                            .pending_proposals_unchecked(&id)
                             .await
                             .into_iter()
                             .any(|p| matches!(p.proposal(), Proposal::Remove(_))));
@@ -371,10 +438,14 @@ pub mod tests {
                             .clear_pending_proposal(&id, update_ref)
                             .await
                             .unwrap();
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert!(!alice_central
                             .mls_central
-                            .pending_proposals(&id)
+                            .pending_proposals_unchecked(&id)
                             .await
                             .into_iter()
                             .any(|p| matches!(p.proposal(), Proposal::Update(_))));
@@ -408,7 +479,7 @@ pub mod tests {
                         .mls_central.new_conversation(&id, case.credential_type, case.cfg.clone())
                         .await
                         .unwrap();
-                    assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                    assert!(alice_central.mls_central.pending_proposals_unchecked(&id).await.is_empty());
                     let any_ref = MlsProposalRef::from(vec![0; case.ciphersuite().hash_length()]);
                     let clear = alice_central.mls_central.clear_pending_proposal(&id, any_ref.clone()).await;
                     assert!(matches!(clear.unwrap_err(), CryptoError::PendingProposalNotFound(prop_ref) if prop_ref == any_ref))
@@ -427,15 +498,15 @@ pub mod tests {
                         .new_conversation(&id, case.credential_type, case.cfg.clone())
                         .await
                         .unwrap();
-                    assert!(cc.mls_central.pending_proposals(&id).await.is_empty());
+                    assert!(cc.mls_central.pending_proposals_unchecked(&id).await.is_empty());
 
                     let init = cc.mls_central.count_entities().await;
 
                     let proposal_ref = cc.mls_central.new_update_proposal(&id).await.unwrap().proposal_ref;
-                    assert_eq!(cc.mls_central.pending_proposals(&id).await.len(), 1);
+                    assert_eq!(cc.mls_central.pending_proposals_unchecked(&id).await.len(), 1);
 
                     cc.mls_central.clear_pending_proposal(&id, proposal_ref).await.unwrap();
-                    assert!(cc.mls_central.pending_proposals(&id).await.is_empty());
+                    assert!(cc.mls_central.pending_proposals_unchecked(&id).await.is_empty());
 
                     // This whole flow should be idempotent.
                     // Here we verify that we are indeed deleting the `EncryptionKeyPair` created
@@ -0,0 +1,94 @@
+//! openmls only persists the group, not the settings core-crypto adds on top of it:
+//! [key_rotation_span](crate::prelude::MlsCustomConfiguration::key_rotation_span),
+//! [max_application_message_epoch_age](crate::prelude::MlsCustomConfiguration::max_application_message_epoch_age) and
+//! [epoch_fence](crate::prelude::MlsCustomConfiguration::epoch_fence). They are kept in a single keystore entity per
+//! conversation, along with when its own key material was last rotated. A conversation restored from the keystore has
+//! the default configuration, hence gets the settings it lacks from this entity once, when it is restored.
+
+use core_crypto_keystore::{entities::MlsConversationMetadata, Connection};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::{
+    mls::client::key_package::unix_now,
+    prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsCustomConfiguration, MlsEpochFence},
+};
+
+impl MlsConversation {
+    /// Persists the settings of a conversation which has just been created or joined, hence whose own key material
+    /// is brand new
+    pub(crate) async fn record_metadata(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let mut metadata = self.metadata_from_configuration();
+        metadata.last_key_rotation = unix_now()?.as_secs();
+        backend.key_store().save(metadata).await?;
+        Ok(())
+    }
+
+    /// Settings of the conversation found in the keystore, if any
+    pub(crate) async fn find_metadata(
+        &self,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Option<MlsConversationMetadata>> {
+        Ok(backend.key_store().find::<MlsConversationMetadata>(self.id()).await?)
+    }
+
+    /// Fills the configuration of a conversation restored from the keystore with its persisted settings
+    pub(crate) async fn restore_metadata(&mut self, keystore: &Connection) -> CryptoResult<()> {
+        if let Some(metadata) = keystore.find::<MlsConversationMetadata>(self.id()).await? {
+            apply_metadata(&mut self.configuration.custom, &metadata);
+        }
+        Ok(())
+    }
+
+    /// Changes the persisted settings of the conversation with `f`. Those of a conversation which has none yet are
+    /// taken from its configuration first.
+    pub(crate) async fn update_metadata(
+        &self,
+        backend: &MlsCryptoProvider,
+        f: impl FnOnce(&mut MlsConversationMetadata),
+    ) -> CryptoResult<()> {
+        let mut metadata = match self.find_metadata(backend).await? {
+            Some(metadata) => metadata,
+            None => self.metadata_from_configuration(),
+        };
+        f(&mut metadata);
+        backend.key_store().save(metadata).await?;
+        Ok(())
+    }
+
+    fn metadata_from_configuration(&self) -> MlsConversationMetadata {
+        let custom = &self.configuration.custom;
+        MlsConversationMetadata {
+            id: self.id().clone(),
+            key_rotation_span: custom.key_rotation_span.map(|span| span.as_secs()).unwrap_or_default(),
+            last_key_rotation: 0,
+            max_application_message_epoch_age: custom.max_application_message_epoch_age.map(u64::from),
+            epoch_fence: custom.epoch_fence as u8,
+        }
+    }
+}
+
+impl MlsCentral {
+    /// Removes the persisted settings of a conversation
+    pub(crate) async fn wipe_conversation_metadata(&self, id: &ConversationId) -> CryptoResult<()> {
+        let keystore = self.mls_backend.key_store();
+        if keystore.find::<MlsConversationMetadata>(id).await?.is_some() {
+            keystore.remove::<MlsConversationMetadata, _>(id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Fills the settings `custom` lacks with the persisted ones
+fn apply_metadata(custom: &mut MlsCustomConfiguration, metadata: &MlsConversationMetadata) {
+    if custom.key_rotation_span.is_none() && metadata.key_rotation_span > 0 {
+        custom.key_rotation_span = Some(std::time::Duration::from_secs(metadata.key_rotation_span));
+    }
+    if custom.max_application_message_epoch_age.is_none() {
+        custom.max_application_message_epoch_age = metadata.max_application_message_epoch_age.map(|age| age as u32);
+    }
+    if custom.epoch_fence == MlsEpochFence::Disabled {
+        custom.epoch_fence = metadata.epoch_fence.into();
+    }
+}
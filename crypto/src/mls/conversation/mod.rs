@@ -27,7 +27,7 @@
 //! | merge     | ❌           | ❌            | ✅           | ✅            |
 //! | decrypt   | ✅           | ✅            | ✅           | ✅            |
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use openmls::{group::MlsGroup, prelude::Credential};
 use openmls_traits::{types::SignatureScheme, OpenMlsCryptoProvider};
@@ -38,33 +38,58 @@ use mls_crypto_provider::MlsCryptoProvider;
 use config::MlsConversationConfiguration;
 
 use crate::{
+    buffer_pool::PooledBuffer,
     group_store::GroupStoreValue,
     mls::{client::Client, MlsCentral},
     prelude::{CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsError},
 };
 
+pub(crate) mod admins;
+pub mod batch_commit;
 mod buffer_messages;
+pub mod claimed_key_package;
 pub mod commit;
 mod commit_delay;
+pub mod commit_delta;
 pub mod config;
 #[cfg(test)]
 mod db_count;
 pub mod decrypt;
+mod decryption_policy;
 mod duplicate;
 #[cfg(test)]
 mod durability;
 pub mod encrypt;
+pub mod epoch_cleanup;
+mod epoch_fence;
+pub mod epoch_history;
 pub mod export;
 pub(crate) mod external_sender;
+pub(crate) mod group_context_extensions;
 pub(crate) mod group_info;
+pub mod health;
+pub mod idempotent_commit;
+mod key_rotation;
 mod leaf_node_validation;
 pub mod merge;
+pub(crate) mod metadata;
 mod orphan_welcome;
+pub mod prefetch;
+pub mod preview;
 pub mod proposal;
+mod psk;
+pub mod ratchet_diagnostics;
+pub mod registry;
 mod renew;
+#[cfg(feature = "rfc-export")]
+pub mod rfc_export;
+pub mod seen_credential;
 mod self_commit;
+mod snapshot;
+pub mod stats;
+pub mod sub_conversation;
 pub(crate) mod welcome;
-mod wipe;
+pub mod wipe;
 /// A unique identifier for a group/conversation. The identifier must be unique within a client.
 pub type ConversationId = Vec<u8>;
 
@@ -78,6 +103,14 @@ pub struct MlsConversation {
     pub(crate) parent_id: Option<ConversationId>,
     pub(crate) group: MlsGroup,
     configuration: MlsConversationConfiguration,
+    /// Reused across the persists of the group, see [MlsConversation::persist_group_when_changed]
+    persist_buffer: PooledBuffer,
+    /// Plaintexts held back by the epoch fence until the pending commit is resolved
+    fenced_messages: VecDeque<Vec<u8>>,
+    /// Fenced messages encrypted once the pending commit was resolved, not yet taken by the application
+    released_messages: Vec<Vec<u8>>,
+    /// Application messages encrypted in the current epoch, see [MlsCentral::ratchet_diagnostics]
+    own_generation: ratchet_diagnostics::OwnGeneration,
 }
 
 impl MlsConversation {
@@ -90,6 +123,9 @@ impl MlsConversation {
     /// * `config` - group configuration
     /// * `backend` - MLS Provider that will be used to persist the group
     ///
+    /// The group and the settings of the conversation are persisted separately, hence this has to run within a
+    /// transaction.
+    ///
     /// # Errors
     /// Errors can happen from OpenMls or from the KeyStore
     pub async fn create(
@@ -104,7 +140,7 @@ impl MlsConversation {
             .get_most_recent_or_create_credential_bundle(backend, cs.signature_algorithm(), ct)
             .await?;
 
-        let group = MlsGroup::new_with_group_id(
+        let mut group = MlsGroup::new_with_group_id(
             backend,
             &cb.signature_key,
             &configuration.as_openmls_default_configuration()?,
@@ -113,20 +149,38 @@ impl MlsConversation {
         )
         .await
         .map_err(MlsError::from)?;
+        if !configuration.custom.admins.is_empty() {
+            // OpenMls can't be given arbitrary group context extensions when creating the group, hence the admins
+            // are set by a first commit
+            let extensions =
+                admins::with_admins(group.export_group_context().extensions(), &configuration.custom.admins)?;
+            group
+                .update_extensions(backend, &cb.signature_key, extensions)
+                .await
+                .map_err(MlsError::from)?;
+            group.merge_pending_commit(backend).await.map_err(MlsError::from)?;
+        }
 
         let mut conversation = Self {
             id,
             group,
             parent_id: None,
             configuration,
+            persist_buffer: PooledBuffer::take(),
+            fenced_messages: Default::default(),
+            released_messages: vec![],
+            own_generation: Default::default(),
         };
 
         conversation.persist_group_when_changed(backend, true).await?;
+        conversation.record_metadata(backend).await?;
 
         Ok(conversation)
     }
 
     /// Internal API: create a group from an existing conversation. For example by external commit
+    ///
+    /// Like [MlsConversation::create], it has to run within a transaction, see [MlsCentral::within_transaction]
     pub(crate) async fn from_mls_group(
         group: MlsGroup,
         configuration: MlsConversationConfiguration,
@@ -139,9 +193,14 @@ impl MlsConversation {
             group,
             configuration,
             parent_id: None,
+            persist_buffer: PooledBuffer::take(),
+            fenced_messages: Default::default(),
+            released_messages: vec![],
+            own_generation: Default::default(),
         };
 
         conversation.persist_group_when_changed(backend, true).await?;
+        conversation.record_metadata(backend).await?;
 
         Ok(conversation)
     }
@@ -160,6 +219,11 @@ impl MlsConversation {
             group,
             parent_id,
             configuration,
+            // the state it has been restored from makes a buffer of the right size
+            persist_buffer: buf.into(),
+            fenced_messages: Default::default(),
+            released_messages: vec![],
+            own_generation: Default::default(),
         })
     }
 
@@ -185,14 +249,17 @@ impl MlsConversation {
     ) -> CryptoResult<()> {
         if force || self.group.state_changed() == openmls::group::InnerState::Changed {
             use core_crypto_keystore::CryptoKeystoreMls as _;
+            core_crypto_keystore::ser_into(&self.group, &mut self.persist_buffer)?;
             backend
                 .key_store()
-                .mls_group_persist(
-                    &self.id,
-                    &core_crypto_keystore::ser(&self.group)?,
-                    self.parent_id.as_deref(),
-                )
+                .mls_group_persist(&self.id, &self.persist_buffer, self.parent_id.as_deref())
                 .await?;
+            if let Err(e) = self.update_index(backend).await {
+                // the group state has been kept aside to be persisted later, the index will follow along next time
+                if !backend.key_store().dirty_mls_groups().await.contains(&self.id) {
+                    return Err(e);
+                }
+            }
 
             self.group.set_state(openmls::group::InnerState::Persisted);
         }
@@ -419,6 +486,73 @@ pub mod tests {
         .await;
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn create_conversation_with_members_should_succeed(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let MlsConversationCreationMessage { welcome, .. } = alice_central
+                        .mls_central
+                        .new_conversation_with_members(&id, case.credential_type, case.cfg.clone(), vec![bob])
+                        .await
+                        .unwrap();
+
+                    // commit is already merged
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), 1);
+                    assert_eq!(alice_central.mls_central.members_count(&id).await, 2);
+
+                    bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn create_conversation_with_members_should_fail_when_already_exists(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let create = alice_central
+                        .mls_central
+                        .new_conversation_with_members(&id, case.credential_type, case.cfg.clone(), vec![bob])
+                        .await;
+                    assert!(matches!(create.unwrap_err(), CryptoError::ConversationAlreadyExists(i) if i == id));
+                    // the existing conversation is left untouched
+                    assert_eq!(alice_central.mls_central.members_count(&id).await, 1);
+                })
+            },
+        )
+        .await;
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn create_many_people_conversation(case: TestCase) {
@@ -0,0 +1,100 @@
+//! Conversations are kept in memory in a bounded cache and loaded from the keystore when missing. Loading one when the
+//! user opens it means keystore I/O and deserialization on the UI path, hence apps can warm the cache beforehand with
+//! [MlsCentral::prefetch_conversations] and watch [MlsCentral::conversation_cache_stats] to size it.
+
+use crate::prelude::{ConversationId, CryptoResult, MlsCentral};
+
+/// Lookups of conversations in memory since they were last restored from the keystore, see
+/// [MlsCentral::conversation_cache_stats]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MlsConversationCacheStats {
+    /// Conversations found in memory
+    pub hits: u64,
+    /// Conversations which had to be loaded from the keystore
+    pub misses: u64,
+    /// Conversations currently held in memory
+    pub cached: u64,
+}
+
+impl MlsCentral {
+    /// Loads the given conversations in memory in a single keystore read, so that opening them later does not hit the
+    /// keystore. The ones already in memory are left untouched and unknown ids are ignored. Prefetching more
+    /// conversations than the cache holds evicts the least recently used ones. Does nothing when the cache is disabled
+    /// with [MlsCentralConfiguration::disable_group_cache](crate::prelude::MlsCentralConfiguration::disable_group_cache).
+    ///
+    /// Returns how many conversations were loaded
+    ///
+    /// # Errors
+    /// KeyStore errors or a persisted group state which cannot be deserialized
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn prefetch_conversations(&mut self, ids: &[ConversationId]) -> CryptoResult<usize> {
+        let keystore = self.mls_backend.borrow_keystore_mut();
+        self.mls_groups.prefetch(ids, keystore).await
+    }
+
+    /// Hits and misses of the in-memory conversation cache. They are reset when restoring from disk
+    pub fn conversation_cache_stats(&self) -> MlsConversationCacheStats {
+        let stats = self.mls_groups.stats();
+        MlsConversationCacheStats {
+            hits: stats.hits,
+            misses: stats.misses,
+            cached: self.mls_groups.len() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_prefetch_conversations(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let ids = [conversation_id(), conversation_id(), conversation_id()];
+                for id in &ids {
+                    alice_central
+                        .mls_central
+                        .new_conversation(id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                }
+
+                // simulates conversations evicted from memory
+                alice_central.mls_central.mls_groups = crate::group_store::GroupStore::default();
+                let stats = alice_central.mls_central.conversation_cache_stats();
+                assert_eq!(stats, MlsConversationCacheStats::default());
+
+                let unknown = conversation_id();
+                let loaded = alice_central
+                    .mls_central
+                    .prefetch_conversations(&[ids[0].clone(), ids[1].clone(), unknown])
+                    .await
+                    .unwrap();
+                assert_eq!(loaded, 2);
+                let stats = alice_central.mls_central.conversation_cache_stats();
+                assert_eq!((stats.hits, stats.misses, stats.cached), (0, 0, 2));
+
+                // already in memory, nothing left to load
+                let loaded = alice_central
+                    .mls_central
+                    .prefetch_conversations(&[ids[0].clone()])
+                    .await
+                    .unwrap();
+                assert_eq!(loaded, 0);
+
+                for id in &ids {
+                    alice_central.mls_central.conversation_epoch(id).await.unwrap();
+                }
+                let stats = alice_central.mls_central.conversation_cache_stats();
+                assert_eq!((stats.hits, stats.misses, stats.cached), (2, 1, 3));
+            })
+        })
+        .await
+    }
+}
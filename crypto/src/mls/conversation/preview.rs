@@ -0,0 +1,264 @@
+//! Before adding or removing members, the application may want to show what the conversation would look like
+//! afterwards or to check that the commit fits in the limits of a proxy. [MlsCentral::preview_commit] generates the
+//! commit on a copy of the group backed by a throwaway keystore, hence neither the group nor the keystore of the
+//! client see any of it. The commit of a preview can't be sent: the actual one has to be generated again.
+
+use core_crypto_keystore::entities::MlsPskBundle;
+use openmls::prelude::{KeyPackageIn, Sender, TlsSerializeTrait};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{
+    ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle, MlsCommitDelta, MlsConversation,
+    MlsCryptoProvider, MlsError, MlsProposal,
+};
+
+/// Key of the throwaway keystore a preview is generated against, it is dropped along with the preview
+const PREVIEW_STORE_KEY: &str = "core-crypto-commit-preview";
+
+/// What a commit would do to a conversation, see [MlsCentral::preview_commit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsCommitPreview {
+    /// Members of the conversation once the commit merged: the remaining ones in the order of their leaves, then
+    /// the added ones
+    pub members: Vec<ClientId>,
+    /// Epoch of the conversation once the commit merged
+    pub epoch: u64,
+    /// Changes made by the commit, pending proposals included
+    pub delta: MlsCommitDelta,
+    /// Size in bytes of the serialized commit
+    pub commit_size: usize,
+    /// Size in bytes of the serialized Welcome, none when nobody is added
+    pub welcome_size: Option<usize>,
+    /// Size in bytes of the GroupInfo published along with the commit
+    pub group_info_size: usize,
+}
+
+impl MlsCentral {
+    /// Generates the commit applying `intents`, along with the pending proposals, on a copy of the conversation and
+    /// describes it without changing anything: the conversation, its pending proposals and the keystore are left as
+    /// they are. The commit generated afterwards for the same intents is not the very same, but its sizes only vary
+    /// by a few bytes.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    /// * `intents` - the changes to commit. Adds only or removes only are committed by value, as
+    ///   [MlsCentral::add_members_to_conversation] and [MlsCentral::remove_members_from_conversation] do. Anything
+    ///   else, or nothing, is committed along with an update of the own leaf. Pre-shared keys have to be added with
+    ///   [MlsCentral::add_external_psk] first
+    ///
+    /// # Errors
+    /// The same as committing the intents would, e.g. [CryptoError::NotConversationAdmin] or
+    /// [CryptoError::ClientNotFound] when a client to remove isn't a member
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn preview_commit(
+        &mut self,
+        id: &ConversationId,
+        intents: Vec<MlsProposal>,
+    ) -> CryptoResult<MlsCommitPreview> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let client = self.mls_client()?;
+
+        let mut key_packages = vec![];
+        let mut removed = vec![];
+        let mut psk_ids = vec![];
+        let mut update = false;
+        for intent in intents {
+            match intent {
+                MlsProposal::Add(key_package) => key_packages.push(KeyPackageIn::from(key_package)),
+                MlsProposal::Remove(client_id) => {
+                    if !conversation.members().contains_key(client_id.as_slice()) {
+                        return Err(CryptoError::ClientNotFound(client_id));
+                    }
+                    removed.push(client_id)
+                }
+                MlsProposal::Update => update = true,
+                MlsProposal::PreSharedKey(psk_id) => psk_ids.push(psk_id),
+            }
+        }
+        if !key_packages.is_empty() || !removed.is_empty() {
+            conversation.ensure_admin(client.id())?;
+        }
+
+        let backend = MlsCryptoProvider::try_new_in_memory(PREVIEW_STORE_KEY).await?;
+        let mut copy = conversation.copy()?;
+        drop(conversation);
+        copy.persist_group_when_changed(&backend, true).await?;
+        // the commit injecting pre-shared keys, pending ones included, needs their secrets
+        for psk in self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsPskBundle>(Default::default())
+            .await?
+        {
+            backend.key_store().save(psk).await?;
+        }
+
+        // pre-shared keys can only be injected by reference
+        let update = update || !psk_ids.is_empty();
+        let bundle = match (key_packages.is_empty(), removed.is_empty(), update) {
+            (false, true, false) => {
+                let message = copy.add_members(client, key_packages, &backend).await?;
+                MlsCommitBundle {
+                    welcome: Some(message.welcome),
+                    commit: message.commit,
+                    group_info: message.group_info,
+                }
+            }
+            (true, false, false) => copy.remove_members(client, &removed, &backend).await?,
+            _ => {
+                for key_package in key_packages {
+                    copy.propose_add_member(client, &backend, key_package).await?;
+                }
+                for client_id in removed {
+                    let index = copy
+                        .group
+                        .members()
+                        .find(|m| m.credential.identity() == client_id.as_slice())
+                        .ok_or(CryptoError::ClientNotFound(client_id))?
+                        .index;
+                    copy.propose_remove_member(client, &backend, index).await?;
+                }
+                for psk_id in psk_ids {
+                    copy.propose_external_psk(client, &backend, psk_id).await?;
+                }
+                copy.update_keying_material(client, &backend, None, None).await?
+            }
+        };
+
+        let commit = copy.group.pending_commit().ok_or(CryptoError::ImplementationError)?;
+        let delta = MlsCommitDelta::new(&copy.group, commit, &Sender::Member(copy.group.own_leaf_index()));
+        let members = copy
+            .group
+            .members()
+            .map(|m| ClientId::from(m.credential.identity()))
+            .filter(|client_id| !delta.removed.contains(client_id))
+            .chain(delta.added.iter().cloned())
+            .collect();
+
+        Ok(MlsCommitPreview {
+            members,
+            epoch: copy.group.epoch().as_u64() + 1,
+            commit_size: bundle.commit.tls_serialize_detached().map_err(MlsError::from)?.len(),
+            welcome_size: bundle
+                .welcome
+                .map(|w| w.tls_serialize_detached().map_err(MlsError::from))
+                .transpose()?
+                .map(|w| w.len()),
+            group_info_size: bundle.group_info.payload.bytes().len(),
+            delta,
+        })
+    }
+}
+
+impl MlsConversation {
+    /// A copy of the conversation which can be changed without affecting this one, provided that it is used with
+    /// another keystore
+    fn copy(&self) -> CryptoResult<Self> {
+        let snapshot = self.snapshot()?;
+        let mut copy = Self::from_serialized_state(snapshot.state.to_vec(), self.parent_id.clone())?;
+        copy.configuration = self.configuration.clone();
+        Ok(copy)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn preview_should_describe_commit_without_changing_anything(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let alice_id = alice_central.mls_central.client_id().unwrap();
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+                    let charlie_id = charlie_central.mls_central.client_id().unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let entities = alice_central.mls_central.count_entities().await;
+
+                    let charlie = charlie_central.mls_central.get_one_key_package(&case).await;
+                    let preview = alice_central
+                        .mls_central
+                        .preview_commit(&id, vec![MlsProposal::Add(charlie)])
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        preview.members,
+                        vec![alice_id.clone(), bob_id.clone(), charlie_id.clone()]
+                    );
+                    assert_eq!(preview.epoch, epoch + 1);
+                    assert_eq!(preview.delta.added, vec![charlie_id.clone()]);
+                    assert!(preview.commit_size > 0);
+                    assert!(preview.welcome_size.unwrap() > 0);
+                    assert!(preview.group_info_size > 0);
+
+                    // nothing changed
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                    assert_eq!(alice_central.mls_central.count_entities().await, entities);
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+                    let conversation = alice_central.mls_central.get_conversation_unchecked(&id).await;
+                    assert_eq!(conversation.group.pending_proposals().count(), 0);
+                    drop(conversation);
+
+                    // the actual commit has about the same size
+                    let charlie = charlie_central.mls_central.get_one_key_package(&case).await;
+                    let preview = alice_central
+                        .mls_central
+                        .preview_commit(&id, vec![MlsProposal::Add(charlie.clone())])
+                        .await
+                        .unwrap();
+                    let (_, commit, ..) = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![charlie.into()])
+                        .await
+                        .unwrap()
+                        .to_bytes()
+                        .unwrap();
+                    assert!(commit.len().abs_diff(preview.commit_size) < 64);
+                    alice_central.mls_central.clear_pending_commit(&id).await.unwrap();
+
+                    // mixed intents
+                    let charlie = charlie_central.mls_central.get_one_key_package(&case).await;
+                    let preview = alice_central
+                        .mls_central
+                        .preview_commit(
+                            &id,
+                            vec![MlsProposal::Add(charlie), MlsProposal::Remove(bob_id.clone())],
+                        )
+                        .await
+                        .unwrap();
+                    assert_eq!(preview.members, vec![alice_id.clone(), charlie_id.clone()]);
+                    assert_eq!(preview.delta.removed, vec![bob_id.clone()]);
+                    assert!(preview.delta.self_updated);
+
+                    let error = alice_central
+                        .mls_central
+                        .preview_commit(&id, vec![MlsProposal::Remove(charlie_id)])
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::ClientNotFound(_)));
+                })
+            },
+        )
+        .await
+    }
+}
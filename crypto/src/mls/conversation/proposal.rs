@@ -5,7 +5,13 @@
 //! | 0 pend. Proposal       | ✅              | ❌              |
 //! | 1+ pend. Proposal      | ✅              | ❌              |
 
-use openmls::{binary_tree::LeafNodeIndex, framing::MlsMessageOut, key_packages::KeyPackageIn, prelude::LeafNode};
+use openmls::{
+    binary_tree::LeafNodeIndex,
+    framing::MlsMessageOut,
+    key_packages::KeyPackageIn,
+    prelude::{ExternalPsk, LeafNode, PreSharedKeyId, Psk},
+};
+use openmls_traits::OpenMlsCryptoProvider;
 
 use mls_crypto_provider::MlsCryptoProvider;
 
@@ -71,6 +77,33 @@ impl MlsConversation {
         Ok(proposal)
     }
 
+    /// see [openmls::group::MlsGroup::propose_external_psk]
+    #[cfg_attr(test, crate::durable)]
+    pub async fn propose_external_psk(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        psk_id: Vec<u8>,
+    ) -> CryptoResult<MlsProposalBundle> {
+        let signer = &self
+            .find_current_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?
+            .signature_key;
+        let psk = PreSharedKeyId::new(
+            self.ciphersuite().0,
+            backend.rand(),
+            Psk::External(ExternalPsk::new(psk_id)),
+        )
+        .map_err(MlsError::from)?;
+        let proposal = self
+            .group
+            .propose_external_psk(backend, signer, psk)
+            .map_err(MlsError::from)
+            .map(MlsProposalBundle::from)?;
+        self.persist_group_when_changed(backend, false).await?;
+        Ok(proposal)
+    }
+
     /// see [openmls::group::MlsGroup::propose_self_update]
     #[cfg_attr(test, crate::durable)]
     pub async fn propose_self_update(
@@ -185,14 +218,21 @@ pub mod tests {
                             .unwrap();
                         let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
 
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         let proposal = alice_central
                             .mls_central
                             .new_add_proposal(&id, charlie_kp)
                             .await
                             .unwrap()
                             .proposal;
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         bob_central
                             .mls_central
                             .decrypt_message(&id, proposal.to_bytes().unwrap())
@@ -285,14 +325,21 @@ pub mod tests {
                             .await
                             .unwrap();
 
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         let proposal = alice_central
                             .mls_central
                             .new_remove_proposal(&id, charlie_central.mls_central.get_client_id())
                             .await
                             .unwrap()
                             .proposal;
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         bob_central
                             .mls_central
                             .decrypt_message(&id, proposal.to_bytes().unwrap())
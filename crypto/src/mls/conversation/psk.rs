@@ -0,0 +1,183 @@
+//! External pre-shared keys let the members of a conversation bind a secret they share out of band to the epoch
+//! secrets: once a commit injects it, only the clients knowing it can follow the conversation. The secret is added
+//! with [MlsCentral::add_external_psk] by every member, then injected by a PreSharedKey proposal, see
+//! [MlsCentral::new_external_psk_proposal]. A member lacking the secret fails to process the commit injecting it.
+//!
+//! The secrets are kept in the keystore, which openmls looks them up in when creating or processing the commit.
+
+use openmls::prelude::{ExternalPsk, PreSharedKeyId, Psk};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{ConversationId, CryptoResult, MlsCentral, MlsError};
+
+impl MlsCentral {
+    /// Adds the external pre-shared key `psk_id` to the keystore, so that a commit of the conversation can inject it.
+    /// Every member has to add it before processing such a commit.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id, whose ciphersuite the secret is used with
+    /// * `psk_id` - identifier of the key, agreed upon by the members
+    /// * `secret` - the key itself
+    ///
+    /// # Errors
+    /// If the conversation can't be found or the keystore fails to persist the key
+    pub async fn add_external_psk(
+        &mut self,
+        id: &ConversationId,
+        psk_id: Vec<u8>,
+        secret: Vec<u8>,
+    ) -> CryptoResult<()> {
+        let ciphersuite = self.get_conversation(id).await?.read().await.ciphersuite();
+        let psk = PreSharedKeyId::new(
+            ciphersuite.0,
+            self.mls_backend.rand(),
+            Psk::External(ExternalPsk::new(psk_id)),
+        )
+        .map_err(MlsError::from)?;
+        psk.write_to_key_store(&self.mls_backend, ciphersuite.0, &secret)
+            .await
+            .map_err(MlsError::from)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_inject_external_psk(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let psk_id = b"shared-out-of-band".to_vec();
+                    let secret = alice_central.mls_central.random_bytes(32).unwrap();
+                    for central in [&mut alice_central.mls_central, &mut bob_central.mls_central] {
+                        central
+                            .add_external_psk(&id, psk_id.clone(), secret.clone())
+                            .await
+                            .unwrap();
+                    }
+
+                    let proposal = alice_central
+                        .mls_central
+                        .new_external_psk_proposal(&id, psk_id)
+                        .await
+                        .unwrap()
+                        .proposal;
+                    let pending = alice_central.mls_central.pending_proposals(&id).await.unwrap();
+                    assert_eq!(pending.len(), 1);
+                    assert_eq!(pending[0].proposal_type, MlsProposalType::PreSharedKey);
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, proposal.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_fail_processing_commit_when_psk_is_unknown(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // only alice knows the secret
+                    let psk_id = b"shared-out-of-band".to_vec();
+                    let secret = alice_central.mls_central.random_bytes(32).unwrap();
+                    alice_central
+                        .mls_central
+                        .add_external_psk(&id, psk_id.clone(), secret)
+                        .await
+                        .unwrap();
+                    let proposal = alice_central
+                        .mls_central
+                        .new_external_psk_proposal(&id, psk_id)
+                        .await
+                        .unwrap()
+                        .proposal;
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, proposal.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .commit;
+
+                    let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .is_err());
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,183 @@
+//! Application messages are encrypted with keys derived from a ratchet per sender and per epoch, each key having a
+//! generation (RFC 9420 §9.1). A member can only decrypt a message whose generation is at most
+//! [MlsCustomConfiguration::maximum_forward_distance] ahead, or [MlsCustomConfiguration::out_of_order_tolerance]
+//! behind, the latest one it received from that sender. [MlsCentral::ratchet_diagnostics] tells how close a
+//! conversation is to these limits so that the user can be warned before messages start failing, or a commit made to
+//! start a new epoch, hence new ratchets.
+//!
+//! openmls keeps its secret tree to itself, so the generations are counted by CoreCrypto. Only the ones of this client
+//! are known: the generation of an incoming message is part of its encrypted sender data.
+//!
+//! [MlsCustomConfiguration::maximum_forward_distance]: crate::prelude::MlsCustomConfiguration::maximum_forward_distance
+//! [MlsCustomConfiguration::out_of_order_tolerance]: crate::prelude::MlsCustomConfiguration::out_of_order_tolerance
+
+use core_crypto_keystore::entities::MlsEpochRecord;
+
+use super::{config::MAX_PAST_EPOCHS, epoch_history::epoch_record_id, MlsConversation};
+use crate::prelude::{ConversationId, CryptoResult, MlsCentral};
+
+/// Ratchet bookkeeping of a conversation in its current epoch, see [MlsCentral::ratchet_diagnostics]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlsRatchetDiagnostics {
+    /// Current epoch of the conversation
+    pub epoch: u64,
+    /// Generation of the next application message this client encrypts, i.e. how many it encrypted in the epoch.
+    /// Only counted while the conversation is loaded, hence a lower bound after a restart
+    pub own_generation: u32,
+    /// How many messages of a sender, older than the latest one received from it in the epoch, can still be decrypted
+    pub out_of_order_tolerance: u32,
+    /// How many messages of a sender can be skipped in the epoch and the next one still be decrypted
+    pub maximum_forward_distance: u32,
+    /// Past epochs whose secrets are kept to decrypt the messages arriving late. At most [MAX_PAST_EPOCHS], fewer when
+    /// this client entered the conversation recently: only the epochs in its epoch history are counted
+    pub retained_past_epochs: u32,
+}
+
+impl MlsRatchetDiagnostics {
+    /// How many more application messages this client can encrypt in the epoch before a member who missed them all
+    /// can no longer decrypt the next one, assuming it has the same [Self::maximum_forward_distance]
+    pub fn forward_distance_remaining(&self) -> u32 {
+        self.maximum_forward_distance.saturating_sub(self.own_generation)
+    }
+}
+
+/// Application messages encrypted by this client in an epoch
+#[derive(Debug, Default)]
+pub(crate) struct OwnGeneration {
+    epoch: u64,
+    generation: u32,
+}
+
+impl OwnGeneration {
+    fn get(&self, epoch: u64) -> u32 {
+        if self.epoch == epoch {
+            self.generation
+        } else {
+            0
+        }
+    }
+}
+
+impl MlsConversation {
+    /// Counts an application message encrypted in the current epoch
+    pub(crate) fn increment_own_generation(&mut self) {
+        let epoch = self.group.epoch().as_u64();
+        self.own_generation = OwnGeneration {
+            epoch,
+            generation: self.own_generation.get(epoch).saturating_add(1),
+        };
+    }
+}
+
+impl MlsCentral {
+    /// Describes the ratchets of a conversation in its current epoch, see [the module documentation](self). Neither
+    /// the conversation nor the keystore are changed.
+    ///
+    /// # Arguments
+    /// * `id` - id of the group/conversation
+    ///
+    /// # Errors
+    /// If the conversation can't be found or KeyStore errors
+    pub async fn ratchet_diagnostics(&mut self, id: &ConversationId) -> CryptoResult<MlsRatchetDiagnostics> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let epoch = conversation.group.epoch().as_u64();
+        let ratchet_configuration = conversation.group.configuration().sender_ratchet_configuration();
+        let diagnostics = MlsRatchetDiagnostics {
+            epoch,
+            own_generation: conversation.own_generation.get(epoch),
+            out_of_order_tolerance: ratchet_configuration.out_of_order_tolerance(),
+            maximum_forward_distance: ratchet_configuration.maximum_forward_distance(),
+            retained_past_epochs: 0,
+        };
+        drop(conversation);
+
+        let past_epochs = (epoch.saturating_sub(MAX_PAST_EPOCHS as u64)..epoch)
+            .map(|epoch| epoch_record_id(id, epoch))
+            .collect::<Vec<_>>();
+        let retained_past_epochs = self
+            .mls_backend
+            .key_store()
+            .find_many::<MlsEpochRecord, _>(&past_epochs)
+            .await?
+            .len();
+
+        Ok(MlsRatchetDiagnostics {
+            retained_past_epochs: retained_past_epochs as u32,
+            ..diagnostics
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{mls::conversation::config::MAX_PAST_EPOCHS, prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_describe_ratchets(mut case: TestCase) {
+        case.cfg.custom.maximum_forward_distance = 10;
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let diagnostics = alice_central.mls_central.ratchet_diagnostics(&id).await.unwrap();
+                    assert_eq!(diagnostics.epoch, 1);
+                    assert_eq!(diagnostics.own_generation, 0);
+                    assert_eq!(diagnostics.maximum_forward_distance, 10);
+                    assert_eq!(
+                        diagnostics.out_of_order_tolerance,
+                        case.cfg.custom.out_of_order_tolerance
+                    );
+                    assert_eq!(diagnostics.retained_past_epochs, 1);
+                    // bob was not there in the epoch before
+                    let bob_diagnostics = bob_central.mls_central.ratchet_diagnostics(&id).await.unwrap();
+                    assert_eq!(bob_diagnostics.retained_past_epochs, 0);
+
+                    for _ in 0..3 {
+                        alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                    }
+                    let diagnostics = alice_central.mls_central.ratchet_diagnostics(&id).await.unwrap();
+                    assert_eq!(diagnostics.own_generation, 3);
+                    assert_eq!(diagnostics.forward_distance_remaining(), 7);
+
+                    // a new epoch comes with new ratchets
+                    for _ in 0..MAX_PAST_EPOCHS {
+                        alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    }
+                    let diagnostics = alice_central.mls_central.ratchet_diagnostics(&id).await.unwrap();
+                    assert_eq!(diagnostics.epoch, 1 + MAX_PAST_EPOCHS as u64);
+                    assert_eq!(diagnostics.own_generation, 0);
+                    assert_eq!(diagnostics.forward_distance_remaining(), 10);
+                    assert_eq!(diagnostics.retained_past_epochs, MAX_PAST_EPOCHS as u32);
+
+                    let error = alice_central
+                        .mls_central
+                        .ratchet_diagnostics(&conversation_id())
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::ConversationNotFound(_)));
+                })
+            },
+        )
+        .await
+    }
+}
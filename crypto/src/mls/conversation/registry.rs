@@ -0,0 +1,134 @@
+//! Restoring a conversation requires deserializing its whole group state. To be able to enumerate the stored
+//! conversations cheaply, some metadata about each of them is indexed in the keystore every time a group is persisted.
+
+use core_crypto_keystore::{entities::MlsGroupIndexEntry, CryptoKeystoreMls};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::{
+    mls::client::key_package::unix_now,
+    prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsConversation},
+};
+
+/// Metadata of a persisted conversation, see [MlsCentral::list_conversations]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsConversationSummary {
+    /// Conversation id
+    pub id: ConversationId,
+    /// Ciphersuite of the conversation
+    pub ciphersuite: MlsCiphersuite,
+    /// Epoch of the conversation when it was last persisted
+    pub epoch: u64,
+    /// Number of members of the conversation when it was last persisted
+    pub member_count: u32,
+    /// Unix timestamp (in seconds) of the last time the conversation was persisted
+    pub last_persisted: u64,
+    /// Id of the parent conversation, for a sub-conversation
+    pub parent_id: Option<ConversationId>,
+}
+
+impl TryFrom<&MlsGroupIndexEntry> for MlsConversationSummary {
+    type Error = CryptoError;
+
+    fn try_from(entry: &MlsGroupIndexEntry) -> CryptoResult<Self> {
+        Ok(Self {
+            id: entry.id.clone(),
+            ciphersuite: entry.ciphersuite.try_into()?,
+            epoch: entry.epoch,
+            member_count: entry.member_count,
+            last_persisted: entry.last_persisted,
+            parent_id: entry.parent_id.clone(),
+        })
+    }
+}
+
+impl MlsCentral {
+    /// Lists every persisted conversation along with some metadata, without restoring them. This is meant for
+    /// diagnostics or for displaying them.
+    ///
+    /// # Errors
+    /// KeyStore errors
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn list_conversations(&self) -> CryptoResult<Vec<MlsConversationSummary>> {
+        self.mls_backend
+            .key_store()
+            .mls_groups_index()
+            .await?
+            .iter()
+            .map(MlsConversationSummary::try_from)
+            .collect()
+    }
+}
+
+impl MlsConversation {
+    /// Indexes the current metadata of the conversation. Has to be called every time the group is persisted.
+    pub(crate) async fn update_index(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        backend
+            .key_store()
+            .save(MlsGroupIndexEntry {
+                id: self.id().clone(),
+                ciphersuite: self.ciphersuite().into(),
+                epoch: self.group.epoch().as_u64(),
+                member_count: self.group.members().count() as u32,
+                last_persisted: unix_now()?.as_secs(),
+                parent_id: self.parent_id.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::test_utils::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_list_persisted_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    assert!(alice_central.mls_central.list_conversations().await.unwrap().is_empty());
+
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let conversations = alice_central.mls_central.list_conversations().await.unwrap();
+                    assert_eq!(conversations.len(), 1);
+                    assert_eq!(conversations[0].id, id);
+                    assert_eq!(conversations[0].ciphersuite, case.ciphersuite());
+                    assert_eq!(conversations[0].epoch, 0);
+                    assert_eq!(conversations[0].member_count, 1);
+
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let conversations = alice_central.mls_central.list_conversations().await.unwrap();
+                    assert_eq!(conversations[0].epoch, 1);
+                    assert_eq!(conversations[0].member_count, 2);
+
+                    // also available to the ones who joined
+                    let conversations = bob_central.mls_central.list_conversations().await.unwrap();
+                    assert_eq!(conversations.len(), 1);
+                    assert_eq!(conversations[0].member_count, 2);
+
+                    alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    assert!(alice_central.mls_central.list_conversations().await.unwrap().is_empty());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -1,5 +1,7 @@
 use core_crypto_keystore::entities::MlsEncryptionKeyPair;
-use openmls::prelude::{LeafNode, LeafNodeIndex, Proposal, QueuedProposal, Sender, StagedCommit};
+use openmls::prelude::{
+    LeafNode, LeafNodeIndex, PreSharedKeyProposal, Proposal, Psk, QueuedProposal, Sender, StagedCommit,
+};
 use openmls_traits::OpenMlsCryptoProvider;
 
 use mls_crypto_provider::MlsCryptoProvider;
@@ -78,6 +80,11 @@ impl Renew {
                 Proposal::Update(ref update) => commit
                     .update_proposals()
                     .any(|p| p.update_proposal().leaf_node() == update.leaf_node()),
+                // the same key injected by another member does the job, whatever the nonce
+                Proposal::PreSharedKey(ref psk) => commit.queued_proposals().any(|p| match p.proposal() {
+                    Proposal::PreSharedKey(committed) => Self::external_psk_id(committed) == Self::external_psk_id(psk),
+                    _ => false,
+                }),
                 _ => true,
             };
             if in_commit {
@@ -90,6 +97,14 @@ impl Renew {
             Some(proposal)
         }
     }
+
+    /// Identifier of the injected key when it is an external one, the only kind of pre-shared key core-crypto proposes
+    pub(crate) fn external_psk_id(proposal: &PreSharedKeyProposal) -> Option<Vec<u8>> {
+        match proposal.clone().into_psk_id().psk() {
+            Psk::External(psk) => Some(psk.psk_id().to_vec()),
+            _ => None,
+        }
+    }
 }
 
 impl MlsConversation {
@@ -110,6 +125,10 @@ impl MlsConversation {
                 Proposal::Add(add) => self.propose_add_member(client, backend, add.key_package.into()).await?,
                 Proposal::Remove(remove) => self.propose_remove_member(client, backend, remove.removed()).await?,
                 Proposal::Update(update) => self.renew_update(client, backend, Some(update.leaf_node())).await?,
+                Proposal::PreSharedKey(psk) => {
+                    let psk_id = Renew::external_psk_id(&psk).ok_or(CryptoError::ImplementationError)?;
+                    self.propose_external_psk(client, backend, psk_id).await?
+                }
                 _ => return Err(CryptoError::ImplementationError),
             };
             bundle.push(msg);
@@ -192,9 +211,16 @@ pub mod tests {
                             .await
                             .unwrap();
 
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central.mls_central.new_update_proposal(&id).await.unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // Bob hasn't Alice's proposal but creates a commit
                         let commit = bob_central
@@ -212,10 +238,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice should renew the proposal because its hers
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
 
                         // It should also renew the proposal when in pending_commit
@@ -235,10 +264,13 @@ pub mod tests {
                             .proposals;
                         // Alice should renew the proposal because its hers
                         // It should also replace existing one
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -284,10 +316,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice should renew the proposal because its her's
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -315,14 +350,21 @@ pub mod tests {
                             .await
                             .unwrap();
 
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         let proposal = alice_central
                             .mls_central
                             .new_update_proposal(&id)
                             .await
                             .unwrap()
                             .proposal;
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // Bob has Alice's update proposal
                         bob_central
@@ -347,10 +389,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice proposal should not be renew as it was in valid commit
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
 
                         // Same if proposal is also in pending commit
@@ -361,7 +407,10 @@ pub mod tests {
                             .unwrap()
                             .proposal;
                         alice_central.mls_central.commit_pending_proposals(&id).await.unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_some());
                         bob_central
                             .mls_central
@@ -381,10 +430,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice should not be renew as it was in valid commit
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -417,13 +470,20 @@ pub mod tests {
                             .unwrap();
 
                         let proposal = bob_central.mls_central.new_update_proposal(&id).await.unwrap().proposal;
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .decrypt_message(&id, proposal.to_bytes().unwrap())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // Charlie does not have other proposals, it creates a commit
                         let commit = charlie_central
@@ -439,10 +499,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice should not renew Bob's update proposal
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -475,13 +539,20 @@ pub mod tests {
                             .unwrap();
 
                         let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .new_add_proposal(&id, charlie_kp)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         let charlie = charlie_central.mls_central.rand_key_package(&case).await;
                         let commit = bob_central
@@ -497,10 +568,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice proposal is not renewed since she also wanted to add Charlie
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -529,13 +604,20 @@ pub mod tests {
                             .unwrap();
 
                         let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .new_add_proposal(&id, charlie_kp)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // Here Alice also creates a commit
                         alice_central.mls_central.commit_pending_proposals(&id).await.unwrap();
@@ -555,10 +637,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Alice proposal is not renewed since she also wanted to add Charlie
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -603,7 +689,10 @@ pub mod tests {
                             .decrypt_message(&id, proposal.to_bytes().unwrap())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // But Charlie will commit meanwhile
                         let commit = charlie_central
@@ -619,10 +708,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // which Alice should not renew since it's not hers
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -652,13 +745,20 @@ pub mod tests {
 
                         // Alice proposes adding Charlie
                         let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .new_add_proposal(&id, charlie_kp)
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // But meanwhile Bob will create a commit without Alice's proposal
                         let commit = bob_central
@@ -675,10 +775,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // So Alice proposal should be renewed
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
 
                         // And same should happen when proposal is in pending commit
@@ -698,10 +801,13 @@ pub mod tests {
                             .proposals;
                         // So Alice proposal should also be renewed
                         // It should also replace existing one
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -752,10 +858,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // So Alice proposal should be renewed
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -791,13 +900,20 @@ pub mod tests {
                             .await
                             .unwrap();
 
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .new_remove_proposal(&id, charlie_central.mls_central.get_client_id())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         let commit = bob_central
                             .mls_central
@@ -812,10 +928,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Remove proposal is not renewed since commit does same
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -853,13 +973,20 @@ pub mod tests {
                             .await
                             .unwrap()
                             .proposal;
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .decrypt_message(&id, proposal.to_bytes().unwrap())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         let commit = charlie_central
                             .mls_central
@@ -874,10 +1001,14 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Remove proposal is not renewed since by ref
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -914,13 +1045,20 @@ pub mod tests {
                             .unwrap();
 
                         // Alice wants to remove Charlie
-                        assert!(alice_central.mls_central.pending_proposals(&id).await.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
                         alice_central
                             .mls_central
                             .new_remove_proposal(&id, charlie_central.mls_central.get_client_id())
                             .await
                             .unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
 
                         // Whereas Bob wants to remove Debbie
                         let commit = bob_central
@@ -936,10 +1074,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Remove is renewed since valid commit removes another
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -997,10 +1138,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Remove is renewed since valid commit removes another
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -1043,7 +1187,10 @@ pub mod tests {
                             .await
                             .unwrap();
                         alice_central.mls_central.commit_pending_proposals(&id).await.unwrap();
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert!(alice_central.mls_central.pending_commit(&id).await.is_some());
 
                         // Whereas Bob wants to remove Debbie
@@ -1060,10 +1207,13 @@ pub mod tests {
                             .unwrap()
                             .proposals;
                         // Remove is renewed since valid commit removes another
-                        assert_eq!(alice_central.mls_central.pending_proposals(&id).await.len(), 1);
+                        assert_eq!(
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len(),
+                            1
+                        );
                         assert_eq!(
                             proposals.len(),
-                            alice_central.mls_central.pending_proposals(&id).await.len()
+                            alice_central.mls_central.pending_proposals_unchecked(&id).await.len()
                         );
                     })
                 },
@@ -1071,4 +1221,155 @@ pub mod tests {
             .await
         }
     }
+
+    mod psk {
+        use super::*;
+        use crate::prelude::MlsProposalType;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn renewable_when_valid_commit_doesnt_inject_same(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let psk_id = b"shared-out-of-band".to_vec();
+                        let secret = alice_central.mls_central.random_bytes(32).unwrap();
+                        for central in [&mut alice_central.mls_central, &mut bob_central.mls_central] {
+                            central
+                                .add_external_psk(&id, psk_id.clone(), secret.clone())
+                                .await
+                                .unwrap();
+                        }
+
+                        // Alice wants to inject the key but Bob commits before receiving her proposal
+                        alice_central
+                            .mls_central
+                            .new_external_psk_proposal(&id, psk_id)
+                            .await
+                            .unwrap();
+                        let commit = bob_central
+                            .mls_central
+                            .update_keying_material(&id)
+                            .await
+                            .unwrap()
+                            .commit;
+                        bob_central.mls_central.commit_accepted(&id).await.unwrap();
+                        let proposals = alice_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap()
+                            .proposals;
+                        assert_eq!(proposals.len(), 1);
+                        let pending = alice_central.mls_central.pending_proposals(&id).await.unwrap();
+                        assert_eq!(pending.len(), 1);
+                        assert_eq!(pending[0].proposal_type, MlsProposalType::PreSharedKey);
+
+                        // the renewed proposal belongs to the current epoch hence it can be committed
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, proposals[0].proposal.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        let commit = alice_central
+                            .mls_central
+                            .commit_pending_proposals(&id)
+                            .await
+                            .unwrap()
+                            .unwrap()
+                            .commit;
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert!(alice_central
+                            .mls_central
+                            .try_talk_to(&id, &mut bob_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn not_renewable_when_valid_commit_injects_same(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let psk_id = b"shared-out-of-band".to_vec();
+                        let secret = alice_central.mls_central.random_bytes(32).unwrap();
+                        for central in [&mut alice_central.mls_central, &mut bob_central.mls_central] {
+                            central
+                                .add_external_psk(&id, psk_id.clone(), secret.clone())
+                                .await
+                                .unwrap();
+                        }
+
+                        // both want to inject the same key, Bob's commit wins
+                        alice_central
+                            .mls_central
+                            .new_external_psk_proposal(&id, psk_id.clone())
+                            .await
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .new_external_psk_proposal(&id, psk_id)
+                            .await
+                            .unwrap();
+                        let commit = bob_central
+                            .mls_central
+                            .commit_pending_proposals(&id)
+                            .await
+                            .unwrap()
+                            .unwrap()
+                            .commit;
+                        let proposals = alice_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap()
+                            .proposals;
+                        assert!(proposals.is_empty());
+                        assert!(alice_central
+                            .mls_central
+                            .pending_proposals_unchecked(&id)
+                            .await
+                            .is_empty());
+                    })
+                },
+            )
+            .await
+        }
+    }
 }
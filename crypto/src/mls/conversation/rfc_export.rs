@@ -0,0 +1,156 @@
+//! Export of the public state of a conversation as the structures defined in
+//! [RFC 9420](https://www.rfc-editor.org/rfc/rfc9420.html), so that it can be fed into third-party MLS analyzers
+//! e.g. during an audit. Only what the Delivery Service could see anyway is exported: the GroupContext, the ratchet
+//! tree and a GroupInfo signed by this client. No secret of the group nor of the client is part of it.
+//!
+//! The structures are TLS serialized and wrapped in a PEM file, one block per structure, which most tooling can read:
+//! * `MLS GROUP CONTEXT` - the `GroupContext` of the current epoch (RFC 9420 §8.1)
+//! * `MLS RATCHET TREE` - the `ratchet_tree` as in the extension of the same name (RFC 9420 §12.4.3.3)
+//! * `MLS GROUP INFO` - a `MLSMessage` wrapping the `GroupInfo` of the current epoch (RFC 9420 §12.4.3)
+//!
+//! Only available with the `rfc-export` feature.
+
+use tls_codec::Serialize;
+
+use crate::prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError};
+
+/// PEM tag of the TLS serialized `GroupContext`
+pub const GROUP_CONTEXT_PEM_TAG: &str = "MLS GROUP CONTEXT";
+/// PEM tag of the TLS serialized ratchet tree
+pub const RATCHET_TREE_PEM_TAG: &str = "MLS RATCHET TREE";
+/// PEM tag of the TLS serialized `MLSMessage` wrapping the `GroupInfo`
+pub const GROUP_INFO_PEM_TAG: &str = "MLS GROUP INFO";
+
+/// Public state of a conversation as RFC 9420 structures, see [MlsCentral::export_rfc_group_state]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsRfcGroupState {
+    /// TLS serialized `GroupContext` of the current epoch
+    pub group_context: Vec<u8>,
+    /// TLS serialized ratchet tree of the current epoch
+    pub ratchet_tree: Vec<u8>,
+    /// TLS serialized `MLSMessage` wrapping a `GroupInfo` of the current epoch, without the ratchet tree extension
+    pub group_info: Vec<u8>,
+}
+
+impl MlsRfcGroupState {
+    /// Content of the portable file, see [the module documentation](self)
+    pub fn to_pem(&self) -> String {
+        pem::encode_many(&[
+            pem::Pem::new(GROUP_CONTEXT_PEM_TAG, self.group_context.clone()),
+            pem::Pem::new(RATCHET_TREE_PEM_TAG, self.ratchet_tree.clone()),
+            pem::Pem::new(GROUP_INFO_PEM_TAG, self.group_info.clone()),
+        ])
+    }
+}
+
+impl MlsCentral {
+    /// Exports the public state of a conversation at its current epoch as RFC 9420 structures, to be analyzed by
+    /// third-party tooling. Neither the conversation nor the keystore are changed.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// If the conversation can't be found or the own credential of the conversation is gone, plus TLS serialization
+    /// errors
+    pub async fn export_rfc_group_state(&mut self, id: &ConversationId) -> CryptoResult<MlsRfcGroupState> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let client = self.mls_client()?;
+        let cb = conversation
+            .find_current_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?;
+
+        let group = &conversation.group;
+        let group_context = group
+            .export_group_context()
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?;
+        let ratchet_tree = group
+            .export_ratchet_tree()
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?;
+        // the ratchet tree already has its own block
+        let group_info = group
+            .export_group_info(&self.mls_backend, &cb.signature_key, false)
+            .map_err(MlsError::from)?
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?;
+
+        Ok(MlsRfcGroupState {
+            group_context,
+            ratchet_tree,
+            group_info,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use openmls::prelude::{GroupContext, MlsMessageIn, MlsMessageInBody, RatchetTreeIn};
+    use tls_codec::Deserialize;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_export_group_state_as_rfc_structures(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let entities = alice_central.mls_central.count_entities().await;
+
+                    let state = alice_central.mls_central.export_rfc_group_state(&id).await.unwrap();
+                    assert_eq!(alice_central.mls_central.count_entities().await, entities);
+
+                    let blocks = pem::parse_many(state.to_pem()).unwrap();
+                    let tags = blocks.iter().map(|b| b.tag()).collect::<Vec<_>>();
+                    assert_eq!(tags, [GROUP_CONTEXT_PEM_TAG, RATCHET_TREE_PEM_TAG, GROUP_INFO_PEM_TAG]);
+
+                    let group_context = GroupContext::tls_deserialize(&mut blocks[0].contents()).unwrap();
+                    assert_eq!(group_context.group_id().as_slice(), id.as_slice());
+                    assert_eq!(
+                        group_context.epoch().as_u64(),
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+
+                    // enough for another client to join, as an analyzer would need
+                    let ratchet_tree = RatchetTreeIn::tls_deserialize(&mut blocks[1].contents()).unwrap();
+                    let group_info = match MlsMessageIn::tls_deserialize(&mut blocks[2].contents())
+                        .unwrap()
+                        .extract()
+                    {
+                        MlsMessageInBody::GroupInfo(group_info) => group_info,
+                        _ => panic!("This payload should contain a GroupInfo"),
+                    };
+                    assert_eq!(group_info.group_id().as_slice(), id.as_slice());
+                    bob_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .join_from_group_info(group_info, ratchet_tree, case.custom_cfg(), case.credential_type)
+                        .await
+                        .unwrap();
+                })
+            },
+        )
+        .await
+    }
+}
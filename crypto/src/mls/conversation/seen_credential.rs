@@ -0,0 +1,246 @@
+//! Every credential presented in a conversation is recorded along with the time it was first seen, so that identity
+//! changes can be audited later on, for example against a key transparency log. An optional
+//! [MlsCredentialObserver] is notified whenever a credential shows up for the first time, including the own one when
+//! creating or joining a conversation.
+
+use std::collections::HashSet;
+
+use core_crypto_keystore::entities::MlsSeenCredential;
+use openmls::prelude::TlsSerializeTrait as _;
+use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+
+use crate::{
+    buffer_pool::PooledBuffer,
+    mls::client::key_package::unix_now,
+    prelude::{ClientId, ConversationId, CryptoResult, MlsCentral, MlsError},
+};
+
+/// A credential seen in a conversation, see [MlsCentral::seen_credentials]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsCredentialFirstSeen {
+    /// Id of the client presenting the credential
+    pub client_id: ClientId,
+    /// TLS-serialized credential
+    pub credential: Vec<u8>,
+    /// Unix timestamp (in seconds) of the first time the credential was seen in the conversation
+    pub first_seen: u64,
+}
+
+/// Notified whenever a credential is seen for the first time in a conversation, see [MlsCentral::credential_observer]
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait MlsCredentialObserver: std::fmt::Debug + Send + Sync {
+    /// Called once the credential has been recorded
+    ///
+    /// # Arguments
+    /// * `conversation_id` - id of the group/conversation
+    /// * `client_id` - id of the client presenting the credential
+    /// * `credential` - TLS-serialized credential
+    async fn on_new_credential(&self, conversation_id: ConversationId, client_id: ClientId, credential: Vec<u8>);
+}
+
+impl MlsCentral {
+    /// Sets the observer notified whenever a credential is seen for the first time in a conversation
+    pub fn credential_observer(&mut self, observer: Box<dyn MlsCredentialObserver>) {
+        self.credential_observer = Some(observer);
+    }
+
+    /// Every credential seen in a conversation along with the time it was first seen, oldest first. Records are kept
+    /// after a member left or changed its credential and removed along with the conversation
+    ///
+    /// # Errors
+    /// KeyStore errors
+    pub async fn seen_credentials(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> CryptoResult<Vec<MlsCredentialFirstSeen>> {
+        let mut seen = self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsSeenCredential>(Default::default())
+            .await?
+            .iter()
+            .filter(|s| &s.conversation_id == conversation_id)
+            .map(|s| MlsCredentialFirstSeen {
+                client_id: s.client_id.as_slice().into(),
+                credential: s.credential.clone(),
+                first_seen: s.first_seen,
+            })
+            .collect::<Vec<_>>();
+        seen.sort_by_key(|s| s.first_seen);
+        Ok(seen)
+    }
+
+    /// Records the credentials of the members of a conversation which have never been seen in it, notifying the
+    /// observer of each of them
+    pub(crate) async fn record_new_credentials(&mut self, id: &ConversationId) -> CryptoResult<()> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let hash_algorithm = conversation.ciphersuite().hash_algorithm();
+        let mut members = vec![];
+        let mut preimage = PooledBuffer::take();
+        for member in conversation.group.members() {
+            let client_id = ClientId::from(member.credential.identity());
+            let credential = member.credential.tls_serialize_detached().map_err(MlsError::from)?;
+            // the lengths prevent distinct triples from hashing the same
+            preimage.clear();
+            for field in [id.as_slice(), client_id.as_slice(), credential.as_slice()] {
+                preimage.extend_from_slice(&(field.len() as u32).to_be_bytes());
+                preimage.extend_from_slice(field);
+            }
+            let hash = self
+                .mls_backend
+                .crypto()
+                .hash(hash_algorithm, &preimage)
+                .map_err(MlsError::from)?;
+            members.push((hash, client_id, credential));
+        }
+        drop(conversation);
+
+        let keystore = self.mls_backend.key_store();
+        let ids = members.iter().map(|(hash, ..)| hash.as_slice()).collect::<Vec<_>>();
+        let known = keystore
+            .find_many::<MlsSeenCredential, _>(&ids)
+            .await?
+            .iter()
+            .map(|s| s.id.clone())
+            .collect::<HashSet<_>>();
+
+        let now = unix_now()?.as_secs();
+        for (hash, client_id, credential) in members.into_iter().filter(|(hash, ..)| !known.contains(hash)) {
+            keystore
+                .save(MlsSeenCredential {
+                    id: hash,
+                    conversation_id: id.clone(),
+                    client_id: client_id.to_vec(),
+                    credential: credential.clone(),
+                    first_seen: now,
+                })
+                .await?;
+            if let Some(observer) = self.credential_observer.as_ref() {
+                observer.on_new_credential(id.clone(), client_id, credential).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex},
+    };
+
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Default, Clone)]
+    struct RecordingObserver(Arc<Mutex<Vec<(ConversationId, ClientId)>>>);
+
+    #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+    impl MlsCredentialObserver for RecordingObserver {
+        async fn on_new_credential(&self, conversation_id: ConversationId, client_id: ClientId, _: Vec<u8>) {
+            self.0.lock().unwrap().push((conversation_id, client_id));
+        }
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_record_credentials_the_first_time_they_are_seen(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let alice_id = alice_central.mls_central.get_client_id();
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    let charlie_id = charlie_central.mls_central.get_client_id();
+
+                    let alice_observer = RecordingObserver::default();
+                    alice_central
+                        .mls_central
+                        .credential_observer(Box::new(alice_observer.clone()));
+                    let bob_observer = RecordingObserver::default();
+                    bob_central
+                        .mls_central
+                        .credential_observer(Box::new(bob_observer.clone()));
+
+                    // the own credential is seen when creating the conversation
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    assert_eq!(*alice_observer.0.lock().unwrap(), vec![(id.clone(), alice_id.clone())]);
+
+                    // the joiner sees every member's credential
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        alice_observer.0.lock().unwrap().last(),
+                        Some(&(id.clone(), bob_id.clone()))
+                    );
+                    let seen_by_bob = bob_observer
+                        .0
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .map(|(_, c)| c.clone())
+                        .collect::<HashSet<_>>();
+                    assert_eq!(seen_by_bob, HashSet::from([alice_id.clone(), bob_id.clone()]));
+
+                    // existing members see the added ones when processing the commit, and only them
+                    let charlie = charlie_central.mls_central.rand_key_package(&case).await;
+                    let commit = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![charlie])
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(bob_observer.0.lock().unwrap().len(), 3);
+                    assert_eq!(
+                        bob_observer.0.lock().unwrap().last(),
+                        Some(&(id.clone(), charlie_id.clone()))
+                    );
+
+                    // an update commit does not change credentials
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert_eq!(alice_observer.0.lock().unwrap().len(), 3);
+
+                    let seen = alice_central.mls_central.seen_credentials(&id).await.unwrap();
+                    assert_eq!(
+                        seen.iter().map(|s| s.client_id.clone()).collect::<HashSet<_>>(),
+                        HashSet::from([alice_id.clone(), bob_id.clone(), charlie_id.clone()])
+                    );
+                    assert!(seen.iter().all(|s| s.first_seen > 0 && !s.credential.is_empty()));
+
+                    // records go away with the conversation
+                    alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .seen_credentials(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     mls::credential::ext::CredentialExt,
-    prelude::{CryptoError, CryptoResult, MlsConversation, MlsConversationDecryptMessage},
+    prelude::{CryptoError, CryptoResult, MlsCommitDelta, MlsConversation, MlsConversationDecryptMessage},
 };
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls::prelude::{ConfirmationTag, ContentType, FramedContentBodyIn, MlsMessageIn, MlsMessageInBody, Sender};
@@ -71,6 +71,10 @@ impl MlsConversation {
         &mut self,
         backend: &MlsCryptoProvider,
     ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let commit_delta = self
+            .group
+            .pending_commit()
+            .map(|commit| MlsCommitDelta::new(&self.group, commit, &Sender::Member(self.group.own_leaf_index())));
         self.commit_accepted(backend).await?;
 
         let own_leaf = self.group.own_leaf().ok_or(CryptoError::InternalMlsError)?;
@@ -86,6 +90,7 @@ impl MlsConversation {
             identity,
             buffered_messages: None,
             crl_new_distribution_points: None,
+            commit_delta,
         })
     }
 }
@@ -0,0 +1,119 @@
+//! Merging a commit moves the group to the next epoch in memory before it is persisted. Should the latter fail, for
+//! example because of a keystore write error, the in-memory group would be ahead of the stored one. To prevent that,
+//! the group is serialized before the merge and put back when anything goes wrong, so that the operation can simply be
+//! retried.
+
+use openmls::prelude::{GroupEpoch, MlsGroup};
+
+use crate::{buffer_pool::PooledBuffer, mls::MlsConversation, CryptoError, CryptoResult};
+
+/// Serialized state of a group captured before merging a commit
+#[derive(Debug)]
+pub(crate) struct GroupSnapshot {
+    epoch: GroupEpoch,
+    /// A snapshot is taken for every merged commit and dropped right after, hence the pooled buffer
+    state: PooledBuffer,
+}
+
+impl MlsConversation {
+    pub(crate) fn snapshot(&self) -> CryptoResult<GroupSnapshot> {
+        let mut state = PooledBuffer::take();
+        core_crypto_keystore::ser_into(&self.group, &mut state)?;
+        Ok(GroupSnapshot {
+            epoch: self.group.epoch(),
+            state,
+        })
+    }
+
+    /// Puts the group back as it was when `snapshot` was taken since `error` occurred. When the commit had already
+    /// been merged in memory, the error is wrapped in a [CryptoError::MergeRolledBack]
+    pub(crate) fn rollback(&mut self, snapshot: GroupSnapshot, error: CryptoError) -> CryptoError {
+        let merged = self.group.epoch() != snapshot.epoch;
+        match core_crypto_keystore::deser::<MlsGroup>(&snapshot.state) {
+            Ok(group) => self.group = group,
+            Err(e) => return e.into(),
+        }
+        if merged {
+            CryptoError::MergeRolledBack(Box::new(error))
+        } else {
+            error
+        }
+    }
+}
+
+// the keystore cannot be made read-only on IndexedDB
+#[cfg(all(test, not(target_family = "wasm")))]
+pub mod tests {
+    use crate::{prelude::*, test_utils::*};
+
+    #[apply(all_cred_cipher)]
+    pub async fn should_rollback_merge_when_it_cannot_be_persisted(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+
+                    // prevents any write to the keystore
+                    alice_central.mls_central.set_keystore_read_only(true).await;
+                    assert!(alice_central.mls_central.commit_accepted(&id).await.is_err());
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_some());
+
+                    // the merge can be retried once the keystore is writable again
+                    alice_central.mls_central.set_keystore_read_only(false).await;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert_eq!(
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+
+                    // same goes for an incoming commit
+                    bob_central.mls_central.set_keystore_read_only(true).await;
+                    assert!(bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .is_err());
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+
+                    bob_central.mls_central.set_keystore_read_only(false).await;
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,231 @@
+//! Analytics need to know how active a conversation is. Rather than having every platform count events on its side,
+//! CoreCrypto counts the messages and commits of a conversation where they happen. Collecting them is opt-in, see
+//! [MlsCentral::set_conversation_stats_enabled], and they are persisted in the keystore until the conversation is
+//! wiped.
+
+use core_crypto_keystore::entities::MlsConversationCounters;
+
+use crate::{
+    mls::client::key_package::unix_now,
+    prelude::{ConversationId, CryptoResult, MlsCentral},
+};
+
+/// Activity of a conversation since stats have been collected for it, see [MlsCentral::conversation_stats]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MlsConversationStats {
+    /// Application messages encrypted
+    pub messages_sent: u64,
+    /// Application messages decrypted
+    pub messages_received: u64,
+    /// Own commits merged, including joining by external commit
+    pub commits_sent: u64,
+    /// Incoming commits merged
+    pub commits_received: u64,
+    /// Epoch of the conversation when one of the above last happened, none when nothing has been counted yet
+    pub last_activity_epoch: Option<u64>,
+    /// Unix timestamp (in seconds) of when one of the above last happened, none when nothing has been counted yet
+    pub last_activity: Option<u64>,
+}
+
+impl From<MlsConversationCounters> for MlsConversationStats {
+    fn from(counters: MlsConversationCounters) -> Self {
+        Self {
+            messages_sent: counters.messages_sent,
+            messages_received: counters.messages_received,
+            commits_sent: counters.commits_sent,
+            commits_received: counters.commits_received,
+            last_activity_epoch: Some(counters.last_activity_epoch),
+            last_activity: Some(counters.last_activity),
+        }
+    }
+}
+
+/// What happened in a conversation, see [MlsCentral::record_conversation_activity]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MlsConversationActivity {
+    MessageSent,
+    MessageReceived,
+    CommitSent,
+    CommitReceived,
+}
+
+impl MlsCentral {
+    /// Starts or stops collecting the stats of every conversation. Disabled by default and not persisted, hence has
+    /// to be enabled again after a restart. Stats collected so far are kept when disabling
+    pub fn set_conversation_stats_enabled(&mut self, enabled: bool) {
+        self.conversation_stats_enabled = enabled;
+    }
+
+    /// Activity of a conversation, counted while [MlsCentral::set_conversation_stats_enabled] was on
+    ///
+    /// # Errors
+    /// If the conversation can't be found or KeyStore errors
+    pub async fn conversation_stats(&mut self, id: &ConversationId) -> CryptoResult<MlsConversationStats> {
+        // fails when the conversation does not exist, rather than reporting no activity
+        self.get_conversation(id).await?;
+        Ok(self
+            .mls_backend
+            .key_store()
+            .find::<MlsConversationCounters>(id)
+            .await?
+            .map(MlsConversationStats::from)
+            .unwrap_or_default())
+    }
+
+    /// Counts something which happened in a conversation, when collecting stats is enabled. It happened already, so
+    /// failing to count it is only reported to the logger
+    pub(crate) async fn record_conversation_activity(
+        &mut self,
+        id: &ConversationId,
+        activity: MlsConversationActivity,
+    ) {
+        if !self.conversation_stats_enabled {
+            return;
+        }
+        if let Err(e) = self.count_conversation_activity(id, activity).await {
+            crate::logger::warn(
+                "Could not count the activity of a conversation",
+                serde_json::json!({ "conversation_id": hex::encode(id), "error": e.to_string() }),
+            );
+        }
+    }
+
+    async fn count_conversation_activity(
+        &mut self,
+        id: &ConversationId,
+        activity: MlsConversationActivity,
+    ) -> CryptoResult<()> {
+        let epoch = self.get_conversation(id).await?.read().await.group.epoch().as_u64();
+
+        let keystore = self.mls_backend.key_store();
+        let mut counters =
+            keystore
+                .find::<MlsConversationCounters>(id)
+                .await?
+                .unwrap_or_else(|| MlsConversationCounters {
+                    id: id.clone(),
+                    messages_sent: 0,
+                    messages_received: 0,
+                    commits_sent: 0,
+                    commits_received: 0,
+                    last_activity_epoch: 0,
+                    last_activity: 0,
+                });
+        let counter = match activity {
+            MlsConversationActivity::MessageSent => &mut counters.messages_sent,
+            MlsConversationActivity::MessageReceived => &mut counters.messages_received,
+            MlsConversationActivity::CommitSent => &mut counters.commits_sent,
+            MlsConversationActivity::CommitReceived => &mut counters.commits_received,
+        };
+        *counter = counter.saturating_add(1);
+        counters.last_activity_epoch = epoch;
+        counters.last_activity = unix_now()?.as_secs();
+        keystore.save(counters).await?;
+        Ok(())
+    }
+
+    /// Removes the stats of a conversation
+    pub(crate) async fn wipe_conversation_stats(&self, id: &ConversationId) -> CryptoResult<()> {
+        let keystore = self.mls_backend.key_store();
+        if keystore.find::<MlsConversationCounters>(id).await?.is_some() {
+            keystore.remove::<MlsConversationCounters, _>(id).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::MlsConversationCounters;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_collect_conversation_stats(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // nothing is counted until opting in
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    bob_central.mls_central.decrypt_message(&id, msg).await.unwrap();
+                    assert_eq!(
+                        alice_central.mls_central.conversation_stats(&id).await.unwrap(),
+                        MlsConversationStats::default()
+                    );
+
+                    alice_central.mls_central.set_conversation_stats_enabled(true);
+                    bob_central.mls_central.set_conversation_stats_enabled(true);
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    for _ in 0..2 {
+                        let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                        bob_central.mls_central.decrypt_message(&id, msg).await.unwrap();
+                    }
+                    let msg = bob_central.mls_central.encrypt_message(&id, b"hi").await.unwrap();
+                    alice_central.mls_central.decrypt_message(&id, msg).await.unwrap();
+
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let alice = alice_central.mls_central.conversation_stats(&id).await.unwrap();
+                    assert_eq!(alice.messages_sent, 2);
+                    assert_eq!(alice.messages_received, 1);
+                    assert_eq!(alice.commits_sent, 1);
+                    assert_eq!(alice.commits_received, 0);
+                    assert_eq!(alice.last_activity_epoch, Some(epoch));
+                    assert!(alice.last_activity.is_some());
+                    let bob = bob_central.mls_central.conversation_stats(&id).await.unwrap();
+                    assert_eq!(bob.messages_sent, 1);
+                    assert_eq!(bob.messages_received, 2);
+                    assert_eq!(bob.commits_sent, 0);
+                    assert_eq!(bob.commits_received, 1);
+
+                    // stats go away with the conversation
+                    alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                    assert!(matches!(
+                        alice_central.mls_central.conversation_stats(&id).await.unwrap_err(),
+                        CryptoError::ConversationNotFound(_)
+                    ));
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .count::<MlsConversationCounters>()
+                            .await
+                            .unwrap(),
+                        0
+                    );
+                })
+            },
+        )
+        .await
+    }
+}
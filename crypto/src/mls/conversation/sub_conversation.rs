@@ -0,0 +1,268 @@
+//! Sub-conversations are ephemeral groups derived from a parent conversation, e.g. for a conference call. Their id is
+//! derived from the id of the parent and from their purpose so that every member of the parent agrees on it. Only
+//! members of the parent are meant to be in a sub-conversation: external joiners are checked against them (see
+//! [CoreCryptoCallbacks::client_is_existing_group_user](crate::CoreCryptoCallbacks::client_is_existing_group_user))
+//! and the ones who left the parent can be removed with [MlsCentral::sync_sub_conversation_members]. The link to the
+//! parent is persisted along with the group.
+
+use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+
+use crate::prelude::{
+    ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversationConfiguration,
+    MlsConversationWipeReport, MlsError,
+};
+
+impl MlsCentral {
+    /// Id of the sub-conversation of a conversation for a given purpose, the same for every member of the parent
+    ///
+    /// # Arguments
+    /// * `parent_id` - id of the parent group/conversation
+    /// * `purpose` - what the sub-conversation is for e.g. `conference`
+    ///
+    /// # Errors
+    /// If the parent conversation can't be found
+    pub async fn sub_conversation_id(
+        &mut self,
+        parent_id: &ConversationId,
+        purpose: &str,
+    ) -> CryptoResult<ConversationId> {
+        let ciphersuite = self.get_conversation(parent_id).await?.read().await.ciphersuite();
+        // length-prefixed so that no other parent and purpose end up with the same preimage
+        let preimage = [
+            (parent_id.len() as u32).to_be_bytes().as_slice(),
+            parent_id.as_slice(),
+            purpose.as_bytes(),
+        ]
+        .concat();
+        Ok(self
+            .mls_backend
+            .crypto()
+            .hash(ciphersuite.hash_algorithm(), &preimage)
+            .map_err(MlsError::from)?)
+    }
+
+    /// Creates the sub-conversation of a conversation for a given purpose, with the same ciphersuite and kind of
+    /// credential as its parent. The other members of the parent then join it, usually by external commit
+    ///
+    /// # Arguments
+    /// * `parent_id` - id of the parent group/conversation
+    /// * `purpose` - what the sub-conversation is for e.g. `conference`, see [MlsCentral::sub_conversation_id]
+    ///
+    /// # Return type
+    /// The id of the sub-conversation
+    ///
+    /// # Errors
+    /// If the parent conversation can't be found or if the sub-conversation already exists. Otherwise the same
+    /// errors as [MlsCentral::new_conversation]
+    pub async fn create_sub_conversation(
+        &mut self,
+        parent_id: &ConversationId,
+        purpose: &str,
+    ) -> CryptoResult<ConversationId> {
+        let id = self.sub_conversation_id(parent_id, purpose).await?;
+        let (ciphersuite, credential_type) = {
+            let parent = self.get_conversation(parent_id).await?;
+            let parent = parent.read().await;
+            (parent.ciphersuite(), parent.own_credential_type()?)
+        };
+        let configuration = MlsConversationConfiguration {
+            ciphersuite,
+            ..Default::default()
+        };
+        self.new_conversation(&id, credential_type, configuration).await?;
+        self.mark_conversation_as_child_of(&id, parent_id).await?;
+        Ok(id)
+    }
+
+    /// Sub-conversations of a conversation, without restoring them
+    ///
+    /// # Errors
+    /// KeyStore errors
+    pub async fn sub_conversations(&self, parent_id: &ConversationId) -> CryptoResult<Vec<ConversationId>> {
+        Ok(self
+            .list_conversations()
+            .await?
+            .into_iter()
+            .filter(|summary| summary.parent_id.as_ref() == Some(parent_id))
+            .map(|summary| summary.id)
+            .collect())
+    }
+
+    /// Removes from a sub-conversation the clients which are not members of its parent anymore, e.g. because they
+    /// have been removed from it. Meant to be called after every commit removing members from the parent
+    ///
+    /// # Return type
+    /// The commit removing them, to be sent and then merged like [MlsCentral::remove_members_from_conversation]'s.
+    /// Nothing when every member of the sub-conversation is still a member of the parent
+    ///
+    /// # Errors
+    /// [CryptoError::ParentGroupNotFound] when the conversation has no parent. Otherwise the same errors as
+    /// [MlsCentral::remove_members_from_conversation]
+    pub async fn sync_sub_conversation_members(
+        &mut self,
+        id: &ConversationId,
+    ) -> CryptoResult<Option<MlsCommitBundle>> {
+        let conversation = self.get_conversation(id).await?;
+        let parent = self
+            .get_parent_conversation(&conversation)
+            .await?
+            .ok_or(CryptoError::ParentGroupNotFound)?;
+        let parent_members = parent.read().await.members();
+        let departed = conversation
+            .read()
+            .await
+            .members()
+            .into_keys()
+            .filter(|member| !parent_members.contains_key(member))
+            .map(ClientId::from)
+            .collect::<Vec<_>>();
+        if departed.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.remove_members_from_conversation(id, &departed).await?))
+    }
+
+    /// Leaves a sub-conversation e.g. when hanging up a call, by wiping it locally. The Delivery Service still has
+    /// to be told so that the remaining members remove this client
+    ///
+    /// # Errors
+    /// [CryptoError::ParentGroupNotFound] when the conversation has no parent, since only sub-conversations can be
+    /// left this way. Otherwise the same errors as [MlsCentral::wipe_conversation]
+    pub async fn leave_sub_conversation(&mut self, id: &ConversationId) -> CryptoResult<MlsConversationWipeReport> {
+        if self.get_conversation(id).await?.read().await.parent_id.is_none() {
+            return Err(CryptoError::ParentGroupNotFound);
+        }
+        self.wipe_conversation(id).await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_manage_sub_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let parent_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&parent_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &parent_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let sub_id = alice_central
+                        .mls_central
+                        .create_sub_conversation(&parent_id, "conference")
+                        .await
+                        .unwrap();
+                    // every member of the parent agrees on the id
+                    assert_eq!(
+                        bob_central
+                            .mls_central
+                            .sub_conversation_id(&parent_id, "conference")
+                            .await
+                            .unwrap(),
+                        sub_id
+                    );
+                    assert_ne!(
+                        alice_central
+                            .mls_central
+                            .sub_conversation_id(&parent_id, "other")
+                            .await
+                            .unwrap(),
+                        sub_id
+                    );
+                    assert!(matches!(
+                        alice_central
+                            .mls_central
+                            .create_sub_conversation(&parent_id, "conference")
+                            .await
+                            .unwrap_err(),
+                        CryptoError::ConversationAlreadyExists(_)
+                    ));
+                    assert_eq!(
+                        alice_central.mls_central.sub_conversations(&parent_id).await.unwrap(),
+                        vec![sub_id.clone()]
+                    );
+                    assert!(alice_central
+                        .mls_central
+                        .sub_conversations(&sub_id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &sub_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .sync_sub_conversation_members(&sub_id)
+                        .await
+                        .unwrap()
+                        .is_none());
+
+                    // Bob leaves the parent hence has to leave the sub-conversation too
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    alice_central
+                        .mls_central
+                        .remove_members_from_conversation(&parent_id, &[bob_id])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&parent_id).await.unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .sync_sub_conversation_members(&sub_id)
+                        .await
+                        .unwrap()
+                        .is_some());
+                    alice_central.mls_central.commit_accepted(&sub_id).await.unwrap();
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .get_conversation_unchecked(&sub_id)
+                            .await
+                            .members()
+                            .len(),
+                        1
+                    );
+
+                    // only sub-conversations can be left
+                    assert!(matches!(
+                        alice_central
+                            .mls_central
+                            .leave_sub_conversation(&parent_id)
+                            .await
+                            .unwrap_err(),
+                        CryptoError::ParentGroupNotFound
+                    ));
+                    alice_central.mls_central.leave_sub_conversation(&sub_id).await.unwrap();
+                    assert!(!alice_central.mls_central.conversation_exists(&sub_id).await);
+                    assert!(alice_central
+                        .mls_central
+                        .sub_conversations(&parent_id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                    assert!(alice_central.mls_central.conversation_exists(&parent_id).await);
+                })
+            },
+        )
+        .await
+    }
+}
@@ -1,16 +1,13 @@
 use crate::mls::credential::crl::extract_dp;
-use crate::{
-    group_store::GroupStore,
-    prelude::{
-        ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsConversationConfiguration,
-        MlsCustomConfiguration, MlsError,
-    },
+use crate::prelude::{
+    ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsConversationConfiguration,
+    MlsCustomConfiguration, MlsError,
 };
-use core_crypto_keystore::entities::PersistedMlsPendingGroup;
+use core_crypto_keystore::entities::{MlsEncryptionKeyPair, MlsEpochEncryptionKeyPair, MlsProcessedWelcome};
 use mls_crypto_provider::MlsCryptoProvider;
-use openmls::prelude::{MlsGroup, MlsMessageIn, MlsMessageInBody, Welcome};
+use openmls::prelude::{MlsGroup, MlsMessageIn, MlsMessageInBody, OpenMlsCrypto, Welcome};
 use openmls_traits::OpenMlsCryptoProvider;
-use tls_codec::Deserialize;
+use tls_codec::Serialize;
 
 /// Contains everything client needs to know after decrypting an (encrypted) Welcome message
 #[derive(Debug)]
@@ -33,19 +30,22 @@ impl MlsCentral {
     ///
     /// # Errors
     /// see [MlsCentral::process_welcome_message]
-    #[cfg_attr(test, crate::dispotent)]
     pub async fn process_raw_welcome_message(
         &mut self,
         welcome: Vec<u8>,
         custom_cfg: MlsCustomConfiguration,
     ) -> CryptoResult<WelcomeBundle> {
-        let mut cursor = std::io::Cursor::new(welcome);
-        let welcome = MlsMessageIn::tls_deserialize(&mut cursor).map_err(MlsError::from)?;
+        let welcome = self.message_limits.deserialize_welcome(&welcome)?;
         self.process_welcome_message(welcome, custom_cfg).await
     }
 
     /// Create a conversation from a received MLS Welcome message
     ///
+    /// Processing the same Welcome again, e.g. when the application crashed before acknowledging it to the Delivery
+    /// Service, returns the conversation it created the first time instead of failing. A conversation with the same
+    /// id which was not created by this Welcome is never overwritten, see
+    /// [MlsCentral::process_welcome_message_replacing].
+    ///
     /// # Arguments
     /// * `welcome` - a `Welcome` message received as a result of a commit adding new members to a group
     /// * `configuration` - configuration of the group/conversation
@@ -57,28 +57,184 @@ impl MlsCentral {
     /// Errors can be originating from the KeyStore of from OpenMls:
     /// * if no [openmls::key_packages::KeyPackage] can be read from the KeyStore
     /// * if the message can't be decrypted
-    #[cfg_attr(test, crate::dispotent)]
+    ///
+    /// [CryptoError::UnsupportedGroupExtension] when the group uses an extension this version does not support,
+    /// [CryptoError::ConversationAlreadyExists] when there already is a conversation with the same id. Nothing is
+    /// persisted on failure: the KeyPackage the Welcome was sent to is kept, hence it can be processed again e.g. with
+    /// [MlsCentral::process_welcome_message_replacing]
     pub async fn process_welcome_message(
         &mut self,
         welcome: MlsMessageIn,
         custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        self.process_welcome(welcome, custom_cfg, false).await
+    }
+
+    /// Same as [MlsCentral::process_raw_welcome_message] but replaces the conversation with the same id, if any,
+    /// see [MlsCentral::process_welcome_message_replacing]
+    pub async fn process_raw_welcome_message_replacing(
+        &mut self,
+        welcome: Vec<u8>,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        let welcome = self.message_limits.deserialize_welcome(&welcome)?;
+        self.process_welcome_message_replacing(welcome, custom_cfg).await
+    }
+
+    /// Same as [MlsCentral::process_welcome_message] but replaces the conversation with the same id, if any, e.g. a
+    /// stale one this client was removed from without noticing before being added back. The replaced conversation
+    /// is wiped along with its key material, see [MlsCentral::wipe_conversation], and so is the pending group of an
+    /// external commit to the same conversation
+    ///
+    /// # Errors
+    /// see [MlsCentral::process_welcome_message]
+    pub async fn process_welcome_message_replacing(
+        &mut self,
+        welcome: MlsMessageIn,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        self.process_welcome(welcome, custom_cfg, true).await
+    }
+
+    async fn process_welcome(
+        &mut self,
+        welcome: MlsMessageIn,
+        custom_cfg: MlsCustomConfiguration,
+        replace: bool,
     ) -> CryptoResult<WelcomeBundle> {
         let welcome = match welcome.extract() {
             MlsMessageInBody::Welcome(welcome) => welcome,
             _ => return Err(CryptoError::ConsumerError),
         };
+
+        let welcome_hash = self
+            .mls_backend
+            .crypto()
+            .hash(
+                welcome.ciphersuite().hash_algorithm(),
+                &welcome.tls_serialize_detached().map_err(MlsError::from)?,
+            )
+            .map_err(MlsError::from)?;
+
+        if let Some(processed) = self
+            .mls_backend
+            .key_store()
+            .find::<MlsProcessedWelcome>(&welcome_hash)
+            .await?
+        {
+            let id = processed.conversation_id.clone();
+            if let Ok(conversation) = self.get_conversation(&id).await {
+                let crl_new_distribution_points = conversation.read().await.members_crl_distribution_points()?;
+                return Ok(WelcomeBundle {
+                    id,
+                    crl_new_distribution_points,
+                });
+            }
+        }
+
+        // the KeyPackage consumed by the Welcome is only deleted along with the conversation being persisted, so that
+        // it can still be processed if anything fails in between
+        self.within_transaction(|central| {
+            Box::pin(async move {
+                let bundle = central.join_by_welcome(welcome, custom_cfg, replace).await?;
+
+                #[cfg(test)]
+                central.fail_at("processed_welcome")?;
+                central
+                    .mls_backend
+                    .key_store()
+                    .save(MlsProcessedWelcome {
+                        id: welcome_hash,
+                        conversation_id: bundle.id.clone(),
+                    })
+                    .await?;
+                central.record_new_credentials(&bundle.id).await?;
+                central.record_epoch(&bundle.id, None).await?;
+
+                Ok(bundle)
+            })
+        })
+        .await
+    }
+
+    #[cfg_attr(test, crate::dispotent)]
+    async fn join_by_welcome(
+        &mut self,
+        welcome: Welcome,
+        custom_cfg: MlsCustomConfiguration,
+        replace: bool,
+    ) -> CryptoResult<WelcomeBundle> {
         let cs = welcome.ciphersuite().into();
         let configuration = MlsConversationConfiguration {
             ciphersuite: cs,
             custom: custom_cfg,
             ..Default::default()
         };
-        let conversation =
-            MlsConversation::from_welcome_message(welcome, configuration, &mut self.mls_backend, &mut self.mls_groups)
-                .await?;
+        let group = MlsConversation::group_from_welcome(welcome, &configuration, &mut self.mls_backend).await?;
+
+        let id = ConversationId::from(group.group_id().as_slice());
+        let conversation_exists = self.conversation_exists(&id).await;
+        let pending_group_exists = self.pending_group_exists(&id).await;
+        if conversation_exists || pending_group_exists {
+            if !replace {
+                MlsConversation::discard_welcome_group(&group, &self.mls_backend).await?;
+                return Err(CryptoError::ConversationAlreadyExists(id));
+            }
+            self.wipe_replaced_conversation(&group, conversation_exists).await?;
+        }
+
+        let conversation = MlsConversation::from_mls_group(group, configuration, &self.mls_backend).await?;
 
         // We wait for the group to be created then we iterate through all members
-        let crl_new_distribution_points = conversation
+        let crl_new_distribution_points = conversation.members_crl_distribution_points()?;
+
+        let id = conversation.id.clone();
+        self.mls_groups.insert(id.clone(), conversation);
+
+        Ok(WelcomeBundle {
+            id,
+            crl_new_distribution_points,
+        })
+    }
+
+    /// Wipes the conversation about to be replaced by the group created from a Welcome, see
+    /// [MlsCentral::process_welcome_message_replacing]
+    async fn wipe_replaced_conversation(&mut self, group: &MlsGroup, conversation_exists: bool) -> CryptoResult<()> {
+        let id = ConversationId::from(group.group_id().as_slice());
+        // the epoch keypair of the new group is identified by the same group id hence would be wiped as well
+        let keystore = self.mls_backend.key_store();
+        let epoch_keypair = keystore
+            .find::<MlsEpochEncryptionKeyPair>(epoch_keypair_id(group))
+            .await?;
+
+        if conversation_exists {
+            self.wipe_conversation(&id).await?;
+        }
+        if self.pending_group_exists(&id).await {
+            self.clear_pending_group_from_external_commit(&id).await?;
+        }
+
+        if let Some(epoch_keypair) = epoch_keypair {
+            self.mls_backend.key_store().save(epoch_keypair).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Id of the encryption keypair of the current epoch of a group, i.e. `group id || leaf index (u32) || epoch (u64)`
+fn epoch_keypair_id(group: &MlsGroup) -> Vec<u8> {
+    [
+        group.group_id().as_slice(),
+        &group.own_leaf_index().u32().to_be_bytes(),
+        &group.epoch().as_u64().to_be_bytes(),
+    ]
+    .concat()
+}
+
+impl MlsConversation {
+    /// CRL distribution points of all the members' certificates
+    fn members_crl_distribution_points(&self) -> CryptoResult<Option<Vec<String>>> {
+        let crl_distribution_points = self
             .group
             .members_credentials()
             .filter_map(|c| match c.mls_credential() {
@@ -89,39 +245,36 @@ impl MlsCentral {
                 acc.extend(extract_dp(c)?);
                 CryptoResult::Ok(acc)
             })?;
-        let crl_new_distribution_points = if crl_new_distribution_points.is_empty() {
+
+        Ok(if crl_distribution_points.is_empty() {
             None
         } else {
-            Some(crl_new_distribution_points)
-        };
-
-        let id = conversation.id.clone();
-        self.mls_groups.insert(id.clone(), conversation);
-
-        Ok(WelcomeBundle {
-            id,
-            crl_new_distribution_points,
+            Some(crl_distribution_points)
         })
     }
-}
 
-impl MlsConversation {
     // ? Do we need to provide the ratchet_tree to the MlsGroup? Does everything crumble down if we can't actually get it?
-    /// Create the MLS conversation from an MLS Welcome message
+    /// Create the MLS group from an MLS Welcome message. It is not persisted yet: either
+    /// [MlsConversation::from_mls_group] or [MlsConversation::discard_welcome_group] has to be called afterwards
     ///
     /// # Arguments
     /// * `welcome` - welcome message to create the group from
-    /// * `config` - group configuration
+    /// * `configuration` - group configuration
     /// * `backend` - the KeyStore to persist the group
     ///
     /// # Errors
-    /// Errors can happen from OpenMls or from the KeyStore
-    async fn from_welcome_message(
+    /// Errors can happen from OpenMls or from the KeyStore. The conversation has to comply with the
+    /// [crate::prelude::MlsJoinPolicy] of the configuration
+    async fn group_from_welcome(
         welcome: Welcome,
-        configuration: MlsConversationConfiguration,
+        configuration: &MlsConversationConfiguration,
         backend: &mut MlsCryptoProvider,
-        mls_groups: &mut GroupStore<MlsConversation>,
-    ) -> CryptoResult<Self> {
+    ) -> CryptoResult<MlsGroup> {
+        configuration
+            .custom
+            .join_policy
+            .check_ciphersuite(configuration.ciphersuite)?;
+
         let mls_group_config = configuration.as_openmls_default_configuration()?;
 
         let group = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None).await;
@@ -131,18 +284,44 @@ impl MlsConversation {
             _ => group.map_err(MlsError::from)?,
         };
 
-        let id = ConversationId::from(group.group_id().as_slice());
-        let existing_conversation = mls_groups.get_fetch(&id[..], backend.borrow_keystore_mut(), None).await;
-        let conversation_exists = existing_conversation.ok().flatten().is_some();
+        // the GroupContext and the members are encrypted in the Welcome hence can only be checked once it has been
+        // processed
+        let checked = MlsConversationConfiguration::check_group_extensions(group.export_group_context().extensions())
+            .and_then(|_| {
+                configuration
+                    .custom
+                    .join_policy
+                    .check_credentials(group.members_credentials())
+            });
+        if let Err(e) = checked {
+            Self::discard_welcome_group(&group, backend).await?;
+            return Err(e);
+        }
 
-        let pending_group = backend.key_store().find::<PersistedMlsPendingGroup>(&id[..]).await;
-        let pending_group_exists = pending_group.ok().flatten().is_some();
+        Ok(group)
+    }
 
-        if conversation_exists || pending_group_exists {
-            return Err(CryptoError::ConversationAlreadyExists(id));
+    /// Deletes the key material openmls stored while creating a group from a Welcome, when the group is not kept
+    async fn discard_welcome_group(group: &MlsGroup, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let keystore = backend.key_store();
+
+        let epoch_keypair_id = epoch_keypair_id(group);
+        if keystore
+            .find::<MlsEpochEncryptionKeyPair>(&epoch_keypair_id)
+            .await?
+            .is_some()
+        {
+            keystore
+                .remove::<MlsEpochEncryptionKeyPair, _>(&epoch_keypair_id)
+                .await?;
         }
 
-        Self::from_mls_group(group, configuration, backend).await
+        if let Some(ek) = group.own_leaf_node().map(|ln| ln.encryption_key().as_slice().to_vec()) {
+            if keystore.find::<MlsEncryptionKeyPair>(&ek).await?.is_some() {
+                keystore.remove::<MlsEncryptionKeyPair, _>(&ek).await?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -201,6 +380,111 @@ pub mod tests {
         .await;
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_be_idempotent(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let welcome = welcome.to_bytes().unwrap();
+
+                    let bundle = bob_central
+                        .mls_central
+                        .process_raw_welcome_message(welcome.clone(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert_eq!(bundle.id, id);
+
+                    // e.g. Bob crashed before acknowledging the Welcome and gets it again after a restart
+                    bob_central.mls_central.restore_from_disk().await.unwrap();
+                    let count = bob_central.mls_central.count_entities().await;
+                    let bundle = bob_central
+                        .mls_central
+                        .process_raw_welcome_message(welcome, case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert_eq!(bundle.id, id);
+                    assert_eq!(bob_central.mls_central.count_entities().await, count);
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_persist_nothing_when_interrupted(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let welcome = welcome.to_bytes().unwrap();
+
+                    // fails after the conversation is persisted but before the Welcome is recorded as processed
+                    let before = bob_central.mls_central.count_entities().await;
+                    bob_central.mls_central.failing_step = Some("processed_welcome");
+                    let interrupted = bob_central
+                        .mls_central
+                        .process_raw_welcome_message(welcome.clone(), case.custom_cfg())
+                        .await;
+                    assert!(matches!(interrupted.unwrap_err(), CryptoError::ImplementationError));
+                    assert_eq!(bob_central.mls_central.count_entities().await, before);
+                    assert!(!bob_central.mls_central.conversation_exists(&id).await);
+
+                    // the KeyPackage has been kept hence the Welcome can be processed again
+                    let bundle = bob_central
+                        .mls_central
+                        .process_raw_welcome_message(welcome, case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert_eq!(bundle.id, id);
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await;
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn process_welcome_should_fail_when_already_exists(case: TestCase) {
@@ -229,11 +513,148 @@ pub mod tests {
                         .new_conversation(&id, case.credential_type, case.cfg.clone())
                         .await
                         .unwrap();
+                    let before = bob_central.mls_central.count_entities().await;
                     let join_welcome = bob_central
                         .mls_central
                         .process_welcome_message(welcome.into(), case.custom_cfg())
                         .await;
                     assert!(matches!(join_welcome.unwrap_err(), CryptoError::ConversationAlreadyExists(i) if i == id));
+
+                    // nothing is left of the group created from the Welcome and the KeyPackage is kept
+                    assert_eq!(bob_central.mls_central.count_entities().await, before);
+                    assert!(bob_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .group
+                        .is_active());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_can_replace_stale_conversation(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // Bob is removed then added back without ever processing the commit removing him
+                    alice_central
+                        .mls_central
+                        .remove_members_from_conversation(&id, &[bob_central.mls_central.get_client_id()])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let stale_epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let bundle = bob_central
+                        .mls_central
+                        .process_raw_welcome_message_replacing(welcome.to_bytes().unwrap(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert_eq!(bundle.id, id);
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+                    assert_ne!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        stale_epoch
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+
+                    // the replacing conversation is the one persisted
+                    bob_central.mls_central.restore_from_disk().await.unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_enforce_join_policy(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome
+                        .to_bytes()
+                        .unwrap();
+
+                    let other_ciphersuite = [
+                        openmls::prelude::Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+                        openmls::prelude::Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+                    ]
+                    .into_iter()
+                    .map(crate::prelude::MlsCiphersuite::from)
+                    .find(|cs| *cs != case.ciphersuite())
+                    .unwrap();
+                    let mut cfg = case.custom_cfg();
+                    cfg.join_policy.allowed_ciphersuites = vec![other_ciphersuite];
+                    let join = bob_central
+                        .mls_central
+                        .process_raw_welcome_message(welcome.clone(), cfg)
+                        .await;
+                    assert!(matches!(join.unwrap_err(), CryptoError::JoinPolicyViolation(_)));
+                    assert!(bob_central.mls_central.get_conversation(&id).await.is_err());
+
+                    let mut cfg = case.custom_cfg();
+                    cfg.join_policy.allowed_ciphersuites = vec![other_ciphersuite, case.ciphersuite()];
+                    cfg.join_policy.require_x509_credentials = true;
+                    let join = bob_central.mls_central.process_raw_welcome_message(welcome, cfg).await;
+                    if case.is_x509() {
+                        assert_eq!(join.unwrap().id, id);
+                    } else {
+                        assert!(matches!(join.unwrap_err(), CryptoError::JoinPolicyViolation(_)));
+                        assert!(bob_central.mls_central.get_conversation(&id).await.is_err());
+                    }
                 })
             },
         )
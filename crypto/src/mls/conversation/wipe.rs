@@ -1,43 +1,106 @@
 use crate::prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsError};
-use core_crypto_keystore::CryptoKeystoreMls;
+use core_crypto_keystore::{
+    entities::{
+        MlsEncryptionKeyPair, MlsEpochEncryptionKeyPair, MlsPendingMessage, MlsProcessedWelcome, MlsSeenCredential,
+        PersistedMlsPendingGroup,
+    },
+    CryptoKeystoreMls,
+};
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls_traits::OpenMlsCryptoProvider;
 
+/// What has been removed from the keystore along with a conversation, see [MlsCentral::wipe_conversation]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MlsConversationWipeReport {
+    /// Proposals which were not committed yet, along with the encryption keypairs of the update ones
+    pub pending_proposals: usize,
+    /// Encryption keypairs of the own leaf node and of the current epoch
+    pub encryption_keypairs: usize,
+    /// Messages buffered because they were received before the commit creating their epoch
+    pub buffered_messages: usize,
+    /// Whether a pending group created when joining by external commit existed
+    pub pending_group: bool,
+}
+
 impl MlsCentral {
-    /// Destroys a group locally
+    /// Destroys a group locally along with all the key material and state related to it: encryption keypairs,
+    /// pending proposals, pending group and buffered messages
+    ///
+    /// # Returns
+    /// A report of what has been removed from the keystore
     ///
     /// # Errors
     /// KeyStore errors, such as IO
     #[cfg_attr(test, crate::dispotent)]
-    pub async fn wipe_conversation(&mut self, id: &ConversationId) -> CryptoResult<()> {
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
-            .wipe_associated_entities(&self.mls_backend)
-            .await?;
-        self.mls_backend.key_store().mls_group_delete(id).await?;
+    pub async fn wipe_conversation(&mut self, id: &ConversationId) -> CryptoResult<MlsConversationWipeReport> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let epoch = conversation.group.epoch().as_u64();
+        let mut report = conversation.wipe_associated_entities(&self.mls_backend).await?;
+        drop(conversation);
+
+        let keystore = self.mls_backend.key_store();
+        keystore.mls_group_delete(id).await?;
+
+        report.pending_group = keystore.find::<PersistedMlsPendingGroup>(id).await?.is_some();
+        if report.pending_group {
+            keystore.mls_pending_groups_delete(id).await?;
+        }
+
+        report.buffered_messages = keystore.remove_all_by_conversation::<MlsPendingMessage>(id).await?;
+        keystore.remove_all_by_conversation::<MlsProcessedWelcome>(id).await?;
+        keystore.remove_all_by_conversation::<MlsSeenCredential>(id).await?;
+
+        self.wipe_conversation_metadata(id).await?;
+        self.wipe_epoch_history(id, epoch).await?;
+        self.wipe_conversation_stats(id).await?;
+        self.wipe_pending_commit(id).await?;
+
         let _ = self.mls_groups.remove(id);
-        Ok(())
+        self.reset_conversation_health(id).await;
+        Ok(report)
     }
 }
 
 impl MlsConversation {
-    async fn wipe_associated_entities(&mut self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+    async fn wipe_associated_entities(
+        &mut self,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<MlsConversationWipeReport> {
+        let mut report = MlsConversationWipeReport::default();
+        let keystore = backend.key_store();
+
         // the own client may or may not have generated an epoch keypair in the previous epoch
         // Since it is a terminal operation, ignoring the error is fine here.
         let _ = self.group.delete_previous_epoch_keypairs(backend).await;
 
+        // the ones of the current epoch
+        report.encryption_keypairs += keystore
+            .remove_all_by_conversation::<MlsEpochEncryptionKeyPair>(self.id())
+            .await?;
+
         let pending_proposals = self.group.pending_proposals().cloned().collect::<Vec<_>>();
+        report.pending_proposals = pending_proposals.len();
         for proposal in pending_proposals {
             // Update proposals rekey the own leaf node. Hence the associated encryption keypair has to be cleared
             self.group
-                .remove_pending_proposal(backend.key_store(), proposal.proposal_reference())
+                .remove_pending_proposal(keystore, proposal.proposal_reference())
                 .await
                 .map_err(MlsError::from)?;
         }
 
-        Ok(())
+        if let Some(ek) = self
+            .group
+            .own_leaf_node()
+            .map(|ln| ln.encryption_key().as_slice().to_vec())
+        {
+            if keystore.find::<MlsEncryptionKeyPair>(&ek).await?.is_some() {
+                keystore.remove::<MlsEncryptionKeyPair, _>(&ek).await?;
+                report.encryption_keypairs += 1;
+            }
+        }
+
+        Ok(report)
     }
 }
 
@@ -107,7 +170,10 @@ pub mod tests {
                     initial_count.encryption_keypair + 1
                 );
 
-                cc.mls_central.wipe_conversation(&id).await.unwrap();
+                let report = cc.mls_central.wipe_conversation(&id).await.unwrap();
+                assert_eq!(report.pending_proposals, 1);
+                assert!(!report.pending_group);
+                assert_eq!(report.buffered_messages, 0);
 
                 let final_count = cc.mls_central.count_entities().await;
                 assert_eq!(final_count.group, 0);
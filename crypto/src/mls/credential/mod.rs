@@ -15,7 +15,7 @@ use openmls_x509_credential::CertificateKeyPair;
 
 use mls_crypto_provider::MlsCryptoProvider;
 
-use crate::prelude::{CertificateBundle, Client, ClientId, CryptoResult, MlsError};
+use crate::prelude::{CertificateBundle, Client, ClientId, CryptoResult, MlsCredentialType, MlsError};
 
 #[derive(Debug)]
 pub struct CredentialBundle {
@@ -35,6 +35,34 @@ impl CredentialBundle {
             signature_key: self.signature_key.to_public_vec().into(),
         }
     }
+
+    pub(crate) fn to_public_key_bundle(&self) -> PublicKeyBundle {
+        let certificate_chain = match self.credential.mls_credential() {
+            openmls::prelude::MlsCredentialType::X509(cert) => {
+                cert.certificates.iter().map(|c| c.as_slice().to_vec()).collect()
+            }
+            openmls::prelude::MlsCredentialType::Basic(_) => vec![],
+        };
+        PublicKeyBundle {
+            signature_scheme: self.signature_key.signature_scheme(),
+            credential_type: self.credential.credential_type().into(),
+            public_key: self.signature_key.to_public_vec(),
+            certificate_chain,
+        }
+    }
+}
+
+/// Public part of one of the client's credentials, see [crate::mls::MlsCentral::client_public_key_bundle]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKeyBundle {
+    /// Signature scheme of the key
+    pub signature_scheme: SignatureScheme,
+    /// Type of the credential the key belongs to
+    pub credential_type: MlsCredentialType,
+    /// Public signature key
+    pub public_key: Vec<u8>,
+    /// DER-encoded certificate chain, leaf first. Empty for a Basic credential
+    pub certificate_chain: Vec<Vec<u8>>,
 }
 
 impl From<CredentialBundle> for CredentialWithKey {
@@ -312,6 +340,8 @@ pub mod tests {
                 alice_central.e2ei_conversation_state(&id).await.unwrap(),
                 E2eiConversationState::Verified
             );
+            // do not tolerate any clock skew, otherwise the certificate would be considered valid
+            alice_central.set_clock_skew_leeway(core::time::Duration::ZERO);
 
             let elapsed = start.elapsed();
             // Give time to the certificate to expire
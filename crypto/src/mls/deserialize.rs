@@ -0,0 +1,258 @@
+//! Incoming messages are untrusted input. Before handing them over to openmls, a few cheap checks are made so that
+//! oversized or obviously invalid payloads are rejected early and so that a panic while parsing them does not take the
+//! whole application down.
+
+use openmls::prelude::{MlsMessageIn, VerifiableGroupInfo};
+use tls_codec::Deserialize;
+
+use crate::prelude::{CryptoError, CryptoResult, MlsCentral, MlsError};
+
+/// Default maximum size of an incoming handshake or application message (1MiB)
+pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Default maximum size of an incoming Welcome (16MiB) which is larger since it can carry the ratchet tree of the group
+pub const MAX_WELCOME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Default maximum size of an incoming GroupInfo (16MiB) which can carry the ratchet tree of the group as well
+pub const MAX_GROUP_INFO_SIZE: usize = 16 * 1024 * 1024;
+
+/// `mls10` in the TLS encoding of a `ProtocolVersion`
+const PROTOCOL_VERSION_MLS10: u16 = 1;
+/// Values of the `WireFormat` of a `MLSMessage`
+const WIRE_FORMAT_PUBLIC_MESSAGE: u16 = 1;
+const WIRE_FORMAT_PRIVATE_MESSAGE: u16 = 2;
+const WIRE_FORMAT_WELCOME: u16 = 3;
+
+/// Limits enforced on incoming messages before they are parsed, see [MlsCentral::set_message_limits]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlsMessageLimits {
+    /// Maximum size in bytes of a message given to [MlsCentral::decrypt_message]. Defaults to [MAX_MESSAGE_SIZE]
+    pub max_message_size: usize,
+    /// Maximum size in bytes of a Welcome given to [MlsCentral::process_raw_welcome_message]. Defaults to
+    /// [MAX_WELCOME_SIZE]
+    pub max_welcome_size: usize,
+    /// Maximum size in bytes of a GroupInfo e.g. used to join a conversation by external commit. Defaults to
+    /// [MAX_GROUP_INFO_SIZE]
+    pub max_group_info_size: usize,
+}
+
+impl Default for MlsMessageLimits {
+    fn default() -> Self {
+        Self {
+            max_message_size: MAX_MESSAGE_SIZE,
+            max_welcome_size: MAX_WELCOME_SIZE,
+            max_group_info_size: MAX_GROUP_INFO_SIZE,
+        }
+    }
+}
+
+impl MlsMessageLimits {
+    /// Validates then deserializes a TLS-serialized public or private message
+    ///
+    /// # Errors
+    /// [CryptoError::InputTooLarge] when the message is too large. [CryptoError::MalformedMessage] when it is not a
+    /// MLS 1.0 public or private message or when parsing it panics. Otherwise the deserialization error
+    pub fn deserialize_message(&self, message: &[u8]) -> CryptoResult<MlsMessageIn> {
+        Self::deserialize(
+            message,
+            self.max_message_size,
+            &[WIRE_FORMAT_PUBLIC_MESSAGE, WIRE_FORMAT_PRIVATE_MESSAGE],
+        )
+    }
+
+    /// Validates then deserializes a TLS-serialized Welcome
+    ///
+    /// # Errors
+    /// see [MlsMessageLimits::deserialize_message]
+    pub fn deserialize_welcome(&self, welcome: &[u8]) -> CryptoResult<MlsMessageIn> {
+        Self::deserialize(welcome, self.max_welcome_size, &[WIRE_FORMAT_WELCOME])
+    }
+
+    /// Validates then deserializes a TLS-serialized GroupInfo, which unlike the other inputs is not wrapped in a
+    /// MLSMessage
+    ///
+    /// # Errors
+    /// [CryptoError::InputTooLarge] when the GroupInfo is too large. [CryptoError::MalformedMessage] when parsing it
+    /// panics. Otherwise the deserialization error
+    pub fn deserialize_group_info(&self, group_info: &[u8]) -> CryptoResult<VerifiableGroupInfo> {
+        Self::check_size(group_info, self.max_group_info_size)?;
+        let group_info = std::panic::catch_unwind(move || {
+            let mut cursor = group_info;
+            VerifiableGroupInfo::tls_deserialize(&mut cursor)
+        })
+        .map_err(|_| CryptoError::MalformedMessage("parsing panicked".to_string()))?;
+        Ok(group_info.map_err(MlsError::from)?)
+    }
+
+    fn check_size(input: &[u8], max: usize) -> CryptoResult<()> {
+        if input.len() > max {
+            return Err(CryptoError::InputTooLarge { size: input.len(), max });
+        }
+        Ok(())
+    }
+
+    fn deserialize(message: &[u8], max_size: usize, wire_formats: &[u16]) -> CryptoResult<MlsMessageIn> {
+        Self::check_size(message, max_size)?;
+
+        // MLSMessage starts with the protocol version followed by the wire format, both being u16
+        let header = |offset: usize| {
+            message
+                .get(offset..offset + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .ok_or_else(|| CryptoError::MalformedMessage("truncated header".to_string()))
+        };
+        let version = header(0)?;
+        if version != PROTOCOL_VERSION_MLS10 {
+            return Err(CryptoError::MalformedMessage(format!(
+                "unsupported protocol version {version}"
+            )));
+        }
+        let wire_format = header(2)?;
+        if !wire_formats.contains(&wire_format) {
+            return Err(CryptoError::MalformedMessage(format!(
+                "unexpected wire format {wire_format}"
+            )));
+        }
+
+        let msg = std::panic::catch_unwind(move || {
+            let mut cursor = message;
+            MlsMessageIn::tls_deserialize(&mut cursor)
+        })
+        .map_err(|_| CryptoError::MalformedMessage("parsing panicked".to_string()))?;
+        Ok(msg.map_err(MlsError::from)?)
+    }
+}
+
+impl MlsCentral {
+    /// Sets the limits enforced on incoming messages before they are parsed. They default to
+    /// [MlsMessageLimits::default]
+    pub fn set_message_limits(&mut self, limits: MlsMessageLimits) {
+        self.message_limits = limits;
+    }
+
+    /// The limits enforced on incoming messages, see [MlsCentral::set_message_limits]
+    pub fn message_limits(&self) -> MlsMessageLimits {
+        self.message_limits
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use openmls::prelude::{MlsMessageInBody, MlsMessageOut};
+    use tls_codec::Serialize;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_malformed_messages(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let encrypted = alice_central.mls_central.encrypt_message(&id, b"Hello").await.unwrap();
+                    let limits = MlsMessageLimits::default();
+                    assert!(matches!(
+                        limits.deserialize_message(&encrypted).unwrap().extract(),
+                        MlsMessageInBody::PrivateMessage(_)
+                    ));
+
+                    // too large
+                    bob_central.mls_central.set_message_limits(MlsMessageLimits {
+                        max_message_size: encrypted.len() - 1,
+                        ..Default::default()
+                    });
+                    let result = bob_central.mls_central.decrypt_message(&id, &encrypted).await;
+                    assert!(matches!(
+                        result.unwrap_err(),
+                        CryptoError::InputTooLarge { size, max } if size == encrypted.len() && max == size - 1
+                    ));
+                    bob_central.mls_central.set_message_limits(MlsMessageLimits::default());
+
+                    // unknown protocol version
+                    let mut unknown_version = encrypted.clone();
+                    unknown_version[..2].copy_from_slice(&2u16.to_be_bytes());
+                    let result = bob_central.mls_central.decrypt_message(&id, &unknown_version).await;
+                    assert!(matches!(result.unwrap_err(), CryptoError::MalformedMessage(_)));
+
+                    // truncated
+                    let result = bob_central.mls_central.decrypt_message(&id, &encrypted[..3]).await;
+                    assert!(matches!(result.unwrap_err(), CryptoError::MalformedMessage(_)));
+
+                    // not a message which can be decrypted
+                    let kp = alice_central.mls_central.get_one_key_package(&case).await;
+                    let kp = MlsMessageOut::from(kp).tls_serialize_detached().unwrap();
+                    let result = bob_central.mls_central.decrypt_message(&id, &kp).await;
+                    assert!(matches!(result.unwrap_err(), CryptoError::MalformedMessage(_)));
+                    assert!(matches!(
+                        limits.deserialize_welcome(&encrypted).unwrap_err(),
+                        CryptoError::MalformedMessage(_)
+                    ));
+
+                    // the untampered message still goes through
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, &encrypted).await.unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), b"Hello");
+                })
+            },
+        )
+        .await
+    }
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_oversized_group_info(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let group_info = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .group_info
+                        .payload
+                        .bytes();
+
+                    let limits = MlsMessageLimits::default();
+                    assert!(limits.deserialize_group_info(&group_info).is_ok());
+
+                    let limits = MlsMessageLimits {
+                        max_group_info_size: group_info.len() - 1,
+                        ..Default::default()
+                    };
+                    assert!(matches!(
+                        limits.deserialize_group_info(&group_info).unwrap_err(),
+                        CryptoError::InputTooLarge { size, .. } if size == group_info.len()
+                    ));
+                })
+            },
+        )
+        .await
+    }
+}
@@ -14,9 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
+use std::collections::HashSet;
+
+use futures_util::StreamExt;
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls::prelude::{
-    group_info::VerifiableGroupInfo, CredentialType, MlsGroup, MlsMessageOut, Proposal, Sender, StagedCommit,
+    group_info::VerifiableGroupInfo, CredentialType, MlsGroup, MlsMessageOut, Proposal, RatchetTreeIn, Sender,
+    StagedCommit, PURE_CIPHERTEXT_WIRE_FORMAT_POLICY,
 };
 use openmls_traits::OpenMlsCryptoProvider;
 use tls_codec::Serialize;
@@ -29,11 +33,14 @@ use core_crypto_keystore::{
 use crate::{
     e2e_identity::conversation_state::compute_state,
     group_store::GroupStoreValue,
-    mls::credential::crl::extract_dp,
+    mls::{
+        conversation::{batch_commit::DEFAULT_COMMIT_CONCURRENCY, stats::MlsConversationActivity},
+        credential::{crl::extract_dp, CredentialBundle},
+    },
     prelude::{
         decrypt::MlsBufferedConversationDecryptMessage, id::ClientId, ConversationId, CoreCryptoCallbacks, CryptoError,
         CryptoResult, E2eiConversationState, MlsCentral, MlsCiphersuite, MlsConversation, MlsConversationConfiguration,
-        MlsCredentialType, MlsCustomConfiguration, MlsError, MlsGroupInfoBundle,
+        MlsCredentialType, MlsCustomConfiguration, MlsError, MlsGroupInfoBundle, MlsWirePolicy,
     },
 };
 
@@ -88,20 +95,115 @@ impl MlsCentral {
     /// commit that was generated by this call
     ///
     /// # Errors
-    /// Errors resulting from OpenMls, the KeyStore calls and serialization
+    /// Errors resulting from OpenMls, the KeyStore calls and serialization.
+    /// [CryptoError::UnsupportedGroupExtension] when the group uses an extension this version does not support
     pub async fn join_by_external_commit(
         &mut self,
         group_info: VerifiableGroupInfo,
         custom_cfg: MlsCustomConfiguration,
         credential_type: MlsCredentialType,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        self.join_by_external_commit_with_tree(group_info, None, custom_cfg, credential_type, None)
+            .await
+    }
+
+    /// Same as [MlsCentral::join_by_external_commit] but pins the ciphersuite the group is expected to use, e.g. the
+    /// one the Delivery Service advertises for the conversation. This fails early when the GroupInfo does not match
+    /// rather than joining with a credential of another signature scheme. The credential is the most recent one of
+    /// `credential_type` for the signature scheme of the ciphersuite, whichever is the default of the client.
+    ///
+    /// # Arguments
+    /// * `group_info` - see [MlsCentral::join_by_external_commit]
+    /// * `custom_cfg` - configuration of the MLS conversation fetched from the Delivery Service
+    /// * `credential_type` - see [MlsCentral::join_by_external_commit]
+    /// * `expected_ciphersuite` - the ciphersuite the group has to use
+    ///
+    /// # Errors
+    /// [CryptoError::GroupCiphersuiteMismatch] when the GroupInfo uses another ciphersuite, in which case nothing is
+    /// created. Otherwise the same as [MlsCentral::join_by_external_commit]
+    pub async fn join_by_external_commit_pinned(
+        &mut self,
+        group_info: VerifiableGroupInfo,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+        expected_ciphersuite: MlsCiphersuite,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        self.join_by_external_commit_with_tree(
+            group_info,
+            None,
+            custom_cfg,
+            credential_type,
+            Some(expected_ciphersuite),
+        )
+        .await
+    }
+
+    /// Joins a group from its GroupInfo and a ratchet tree delivered alongside it, for Delivery Services which
+    /// strip the ratchet tree extension out of the GroupInfo (e.g. some federated backends). Otherwise identical
+    /// to [MlsCentral::join_by_external_commit], including having to merge the pending group afterwards.
+    ///
+    /// The secrets of a group are only ever sent to a new member in a Welcome, encrypted to its KeyPackage, hence
+    /// they can't be recovered from the public GroupInfo and ratchet tree. So even when an Add commit already
+    /// included this client but its Welcome has been dropped, the group can only be joined with an external commit.
+    ///
+    /// # Arguments
+    /// * `group_info` - a GroupInfo wrapped in a MLS message, which does not have to embed the ratchet tree
+    /// * `ratchet_tree` - the ratchet tree of the group at the epoch of `group_info`
+    /// * `custom_cfg` - configuration of the MLS conversation fetched from the Delivery Service
+    /// * `credential_type` - see [MlsCentral::join_by_external_commit]
+    ///
+    /// # Errors
+    /// Errors resulting from OpenMls e.g. when the ratchet tree does not match the GroupInfo, the KeyStore calls and
+    /// serialization. [CryptoError::UnsupportedGroupExtension] when the group uses an extension this version does not
+    /// support
+    pub async fn join_from_group_info(
+        &mut self,
+        group_info: VerifiableGroupInfo,
+        ratchet_tree: RatchetTreeIn,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        self.join_by_external_commit_with_tree(group_info, Some(ratchet_tree), custom_cfg, credential_type, None)
+            .await
+    }
+
+    async fn join_by_external_commit_with_tree(
+        &mut self,
+        group_info: VerifiableGroupInfo,
+        ratchet_tree: Option<RatchetTreeIn>,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+        expected_ciphersuite: Option<MlsCiphersuite>,
     ) -> CryptoResult<MlsConversationInitBundle> {
         let mls_client = self.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
 
         let cs: MlsCiphersuite = group_info.ciphersuite().into();
+        if let Some(expected) = expected_ciphersuite.filter(|expected| *expected != cs) {
+            return Err(CryptoError::GroupCiphersuiteMismatch {
+                expected: expected.into(),
+                actual: cs.into(),
+            });
+        }
+
+        MlsConversationConfiguration::check_group_extensions(group_info.extensions())?;
+
         let cb = mls_client
             .get_most_recent_or_create_credential_bundle(&self.mls_backend, cs.signature_algorithm(), credential_type)
             .await?;
 
+        Self::join_with_credential_bundle(&self.mls_backend, cb, group_info, ratchet_tree, custom_cfg).await
+    }
+
+    /// Creates the external commit and persists the pending group, once the GroupInfo has been checked and the
+    /// credential to join with found
+    async fn join_with_credential_bundle(
+        backend: &MlsCryptoProvider,
+        cb: &CredentialBundle,
+        group_info: VerifiableGroupInfo,
+        ratchet_tree: Option<RatchetTreeIn>,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        let cs: MlsCiphersuite = group_info.ciphersuite().into();
         let serialized_cfg = serde_json::to_vec(&custom_cfg).map_err(MlsError::MlsKeystoreSerializationError)?;
 
         let configuration = MlsConversationConfiguration {
@@ -111,9 +213,9 @@ impl MlsCentral {
         };
 
         let (group, commit, group_info) = MlsGroup::join_by_external_commit(
-            &self.mls_backend,
+            backend,
             &cb.signature_key,
-            None,
+            ratchet_tree,
             group_info,
             &configuration.as_openmls_default_configuration()?,
             &[],
@@ -131,7 +233,7 @@ impl MlsCentral {
             _ => None,
         };
 
-        self.mls_backend
+        backend
             .key_store()
             .mls_pending_groups_save(
                 group.group_id().as_slice(),
@@ -162,6 +264,17 @@ impl MlsCentral {
         &mut self,
         id: &ConversationId,
     ) -> CryptoResult<Option<Vec<MlsBufferedConversationDecryptMessage>>> {
+        // the group and its settings are persisted along with the deletion of the pending group, or none of them is
+        let id = id.clone();
+        self.within_transaction(|central| Box::pin(central.merge_pending_group(id)))
+            .await
+    }
+
+    async fn merge_pending_group(
+        &mut self,
+        id: ConversationId,
+    ) -> CryptoResult<Option<Vec<MlsBufferedConversationDecryptMessage>>> {
+        let id = &id;
         // Retrieve the pending MLS group from the keystore
         let (group, cfg) = self.mls_backend.key_store().mls_pending_groups_load(id).await?;
 
@@ -184,12 +297,19 @@ impl MlsCentral {
         let is_rejoin = self.mls_backend.key_store().mls_group_exists(id.as_slice()).await;
 
         // Persist the now usable MLS group in the keystore
-        // TODO: find a way to make the insertion of the MlsGroup and deletion of the pending group transactional
         let mut conversation = MlsConversation::from_mls_group(mls_group, configuration, &self.mls_backend).await?;
+        self.metrics.epoch_advanced();
+        self.cleanup_epoch_key_material(&conversation).await;
 
         let pending_messages = self.restore_pending_messages(&mut conversation, is_rejoin).await?;
 
         self.mls_groups.insert(id.clone(), conversation);
+        // rejoining is the way to recover from a broken conversation
+        self.reset_conversation_health(id).await;
+        self.record_new_credentials(id).await?;
+        self.record_epoch(id, Some(self.client_id()?)).await?;
+        self.record_conversation_activity(id, MlsConversationActivity::CommitSent)
+            .await;
 
         // cleanup the pending group we no longer need
         self.mls_backend.key_store().mls_pending_groups_delete(id).await?;
@@ -215,6 +335,109 @@ impl MlsCentral {
         Ok(self.mls_backend.key_store().mls_pending_groups_delete(id).await?)
     }
 
+    /// Recovers a conversation whose local state is unrecoverable, e.g. when [MlsCentral::decrypt_message] keeps
+    /// failing with [CryptoError::WrongEpoch] or the conversation turned [MlsConversationHealth::Broken](crate::prelude::MlsConversationHealth::Broken).
+    /// The conversation is wiped locally, see [MlsCentral::wipe_conversation], then joined again by external commit
+    /// with the same custom configuration and the same kind of credential.
+    ///
+    /// As with [MlsCentral::join_by_external_commit], the returned commit has to be sent to the Delivery Service and
+    /// then [MlsCentral::merge_pending_group_from_external_commit] called once it is accepted. Since the local state is
+    /// gone, retrying with a fresher `GroupInfo` is the only way forward if it is rejected.
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `group_info` - the current GroupInfo of the conversation, fetched from the Delivery Service
+    ///
+    /// # Errors
+    /// [CryptoError::ConsumerError] when the GroupInfo is not the one of the conversation, plus the errors of
+    /// [MlsCentral::wipe_conversation] and [MlsCentral::join_by_external_commit]
+    pub async fn recover_conversation(
+        &mut self,
+        id: &ConversationId,
+        group_info: VerifiableGroupInfo,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        if group_info.group_id().as_slice() != id.as_slice() {
+            return Err(CryptoError::ConsumerError);
+        }
+
+        let (custom_cfg, credential_type) = {
+            let conversation = self.get_conversation(id).await?;
+            let conversation = conversation.read().await;
+            (conversation.custom_configuration(), conversation.own_credential_type()?)
+        };
+
+        self.wipe_conversation(id).await?;
+        self.join_by_external_commit(group_info, custom_cfg, credential_type)
+            .await
+    }
+
+    /// Joins many conversations by external commit at once, e.g. after restoring a backup made on another device,
+    /// which holds the conversation ids but no usable MLS state. The external commits are generated concurrently, at
+    /// most [DEFAULT_COMMIT_CONCURRENCY] at a time, and a conversation failing to be joined does not prevent the
+    /// others from being joined. A conversation listed more than once is only joined once.
+    ///
+    /// As with [MlsCentral::join_by_external_commit], every commit has to be sent to the Delivery Service and then
+    /// [MlsCentral::merge_pending_group_from_external_commit] called once it is accepted. A stale local state of a
+    /// conversation is only replaced when merging.
+    ///
+    /// # Arguments
+    /// * `group_infos` - the conversations along with their current GroupInfo, fetched from the Delivery Service
+    /// * `custom_cfg` - configuration of the conversations
+    /// * `credential_type` - see [MlsCentral::join_by_external_commit]
+    ///
+    /// # Return type
+    /// The external commit of every conversation, or why it could not be generated, in the order of `group_infos`.
+    /// [CryptoError::ConsumerError] when the GroupInfo is not the one of the conversation
+    ///
+    /// # Errors
+    /// [CryptoError::MlsNotInitialized] when the client is not initialized, in which case nothing is joined
+    pub async fn rejoin_all_from_group_infos(
+        &mut self,
+        group_infos: Vec<(ConversationId, VerifiableGroupInfo)>,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<Vec<(ConversationId, CryptoResult<MlsConversationInitBundle>)>> {
+        let mls_client = self.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
+        let backend = &self.mls_backend;
+
+        // credentials might have to be created, which can't be done concurrently
+        let mut seen = HashSet::with_capacity(group_infos.len());
+        let mut joins = Vec::with_capacity(group_infos.len());
+        for (id, group_info) in group_infos {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let cb = async {
+                if group_info.group_id().as_slice() != id.as_slice() {
+                    return Err(CryptoError::ConsumerError);
+                }
+                MlsConversationConfiguration::check_group_extensions(group_info.extensions())?;
+                let cs: MlsCiphersuite = group_info.ciphersuite().into();
+                mls_client
+                    .get_most_recent_or_create_credential_bundle(backend, cs.signature_algorithm(), credential_type)
+                    .await
+                    .cloned()
+            }
+            .await;
+            joins.push((id, group_info, cb));
+        }
+
+        let custom_cfg = &custom_cfg;
+        Ok(futures_util::stream::iter(joins)
+            .map(|(id, group_info, cb)| async move {
+                let joined = match cb {
+                    Ok(cb) => {
+                        Self::join_with_credential_bundle(backend, &cb, group_info, None, custom_cfg.clone()).await
+                    }
+                    Err(e) => Err(e),
+                };
+                (id, joined)
+            })
+            .buffered(DEFAULT_COMMIT_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
     pub(crate) async fn pending_group_exists(&self, id: &ConversationId) -> bool {
         self.mls_backend
             .borrow_keystore()
@@ -227,6 +450,24 @@ impl MlsCentral {
 }
 
 impl MlsConversation {
+    /// Custom configuration of the conversation. What the group itself holds prevails over the configuration this
+    /// instance has been given, which is the default one once the conversation has been restored
+    fn custom_configuration(&self) -> MlsCustomConfiguration {
+        let group_config = self.group.configuration();
+        let ratchet_config = group_config.sender_ratchet_configuration();
+        let wire_policy = if group_config.wire_format_policy() == PURE_CIPHERTEXT_WIRE_FORMAT_POLICY {
+            MlsWirePolicy::Ciphertext
+        } else {
+            MlsWirePolicy::Plaintext
+        };
+        MlsCustomConfiguration {
+            wire_policy,
+            out_of_order_tolerance: ratchet_config.out_of_order_tolerance(),
+            maximum_forward_distance: ratchet_config.maximum_forward_distance(),
+            ..self.configuration.custom.clone()
+        }
+    }
+
     pub(crate) async fn validate_external_commit(
         &self,
         commit: &StagedCommit,
@@ -234,6 +475,7 @@ impl MlsConversation {
         parent_conversation: Option<&GroupStoreValue<MlsConversation>>,
         backend: &MlsCryptoProvider,
         callbacks: Option<&dyn CoreCryptoCallbacks>,
+        leeway: std::time::Duration,
     ) -> CryptoResult<()> {
         // i.e. has this commit been created by [MlsCentral::join_by_external_commit] ?
         let is_external_init = commit.queued_proposals().any(|p| {
@@ -287,7 +529,7 @@ impl MlsConversation {
                     matches!(credential.credential_type(), CredentialType::X509).then(|| credential.clone())
                 })
                 .collect();
-            let state = compute_state(credentials.iter(), backend, MlsCredentialType::X509);
+            let state = compute_state(credentials.iter(), backend, MlsCredentialType::X509, leeway);
             if state != E2eiConversationState::Verified {
                 // FIXME: Uncomment when PKI env can be seeded - the computation is still done to assess performance and impact of the validations
                 // return Err(CryptoError::InvalidCertificateChain);
@@ -306,7 +548,7 @@ pub mod tests {
 
     use core_crypto_keystore::{CryptoKeystoreError, CryptoKeystoreMls, MissingKeyErrorKind};
 
-    use crate::prelude::MlsConversationConfiguration;
+    use crate::prelude::{MlsCiphersuite, MlsConversationConfiguration};
     use crate::{prelude::MlsConversationInitBundle, test_utils::*, CryptoError, MlsError};
 
     wasm_bindgen_test_configure!(run_in_browser);
@@ -418,6 +660,68 @@ pub mod tests {
         .await
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_join_from_group_info_and_ratchet_tree(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    let group_info = alice_central.mls_central.get_group_info_without_ratchet_tree(&id).await;
+                    let ratchet_tree: RatchetTreeIn = alice_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .group
+                        .export_ratchet_tree()
+                        .into();
+
+                    // the ratchet tree is required when the GroupInfo does not embed it
+                    assert!(bob_central
+                        .mls_central
+                        .join_by_external_commit(group_info.clone(), case.custom_cfg(), case.credential_type)
+                        .await
+                        .is_err());
+
+                    let MlsConversationInitBundle {
+                        conversation_id,
+                        commit,
+                        ..
+                    } = bob_central
+                        .mls_central
+                        .join_from_group_info(group_info, ratchet_tree, case.custom_cfg(), case.credential_type)
+                        .await
+                        .unwrap();
+                    assert_eq!(conversation_id, id);
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, &commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    bob_central
+                        .mls_central
+                        .merge_pending_group_from_external_commit(&id)
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn join_by_external_commit_should_be_retriable(case: TestCase) {
@@ -1073,4 +1377,222 @@ pub mod tests {
         )
         .await
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_recover_conversation(mut case: TestCase) {
+        case.cfg.custom.out_of_order_tolerance = 7;
+        case.cfg.custom.key_rotation_span = Some(std::time::Duration::from_secs(3600));
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // Bob misses a commit and can no longer talk with Alice
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_err());
+
+                    // a GroupInfo of another conversation is rejected and leaves the conversation untouched
+                    let other_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&other_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let other_group_info = alice_central.mls_central.get_group_info(&other_id).await;
+                    let recovery = bob_central
+                        .mls_central
+                        .recover_conversation(&id, other_group_info)
+                        .await;
+                    assert!(matches!(recovery.unwrap_err(), CryptoError::ConsumerError));
+                    assert!(bob_central.mls_central.get_conversation(&id).await.is_ok());
+
+                    // Bob restarts: only what the group and the keystore hold is left of the configuration
+                    bob_central.mls_central.restore_from_disk().await.unwrap();
+
+                    let group_info = alice_central.mls_central.get_group_info(&id).await;
+                    let MlsConversationInitBundle { commit, .. } = bob_central
+                        .mls_central
+                        .recover_conversation(&id, group_info)
+                        .await
+                        .unwrap();
+                    assert!(bob_central.mls_central.get_conversation(&id).await.is_err());
+
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, &commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    bob_central
+                        .mls_central
+                        .merge_pending_group_from_external_commit(&id)
+                        .await
+                        .unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+
+                    let bob_conv = bob_central.mls_central.get_conversation_unchecked(&id).await;
+                    assert_eq!(bob_conv.members().len(), 2);
+                    assert_eq!(bob_conv.own_credential_type().unwrap(), case.credential_type);
+                    let bob_cfg = bob_conv.custom_configuration();
+                    assert_eq!(bob_cfg.wire_policy, case.cfg.custom.wire_policy);
+                    assert_eq!(bob_cfg.out_of_order_tolerance, 7);
+                    assert_eq!(bob_cfg.key_rotation_span, case.cfg.custom.key_rotation_span);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_rejoin_all_from_group_infos(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let ids = (0..3).map(|_| conversation_id()).collect::<Vec<_>>();
+                    for id in &ids {
+                        alice_central
+                            .mls_central
+                            .new_conversation(id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        // Bob restores a backup holding the conversation ids only
+                        bob_central.mls_central.wipe_conversation(id).await.unwrap();
+                    }
+
+                    let mut group_infos = vec![];
+                    for id in &ids {
+                        group_infos.push((id.clone(), alice_central.mls_central.get_group_info(id).await));
+                    }
+                    // a GroupInfo of another conversation is rejected without preventing the others from being joined
+                    let unknown_id = conversation_id();
+                    let group_info = alice_central.mls_central.get_group_info(&ids[0]).await;
+                    group_infos.insert(1, (unknown_id.clone(), group_info));
+
+                    let results = bob_central
+                        .mls_central
+                        .rejoin_all_from_group_infos(group_infos, case.custom_cfg(), case.credential_type)
+                        .await
+                        .unwrap();
+                    assert_eq!(results.len(), 4);
+                    assert_eq!(results[1].0, unknown_id);
+                    assert!(matches!(results[1].1, Err(CryptoError::ConsumerError)));
+                    assert!(!bob_central.mls_central.pending_group_exists(&unknown_id).await);
+
+                    for (id, result) in results.into_iter().filter(|(id, _)| id != &unknown_id) {
+                        let MlsConversationInitBundle { commit, .. } = result.unwrap();
+                        alice_central
+                            .mls_central
+                            .decrypt_message(&id, &commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .merge_pending_group_from_external_commit(&id)
+                            .await
+                            .unwrap();
+                        assert!(bob_central
+                            .mls_central
+                            .try_talk_to(&id, &mut alice_central.mls_central)
+                            .await
+                            .is_ok());
+                    }
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn join_by_external_commit_pinned_should_check_ciphersuite(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    let other = if case.ciphersuite() == MlsCiphersuite::default() {
+                        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256.into()
+                    } else {
+                        MlsCiphersuite::default()
+                    };
+                    let group_info = alice_central.mls_central.get_group_info(&id).await;
+                    let error = bob_central
+                        .mls_central
+                        .join_by_external_commit_pinned(group_info, case.custom_cfg(), case.credential_type, other)
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(
+                        error,
+                        CryptoError::GroupCiphersuiteMismatch { expected, actual }
+                            if expected == u16::from(other) && actual == u16::from(case.ciphersuite())
+                    ));
+                    assert!(!bob_central.mls_central.pending_group_exists(&id).await);
+
+                    let group_info = alice_central.mls_central.get_group_info(&id).await;
+                    let MlsConversationInitBundle { commit, .. } = bob_central
+                        .mls_central
+                        .join_by_external_commit_pinned(
+                            group_info,
+                            case.custom_cfg(),
+                            case.credential_type,
+                            case.ciphersuite(),
+                        )
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&id, &commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    bob_central
+                        .mls_central
+                        .merge_pending_group_from_external_commit(&id)
+                        .await
+                        .unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
 }
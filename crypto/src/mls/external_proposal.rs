@@ -2,7 +2,10 @@ use std::collections::HashSet;
 
 use openmls::{
     group::QueuedProposal,
-    prelude::{GroupEpoch, GroupId, JoinProposal, LeafNodeIndex, MlsMessageOut, Proposal, Sender},
+    prelude::{
+        ExternalProposal, GroupEpoch, GroupId, JoinProposal, LeafNodeIndex, MlsMessageOut, Proposal, Sender,
+        SenderExtensionIndex,
+    },
 };
 
 use crate::{
@@ -55,6 +58,20 @@ impl MlsConversation {
         Ok(())
     }
 
+    /// Validates the proposal if it is a `Remove` one issued by an external sender, typically by the delivery
+    /// service when a device is deleted: the removed client has to be a member of the group. Once stored, such a
+    /// proposal is included in the next commit like any other pending proposal.
+    pub(crate) fn validate_external_remove_proposal(&self, proposal: &QueuedProposal) -> CryptoResult<()> {
+        if let (Sender::External(_), Proposal::Remove(remove_proposal)) = (proposal.sender(), proposal.proposal()) {
+            // the signature has already been verified by openmls against the external senders of the group
+            let is_member = self.group.members().any(|m| m.index == remove_proposal.removed());
+            if !is_member {
+                return Err(CryptoError::UnauthorizedExternalRemoveProposal);
+            }
+        }
+        Ok(())
+    }
+
     /// Get actual group members and subtract pending remove proposals
     pub fn members_in_next_epoch(&self) -> Vec<ClientId> {
         let pending_removals = self.pending_removals();
@@ -137,6 +154,65 @@ impl MlsCentral {
 
         Ok(JoinProposal::new(kp, group_id, epoch, &cb.signature_key).map_err(MlsError::from)?)
     }
+
+    /// Crafts a new external Remove proposal, as the delivery service does when a device is deleted in order to
+    /// evict it from the group. One of this client's credentials has to be an external sender of the group.
+    /// This is meant for tooling acting as a delivery service.
+    ///
+    /// # Arguments
+    /// * `conversation_id` - the group/conversation id
+    /// * `client_id` - the client to remove from the group
+    ///
+    /// # Return type
+    /// Returns a message with the proposal to remove the client
+    ///
+    /// # Errors
+    /// If the conversation or the client cannot be found, or if none of this client's credentials is an external
+    /// sender of the group. Errors resulting from the creation of the proposal within OpenMls.
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn new_external_remove_proposal(
+        &mut self,
+        conversation_id: &ConversationId,
+        client_id: ClientId,
+    ) -> CryptoResult<MlsMessageOut> {
+        let conversation = self.get_conversation(conversation_id).await?;
+        let conversation = conversation.read().await;
+
+        let removed = conversation
+            .group
+            .members()
+            .find(|m| m.credential.identity() == client_id.as_slice())
+            .ok_or(CryptoError::ClientNotFound(client_id))?
+            .index;
+
+        let external_senders = conversation
+            .group
+            .group_context_extensions()
+            .external_senders()
+            .ok_or(CryptoError::MissingExternalSenderExtension)?;
+        let sc = conversation.ciphersuite().signature_algorithm();
+        let (sender_index, cb) = self
+            .mls_client()?
+            .identities
+            .iter()
+            .filter(|(cb_sc, _)| *cb_sc == sc)
+            .find_map(|(_, cb)| {
+                external_senders
+                    .iter()
+                    .position(|s| s.signature_key().as_slice() == cb.signature_key.public())
+                    .map(|i| (i, cb))
+            })
+            .ok_or(CryptoError::UnauthorizedExternalRemoveProposal)?;
+
+        Ok(ExternalProposal::new_remove(
+            removed,
+            conversation.group.group_id().clone(),
+            conversation.group.epoch(),
+            &cb.signature_key,
+            SenderExtensionIndex::new(sender_index as u32),
+        )
+        .map_err(MlsError::from)?)
+    }
 }
 
 #[cfg(test)]
@@ -243,4 +319,160 @@ pub mod tests {
             .await
         }
     }
+
+    mod remove {
+        use super::*;
+        use crate::prelude::{CryptoError, MlsCentral, MlsConversationConfiguration};
+        use openmls::prelude::{
+            Credential, ExternalProposal, ExternalSender, GroupId, LeafNodeIndex, SenderExtensionIndex,
+            SignaturePublicKey,
+        };
+
+        /// Makes `central` an external sender of the conversation, as the delivery service would be
+        fn ds_cfg(case: &TestCase, central: &MlsCentral) -> MlsConversationConfiguration {
+            let ds_key = central
+                .client_public_key(case.ciphersuite(), case.credential_type)
+                .unwrap();
+            let mut cfg = case.cfg.clone();
+            cfg.external_senders = vec![ExternalSender::new(
+                SignaturePublicKey::from(ds_key),
+                Credential::new_basic(b"ds".to_vec()),
+            )];
+            cfg
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        async fn ds_should_externally_propose_removing_a_deleted_client(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob", "charlie"],
+                move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        let cfg = ds_cfg(&case, &alice_central.mls_central);
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, cfg)
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(
+                                &case,
+                                &id,
+                                [&mut bob_central.mls_central, &mut charlie_central.mls_central],
+                            )
+                            .await
+                            .unwrap();
+
+                        // only an external sender can do it
+                        let charlie_id = charlie_central.mls_central.get_client_id();
+                        let not_ds = bob_central
+                            .mls_central
+                            .new_external_remove_proposal(&id, charlie_id.clone())
+                            .await;
+                        assert!(matches!(
+                            not_ds.unwrap_err(),
+                            CryptoError::UnauthorizedExternalRemoveProposal
+                        ));
+
+                        // charlie's device got deleted, the DS proposes to remove it
+                        let external_remove = alice_central
+                            .mls_central
+                            .new_external_remove_proposal(&id, charlie_id)
+                            .await
+                            .unwrap()
+                            .to_bytes()
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, &external_remove)
+                            .await
+                            .unwrap();
+                        charlie_central
+                            .mls_central
+                            .decrypt_message(&id, &external_remove)
+                            .await
+                            .unwrap();
+
+                        // the proposal is included in the next commit
+                        let commit = bob_central
+                            .mls_central
+                            .commit_pending_proposals(&id)
+                            .await
+                            .unwrap()
+                            .unwrap()
+                            .commit;
+                        bob_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(bob_central.mls_central.members_count(&id).await, 2);
+
+                        let decrypted = charlie_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert!(!decrypted.is_active);
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        async fn should_fail_when_external_remove_proposal_targets_a_non_member(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        let cfg = ds_cfg(&case, &alice_central.mls_central);
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, cfg)
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let epoch = bob_central
+                            .mls_central
+                            .get_conversation_unchecked(&id)
+                            .await
+                            .group
+                            .epoch();
+                        let cb = alice_central
+                            .mls_central
+                            .mls_client()
+                            .unwrap()
+                            .find_most_recent_credential_bundle(case.signature_scheme(), case.credential_type)
+                            .unwrap();
+                        let external_remove = ExternalProposal::new_remove(
+                            LeafNodeIndex::new(42),
+                            GroupId::from_slice(&id),
+                            epoch,
+                            &cb.signature_key,
+                            SenderExtensionIndex::new(0),
+                        )
+                        .unwrap();
+
+                        let result = bob_central
+                            .mls_central
+                            .decrypt_message(&id, external_remove.to_bytes().unwrap())
+                            .await;
+                        assert!(matches!(
+                            result.unwrap_err(),
+                            CryptoError::UnauthorizedExternalRemoveProposal
+                        ));
+                    })
+                },
+            )
+            .await
+        }
+    }
 }
@@ -0,0 +1,316 @@
+//! The MLS client identity, i.e. its credentials and signature keys, can be carried over to another device, for
+//! example when a user moves to a new phone. It is exported encrypted with a key supplied by the consumer and can only
+//! be imported in a keystore holding no identity yet, or the very same one. Both operations have to be explicitly
+//! allowed in the [crate::prelude::MlsCentralConfiguration].
+//!
+//! A backup is laid out as `version (1 byte) || salt (16 bytes) || nonce (12 bytes) || AES-256-GCM ciphertext`, the
+//! encryption key being derived from the supplied key and the salt with HKDF-SHA256.
+
+use std::collections::HashSet;
+
+use core_crypto_keystore::entities::{EntityFindParams, MlsCredential, MlsSignatureKeyPair};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    random::OpenMlsRand,
+    types::{AeadType, HashType},
+    OpenMlsCryptoProvider,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::prelude::{ClientId, ClientIdentifier, CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsError};
+
+const BACKUP_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Length of the key the consumer has to supply
+const IDENTITY_BACKUP_KEY_LEN: usize = 32;
+const KDF_INFO: &[u8] = b"core-crypto identity backup";
+
+#[derive(serde::Serialize, serde::Deserialize, Zeroize)]
+#[zeroize(drop)]
+struct IdentityBackup {
+    client_id: Vec<u8>,
+    /// TLS-serialized credentials, oldest first
+    credentials: Vec<Vec<u8>>,
+    signature_keypairs: Vec<BackupSignatureKeyPair>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Zeroize)]
+#[zeroize(drop)]
+struct BackupSignatureKeyPair {
+    signature_scheme: u16,
+    pk: Vec<u8>,
+    keypair: Vec<u8>,
+}
+
+impl MlsCentral {
+    /// Exports the identity of the client, encrypted with `backup_key`, so that it can be imported on another device
+    /// with [MlsCentral::import_identity]. Conversations are not part of the backup
+    ///
+    /// # Arguments
+    /// * `backup_key` - 32 bytes key the backup is encrypted with
+    ///
+    /// # Errors
+    /// [CryptoError::IdentityExportNotAllowed] unless allowed in the configuration, [CryptoError::MlsNotInitialized]
+    /// and KeyStore errors
+    pub async fn export_identity(&self, backup_key: &[u8]) -> CryptoResult<Vec<u8>> {
+        self.ensure_identity_export_allowed()?;
+        let client_id = self.mls_client()?.id().clone();
+
+        let keystore = self.mls_backend.key_store();
+        let mut credentials = keystore
+            .find_all::<MlsCredential>(EntityFindParams::default())
+            .await?
+            .into_iter()
+            .filter(|c| c.id == client_id.as_slice())
+            .collect::<Vec<_>>();
+        credentials.sort_by_key(|c| c.created_at);
+        let signature_keypairs = keystore
+            .find_all::<MlsSignatureKeyPair>(EntityFindParams::default())
+            .await?
+            .iter()
+            .filter(|kp| kp.credential_id == client_id.as_slice())
+            .map(|kp| BackupSignatureKeyPair {
+                signature_scheme: kp.signature_scheme,
+                pk: kp.pk.clone(),
+                keypair: kp.keypair.clone(),
+            })
+            .collect();
+
+        let backup = IdentityBackup {
+            client_id: client_id.to_vec(),
+            credentials: credentials.iter().map(|c| c.credential.clone()).collect(),
+            signature_keypairs,
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&backup)?);
+        self.seal_identity_backup(&plaintext, backup_key)
+    }
+
+    /// Imports an identity exported with [MlsCentral::export_identity] and initializes the MLS client with it. This
+    /// has to be done instead of initializing the client, i.e. on a [MlsCentral] created without a client id.
+    /// Importing the identity the keystore already contains is a no-op
+    ///
+    /// # Arguments
+    /// * `backup` - the exported identity
+    /// * `backup_key` - key the backup has been encrypted with
+    /// * `ciphersuites` - all ciphersuites the client is supposed to support
+    ///
+    /// # Errors
+    /// [CryptoError::IdentityExportNotAllowed] unless allowed in the configuration,
+    /// [CryptoError::MalformedIdentityBackup] when the backup cannot be decrypted with `backup_key`,
+    /// [CryptoError::IdentityConflict] when the keystore already contains another identity and KeyStore errors
+    pub async fn import_identity(
+        &mut self,
+        backup: &[u8],
+        backup_key: &[u8],
+        ciphersuites: Vec<MlsCiphersuite>,
+    ) -> CryptoResult<()> {
+        self.ensure_identity_export_allowed()?;
+        let plaintext = self.open_identity_backup(backup, backup_key)?;
+        let backup =
+            serde_json::from_slice::<IdentityBackup>(&plaintext).map_err(|_| CryptoError::MalformedIdentityBackup)?;
+        let client_id = ClientId::from(backup.client_id.as_slice());
+
+        let keystore = self.mls_backend.key_store();
+        let stored_keypairs = keystore
+            .find_all::<MlsSignatureKeyPair>(EntityFindParams::default())
+            .await?;
+        let stored_credentials = keystore.find_all::<MlsCredential>(EntityFindParams::default()).await?;
+        if !stored_keypairs.is_empty() || !stored_credentials.is_empty() {
+            let backup_pks = backup
+                .signature_keypairs
+                .iter()
+                .map(|kp| kp.pk.as_slice())
+                .collect::<HashSet<_>>();
+            let stored_pks = stored_keypairs
+                .iter()
+                .map(|kp| kp.pk.as_slice())
+                .collect::<HashSet<_>>();
+            let same_identity = stored_pks == backup_pks
+                && stored_keypairs.iter().all(|kp| kp.credential_id == backup.client_id)
+                && stored_credentials.iter().all(|c| c.id == backup.client_id);
+            if !same_identity {
+                return Err(CryptoError::IdentityConflict);
+            }
+        } else {
+            for kp in &backup.signature_keypairs {
+                keystore
+                    .save(MlsSignatureKeyPair {
+                        signature_scheme: kp.signature_scheme,
+                        pk: kp.pk.clone(),
+                        keypair: kp.keypair.clone(),
+                        credential_id: backup.client_id.clone(),
+                    })
+                    .await?;
+            }
+            // the creation date is set when inserting, which keeps credentials in order
+            for credential in &backup.credentials {
+                keystore
+                    .save(MlsCredential {
+                        id: backup.client_id.clone(),
+                        credential: credential.clone(),
+                        created_at: 0,
+                    })
+                    .await?;
+            }
+        }
+
+        if self.mls_client.is_none() {
+            self.mls_init(ClientIdentifier::Basic(client_id), ciphersuites, None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn ensure_identity_export_allowed(&self) -> CryptoResult<()> {
+        if self.allow_identity_export {
+            Ok(())
+        } else {
+            Err(CryptoError::IdentityExportNotAllowed)
+        }
+    }
+
+    fn seal_identity_backup(&self, plaintext: &[u8], backup_key: &[u8]) -> CryptoResult<Vec<u8>> {
+        let salt = self.mls_backend.rand().random_vec(SALT_LEN)?;
+        let nonce = self.mls_backend.rand().random_vec(NONCE_LEN)?;
+        let key = self.derive_identity_backup_key(backup_key, &salt)?;
+        let ciphertext = self
+            .mls_backend
+            .crypto()
+            .aead_encrypt(AeadType::Aes256Gcm, &key, plaintext, &nonce, &[BACKUP_VERSION])
+            .map_err(MlsError::from)?;
+
+        let mut backup = Vec::with_capacity(1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+        backup.push(BACKUP_VERSION);
+        backup.extend_from_slice(&salt);
+        backup.extend_from_slice(&nonce);
+        backup.extend_from_slice(&ciphertext);
+        Ok(backup)
+    }
+
+    fn open_identity_backup(&self, backup: &[u8], backup_key: &[u8]) -> CryptoResult<Zeroizing<Vec<u8>>> {
+        match backup.split_first() {
+            Some((&BACKUP_VERSION, rest)) if rest.len() > SALT_LEN + NONCE_LEN => {
+                let (salt, rest) = rest.split_at(SALT_LEN);
+                let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+                let key = self.derive_identity_backup_key(backup_key, salt)?;
+                self.mls_backend
+                    .crypto()
+                    .aead_decrypt(AeadType::Aes256Gcm, &key, ciphertext, nonce, &[BACKUP_VERSION])
+                    .map(Zeroizing::new)
+                    .map_err(|_| CryptoError::MalformedIdentityBackup)
+            }
+            _ => Err(CryptoError::MalformedIdentityBackup),
+        }
+    }
+
+    fn derive_identity_backup_key(&self, backup_key: &[u8], salt: &[u8]) -> CryptoResult<Zeroizing<Vec<u8>>> {
+        if backup_key.len() != IDENTITY_BACKUP_KEY_LEN {
+            return Err(CryptoError::InvalidByteArrayError(IDENTITY_BACKUP_KEY_LEN));
+        }
+        let crypto = self.mls_backend.crypto();
+        let prk = crypto
+            .hkdf_extract(HashType::Sha2_256, salt, backup_key)
+            .map_err(MlsError::from)?;
+        let key = crypto
+            .hkdf_expand(HashType::Sha2_256, prk.as_slice(), KDF_INFO, IDENTITY_BACKUP_KEY_LEN)
+            .map_err(MlsError::from)?;
+        Ok(Zeroizing::new(key.as_slice().to_vec()))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn configuration(path: String, client_id: Option<&str>, allow_identity_export: bool) -> MlsCentralConfiguration {
+        let mut configuration = MlsCentralConfiguration::try_new(
+            path,
+            "test".to_string(),
+            client_id.map(ClientId::from),
+            vec![MlsCiphersuite::default()],
+            None,
+            Some(2),
+        )
+        .unwrap();
+        configuration.set_allow_identity_export(allow_identity_export);
+        configuration
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn should_carry_the_identity_over_to_another_device() {
+        run_tests(|[alice_path, new_device_path, bob_path, charlie_path]| {
+            Box::pin(async move {
+                let cs = MlsCiphersuite::default();
+                let backup_key = [42u8; 32];
+
+                // not allowed unless explicitly configured
+                let charlie = MlsCentral::try_new(configuration(charlie_path, Some("charlie"), false))
+                    .await
+                    .unwrap();
+                assert!(matches!(
+                    charlie.export_identity(&backup_key).await.unwrap_err(),
+                    CryptoError::IdentityExportNotAllowed
+                ));
+
+                let alice = MlsCentral::try_new(configuration(alice_path, Some("alice"), true))
+                    .await
+                    .unwrap();
+                let backup = alice.export_identity(&backup_key).await.unwrap();
+                let alice_pk = alice.client_public_key(cs, MlsCredentialType::Basic).unwrap();
+
+                let mut new_device = MlsCentral::try_new(configuration(new_device_path, None, true))
+                    .await
+                    .unwrap();
+                assert!(matches!(
+                    new_device
+                        .import_identity(&backup, &[0u8; 32], vec![cs])
+                        .await
+                        .unwrap_err(),
+                    CryptoError::MalformedIdentityBackup
+                ));
+                assert!(new_device.mls_client.is_none());
+
+                new_device
+                    .import_identity(&backup, &backup_key, vec![cs])
+                    .await
+                    .unwrap();
+                assert_eq!(new_device.client_id().unwrap(), ClientId::from("alice"));
+                assert_eq!(
+                    new_device.client_public_key(cs, MlsCredentialType::Basic).unwrap(),
+                    alice_pk
+                );
+                assert!(new_device
+                    .get_or_create_client_keypackages(cs, MlsCredentialType::Basic, 1)
+                    .await
+                    .is_ok());
+
+                // importing the same identity again does nothing
+                new_device
+                    .import_identity(&backup, &backup_key, vec![cs])
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    new_device.client_public_key(cs, MlsCredentialType::Basic).unwrap(),
+                    alice_pk
+                );
+
+                // but another identity cannot be replaced
+                let mut bob = MlsCentral::try_new(configuration(bob_path, Some("bob"), true))
+                    .await
+                    .unwrap();
+                assert!(matches!(
+                    bob.import_identity(&backup, &backup_key, vec![cs]).await.unwrap_err(),
+                    CryptoError::IdentityConflict
+                ));
+                assert_eq!(bob.client_id().unwrap(), ClientId::from("bob"));
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,268 @@
+//! CoreCrypto accumulates work which does not have to be done right away: expired KeyPackages, key material due for
+//! rotation, messages buffered for conversations which are gone, transient entities which were abandoned, storage
+//! left over by deleted entities. Rather than
+//! doing it on the hot path, [MlsCentral::pending_maintenance_tasks] lists it so that platforms can schedule it with
+//! their background task facilities (e.g. BGTaskScheduler or WorkManager) and run it with
+//! [MlsCentral::run_maintenance].
+
+use core_crypto_keystore::{
+    entities::{EntityFindParams, MlsPendingMessage, PersistedMlsPendingGroup},
+    CryptoKeystoreMls as _,
+};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle};
+
+/// Storage left over by deleted entities above which vacuuming the keystore is suggested, in bytes
+pub const MAINTENANCE_VACUUM_THRESHOLD: u64 = 1_000_000;
+
+/// Work to run with [MlsCentral::run_maintenance]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MlsMaintenanceTask {
+    /// Deletes the expired KeyPackages along with their private keys
+    PruneExpiredKeyPackages,
+    /// Rotates the own key material of a conversation whose
+    /// [key_rotation_span](crate::prelude::MlsCustomConfiguration::key_rotation_span) elapsed
+    UpdateKeyingMaterial(ConversationId),
+    /// Discards the messages buffered for conversations which no longer exist locally, they can never be decrypted
+    ExpireBufferedMessages,
+    /// Deletes the transient entities left behind for too long, e.g. the pending group of an external commit which
+    /// was neither merged nor cleared. See [core_crypto_keystore::connection::Connection::purge_expired]
+    PurgeExpiredEntities,
+    /// Gives the storage left over by deleted entities back to the filesystem
+    VacuumKeystore,
+}
+
+/// How soon a [MlsMaintenanceTask] should run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MlsMaintenancePriority {
+    /// Whenever the device is idle
+    Low,
+    /// In the next background slot
+    Normal,
+    /// As soon as possible, e.g. it affects the security of conversations
+    High,
+}
+
+/// A pending [MlsMaintenanceTask] along with its suggested priority
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsMaintenanceItem {
+    /// What to run
+    pub task: MlsMaintenanceTask,
+    /// How soon
+    pub priority: MlsMaintenancePriority,
+}
+
+impl MlsCentral {
+    /// Lists the maintenance work currently pending, the most urgent first. Each task can then be run with
+    /// [MlsCentral::run_maintenance], in any order. Nothing is changed by listing them.
+    ///
+    /// # Errors
+    /// KeyStore errors
+    pub async fn pending_maintenance_tasks(&self) -> CryptoResult<Vec<MlsMaintenanceItem>> {
+        let mut items = self
+            .conversations_needing_key_update()
+            .await?
+            .into_iter()
+            .map(|id| MlsMaintenanceItem {
+                task: MlsMaintenanceTask::UpdateKeyingMaterial(id),
+                priority: MlsMaintenancePriority::High,
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(client) = self.mls_client.as_ref() {
+            if client.expired_keypackages_count(&self.mls_backend).await? > 0 {
+                items.push(MlsMaintenanceItem {
+                    task: MlsMaintenanceTask::PruneExpiredKeyPackages,
+                    priority: MlsMaintenancePriority::Normal,
+                });
+            }
+        }
+
+        if !self.orphaned_buffered_messages().await?.is_empty() {
+            items.push(MlsMaintenanceItem {
+                task: MlsMaintenanceTask::ExpireBufferedMessages,
+                priority: MlsMaintenancePriority::Normal,
+            });
+        }
+
+        if self.mls_backend.key_store().expired_count().await? > 0 {
+            items.push(MlsMaintenanceItem {
+                task: MlsMaintenanceTask::PurgeExpiredEntities,
+                priority: MlsMaintenancePriority::Low,
+            });
+        }
+
+        if self.mls_backend.key_store().reclaimable_space().await? >= MAINTENANCE_VACUUM_THRESHOLD {
+            items.push(MlsMaintenanceItem {
+                task: MlsMaintenanceTask::VacuumKeystore,
+                priority: MlsMaintenancePriority::Low,
+            });
+        }
+
+        items.sort_by(|a, b| b.priority.cmp(&a.priority));
+        Ok(items)
+    }
+
+    /// Runs a task listed by [MlsCentral::pending_maintenance_tasks]. Running a task which is no longer pending is
+    /// harmless.
+    ///
+    /// Returns the commit of [MlsMaintenanceTask::UpdateKeyingMaterial], which has to be sent to the Delivery Service
+    /// then merged with [MlsCentral::commit_accepted] like any other. Nothing is returned for the other tasks.
+    ///
+    /// # Errors
+    /// When MLS has not been initialized, the errors of [MlsCentral::update_keying_material] and KeyStore errors
+    pub async fn run_maintenance(&mut self, task: &MlsMaintenanceTask) -> CryptoResult<Option<MlsCommitBundle>> {
+        match task {
+            MlsMaintenanceTask::PruneExpiredKeyPackages => {
+                self.mls_client
+                    .as_ref()
+                    .ok_or(CryptoError::MlsNotInitialized)?
+                    .prune_keypackages(&self.mls_backend, &[])
+                    .await?;
+            }
+            MlsMaintenanceTask::UpdateKeyingMaterial(id) => {
+                return self.update_keying_material(id).await.map(Some);
+            }
+            MlsMaintenanceTask::ExpireBufferedMessages => {
+                let keystore = self.mls_backend.key_store();
+                for id in self.orphaned_buffered_messages().await? {
+                    keystore.remove::<MlsPendingMessage, _>(&id).await?;
+                }
+            }
+            MlsMaintenanceTask::PurgeExpiredEntities => {
+                self.mls_backend.key_store().purge_expired().await?;
+            }
+            MlsMaintenanceTask::VacuumKeystore => self.mls_backend.key_store().vacuum().await?,
+        }
+        Ok(None)
+    }
+
+    /// Conversations which have buffered messages but are neither known locally nor being joined
+    async fn orphaned_buffered_messages(&self) -> CryptoResult<Vec<ConversationId>> {
+        let keystore = self.mls_backend.key_store();
+        let mut orphans = vec![];
+        for message in keystore
+            .find_all::<MlsPendingMessage>(EntityFindParams::default())
+            .await?
+        {
+            if orphans.contains(&message.id) {
+                continue;
+            }
+            let is_known = keystore.mls_group_exists(&message.id).await
+                || keystore.find::<PersistedMlsPendingGroup>(&message.id).await?.is_some();
+            if !is_known {
+                orphans.push(message.id);
+            }
+        }
+        Ok(orphans)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::{MlsConversationMetadata, MlsPendingMessage};
+    use openmls_traits::OpenMlsCryptoProvider;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_list_and_run_maintenance_tasks(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                let mut cfg = case.cfg.clone();
+                cfg.custom.key_rotation_span = Some(std::time::Duration::from_secs(3600));
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, cfg)
+                    .await
+                    .unwrap();
+                let pending = alice_central.mls_central.pending_maintenance_tasks().await.unwrap();
+                assert!(pending
+                    .iter()
+                    .all(|item| item.task == MlsMaintenanceTask::VacuumKeystore));
+
+                // key material due for rotation
+                let keystore = alice_central.mls_central.mls_backend.key_store();
+                let mut metadata = keystore.find::<MlsConversationMetadata>(&id).await.unwrap().unwrap();
+                metadata.last_key_rotation -= 7200;
+                keystore.save(metadata).await.unwrap();
+
+                // a message buffered for a conversation which is gone
+                keystore
+                    .save(MlsPendingMessage {
+                        id: conversation_id(),
+                        message: b"some message".to_vec(),
+                        created_at: 0,
+                    })
+                    .await
+                    .unwrap();
+
+                // expired KeyPackages
+                alice_central
+                    .mls_central
+                    .mls_client
+                    .as_mut()
+                    .unwrap()
+                    .set_keypackage_lifetime(std::time::Duration::from_secs(1));
+                alice_central
+                    .mls_central
+                    .set_clock_skew_leeway(std::time::Duration::ZERO);
+                alice_central
+                    .mls_central
+                    .get_or_create_client_keypackages(
+                        case.ciphersuite(),
+                        case.credential_type,
+                        INITIAL_KEYING_MATERIAL_COUNT + 1,
+                    )
+                    .await
+                    .unwrap();
+                async_std::task::sleep(std::time::Duration::from_secs(2)).await;
+
+                let pending = alice_central.mls_central.pending_maintenance_tasks().await.unwrap();
+                let tasks = pending
+                    .iter()
+                    .filter(|item| item.task != MlsMaintenanceTask::VacuumKeystore)
+                    .map(|item| (item.task.clone(), item.priority))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    tasks,
+                    vec![
+                        (
+                            MlsMaintenanceTask::UpdateKeyingMaterial(id.clone()),
+                            MlsMaintenancePriority::High
+                        ),
+                        (
+                            MlsMaintenanceTask::PruneExpiredKeyPackages,
+                            MlsMaintenancePriority::Normal
+                        ),
+                        (
+                            MlsMaintenanceTask::ExpireBufferedMessages,
+                            MlsMaintenancePriority::Normal
+                        ),
+                    ]
+                );
+
+                for item in pending {
+                    let commit = alice_central.mls_central.run_maintenance(&item.task).await.unwrap();
+                    assert_eq!(
+                        commit.is_some(),
+                        matches!(item.task, MlsMaintenanceTask::UpdateKeyingMaterial(_))
+                    );
+                }
+                alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                let pending = alice_central.mls_central.pending_maintenance_tasks().await.unwrap();
+                assert!(pending
+                    .iter()
+                    .all(|item| item.task == MlsMaintenanceTask::VacuumKeystore));
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,219 @@
+//! Runtime metrics of the MLS operations. With the `tracing` feature, spans are emitted around those operations and
+//! the keystore ones. The counters below are kept regardless, so that platforms without a tracing subscriber can poll
+//! them with [MlsCentral::metrics_snapshot].
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::mls::MlsCentral;
+
+/// Durations of the calls to an operation, see [MlsMetricsSnapshot]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MlsOperationMetrics {
+    /// Number of calls, successful or not
+    pub calls: u64,
+    /// Cumulated duration of the calls in microseconds
+    pub total_micros: u64,
+    /// Duration of the slowest call in microseconds
+    pub max_micros: u64,
+}
+
+/// Counters of the activity since [MlsCentral] was created, see [MlsCentral::metrics_snapshot]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MlsMetricsSnapshot {
+    /// Application messages encrypted
+    pub messages_encrypted: u64,
+    /// Messages decrypted, handshake ones included
+    pub messages_decrypted: u64,
+    /// Commits merged, be they own, incoming or external ones
+    pub epochs_advanced: u64,
+    /// Entities saved in the keystore
+    pub keystore_writes: u64,
+    /// Bytes of MLS group states and key material written in the keystore, before encryption
+    pub keystore_bytes_written: u64,
+    /// see [MlsCentral::encrypt_message]
+    pub encrypt: MlsOperationMetrics,
+    /// see [MlsCentral::decrypt_message]
+    pub decrypt: MlsOperationMetrics,
+    /// Creating a commit e.g. [MlsCentral::update_keying_material]
+    pub commit: MlsOperationMetrics,
+    /// see [MlsCentral::commit_accepted]
+    pub merge: MlsOperationMetrics,
+}
+
+/// Operations whose durations are recorded
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum MlsOperation {
+    Encrypt,
+    Decrypt,
+    Commit,
+    Merge,
+}
+
+#[derive(Debug, Default)]
+struct OperationCounters {
+    calls: AtomicU64,
+    total_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+impl OperationCounters {
+    fn snapshot(&self) -> MlsOperationMetrics {
+        MlsOperationMetrics {
+            calls: self.calls.load(Ordering::Relaxed),
+            total_micros: self.total_micros.load(Ordering::Relaxed),
+            max_micros: self.max_micros.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MlsMetrics {
+    messages_encrypted: AtomicU64,
+    messages_decrypted: AtomicU64,
+    epochs_advanced: AtomicU64,
+    operations: [OperationCounters; 4],
+}
+
+impl MlsMetrics {
+    /// Starts timing a call to `operation`, recorded once the returned timer is dropped
+    pub(crate) fn start(self: &Arc<Self>, operation: MlsOperation) -> OperationTimer {
+        OperationTimer {
+            metrics: self.clone(),
+            operation,
+            start: fluvio_wasm_timer::Instant::now(),
+        }
+    }
+
+    pub(crate) fn message_encrypted(&self) {
+        self.messages_encrypted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn message_decrypted(&self) {
+        self.messages_decrypted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn epoch_advanced(&self) {
+        self.epochs_advanced.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Records the duration of a call when dropped, hence however the call ends
+#[derive(Debug)]
+pub(crate) struct OperationTimer {
+    metrics: Arc<MlsMetrics>,
+    operation: MlsOperation,
+    start: fluvio_wasm_timer::Instant,
+}
+
+impl Drop for OperationTimer {
+    fn drop(&mut self) {
+        let micros = self.start.elapsed().as_micros() as u64;
+        let counters = &self.metrics.operations[self.operation as usize];
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        counters.total_micros.fetch_add(micros, Ordering::Relaxed);
+        counters.max_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+}
+
+impl MlsCentral {
+    /// Counters of the activity since this instance was created. They are only kept in memory
+    pub fn metrics_snapshot(&self) -> MlsMetricsSnapshot {
+        let keystore = self.mls_backend.key_store().metrics();
+        let operation = |op: MlsOperation| self.metrics.operations[op as usize].snapshot();
+        MlsMetricsSnapshot {
+            messages_encrypted: self.metrics.messages_encrypted.load(Ordering::Relaxed),
+            messages_decrypted: self.metrics.messages_decrypted.load(Ordering::Relaxed),
+            epochs_advanced: self.metrics.epochs_advanced.load(Ordering::Relaxed),
+            keystore_writes: keystore.writes,
+            keystore_bytes_written: keystore.bytes_written,
+            encrypt: operation(MlsOperation::Encrypt),
+            decrypt: operation(MlsOperation::Decrypt),
+            commit: operation(MlsOperation::Commit),
+            merge: operation(MlsOperation::Merge),
+        }
+    }
+
+    /// Starts (or stops with `None`) logging the keystore operations taking at least `threshold`, e.g. to find out
+    /// why sending messages is slow on a device. The last ones are listed in the
+    /// [diagnostic report](crate::CoreCrypto::generate_diagnostic_report). Disabled by default
+    pub fn set_keystore_slow_operation_threshold(&self, threshold: Option<std::time::Duration>) {
+        self.mls_backend.key_store().set_slow_operation_threshold(threshold);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_count_operations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let alice_before = alice_central.mls_central.metrics_snapshot();
+                    let bob_before = bob_central.mls_central.metrics_snapshot();
+
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let encrypted = alice_central
+                        .mls_central
+                        .encrypt_message(&id, b"Hello Bob")
+                        .await
+                        .unwrap();
+                    bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                    // failures are timed but not counted as processed messages
+                    assert!(bob_central.mls_central.decrypt_message(&id, b"garbage").await.is_err());
+
+                    let alice = alice_central.mls_central.metrics_snapshot();
+                    assert_eq!(alice.commit.calls, alice_before.commit.calls + 1);
+                    assert_eq!(alice.merge.calls, alice_before.merge.calls + 1);
+                    assert_eq!(alice.encrypt.calls, alice_before.encrypt.calls + 1);
+                    assert_eq!(alice.messages_encrypted, alice_before.messages_encrypted + 1);
+                    assert_eq!(alice.epochs_advanced, alice_before.epochs_advanced + 1);
+                    assert!(alice.merge.total_micros >= alice.merge.max_micros);
+                    assert!(alice.keystore_writes > alice_before.keystore_writes);
+                    assert!(alice.keystore_bytes_written > alice_before.keystore_bytes_written);
+
+                    let bob = bob_central.mls_central.metrics_snapshot();
+                    assert_eq!(bob.decrypt.calls, bob_before.decrypt.calls + 3);
+                    assert_eq!(bob.messages_decrypted, bob_before.messages_decrypted + 2);
+                    assert_eq!(bob.epochs_advanced, bob_before.epochs_advanced + 1);
+                })
+            },
+        )
+        .await
+    }
+}
@@ -1,11 +1,14 @@
+use openmls::prelude::KeyPackageIn;
 use openmls_traits::OpenMlsCryptoProvider;
 
-use mls_crypto_provider::{MlsCryptoProvider, MlsCryptoProviderConfiguration};
+use core_crypto_keystore::CryptoKeystoreError;
+use mls_crypto_provider::{MlsCryptoProvider, MlsCryptoProviderConfiguration, MlsProviderError};
 
 use crate::prelude::{
     identifier::ClientIdentifier, key_package::INITIAL_KEYING_MATERIAL_COUNT, Client, ClientId, ConversationId,
     CoreCryptoCallbacks, CryptoError, CryptoResult, MlsCentralConfiguration, MlsCiphersuite, MlsConversation,
-    MlsConversationConfiguration, MlsCredentialType, MlsError,
+    MlsConversationConfiguration, MlsConversationCreationMessage, MlsCredentialType, MlsError,
+    PersistenceFailureCallback, PublicKeyBundle,
 };
 
 pub(crate) mod buffer_external_commit;
@@ -13,14 +16,19 @@ pub(crate) mod ciphersuite;
 pub(crate) mod client;
 pub(crate) mod conversation;
 pub(crate) mod credential;
+pub(crate) mod deserialize;
 pub(crate) mod external_commit;
 pub(crate) mod external_proposal;
+pub(crate) mod identity_backup;
+pub(crate) mod maintenance;
+pub(crate) mod metrics;
 pub(crate) mod proposal;
+pub(crate) mod public_group_state;
 pub(crate) mod restore;
 
 // Prevents direct instantiation of [MlsCentralConfiguration]
 pub(crate) mod config {
-    use mls_crypto_provider::EntropySeed;
+    use mls_crypto_provider::{EntropySeed, FileProtectionClass, KeyProvider};
 
     use super::*;
 
@@ -41,6 +49,35 @@ pub(crate) mod config {
         pub ciphersuites: Vec<ciphersuite::MlsCiphersuite>,
         /// Number of [openmls::prelude::KeyPackage] to create when creating a MLS client. Default to [INITIAL_KEYING_MATERIAL_COUNT]
         pub nb_init_key_packages: Option<usize>,
+        /// Allows the client identity to leave the device through [MlsCentral::export_identity] and to be replaced by
+        /// [MlsCentral::import_identity]. Disabled by default
+        pub allow_identity_export: bool,
+        /// Rejects client ids not following Wire's qualified format when initializing the MLS client, see
+        /// [crate::prelude::QualifiedClientId]. Disabled by default
+        pub require_qualified_client_id: bool,
+        /// Opens the store even if another instance holds it, which then fails all its writes with
+        /// [CryptoError::StoreAlreadyInUse]. Meant for e.g. an app extension which has to run while the main app
+        /// is suspended. Disabled by default
+        pub take_over_store: bool,
+        /// Protects the master key of the store with the platform's secure hardware, in which case `identity_key` is
+        /// not used to encrypt the store. Unset by default
+        pub key_provider: Option<std::sync::Arc<dyn KeyProvider>>,
+        /// Does not keep the conversations in memory, for devices which can't afford it: each operation loads the
+        /// conversation from the keystore, and it is dropped once persisted. Conversations are then not restored at
+        /// startup, and in-memory bookkeeping such as [MlsCentral::ratchet_diagnostics] behaves as after a restart.
+        /// Disabled by default
+        pub disable_group_cache: bool,
+        /// iOS data protection class of the files of the store. When it doesn't allow opening them while the device
+        /// is locked, e.g. from a notification extension, initialization fails with [CryptoError::DeviceLocked].
+        /// Unset by default: the files get the class of their directory
+        pub file_protection: Option<FileProtectionClass>,
+        /// Keeps the salt of the store in the keychain and its header in plaintext, so that iOS doesn't kill the
+        /// suspended app holding it. Only with the `ios-wal-compat` feature, enabled by default then. It cannot be
+        /// changed for an existing store
+        pub wal_compat: bool,
+        /// Clock skew tolerated by time-based validations, see [MlsCentral::set_clock_skew_leeway]. Defaults to
+        /// [crate::prelude::CLOCK_SKEW_DEFAULT_LEEWAY]
+        pub clock_skew_leeway: std::time::Duration,
     }
 
     impl MlsCentralConfiguration {
@@ -55,6 +92,7 @@ pub(crate) mod config {
         ///
         /// # Errors
         /// Any empty string parameter will result in a [CryptoError::MalformedIdentifier] error.
+        /// Hybrid ciphersuites without the `pq-ciphersuites` feature result in a [CryptoError::UnsupportedCiphersuite] error.
         ///
         /// # Examples
         ///
@@ -102,6 +140,7 @@ pub(crate) mod config {
                     return Err(CryptoError::MalformedIdentifier("client_id"));
                 }
             }
+            ciphersuites.iter().try_for_each(MlsCiphersuite::ensure_supported)?;
             let external_entropy = entropy
                 .as_deref()
                 .map(|seed| &seed[..EntropySeed::EXPECTED_LEN])
@@ -114,6 +153,14 @@ pub(crate) mod config {
                 ciphersuites,
                 external_entropy,
                 nb_init_key_packages,
+                allow_identity_export: false,
+                require_qualified_client_id: false,
+                take_over_store: false,
+                key_provider: None,
+                disable_group_cache: false,
+                file_protection: None,
+                wal_compat: true,
+                clock_skew_leeway: client::key_package::CLOCK_SKEW_DEFAULT_LEEWAY,
             })
         }
 
@@ -122,6 +169,57 @@ pub(crate) mod config {
             self.external_entropy = Some(entropy);
         }
 
+        /// Allows exporting and importing the client identity, see [MlsCentralConfiguration::allow_identity_export]
+        pub fn set_allow_identity_export(&mut self, allow: bool) {
+            self.allow_identity_export = allow;
+        }
+
+        /// Rejects client ids which are not qualified, see [MlsCentralConfiguration::require_qualified_client_id]
+        pub fn set_require_qualified_client_id(&mut self, require: bool) {
+            self.require_qualified_client_id = require;
+        }
+
+        /// Fails with [CryptoError::MalformedClientId] when a qualified client id is required but the configured one
+        /// is not
+        pub(crate) fn validate_client_id(&self) -> CryptoResult<()> {
+            if let Some(client_id) = self.client_id.as_ref().filter(|_| self.require_qualified_client_id) {
+                client_id.to_qualified()?;
+            }
+            Ok(())
+        }
+
+        /// Takes the store over from any instance holding it, see [MlsCentralConfiguration::take_over_store]
+        pub fn set_take_over_store(&mut self, take_over: bool) {
+            self.take_over_store = take_over;
+        }
+
+        /// Protects the master key of the store with the platform's secure hardware, see
+        /// [MlsCentralConfiguration::key_provider]
+        pub fn set_key_provider(&mut self, key_provider: std::sync::Arc<dyn KeyProvider>) {
+            self.key_provider = Some(key_provider);
+        }
+
+        /// Does not keep the conversations in memory, see [MlsCentralConfiguration::disable_group_cache]
+        pub fn set_disable_group_cache(&mut self, disable: bool) {
+            self.disable_group_cache = disable;
+        }
+
+        /// Sets the iOS data protection class of the files of the store, see
+        /// [MlsCentralConfiguration::file_protection]
+        pub fn set_file_protection(&mut self, class: FileProtectionClass) {
+            self.file_protection = Some(class);
+        }
+
+        /// Keeps the salt of the store in the keychain or not, see [MlsCentralConfiguration::wal_compat]
+        pub fn set_wal_compat(&mut self, wal_compat: bool) {
+            self.wal_compat = wal_compat;
+        }
+
+        /// Sets the tolerated clock skew, see [MlsCentralConfiguration::clock_skew_leeway]
+        pub fn set_clock_skew_leeway(&mut self, leeway: std::time::Duration) {
+            self.clock_skew_leeway = leeway;
+        }
+
         #[cfg(test)]
         #[allow(dead_code)]
         /// Creates temporary file to prevent test collisions which would happen with hardcoded file path
@@ -142,6 +240,20 @@ pub struct MlsCentral {
     pub(crate) mls_backend: MlsCryptoProvider,
     pub(crate) mls_groups: crate::group_store::GroupStore<MlsConversation>,
     pub(crate) callbacks: Option<Box<dyn CoreCryptoCallbacks + 'static>>,
+    pub(crate) message_limits: deserialize::MlsMessageLimits,
+    pub(crate) conversation_health: conversation::health::ConversationHealthTracker,
+    pub(crate) credential_observer: Option<Box<dyn conversation::seen_credential::MlsCredentialObserver>>,
+    pub(crate) allow_identity_export: bool,
+    pub(crate) require_qualified_client_id: bool,
+    pub(crate) conversation_stats_enabled: bool,
+    pub(crate) epoch_cleanup_metrics: conversation::epoch_cleanup::MlsEpochCleanupMetrics,
+    pub(crate) metrics: std::sync::Arc<metrics::MlsMetrics>,
+    pub(crate) pending_group_restoration: Option<restore::PendingGroupRestoration>,
+    pub(crate) clock_skew_leeway: std::time::Duration,
+    pub(crate) transaction_snapshot: Option<crate::context::TransactionSnapshot>,
+    /// Step at which the next operation fails, see [MlsCentral::fail_at]
+    #[cfg(test)]
+    pub(crate) failing_step: Option<&'static str>,
 }
 
 impl MlsCentral {
@@ -161,14 +273,37 @@ impl MlsCentral {
     /// * for Basic Credentials if the signature key cannot be generated either by not supported
     /// scheme or the key generation fails
     pub async fn try_new(configuration: MlsCentralConfiguration) -> CryptoResult<Self> {
+        Self::try_new_impl(configuration, false).await
+    }
+
+    /// Same as [MlsCentral::try_new] but returns without restoring the persisted conversations, which on accounts
+    /// with thousands of them delays the start of the app. Conversations are loaded from the KeyStore when first used
+    /// and the others can be restored in the background, most recently persisted first, with
+    /// [MlsCentral::restore_groups_progressively] or step by step with [MlsCentral::restore_next_groups]
+    pub async fn try_new_with_progress(configuration: MlsCentralConfiguration) -> CryptoResult<Self> {
+        Self::try_new_impl(configuration, true).await
+    }
+
+    async fn try_new_impl(configuration: MlsCentralConfiguration, lazy_restore: bool) -> CryptoResult<Self> {
+        configuration.validate_client_id()?;
         // Init backend (crypto + rand + keystore)
         let mls_backend = MlsCryptoProvider::try_new_with_configuration(MlsCryptoProviderConfiguration {
             db_path: &configuration.store_path,
             identity_key: &configuration.identity_key,
+            key_provider: configuration.key_provider.as_deref(),
             in_memory: false,
+            take_over_store: configuration.take_over_store,
+            file_protection: configuration.file_protection,
+            wal_compat: configuration.wal_compat,
             entropy_seed: configuration.external_entropy,
         })
-        .await?;
+        .await
+        .map_err(|e| match e {
+            MlsProviderError::KeystoreError(CryptoKeystoreError::StoreAlreadyInUse) => CryptoError::StoreAlreadyInUse,
+            MlsProviderError::KeystoreError(CryptoKeystoreError::DeviceLocked) => CryptoError::DeviceLocked,
+            MlsProviderError::KeystoreError(CryptoKeystoreError::StoreEvicted) => CryptoError::StoreEvicted,
+            e => e.into(),
+        })?;
         let mls_client = if let Some(id) = configuration.client_id {
             // Init client identity (load or create)
             Some(
@@ -180,14 +315,21 @@ impl MlsCentral {
                         .nb_init_key_packages
                         .unwrap_or(INITIAL_KEYING_MATERIAL_COUNT),
                 )
-                .await?,
+                .await?
+                .with_clock_skew_leeway(configuration.clock_skew_leeway),
             )
         } else {
             None
         };
 
         // Restore persisted groups if there are any
-        let mls_groups = Self::restore_groups(&mls_backend).await?;
+        let (mls_groups, pending_group_restoration) = if configuration.disable_group_cache {
+            (crate::group_store::GroupStore::uncached(), None)
+        } else if lazy_restore {
+            (Default::default(), Self::pending_group_restoration(&mls_backend).await?)
+        } else {
+            (Self::restore_groups(&mls_backend, None).await?, None)
+        };
         mls_backend
             .authentication_service()
             .update_env(Self::restore_pki_env(&mls_backend).await?)?;
@@ -197,15 +339,33 @@ impl MlsCentral {
             mls_client,
             mls_groups,
             callbacks: None,
+            message_limits: Default::default(),
+            conversation_health: Default::default(),
+            credential_observer: None,
+            allow_identity_export: configuration.allow_identity_export,
+            require_qualified_client_id: configuration.require_qualified_client_id,
+            conversation_stats_enabled: false,
+            epoch_cleanup_metrics: Default::default(),
+            metrics: Default::default(),
+            pending_group_restoration,
+            clock_skew_leeway: configuration.clock_skew_leeway,
+            transaction_snapshot: None,
+            #[cfg(test)]
+            failing_step: None,
         })
     }
 
     /// Same as the [MlsCentral::try_new] but instead, it uses an in memory KeyStore. Although required, the `store_path` parameter from the `MlsCentralConfiguration` won't be used here.
     pub async fn try_new_in_memory(configuration: MlsCentralConfiguration) -> CryptoResult<Self> {
+        configuration.validate_client_id()?;
         let mls_backend = MlsCryptoProvider::try_new_with_configuration(MlsCryptoProviderConfiguration {
             db_path: &configuration.store_path,
             identity_key: &configuration.identity_key,
+            key_provider: None,
             in_memory: true,
+            take_over_store: false,
+            file_protection: None,
+            wal_compat: true,
             entropy_seed: configuration.external_entropy,
         })
         .await?;
@@ -219,12 +379,17 @@ impl MlsCentral {
                         .nb_init_key_packages
                         .unwrap_or(INITIAL_KEYING_MATERIAL_COUNT),
                 )
-                .await?,
+                .await?
+                .with_clock_skew_leeway(configuration.clock_skew_leeway),
             )
         } else {
             None
         };
-        let mls_groups = Self::restore_groups(&mls_backend).await?;
+        let mls_groups = if configuration.disable_group_cache {
+            crate::group_store::GroupStore::uncached()
+        } else {
+            Self::restore_groups(&mls_backend, None).await?
+        };
         mls_backend
             .authentication_service()
             .update_env(Self::restore_pki_env(&mls_backend).await?)?;
@@ -234,12 +399,29 @@ impl MlsCentral {
             mls_client,
             mls_groups,
             callbacks: None,
+            message_limits: Default::default(),
+            conversation_health: Default::default(),
+            credential_observer: None,
+            allow_identity_export: configuration.allow_identity_export,
+            require_qualified_client_id: configuration.require_qualified_client_id,
+            conversation_stats_enabled: false,
+            epoch_cleanup_metrics: Default::default(),
+            metrics: Default::default(),
+            pending_group_restoration: None,
+            clock_skew_leeway: configuration.clock_skew_leeway,
+            transaction_snapshot: None,
+            #[cfg(test)]
+            failing_step: None,
         })
     }
 
     /// Initializes the MLS client if [super::CoreCrypto] has previously been initialized with
     /// `CoreCrypto::deferred_init` instead of `CoreCrypto::new`.
     /// This should stay as long as proteus is supported. Then it should be removed.
+    ///
+    /// # Errors
+    /// [CryptoError::MalformedClientId] when [MlsCentralConfiguration::require_qualified_client_id] is set and the
+    /// client id is not qualified. Otherwise KeyStore and OpenMls errors
     pub async fn mls_init(
         &mut self,
         identifier: ClientIdentifier,
@@ -250,8 +432,13 @@ impl MlsCentral {
             // prevents wrong usage of the method instead of silently hiding the mistake
             return Err(CryptoError::ConsumerError);
         }
+        if self.require_qualified_client_id {
+            identifier.get_id()?.to_qualified()?;
+        }
         let nb_key_package = nb_init_key_packages.unwrap_or(INITIAL_KEYING_MATERIAL_COUNT);
-        let mls_client = Client::init(identifier, &ciphersuites, &self.mls_backend, nb_key_package).await?;
+        let mls_client = Client::init(identifier, &ciphersuites, &self.mls_backend, nb_key_package)
+            .await?
+            .with_clock_skew_leeway(self.clock_skew_leeway);
 
         if mls_client.is_e2ei_capable() {
             self.init_pki_env().await?;
@@ -290,9 +477,14 @@ impl MlsCentral {
             // prevents wrong usage of the method instead of silently hiding the mistake
             return Err(CryptoError::ConsumerError);
         }
+        if self.require_qualified_client_id {
+            client_id.to_qualified()?;
+        }
 
         let mls_client =
-            Client::init_with_external_client_id(client_id, tmp_client_ids, &ciphersuites, &self.mls_backend).await?;
+            Client::init_with_external_client_id(client_id, tmp_client_ids, &ciphersuites, &self.mls_backend)
+                .await?
+                .with_clock_skew_leeway(self.clock_skew_leeway);
 
         self.mls_client = Some(mls_client);
         Ok(())
@@ -324,6 +516,27 @@ impl MlsCentral {
         Ok(cb.signature_key.to_public_vec())
     }
 
+    /// Returns the public part of the client's most recent credential of the given type for the signature scheme of
+    /// `ciphersuite`: its public signature key along with, for a X509 credential, the certificate chain.
+    ///
+    /// # Arguments
+    /// * `ciphersuite` - whose signature scheme the credential uses
+    /// * `credential_type` - of the credential to look for
+    ///
+    /// # Errors
+    /// [CryptoError::ClientSignatureNotFound] when the client has no such credential
+    pub fn client_public_key_bundle(
+        &self,
+        ciphersuite: MlsCiphersuite,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<PublicKeyBundle> {
+        let cb = self
+            .mls_client()?
+            .find_most_recent_credential_bundle(ciphersuite.signature_algorithm(), credential_type)
+            .ok_or(CryptoError::ClientSignatureNotFound)?;
+        Ok(cb.to_public_key_bundle())
+    }
+
     /// Returns the client's id as a buffer
     pub fn client_id(&self) -> CryptoResult<ClientId> {
         Ok(self.mls_client()?.id().clone())
@@ -351,19 +564,86 @@ impl MlsCentral {
             return Err(CryptoError::ConversationAlreadyExists(id.clone()));
         }
 
-        let mls_client = self.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
-        let conversation = MlsConversation::create(
-            id.clone(),
-            mls_client,
-            creator_credential_type,
-            config,
-            &self.mls_backend,
-        )
-        .await?;
+        let id = id.clone();
+        self.within_transaction(|central| {
+            Box::pin(async move {
+                let mls_client = central.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
+                let conversation = MlsConversation::create(
+                    id.clone(),
+                    mls_client,
+                    creator_credential_type,
+                    config,
+                    &central.mls_backend,
+                )
+                .await?;
 
-        self.mls_groups.insert(id.clone(), conversation);
+                central.mls_groups.insert(id.clone(), conversation);
+                central.record_new_credentials(&id).await?;
+                central.record_epoch(&id, Some(central.client_id()?)).await?;
 
-        Ok(())
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    /// Creates a new conversation and adds the given members to it in a single operation. Since nobody else can
+    /// commit to a conversation which has just been created, the commit adding the members is merged right away.
+    ///
+    /// # Arguments
+    /// * `id` - identifier of the group/conversation
+    /// * `creator_credential_type` - kind of credential the creator wants to create the group with
+    /// * `config` - configuration of the group/conversation
+    /// * `key_packages` - KeyPackages of the members to be added to the group
+    ///
+    /// # Return type
+    /// The welcome to send to the new members along with the group info. The commit is also returned but does not
+    /// have to be sent since there is no other member in the group to process it.
+    ///
+    /// # Errors
+    /// If the conversation already exists. Other errors are KeyStore and OpenMls errors, in which case nothing
+    /// remains of the conversation, neither in memory nor in the KeyStore
+    pub async fn new_conversation_with_members(
+        &mut self,
+        id: &ConversationId,
+        creator_credential_type: MlsCredentialType,
+        config: MlsConversationConfiguration,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        if self.conversation_exists(id).await || self.pending_group_exists(id).await {
+            return Err(CryptoError::ConversationAlreadyExists(id.clone()));
+        }
+
+        // the group is persisted when created, so it has to be discarded along with it when adding the members fails
+        let id = id.clone();
+        self.within_transaction(|central| {
+            Box::pin(async move {
+                let mls_client = central.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
+                let conversation = MlsConversation::create(
+                    id.clone(),
+                    mls_client,
+                    creator_credential_type,
+                    config,
+                    &central.mls_backend,
+                )
+                .await?;
+
+                central.mls_groups.insert(id.clone(), conversation);
+                central.record_epoch(&id, Some(central.client_id()?)).await?;
+
+                let conversation = central.get_conversation(&id).await?;
+                let mut conversation = conversation.write().await;
+                let creation = conversation
+                    .add_members(central.mls_client()?, key_packages, &central.mls_backend)
+                    .await?;
+                conversation.commit_accepted(&central.mls_backend).await?;
+                drop(conversation);
+                central.record_new_credentials(&id).await?;
+                central.record_epoch(&id, Some(central.client_id()?)).await?;
+                Ok(creation)
+            })
+        })
+        .await
     }
 
     /// Checks if a given conversation id exists locally
@@ -405,6 +685,9 @@ impl MlsCentral {
 
     /// Destroys everything we have, in-memory and on disk.
     ///
+    /// On disk, the keystore content is overwritten before its files are removed so that it cannot be recovered
+    /// afterwards. On WASM, the IndexedDB database is deleted and the cached encryption key is zeroized.
+    ///
     /// # Errors
     /// KeyStore errors, such as IO
     pub async fn wipe(self) -> CryptoResult<()> {
@@ -412,6 +695,25 @@ impl MlsCentral {
         Ok(())
     }
 
+    /// Registers a callback invoked with the conversation id and the error when persisting a conversation fails, for
+    /// example because the disk is full. While one is registered, such a failure is not returned anymore: the
+    /// conversation is marked dirty, its latest state kept in memory and persisted again on the next successful write.
+    /// Passing `None` unregisters it.
+    ///
+    /// Merging a commit is not covered: the group is persisted along with the key material of the new epoch within a
+    /// transaction, hence the failure is returned and the conversation rolled back, see [CryptoError::MergeRolledBack]
+    pub async fn set_persistence_failure_callback(&self, callback: Option<PersistenceFailureCallback>) {
+        self.mls_backend
+            .borrow_keystore()
+            .set_persistence_failure_callback(callback)
+            .await
+    }
+
+    /// Conversations whose latest state could not be persisted yet, see [MlsCentral::set_persistence_failure_callback]
+    pub async fn dirty_conversations(&self) -> Vec<ConversationId> {
+        self.mls_backend.borrow_keystore().dirty_mls_groups().await
+    }
+
     /// Generates a random byte array of the specified size
     pub fn random_bytes(&self, len: usize) -> CryptoResult<Vec<u8>> {
         use openmls_traits::random::OpenMlsRand as _;
@@ -435,6 +737,7 @@ pub mod tests {
 
     use crate::prelude::{CertificateBundle, ClientIdentifier, MlsCredentialType, INITIAL_KEYING_MATERIAL_COUNT};
     use crate::{
+        group_store::{GroupStore, GroupStoreMode},
         mls::{CryptoError, MlsCentral, MlsCentralConfiguration},
         test_utils::{x509::X509TestChain, *},
     };
@@ -641,6 +944,28 @@ pub mod tests {
         .await
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_fetch_client_public_key_bundle(case: TestCase) {
+        run_test_with_central(case.clone(), move |[central]| {
+            Box::pin(async move {
+                let (cs, ct) = (case.ciphersuite(), case.credential_type);
+                let bundle = central.mls_central.client_public_key_bundle(cs, ct).unwrap();
+                assert_eq!(bundle.signature_scheme, case.signature_scheme());
+                assert_eq!(bundle.credential_type, ct);
+                assert_eq!(
+                    bundle.public_key,
+                    central.mls_central.client_public_key(cs, ct).unwrap()
+                );
+                match ct {
+                    MlsCredentialType::Basic => assert!(bundle.certificate_chain.is_empty()),
+                    MlsCredentialType::X509 => assert!(!bundle.certificate_chain.is_empty()),
+                }
+            })
+        })
+        .await
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn can_2_phase_init_central(case: TestCase) {
@@ -691,4 +1016,138 @@ pub mod tests {
         })
         .await
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_require_qualified_client_id_when_configured(case: TestCase) {
+        run_tests(move |[tmp_dir_argument]| {
+            Box::pin(async move {
+                let mut configuration = MlsCentralConfiguration::try_new(
+                    tmp_dir_argument,
+                    "test".to_string(),
+                    None,
+                    vec![case.ciphersuite()],
+                    None,
+                    Some(INITIAL_KEYING_MATERIAL_COUNT),
+                )
+                .unwrap();
+                configuration.set_require_qualified_client_id(true);
+                let mut central = MlsCentral::try_new(configuration).await.unwrap();
+
+                let init = |client_id: &str| ClientIdentifier::Basic(client_id.into());
+                assert!(matches!(
+                    central
+                        .mls_init(init("alice"), vec![case.ciphersuite()], None)
+                        .await
+                        .unwrap_err(),
+                    CryptoError::MalformedClientId(_)
+                ));
+                assert!(central.mls_client.is_none());
+                central
+                    .mls_init(
+                        init("bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com"),
+                        vec![case.ciphersuite()],
+                        None,
+                    )
+                    .await
+                    .unwrap();
+                assert!(central.mls_client.is_some());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_cache_groups_when_disabled(case: TestCase) {
+        run_tests(move |[tmp_dir_argument]| {
+            Box::pin(async move {
+                let mut configuration = MlsCentralConfiguration::try_new(
+                    tmp_dir_argument,
+                    "test".to_string(),
+                    Some("alice".into()),
+                    vec![case.ciphersuite()],
+                    None,
+                    Some(INITIAL_KEYING_MATERIAL_COUNT),
+                )
+                .unwrap();
+                configuration.set_disable_group_cache(true);
+                let mut central = MlsCentral::try_new(configuration).await.unwrap();
+                assert_eq!(central.mls_groups.mode(), GroupStoreMode::Uncached);
+
+                let id = conversation_id();
+                central
+                    .new_conversation(&id, MlsCredentialType::Basic, case.cfg.clone())
+                    .await
+                    .unwrap();
+                central.restore_from_disk().await.unwrap();
+                assert_eq!(central.mls_groups.mode(), GroupStoreMode::Uncached);
+                assert_eq!(central.mls_groups.len(), 0);
+                assert_eq!(central.prefetch_conversations(&[id.clone()]).await.unwrap(), 0);
+                assert_eq!(central.conversation_epoch(&id).await.unwrap(), 0);
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_keep_pending_state_without_group_cache(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    // as if configured with MlsCentralConfiguration::set_disable_group_cache
+                    alice_central.mls_central.mls_groups = GroupStore::uncached();
+                    bob_central.mls_central.mls_groups = GroupStore::uncached();
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // the pending proposal and commit are only in the persisted group between operations
+                    let charlie = charlie_central.mls_central.get_one_key_package(&case).await;
+                    alice_central.mls_central.new_add_proposal(&id, charlie).await.unwrap();
+                    assert!(alice_central.mls_central.mls_groups.len() <= 1);
+                    let commit = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), 1);
+                    assert!(alice_central.mls_central.pending_commit(&id).await.is_some());
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), 2);
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), 2);
+
+                    for _ in 0..3 {
+                        alice_central
+                            .mls_central
+                            .try_talk_to(&id, &mut bob_central.mls_central)
+                            .await
+                            .unwrap();
+                    }
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), 3);
+                    assert!(alice_central.mls_central.mls_groups.len() <= 1);
+                })
+            },
+        )
+        .await
+    }
 }
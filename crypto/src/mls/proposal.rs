@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
-use openmls::prelude::{hash_ref::ProposalRef, KeyPackage};
+use openmls::prelude::{hash_ref::ProposalRef, KeyPackage, LeafNodeIndex, Proposal, QueuedProposal, Sender};
 
 use mls_crypto_provider::MlsCryptoProvider;
 
@@ -39,7 +39,8 @@ impl MlsProposalRef {
         self.0
     }
 
-    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    /// Serializes the reference to raw bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
         self.0.as_slice().to_vec()
     }
 }
@@ -60,6 +61,9 @@ pub enum MlsProposal {
     Update,
     /// Requests that the member with LeafNodeRef removed be removed from the group
     Remove(ClientId),
+    /// Injects the external pre-shared key with this id, previously added with [MlsCentral::add_external_psk], in
+    /// the key schedule
+    PreSharedKey(Vec<u8>),
 }
 
 impl MlsProposal {
@@ -86,11 +90,111 @@ impl MlsProposal {
                     .map(|kp| kp.index)?;
                 (*conversation).propose_remove_member(client, backend, index).await
             }
+            MlsProposal::PreSharedKey(psk_id) => (*conversation).propose_external_psk(client, backend, psk_id).await,
         }?;
         Ok(proposal)
     }
 }
 
+/// Kind of a proposal waiting in the local proposal store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MlsProposalType {
+    /// Adds a new client to the group
+    Add = 1,
+    /// Rekeys the sender's LeafNode
+    Update = 2,
+    /// Removes a client from the group
+    Remove = 3,
+    /// Injects a pre-shared key in the key schedule
+    PreSharedKey = 4,
+    /// Reinitializes the group with different parameters
+    ReInit = 5,
+    /// Only used in an external commit by the joiner
+    ExternalInit = 6,
+    /// Updates the group context extensions
+    GroupContextExtensions = 7,
+    /// Any other proposal kind we do not (yet) interpret
+    Other = 0xFF,
+}
+
+impl From<&Proposal> for MlsProposalType {
+    fn from(proposal: &Proposal) -> Self {
+        match proposal {
+            Proposal::Add(_) => Self::Add,
+            Proposal::Update(_) => Self::Update,
+            Proposal::Remove(_) => Self::Remove,
+            Proposal::PreSharedKey(_) => Self::PreSharedKey,
+            Proposal::ReInit(_) => Self::ReInit,
+            Proposal::ExternalInit(_) => Self::ExternalInit,
+            Proposal::GroupContextExtensions(_) => Self::GroupContextExtensions,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Describes a pending proposal so that a client can render it (e.g. "Alice wants to add Bob")
+/// before committing it
+#[derive(Debug, Clone)]
+pub struct MlsProposalInfo {
+    /// Kind of the proposal
+    pub proposal_type: MlsProposalType,
+    /// Client who created the proposal. `None` when the proposal was issued by an external sender
+    /// (e.g. the Delivery Service)
+    pub proposer: Option<ClientId>,
+    /// Clients added, removed or updated by this proposal
+    pub affected_clients: Vec<ClientId>,
+    /// Stable reference of the proposal. Use it with [MlsCentral::clear_pending_proposal]
+    pub proposal_ref: MlsProposalRef,
+}
+
+/// Rejects the proposals among `proposals` this client can't follow. Following a ReInit means creating the successor
+/// group, which OpenMls does not support: failing is better than staying in a group the other members have left
+pub(crate) fn validate_supported_proposals<'a>(mut proposals: impl Iterator<Item = &'a Proposal>) -> CryptoResult<()> {
+    match proposals.find(|proposal| matches!(proposal, Proposal::ReInit(_))) {
+        Some(proposal) => Err(CryptoError::UnsupportedProposal(proposal.into())),
+        None => Ok(()),
+    }
+}
+
+impl MlsConversation {
+    /// see [MlsCentral::pending_proposals]
+    pub fn pending_proposals(&self) -> Vec<MlsProposalInfo> {
+        self.group.pending_proposals().map(|p| self.proposal_info(p)).collect()
+    }
+
+    fn proposal_info(&self, queued: &QueuedProposal) -> MlsProposalInfo {
+        let member_id = |index: LeafNodeIndex| {
+            self.group
+                .members()
+                .find(|m| m.index == index)
+                .map(|m| ClientId::from(m.credential.identity()))
+        };
+
+        let proposer = match (queued.sender(), queued.proposal()) {
+            (Sender::Member(index), _) => member_id(*index),
+            (Sender::NewMemberProposal, Proposal::Add(add)) => {
+                Some(add.key_package().leaf_node().credential().identity().into())
+            }
+            _ => None,
+        };
+
+        let affected_clients = match queued.proposal() {
+            Proposal::Add(add) => vec![add.key_package().leaf_node().credential().identity().into()],
+            Proposal::Remove(remove) => member_id(remove.removed()).into_iter().collect(),
+            Proposal::Update(_) => proposer.clone().into_iter().collect(),
+            _ => vec![],
+        };
+
+        MlsProposalInfo {
+            proposal_type: queued.proposal().into(),
+            proposer,
+            affected_clients,
+            proposal_ref: queued.proposal_reference().clone().into(),
+        }
+    }
+}
+
 impl MlsCentral {
     /// Creates a new Add proposal
     #[cfg_attr(test, crate::idempotent)]
@@ -118,6 +222,28 @@ impl MlsCentral {
         self.new_proposal(id, MlsProposal::Update).await
     }
 
+    /// Creates a new PreSharedKey proposal injecting an external pre-shared key, see [MlsCentral::add_external_psk]
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn new_external_psk_proposal(
+        &mut self,
+        id: &ConversationId,
+        psk_id: Vec<u8>,
+    ) -> CryptoResult<MlsProposalBundle> {
+        self.new_proposal(id, MlsProposal::PreSharedKey(psk_id)).await
+    }
+
+    /// Lists the proposals waiting in the local proposal store of a conversation, whether we
+    /// created them or received them. Use [MlsCentral::clear_pending_proposal] to drop one of them.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    pub async fn pending_proposals(&mut self, id: &ConversationId) -> CryptoResult<Vec<MlsProposalInfo>> {
+        Ok(self.get_conversation(id).await?.read().await.pending_proposals())
+    }
+
     /// Creates a new proposal within a group
     ///
     /// # Arguments
@@ -133,9 +259,11 @@ impl MlsCentral {
     async fn new_proposal(&mut self, id: &ConversationId, proposal: MlsProposal) -> CryptoResult<MlsProposalBundle> {
         let conversation = self.get_conversation(id).await?;
         let client = self.mls_client()?;
-        proposal
-            .create(client, &self.mls_backend, conversation.write().await)
-            .await
+        let conversation = conversation.write().await;
+        if matches!(proposal, MlsProposal::Add(_) | MlsProposal::Remove(_)) {
+            conversation.ensure_admin(client.id())?;
+        }
+        proposal.create(client, &self.mls_backend, conversation).await
     }
 }
 
@@ -147,6 +275,31 @@ pub mod tests {
 
     wasm_bindgen_test_configure!(run_in_browser);
 
+    pub mod unsupported {
+        use openmls::prelude::{Proposal, ReInitProposal, RemoveProposal};
+        use tls_codec::Deserialize as _;
+
+        use crate::mls::proposal::validate_supported_proposals;
+
+        use super::*;
+
+        #[test]
+        #[wasm_bindgen_test]
+        pub fn should_reject_reinit() {
+            // OpenMls can't create a ReInit, hence it is decoded: a group id, MLS 1.0, a ciphersuite and no extension
+            let reinit = [4, b'g', b'r', b'o', b'u', 0x00, 0x01, 0x00, 0x01, 0];
+            let reinit = ReInitProposal::tls_deserialize(&mut reinit.as_slice()).unwrap();
+            // removes the member at leaf 0
+            let remove = RemoveProposal::tls_deserialize(&mut [0u8; 4].as_slice()).unwrap();
+            let proposals = [Proposal::Remove(remove), Proposal::ReInit(reinit)];
+            assert!(matches!(
+                validate_supported_proposals(proposals.iter()).unwrap_err(),
+                CryptoError::UnsupportedProposal(MlsProposalType::ReInit)
+            ));
+            assert!(validate_supported_proposals(proposals[..1].iter()).is_ok());
+        }
+    }
+
     pub mod add {
         use super::*;
 
@@ -344,4 +497,116 @@ pub mod tests {
             .await
         }
     }
+
+    pub mod pending_proposals {
+        use super::*;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_describe_received_proposals(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob", "charlie"],
+                move |[mut alice_central, mut bob_central, charlie_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        assert!(bob_central.mls_central.pending_proposals(&id).await.unwrap().is_empty());
+
+                        let charlie_kp = charlie_central.mls_central.get_one_key_package(&case).await;
+                        let MlsProposalBundle {
+                            proposal, proposal_ref, ..
+                        } = alice_central
+                            .mls_central
+                            .new_add_proposal(&id, charlie_kp)
+                            .await
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, proposal.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+
+                        let pending = bob_central.mls_central.pending_proposals(&id).await.unwrap();
+                        assert_eq!(pending.len(), 1);
+                        let info = pending.first().unwrap();
+                        assert_eq!(info.proposal_type, MlsProposalType::Add);
+                        assert_eq!(info.proposer, Some(alice_central.mls_central.get_client_id()));
+                        assert_eq!(info.affected_clients, vec![charlie_central.mls_central.get_client_id()]);
+                        assert_eq!(info.proposal_ref, proposal_ref);
+
+                        // the proposal reference is stable and can be used to discard the proposal
+                        bob_central
+                            .mls_central
+                            .clear_pending_proposal(&id, info.proposal_ref.clone())
+                            .await
+                            .unwrap();
+                        assert!(bob_central.mls_central.pending_proposals(&id).await.unwrap().is_empty());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_describe_remove_proposal(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let bob_id = bob_central.mls_central.get_client_id();
+                        alice_central
+                            .mls_central
+                            .new_remove_proposal(&id, bob_id.clone())
+                            .await
+                            .unwrap();
+
+                        let pending = alice_central.mls_central.pending_proposals(&id).await.unwrap();
+                        assert_eq!(pending.len(), 1);
+                        let info = pending.first().unwrap();
+                        assert_eq!(info.proposal_type, MlsProposalType::Remove);
+                        assert_eq!(info.proposer, Some(alice_central.mls_central.get_client_id()));
+                        assert_eq!(info.affected_clients, vec![bob_id]);
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_fail_when_conversation_not_found(case: TestCase) {
+            run_test_with_central(case.clone(), move |[mut central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let err = central.mls_central.pending_proposals(&id).await.unwrap_err();
+                    assert!(matches!(err, CryptoError::ConversationNotFound(conv_id) if conv_id == id));
+                })
+            })
+            .await
+        }
+    }
 }
@@ -0,0 +1,178 @@
+//! Tooling auditing a group, e.g. for trust & safety, has to answer questions about its public state without being a
+//! member of it nor having a client at all. [PublicGroupStateInspector] loads the GroupInfo of a group, as published
+//! to the Delivery Service, and only exposes read-only getters over it. The GroupInfo and its ratchet tree are
+//! verified before anything is exposed so that the answers can't be forged by whoever serves it.
+
+use openmls::prelude::{group_info::VerifiableGroupInfo, CredentialType, Node};
+use tls_codec::Deserialize;
+
+use crate::prelude::{ClientId, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsCryptoProvider, MlsError};
+
+/// Key of the throwaway keystore required to verify a GroupInfo, nothing is ever written in it
+const INSPECTOR_STORE_KEY: &str = "core-crypto-public-group-state-inspector";
+
+/// A member of a group, see [PublicGroupStateInspector::members]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicGroupStateMember {
+    /// Id of the member's client
+    pub client_id: ClientId,
+    /// Kind of credential of the member, none when it is unknown to CoreCrypto
+    pub credential_type: Option<MlsCredentialType>,
+}
+
+/// Read-only view over the public state of a group, loaded from its GroupInfo without instantiating a
+/// [crate::prelude::MlsCentral]
+#[derive(Debug, Clone)]
+pub struct PublicGroupStateInspector {
+    group_id: Vec<u8>,
+    epoch: u64,
+    ciphersuite: MlsCiphersuite,
+    extension_types: Vec<u16>,
+    members: Vec<PublicGroupStateMember>,
+}
+
+impl PublicGroupStateInspector {
+    /// Loads the public state of a group from its GroupInfo
+    ///
+    /// # Arguments
+    /// * `bytes` - a TLS encoded GroupInfo, as returned by the Delivery Service. It has to embed the ratchet tree
+    ///
+    /// # Errors
+    /// If the GroupInfo can't be deserialized, if its signature or its ratchet tree are invalid, if it does not embed
+    /// the ratchet tree or if its ciphersuite is not supported
+    pub async fn try_from_tls(bytes: &[u8]) -> CryptoResult<Self> {
+        let group_info = VerifiableGroupInfo::tls_deserialize(&mut &bytes[..]).map_err(MlsError::from)?;
+        let ciphersuite = MlsCiphersuite::from(group_info.ciphersuite());
+        ciphersuite.ensure_supported()?;
+        let group_id = group_info.group_id().as_slice().to_vec();
+        let epoch = group_info.epoch().as_u64();
+        let extension_types = group_info
+            .extensions()
+            .iter()
+            .map(|e| e.extension_type().into())
+            .collect();
+
+        // only the crypto primitives are used to verify the GroupInfo, the keystore is dropped right after
+        let backend = MlsCryptoProvider::try_new_in_memory(INSPECTOR_STORE_KEY).await?;
+        let ratchet_tree = group_info
+            .take_ratchet_tree(&backend, false)
+            .await
+            .map_err(MlsError::from)?;
+        let members = ratchet_tree
+            .iter()
+            .filter_map(|n| match n {
+                Some(Node::LeafNode(ln)) => Some(ln.credential()),
+                _ => None,
+            })
+            .map(|credential| PublicGroupStateMember {
+                client_id: credential.identity().into(),
+                credential_type: match credential.credential_type() {
+                    CredentialType::Basic => Some(MlsCredentialType::Basic),
+                    CredentialType::X509 => Some(MlsCredentialType::X509),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        Ok(Self {
+            group_id,
+            epoch,
+            ciphersuite,
+            extension_types,
+            members,
+        })
+    }
+
+    /// Id of the group
+    pub fn group_id(&self) -> &[u8] {
+        self.group_id.as_slice()
+    }
+
+    /// Epoch of the group when the GroupInfo was created
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Ciphersuite of the group
+    pub fn ciphersuite(&self) -> MlsCiphersuite {
+        self.ciphersuite
+    }
+
+    /// Types of the extensions of the GroupInfo, as registered by the IANA e.g. 2 for the ratchet tree
+    pub fn extension_types(&self) -> &[u16] {
+        self.extension_types.as_slice()
+    }
+
+    /// Members of the group, in the order of their leaves
+    pub fn members(&self) -> &[PublicGroupStateMember] {
+        self.members.as_slice()
+    }
+
+    /// Whether a client is a member of the group
+    pub fn is_member(&self, client_id: &ClientId) -> bool {
+        self.members.iter().any(|m| &m.client_id == client_id)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_inspect_public_group_state(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                    let group_info = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob_kp])
+                        .await
+                        .unwrap()
+                        .group_info
+                        .payload
+                        .bytes();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let inspector = PublicGroupStateInspector::try_from_tls(&group_info).await.unwrap();
+                    assert_eq!(inspector.group_id(), id.as_slice());
+                    assert_eq!(
+                        inspector.epoch(),
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+                    assert_eq!(inspector.ciphersuite(), case.ciphersuite());
+                    assert!(!inspector.extension_types().is_empty());
+                    assert_eq!(inspector.members().len(), 2);
+                    assert!(inspector
+                        .members()
+                        .iter()
+                        .all(|m| m.credential_type == Some(case.credential_type)));
+                    assert!(inspector.is_member(&alice_central.mls_central.get_client_id()));
+                    assert!(inspector.is_member(&bob_central.mls_central.get_client_id()));
+
+                    // garbage is rejected
+                    assert!(PublicGroupStateInspector::try_from_tls(b"garbage").await.is_err());
+                    // so is a tampered GroupInfo
+                    let mut tampered = group_info.clone();
+                    let last = tampered.len() - 1;
+                    tampered[last] ^= 0xFF;
+                    assert!(PublicGroupStateInspector::try_from_tls(&tampered).await.is_err());
+                })
+            },
+        )
+        .await
+    }
+}
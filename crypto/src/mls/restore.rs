@@ -1,8 +1,34 @@
+use std::collections::VecDeque;
+
+use crate::cancellation::{check_cancelled, CancellationToken};
+use crate::group_store::{GroupStore, GroupStoreEntity, GroupStoreMode};
 use crate::prelude::{MlsCentral, MlsConversation};
 use crate::CryptoResult;
+use core_crypto_keystore::entities::PersistedMlsGroup;
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls_traits::OpenMlsCryptoProvider;
 
+/// Number of conversations restored by each step of [MlsCentral::restore_next_groups]
+pub const GROUP_RESTORE_BATCH_SIZE: usize = 20;
+
+/// Progress of the restoration of the conversations left over by [MlsCentral::try_new_with_progress]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MlsGroupRestoreProgress {
+    /// Conversations restored so far, the ones already loaded on demand in the meantime included
+    pub restored: usize,
+    /// Conversations persisted when the restoration started
+    pub total: usize,
+}
+
+/// Conversations still to be restored in the background, most recently persisted first
+#[derive(Debug)]
+pub(crate) struct PendingGroupRestoration {
+    ids: VecDeque<Vec<u8>>,
+    /// groups persisted before the index existed are not listed in it, they have to be indexed first
+    unindexed: bool,
+    progress: MlsGroupRestoreProgress,
+}
+
 impl MlsCentral {
     /// [MlsCentral] is supposed to be a singleton. Knowing that, it does some optimizations by
     /// keeping MLS groups in memory. Sometimes, especially on iOS, it is required to use extensions
@@ -11,16 +37,43 @@ impl MlsCentral {
     /// It simply fetches the MLS group from keystore in memory.
     #[cfg_attr(test, crate::idempotent)]
     pub async fn restore_from_disk(&mut self) -> CryptoResult<()> {
-        self.mls_groups = Self::restore_groups(&self.mls_backend).await?;
-        self.mls_backend
-            .authentication_service()
-            .update_env(Self::restore_pki_env(&self.mls_backend).await?)?;
+        self.restore_from_disk_impl(None).await
+    }
+
+    /// Same as [MlsCentral::restore_from_disk] but stops before the next conversation once `cancellation` is
+    /// cancelled, in which case the conversations in memory are left as they were
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn restore_from_disk_cancellable(&mut self, cancellation: &CancellationToken) -> CryptoResult<()> {
+        self.restore_from_disk_impl(Some(cancellation)).await
+    }
+
+    async fn restore_from_disk_impl(&mut self, cancellation: Option<&CancellationToken>) -> CryptoResult<()> {
+        let mls_groups = match self.mls_groups.mode() {
+            GroupStoreMode::Cached => Self::restore_groups(&self.mls_backend, cancellation).await?,
+            // only the conversations in memory have to be dropped, they are loaded from the keystore when used
+            GroupStoreMode::Uncached => GroupStore::uncached(),
+        };
+        let pki_env = Self::restore_pki_env(&self.mls_backend).await?;
+        check_cancelled(cancellation)?;
+        self.mls_groups = mls_groups;
+        self.pending_group_restoration = None;
+        self.mls_backend.authentication_service().update_env(pki_env)?;
         Ok(())
     }
 
+    /// Takes the store back over from the instance which opened it with
+    /// [crate::prelude::MlsCentralConfiguration::set_take_over_store], e.g. the main app resuming after its extension
+    /// ran. Until then, every write of this instance fails with [crate::CryptoError::StoreAlreadyInUse]. The groups are
+    /// then restored from the keystore since the other instance most likely changed them
+    pub async fn take_over_store(&mut self) -> CryptoResult<()> {
+        self.mls_backend.key_store().take_over().await?;
+        self.restore_from_disk().await
+    }
+
     /// Restore existing groups from the KeyStore.
     pub(crate) async fn restore_groups(
         backend: &MlsCryptoProvider,
+        cancellation: Option<&CancellationToken>,
     ) -> CryptoResult<crate::group_store::GroupStore<MlsConversation>> {
         use core_crypto_keystore::CryptoKeystoreMls as _;
         let groups = backend.key_store().mls_groups_restore().await?;
@@ -31,15 +84,140 @@ impl MlsCentral {
             return Ok(group_store);
         }
 
-        for (group_id, (parent_id, state)) in groups.into_iter() {
-            let conversation = MlsConversation::from_serialized_state(state, parent_id)?;
+        // groups persisted before the index existed have to be indexed
+        let indexed = backend
+            .key_store()
+            .mls_groups_index()
+            .await?
+            .iter()
+            .map(|entry| entry.id.clone())
+            .collect::<std::collections::HashSet<_>>();
+
+        let count = groups.len();
+        for (restored, (group_id, (parent_id, state))) in groups.into_iter().enumerate() {
+            check_cancelled(cancellation)?;
+            let mut conversation = MlsConversation::from_serialized_state(state, parent_id)?;
+            conversation.restore_metadata(backend.key_store()).await?;
+            if !indexed.contains(&group_id) {
+                conversation.update_index(backend).await?;
+            }
             if group_store.try_insert(group_id, conversation).is_err() {
+                // the others will be loaded from the keystore when first used
+                crate::logger::warn(
+                    "Group store is full, not every conversation has been restored",
+                    serde_json::json!({ "restored": restored, "persisted": count }),
+                );
                 break;
             }
         }
 
         Ok(group_store)
     }
+
+    /// Lists the groups to restore in the background instead of restoring them right away, none when there is none
+    pub(crate) async fn pending_group_restoration(
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Option<PendingGroupRestoration>> {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        let mut index = backend.key_store().mls_groups_index().await?;
+        let persisted = backend.key_store().count::<PersistedMlsGroup>().await?;
+        if persisted == 0 && index.is_empty() {
+            return Ok(None);
+        }
+        index.sort_by(|a, b| b.last_persisted.cmp(&a.last_persisted));
+
+        Ok(Some(PendingGroupRestoration {
+            unindexed: persisted > index.len(),
+            progress: MlsGroupRestoreProgress {
+                restored: 0,
+                total: persisted.max(index.len()),
+            },
+            ids: index.into_iter().map(|entry| entry.id).collect(),
+        }))
+    }
+
+    /// Restores the next [GROUP_RESTORE_BATCH_SIZE] conversations left over by [MlsCentral::try_new_with_progress].
+    /// The ones already loaded on demand are left untouched. Once the conversation cache is full, the remaining
+    /// conversations are left to be loaded on demand and the restoration ends early.
+    ///
+    /// Returns the progress made so far, or none once there is nothing left to restore
+    ///
+    /// # Errors
+    /// KeyStore errors or a persisted group state which cannot be deserialized, the failed step can be retried
+    pub async fn restore_next_groups(&mut self) -> CryptoResult<Option<MlsGroupRestoreProgress>> {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        let Some(pending) = self.pending_group_restoration.as_mut() else {
+            return Ok(None);
+        };
+
+        if pending.unindexed {
+            let indexed = self
+                .mls_backend
+                .key_store()
+                .mls_groups_index()
+                .await?
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<std::collections::HashSet<_>>();
+            for (group_id, (parent_id, state)) in self.mls_backend.key_store().mls_groups_restore().await? {
+                if !indexed.contains(&group_id) {
+                    MlsConversation::from_serialized_state(state, parent_id)?
+                        .update_index(&self.mls_backend)
+                        .await?;
+                    pending.ids.push_back(group_id);
+                }
+            }
+            pending.unindexed = false;
+        }
+
+        let batch = pending
+            .ids
+            .iter()
+            .take(GROUP_RESTORE_BATCH_SIZE)
+            .filter(|id| !self.mls_groups.contains_key(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let conversations = MlsConversation::fetch_many(&batch, self.mls_backend.borrow_keystore()).await?;
+
+        let count = pending.ids.len().min(GROUP_RESTORE_BATCH_SIZE);
+        pending.ids.drain(..count);
+        pending.progress.restored += count;
+        let progress = pending.progress;
+        let mut done = pending.ids.is_empty();
+
+        for conversation in conversations {
+            let id = conversation.id().clone();
+            if self.mls_groups.try_insert(id, conversation).is_err() {
+                // the others will be loaded from the keystore when first used
+                crate::logger::warn(
+                    "Group store is full, not every conversation has been restored",
+                    serde_json::json!({ "restored": progress.restored, "persisted": progress.total }),
+                );
+                done = true;
+                break;
+            }
+        }
+
+        if done {
+            self.pending_group_restoration = None;
+        }
+        Ok(Some(progress))
+    }
+
+    /// Restores the conversations left over by [MlsCentral::try_new_with_progress] batch by batch, yielding the
+    /// progress after each one, see [MlsCentral::restore_next_groups]. The stream ends after the first error
+    pub fn restore_groups_progressively(
+        &mut self,
+    ) -> impl futures_util::Stream<Item = CryptoResult<MlsGroupRestoreProgress>> + '_ {
+        futures_util::stream::unfold(Some(self), |central| async move {
+            let central = central?;
+            match central.restore_next_groups().await {
+                Ok(Some(progress)) => Some((Ok(progress), Some(central))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -48,11 +226,12 @@ pub mod tests {
 
     use crate::{
         prelude::{
-            CertificateBundle, ClientIdentifier, MlsCentral, MlsCentralConfiguration, MlsCredentialType,
-            INITIAL_KEYING_MATERIAL_COUNT,
+            CancellationToken, CertificateBundle, ClientIdentifier, CryptoError, MlsCentral, MlsCentralConfiguration,
+            MlsCredentialType, MlsGroupRestoreProgress, INITIAL_KEYING_MATERIAL_COUNT,
         },
         test_utils::{x509::X509TestChain, *},
     };
+    use futures_util::StreamExt as _;
     use std::collections::HashMap;
 
     wasm_bindgen_test_configure!(run_in_browser);
@@ -178,30 +357,160 @@ pub mod tests {
                     .unwrap();
                 alice_central.invite_all(&case, &id, [&mut bob_central]).await.unwrap();
 
-                // Create another central which will be desynchronized at some point
-                let mut alice_central_mirror = MlsCentral::try_new(alice_cfg).await.unwrap();
+                // Another central on the same store has to take it over, like an extension would
+                let mut alice_mirror_cfg = alice_cfg.clone();
+                alice_mirror_cfg.set_take_over_store(true);
+                let mut alice_central_mirror = MlsCentral::try_new(alice_mirror_cfg).await.unwrap();
                 alice_central_mirror
                     .mls_init(alice_cid, vec![case.ciphersuite()], Some(INITIAL_KEYING_MATERIAL_COUNT))
                     .await
                     .unwrap();
                 assert!(alice_central_mirror.try_talk_to(&id, &mut bob_central).await.is_ok());
 
-                // alice original instance will update its key without synchronizing with its mirror
-                let commit = alice_central.update_keying_material(&id).await.unwrap().commit;
-                alice_central.commit_accepted(&id).await.unwrap();
-                // at this point using mirror instance is unsafe since it will erase the other
-                // instance state in keystore...
+                // the mirror instance will update its key without synchronizing with the original one
+                let commit = alice_central_mirror.update_keying_material(&id).await.unwrap().commit;
+                alice_central_mirror.commit_accepted(&id).await.unwrap();
                 bob_central
                     .decrypt_message(&id, commit.to_bytes().unwrap())
                     .await
                     .unwrap();
-                // so here we cannot test that mirror instance can talk to Bob because it would
-                // mess up the test, but trust me, it does !
+                // which cannot erase the mirror state in the keystore anymore
+                assert!(matches!(
+                    alice_central.encrypt_message(&id, b"Hello Bob").await.unwrap_err(),
+                    CryptoError::KeyStoreError(CryptoKeystoreError::StoreAlreadyInUse)
+                ));
 
-                // after restoring from disk, mirror instance got the right key material for
+                // after taking the store back, the original instance got the right key material for
                 // the current epoch hence can talk to Bob
-                alice_central_mirror.restore_from_disk().await.unwrap();
-                assert!(alice_central_mirror.try_talk_to(&id, &mut bob_central).await.is_ok());
+                alice_central.take_over_store().await.unwrap();
+                assert!(alice_central.try_talk_to(&id, &mut bob_central).await.is_ok());
+                assert!(alice_central_mirror.try_talk_to(&id, &mut bob_central).await.is_err());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn cancelled_restore_should_keep_conversations_in_memory(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let cancellation = CancellationToken::new();
+                    cancellation.cancel();
+                    let error = alice_central
+                        .mls_central
+                        .restore_from_disk_cancellable(&cancellation)
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::OperationCancelled));
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+
+                    alice_central
+                        .mls_central
+                        .restore_from_disk_cancellable(&CancellationToken::new())
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_restore_groups_progressively(case: TestCase) {
+        run_tests(move |[store_path]| {
+            Box::pin(async move {
+                let x509_test_chain = X509TestChain::init_empty(case.signature_scheme());
+                let cid = match case.credential_type {
+                    MlsCredentialType::Basic => ClientIdentifier::Basic("potato".into()),
+                    MlsCredentialType::X509 => {
+                        let cert =
+                            CertificateBundle::rand(&"potato".into(), x509_test_chain.find_local_intermediate_ca());
+                        ClientIdentifier::X509(HashMap::from([(case.cfg.ciphersuite.signature_algorithm(), cert)]))
+                    }
+                };
+                let configuration = MlsCentralConfiguration::try_new(
+                    store_path,
+                    "test".to_string(),
+                    None,
+                    vec![case.ciphersuite()],
+                    None,
+                    Some(INITIAL_KEYING_MATERIAL_COUNT),
+                )
+                .unwrap();
+
+                let mut central = MlsCentral::try_new(configuration.clone()).await.unwrap();
+                x509_test_chain.register_with_central(&central).await;
+                central
+                    .mls_init(
+                        cid.clone(),
+                        vec![case.ciphersuite()],
+                        Some(INITIAL_KEYING_MATERIAL_COUNT),
+                    )
+                    .await
+                    .unwrap();
+                let ids = [conversation_id(), conversation_id(), conversation_id()];
+                for id in &ids {
+                    central
+                        .new_conversation(id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                }
+                central.close().await.unwrap();
+
+                let mut central = MlsCentral::try_new_with_progress(configuration).await.unwrap();
+                central
+                    .mls_init(cid, vec![case.ciphersuite()], Some(INITIAL_KEYING_MATERIAL_COUNT))
+                    .await
+                    .unwrap();
+                assert_eq!(central.conversation_cache_stats().cached, 0);
+
+                // usable before being restored
+                central.encrypt_message(&ids[0], b"Test").await.unwrap();
+                assert_eq!(central.conversation_cache_stats().misses, 1);
+
+                let progress = central
+                    .restore_groups_progressively()
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(progress, vec![MlsGroupRestoreProgress { restored: 3, total: 3 }]);
+                assert_eq!(central.conversation_cache_stats().cached, 3);
+                assert!(central.restore_next_groups().await.unwrap().is_none());
+
+                for id in &ids {
+                    central.encrypt_message(id, b"Test").await.unwrap();
+                }
+                assert_eq!(central.conversation_cache_stats().misses, 1);
+
+                central.mls_backend.destroy_and_reset().await.unwrap();
             })
         })
         .await
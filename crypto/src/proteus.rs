@@ -15,13 +15,16 @@
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
 use crate::{
+    cancellation::{check_cancelled, CancellationToken},
     group_store::{GroupStore, GroupStoreValue},
-    CoreCrypto, CryptoError, CryptoResult, ProteusError,
+    prelude::{ConversationId, MlsConversationConfiguration, MlsCredentialType},
+    CoreCrypto, CoreCryptoCallbacks, CryptoError, CryptoResult, MlsError, ProteusError,
 };
 use core_crypto_keystore::{
-    entities::{ProteusIdentity, ProteusSession},
-    Connection as CryptoKeystore,
+    entities::{ProteusIdentity, ProteusRemoteIdentity, ProteusSession},
+    Connection as CryptoKeystore, CryptoKeystoreError, CryptoKeystoreResult,
 };
+use openmls_traits::{crypto::OpenMlsCrypto, types::HashType, OpenMlsCryptoProvider};
 use proteus_wasm::{
     keys::{IdentityKeyPair, PreKeyBundle},
     message::Envelope,
@@ -29,6 +32,227 @@ use proteus_wasm::{
 };
 use std::{collections::HashMap, sync::Arc};
 
+/// Outcome of [CoreCrypto::migrate_proteus_conversation]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProteusMigrationReport {
+    /// Proteus session the conversation has been migrated from
+    pub session_id: String,
+    /// MLS conversation now standing for the Proteus session
+    pub conversation_id: ConversationId,
+    /// Fingerprint of the remote identity of the Proteus session, to be matched against the credential the peer
+    /// joins the MLS conversation with
+    pub remote_fingerprint: String,
+    /// Whether the MLS conversation has been created. `false` when it already existed e.g. when retrying
+    pub conversation_created: bool,
+}
+
+/// Redacted view of a Proteus session for support bundles: it only holds counters, never any key material
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProteusSessionDebugInfo {
+    /// see [ProteusConversationSession::identifier]
+    pub session_id: String,
+    /// Hex-encoded tag of the current session state, the same on both ends of the session
+    pub session_tag: String,
+    /// Whether nothing was received on this session yet. Outgoing messages then still carry the prekey it was
+    /// initiated from
+    pub pending_prekey: bool,
+    /// Number of session states kept, the current one included
+    pub session_states: usize,
+    /// Number of messages sent on the current sending chain
+    pub send_chain_counter: u32,
+    /// Number of messages sent on the previous sending chain
+    pub previous_send_chain_counter: u32,
+    /// Index of the next message expected on each receiving chain of the current session state
+    pub recv_chain_counters: Vec<u32>,
+    /// Number of message keys kept aside to decrypt delayed messages, across the receiving chains
+    pub skipped_message_keys: usize,
+}
+
+impl ProteusSessionDebugInfo {
+    /// Reads the counters out of a serialized session. The key material it contains is skipped over
+    fn from_serialized(session_id: &str, session: &[u8]) -> CryptoResult<Self> {
+        use ciborium::value::Value;
+
+        fn field(value: &Value, key: u8) -> Option<&Value> {
+            value
+                .as_map()?
+                .iter()
+                .find_map(|(k, v)| (k.as_integer()? == key.into()).then_some(v))
+        }
+
+        fn counter(value: &Value) -> Option<u32> {
+            u32::try_from(value.as_integer()?).ok()
+        }
+
+        /// Index of the chain key of a sending or receiving chain
+        fn chain_counter(chain: &Value) -> Option<u32> {
+            counter(field(field(chain, 0)?, 1)?)
+        }
+
+        let parse = || -> Option<Self> {
+            let session: Value = ciborium::de::from_reader(session).ok()?;
+            let tag = field(&session, 1)?.as_bytes()?;
+            let states = field(&session, 5)?.as_map()?;
+            let current = states
+                .iter()
+                .find_map(|(state_tag, state)| (state_tag.as_bytes()? == tag).then_some(state))?;
+            let recv_chains = field(current, 0)?.as_array()?;
+
+            Some(Self {
+                session_id: session_id.to_string(),
+                session_tag: hex::encode(tag),
+                pending_prekey: !field(&session, 4)?.is_null(),
+                session_states: states.len(),
+                send_chain_counter: chain_counter(field(current, 1)?)?,
+                previous_send_chain_counter: counter(field(current, 3)?)?,
+                recv_chain_counters: recv_chains.iter().map(chain_counter).collect::<Option<_>>()?,
+                skipped_message_keys: recv_chains
+                    .iter()
+                    .map(|chain| Some(field(chain, 2)?.as_array()?.len()))
+                    .sum::<Option<usize>>()?,
+            })
+        };
+        // the sessions are serialized by proteus itself hence always have this layout
+        parse().ok_or(CryptoError::ImplementationError)
+    }
+}
+
+/// Redacted overview of the Proteus state for support bundles, see [CoreCrypto::proteus_diagnostics]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProteusDiagnostics {
+    /// see [CoreCrypto::proteus_fingerprint]
+    pub identity_fingerprint: String,
+    /// Every session of the keystore, including those not loaded in memory
+    pub sessions: Vec<ProteusSessionDebugInfo>,
+}
+
+/// Kind of item migrated from a Cryptobox data store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoboxMigrationItem {
+    /// The identity keypair
+    Identity,
+    /// A Proteus session
+    Session,
+    /// A Proteus prekey
+    PreKey,
+}
+
+/// Item that could not be migrated from a Cryptobox data store, e.g. because it is corrupted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoboxMigrationItemError {
+    /// Kind of the item
+    pub item: CryptoboxMigrationItem,
+    /// Identifier of the item i.e. the session id or the prekey id
+    pub id: String,
+    /// Why it could not be migrated
+    pub reason: String,
+}
+
+/// Progress of a Cryptobox migration, reported after each processed item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CryptoboxMigrationProgress {
+    /// Kind of the items being migrated
+    pub item: CryptoboxMigrationItem,
+    /// Number of items of this kind processed so far
+    pub processed: usize,
+    /// Number of items of this kind to process
+    pub total: usize,
+}
+
+/// Options of [CoreCrypto::proteus_cryptobox_migrate_with_options]
+#[derive(Default)]
+pub struct CryptoboxMigrationOptions<'a> {
+    /// Only reports what would be migrated, without writing anything to the keystore nor to the Cryptobox data store
+    pub dry_run: bool,
+    /// Called after each processed session & prekey
+    pub on_progress: Option<&'a (dyn Fn(CryptoboxMigrationProgress) + Send + Sync)>,
+    /// Stops the migration before the next session or prekey once cancelled. What has been migrated so far is kept,
+    /// migrating again resumes where it stopped since the items already in the keystore are skipped
+    pub cancellation: Option<&'a CancellationToken>,
+}
+
+impl CryptoboxMigrationOptions<'_> {
+    fn progress(&self, item: CryptoboxMigrationItem, processed: usize, total: usize) {
+        if let Some(on_progress) = self.on_progress {
+            on_progress(CryptoboxMigrationProgress { item, processed, total });
+        }
+    }
+
+    fn check_cancelled(&self) -> CryptoResult<()> {
+        check_cancelled(self.cancellation)
+    }
+}
+
+/// Outcome of [CoreCrypto::proteus_cryptobox_migrate_with_options]. Items already present in the keystore are
+/// neither counted nor reported as errors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CryptoboxMigrationReport {
+    /// Number of identities migrated (or which would be, in a dry run)
+    pub identities: usize,
+    /// Number of sessions migrated (or which would be, in a dry run)
+    pub sessions: usize,
+    /// Number of prekeys migrated (or which would be, in a dry run)
+    pub prekeys: usize,
+    /// Items which could not be migrated
+    pub errors: Vec<CryptoboxMigrationItemError>,
+}
+
+impl CryptoboxMigrationReport {
+    fn failed(&mut self, item: CryptoboxMigrationItem, id: impl Into<String>, reason: impl ToString) {
+        self.errors.push(CryptoboxMigrationItemError {
+            item,
+            id: id.into(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// Storage for the Proteus prekeys, used instead of the keystore when provided to
+/// [CoreCrypto::proteus_init_with_store] e.g. to keep them in a hardware-backed store.
+///
+/// Only reading and deleting prekeys (once they have been consumed) go through it. Prekeys created with
+/// [CoreCrypto::proteus_new_prekey] still end up in the keystore, so they have to be provisioned in this store by its
+/// owner.
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait ProteusPreKeyStore: std::fmt::Debug + Send + Sync {
+    /// Fetches the CBOR-serialized prekey with the given id, if any
+    async fn prekey(&self, id: u16) -> CryptoKeystoreResult<Option<Vec<u8>>>;
+
+    /// Deletes the prekey with the given id. Called once a (non last resort) prekey has been consumed
+    async fn remove_prekey(&self, id: u16) -> CryptoKeystoreResult<()>;
+}
+
+/// Routes prekey reads & deletions either to the keystore or to the [ProteusPreKeyStore] overriding it
+#[derive(Debug)]
+enum PreKeyStoreAdapter<'a> {
+    Keystore(&'a mut CryptoKeystore),
+    External(&'a dyn ProteusPreKeyStore),
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl proteus_traits::PreKeyStore for PreKeyStoreAdapter<'_> {
+    type Error = CryptoKeystoreError;
+
+    async fn prekey(
+        &mut self,
+        id: proteus_traits::RawPreKeyId,
+    ) -> Result<Option<proteus_traits::RawPreKey>, Self::Error> {
+        match self {
+            Self::Keystore(keystore) => proteus_traits::PreKeyStore::prekey(&mut **keystore, id).await,
+            Self::External(store) => store.prekey(id).await,
+        }
+    }
+
+    async fn remove(&mut self, id: proteus_traits::RawPreKeyId) -> Result<(), Self::Error> {
+        match self {
+            Self::Keystore(keystore) => proteus_traits::PreKeyStore::remove(&mut **keystore, id).await,
+            Self::External(store) => store.remove_prekey(id).await,
+        }
+    }
+}
+
 /// Proteus session IDs, it seems it's basically a string
 pub type SessionIdentifier = String;
 
@@ -37,6 +261,9 @@ pub type SessionIdentifier = String;
 pub struct ProteusConversationSession {
     pub(crate) identifier: SessionIdentifier,
     pub(crate) session: Session<Arc<IdentityKeyPair>>,
+    /// Serialized state this session has been loaded from or last persisted as, `None` until it is persisted. See
+    /// [ProteusCentral::check_in_sync]
+    pub(crate) synced: Option<zeroize::Zeroizing<Vec<u8>>>,
 }
 
 impl ProteusConversationSession {
@@ -52,7 +279,7 @@ impl ProteusConversationSession {
     /// Decrypts a message for this Proteus session
     pub async fn decrypt(
         &mut self,
-        store: &mut core_crypto_keystore::Connection,
+        store: &mut impl proteus_traits::PreKeyStore<Error = CryptoKeystoreError>,
         ciphertext: &[u8],
     ) -> CryptoResult<Vec<u8>> {
         let envelope = Envelope::deserialise(ciphertext).map_err(ProteusError::from)?;
@@ -77,6 +304,12 @@ impl ProteusConversationSession {
     pub fn fingerprint_remote(&self) -> String {
         self.session.remote_identity().fingerprint()
     }
+
+    /// Redacted view of this session for support bundles, see [ProteusSessionDebugInfo]
+    pub fn debug_info(&self) -> CryptoResult<ProteusSessionDebugInfo> {
+        let session = self.session.serialise().map_err(ProteusError::from)?;
+        ProteusSessionDebugInfo::from_serialized(&self.identifier, &session)
+    }
 }
 
 impl CoreCrypto {
@@ -93,6 +326,20 @@ impl CoreCrypto {
         Ok(())
     }
 
+    /// Initializes the proteus client, reading and deleting its prekeys from `store` instead of the keystore. The
+    /// sessions and the identity are still kept in the keystore.
+    ///
+    /// Unlike [CoreCrypto::proteus_init], this does not create the last resort prekey: it has to be provisioned in
+    /// `store` beforehand.
+    pub async fn proteus_init_with_store(&mut self, store: Box<dyn ProteusPreKeyStore>) -> CryptoResult<()> {
+        let keystore = self.mls.mls_backend.borrow_keystore();
+        let mut proteus_client = ProteusCentral::try_new(keystore).await?;
+        proteus_client.prekey_store(store);
+
+        self.proteus = Some(proteus_client);
+        Ok(())
+    }
+
     /// Reloads the sessions from the key store
     ///
     /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or it will do nothing
@@ -105,8 +352,34 @@ impl CoreCrypto {
         }
     }
 
+    /// Whether Proteus sessions are persisted right after every successful encryption or decryption, which is the
+    /// default. See [ProteusCentral::set_auto_persist]
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub fn proteus_set_auto_persist(&mut self, enabled: bool) -> CryptoResult<()> {
+        let proteus = self.proteus.as_mut().ok_or(CryptoError::ProteusNotInitialized)?;
+        proteus.set_auto_persist(enabled);
+        Ok(())
+    }
+
+    /// Ids of the sessions loaded in memory which are out of sync with the keystore, see
+    /// [ProteusCentral::stale_sessions]. When another process sharing the keystore (e.g. a notification extension)
+    /// may have used them, [CoreCrypto::proteus_reload_sessions] has to be called before decrypting anything if this
+    /// is not empty.
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub async fn proteus_stale_sessions(&self) -> CryptoResult<Vec<String>> {
+        let proteus = self.proteus.as_ref().ok_or(CryptoError::ProteusNotInitialized)?;
+        let keystore = self.mls.mls_backend.borrow_keystore();
+        proteus.stale_sessions(keystore, self.mls.mls_backend.crypto()).await
+    }
+
     /// Creates a proteus session from a prekey
     ///
+    /// When a session with the same id has been created with another remote identity before, the change has to be
+    /// confirmed through [CoreCryptoCallbacks::proteus_identity_changed] or this fails with
+    /// [CryptoError::ProteusIdentityChanged], see [ProteusCentral::check_remote_identity]
+    ///
     /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
     pub async fn proteus_session_from_prekey(
         &mut self,
@@ -114,8 +387,11 @@ impl CoreCrypto {
         prekey: &[u8],
     ) -> CryptoResult<GroupStoreValue<ProteusConversationSession>> {
         let proteus = self.proteus.as_mut().ok_or(CryptoError::ProteusNotInitialized)?;
-        let session = proteus.session_from_prekey(session_id, prekey).await?;
         let keystore = self.mls.mls_backend.borrow_keystore_mut();
+        let callbacks = self.mls.callbacks.as_deref();
+        let session = proteus
+            .session_from_prekey_checked(keystore, session_id, prekey, callbacks)
+            .await?;
         ProteusCentral::session_save_by_ref(keystore, session.clone()).await?;
 
         Ok(session)
@@ -123,6 +399,9 @@ impl CoreCrypto {
 
     /// Creates a proteus session from a Proteus message envelope
     ///
+    /// The remote identity is checked like in [CoreCrypto::proteus_session_from_prekey]. The message is lost when the
+    /// session is rejected
+    ///
     /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
     pub async fn proteus_session_from_message(
         &mut self,
@@ -131,7 +410,10 @@ impl CoreCrypto {
     ) -> CryptoResult<(GroupStoreValue<ProteusConversationSession>, Vec<u8>)> {
         let proteus = self.proteus.as_mut().ok_or(CryptoError::ProteusNotInitialized)?;
         let keystore = self.mls.mls_backend.borrow_keystore_mut();
-        let (session, message) = proteus.session_from_message(keystore, session_id, envelope).await?;
+        let callbacks = self.mls.callbacks.as_deref();
+        let (session, message) = proteus
+            .session_from_message_checked(keystore, session_id, envelope, callbacks)
+            .await?;
         ProteusCentral::session_save_by_ref(keystore, session.clone()).await?;
 
         Ok((session, message))
@@ -146,7 +428,8 @@ impl CoreCrypto {
         proteus.session_save(keystore, session_id).await
     }
 
-    /// Deletes a proteus session from the keystore
+    /// Deletes a proteus session from the keystore. The remote identity recorded for it is kept, see
+    /// [ProteusCentral::check_remote_identity]
     ///
     /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
     pub async fn proteus_session_delete(&mut self, session_id: &str) -> CryptoResult<()> {
@@ -185,6 +468,34 @@ impl CoreCrypto {
         proteus.decrypt(keystore, session_id, ciphertext).await
     }
 
+    /// Decrypts several proteus messages of a given session ID, persisting the updated session only once at the end.
+    /// This is more efficient than calling [CoreCrypto::proteus_decrypt] for each of them, e.g. when catching up
+    /// from a notification extension
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub async fn proteus_decrypt_batched(
+        &mut self,
+        session_id: &str,
+        ciphertexts: &[impl AsRef<[u8]>],
+    ) -> CryptoResult<Vec<Vec<u8>>> {
+        let proteus = self.proteus.as_mut().ok_or(CryptoError::ProteusNotInitialized)?;
+        let keystore = self.mls.mls_backend.borrow_keystore_mut();
+        proteus.decrypt_batched(keystore, session_id, ciphertexts).await
+    }
+
+    /// Decrypts several proteus messages for each of the given session IDs, persisting each updated session only once
+    /// at the end. See [CoreCrypto::proteus_decrypt_batched]
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub async fn proteus_decrypt_batched_multi(
+        &mut self,
+        messages: &std::collections::HashMap<String, Vec<Vec<u8>>>,
+    ) -> CryptoResult<std::collections::HashMap<String, Vec<Vec<u8>>>> {
+        let proteus = self.proteus.as_mut().ok_or(CryptoError::ProteusNotInitialized)?;
+        let keystore = self.mls.mls_backend.borrow_keystore_mut();
+        proteus.decrypt_batched_multi(keystore, messages).await
+    }
+
     /// Encrypts proteus message for a given session ID
     ///
     /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
@@ -279,6 +590,102 @@ impl CoreCrypto {
         }
     }
 
+    /// Redacted overview of the identity and of every session, meant to be attached to support tickets
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub async fn proteus_diagnostics(&self) -> CryptoResult<ProteusDiagnostics> {
+        let proteus = self.proteus.as_ref().ok_or(CryptoError::ProteusNotInitialized)?;
+        proteus.diagnostics(self.mls.mls_backend.borrow_keystore()).await
+    }
+
+    /// Payload of the QR code verifying the session with the remote client, see [crate::verification]. It is the
+    /// same on both ends of the session
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first or an error will be returned
+    pub async fn proteus_fingerprint_qr(&mut self, session_id: &str) -> CryptoResult<String> {
+        let local = self.proteus_fingerprint_local(session_id).await?;
+        let remote = self.proteus_fingerprint_remote(session_id).await?;
+        Ok(crate::verification::proteus_payload(&local, &remote))
+    }
+
+    /// Bootstraps the MLS conversation `conversation_id` taking over the existing Proteus session `session_id`.
+    /// The Proteus session is left untouched so that it can still be used until the peer has joined the MLS
+    /// conversation. Migrating an already migrated session does not create the conversation again, which makes it
+    /// safe to retry.
+    ///
+    /// Warning: The Proteus client **MUST** be initialized with [CoreCrypto::proteus_init] first and the MLS one
+    /// as well, or an error will be returned
+    pub async fn migrate_proteus_conversation(
+        &mut self,
+        session_id: &str,
+        conversation_id: &ConversationId,
+        creator_credential_type: MlsCredentialType,
+        config: MlsConversationConfiguration,
+    ) -> CryptoResult<ProteusMigrationReport> {
+        if !self.proteus_session_exists(session_id).await? {
+            return Err(CryptoError::ConversationNotFound(session_id.as_bytes().into()));
+        }
+        let remote_fingerprint = self.proteus_fingerprint_remote(session_id).await?;
+
+        let conversation_created = !self.mls.conversation_exists(conversation_id).await;
+        if conversation_created {
+            self.mls
+                .new_conversation(conversation_id, creator_credential_type, config)
+                .await?;
+        }
+
+        Ok(ProteusMigrationReport {
+            session_id: session_id.to_string(),
+            conversation_id: conversation_id.clone(),
+            remote_fingerprint,
+            conversation_created,
+        })
+    }
+
+    /// Migrates many Proteus sessions at once, e.g. every 1:1 conversation of the account, see
+    /// [CoreCrypto::migrate_proteus_conversation]. A session failing to be migrated does not prevent the others from
+    /// being migrated, and since already migrated sessions are reported without creating their conversation again,
+    /// the whole batch can be retried.
+    ///
+    /// # Arguments
+    /// * `migrations` - the Proteus sessions along with the id of the MLS conversation taking them over
+    /// * `creator_credential_type` - kind of credential the conversations are created with
+    /// * `config` - configuration of the MLS conversations
+    ///
+    /// # Return type
+    /// The report of every session, or why it could not be migrated, in the order of `migrations`
+    ///
+    /// # Errors
+    /// [CryptoError::ProteusNotInitialized] or [CryptoError::MlsNotInitialized], in which case nothing is migrated
+    pub async fn migrate_proteus_conversations(
+        &mut self,
+        migrations: Vec<(SessionIdentifier, ConversationId)>,
+        creator_credential_type: MlsCredentialType,
+        config: MlsConversationConfiguration,
+    ) -> CryptoResult<Vec<(SessionIdentifier, CryptoResult<ProteusMigrationReport>)>> {
+        self.proteus.as_ref().ok_or(CryptoError::ProteusNotInitialized)?;
+        self.mls.mls_client()?;
+
+        let mut reports = Vec::with_capacity(migrations.len());
+        for (session_id, conversation_id) in migrations {
+            let report = self
+                .migrate_proteus_conversation(&session_id, &conversation_id, creator_credential_type, config.clone())
+                .await;
+            if let Err(e) = &report {
+                crate::logger::warn(
+                    "Could not migrate a Proteus session",
+                    serde_json::json!({
+                        "session_id": session_id,
+                        "conversation_id": hex::encode(&conversation_id),
+                        "error": e.to_string(),
+                    }),
+                );
+            }
+            reports.push((session_id, report));
+        }
+        Ok(reports)
+    }
+
     /// Migrates an existing Cryptobox data store (whether a folder or an IndexedDB database) located at `path` to the keystore.
     ///
     ///The client can then be initialized with [CoreCrypto::proteus_init]
@@ -286,6 +693,17 @@ impl CoreCrypto {
         let keystore = self.mls.mls_backend.borrow_keystore();
         ProteusCentral::cryptobox_migrate(keystore, path).await
     }
+
+    /// Same as [CoreCrypto::proteus_cryptobox_migrate] but with the ability to only preflight the migration and to
+    /// follow its progress. Corrupted sessions & prekeys do not abort the migration, they are reported instead.
+    pub async fn proteus_cryptobox_migrate_with_options(
+        &self,
+        path: &str,
+        options: CryptoboxMigrationOptions<'_>,
+    ) -> CryptoResult<CryptoboxMigrationReport> {
+        let keystore = self.mls.mls_backend.borrow_keystore();
+        ProteusCentral::cryptobox_migrate_with_options(keystore, path, options).await
+    }
 }
 
 /// Proteus counterpart of [crate::mls::MlsCentral]
@@ -295,6 +713,8 @@ impl CoreCrypto {
 pub struct ProteusCentral {
     proteus_identity: Arc<IdentityKeyPair>,
     proteus_sessions: GroupStore<ProteusConversationSession>,
+    prekey_store: Option<Box<dyn ProteusPreKeyStore>>,
+    auto_persist: bool,
 }
 
 impl ProteusCentral {
@@ -306,15 +726,91 @@ impl ProteusCentral {
         Ok(Self {
             proteus_identity,
             proteus_sessions,
+            prekey_store: None,
+            auto_persist: true,
         })
     }
 
+    /// Reads & deletes the prekeys from `store` instead of the keystore from now on
+    pub fn prekey_store(&mut self, store: Box<dyn ProteusPreKeyStore>) {
+        self.prekey_store = Some(store);
+    }
+
+    /// Whether sessions are persisted right after every successful encryption or decryption, which is the default.
+    /// When disabled, they have to be persisted with [ProteusCentral::session_save] and a failed batched decryption
+    /// reverts the session to its persisted state
+    pub fn set_auto_persist(&mut self, enabled: bool) {
+        self.auto_persist = enabled;
+    }
+
+    /// Fails with [CryptoError::ProteusSessionOutOfSync] when the persisted state of a session is not the one it has
+    /// been loaded from or last persisted as, i.e. another process sharing the keystore (e.g. a notification
+    /// extension) used it in the meantime. Going on would make the ratchets of both sides diverge, hence the stale
+    /// session is discarded from memory so that the persisted one is used from now on
+    async fn check_in_sync(
+        &mut self,
+        keystore: &CryptoKeystore,
+        session: &ProteusConversationSession,
+    ) -> CryptoResult<()> {
+        let Some(synced) = &session.synced else {
+            return Ok(());
+        };
+        let persisted = keystore.find::<ProteusSession>(session.identifier.as_bytes()).await?;
+        if persisted.is_some_and(|persisted| persisted.session == **synced) {
+            return Ok(());
+        }
+
+        let _ = self.proteus_sessions.remove(session.identifier.as_bytes());
+        Err(CryptoError::ProteusSessionOutOfSync(session.identifier.clone()))
+    }
+
+    fn prekeys<'a>(
+        prekey_store: &'a Option<Box<dyn ProteusPreKeyStore>>,
+        keystore: &'a mut CryptoKeystore,
+    ) -> PreKeyStoreAdapter<'a> {
+        match prekey_store {
+            Some(store) => PreKeyStoreAdapter::External(store.as_ref()),
+            None => PreKeyStoreAdapter::Keystore(keystore),
+        }
+    }
+
     /// Restore proteus sessions from disk
     pub async fn reload_sessions(&mut self, keystore: &CryptoKeystore) -> CryptoResult<()> {
         self.proteus_sessions = Self::restore_sessions(keystore, &self.proteus_identity).await?;
         Ok(())
     }
 
+    /// Ids of the sessions loaded in memory whose state differs from the one persisted in the keystore, or which
+    /// have been deleted from it, i.e. which another process sharing the keystore has used in the meantime. States
+    /// are compared by the hash of their serialization so this only costs a single keystore read. Sessions not
+    /// loaded in memory are never stale since they're fetched from the keystore when needed
+    pub async fn stale_sessions(
+        &self,
+        keystore: &CryptoKeystore,
+        crypto: &impl OpenMlsCrypto,
+    ) -> CryptoResult<Vec<SessionIdentifier>> {
+        let hash = |session: &[u8]| crypto.hash(HashType::Sha2_256, session).map_err(MlsError::from);
+
+        let loaded = self.proteus_sessions.values().cloned().collect::<Vec<_>>();
+        let mut hashes = HashMap::with_capacity(loaded.len());
+        for session in loaded {
+            let session = session.read().await;
+            let serialized = session.session.serialise().map_err(ProteusError::from)?;
+            hashes.insert(session.identifier.clone(), hash(&serialized)?);
+        }
+
+        let ids = hashes.keys().cloned().collect::<Vec<_>>();
+        for persisted in keystore.find_many::<ProteusSession, _>(&ids).await? {
+            if hashes.get(&persisted.id) == Some(&hash(&persisted.session)?) {
+                hashes.remove(&persisted.id);
+            }
+        }
+
+        let mut stale = hashes.into_keys().collect::<Vec<_>>();
+        stale.sort();
+        Ok(stale)
+    }
+
     /// This function will try to load a proteus Identity from our keystore; If it cannot, it will create a new one
     /// This means this function doesn't fail except in cases of deeper errors (such as in the Keystore and other crypto errors)
     async fn load_or_create_identity(keystore: &CryptoKeystore) -> CryptoResult<IdentityKeyPair> {
@@ -364,6 +860,7 @@ impl ProteusCentral {
             let proteus_conversation = ProteusConversationSession {
                 identifier: identifier.clone(),
                 session: proteus_session,
+                synced: Some(session.session.clone().into()),
             };
 
             if proteus_sessions
@@ -383,18 +880,20 @@ impl ProteusCentral {
         session_id: &str,
         key: &[u8],
     ) -> CryptoResult<GroupStoreValue<ProteusConversationSession>> {
+        let session = self.new_session_from_prekey(session_id, key)?;
+        Ok(self.insert_session(session))
+    }
+
+    fn new_session_from_prekey(&self, session_id: &str, key: &[u8]) -> CryptoResult<ProteusConversationSession> {
         let prekey = PreKeyBundle::deserialise(key).map_err(ProteusError::from)?;
         let proteus_session =
             Session::init_from_prekey(self.proteus_identity.clone(), prekey).map_err(ProteusError::from)?;
 
-        let proteus_conversation = ProteusConversationSession {
+        Ok(ProteusConversationSession {
             identifier: session_id.into(),
             session: proteus_session,
-        };
-
-        self.proteus_sessions.insert(session_id.into(), proteus_conversation);
-
-        Ok(self.proteus_sessions.get(session_id.as_bytes()).unwrap().clone())
+            synced: None,
+        })
     }
 
     /// Creates a new proteus Session from a received message
@@ -404,22 +903,112 @@ impl ProteusCentral {
         session_id: &str,
         envelope: &[u8],
     ) -> CryptoResult<(GroupStoreValue<ProteusConversationSession>, Vec<u8>)> {
+        let (session, payload) = self.new_session_from_message(keystore, session_id, envelope).await?;
+        Ok((self.insert_session(session), payload))
+    }
+
+    async fn new_session_from_message(
+        &self,
+        keystore: &mut CryptoKeystore,
+        session_id: &str,
+        envelope: &[u8],
+    ) -> CryptoResult<(ProteusConversationSession, Vec<u8>)> {
         let message = Envelope::deserialise(envelope).map_err(ProteusError::from)?;
-        let (session, payload) = Session::init_from_message(self.proteus_identity.clone(), keystore, &message)
+        let mut prekeys = Self::prekeys(&self.prekey_store, keystore);
+        let (session, payload) = Session::init_from_message(self.proteus_identity.clone(), &mut prekeys, &message)
             .await
             .map_err(ProteusError::from)?;
 
         let proteus_conversation = ProteusConversationSession {
             identifier: session_id.into(),
             session,
+            synced: None,
         };
 
-        self.proteus_sessions.insert(session_id.into(), proteus_conversation);
+        Ok((proteus_conversation, payload))
+    }
 
-        Ok((
-            self.proteus_sessions.get(session_id.as_bytes()).unwrap().clone(),
-            payload,
-        ))
+    fn insert_session(&mut self, session: ProteusConversationSession) -> GroupStoreValue<ProteusConversationSession> {
+        let session_id = session.identifier.clone();
+        self.proteus_sessions.insert(session_id.clone().into_bytes(), session);
+        self.proteus_sessions.get(session_id.as_bytes()).unwrap().clone()
+    }
+
+    /// Creates a new session from a prekey, like [ProteusCentral::session_from_prekey], once its remote identity
+    /// passed [ProteusCentral::check_remote_identity]
+    pub async fn session_from_prekey_checked(
+        &mut self,
+        keystore: &mut CryptoKeystore,
+        session_id: &str,
+        key: &[u8],
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+    ) -> CryptoResult<GroupStoreValue<ProteusConversationSession>> {
+        let session = self.new_session_from_prekey(session_id, key)?;
+        self.check_remote_identity(keystore, session_id, session.fingerprint_remote(), callbacks)
+            .await?;
+        Ok(self.insert_session(session))
+    }
+
+    /// Creates a new session from a received message, like [ProteusCentral::session_from_message], once its remote
+    /// identity passed [ProteusCentral::check_remote_identity]. The prekey the message has been encrypted with is
+    /// consumed even when the session is rejected
+    pub async fn session_from_message_checked(
+        &mut self,
+        keystore: &mut CryptoKeystore,
+        session_id: &str,
+        envelope: &[u8],
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+    ) -> CryptoResult<(GroupStoreValue<ProteusConversationSession>, Vec<u8>)> {
+        let (session, payload) = self.new_session_from_message(keystore, session_id, envelope).await?;
+        self.check_remote_identity(keystore, session_id, session.fingerprint_remote(), callbacks)
+            .await?;
+        Ok((self.insert_session(session), payload))
+    }
+
+    /// Checks the remote identity a session is about to be created with against the one recorded for the same
+    /// session id, then records it. The identity is recorded apart from the session so that it outlives it: a session
+    /// deleted and created again, e.g. after the remote client has been reinstalled, is still checked.
+    ///
+    /// A different identity has to be confirmed through [CoreCryptoCallbacks::proteus_identity_changed], otherwise
+    /// this fails with [CryptoError::ProteusIdentityChanged] and nothing is recorded. Without callbacks, such a change
+    /// is always rejected
+    pub async fn check_remote_identity(
+        &mut self,
+        keystore: &mut CryptoKeystore,
+        session_id: &str,
+        fingerprint: String,
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+    ) -> CryptoResult<()> {
+        let recorded = match keystore.find::<ProteusRemoteIdentity>(session_id.as_bytes()).await? {
+            Some(identity) => Some(identity.fingerprint.clone()),
+            // sessions created before identities were recorded only know it from their own state
+            None => match self.session(session_id, keystore).await? {
+                Some(session) => Some(session.read().await.fingerprint_remote()),
+                None => None,
+            },
+        };
+
+        if let Some(previous) = recorded.filter(|previous| previous != &fingerprint) {
+            let confirmed = match callbacks {
+                Some(callbacks) => {
+                    callbacks
+                        .proteus_identity_changed(session_id.to_string(), previous, fingerprint.clone())
+                        .await
+                }
+                None => false,
+            };
+            if !confirmed {
+                return Err(CryptoError::ProteusIdentityChanged(session_id.to_string()));
+            }
+        }
+
+        keystore
+            .save(ProteusRemoteIdentity {
+                id: session_id.to_string(),
+                fingerprint,
+            })
+            .await?;
+        Ok(())
     }
 
     /// Persists a session in store
@@ -431,7 +1020,14 @@ impl ProteusCentral {
             .get_fetch(session_id.as_bytes(), keystore, Some(self.proteus_identity.clone()))
             .await?
         {
-            Self::session_save_by_ref(keystore, session).await?;
+            let mut session = session.write().await;
+            self.check_in_sync(keystore, &session).await?;
+            Self::persist(keystore, &mut session).await?;
+        } else {
+            crate::logger::warn(
+                "Proteus session to save does not exist",
+                serde_json::json!({ "session_id": session_id }),
+            );
         }
 
         Ok(())
@@ -441,12 +1037,17 @@ impl ProteusCentral {
         keystore: &CryptoKeystore,
         session: GroupStoreValue<ProteusConversationSession>,
     ) -> CryptoResult<()> {
-        let session = session.read().await;
+        Self::persist(keystore, &mut *session.write().await).await
+    }
+
+    async fn persist(keystore: &CryptoKeystore, session: &mut ProteusConversationSession) -> CryptoResult<()> {
+        let serialized = zeroize::Zeroizing::new(session.session.serialise().map_err(ProteusError::from)?);
         let db_session = ProteusSession {
             id: session.identifier().to_string(),
-            session: session.session.serialise().map_err(ProteusError::from)?,
+            session: serialized.to_vec(),
         };
         keystore.save(db_session).await?;
+        session.synced = Some(serialized);
         Ok(())
     }
 
@@ -487,8 +1088,14 @@ impl ProteusCentral {
             .get_fetch(session_id.as_bytes(), keystore, Some(self.proteus_identity.clone()))
             .await?
         {
-            let plaintext = session.write().await.decrypt(keystore, ciphertext).await?;
-            ProteusCentral::session_save_by_ref(keystore, session).await?;
+            // the session stays locked until it is persisted so that no other call sees an unpersisted state
+            let mut session = session.write().await;
+            self.check_in_sync(keystore, &session).await?;
+            let mut prekeys = Self::prekeys(&self.prekey_store, keystore);
+            let plaintext = session.decrypt(&mut prekeys, ciphertext).await?;
+            if self.auto_persist {
+                Self::persist(keystore, &mut session).await?;
+            }
 
             Ok(plaintext)
         } else {
@@ -496,6 +1103,102 @@ impl ProteusCentral {
         }
     }
 
+    /// Decrypts several messages of an already existing session, in order. The updated session is only persisted
+    /// once, after all of them have been decrypted.
+    ///
+    /// If any message cannot be decrypted, the session is left as it was before the call and the error is returned
+    pub async fn decrypt_batched(
+        &mut self,
+        keystore: &mut CryptoKeystore,
+        session_id: &str,
+        ciphertexts: &[impl AsRef<[u8]>],
+    ) -> CryptoResult<Vec<Vec<u8>>> {
+        let session = self
+            .session(session_id, keystore)
+            .await?
+            .ok_or_else(|| CryptoError::ConversationNotFound(session_id.as_bytes().into()))?;
+
+        let mut session = session.write().await;
+        self.check_in_sync(keystore, &session).await?;
+        let mut prekeys = Self::prekeys(&self.prekey_store, keystore);
+        match Self::decrypt_all(&mut prekeys, &mut session, ciphertexts).await {
+            Ok(plaintexts) => {
+                if self.auto_persist {
+                    Self::persist(keystore, &mut session).await?;
+                }
+                Ok(plaintexts)
+            }
+            Err(e) => {
+                // the in-memory session is discarded so that it gets fetched again from the keystore
+                let _ = self.proteus_sessions.remove(session_id.as_bytes());
+                Err(e)
+            }
+        }
+    }
+
+    /// Decrypts several messages for each of the given sessions, see [ProteusCentral::decrypt_batched]. Every updated
+    /// session is only persisted once all the messages have been decrypted.
+    ///
+    /// If any message cannot be decrypted, all the sessions are left as they were before the call and the error is
+    /// returned
+    pub async fn decrypt_batched_multi(
+        &mut self,
+        keystore: &mut CryptoKeystore,
+        messages: &HashMap<String, Vec<Vec<u8>>>,
+    ) -> CryptoResult<HashMap<String, Vec<Vec<u8>>>> {
+        let mut acc = HashMap::with_capacity(messages.len());
+        let mut sessions = Vec::with_capacity(messages.len());
+        for (session_id, ciphertexts) in messages {
+            let result = match self.session(session_id, keystore).await? {
+                Some(session) => {
+                    let mut session = session.write_arc().await;
+                    match self.check_in_sync(keystore, &session).await {
+                        Ok(()) => {
+                            let mut prekeys = Self::prekeys(&self.prekey_store, keystore);
+                            let plaintexts = Self::decrypt_all(&mut prekeys, &mut session, ciphertexts).await;
+                            sessions.push(session);
+                            plaintexts
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                None => Err(CryptoError::ConversationNotFound(session_id.as_bytes().into())),
+            };
+
+            match result {
+                Ok(plaintexts) => {
+                    acc.insert(session_id.clone(), plaintexts);
+                }
+                Err(e) => {
+                    // the in-memory sessions are discarded so that they get fetched again from the keystore
+                    for session_id in acc.keys().chain(std::iter::once(session_id)) {
+                        let _ = self.proteus_sessions.remove(session_id.as_bytes());
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        if self.auto_persist {
+            for mut session in sessions {
+                Self::persist(keystore, &mut session).await?;
+            }
+        }
+        Ok(acc)
+    }
+
+    async fn decrypt_all(
+        prekeys: &mut PreKeyStoreAdapter<'_>,
+        session: &mut ProteusConversationSession,
+        ciphertexts: &[impl AsRef<[u8]>],
+    ) -> CryptoResult<Vec<Vec<u8>>> {
+        let mut plaintexts = Vec::with_capacity(ciphertexts.len());
+        for ciphertext in ciphertexts {
+            plaintexts.push(session.decrypt(prekeys, ciphertext.as_ref()).await?);
+        }
+        Ok(plaintexts)
+    }
+
     /// Encrypt a message for a session
     pub async fn encrypt(
         &mut self,
@@ -504,8 +1207,12 @@ impl ProteusCentral {
         plaintext: &[u8],
     ) -> CryptoResult<Vec<u8>> {
         if let Some(session) = self.session(session_id, keystore).await? {
-            let ciphertext = session.write().await.encrypt(plaintext)?;
-            ProteusCentral::session_save_by_ref(keystore, session).await?;
+            let mut session = session.write().await;
+            self.check_in_sync(keystore, &session).await?;
+            let ciphertext = session.encrypt(plaintext)?;
+            if self.auto_persist {
+                Self::persist(keystore, &mut session).await?;
+            }
 
             Ok(ciphertext)
         } else {
@@ -524,11 +1231,12 @@ impl ProteusCentral {
         let mut acc = HashMap::new();
         for session_id in sessions {
             if let Some(session) = self.session(session_id.as_ref(), keystore).await? {
-                let mut session_w = session.write().await;
-                acc.insert(session_w.identifier.clone(), session_w.encrypt(plaintext)?);
-                drop(session_w);
-
-                ProteusCentral::session_save_by_ref(keystore, session).await?;
+                let mut session = session.write().await;
+                self.check_in_sync(keystore, &session).await?;
+                acc.insert(session.identifier.clone(), session.encrypt(plaintext)?);
+                if self.auto_persist {
+                    Self::persist(keystore, &mut session).await?;
+                }
             }
         }
         Ok(acc)
@@ -629,6 +1337,20 @@ impl ProteusCentral {
         }
     }
 
+    /// Redacted overview of the identity and of every session of the keystore, see [ProteusDiagnostics]
+    pub async fn diagnostics(&self, keystore: &CryptoKeystore) -> CryptoResult<ProteusDiagnostics> {
+        let sessions = keystore
+            .find_all::<ProteusSession>(Default::default())
+            .await?
+            .iter()
+            .map(|session| ProteusSessionDebugInfo::from_serialized(&session.id, &session.session))
+            .collect::<CryptoResult<Vec<_>>>()?;
+        Ok(ProteusDiagnostics {
+            identity_fingerprint: self.fingerprint(),
+            sessions,
+        })
+    }
+
     /// Hex-encoded fingerprint of the given prekey
     ///
     /// # Errors
@@ -639,12 +1361,21 @@ impl ProteusCentral {
     }
 
     /// Cryptobox -> CoreCrypto migration
-    #[cfg_attr(not(feature = "cryptobox-migrate"), allow(unused_variables))]
     pub async fn cryptobox_migrate(keystore: &CryptoKeystore, path: &str) -> CryptoResult<()> {
+        Self::cryptobox_migrate_with_options(keystore, path, Default::default()).await?;
+        Ok(())
+    }
+
+    /// Cryptobox -> CoreCrypto migration, see [CoreCrypto::proteus_cryptobox_migrate_with_options]
+    #[cfg_attr(not(feature = "cryptobox-migrate"), allow(unused_variables))]
+    pub async fn cryptobox_migrate_with_options(
+        keystore: &CryptoKeystore,
+        path: &str,
+        options: CryptoboxMigrationOptions<'_>,
+    ) -> CryptoResult<CryptoboxMigrationReport> {
         cfg_if::cfg_if! {
             if #[cfg(feature = "cryptobox-migrate")] {
-                Self::cryptobox_migrate_impl(keystore, path).await?;
-                Ok(())
+                Self::cryptobox_migrate_impl(keystore, path, &options).await
             } else {
                 Err(CryptoError::ProteusSupportNotEnabled("cryptobox-migrate".into()))
             }
@@ -656,7 +1387,11 @@ impl ProteusCentral {
 #[allow(dead_code)]
 impl ProteusCentral {
     #[cfg(not(target_family = "wasm"))]
-    async fn cryptobox_migrate_impl(keystore: &CryptoKeystore, path: &str) -> CryptoResult<()> {
+    async fn cryptobox_migrate_impl(
+        keystore: &CryptoKeystore,
+        path: &str,
+        options: &CryptoboxMigrationOptions<'_>,
+    ) -> CryptoResult<CryptoboxMigrationReport> {
         let root_dir = std::path::PathBuf::from(path);
 
         if !root_dir.exists() {
@@ -666,6 +1401,8 @@ impl ProteusCentral {
         let session_dir = root_dir.join("sessions");
         let prekey_dir = root_dir.join("prekeys");
 
+        let mut report = CryptoboxMigrationReport::default();
+
         let mut identity = if let Some(store_kp) = keystore.find::<ProteusIdentity>(&[]).await? {
             Some(unsafe {
                 IdentityKeyPair::from_raw_key_pair(*store_kp.sk_raw(), *store_kp.pk_raw())
@@ -694,18 +1431,21 @@ impl ProteusCentral {
             };
 
             if let Some((kp, delete)) = identity_check {
-                let pk = kp.public_key.public_key.as_slice().into();
+                if !options.dry_run {
+                    let pk = kp.public_key.public_key.as_slice().into();
 
-                let ks_identity = ProteusIdentity {
-                    sk: kp.secret_key.to_keypair_bytes().into(),
-                    pk,
-                };
+                    let ks_identity = ProteusIdentity {
+                        sk: kp.secret_key.to_keypair_bytes().into(),
+                        pk,
+                    };
 
-                keystore.save(ks_identity).await?;
+                    keystore.save(ks_identity).await?;
 
-                if delete && legacy_identity.exists() {
-                    async_fs::remove_file(legacy_identity).await?;
+                    if delete && legacy_identity.exists() {
+                        async_fs::remove_file(legacy_identity).await?;
+                    }
                 }
+                report.identities += 1;
 
                 Some(kp)
             } else {
@@ -719,60 +1459,111 @@ impl ProteusCentral {
 
         use futures_lite::stream::StreamExt as _;
         // Session migration
-        let mut session_entries = async_fs::read_dir(session_dir).await?;
-        while let Some(session_file) = session_entries.try_next().await? {
-            // The name of the file is the session id
-            let proteus_session_id: String = session_file.file_name().to_string_lossy().to_string();
-
+        let session_entries = async_fs::read_dir(session_dir)
+            .await?
+            .try_collect::<_, _, Vec<_>>()
+            .await?;
+        let total = session_entries.len();
+        // The name of the file is the session id
+        let session_ids = session_entries
+            .iter()
+            .map(|session_file| session_file.file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let stored_session_ids = keystore
+            .find_many::<ProteusSession, _>(&session_ids)
+            .await?
+            .iter()
+            .map(|session| session.id.clone())
+            .collect::<std::collections::HashSet<_>>();
+        for (i, (session_file, proteus_session_id)) in session_entries.into_iter().zip(session_ids).enumerate() {
+            options.check_cancelled()?;
             // If the session is already in store, skip ahead
-            if keystore
-                .find::<ProteusSession>(proteus_session_id.as_bytes())
-                .await?
-                .is_some()
-            {
-                continue;
-            }
+            if !stored_session_ids.contains(&proteus_session_id) {
+                let raw_session = async_fs::read(session_file.path())
+                    .await
+                    .map_err(|e| e.to_string())
+                    // Session integrity check
+                    .and_then(|raw| {
+                        Session::deserialise(&identity, &raw)
+                            .map(|_| raw)
+                            .map_err(|e| e.to_string())
+                    });
 
-            let raw_session = async_fs::read(session_file.path()).await?;
-            // Session integrity check
-            let Ok(_) = Session::deserialise(&identity, &raw_session) else {
-                continue;
-            };
-
-            let keystore_session = ProteusSession {
-                id: proteus_session_id,
-                session: raw_session,
-            };
-
-            keystore.save(keystore_session).await?;
+                match raw_session {
+                    Ok(raw_session) => {
+                        if !options.dry_run {
+                            let keystore_session = ProteusSession {
+                                id: proteus_session_id,
+                                session: raw_session,
+                            };
+                            keystore.save(keystore_session).await?;
+                        }
+                        report.sessions += 1;
+                    }
+                    Err(reason) => report.failed(CryptoboxMigrationItem::Session, proteus_session_id, reason),
+                }
+            }
+            options.progress(CryptoboxMigrationItem::Session, i + 1, total);
         }
 
         // Prekey migration
         use core_crypto_keystore::entities::ProteusPrekey;
-        let mut prekey_entries = async_fs::read_dir(prekey_dir).await?;
-        while let Some(prekey_file) = prekey_entries.try_next().await? {
+        let prekey_entries = async_fs::read_dir(prekey_dir)
+            .await?
+            .try_collect::<_, _, Vec<_>>()
+            .await?;
+        let total = prekey_entries.len();
+        let prekey_ids = prekey_entries
+            .iter()
+            .filter_map(|prekey_file| prekey_file.file_name().to_string_lossy().parse::<u16>().ok())
+            .map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        let stored_prekey_ids = keystore
+            .find_many::<ProteusPrekey, _>(&prekey_ids)
+            .await?
+            .iter()
+            .map(|prekey| prekey.id)
+            .collect::<std::collections::HashSet<_>>();
+        for (i, prekey_file) in prekey_entries.into_iter().enumerate() {
+            options.check_cancelled()?;
             // The name of the file is the prekey id, so we parse it to get the ID
-            let proteus_prekey_id =
-                proteus_wasm::keys::PreKeyId::new(prekey_file.file_name().to_string_lossy().parse()?);
+            let file_name = prekey_file.file_name().to_string_lossy().to_string();
+            let proteus_prekey_id = match file_name.parse::<u16>() {
+                Ok(id) => id,
+                Err(reason) => {
+                    report.failed(CryptoboxMigrationItem::PreKey, file_name, reason);
+                    options.progress(CryptoboxMigrationItem::PreKey, i + 1, total);
+                    continue;
+                }
+            };
 
             // Check if the prekey ID is already existing
-            if keystore
-                .find::<ProteusPrekey>(&proteus_prekey_id.value().to_le_bytes())
-                .await?
-                .is_some()
-            {
-                continue;
-            }
+            if !stored_prekey_ids.contains(&proteus_prekey_id) {
+                let raw_prekey = async_fs::read(prekey_file.path())
+                    .await
+                    .map_err(|e| e.to_string())
+                    // Integrity check to see if the PreKey is actually correct
+                    .and_then(|raw| {
+                        proteus_wasm::keys::PreKey::deserialise(&raw)
+                            .map(|_| raw)
+                            .map_err(|e| e.to_string())
+                    });
 
-            let raw_prekey = async_fs::read(prekey_file.path()).await?;
-            // Integrity check to see if the PreKey is actually correct
-            if proteus_wasm::keys::PreKey::deserialise(&raw_prekey).is_ok() {
-                let keystore_prekey = ProteusPrekey::from_raw(proteus_prekey_id.value(), raw_prekey);
-                keystore.save(keystore_prekey).await?;
+                match raw_prekey {
+                    Ok(raw_prekey) => {
+                        if !options.dry_run {
+                            let keystore_prekey = ProteusPrekey::from_raw(proteus_prekey_id, raw_prekey);
+                            keystore.save(keystore_prekey).await?;
+                        }
+                        report.prekeys += 1;
+                    }
+                    Err(reason) => report.failed(CryptoboxMigrationItem::PreKey, file_name, reason),
+                }
             }
+            options.progress(CryptoboxMigrationItem::PreKey, i + 1, total);
         }
 
-        Ok(())
+        Ok(report)
     }
 
     #[cfg(target_family = "wasm")]
@@ -795,7 +1586,18 @@ impl ProteusCentral {
     }
 
     #[cfg(target_family = "wasm")]
-    async fn cryptobox_migrate_impl(keystore: &CryptoKeystore, path: &str) -> CryptoResult<()> {
+    fn get_cbor_bytes_from_js_value(js_value: wasm_bindgen::JsValue) -> CryptoResult<Vec<u8>> {
+        let js_value: serde_json::map::Map<String, serde_json::Value> =
+            serde_wasm_bindgen::from_value(js_value).map_err(crate::CryptoboxMigrationError::from)?;
+        Self::get_cbor_bytes_from_map(js_value)
+    }
+
+    #[cfg(target_family = "wasm")]
+    async fn cryptobox_migrate_impl(
+        keystore: &CryptoKeystore,
+        path: &str,
+        options: &CryptoboxMigrationOptions<'_>,
+    ) -> CryptoResult<CryptoboxMigrationReport> {
         use rexie::{Rexie, TransactionMode};
 
         use crate::CryptoboxMigrationError;
@@ -822,6 +1624,8 @@ impl ProteusCentral {
             return Err(crate::CryptoboxMigrationError::ProvidedPathDoesNotExist(path.into()).into());
         }
 
+        let mut report = CryptoboxMigrationReport::default();
+
         let mut proteus_identity = if let Some(store_kp) = keystore.find::<ProteusIdentity>(&[]).await? {
             Some(unsafe {
                 proteus_wasm::keys::IdentityKeyPair::from_raw_key_pair(*store_kp.sk_raw(), *store_kp.pk_raw())
@@ -841,20 +1645,20 @@ impl ProteusCentral {
                 .await
                 .map_err(CryptoboxMigrationError::from)?
             {
-                let js_value: serde_json::map::Map<String, serde_json::Value> =
-                    serde_wasm_bindgen::from_value(cryptobox_js_value).map_err(CryptoboxMigrationError::from)?;
-
-                let kp_cbor = Self::get_cbor_bytes_from_map(js_value)?;
+                let kp_cbor = Self::get_cbor_bytes_from_js_value(cryptobox_js_value)?;
 
                 let kp = proteus_wasm::keys::IdentityKeyPair::deserialise(&kp_cbor).map_err(ProteusError::from)?;
 
-                let pk = kp.public_key.public_key.as_slice().to_vec();
+                if !options.dry_run {
+                    let pk = kp.public_key.public_key.as_slice().to_vec();
 
-                let ks_identity = ProteusIdentity {
-                    sk: kp.secret_key.to_keypair_bytes().into(),
-                    pk,
-                };
-                keystore.save(ks_identity).await?;
+                    let ks_identity = ProteusIdentity {
+                        sk: kp.secret_key.to_keypair_bytes().into(),
+                        pk,
+                    };
+                    keystore.save(ks_identity).await?;
+                }
+                report.identities += 1;
 
                 Some(kp)
             } else {
@@ -880,26 +1684,47 @@ impl ProteusCentral {
                 .await
                 .map_err(CryptoboxMigrationError::from)?;
 
-            for (session_id, session_js_value) in sessions.into_iter().map(|(k, v)| (k.as_string().unwrap(), v)) {
+            let total = sessions.len();
+            let sessions = sessions
+                .into_iter()
+                .map(|(k, v)| (k.as_string().unwrap(), v))
+                .collect::<Vec<_>>();
+            let session_ids = sessions.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>();
+            let stored_session_ids = keystore
+                .find_many::<ProteusSession, _>(&session_ids)
+                .await?
+                .iter()
+                .map(|session| session.id.clone())
+                .collect::<std::collections::HashSet<_>>();
+            for (i, (session_id, session_js_value)) in sessions.into_iter().enumerate() {
+                options.check_cancelled()?;
                 // If the session is already in store, skip ahead
-                if keystore.find::<ProteusSession>(session_id.as_bytes()).await?.is_some() {
-                    continue;
-                }
-
-                let js_value: serde_json::map::Map<String, serde_json::Value> =
-                    serde_wasm_bindgen::from_value(session_js_value).map_err(CryptoboxMigrationError::from)?;
-
-                let session_cbor_bytes = Self::get_cbor_bytes_from_map(js_value)?;
-
-                // Integrity check
-                if proteus_wasm::session::Session::deserialise(&proteus_identity, &session_cbor_bytes).is_ok() {
-                    let keystore_session = ProteusSession {
-                        id: session_id,
-                        session: session_cbor_bytes,
-                    };
+                if !stored_session_ids.contains(&session_id) {
+                    let session_cbor_bytes = Self::get_cbor_bytes_from_js_value(session_js_value)
+                        .map_err(|e| e.to_string())
+                        // Integrity check
+                        .and_then(|raw| {
+                            proteus_wasm::session::Session::deserialise(&proteus_identity, &raw)
+                                .map(|_| raw)
+                                .map_err(|e| e.to_string())
+                        });
 
-                    keystore.save(keystore_session).await?;
+                    match session_cbor_bytes {
+                        Ok(session_cbor_bytes) => {
+                            if !options.dry_run {
+                                let keystore_session = ProteusSession {
+                                    id: session_id,
+                                    session: session_cbor_bytes,
+                                };
+
+                                keystore.save(keystore_session).await?;
+                            }
+                            report.sessions += 1;
+                        }
+                        Err(reason) => report.failed(CryptoboxMigrationItem::Session, session_id, reason),
+                    }
                 }
+                options.progress(CryptoboxMigrationItem::Session, i + 1, total);
             }
         }
 
@@ -919,35 +1744,60 @@ impl ProteusCentral {
                 .await
                 .map_err(CryptoboxMigrationError::from)?;
 
-            for (prekey_id, prekey_js_value) in prekeys
+            let total = prekeys.len();
+            let prekeys = prekeys
                 .into_iter()
                 .map(|(id, prekey_js_value)| (id.as_string().unwrap(), prekey_js_value))
-            {
-                let prekey_id: u16 = prekey_id.parse()?;
+                .collect::<Vec<_>>();
+            let prekey_ids = prekeys
+                .iter()
+                .filter_map(|(prekey_id_str, _)| prekey_id_str.parse::<u16>().ok())
+                .map(u16::to_le_bytes)
+                .collect::<Vec<_>>();
+            let stored_prekey_ids = keystore
+                .find_many::<ProteusPrekey, _>(&prekey_ids)
+                .await?
+                .iter()
+                .map(|prekey| prekey.id)
+                .collect::<std::collections::HashSet<_>>();
+            for (i, (prekey_id_str, prekey_js_value)) in prekeys.into_iter().enumerate() {
+                options.check_cancelled()?;
+                let prekey_id = match prekey_id_str.parse::<u16>() {
+                    Ok(id) => id,
+                    Err(reason) => {
+                        report.failed(CryptoboxMigrationItem::PreKey, prekey_id_str, reason);
+                        options.progress(CryptoboxMigrationItem::PreKey, i + 1, total);
+                        continue;
+                    }
+                };
 
                 // Check if the prekey ID is already existing
-                if keystore
-                    .find::<ProteusPrekey>(&prekey_id.to_le_bytes())
-                    .await?
-                    .is_some()
-                {
-                    continue;
-                }
-
-                let js_value: serde_json::map::Map<String, serde_json::Value> =
-                    serde_wasm_bindgen::from_value(prekey_js_value).map_err(CryptoboxMigrationError::from)?;
-
-                let raw_prekey_cbor = Self::get_cbor_bytes_from_map(js_value)?;
+                if !stored_prekey_ids.contains(&prekey_id) {
+                    let raw_prekey_cbor = Self::get_cbor_bytes_from_js_value(prekey_js_value)
+                        .map_err(|e| e.to_string())
+                        // Integrity check to see if the PreKey is actually correct
+                        .and_then(|raw| {
+                            proteus_wasm::keys::PreKey::deserialise(&raw)
+                                .map(|_| raw)
+                                .map_err(|e| e.to_string())
+                        });
 
-                // Integrity check to see if the PreKey is actually correct
-                if proteus_wasm::keys::PreKey::deserialise(&raw_prekey_cbor).is_ok() {
-                    let keystore_prekey = ProteusPrekey::from_raw(prekey_id, raw_prekey_cbor);
-                    keystore.save(keystore_prekey).await?;
+                    match raw_prekey_cbor {
+                        Ok(raw_prekey_cbor) => {
+                            if !options.dry_run {
+                                let keystore_prekey = ProteusPrekey::from_raw(prekey_id, raw_prekey_cbor);
+                                keystore.save(keystore_prekey).await?;
+                            }
+                            report.prekeys += 1;
+                        }
+                        Err(reason) => report.failed(CryptoboxMigrationItem::PreKey, prekey_id_str, reason),
+                    }
                 }
+                options.progress(CryptoboxMigrationItem::PreKey, i + 1, total);
             }
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -990,6 +1840,178 @@ mod tests {
         drop(db_file);
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    async fn cc_can_compute_fingerprint_qr(case: TestCase) {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+        let cfg = MlsCentralConfiguration::try_new(
+            path,
+            "test".to_string(),
+            Some("alice".into()),
+            vec![case.ciphersuite()],
+            None,
+            Some(INITIAL_KEYING_MATERIAL_COUNT),
+        )
+        .unwrap();
+        let mut cc: CoreCrypto = MlsCentral::try_new(cfg).await.unwrap().into();
+        cc.proteus_init().await.unwrap();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+        let err = cc.proteus_fingerprint_qr(&session_id).await.unwrap_err();
+        assert!(matches!(err, CryptoError::ConversationNotFound(_)));
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+        cc.proteus_session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+
+        let payload = cc.proteus_fingerprint_qr(&session_id).await.unwrap();
+        let alice_fingerprint = cc.proteus_fingerprint().unwrap();
+        assert!(payload.contains(&alice_fingerprint.to_uppercase()));
+        assert!(payload.contains(&bob.fingerprint().to_uppercase()));
+        // the very same payload is computed on the side of Bob
+        assert_eq!(
+            payload,
+            crate::verification::proteus_payload(&bob.fingerprint(), &alice_fingerprint)
+        );
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    async fn migration_test_central(case: &TestCase, path: String) -> CoreCrypto {
+        let cfg = MlsCentralConfiguration::try_new(
+            path,
+            "test".to_string(),
+            None,
+            vec![case.ciphersuite()],
+            None,
+            Some(INITIAL_KEYING_MATERIAL_COUNT),
+        )
+        .unwrap();
+        let mut cc: CoreCrypto = MlsCentral::try_new(cfg).await.unwrap().into();
+        let x509_test_chain = X509TestChain::init_empty(case.signature_scheme());
+        x509_test_chain.register_with_central(&cc.mls).await;
+        cc.proteus_init().await.unwrap();
+        let identifier = match case.credential_type {
+            MlsCredentialType::Basic => ClientIdentifier::Basic("alice".into()),
+            MlsCredentialType::X509 => {
+                CertificateBundle::rand_identifier("alice", &[x509_test_chain.find_local_intermediate_ca()])
+            }
+        };
+        cc.mls_init(
+            identifier,
+            vec![case.ciphersuite()],
+            Some(INITIAL_KEYING_MATERIAL_COUNT),
+        )
+        .await
+        .unwrap();
+        cc
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    async fn cc_can_migrate_proteus_conversation(case: TestCase) {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+        let mut cc = migration_test_central(&case, path).await;
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+        let id = conversation_id();
+
+        // there's no such session yet
+        let err = cc
+            .migrate_proteus_conversation(&session_id, &id, case.credential_type, case.cfg.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CryptoError::ConversationNotFound(_)));
+        assert!(!cc.conversation_exists(&id).await);
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+        cc.proteus_session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+
+        let report = cc
+            .migrate_proteus_conversation(&session_id, &id, case.credential_type, case.cfg.clone())
+            .await
+            .unwrap();
+        assert!(report.conversation_created);
+        assert_eq!(report.session_id, session_id);
+        assert_eq!(report.remote_fingerprint, bob.fingerprint());
+        assert!(cc.conversation_exists(&id).await);
+        // the proteus session is still usable
+        assert!(cc.proteus_session_exists(&session_id).await.unwrap());
+
+        // retrying is harmless
+        let report = cc
+            .migrate_proteus_conversation(&session_id, &id, case.credential_type, case.cfg.clone())
+            .await
+            .unwrap();
+        assert!(!report.conversation_created);
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    async fn cc_can_migrate_proteus_conversations_in_batch(case: TestCase) {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+        let mut cc = migration_test_central(&case, path).await;
+
+        let mut bob = CryptoboxLike::init();
+        let bob_session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+        cc.proteus_session_from_prekey(&bob_session_id, &bob.new_prekey().serialise().unwrap())
+            .await
+            .unwrap();
+        let bob_id = conversation_id();
+
+        let missing_session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+        let missing_id = conversation_id();
+
+        let migrations = vec![
+            (bob_session_id.clone(), bob_id.clone()),
+            (missing_session_id.clone(), missing_id.clone()),
+        ];
+        let results = cc
+            .migrate_proteus_conversations(migrations.clone(), case.credential_type, case.cfg.clone())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        // one failure does not prevent the others from being migrated
+        let (session_id, report) = &results[0];
+        assert_eq!(session_id, &bob_session_id);
+        let report = report.as_ref().unwrap();
+        assert!(report.conversation_created);
+        assert_eq!(report.remote_fingerprint, bob.fingerprint());
+        assert!(cc.conversation_exists(&bob_id).await);
+
+        let (session_id, report) = &results[1];
+        assert_eq!(session_id, &missing_session_id);
+        assert!(matches!(report, Err(CryptoError::ConversationNotFound(_))));
+        assert!(!cc.conversation_exists(&missing_id).await);
+
+        // retrying the whole batch only reports what is left to do
+        let results = cc
+            .migrate_proteus_conversations(migrations, case.credential_type, case.cfg.clone())
+            .await
+            .unwrap();
+        assert!(!results[0].1.as_ref().unwrap().conversation_created);
+        assert!(results[1].1.is_err());
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     async fn cc_can_2_phase_init(case: TestCase) {
@@ -1052,6 +2074,8 @@ mod tests {
             .unwrap();
         let central = ProteusCentral::try_new(&keystore).await.unwrap();
         let identity = (*central.proteus_identity).clone();
+        // a store can only be opened by one instance at a time
+        keystore.close().await.unwrap();
 
         let keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
             .await
@@ -1104,6 +2128,271 @@ mod tests {
         drop(db_file);
     }
 
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn can_describe_sessions_without_keys() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+
+        let session = alice
+            .session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let encrypted = alice.encrypt(&mut keystore, &session_id, b"Hello Bob").await.unwrap();
+            bob.decrypt(&session_id, &encrypted).await;
+        }
+
+        let info = session.read().await.debug_info().unwrap();
+        assert_eq!(info.session_id, session_id);
+        assert!(info.pending_prekey);
+        assert_eq!(info.send_chain_counter, 2);
+        let bob_session = bob.sessions[&session_id].serialise().unwrap();
+        let bob_info = ProteusSessionDebugInfo::from_serialized(&session_id, &bob_session).unwrap();
+        assert_eq!(info.session_tag, bob_info.session_tag);
+        assert!(!bob_info.pending_prekey);
+
+        // receiving a reply confirms the session
+        let encrypted = bob.encrypt(&session_id, b"Hello Alice");
+        alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap();
+        let info = session.read().await.debug_info().unwrap();
+        assert!(!info.pending_prekey);
+
+        let diagnostics = alice.diagnostics(&keystore).await.unwrap();
+        assert_eq!(diagnostics.identity_fingerprint, alice.fingerprint());
+        assert_eq!(diagnostics.sessions, vec![info]);
+        let json = serde_json::to_string(&diagnostics).unwrap();
+        assert!(json.contains(&diagnostics.sessions[0].session_tag));
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn can_detect_stale_sessions() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+        let crypto = mls_crypto_provider::RustCrypto::default();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+
+        alice
+            .session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+        let encrypted = alice.encrypt(&mut keystore, &session_id, b"Hello bob").await.unwrap();
+        bob.decrypt(&session_id, &encrypted).await;
+        assert!(alice.stale_sessions(&keystore, &crypto).await.unwrap().is_empty());
+
+        // another process sharing the keystore, e.g. a notification extension, decrypts a message
+        let mut extension = ProteusCentral::try_new(&keystore).await.unwrap();
+        let encrypted = bob.encrypt(&session_id, b"Hello alice");
+        extension.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap();
+        assert_eq!(
+            alice.stale_sessions(&keystore, &crypto).await.unwrap(),
+            vec![session_id.clone()]
+        );
+
+        alice.reload_sessions(&keystore).await.unwrap();
+        assert!(alice.stale_sessions(&keystore, &crypto).await.unwrap().is_empty());
+        let encrypted = bob.encrypt(&session_id, b"Hello again");
+        assert_eq!(
+            alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap(),
+            b"Hello again"
+        );
+
+        // deleted sessions are stale as well
+        extension.session_delete(&keystore, &session_id).await.unwrap();
+        assert_eq!(
+            alice.stale_sessions(&keystore, &crypto).await.unwrap(),
+            vec![session_id]
+        );
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn should_not_use_a_session_out_of_sync() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+
+        alice
+            .session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+        let encrypted = alice.encrypt(&mut keystore, &session_id, b"Hello bob").await.unwrap();
+        bob.decrypt(&session_id, &encrypted).await;
+
+        // another process sharing the keystore, e.g. a notification extension, decrypts a message
+        let mut extension = ProteusCentral::try_new(&keystore).await.unwrap();
+        let encrypted = bob.encrypt(&session_id, b"Hello alice");
+        extension.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap();
+
+        let encrypted = bob.encrypt(&session_id, b"Hello again");
+        assert!(matches!(
+            alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap_err(),
+            CryptoError::ProteusSessionOutOfSync(id) if id == session_id
+        ));
+        // the stale session has been discarded, retrying uses the persisted one
+        assert_eq!(
+            alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap(),
+            b"Hello again"
+        );
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn can_disable_auto_persist() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+        alice.set_auto_persist(false);
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+
+        alice
+            .session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+        alice.session_save(&mut keystore, &session_id).await.unwrap();
+        let persisted = keystore
+            .find::<ProteusSession>(session_id.as_bytes())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let encrypted = alice.encrypt(&mut keystore, &session_id, b"Hello bob").await.unwrap();
+        bob.decrypt(&session_id, &encrypted).await;
+        let encrypted = bob.encrypt(&session_id, b"Hello alice");
+        alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap();
+        let unchanged = keystore
+            .find::<ProteusSession>(session_id.as_bytes())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged.session, persisted.session);
+
+        alice.session_save(&mut keystore, &session_id).await.unwrap();
+        let saved = keystore
+            .find::<ProteusSession>(session_id.as_bytes())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(saved.session, persisted.session);
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn can_decrypt_batched() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let mut bob = CryptoboxLike::init();
+        let bob_pk_bundle = bob.new_prekey();
+
+        alice
+            .session_from_prekey(&session_id, &bob_pk_bundle.serialise().unwrap())
+            .await
+            .unwrap();
+        let encrypted = alice.encrypt(&mut keystore, &session_id, b"Hello bob").await.unwrap();
+        bob.decrypt(&session_id, &encrypted).await;
+
+        let messages = [b"Hello".to_vec(), b"world".to_vec(), b"!".to_vec()];
+        let encrypted = messages.iter().map(|m| bob.encrypt(&session_id, m)).collect::<Vec<_>>();
+
+        // a single invalid message discards the whole batch
+        let invalid = [encrypted[0].clone(), b"invalid".to_vec()];
+        assert!(alice
+            .decrypt_batched(&mut keystore, &session_id, &invalid)
+            .await
+            .is_err());
+
+        let decrypted = alice
+            .decrypt_batched(&mut keystore, &session_id, &encrypted[..2])
+            .await
+            .unwrap();
+        assert_eq!(decrypted, messages[..2]);
+
+        let batch = HashMap::from([(session_id.clone(), encrypted[2..].to_vec())]);
+        let decrypted = alice.decrypt_batched_multi(&mut keystore, &batch).await.unwrap();
+        assert_eq!(decrypted[&session_id], messages[2..]);
+
+        // the updated session has been persisted
+        let _ = alice.proteus_sessions.remove(session_id.as_bytes());
+        let encrypted = bob.encrypt(&session_id, b"again");
+        let decrypted = alice.decrypt(&mut keystore, &session_id, &encrypted).await.unwrap();
+        assert_eq!(decrypted, b"again");
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
     #[async_std::test]
     #[wasm_bindgen_test]
     async fn can_produce_proteus_consumed_prekeys() {
@@ -1144,6 +2433,138 @@ mod tests {
         drop(db_file);
     }
 
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn should_check_remote_identity_continuity() {
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let mut bob = CryptoboxLike::init();
+        alice
+            .session_from_prekey_checked(&mut keystore, &session_id, &bob.new_prekey().serialise().unwrap(), None)
+            .await
+            .unwrap();
+        alice.session_delete(&keystore, &session_id).await.unwrap();
+
+        // same identity, nothing to confirm
+        alice
+            .session_from_prekey_checked(&mut keystore, &session_id, &bob.new_prekey().serialise().unwrap(), None)
+            .await
+            .unwrap();
+
+        // Bob reinstalled, the change is rejected unless confirmed
+        let mut new_bob = CryptoboxLike::init();
+        let new_prekey = new_bob.new_prekey().serialise().unwrap();
+        let rejecting = ValidationCallbacks {
+            proteus_identity_changed: false,
+            ..Default::default()
+        };
+        for callbacks in [None, Some(&rejecting as &dyn CoreCryptoCallbacks)] {
+            let result = alice
+                .session_from_prekey_checked(&mut keystore, &session_id, &new_prekey, callbacks)
+                .await;
+            assert!(matches!(result.unwrap_err(), CryptoError::ProteusIdentityChanged(id) if id == session_id));
+        }
+        // the previous session is left untouched
+        assert_eq!(
+            alice.fingerprint_remote(&session_id, &mut keystore).await.unwrap(),
+            bob.fingerprint()
+        );
+
+        let confirming = ValidationCallbacks::default();
+        alice
+            .session_from_prekey_checked(&mut keystore, &session_id, &new_prekey, Some(&confirming))
+            .await
+            .unwrap();
+        assert_eq!(
+            alice.fingerprint_remote(&session_id, &mut keystore).await.unwrap(),
+            new_bob.fingerprint()
+        );
+
+        // the confirmed identity is the one recorded from now on, also for sessions initiated by the remote client
+        let prekey = alice.new_prekey(1, &keystore).await.unwrap();
+        bob.init_session_from_prekey_bundle(&session_id, &prekey);
+        let message = bob.encrypt(&session_id, b"Hello Alice");
+        alice.session_delete(&keystore, &session_id).await.unwrap();
+        let result = alice
+            .session_from_message_checked(&mut keystore, &session_id, &message, None)
+            .await;
+        assert!(matches!(result.unwrap_err(), CryptoError::ProteusIdentityChanged(_)));
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
+    #[derive(Debug, Default)]
+    struct InMemoryPreKeyStore(Arc<std::sync::Mutex<HashMap<u16, Vec<u8>>>>);
+
+    #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+    impl ProteusPreKeyStore for InMemoryPreKeyStore {
+        async fn prekey(&self, id: u16) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn remove_prekey(&self, id: u16) -> CryptoKeystoreResult<()> {
+            self.0.lock().unwrap().remove(&id);
+            Ok(())
+        }
+    }
+
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    async fn can_consume_prekeys_from_external_store() {
+        use proteus_wasm::keys::{PreKey, PreKeyId};
+
+        #[cfg(not(target_family = "wasm"))]
+        let (path, db_file) = tmp_db_file();
+        #[cfg(target_family = "wasm")]
+        let (path, _) = tmp_db_file();
+
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let mut keystore = core_crypto_keystore::Connection::open_with_key(path, "test")
+            .await
+            .unwrap();
+        let mut alice = ProteusCentral::try_new(&keystore).await.unwrap();
+
+        let prekeys = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        alice.prekey_store(Box::new(InMemoryPreKeyStore(prekeys.clone())));
+
+        // the prekey only lives in the external store
+        let prekey = PreKey::new(PreKeyId::new(1));
+        prekeys.lock().unwrap().insert(1, prekey.serialise().unwrap());
+        let bundle = PreKeyBundle::new(alice.identity().public_key.clone(), &prekey);
+
+        let mut bob = CryptoboxLike::init();
+        bob.init_session_from_prekey_bundle(&session_id, &bundle.serialise().unwrap());
+        let message = b"Hello world!";
+        let encrypted = bob.encrypt(&session_id, message);
+
+        let (_, decrypted) = alice
+            .session_from_message(&mut keystore, &session_id, &encrypted)
+            .await
+            .unwrap();
+        assert_eq!(message, decrypted.as_slice());
+
+        // the consumed prekey got deleted from the external store
+        assert!(prekeys.lock().unwrap().is_empty());
+
+        keystore.wipe().await.unwrap();
+        #[cfg(not(target_family = "wasm"))]
+        drop(db_file);
+    }
+
     #[async_std::test]
     #[wasm_bindgen_test]
     async fn auto_prekeys_are_sequential() {
@@ -1329,6 +2750,107 @@ mod tests {
         keystore.wipe().await.unwrap();
     }
 
+    #[cfg(all(feature = "cryptobox-migrate", not(target_family = "wasm")))]
+    #[async_std::test]
+    async fn can_preflight_cryptobox_import() {
+        let session_id = uuid::Uuid::new_v4().hyphenated().to_string();
+
+        let cryptobox_folder = tempfile::tempdir().unwrap();
+        let alice = cryptobox::CBox::file_open(cryptobox_folder.path()).unwrap();
+        let mut bob = CryptoboxLike::init();
+        alice.new_prekey(proteus::keys::PreKeyId::new(1u16)).unwrap();
+        let mut alice_session = alice
+            .session_from_prekey(session_id.clone(), &bob.new_prekey().serialise().unwrap())
+            .unwrap();
+        alice.session_save(&mut alice_session).unwrap();
+        drop(alice);
+
+        let prekey_dir = cryptobox_folder.path().join("prekeys");
+        let prekey_count = std::fs::read_dir(&prekey_dir).unwrap().count();
+        // corrupted items
+        std::fs::write(cryptobox_folder.path().join("sessions").join("corrupted"), b"corrupted").unwrap();
+        std::fs::write(prekey_dir.join("not-an-id"), b"corrupted").unwrap();
+
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let keystore_file = keystore_dir.path().join("keystore");
+        let keystore =
+            core_crypto_keystore::Connection::open_with_key(keystore_file.as_os_str().to_string_lossy(), "test")
+                .await
+                .unwrap();
+
+        let path = cryptobox_folder.path().to_string_lossy();
+        let progress = std::sync::Mutex::new(vec![]);
+        let on_progress = |p: CryptoboxMigrationProgress| progress.lock().unwrap().push(p);
+        let options = CryptoboxMigrationOptions {
+            dry_run: true,
+            on_progress: Some(&on_progress),
+            ..Default::default()
+        };
+        let report = ProteusCentral::cryptobox_migrate_with_options(&keystore, &path, options)
+            .await
+            .unwrap();
+        assert_eq!(report.identities, 1);
+        assert_eq!(report.sessions, 1);
+        assert_eq!(report.prekeys, prekey_count);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report
+            .errors
+            .iter()
+            .any(|e| e.item == CryptoboxMigrationItem::Session && e.id == "corrupted"));
+        assert!(report
+            .errors
+            .iter()
+            .any(|e| e.item == CryptoboxMigrationItem::PreKey && e.id == "not-an-id"));
+
+        let progress = progress.into_inner().unwrap();
+        assert_eq!(progress.len(), 2 + prekey_count + 1);
+        assert_eq!(
+            progress.last(),
+            Some(&CryptoboxMigrationProgress {
+                item: CryptoboxMigrationItem::PreKey,
+                processed: prekey_count + 1,
+                total: prekey_count + 1,
+            })
+        );
+
+        // a cancelled migration stops before the first session
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let options = CryptoboxMigrationOptions {
+            dry_run: true,
+            cancellation: Some(&cancellation),
+            ..Default::default()
+        };
+        let error = ProteusCentral::cryptobox_migrate_with_options(&keystore, &path, options)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, CryptoError::OperationCancelled));
+
+        // nothing has been written
+        assert!(keystore.find::<ProteusIdentity>(&[]).await.unwrap().is_none());
+        assert!(keystore
+            .find_all::<ProteusSession>(Default::default())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // the actual migration reports the same, and skips everything once done
+        let report_after = ProteusCentral::cryptobox_migrate_with_options(&keystore, &path, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(report_after, report);
+        let report_again = ProteusCentral::cryptobox_migrate_with_options(&keystore, &path, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            report_again.sessions + report_again.prekeys + report_again.identities,
+            0
+        );
+        assert_eq!(report_again.errors.len(), 2);
+
+        keystore.wipe().await.unwrap();
+    }
+
     cfg_if::cfg_if! {
         if #[cfg(all(feature = "cryptobox-migrate", target_family = "wasm"))] {
             // use wasm_bindgen::prelude::*;
@@ -0,0 +1,233 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! A quick self-test of the crypto primitives and of the keystore, meant to be run on startup to detect a broken
+//! platform early (e.g. a quirky WebCrypto implementation) rather than failing later in a conversation. It works
+//! offline on fixed inputs and leaves nothing behind in the keystore.
+
+use core_crypto_keystore::entities::MlsPendingMessage;
+use openmls_traits::{crypto::OpenMlsCrypto, types::CryptoError as MlsCryptoError, OpenMlsCryptoProvider};
+use zeroize::Zeroizing;
+
+use crate::{
+    prelude::{CryptoError, MlsCiphersuite, MlsError},
+    CoreCrypto,
+};
+
+const MESSAGE: &[u8] = b"core-crypto self-test";
+const AAD: &[u8] = b"core-crypto self-test aad";
+const HPKE_INFO: &[u8] = b"core-crypto self-test info";
+const HPKE_IKM: [u8; 32] = [0x42; 32];
+/// Keystore id of the record written and removed by the keystore check, not a valid conversation id
+const KEYSTORE_RECORD_ID: &[u8] = b"\0core-crypto-self-test";
+
+/// What a check of [CoreCrypto::self_test] exercises
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestCheckKind {
+    /// Key generation, signature and verification with the signature scheme of a ciphersuite
+    Signature,
+    /// HPKE seal then open with the HPKE configuration of a ciphersuite
+    Hpke,
+    /// AEAD encryption then decryption with the AEAD of a ciphersuite
+    Aead,
+    /// Writing, reading and removing a record in the keystore
+    Keystore,
+}
+
+/// Outcome of a check of [CoreCrypto::self_test]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    /// What has been checked
+    pub kind: SelfTestCheckKind,
+    /// Ciphersuite whose primitives have been checked, none for the keystore
+    pub ciphersuite: Option<MlsCiphersuite>,
+    /// Why the check failed, none when it passed
+    pub failure: Option<String>,
+}
+
+/// Report of [CoreCrypto::self_test]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Every check which has been run
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.failure.is_none())
+    }
+}
+
+type CheckResult = Result<(), String>;
+
+fn crypto_failure(e: MlsCryptoError) -> String {
+    MlsError::from(e).to_string()
+}
+
+fn check_signature(crypto: &impl OpenMlsCrypto, ciphersuite: MlsCiphersuite) -> CheckResult {
+    let scheme = ciphersuite.signature_algorithm();
+    let (sk, pk) = crypto.signature_key_gen(scheme).map_err(crypto_failure)?;
+    let sk = Zeroizing::new(sk);
+    let signature = crypto.sign(scheme, MESSAGE, &sk).map_err(crypto_failure)?;
+    crypto
+        .verify_signature(scheme, MESSAGE, &pk, &signature)
+        .map_err(crypto_failure)?;
+    if crypto.verify_signature(scheme, AAD, &pk, &signature).is_ok() {
+        return Err("a signature has been accepted for another message".to_string());
+    }
+    Ok(())
+}
+
+fn check_hpke(crypto: &impl OpenMlsCrypto, ciphersuite: MlsCiphersuite) -> CheckResult {
+    let config = ciphersuite.hpke_config();
+    let keypair = crypto.derive_hpke_keypair(config, &HPKE_IKM).map_err(crypto_failure)?;
+    let ciphertext = crypto
+        .hpke_seal(config, &keypair.public, HPKE_INFO, AAD, MESSAGE)
+        .map_err(crypto_failure)?;
+    let plaintext = crypto
+        .hpke_open(config, &ciphertext, &keypair.private, HPKE_INFO, AAD)
+        .map_err(crypto_failure)?;
+    if plaintext != MESSAGE {
+        return Err("the opened HPKE ciphertext differs from the sealed plaintext".to_string());
+    }
+    Ok(())
+}
+
+fn check_aead(crypto: &impl OpenMlsCrypto, ciphersuite: MlsCiphersuite) -> CheckResult {
+    let aead = ciphersuite.aead_algorithm();
+    let key = vec![0x42; ciphersuite.aead_key_length()];
+    let nonce = vec![0x24; ciphersuite.aead_nonce_length()];
+    let mut ciphertext = crypto
+        .aead_encrypt(aead, &key, MESSAGE, &nonce, AAD)
+        .map_err(crypto_failure)?;
+    let plaintext = crypto
+        .aead_decrypt(aead, &key, &ciphertext, &nonce, AAD)
+        .map_err(crypto_failure)?;
+    if plaintext != MESSAGE {
+        return Err("the decrypted AEAD ciphertext differs from the encrypted plaintext".to_string());
+    }
+    ciphertext[0] ^= 0xFF;
+    if crypto.aead_decrypt(aead, &key, &ciphertext, &nonce, AAD).is_ok() {
+        return Err("a tampered AEAD ciphertext has been decrypted".to_string());
+    }
+    Ok(())
+}
+
+impl CoreCrypto {
+    /// Runs a quick self-test of the crypto primitives of every supported ciphersuite (signature, HPKE and AEAD
+    /// roundtrips) and of the keystore (write, read and remove). Failures are reported rather than returned as
+    /// errors so that every check runs. Only available with the `self-test` feature
+    pub async fn self_test(&self) -> SelfTestReport {
+        let crypto = self.mls.mls_backend.crypto();
+        let mut checks = vec![];
+        for ciphersuite in crypto.supported_ciphersuites().into_iter().map(MlsCiphersuite::from) {
+            for (kind, result) in [
+                (SelfTestCheckKind::Signature, check_signature(crypto, ciphersuite)),
+                (SelfTestCheckKind::Hpke, check_hpke(crypto, ciphersuite)),
+                (SelfTestCheckKind::Aead, check_aead(crypto, ciphersuite)),
+            ] {
+                checks.push(SelfTestCheck {
+                    kind,
+                    ciphersuite: Some(ciphersuite),
+                    failure: result.err(),
+                });
+            }
+        }
+        checks.push(SelfTestCheck {
+            kind: SelfTestCheckKind::Keystore,
+            ciphersuite: None,
+            failure: self.check_keystore().await.err(),
+        });
+        SelfTestReport { checks }
+    }
+
+    async fn check_keystore(&self) -> CheckResult {
+        let keystore = self.mls.mls_backend.key_store();
+        let failure = |e: core_crypto_keystore::CryptoKeystoreError| CryptoError::from(e).to_string();
+        keystore
+            .save(MlsPendingMessage {
+                id: KEYSTORE_RECORD_ID.to_vec(),
+                message: MESSAGE.to_vec(),
+                created_at: 0,
+            })
+            .await
+            .map_err(failure)?;
+        let read = keystore
+            .find::<MlsPendingMessage>(KEYSTORE_RECORD_ID)
+            .await
+            .map_err(failure)?;
+        keystore
+            .remove::<MlsPendingMessage, _>(KEYSTORE_RECORD_ID)
+            .await
+            .map_err(failure)?;
+        if read.as_ref().map(|r| r.message.as_slice()) != Some(MESSAGE) {
+            return Err("the record read from the keystore differs from the one written".to_string());
+        }
+        if keystore
+            .find::<MlsPendingMessage>(KEYSTORE_RECORD_ID)
+            .await
+            .map_err(failure)?
+            .is_some()
+        {
+            return Err("a record removed from the keystore can still be read".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::*, test_utils::*, CoreCrypto};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn self_test_should_pass(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[alice_central]| {
+            Box::pin(async move {
+                let cc = CoreCrypto::from(alice_central.mls_central);
+                let report = cc.self_test().await;
+                assert!(report.passed(), "{report:?}");
+                assert!(report
+                    .checks
+                    .iter()
+                    .any(|c| c.ciphersuite == Some(case.ciphersuite()) && c.kind == SelfTestCheckKind::Hpke));
+                assert_eq!(
+                    report
+                        .checks
+                        .iter()
+                        .filter(|c| c.kind == SelfTestCheckKind::Keystore)
+                        .count(),
+                    1
+                );
+                // nothing is left behind
+                assert_eq!(
+                    cc.mls_backend
+                        .key_store()
+                        .count::<core_crypto_keystore::entities::MlsPendingMessage>()
+                        .await
+                        .unwrap(),
+                    0
+                );
+            })
+        })
+        .await
+    }
+}
@@ -108,7 +108,7 @@ impl MlsCentral {
             .into()
     }
 
-    pub async fn pending_proposals(&mut self, id: &ConversationId) -> Vec<QueuedProposal> {
+    pub async fn pending_proposals_unchecked(&mut self, id: &ConversationId) -> Vec<QueuedProposal> {
         self.get_conversation_unchecked(id)
             .await
             .group
@@ -117,6 +117,18 @@ impl MlsCentral {
             .collect::<Vec<_>>()
     }
 
+    /// Makes every keystore write fail, as when the disk is full, or succeed again
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn set_keystore_read_only(&self, read_only: bool) {
+        self.mls_backend
+            .key_store()
+            .borrow_conn()
+            .await
+            .unwrap()
+            .pragma_update(None, "query_only", read_only)
+            .unwrap();
+    }
+
     pub async fn pending_commit(&mut self, id: &ConversationId) -> Option<StagedCommit> {
         self.get_conversation_unchecked(id)
             .await
@@ -238,6 +250,15 @@ impl MlsCentral {
     }
 
     pub async fn get_group_info(&mut self, id: &ConversationId) -> VerifiableGroupInfo {
+        self.export_group_info(id, true).await
+    }
+
+    /// GroupInfo without the ratchet tree extension, as some Delivery Services deliver it
+    pub async fn get_group_info_without_ratchet_tree(&mut self, id: &ConversationId) -> VerifiableGroupInfo {
+        self.export_group_info(id, false).await
+    }
+
+    async fn export_group_info(&mut self, id: &ConversationId, with_ratchet_tree: bool) -> VerifiableGroupInfo {
         let conversation_arc = self.get_conversation(id).await.unwrap();
         let mut conversation = conversation_arc.write().await;
         let group = &mut conversation.group;
@@ -251,7 +272,7 @@ impl MlsCentral {
             .unwrap();
 
         let gi = group
-            .export_group_info(&self.mls_backend, &cb.signature_key, true)
+            .export_group_info(&self.mls_backend, &cb.signature_key, with_ratchet_tree)
             .unwrap();
         gi.group_info().unwrap()
     }
@@ -300,6 +300,8 @@ pub struct ValidationCallbacks {
     pub authorize: bool,
     pub user_authorize: bool,
     pub client_is_existing_group_user: bool,
+    pub member_change_authorize: bool,
+    pub proteus_identity_changed: bool,
 }
 
 impl Default for ValidationCallbacks {
@@ -308,6 +310,8 @@ impl Default for ValidationCallbacks {
             authorize: true,
             user_authorize: true,
             client_is_existing_group_user: true,
+            member_change_authorize: true,
+            proteus_identity_changed: true,
         }
     }
 }
@@ -337,4 +341,23 @@ impl CoreCryptoCallbacks for ValidationCallbacks {
     ) -> bool {
         self.client_is_existing_group_user
     }
+
+    async fn member_change_authorize(
+        &self,
+        _conversation_id: ConversationId,
+        _committer: ClientId,
+        _added_clients: Vec<ClientId>,
+        _removed_clients: Vec<ClientId>,
+    ) -> bool {
+        self.member_change_authorize
+    }
+
+    async fn proteus_identity_changed(
+        &self,
+        _session_id: String,
+        _previous_fingerprint: String,
+        _new_fingerprint: String,
+    ) -> bool {
+        self.proteus_identity_changed
+    }
 }
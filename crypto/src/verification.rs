@@ -0,0 +1,175 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Payloads displayed as QR codes on the security verification screen. Both peers compute the very same payload when
+//! they share the same view of their session, hence comparing them (by scanning the QR code of the other device) is
+//! enough to detect a man in the middle.
+//!
+//! The payloads are made of colon separated fields, starting with the protocol and the version of the format:
+//! * Proteus: `WIRE:PROTEUS:1:<fingerprint>:<fingerprint>`
+//! * MLS: `WIRE:MLS:1:<conversation id>:<epoch>:<client id>:<client id>:<epoch authenticator>`
+//!
+//! Binary fields are hex-encoded and the pairs of fields belonging to either peer are sorted, so that the payload does
+//! not depend on which side computes it. Everything is uppercase so that the QR code can use the compact alphanumeric
+//! mode.
+
+use crate::prelude::{ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral};
+
+const PROTEUS_PREFIX: &str = "WIRE:PROTEUS:1";
+const MLS_PREFIX: &str = "WIRE:MLS:1";
+
+/// Sorts the fields belonging to either peer
+fn sorted<T: Ord>(a: T, b: T) -> [T; 2] {
+    if a <= b {
+        [a, b]
+    } else {
+        [b, a]
+    }
+}
+
+/// Payload of a Proteus session given the hex-encoded fingerprints of both identities
+#[cfg(feature = "proteus")]
+pub(crate) fn proteus_payload(local_fingerprint: &str, remote_fingerprint: &str) -> String {
+    let [a, b] = sorted(local_fingerprint.to_uppercase(), remote_fingerprint.to_uppercase());
+    format!("{PROTEUS_PREFIX}:{a}:{b}")
+}
+
+/// Payload of a MLS conversation between 2 of its members
+pub(crate) fn mls_payload(
+    conversation_id: &[u8],
+    epoch: u64,
+    local_client_id: &[u8],
+    remote_client_id: &[u8],
+    epoch_authenticator: &[u8],
+) -> String {
+    let [a, b] = sorted(hex::encode_upper(local_client_id), hex::encode_upper(remote_client_id));
+    format!(
+        "{MLS_PREFIX}:{}:{epoch}:{a}:{b}:{}",
+        hex::encode_upper(conversation_id),
+        hex::encode_upper(epoch_authenticator)
+    )
+}
+
+impl MlsCentral {
+    /// Payload of the QR code verifying the conversation with another member, see [crate::verification]. It
+    /// is based on the epoch authenticator, which both members share only when they have the same view of the
+    /// group in the current epoch, hence it changes with every commit.
+    ///
+    /// # Arguments
+    /// * `conversation_id` - the group/conversation id
+    /// * `client_id` - the member to verify
+    ///
+    /// # Errors
+    /// If the conversation can't be found or if the client is not a member of it
+    pub async fn mls_fingerprint_qr(
+        &mut self,
+        conversation_id: &ConversationId,
+        client_id: &ClientId,
+    ) -> CryptoResult<String> {
+        let local_client_id = self.client_id()?;
+        let conversation = self.get_conversation(conversation_id).await?;
+        let conversation = conversation.read().await;
+        if !conversation.get_client_ids().contains(client_id) {
+            return Err(CryptoError::ClientNotFound(client_id.clone()));
+        }
+
+        Ok(mls_payload(
+            conversation_id,
+            conversation.group.epoch().as_u64(),
+            &local_client_id,
+            client_id,
+            conversation.group.epoch_authenticator().as_slice(),
+        ))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn mls_payload_should_not_depend_on_the_side() {
+        let payload = mls_payload(b"conv", 3, b"alice", b"bob", &[0xab, 0x01]);
+        assert_eq!(payload, "WIRE:MLS:1:636F6E76:3:414C494345:424F42:AB01");
+        assert_eq!(payload, mls_payload(b"conv", 3, b"bob", b"alice", &[0xab, 0x01]));
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn both_members_should_compute_the_same_payload(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let alice_id = alice_central.mls_central.get_client_id();
+                    let bob_id = bob_central.mls_central.get_client_id();
+                    let alice_payload = alice_central
+                        .mls_central
+                        .mls_fingerprint_qr(&id, &bob_id)
+                        .await
+                        .unwrap();
+                    let bob_payload = bob_central
+                        .mls_central
+                        .mls_fingerprint_qr(&id, &alice_id)
+                        .await
+                        .unwrap();
+                    assert!(alice_payload.starts_with(MLS_PREFIX));
+                    assert_eq!(alice_payload, bob_payload);
+
+                    // every commit changes the epoch authenticator
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let new_payload = alice_central
+                        .mls_central
+                        .mls_fingerprint_qr(&id, &bob_id)
+                        .await
+                        .unwrap();
+                    assert_ne!(new_payload, alice_payload);
+
+                    // only members can be verified
+                    let charlie_id = charlie_central.mls_central.get_client_id();
+                    let error = alice_central
+                        .mls_central
+                        .mls_fingerprint_qr(&id, &charlie_id)
+                        .await
+                        .unwrap_err();
+                    assert!(matches!(error, CryptoError::ClientNotFound(_)));
+                })
+            },
+        )
+        .await
+    }
+}
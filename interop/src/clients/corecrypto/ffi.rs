@@ -109,6 +109,8 @@ impl<'a> EmulatedMlsClient for CoreCryptoFfiClient<'a> {
                 custom: CustomConfiguration {
                     key_rotation_span: None,
                     wire_policy: None,
+                    out_of_order_tolerance: None,
+                    maximum_forward_distance: None,
                 },
             };
             self.cc.create_conversation(conversation_id.to_vec(), cfg)?;
@@ -137,6 +139,8 @@ impl<'a> EmulatedMlsClient for CoreCryptoFfiClient<'a> {
         let cfg = CustomConfiguration {
             key_rotation_span: None,
             wire_policy: None,
+            out_of_order_tolerance: None,
+            maximum_forward_distance: None,
         };
         Ok(self.cc.process_welcome_message(welcome, cfg)?.id)
     }
@@ -20,7 +20,7 @@ use tls_codec::Serialize;
 use core_crypto::prelude::*;
 
 use crate::{
-    clients::{EmulatedClient, EmulatedClientProtocol, EmulatedClientType, EmulatedMlsClient},
+    clients::{EmulatedClient, EmulatedClientProtocol, EmulatedClientType, EmulatedMlsClient, MlsCommitOutcome},
     CIPHERSUITE_IN_USE,
 };
 
@@ -54,7 +54,7 @@ impl CoreCryptoNativeClient {
 
         Ok(Self {
             cc,
-            client_id: client_id.into_bytes().into(),
+            client_id: client_id.as_hyphenated().to_string().into_bytes(),
             #[cfg(feature = "proteus")]
             prekey_last_id: 0,
         })
@@ -155,6 +155,83 @@ impl EmulatedMlsClient for CoreCryptoNativeClient {
             .await?
             .app_msg)
     }
+
+    async fn create_conversation(&mut self, conversation_id: &[u8]) -> Result<()> {
+        let config = MlsConversationConfiguration {
+            ciphersuite: CIPHERSUITE_IN_USE.into(),
+            ..Default::default()
+        };
+        Ok(self
+            .cc
+            .new_conversation(&conversation_id.to_vec(), MlsCredentialType::Basic, config)
+            .await?)
+    }
+
+    async fn add_clients(&mut self, conversation_id: &[u8], kps: &[Vec<u8>]) -> Result<MlsCommitOutcome> {
+        use tls_codec::Deserialize as _;
+
+        let conversation_id = conversation_id.to_vec();
+        let kps = kps
+            .iter()
+            .map(|kp| KeyPackageIn::tls_deserialize(&mut kp.as_slice()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let bundle = self.cc.add_members_to_conversation(&conversation_id, kps).await?;
+        self.cc.commit_accepted(&conversation_id).await?;
+
+        Ok(MlsCommitOutcome {
+            commit: bundle.commit.tls_serialize_detached()?,
+            welcome: Some(bundle.welcome.tls_serialize_detached()?),
+            group_info: bundle.group_info.payload.bytes(),
+        })
+    }
+
+    async fn remove_clients(&mut self, conversation_id: &[u8], client_ids: &[Vec<u8>]) -> Result<MlsCommitOutcome> {
+        let conversation_id = conversation_id.to_vec();
+        let client_ids = client_ids.iter().map(|id| id.clone().into()).collect::<Vec<ClientId>>();
+        let bundle = self
+            .cc
+            .remove_members_from_conversation(&conversation_id, &client_ids)
+            .await?;
+        self.cc.commit_accepted(&conversation_id).await?;
+        Self::commit_outcome(bundle)
+    }
+
+    async fn update_keying_material(&mut self, conversation_id: &[u8]) -> Result<MlsCommitOutcome> {
+        let conversation_id = conversation_id.to_vec();
+        let bundle = self.cc.update_keying_material(&conversation_id).await?;
+        self.cc.commit_accepted(&conversation_id).await?;
+        Self::commit_outcome(bundle)
+    }
+
+    async fn join_by_external_commit(&mut self, group_info: &[u8]) -> Result<(Vec<u8>, MlsCommitOutcome)> {
+        use tls_codec::Deserialize as _;
+
+        let group_info = VerifiableGroupInfo::tls_deserialize(&mut &group_info[..])?;
+        let bundle = self
+            .cc
+            .join_by_external_commit(group_info, MlsCustomConfiguration::default(), MlsCredentialType::Basic)
+            .await?;
+        self.cc
+            .merge_pending_group_from_external_commit(&bundle.conversation_id)
+            .await?;
+
+        let outcome = MlsCommitOutcome {
+            commit: bundle.commit.tls_serialize_detached()?,
+            welcome: None,
+            group_info: bundle.group_info.payload.bytes(),
+        };
+        Ok((bundle.conversation_id, outcome))
+    }
+}
+
+impl CoreCryptoNativeClient {
+    fn commit_outcome(bundle: MlsCommitBundle) -> Result<MlsCommitOutcome> {
+        Ok(MlsCommitOutcome {
+            commit: bundle.commit.tls_serialize_detached()?,
+            welcome: bundle.welcome.map(|w| w.tls_serialize_detached()).transpose()?,
+            group_info: bundle.group_info.payload.bytes(),
+        })
+    }
 }
 
 #[cfg(feature = "proteus")]
@@ -191,3 +268,31 @@ impl crate::clients::EmulatedProteusClient for CoreCryptoNativeClient {
         Ok(self.cc.proteus_fingerprint()?)
     }
 }
+
+#[cfg(feature = "proteus")]
+#[async_trait::async_trait(?Send)]
+impl crate::clients::EmulatedCoexistenceClient for CoreCryptoNativeClient {
+    async fn proteus_session_exists(&mut self, session_id: &str) -> Result<bool> {
+        Ok(self.cc.proteus_session_exists(session_id).await?)
+    }
+
+    async fn migrate_proteus_conversation(
+        &mut self,
+        session_id: &str,
+        conversation_id: &[u8],
+    ) -> Result<crate::clients::ProteusMigrationOutcome> {
+        let config = MlsConversationConfiguration {
+            ciphersuite: CIPHERSUITE_IN_USE.into(),
+            ..Default::default()
+        };
+        let report = self
+            .cc
+            .migrate_proteus_conversation(session_id, &conversation_id.to_vec(), MlsCredentialType::Basic, config)
+            .await?;
+
+        Ok(crate::clients::ProteusMigrationOutcome {
+            remote_fingerprint: report.remote_fingerprint,
+            conversation_created: report.conversation_created,
+        })
+    }
+}
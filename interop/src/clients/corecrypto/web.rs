@@ -15,7 +15,7 @@
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
 use crate::{
-    clients::{EmulatedClient, EmulatedClientProtocol, EmulatedClientType, EmulatedMlsClient},
+    clients::{EmulatedClient, EmulatedClientProtocol, EmulatedClientType, EmulatedMlsClient, MlsCommitOutcome},
     CIPHERSUITE_IN_USE,
 };
 use color_eyre::eyre::Result;
@@ -60,7 +60,7 @@ callback();"#,
 
         Ok(Self {
             browser,
-            client_id: client_id.into_bytes().into(),
+            client_id: client_id_str.into_bytes(),
             #[cfg(feature = "proteus")]
             prekey_last_id: 0,
         })
@@ -248,6 +248,92 @@ window.cc.decryptMessage(conversationId, encryptedMessage)
             Ok(Some(serde_json::from_value(res)?))
         }
     }
+
+    async fn create_conversation(&mut self, conversation_id: &[u8]) -> Result<()> {
+        self.browser
+            .execute_async(
+                r#"
+const [cId, callback] = arguments;
+const conversationId = Uint8Array.from(Object.values(cId));
+window.cc.createConversation(conversationId, window.credentialType)
+    .then(() => callback());"#,
+                vec![conversation_id.into()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn add_clients(&mut self, conversation_id: &[u8], kps: &[Vec<u8>]) -> Result<MlsCommitOutcome> {
+        Ok(self
+            .browser
+            .execute_async(
+                r#"
+const [cId, kps, callback] = arguments;
+const conversationId = Uint8Array.from(Object.values(cId));
+const keyPackages = kps.map((kp) => Uint8Array.from(Object.values(kp)));
+const { commit, welcome, groupInfo } = await window.cc.addClientsToConversation(conversationId, keyPackages);
+await window.cc.commitAccepted(conversationId);
+callback({ commit: Array.from(commit), welcome: Array.from(welcome), groupInfo: Array.from(groupInfo.payload) });"#,
+                vec![conversation_id.into(), kps.into()],
+            )
+            .await
+            .and_then(|value| Ok(serde_json::from_value(value)?))?)
+    }
+
+    async fn remove_clients(&mut self, conversation_id: &[u8], client_ids: &[Vec<u8>]) -> Result<MlsCommitOutcome> {
+        Ok(self
+            .browser
+            .execute_async(
+                r#"
+const [cId, clIds, callback] = arguments;
+const conversationId = Uint8Array.from(Object.values(cId));
+const clientIds = clIds.map((clId) => Uint8Array.from(Object.values(clId)));
+const { commit, welcome, groupInfo } = await window.cc.removeClientsFromConversation(conversationId, clientIds);
+await window.cc.commitAccepted(conversationId);
+callback({ commit: Array.from(commit), welcome: welcome && Array.from(welcome), groupInfo: Array.from(groupInfo.payload) });"#,
+                vec![conversation_id.into(), client_ids.into()],
+            )
+            .await
+            .and_then(|value| Ok(serde_json::from_value(value)?))?)
+    }
+
+    async fn update_keying_material(&mut self, conversation_id: &[u8]) -> Result<MlsCommitOutcome> {
+        Ok(self
+            .browser
+            .execute_async(
+                r#"
+const [cId, callback] = arguments;
+const conversationId = Uint8Array.from(Object.values(cId));
+const { commit, welcome, groupInfo } = await window.cc.updateKeyingMaterial(conversationId);
+await window.cc.commitAccepted(conversationId);
+callback({ commit: Array.from(commit), welcome: welcome && Array.from(welcome), groupInfo: Array.from(groupInfo.payload) });"#,
+                vec![conversation_id.into()],
+            )
+            .await
+            .and_then(|value| Ok(serde_json::from_value(value)?))?)
+    }
+
+    async fn join_by_external_commit(&mut self, group_info: &[u8]) -> Result<(Vec<u8>, MlsCommitOutcome)> {
+        let mut res = self
+            .browser
+            .execute_async(
+                r#"
+const [gi, callback] = arguments;
+const groupInfo = Uint8Array.from(Object.values(gi));
+const bundle = await window.cc.joinByExternalCommit(groupInfo, window.credentialType);
+await window.cc.mergePendingGroupFromExternalCommit(bundle.conversationId);
+callback({
+    conversationId: Array.from(bundle.conversationId),
+    commit: Array.from(bundle.commit),
+    groupInfo: Array.from(bundle.groupInfo.payload),
+});"#,
+                vec![group_info.into()],
+            )
+            .await?;
+
+        let conversation_id = serde_json::from_value(res["conversationId"].take())?;
+        Ok((conversation_id, serde_json::from_value(res)?))
+    }
 }
 
 #[cfg(feature = "proteus")]
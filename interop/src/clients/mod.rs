@@ -66,6 +66,16 @@ pub trait EmulatedClient {
     async fn wipe(mut self) -> Result<()>;
 }
 
+/// A commit accepted by the Delivery Service and already merged by its author, along with what has to be fanned out
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MlsCommitOutcome {
+    pub commit: Vec<u8>,
+    pub welcome: Option<Vec<u8>>,
+    /// TLS-serialized GroupInfo of the new epoch
+    pub group_info: Vec<u8>,
+}
+
 #[async_trait::async_trait(?Send)]
 pub trait EmulatedMlsClient: EmulatedClient {
     async fn get_keypackage(&mut self) -> Result<Vec<u8>>;
@@ -75,6 +85,12 @@ pub trait EmulatedMlsClient: EmulatedClient {
     async fn encrypt_message(&mut self, conversation_id: &[u8], message: &[u8]) -> Result<Vec<u8>>;
     // TODO: Make it more complex so that we can extract other things like proposals etc
     async fn decrypt_message(&mut self, conversation_id: &[u8], message: &[u8]) -> Result<Option<Vec<u8>>>;
+    async fn create_conversation(&mut self, conversation_id: &[u8]) -> Result<()>;
+    async fn add_clients(&mut self, conversation_id: &[u8], kps: &[Vec<u8>]) -> Result<MlsCommitOutcome>;
+    async fn remove_clients(&mut self, conversation_id: &[u8], client_ids: &[Vec<u8>]) -> Result<MlsCommitOutcome>;
+    async fn update_keying_material(&mut self, conversation_id: &[u8]) -> Result<MlsCommitOutcome>;
+    /// Returns the id of the joined conversation along with the external commit
+    async fn join_by_external_commit(&mut self, group_info: &[u8]) -> Result<(Vec<u8>, MlsCommitOutcome)>;
 }
 
 #[async_trait::async_trait(?Send)]
@@ -94,3 +110,26 @@ pub trait EmulatedProteusClient: EmulatedClient {
 pub trait EmulatedE2eIdentityClient: EmulatedClient {
     async fn e2ei_new_enrollment(&mut self, ciphersuite: MlsCiphersuite) -> Result<()>;
 }
+
+/// What a client learnt when upgrading one of its Proteus sessions to an MLS conversation
+#[cfg(feature = "proteus")]
+#[derive(Debug)]
+pub struct ProteusMigrationOutcome {
+    /// Fingerprint of the peer of the Proteus session
+    pub remote_fingerprint: String,
+    /// `false` when the conversation already existed e.g. when retrying
+    pub conversation_created: bool,
+}
+
+/// Client speaking both protocols, able to upgrade a Proteus conversation to MLS while keeping the Proteus session
+/// usable until the peer has joined
+#[cfg(feature = "proteus")]
+#[async_trait::async_trait(?Send)]
+pub trait EmulatedCoexistenceClient: EmulatedMlsClient + EmulatedProteusClient {
+    async fn proteus_session_exists(&mut self, session_id: &str) -> Result<bool>;
+    async fn migrate_proteus_conversation(
+        &mut self,
+        session_id: &str,
+        conversation_id: &[u8],
+    ) -> Result<ProteusMigrationOutcome>;
+}
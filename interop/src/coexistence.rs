@@ -0,0 +1,211 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Proteus and MLS coexistence: two clients talk over Proteus first, then the conversation is upgraded to MLS with
+//! [EmulatedCoexistenceClient::migrate_proteus_conversation]. The Proteus session has to keep working until the peer
+//! joined the MLS conversation, and every message sent along the way has to be received once and in order whatever
+//! the protocol it went through.
+
+use color_eyre::eyre::{bail, eyre, Result};
+
+use crate::{clients::EmulatedCoexistenceClient, util, ROUNDTRIP_MSG_AMOUNT};
+
+const COEXISTENCE_CONVERSATION_ID: &[u8] = b"coexistence_conversation";
+
+/// Numbered messages exchanged between the two clients, checking that none is lost, replayed or reordered across the
+/// migration
+#[derive(Debug, Default)]
+struct MessageSequence {
+    sent: u64,
+    received: u64,
+}
+
+impl MessageSequence {
+    fn next(&mut self) -> Vec<u8> {
+        self.sent += 1;
+        format!("coexistence-{}", self.sent).into_bytes()
+    }
+
+    fn check(&mut self, receiver: &str, message: &[u8]) -> Result<()> {
+        self.received += 1;
+        let expected = format!("coexistence-{}", self.received);
+        if message != expected.as_bytes() {
+            bail!(
+                "{receiver} received '{}' instead of '{expected}'",
+                String::from_utf8_lossy(message)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Drives the migration of a Proteus conversation between `alice` and `bob` to MLS, `alice` being the one upgrading it
+pub async fn run_coexistence_scenario(
+    alice: &mut dyn EmulatedCoexistenceClient,
+    bob: &mut dyn EmulatedCoexistenceClient,
+) -> Result<()> {
+    let mut spinner = util::RunningProcess::new("[Coexistence] Step 0: Initializing Proteus...", true);
+
+    alice.init().await?;
+    bob.init().await?;
+    let alice_fingerprint = alice.fingerprint().await?;
+    let bob_fingerprint = bob.fingerprint().await?;
+    // each side names the session after its peer
+    let alice_session = format!("session-{bob_fingerprint}");
+    let bob_session = format!("session-{alice_fingerprint}");
+
+    spinner.update("[Coexistence] Step 0: Creating the Proteus session...");
+    let mut sequence = MessageSequence::default();
+    let prekey = bob.get_prekey().await?;
+    alice.session_from_prekey(&alice_session, &prekey).await?;
+    let message = sequence.next();
+    let ciphertext = alice.encrypt(&alice_session, &message).await?;
+    let decrypted = bob.session_from_message(&bob_session, &ciphertext).await?;
+    sequence.check(bob.client_name(), &decrypted)?;
+
+    spinner.success("[Coexistence] Step 0: Proteus session [OK]");
+
+    let mut spinner = util::RunningProcess::new(
+        format!("[Coexistence] Step 1: Roundtripping Proteus messages [0/{ROUNDTRIP_MSG_AMOUNT}]"),
+        true,
+    );
+    for i in 1..=ROUNDTRIP_MSG_AMOUNT {
+        proteus_roundtrip(alice, &alice_session, bob, &bob_session, &mut sequence).await?;
+        spinner.update(format!(
+            "[Coexistence] Step 1: Roundtripping Proteus messages [{i}/{ROUNDTRIP_MSG_AMOUNT}]"
+        ));
+    }
+    spinner.success(format!(
+        "[Coexistence] Step 1: Roundtripping {ROUNDTRIP_MSG_AMOUNT} Proteus messages [OK]"
+    ));
+
+    let spinner = util::RunningProcess::new("[Coexistence] Step 2: Migrating the conversation to MLS...", true);
+
+    let outcome = alice
+        .migrate_proteus_conversation(&alice_session, COEXISTENCE_CONVERSATION_ID)
+        .await?;
+    if !outcome.conversation_created {
+        bail!("{} did not create the MLS conversation", alice.client_name());
+    }
+    if outcome.remote_fingerprint != bob_fingerprint {
+        bail!(
+            "The Proteus session of {} is not with {}",
+            alice.client_name(),
+            bob.client_name()
+        );
+    }
+    // retrying must not create the conversation again
+    let retry = alice
+        .migrate_proteus_conversation(&alice_session, COEXISTENCE_CONVERSATION_ID)
+        .await?;
+    if retry.conversation_created {
+        bail!(
+            "{} created the MLS conversation again when retrying",
+            alice.client_name()
+        );
+    }
+
+    // bob has not joined yet, Proteus is still the way to reach it
+    if !alice.proteus_session_exists(&alice_session).await? {
+        bail!("The migration discarded the Proteus session of {}", alice.client_name());
+    }
+    proteus_roundtrip(alice, &alice_session, bob, &bob_session, &mut sequence).await?;
+
+    spinner.success("[Coexistence] Step 2: Migrated the conversation [OK]");
+
+    let spinner = util::RunningProcess::new("[Coexistence] Step 3: Adding the peer to the MLS conversation...", true);
+
+    let kp = bob.get_keypackage().await?;
+    let outcome = alice.add_clients(COEXISTENCE_CONVERSATION_ID, &[kp]).await?;
+    let welcome = outcome
+        .welcome
+        .ok_or_else(|| eyre!("Adding {} did not produce a Welcome", bob.client_name()))?;
+    let conversation_id = bob.process_welcome(&welcome).await?;
+    if conversation_id != COEXISTENCE_CONVERSATION_ID {
+        bail!(
+            "{} joined another conversation than the migrated one",
+            bob.client_name()
+        );
+    }
+
+    spinner.success("[Coexistence] Step 3: Added the peer [OK]");
+
+    let mut spinner = util::RunningProcess::new(
+        format!("[Coexistence] Step 4: Roundtripping MLS messages [0/{ROUNDTRIP_MSG_AMOUNT}]"),
+        true,
+    );
+    for i in 1..=ROUNDTRIP_MSG_AMOUNT {
+        mls_roundtrip(alice, bob, &mut sequence).await?;
+        spinner.update(format!(
+            "[Coexistence] Step 4: Roundtripping MLS messages [{i}/{ROUNDTRIP_MSG_AMOUNT}]"
+        ));
+    }
+
+    if sequence.received != sequence.sent {
+        bail!(
+            "{} messages were sent but {} received",
+            sequence.sent,
+            sequence.received
+        );
+    }
+    spinner.success(format!(
+        "[Coexistence] Step 4: Roundtripping {ROUNDTRIP_MSG_AMOUNT} MLS messages, {} in total [OK]",
+        sequence.sent
+    ));
+
+    Ok(())
+}
+
+async fn proteus_roundtrip(
+    alice: &mut dyn EmulatedCoexistenceClient,
+    alice_session: &str,
+    bob: &mut dyn EmulatedCoexistenceClient,
+    bob_session: &str,
+    sequence: &mut MessageSequence,
+) -> Result<()> {
+    let message = sequence.next();
+    let ciphertext = alice.encrypt(alice_session, &message).await?;
+    let decrypted = bob.decrypt(bob_session, &ciphertext).await?;
+    sequence.check(bob.client_name(), &decrypted)?;
+
+    let message = sequence.next();
+    let ciphertext = bob.encrypt(bob_session, &message).await?;
+    let decrypted = alice.decrypt(alice_session, &ciphertext).await?;
+    sequence.check(alice.client_name(), &decrypted)
+}
+
+async fn mls_roundtrip(
+    alice: &mut dyn EmulatedCoexistenceClient,
+    bob: &mut dyn EmulatedCoexistenceClient,
+    sequence: &mut MessageSequence,
+) -> Result<()> {
+    mls_send(alice, bob, sequence).await?;
+    mls_send(bob, alice, sequence).await
+}
+
+async fn mls_send(
+    sender: &mut dyn EmulatedCoexistenceClient,
+    receiver: &mut dyn EmulatedCoexistenceClient,
+    sequence: &mut MessageSequence,
+) -> Result<()> {
+    let message = sequence.next();
+    let ciphertext = sender.encrypt_message(COEXISTENCE_CONVERSATION_ID, &message).await?;
+    let decrypted = receiver
+        .decrypt_message(COEXISTENCE_CONVERSATION_ID, &ciphertext)
+        .await?
+        .ok_or_else(|| eyre!("[Coexistence] No message received by {}", receiver.client_name()))?;
+    sequence.check(receiver.client_name(), &decrypted)
+}
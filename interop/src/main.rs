@@ -25,6 +25,10 @@ use core_crypto::prelude::CiphersuiteName;
 mod build;
 #[cfg(not(target_family = "wasm"))]
 mod clients;
+#[cfg(all(not(target_family = "wasm"), feature = "proteus"))]
+mod coexistence;
+#[cfg(not(target_family = "wasm"))]
+mod scenario;
 #[cfg(not(target_family = "wasm"))]
 mod util;
 
@@ -100,9 +104,14 @@ fn run_test() -> Result<()> {
 
         run_mls_test(&chrome_driver_addr).await?;
 
+        scenario::run_scenarios(&chrome_driver_addr).await?;
+
         #[cfg(feature = "proteus")]
         run_proteus_test(&chrome_driver_addr).await?;
 
+        #[cfg(feature = "proteus")]
+        run_coexistence_test().await?;
+
         chrome_webdriver.kill().await?;
         http_server_hwnd.abort();
         Ok(())
@@ -395,3 +404,10 @@ async fn run_proteus_test(chrome_driver_addr: &std::net::SocketAddr) -> Result<(
 
     Ok(())
 }
+
+#[cfg(all(not(target_family = "wasm"), feature = "proteus"))]
+async fn run_coexistence_test() -> Result<()> {
+    let mut alice = clients::corecrypto::native::CoreCryptoNativeClient::new().await?;
+    let mut bob = clients::corecrypto::native::CoreCryptoNativeClient::new().await?;
+    coexistence::run_coexistence_scenario(&mut alice, &mut bob).await
+}
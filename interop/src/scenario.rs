@@ -0,0 +1,387 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Scenarios describe multi-client MLS flows in JSON so that regressions can be covered without touching Rust code.
+//! Each scenario declares its clients and a list of steps executed in order. The runner acts as the Delivery Service:
+//! commits are fanned out to every other member of the conversation, welcomes to the invitees, and application
+//! messages are checked to decrypt to the sent text on every other member.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+
+use crate::{
+    clients::{EmulatedMlsClient, MlsCommitOutcome},
+    util,
+};
+
+/// Directory scenarios are loaded from, relative to the repository root
+const SCENARIOS_DIR: &str = "interop/scenarios";
+/// Overrides [SCENARIOS_DIR] with a single scenario file or another directory
+const SCENARIOS_ENV: &str = "INTEROP_SCENARIOS";
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub clients: Vec<ClientSpec>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientSpec {
+    /// Name the client is referred to by in the steps
+    pub name: String,
+    pub kind: ClientKind,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientKind {
+    Native,
+    Web,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub operation: Operation,
+    #[serde(default)]
+    pub expect: Outcome,
+}
+
+/// Conversations are referred to by name, which is also used as their id
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// `client` creates a conversation
+    Create { client: String, conversation: String },
+    /// `client` adds `invitees` with a single commit
+    Invite {
+        client: String,
+        conversation: String,
+        invitees: Vec<String>,
+    },
+    /// `client` removes `members` with a single commit
+    Remove {
+        client: String,
+        conversation: String,
+        members: Vec<String>,
+    },
+    /// `client` joins with the latest GroupInfo of the conversation
+    ExternalJoin { client: String, conversation: String },
+    /// `client` updates its leaf node
+    Rotate { client: String, conversation: String },
+    /// `client` sends `text` to the conversation
+    Message {
+        client: String,
+        conversation: String,
+        text: String,
+    },
+}
+
+impl Operation {
+    fn client(&self) -> &str {
+        match self {
+            Self::Create { client, .. }
+            | Self::Invite { client, .. }
+            | Self::Remove { client, .. }
+            | Self::ExternalJoin { client, .. }
+            | Self::Rotate { client, .. }
+            | Self::Message { client, .. } => client,
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create { client, conversation } => write!(f, "{client} creates '{conversation}'"),
+            Self::Invite {
+                client,
+                conversation,
+                invitees,
+            } => write!(f, "{client} invites {} to '{conversation}'", invitees.join(", ")),
+            Self::Remove {
+                client,
+                conversation,
+                members,
+            } => write!(f, "{client} removes {} from '{conversation}'", members.join(", ")),
+            Self::ExternalJoin { client, conversation } => write!(f, "{client} joins '{conversation}' externally"),
+            Self::Rotate { client, conversation } => write!(f, "{client} rotates in '{conversation}'"),
+            Self::Message {
+                client, conversation, ..
+            } => write!(f, "{client} messages '{conversation}'"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    #[default]
+    Success,
+    /// The operation has to be rejected by the client performing it or by any member processing its output
+    Failure,
+}
+
+#[derive(Debug, Default)]
+struct ConversationState {
+    /// Names of the clients currently in the conversation
+    members: Vec<String>,
+    /// Latest GroupInfo published by a committer
+    group_info: Option<Vec<u8>>,
+}
+
+struct ScenarioRunner {
+    clients: HashMap<String, Box<dyn EmulatedMlsClient>>,
+    conversations: HashMap<String, ConversationState>,
+}
+
+impl ScenarioRunner {
+    async fn try_new(scenario: &Scenario, chrome_driver_addr: &SocketAddr) -> Result<Self> {
+        let mut clients = HashMap::<String, Box<dyn EmulatedMlsClient>>::new();
+        for spec in &scenario.clients {
+            let client: Box<dyn EmulatedMlsClient> = match spec.kind {
+                ClientKind::Native => {
+                    Box::new(crate::clients::corecrypto::native::CoreCryptoNativeClient::new().await?)
+                }
+                ClientKind::Web => {
+                    Box::new(crate::clients::corecrypto::web::CoreCryptoWebClient::new(chrome_driver_addr).await?)
+                }
+            };
+            if clients.insert(spec.name.clone(), client).is_some() {
+                bail!("Client '{}' is declared twice", spec.name);
+            }
+        }
+        Ok(Self {
+            clients,
+            conversations: HashMap::new(),
+        })
+    }
+
+    fn client(&mut self, name: &str) -> Result<&mut Box<dyn EmulatedMlsClient>> {
+        self.clients
+            .get_mut(name)
+            .ok_or_else(|| eyre!("Unknown client '{name}'"))
+    }
+
+    fn conversation(&mut self, name: &str) -> Result<&mut ConversationState> {
+        self.conversations
+            .get_mut(name)
+            .ok_or_else(|| eyre!("Unknown conversation '{name}'"))
+    }
+
+    async fn run(&mut self, operation: &Operation) -> Result<()> {
+        match operation {
+            Operation::Create { client, conversation } => {
+                if self.conversations.contains_key(conversation) {
+                    bail!("Conversation '{conversation}' already exists");
+                }
+                self.client(client)?
+                    .create_conversation(conversation.as_bytes())
+                    .await?;
+                self.conversations.insert(
+                    conversation.clone(),
+                    ConversationState {
+                        members: vec![client.clone()],
+                        group_info: None,
+                    },
+                );
+            }
+            Operation::Invite {
+                client,
+                conversation,
+                invitees,
+            } => {
+                let mut kps = vec![];
+                for invitee in invitees {
+                    kps.push(self.client(invitee)?.get_keypackage().await?);
+                }
+                let outcome = self.client(client)?.add_clients(conversation.as_bytes(), &kps).await?;
+                let welcome = outcome
+                    .welcome
+                    .clone()
+                    .ok_or_else(|| eyre!("Adding clients did not produce a Welcome"))?;
+                self.fan_out_commit(client, conversation, outcome).await?;
+                for invitee in invitees {
+                    let id = self.client(invitee)?.process_welcome(&welcome).await?;
+                    if id != conversation.as_bytes() {
+                        bail!("{invitee} joined another conversation than '{conversation}'");
+                    }
+                    self.conversation(conversation)?.members.push(invitee.clone());
+                }
+            }
+            Operation::Remove {
+                client,
+                conversation,
+                members,
+            } => {
+                let mut client_ids = vec![];
+                for member in members {
+                    client_ids.push(self.client(member)?.client_id().to_vec());
+                }
+                let outcome = self
+                    .client(client)?
+                    .remove_clients(conversation.as_bytes(), &client_ids)
+                    .await?;
+                self.conversation(conversation)?
+                    .members
+                    .retain(|m| !members.contains(m));
+                self.fan_out_commit(client, conversation, outcome).await?;
+            }
+            Operation::ExternalJoin { client, conversation } => {
+                let group_info = self
+                    .conversation(conversation)?
+                    .group_info
+                    .clone()
+                    .ok_or_else(|| eyre!("No GroupInfo was published for '{conversation}' yet"))?;
+                let (id, outcome) = self.client(client)?.join_by_external_commit(&group_info).await?;
+                if id != conversation.as_bytes() {
+                    bail!("{client} joined another conversation than '{conversation}'");
+                }
+                self.fan_out_commit(client, conversation, outcome).await?;
+                self.conversation(conversation)?.members.push(client.clone());
+            }
+            Operation::Rotate { client, conversation } => {
+                let outcome = self
+                    .client(client)?
+                    .update_keying_material(conversation.as_bytes())
+                    .await?;
+                self.fan_out_commit(client, conversation, outcome).await?;
+            }
+            Operation::Message {
+                client,
+                conversation,
+                text,
+            } => {
+                let message = self
+                    .client(client)?
+                    .encrypt_message(conversation.as_bytes(), text.as_bytes())
+                    .await?;
+                for member in self.others(client, conversation)? {
+                    let decrypted = self
+                        .client(&member)?
+                        .decrypt_message(conversation.as_bytes(), &message)
+                        .await?
+                        .ok_or_else(|| eyre!("{member} did not get an application message"))?;
+                    if decrypted != text.as_bytes() {
+                        bail!("{member} decrypted a different message than the one sent by {client}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Delivers a commit to every member of the conversation but its author and publishes its GroupInfo
+    async fn fan_out_commit(&mut self, author: &str, conversation: &str, outcome: MlsCommitOutcome) -> Result<()> {
+        for member in self.others(author, conversation)? {
+            self.client(&member)?
+                .decrypt_message(conversation.as_bytes(), &outcome.commit)
+                .await
+                .wrap_err_with(|| format!("{member} could not process the commit"))?;
+        }
+        self.conversation(conversation)?.group_info = Some(outcome.group_info);
+        Ok(())
+    }
+
+    fn others(&mut self, client: &str, conversation: &str) -> Result<Vec<String>> {
+        Ok(self
+            .conversation(conversation)?
+            .members
+            .iter()
+            .filter(|m| m.as_str() != client)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Loads every scenario from [SCENARIOS_DIR], or from the file or directory pointed by [SCENARIOS_ENV]
+pub fn load_scenarios() -> Result<Vec<Scenario>> {
+    let path = std::env::var(SCENARIOS_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(SCENARIOS_DIR));
+
+    let mut files = if path.is_dir() {
+        let mut files = vec![];
+        for entry in std::fs::read_dir(&path)? {
+            let file = entry?.path();
+            if file.extension().is_some_and(|ext| ext == "json") {
+                files.push(file);
+            }
+        }
+        files
+    } else {
+        vec![path]
+    };
+    files.sort();
+
+    files.iter().map(|file| load_scenario(file)).collect()
+}
+
+fn load_scenario(file: &Path) -> Result<Scenario> {
+    let content = std::fs::read(file).wrap_err_with(|| format!("Cannot read scenario {file:?}"))?;
+    serde_json::from_slice(&content).wrap_err_with(|| format!("Invalid scenario {file:?}"))
+}
+
+pub async fn run_scenarios(chrome_driver_addr: &SocketAddr) -> Result<()> {
+    for scenario in load_scenarios()? {
+        run_scenario(&scenario, chrome_driver_addr).await?;
+    }
+    Ok(())
+}
+
+async fn run_scenario(scenario: &Scenario, chrome_driver_addr: &SocketAddr) -> Result<()> {
+    let name = &scenario.name;
+    if let Some(description) = &scenario.description {
+        log::info!("[Scenario] {name}: {description}");
+    }
+
+    let mut spinner = util::RunningProcess::new(format!("[Scenario] {name}: Initializing clients..."), true);
+    let mut runner = ScenarioRunner::try_new(scenario, chrome_driver_addr).await?;
+
+    let total = scenario.steps.len();
+    for (i, step) in scenario.steps.iter().enumerate() {
+        let i = i + 1;
+        spinner.update(format!("[Scenario] {name}: Step {i}/{total}: {}", step.operation));
+        let result = runner.run(&step.operation).await;
+        match (step.expect, result) {
+            (Outcome::Success, Err(e)) => {
+                return Err(e.wrap_err(format!(
+                    "[Scenario] {name}: Step {i} '{}' failed [Client = {}]",
+                    step.operation,
+                    step.operation.client()
+                )))
+            }
+            (Outcome::Failure, Ok(())) => {
+                bail!("[Scenario] {name}: Step {i} '{}' was expected to fail", step.operation)
+            }
+            (Outcome::Failure, Err(e)) => log::info!("[Scenario] {name}: Step {i} failed as expected: {e}"),
+            (Outcome::Success, Ok(())) => {}
+        }
+    }
+
+    spinner.success(format!("[Scenario] {name}: {total} steps [OK]"));
+    Ok(())
+}
@@ -145,11 +145,60 @@ fn benchmark_reads_mls(c: &mut Criterion) {
     });
 }
 
+fn benchmark_concurrent_reads_mls(c: &mut Criterion) {
+    use core_crypto_keystore::{connection::DEFAULT_READ_POOL_SIZE, entities::MlsKeyPackage};
+
+    const NB_THREADS: usize = 8;
+    const READS_PER_THREAD: usize = 100;
+
+    let mut group = c.benchmark_group("MLS Concurrent Reads");
+    group.throughput(Throughput::Elements((NB_THREADS * READS_PER_THREAD) as u64));
+
+    for read_pool_size in [0, DEFAULT_READ_POOL_SIZE] {
+        let store = block_on(CryptoKeystore::open_with_key_and_read_pool(
+            format!("bench_concurrent_read_mls_{read_pool_size}"),
+            "key",
+            read_pool_size,
+        ))
+        .unwrap();
+
+        let keypackage_ref = uuid::Uuid::new_v4().as_bytes().to_vec();
+        block_on(store.save(MlsKeyPackage {
+            keypackage_ref: keypackage_ref.clone(),
+            keypackage: vec![0u8; 1024],
+        }))
+        .unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("Readers", read_pool_size),
+            &keypackage_ref,
+            |b, keypackage_ref| {
+                b.iter(|| {
+                    std::thread::scope(|s| {
+                        for _ in 0..NB_THREADS {
+                            s.spawn(|| {
+                                for _ in 0..READS_PER_THREAD {
+                                    let kp = block_on(store.find::<MlsKeyPackage>(keypackage_ref)).unwrap();
+                                    black_box(kp);
+                                }
+                            });
+                        }
+                    })
+                })
+            },
+        );
+
+        block_on(store.wipe()).unwrap();
+    }
+
+    group.finish();
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "proteus-keystore")] {
-        criterion_group!(benches, benchmark_reads_mls, benchmark_reads_proteus);
+        criterion_group!(benches, benchmark_reads_mls, benchmark_concurrent_reads_mls, benchmark_reads_proteus);
     } else {
-        criterion_group!(benches, benchmark_reads_mls);
+        criterion_group!(benches, benchmark_reads_mls, benchmark_concurrent_reads_mls);
     }
 }
 
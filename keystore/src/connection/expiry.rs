@@ -0,0 +1,127 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Transient entities, e.g. the pending group of an external commit which is never merged, are of no use once
+//! abandoned but nothing deletes them. The entities opting in with [Entity::TTL] expire once it elapsed since they
+//! were last saved: the lookups of the [Connection] no longer return them and [Connection::purge_expired] deletes
+//! them for good. Entities saved on WASM before they had a TTL have no timestamp, hence are expired right away.
+
+use super::Connection;
+use crate::{entities::Entity, CryptoKeystoreResult};
+
+/// Current time as a Unix timestamp in seconds, as stored in the timestamps of entities
+pub(crate) fn unix_timestamp() -> CryptoKeystoreResult<u64> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_family = "wasm")] {
+            Ok(fluvio_wasm_timer::SystemTime::now()
+                .duration_since(fluvio_wasm_timer::UNIX_EPOCH)
+                .map_err(|_| crate::CryptoKeystoreError::TimestampError)?
+                .as_secs())
+        } else {
+            Ok(std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs())
+        }
+    }
+}
+
+fn is_expired<E: Entity>(entity: &E, now: u64) -> bool {
+    E::TTL.is_some_and(|ttl| entity.created_at().saturating_add(ttl.as_secs()) <= now)
+}
+
+impl Connection {
+    pub(crate) fn unexpired<E: Entity>(entity: Option<E>) -> CryptoKeystoreResult<Option<E>> {
+        if E::TTL.is_none() {
+            return Ok(entity);
+        }
+        let now = unix_timestamp()?;
+        Ok(entity.filter(|entity| !is_expired(entity, now)))
+    }
+
+    pub(crate) fn retain_unexpired<E: Entity>(entities: &mut Vec<E>) -> CryptoKeystoreResult<()> {
+        if E::TTL.is_none() {
+            return Ok(());
+        }
+        let now = unix_timestamp()?;
+        entities.retain(|entity| !is_expired(entity, now));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mls-keystore")]
+mod purge {
+    use std::collections::HashMap;
+
+    use super::{is_expired, unix_timestamp};
+    use crate::{
+        connection::{Connection, KeystoreDatabaseConnection},
+        entities::{E2eiEnrollment, Entity, EntityFindParams, MlsPendingMessage, PersistedMlsPendingGroup},
+        CryptoKeystoreResult,
+    };
+
+    impl Connection {
+        /// Number of expired entities [Self::purge_expired] would delete, entities sharing an id counting once
+        pub async fn expired_count(&self) -> CryptoKeystoreResult<usize> {
+            let mut conn = self.borrow_reader().await;
+            let now = unix_timestamp()?;
+            let mut expired = Self::expired_ids::<PersistedMlsPendingGroup>(&mut conn, now)
+                .await?
+                .len();
+            expired += Self::expired_ids::<MlsPendingMessage>(&mut conn, now).await?.len();
+            expired += Self::expired_ids::<E2eiEnrollment>(&mut conn, now).await?.len();
+            Ok(expired)
+        }
+
+        /// Deletes the transient entities whose [Entity::TTL] elapsed: pending groups of external commits,
+        /// buffered messages and stashed E2EI enrollments. Buffered messages are deleted once all the ones of their
+        /// conversation expired. Returns the number of entities deleted, entities sharing an id counting once
+        pub async fn purge_expired(&self) -> CryptoKeystoreResult<usize> {
+            let mut conn = self.borrow_writer().await?;
+            let now = unix_timestamp()?;
+            let mut purged = Self::purge_collection::<PersistedMlsPendingGroup>(&mut conn, now).await?;
+            purged += Self::purge_collection::<MlsPendingMessage>(&mut conn, now).await?;
+            purged += Self::purge_collection::<E2eiEnrollment>(&mut conn, now).await?;
+            Ok(purged)
+        }
+
+        async fn purge_collection<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+            conn: &mut KeystoreDatabaseConnection,
+            now: u64,
+        ) -> CryptoKeystoreResult<usize> {
+            let ids = Self::expired_ids::<E>(conn, now).await?;
+            for id in &ids {
+                E::delete(conn, &[id.as_slice().into()]).await?;
+                Self::remove_checksum::<E>(conn, id).await?;
+            }
+            Ok(ids.len())
+        }
+
+        /// Ids all the entities of which expired
+        async fn expired_ids<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+            conn: &mut KeystoreDatabaseConnection,
+            now: u64,
+        ) -> CryptoKeystoreResult<Vec<Vec<u8>>> {
+            let mut expired = HashMap::<Vec<u8>, bool>::new();
+            for entity in E::find_all(conn, EntityFindParams::default()).await? {
+                *expired.entry(entity.id_raw().to_vec()).or_insert(true) &= is_expired(&entity, now);
+            }
+            Ok(expired
+                .into_iter()
+                .filter_map(|(id, expired)| expired.then_some(id))
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Filesystem corruption can leave content in the store which is not what was written, and which only fails later
+//! when it is deserialized or decrypted, indistinguishable from the store being opened with the wrong key. The
+//! entities opting in with [Entity::CHECKSUM_COLLECTION] get a checksum of their content written along with them and
+//! validated whenever they are looked up through the [Connection], so that such corruption surfaces as
+//! [CryptoKeystoreError::CorruptedEntity]. Content written before checksums existed has none, hence is not validated.
+
+use std::collections::HashMap;
+
+use sha2::{Digest as _, Sha256};
+
+use super::{Connection, KeystoreDatabaseConnection};
+use crate::{
+    entities::{Entity, EntityBase as _, EntityChecksum, EntityFindParams, StringEntityId},
+    CryptoKeystoreError, CryptoKeystoreResult,
+};
+
+/// An entity whose content does not match its checksum, see [Connection::integrity_check]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorruptedEntry {
+    /// Collection of the entity, e.g. `mls_groups`
+    pub collection: &'static str,
+    /// Id of the entity
+    pub id: Vec<u8>,
+}
+
+fn checksum_id(collection: &str, id: &[u8]) -> Vec<u8> {
+    let mut checksum_id = Vec::with_capacity(collection.len() + 1 + id.len());
+    checksum_id.extend_from_slice(collection.as_bytes());
+    checksum_id.push(b'/');
+    checksum_id.extend_from_slice(id);
+    checksum_id
+}
+
+fn checksum<E: Entity>(entity: &E) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in entity.checksummed_content() {
+        // length-prefixed so that moving bytes from one part to the next changes the checksum
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+impl EntityChecksum {
+    fn matches(&self, checksum: &[u8]) -> bool {
+        self.checksum == checksum || self.previous_checksum.as_deref() == Some(checksum)
+    }
+}
+
+impl Connection {
+    /// Checks every checksummed entity of the store against its checksum and lists the ones which do not match,
+    /// e.g. to tell the user which conversations are lost after a filesystem corruption rather than failing on the
+    /// first one. Entities without checksum are not reported
+    ///
+    /// # Errors
+    /// When the entities can't be read at all, e.g. because the store is opened with the wrong key
+    pub async fn integrity_check(&self) -> CryptoKeystoreResult<Vec<CorruptedEntry>> {
+        let mut conn = self.borrow_reader().await;
+        let mut corrupted = vec![];
+        #[cfg(feature = "mls-keystore")]
+        {
+            use crate::entities::{
+                MlsEpochEncryptionKeyPair, MlsSignatureKeyPair, PersistedMlsGroup, PersistedMlsPendingGroup,
+            };
+            corrupted.extend(Self::check_collection::<PersistedMlsGroup>(&mut conn).await?);
+            corrupted.extend(Self::check_collection::<PersistedMlsPendingGroup>(&mut conn).await?);
+            corrupted.extend(Self::check_collection::<MlsSignatureKeyPair>(&mut conn).await?);
+            corrupted.extend(Self::check_collection::<MlsEpochEncryptionKeyPair>(&mut conn).await?);
+        }
+        #[cfg(feature = "proteus-keystore")]
+        {
+            use crate::entities::{ProteusIdentity, ProteusPrekey, ProteusSession};
+            corrupted.extend(Self::check_collection::<ProteusIdentity>(&mut conn).await?);
+            corrupted.extend(Self::check_collection::<ProteusPrekey>(&mut conn).await?);
+            corrupted.extend(Self::check_collection::<ProteusSession>(&mut conn).await?);
+        }
+        Ok(corrupted)
+    }
+
+    async fn check_collection<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+        conn: &mut KeystoreDatabaseConnection,
+    ) -> CryptoKeystoreResult<Vec<CorruptedEntry>> {
+        let entities = E::find_all(conn, EntityFindParams::default()).await?;
+        Self::find_corrupted(conn, &entities).await
+    }
+
+    async fn find_corrupted<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+        conn: &mut KeystoreDatabaseConnection,
+        entities: &[E],
+    ) -> CryptoKeystoreResult<Vec<CorruptedEntry>> {
+        let Some(collection) = E::CHECKSUM_COLLECTION else {
+            return Ok(vec![]);
+        };
+        if entities.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let ids = entities
+            .iter()
+            .map(|entity| checksum_id(collection, entity.id_raw()))
+            .collect::<Vec<_>>();
+        let entity_ids = ids
+            .iter()
+            .map(|id| id.as_slice().into())
+            .collect::<Vec<StringEntityId>>();
+        let checksums = EntityChecksum::find_many(conn, &entity_ids)
+            .await?
+            .into_iter()
+            .map(|stored| (stored.id.clone(), stored))
+            .collect::<HashMap<_, _>>();
+
+        Ok(entities
+            .iter()
+            .zip(ids.iter())
+            .filter(|(entity, id)| {
+                checksums
+                    .get(*id)
+                    .is_some_and(|stored| !stored.matches(&checksum(*entity)))
+            })
+            .map(|(entity, _)| CorruptedEntry {
+                collection,
+                id: entity.id_raw().to_vec(),
+            })
+            .collect())
+    }
+
+    /// Fails with [CryptoKeystoreError::CorruptedEntity] when one of the entities read from the store does not match
+    /// its checksum
+    pub(crate) async fn verify_checksums<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+        conn: &mut KeystoreDatabaseConnection,
+        entities: &[E],
+    ) -> CryptoKeystoreResult<()> {
+        match Self::find_corrupted(conn, entities).await?.into_iter().next() {
+            Some(corrupted) => Err(CryptoKeystoreError::CorruptedEntity {
+                collection: corrupted.collection,
+                id: hex::encode(corrupted.id),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the checksum of an entity about to be saved. The checksum it replaces stays valid since the entity
+    /// itself might not make it to the store, e.g. when the application is killed in between
+    pub(crate) async fn stage_checksum<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+        conn: &mut KeystoreDatabaseConnection,
+        entity: &E,
+    ) -> CryptoKeystoreResult<()> {
+        let Some(collection) = E::CHECKSUM_COLLECTION else {
+            return Ok(());
+        };
+        let id = checksum_id(collection, entity.id_raw());
+        let previous_checksum = EntityChecksum::find_one(conn, &id.as_slice().into())
+            .await?
+            .map(|stored| stored.checksum.clone());
+        EntityChecksum {
+            id,
+            checksum: checksum(entity),
+            previous_checksum,
+        }
+        .save(conn)
+        .await
+    }
+
+    /// Removes the checksum of an entity which has been removed
+    pub(crate) async fn remove_checksum<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
+        conn: &mut KeystoreDatabaseConnection,
+        id: &[u8],
+    ) -> CryptoKeystoreResult<()> {
+        let Some(collection) = E::CHECKSUM_COLLECTION else {
+            return Ok(());
+        };
+        let id = checksum_id(collection, id);
+        if EntityChecksum::find_one(conn, &id.as_slice().into()).await?.is_some() {
+            EntityChecksum::delete(conn, &[id.as_slice().into()]).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Instead of being encrypted with a key derived from one the app knows, a persistent store can be encrypted with a
+//! random master key which never leaves the device unprotected: it is wrapped by a [KeyProvider] backed by the
+//! platform's secure hardware (Android Keystore, Secure Enclave...) and only the wrapped key is kept, next to the
+//! store. Native platforms write it in a `<store path>.key` file while WASM keeps it in a `<store name>.key`
+//! IndexedDB database.
+
+use rand::RngCore as _;
+use zeroize::Zeroizing;
+
+use crate::CryptoKeystoreResult;
+
+/// Length in bytes of the master key of a store
+const MASTER_KEY_LEN: usize = 32;
+
+/// Wraps and unwraps the master key of a store with a key held by the platform's secure hardware, see
+/// [Connection::open_with_key_provider](super::Connection::open_with_key_provider)
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait KeyProvider: std::fmt::Debug + Send + Sync {
+    /// Encrypts the master key of a store being created
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::KeyProviderError](crate::CryptoKeystoreError::KeyProviderError) when the platform fails
+    /// to do so, in which case the store is not created
+    async fn wrap_key(&self, key: &[u8]) -> CryptoKeystoreResult<Vec<u8>>;
+
+    /// Decrypts a master key previously encrypted by [Self::wrap_key]
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::KeyProviderError](crate::CryptoKeystoreError::KeyProviderError) when the platform fails
+    /// to do so, in which case the store cannot be opened
+    async fn unwrap_key(&self, wrapped_key: &[u8]) -> CryptoKeystoreResult<Vec<u8>>;
+}
+
+/// The key the store `name` is encrypted with. Its master key is generated and wrapped by `key_provider` when the
+/// store has none yet, unwrapped otherwise
+pub(crate) async fn master_key(name: &str, key_provider: &dyn KeyProvider) -> CryptoKeystoreResult<Zeroizing<String>> {
+    let wrapped_key = match platform::read_wrapped_key(name).await? {
+        Some(wrapped_key) => wrapped_key,
+        None => {
+            let mut key = Zeroizing::new(vec![0u8; MASTER_KEY_LEN]);
+            rand::rngs::OsRng.fill_bytes(&mut key);
+            let wrapped_key = key_provider.wrap_key(&key).await?;
+            match platform::write_wrapped_key_if_absent(name, &wrapped_key).await? {
+                None => return Ok(Zeroizing::new(hex::encode(&*key))),
+                // another instance created the store in the meantime
+                Some(existing) => existing,
+            }
+        }
+    };
+    let key = Zeroizing::new(key_provider.unwrap_key(&wrapped_key).await?);
+    Ok(Zeroizing::new(hex::encode(&*key)))
+}
+
+/// Removes the wrapped master key of the store `name`, once the store itself has been wiped
+pub(crate) async fn remove_wrapped_key(name: &str) -> CryptoKeystoreResult<()> {
+    platform::remove_wrapped_key(name).await
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{io::Write as _, path::PathBuf};
+
+    use crate::CryptoKeystoreResult;
+
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(format!("{name}.key"))
+    }
+
+    pub(super) async fn read_wrapped_key(name: &str) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+        match std::fs::read(path(name)) {
+            Ok(wrapped_key) => Ok(Some(wrapped_key)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the wrapped key unless there's already one, in which case it is returned instead
+    pub(super) async fn write_wrapped_key_if_absent(
+        name: &str,
+        wrapped_key: &[u8],
+    ) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+        static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let tmp_path = PathBuf::from(format!("{name}.key.{}-{count}", std::process::id()));
+
+        // written aside then linked, so that the key is never seen half-written and an existing one never replaced
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(wrapped_key)?;
+        file.sync_all()?;
+        let linked = std::fs::hard_link(&tmp_path, path(name));
+        let _ = std::fs::remove_file(&tmp_path);
+        match linked {
+            Ok(()) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => read_wrapped_key(name).await,
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub(super) async fn remove_wrapped_key(name: &str) -> CryptoKeystoreResult<()> {
+        match std::fs::remove_file(path(name)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use rexie::{ObjectStore, Rexie, TransactionMode};
+    use wasm_bindgen::JsValue;
+
+    use crate::CryptoKeystoreResult;
+
+    const WRAPPED_KEYS: &str = "wrapped_keys";
+    const MASTER_KEY_ID: &str = "master";
+
+    fn db_name(name: &str) -> String {
+        format!("{name}.key")
+    }
+
+    async fn open(name: &str) -> CryptoKeystoreResult<Rexie> {
+        Ok(Rexie::builder(&db_name(name))
+            .version(1)
+            .add_object_store(ObjectStore::new(WRAPPED_KEYS).auto_increment(false))
+            .build()
+            .await?)
+    }
+
+    pub(super) async fn read_wrapped_key(name: &str) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+        let rexie = open(name).await?;
+        let wrapped_key = {
+            let transaction = rexie.transaction(&[WRAPPED_KEYS], TransactionMode::ReadOnly)?;
+            let store = transaction.store(WRAPPED_KEYS)?;
+            store
+                .get(&JsValue::from_str(MASTER_KEY_ID))
+                .await?
+                .map(|wrapped_key| js_sys::Uint8Array::new(&wrapped_key).to_vec())
+        };
+        rexie.close();
+        Ok(wrapped_key)
+    }
+
+    /// Writes the wrapped key unless there's already one, in which case it is returned instead
+    pub(super) async fn write_wrapped_key_if_absent(
+        name: &str,
+        wrapped_key: &[u8],
+    ) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+        let rexie = open(name).await?;
+        let existing = {
+            // IndexedDB serializes read-write transactions, no other instance can write in between
+            let transaction = rexie.transaction(&[WRAPPED_KEYS], TransactionMode::ReadWrite)?;
+            let store = transaction.store(WRAPPED_KEYS)?;
+            let key_id = JsValue::from_str(MASTER_KEY_ID);
+            let existing = store
+                .get(&key_id)
+                .await?
+                .map(|wrapped_key| js_sys::Uint8Array::new(&wrapped_key).to_vec());
+            if existing.is_none() {
+                store.put(&js_sys::Uint8Array::from(wrapped_key), Some(&key_id)).await?;
+            }
+            transaction.done().await?;
+            existing
+        };
+        rexie.close();
+        Ok(existing)
+    }
+
+    pub(super) async fn remove_wrapped_key(name: &str) -> CryptoKeystoreResult<()> {
+        Rexie::builder(&db_name(name)).delete().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters of the keystore activity since it was opened, see [super::Connection::metrics]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeystoreMetrics {
+    /// Number of entities saved
+    pub writes: u64,
+    /// Bytes of MLS group states and key material handed to the keystore, before encryption
+    pub bytes_written: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KeystoreCounters {
+    writes: AtomicU64,
+    bytes_written: AtomicU64,
+}
+
+impl KeystoreCounters {
+    pub(crate) fn record_write(&self) {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_bytes(&self, len: usize) {
+        self.bytes_written.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> KeystoreMetrics {
+        KeystoreMetrics {
+            writes: self.writes.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+        }
+    }
+}
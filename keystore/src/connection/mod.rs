@@ -23,11 +23,28 @@ pub mod platform {
         } else {
             mod generic;
             pub use self::generic::SqlCipherConnection as KeystoreDatabaseConnection;
+            pub use self::generic::{ReadPool, DEFAULT_READ_POOL_SIZE};
         }
     }
 }
 
 pub use self::platform::*;
+pub(crate) mod expiry;
+mod integrity;
+pub use self::integrity::CorruptedEntry;
+mod key_provider;
+pub use self::key_provider::KeyProvider;
+mod metrics;
+pub use self::metrics::KeystoreMetrics;
+mod options;
+pub use self::options::{ConnectionOptions, FileProtectionClass};
+mod slow_log;
+pub use self::slow_log::{KeystoreOperation, SlowOperation, SLOW_OPERATION_LOG_CAPACITY};
+#[cfg(feature = "mls-keystore")]
+mod recovery;
+mod store_lock;
+#[cfg(feature = "mls-keystore")]
+pub use self::recovery::PersistenceFailureCallback;
 use crate::entities::{Entity, EntityFindParams, StringEntityId};
 
 use crate::{CryptoKeystoreError, CryptoKeystoreResult};
@@ -80,6 +97,22 @@ pub trait DatabaseConnection: DatabaseConnectionRequirements {
 #[derive(Debug)]
 pub struct Connection {
     pub(crate) conn: Arc<Mutex<KeystoreDatabaseConnection>>,
+    /// Read-only connections used by lookups so that they don't serialize on `conn`
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) readers: ReadPool,
+    /// Set while an explicit transaction is in progress: lookups then have to go through `conn` to see its writes
+    #[cfg(not(target_family = "wasm"))]
+    in_transaction: std::sync::atomic::AtomicBool,
+    /// Groups whose latest state could not be persisted yet, see [Self::set_persistence_failure_callback]
+    #[cfg(feature = "mls-keystore")]
+    pub(crate) recovery: Mutex<recovery::PersistenceRecovery>,
+    pub(crate) metrics: metrics::KeystoreCounters,
+    /// Operations which took longer than a threshold, see [Self::set_slow_operation_threshold]
+    pub(crate) slow_log: slow_log::SlowOperationLog,
+    /// Guards a persistent store against other instances, see [Self::take_over_with_key]
+    store_lock: Option<store_lock::StoreLock>,
+    /// Name of the store when its master key is wrapped by a [KeyProvider], so that the wrapped key goes away with it
+    wrapped_key_store: Option<String>,
 }
 
 // * SAFETY: this has mutexes and atomics protecting underlying data so this is safe to share between threads
@@ -87,13 +120,156 @@ unsafe impl Send for Connection {}
 unsafe impl Sync for Connection {}
 
 impl Connection {
+    /// Opens the persistent store `name`.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::StoreAlreadyInUse] when another instance holds the store, see [Self::take_over_with_key]
     pub async fn open_with_key(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
-        let conn = KeystoreDatabaseConnection::open(name.as_ref(), key.as_ref())
+        Self::open(name.as_ref(), key.as_ref(), &ConnectionOptions::default()).await
+    }
+
+    /// Opens the persistent store `name` with the given [ConnectionOptions]
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::DeviceLocked] when the store can't be accessed until the device is unlocked, see
+    /// [ConnectionOptions::file_protection]. Otherwise the same as [Self::open_with_key]
+    pub async fn open_with_options(
+        name: impl AsRef<str>,
+        key: impl AsRef<str>,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        Self::open(name.as_ref(), key.as_ref(), options).await
+    }
+
+    /// Opens the persistent store `name` even if another instance holds it, e.g. an app extension running while the
+    /// main app is suspended. Every write the previous holder attempts afterwards fails with
+    /// [CryptoKeystoreError::StoreAlreadyInUse], so that it cannot overwrite the state of this instance
+    pub async fn take_over_with_key(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
+        let options = ConnectionOptions {
+            take_over: true,
+            ..Default::default()
+        };
+        Self::open(name.as_ref(), key.as_ref(), &options).await
+    }
+
+    /// Opens the persistent store `name`, encrypted with a master key protected by the platform's secure hardware
+    /// rather than with a key known by the app, see [KeyProvider]. The master key is generated when the store is
+    /// created
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::KeyProviderError] when the master key cannot be wrapped or unwrapped, otherwise the same
+    /// as [Self::open_with_key]
+    pub async fn open_with_key_provider(
+        name: impl AsRef<str>,
+        key_provider: &dyn KeyProvider,
+    ) -> CryptoKeystoreResult<Self> {
+        Self::open_with_key_provider_and_options(name, key_provider, &ConnectionOptions::default()).await
+    }
+
+    /// Same as [Self::open_with_key_provider] but takes the store over like [Self::take_over_with_key] does
+    pub async fn take_over_with_key_provider(
+        name: impl AsRef<str>,
+        key_provider: &dyn KeyProvider,
+    ) -> CryptoKeystoreResult<Self> {
+        let options = ConnectionOptions {
+            take_over: true,
+            ..Default::default()
+        };
+        Self::open_with_key_provider_and_options(name, key_provider, &options).await
+    }
+
+    /// Same as [Self::open_with_key_provider] with the given [ConnectionOptions], see [Self::open_with_options]
+    pub async fn open_with_key_provider_and_options(
+        name: impl AsRef<str>,
+        key_provider: &dyn KeyProvider,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        let name = name.as_ref();
+        let key = key_provider::master_key(name, key_provider).await?;
+        let mut conn = Self::open(name, &key, options).await?;
+        conn.wrapped_key_store = Some(name.to_string());
+        Ok(conn)
+    }
+
+    async fn open(name: &str, key: &str, options: &ConnectionOptions) -> CryptoKeystoreResult<Self> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_family = "wasm")] {
+                let store_lock = store_lock::StoreLock::acquire(name, options.take_over).await?;
+                let conn = KeystoreDatabaseConnection::open(name, key).await?.into();
+                #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
+                let conn = Arc::new(conn);
+                Ok(Self {
+                    conn,
+                    #[cfg(feature = "mls-keystore")]
+                    recovery: Default::default(),
+                    metrics: Default::default(),
+                    slow_log: Default::default(),
+                    store_lock: Some(store_lock),
+                    wrapped_key_store: None,
+                })
+            } else {
+                Self::open_with_read_pool(name, key, DEFAULT_READ_POOL_SIZE, options).await
+            }
+        }
+    }
+
+    /// Opens the keystore along with `read_pool_size` read-only connections serving lookups concurrently.
+    /// A size of 0 makes every operation go through the single read-write connection
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn open_with_key_and_read_pool(
+        name: impl AsRef<str>,
+        key: impl AsRef<str>,
+        read_pool_size: usize,
+    ) -> CryptoKeystoreResult<Self> {
+        Self::open_with_read_pool(
+            name.as_ref(),
+            key.as_ref(),
+            read_pool_size,
+            &ConnectionOptions::default(),
+        )
+        .await
+    }
+
+    /// Opens the persistent store `name` like [Self::open_with_key], encrypting it with `key` first if it has been
+    /// created before stores were encrypted. Every table is copied into an encrypted database which replaces the
+    /// unencrypted one once its row counts have been checked, then the unencrypted one is shredded. Stores which are
+    /// already encrypted are opened as they are, hence this can be called on every start until no legacy install is
+    /// left.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::UnencryptedMigrationError] when the unencrypted database is not a keystore or has not
+    /// been copied entirely, in which case it is left as it was. Otherwise the same as [Self::open_with_key]
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn migrate_unencrypted(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
+        KeystoreDatabaseConnection::migrate_unencrypted(name.as_ref(), key.as_ref()).await?;
+        Self::open_with_key(name, key).await
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    async fn open_with_read_pool(
+        name: &str,
+        key: &str,
+        read_pool_size: usize,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        // Locked before anything touches the database, the migrations included
+        let store_lock = store_lock::StoreLock::acquire(name, options.take_over).await?;
+        // The read-write connection has to be opened first since it creates the database and runs the migrations
+        let conn = KeystoreDatabaseConnection::open_with_options(name, key, options)
             .await?
             .into();
-        #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
-        let conn = Arc::new(conn);
-        Ok(Self { conn })
+        let readers = ReadPool::open(name, key, read_pool_size, options).await?;
+        Ok(Self {
+            conn: Arc::new(conn),
+            readers,
+            in_transaction: Default::default(),
+            #[cfg(feature = "mls-keystore")]
+            recovery: Default::default(),
+            metrics: Default::default(),
+            slow_log: Default::default(),
+            store_lock: Some(store_lock),
+            wrapped_key_store: None,
+        })
     }
 
     pub async fn open_in_memory_with_key(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
@@ -102,78 +278,298 @@ impl Connection {
             .into();
         #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
         let conn = Arc::new(conn);
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            // in-memory databases cannot be shared between connections
+            #[cfg(not(target_family = "wasm"))]
+            readers: ReadPool::default(),
+            #[cfg(not(target_family = "wasm"))]
+            in_transaction: Default::default(),
+            #[cfg(feature = "mls-keystore")]
+            recovery: Default::default(),
+            metrics: Default::default(),
+            slow_log: Default::default(),
+            // nothing to share with other instances
+            store_lock: None,
+            wrapped_key_store: None,
+        })
     }
 
     pub async fn borrow_conn(&self) -> CryptoKeystoreResult<MutexGuard<'_, KeystoreDatabaseConnection>> {
+        self.borrow_writer().await
+    }
+
+    /// Borrows the read-write connection, provided that no other instance took the store over
+    pub(crate) async fn borrow_writer(&self) -> CryptoKeystoreResult<MutexGuard<'_, KeystoreDatabaseConnection>> {
+        if let Some(store_lock) = &self.store_lock {
+            store_lock.ensure_held()?;
+        }
         Ok(self.conn.lock().await)
     }
 
+    /// Takes the store back over from the instance which took it over, e.g. the main app resuming after its extension
+    /// ran. Writes of this instance fail with [CryptoKeystoreError::StoreAlreadyInUse] until then
+    pub async fn take_over(&self) -> CryptoKeystoreResult<()> {
+        if let Some(store_lock) = &self.store_lock {
+            store_lock.take_over().await?;
+        }
+        Ok(())
+    }
+
+    /// Counters of the keystore activity since it was opened
+    pub fn metrics(&self) -> KeystoreMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// Starts (or stops with `None`) recording the operations taking at least `threshold`, see
+    /// [Self::slow_operations]. Stopping discards the operations recorded so far
+    pub fn set_slow_operation_threshold(&self, threshold: Option<std::time::Duration>) {
+        self.slow_log.set_threshold(threshold);
+    }
+
+    /// The last [SLOW_OPERATION_LOG_CAPACITY] operations which took longer than the threshold set with
+    /// [Self::set_slow_operation_threshold], oldest first
+    pub fn slow_operations(&self) -> Vec<SlowOperation> {
+        self.slow_log.snapshot()
+    }
+
+    /// Number of read-only connections serving lookups alongside the read-write one
+    #[cfg(not(target_family = "wasm"))]
+    pub fn read_pool_size(&self) -> usize {
+        self.readers.size()
+    }
+
+    /// Borrows a connection to perform lookups: a read-only one from the pool when there's one,
+    /// the read-write connection otherwise
+    async fn borrow_reader(&self) -> MutexGuard<'_, KeystoreDatabaseConnection> {
+        #[cfg(not(target_family = "wasm"))]
+        if !self.in_transaction.load(std::sync::atomic::Ordering::Acquire) {
+            if let Some(reader) = self.readers.acquire().await {
+                return reader;
+            }
+        }
+        self.conn.lock().await
+    }
+
+    /// Starts a transaction spanning every following write until [Self::commit_transaction] persists them or
+    /// [Self::rollback_transaction] discards them. Only one can be in progress at a time
+    pub async fn begin_transaction(&self) -> CryptoKeystoreResult<()> {
+        let mut conn = self.borrow_writer().await?;
+        #[cfg(not(target_family = "wasm"))]
+        {
+            conn.begin_transaction()?;
+            self.in_transaction.store(true, std::sync::atomic::Ordering::Release);
+        }
+        #[cfg(target_family = "wasm")]
+        conn.storage_mut().begin_transaction()?;
+        Ok(())
+    }
+
+    /// Persists every write made since [Self::begin_transaction]. When it fails, they are discarded as with
+    /// [Self::rollback_transaction]
+    pub async fn commit_transaction(&self) -> CryptoKeystoreResult<()> {
+        let mut conn = self.borrow_writer().await?;
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let committed = conn.commit_transaction();
+            self.in_transaction.store(false, std::sync::atomic::Ordering::Release);
+            committed?;
+        }
+        #[cfg(target_family = "wasm")]
+        conn.storage_mut().commit_transaction().await?;
+        #[cfg(feature = "mls-keystore")]
+        self.flush_dirty_mls_groups(&mut conn).await;
+        Ok(())
+    }
+
+    /// Discards every write made since [Self::begin_transaction]
+    pub async fn rollback_transaction(&self) -> CryptoKeystoreResult<()> {
+        let mut conn = self.borrow_writer().await?;
+        #[cfg(not(target_family = "wasm"))]
+        {
+            conn.rollback_transaction()?;
+            self.in_transaction.store(false, std::sync::atomic::Ordering::Release);
+        }
+        #[cfg(target_family = "wasm")]
+        conn.storage_mut().rollback_transaction()?;
+        Ok(())
+    }
+
+    /// Sets (or unsets) the callback invoked when a write would exceed the storage quota of the origin
+    #[cfg(target_family = "wasm")]
+    pub async fn set_storage_pressure_callback(&self, callback: Option<storage::StoragePressureCallback>) {
+        self.conn
+            .lock()
+            .await
+            .storage_mut()
+            .set_storage_pressure_callback(callback);
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn save<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
         &self,
         entity: E,
     ) -> CryptoKeystoreResult<E> {
-        let mut conn = self.conn.lock().await;
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::Save);
+        timer.entities = 1;
+        let mut conn = self.borrow_writer().await?;
+        Self::stage_checksum(&mut conn, &entity).await?;
         entity.save(&mut conn).await?;
+        self.metrics.record_write();
+        #[cfg(feature = "mls-keystore")]
+        self.flush_dirty_mls_groups(&mut conn).await;
         Ok(entity)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn insert<E: Entity<ConnectionType = KeystoreDatabaseConnection> + std::marker::Sync>(
         &self,
         entity: E,
     ) -> CryptoKeystoreResult<E::AutoGeneratedFields> {
-        let mut conn = self.conn.lock().await;
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::Insert);
+        timer.entities = 1;
+        let mut conn = self.borrow_writer().await?;
         let fields = entity.insert(&mut conn).await?;
+        self.metrics.record_write();
+        #[cfg(feature = "mls-keystore")]
+        self.flush_dirty_mls_groups(&mut conn).await;
         Ok(fields)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn find<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
         &self,
         id: impl AsRef<[u8]>,
     ) -> CryptoKeystoreResult<Option<E>> {
-        let mut conn = self.conn.lock().await;
-        E::find_one(&mut conn, &id.as_ref().into()).await
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::Find);
+        let mut conn = self.borrow_reader().await;
+        let entity = Self::unexpired(E::find_one(&mut conn, &id.as_ref().into()).await?)?;
+        if let Some(entity) = &entity {
+            timer.entities = 1;
+            Self::verify_checksums(&mut conn, std::slice::from_ref(entity)).await?;
+        }
+        Ok(entity)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn find_all<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
         &self,
         params: EntityFindParams,
     ) -> CryptoKeystoreResult<Vec<E>> {
-        let mut conn = self.conn.lock().await;
-        E::find_all(&mut conn, params).await
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::FindAll);
+        let mut conn = self.borrow_reader().await;
+        let mut entities = E::find_all(&mut conn, params).await?;
+        Self::retain_unexpired(&mut entities)?;
+        timer.entities = entities.len();
+        Self::verify_checksums(&mut conn, &entities).await?;
+        Ok(entities)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn find_many<E: Entity<ConnectionType = KeystoreDatabaseConnection>, S: AsRef<[u8]>>(
         &self,
         ids: &[S],
     ) -> CryptoKeystoreResult<Vec<E>> {
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::FindMany);
         let entity_ids: Vec<StringEntityId> = ids.iter().map(|id| id.as_ref().into()).collect();
-        let mut conn = self.conn.lock().await;
-        E::find_many(&mut conn, &entity_ids).await
+        let mut conn = self.borrow_reader().await;
+        let mut entities = E::find_many(&mut conn, &entity_ids).await?;
+        Self::retain_unexpired(&mut entities)?;
+        timer.entities = entities.len();
+        Self::verify_checksums(&mut conn, &entities).await?;
+        Ok(entities)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn remove<E: Entity<ConnectionType = KeystoreDatabaseConnection>, S: AsRef<[u8]>>(
         &self,
         id: S,
     ) -> CryptoKeystoreResult<()> {
-        let mut conn = self.conn.lock().await;
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::Remove);
+        timer.entities = 1;
+        let mut conn = self.borrow_writer().await?;
         E::delete(&mut conn, &[id.as_ref().into()]).await?;
+        Self::remove_checksum::<E>(&mut conn, id.as_ref()).await?;
         Ok(())
     }
 
+    /// Removes every entity belonging to the given conversation and returns how many there were
+    #[cfg(feature = "mls-keystore")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
+    pub async fn remove_all_by_conversation<
+        E: crate::entities::ConversationScopedEntity<ConnectionType = KeystoreDatabaseConnection>,
+    >(
+        &self,
+        conversation_id: impl AsRef<[u8]>,
+    ) -> CryptoKeystoreResult<usize> {
+        let mut timer = self.slow_log.start::<E>(KeystoreOperation::Remove);
+        let mut conn = self.borrow_writer().await?;
+        let ids = E::delete_all_by_conversation(&mut conn, conversation_id.as_ref()).await?;
+        for id in &ids {
+            Self::remove_checksum::<E>(&mut conn, id).await?;
+        }
+        timer.entities = ids.len();
+        Ok(ids.len())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all, fields(entity = std::any::type_name::<E>())))]
     pub async fn count<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(&self) -> CryptoKeystoreResult<usize> {
-        let mut conn = self.conn.lock().await;
+        let _timer = self.slow_log.start::<E>(KeystoreOperation::Count);
+        let mut conn = self.borrow_reader().await;
         E::count(&mut conn).await
     }
 
+    /// Bytes left unused by deleted entities, which [Self::vacuum] would give back to the filesystem. Always 0 on
+    /// WASM since IndexedDB compacts on its own
+    pub async fn reclaimable_space(&self) -> CryptoKeystoreResult<u64> {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let conn = self.borrow_reader().await;
+            let free_pages: u64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+            let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+            Ok(free_pages * page_size)
+        }
+        #[cfg(target_family = "wasm")]
+        Ok(0)
+    }
+
+    /// Rebuilds the database to give the space left unused by deleted entities back to the filesystem. It rewrites
+    /// the whole database hence should be scheduled when the app is idle. Cannot be done during a transaction. Does
+    /// nothing on WASM
+    pub async fn vacuum(&self) -> CryptoKeystoreResult<()> {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let conn = self.borrow_writer().await?;
+            conn.execute_batch("VACUUM")?;
+        }
+        Ok(())
+    }
+
     pub async fn wipe(self) -> CryptoKeystoreResult<()> {
+        // readers have to be closed before the WAL can be truncated and the database file removed
+        #[cfg(not(target_family = "wasm"))]
+        self.readers.close().await?;
         let conn: KeystoreDatabaseConnection = Arc::try_unwrap(self.conn).unwrap().into_inner();
 
         conn.wipe().await?;
+        if let Some(name) = self.wrapped_key_store {
+            key_provider::remove_wrapped_key(&name).await?;
+        }
+        if let Some(store_lock) = self.store_lock {
+            store_lock.release_wiped();
+        }
         Ok(())
     }
 
     pub async fn close(self) -> CryptoKeystoreResult<()> {
+        // last chance to write the groups which could not be persisted so far, unless another instance took the
+        // store over in the meantime
+        #[cfg(feature = "mls-keystore")]
+        if let Ok(mut conn) = self.borrow_writer().await {
+            self.flush_dirty_mls_groups(&mut conn).await;
+        }
+        #[cfg(not(target_family = "wasm"))]
+        self.readers.close().await?;
         let conn: KeystoreDatabaseConnection = Arc::try_unwrap(self.conn).unwrap().into_inner();
         conn.close().await?;
         Ok(())
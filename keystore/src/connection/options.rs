@@ -0,0 +1,65 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Options of a persistent store when opening it, see
+//! [Connection::open_with_options](super::Connection::open_with_options).
+//!
+//! Most of them are about iOS, which restricts the access to the files of an app while the device is locked depending
+//! on their data protection class, and kills a suspended app holding a lock on a file it doesn't recognize as a SQLite
+//! database. They are ignored on the other platforms.
+
+/// Data protection class of the files of a store on iOS, see `NSFileProtectionType`. The stricter it is, the longer
+/// the store can't be opened while the device is locked, which then fails with
+/// [CryptoKeystoreError::DeviceLocked](crate::CryptoKeystoreError::DeviceLocked)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileProtectionClass {
+    /// `NSFileProtectionComplete`: the files can't be read nor written while the device is locked
+    Complete,
+    /// `NSFileProtectionCompleteUnlessOpen`: the files opened while the device was unlocked remain accessible once it
+    /// is locked, but they can't be opened again until it is unlocked
+    CompleteUnlessOpen,
+    /// `NSFileProtectionCompleteUntilFirstUserAuthentication`: the files are accessible once the device has been
+    /// unlocked after booting
+    CompleteUntilFirstUserAuthentication,
+    /// `NSFileProtectionNone`: the files are always accessible
+    None,
+}
+
+/// Options of a persistent store when opening it, see [the module documentation](self)
+#[derive(Debug, Clone)]
+pub struct ConnectionOptions {
+    /// Opens the store even if another instance holds it, see
+    /// [Connection::take_over_with_key](super::Connection::take_over_with_key). Disabled by default
+    pub take_over: bool,
+    /// Data protection class set on the database file, its WAL and its shared memory file every time the store is
+    /// opened. When unset, they get the class of their directory. The keychain item holding the salt of the database
+    /// is made accessible accordingly. Only on iOS
+    pub file_protection: Option<FileProtectionClass>,
+    /// Moves the salt of the database into the keychain and leaves the header of the database in plaintext, so that
+    /// iOS recognizes the file and doesn't kill the app holding it while suspended. Only with the `ios-wal-compat`
+    /// feature, enabled by default then. A store created with it cannot be opened without it and conversely
+    pub wal_compat: bool,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            take_over: false,
+            file_protection: None,
+            wal_compat: true,
+        }
+    }
+}
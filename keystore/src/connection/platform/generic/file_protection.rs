@@ -0,0 +1,108 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! iOS data protection of the files of a store, see [ConnectionOptions::file_protection]. The class of a file is set
+//! with `fcntl(F_SETPROTECTIONCLASS)`, the system call behind `NSFileProtectionKey`, so that no Objective-C runtime is
+//! needed. Nothing is done on the other platforms.
+//!
+//! [ConnectionOptions::file_protection]: crate::connection::ConnectionOptions::file_protection
+
+use crate::{connection::FileProtectionClass, CryptoKeystoreError, CryptoKeystoreResult};
+
+/// `errSecInteractionNotAllowed`: the keychain item is not accessible while the device is locked
+#[cfg(feature = "ios-wal-compat")]
+const ERR_SEC_INTERACTION_NOT_ALLOWED: i32 = -25308;
+
+/// Sets the protection class of the database file at `path` along with its WAL and shared memory files, if any
+#[cfg(target_os = "ios")]
+pub(super) fn apply(path: &str, class: FileProtectionClass) -> CryptoKeystoreResult<()> {
+    use std::os::{fd::AsRawFd as _, raw::c_int};
+
+    extern "C" {
+        fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+    }
+    const F_SETPROTECTIONCLASS: c_int = 64;
+
+    // classes A to D of sys/fcntl.h
+    let class: c_int = match class {
+        FileProtectionClass::Complete => 1,
+        FileProtectionClass::CompleteUnlessOpen => 2,
+        FileProtectionClass::CompleteUntilFirstUserAuthentication => 3,
+        FileProtectionClass::None => 4,
+    };
+
+    let sidecars = ["-wal", "-shm"].map(|suffix| format!("{path}{suffix}"));
+    for (i, file) in std::iter::once(path.to_string()).chain(sidecars).enumerate() {
+        let f = match std::fs::File::open(&file) {
+            Ok(f) => f,
+            // the database file itself has to exist, the other ones are optional
+            Err(e) if i > 0 && e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if unsafe { fcntl(f.as_raw_fd(), F_SETPROTECTIONCLASS, class) } == -1 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "ios"))]
+pub(super) fn apply(_path: &str, _class: FileProtectionClass) -> CryptoKeystoreResult<()> {
+    Ok(())
+}
+
+/// Whether the keychain item holding the salt of a store has to be accessible only while the device is unlocked,
+/// as opposed to once it has been unlocked after booting
+#[cfg(feature = "ios-wal-compat")]
+pub(super) fn salt_accessible_when_unlocked(class: Option<FileProtectionClass>) -> bool {
+    matches!(
+        class,
+        Some(FileProtectionClass::Complete | FileProtectionClass::CompleteUnlessOpen)
+    )
+}
+
+/// Replaces the error of a store which failed to open with [CryptoKeystoreError::DeviceLocked] when it is caused by
+/// the device being locked: either the keychain item holding its salt or its database file cannot be read
+pub(super) fn map_device_locked(e: CryptoKeystoreError, path: &str) -> CryptoKeystoreError {
+    match e {
+        #[cfg(feature = "ios-wal-compat")]
+        CryptoKeystoreError::SecurityFrameworkError(e) if e.code() == ERR_SEC_INTERACTION_NOT_ALLOWED => {
+            CryptoKeystoreError::DeviceLocked
+        }
+        CryptoKeystoreError::DbError(rusqlite::Error::SqliteFailure(ref failure, _))
+            if matches!(
+                failure.code,
+                rusqlite::ErrorCode::CannotOpen | rusqlite::ErrorCode::SystemIoFailure
+            ) && is_protected_file(path) =>
+        {
+            CryptoKeystoreError::DeviceLocked
+        }
+        e => e,
+    }
+}
+
+/// Whether the file at `path` exists but can't be read because of its protection class
+#[cfg(target_os = "ios")]
+fn is_protected_file(path: &str) -> bool {
+    // EPERM, as opposed to the EACCES of a file the app doesn't own
+    matches!(std::fs::File::open(path), Err(e) if e.raw_os_error() == Some(1))
+}
+
+#[cfg(not(target_os = "ios"))]
+fn is_protected_file(_path: &str) -> bool {
+    false
+}
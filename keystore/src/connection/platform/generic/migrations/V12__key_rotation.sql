@@ -0,0 +1,5 @@
+CREATE TABLE mls_key_rotations (
+    id BLOB PRIMARY KEY,
+    span INTEGER NOT NULL,
+    last_update INTEGER NOT NULL
+);
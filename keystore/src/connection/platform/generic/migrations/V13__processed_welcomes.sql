@@ -0,0 +1,4 @@
+CREATE TABLE mls_processed_welcomes (
+    id BLOB PRIMARY KEY,
+    conversation_id BLOB NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE mls_groups_index (
+    id BLOB PRIMARY KEY,
+    ciphersuite INTEGER NOT NULL,
+    epoch INTEGER NOT NULL,
+    member_count INTEGER NOT NULL,
+    last_persisted INTEGER NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE mls_seen_credentials (
+    id BLOB PRIMARY KEY,
+    conversation_id BLOB NOT NULL,
+    client_id BLOB NOT NULL,
+    credential BLOB NOT NULL,
+    first_seen INTEGER NOT NULL
+);
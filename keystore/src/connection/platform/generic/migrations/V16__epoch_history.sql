@@ -0,0 +1,8 @@
+CREATE TABLE mls_epoch_history (
+    id BLOB PRIMARY KEY,
+    conversation_id BLOB NOT NULL,
+    epoch INTEGER NOT NULL,
+    authenticator_hash BLOB NOT NULL,
+    timestamp INTEGER NOT NULL,
+    commit_sender BLOB
+);
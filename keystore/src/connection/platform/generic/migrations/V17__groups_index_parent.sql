@@ -0,0 +1 @@
+ALTER TABLE mls_groups_index ADD COLUMN parent_id BLOB;
@@ -0,0 +1,9 @@
+CREATE TABLE mls_conversation_counters (
+    id BLOB PRIMARY KEY,
+    messages_sent INTEGER NOT NULL,
+    messages_received INTEGER NOT NULL,
+    commits_sent INTEGER NOT NULL,
+    commits_received INTEGER NOT NULL,
+    last_activity_epoch INTEGER NOT NULL,
+    last_activity INTEGER NOT NULL
+);
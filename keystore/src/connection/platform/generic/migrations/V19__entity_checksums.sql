@@ -0,0 +1,5 @@
+CREATE TABLE entity_checksums (
+    id BLOB PRIMARY KEY,
+    checksum BLOB NOT NULL,
+    previous_checksum BLOB
+);
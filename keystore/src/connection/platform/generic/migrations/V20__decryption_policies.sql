@@ -0,0 +1,4 @@
+CREATE TABLE mls_decryption_policies (
+    id BLOB PRIMARY KEY,
+    max_application_message_epoch_age INTEGER NOT NULL
+);
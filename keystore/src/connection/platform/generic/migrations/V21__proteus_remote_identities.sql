@@ -0,0 +1,4 @@
+CREATE TABLE proteus_remote_identities (
+    id VARCHAR(255) UNIQUE,
+    fingerprint VARCHAR(255) NOT NULL
+);
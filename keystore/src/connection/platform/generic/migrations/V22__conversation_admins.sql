@@ -0,0 +1,4 @@
+CREATE TABLE mls_conversation_admins (
+    id BLOB PRIMARY KEY,
+    admins BLOB NOT NULL
+);
@@ -0,0 +1,7 @@
+ALTER TABLE mls_pending_groups ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE mls_pending_messages ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE e2ei_enrollment ADD COLUMN created_at INTEGER NOT NULL DEFAULT 0;
+-- the age of existing entities is unknown, their TTL runs from the upgrade
+UPDATE mls_pending_groups SET created_at = unixepoch();
+UPDATE mls_pending_messages SET created_at = unixepoch();
+UPDATE e2ei_enrollment SET created_at = unixepoch();
@@ -0,0 +1,4 @@
+CREATE TABLE mls_epoch_fence_policies (
+    id BLOB PRIMARY KEY,
+    fence INTEGER NOT NULL
+);
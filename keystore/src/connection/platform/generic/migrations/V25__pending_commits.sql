@@ -0,0 +1,6 @@
+CREATE TABLE mls_pending_commits (
+    id BLOB PRIMARY KEY,
+    operation_key BLOB NOT NULL,
+    confirmation_tag BLOB NOT NULL,
+    bundle BLOB NOT NULL
+);
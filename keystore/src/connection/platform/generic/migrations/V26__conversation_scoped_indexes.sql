@@ -0,0 +1,4 @@
+CREATE INDEX mls_processed_welcomes_conversation_id ON mls_processed_welcomes (conversation_id);
+CREATE INDEX mls_seen_credentials_conversation_id ON mls_seen_credentials (conversation_id);
+CREATE INDEX mls_pending_messages_id ON mls_pending_messages (id);
+CREATE INDEX mls_epoch_encryption_keypairs_id ON mls_epoch_encryption_keypairs (id);
@@ -0,0 +1,5 @@
+CREATE TABLE mls_pending_rotations (
+    id BLOB PRIMARY KEY,
+    conversation_ids BLOB NOT NULL,
+    key_package_refs BLOB NOT NULL
+);
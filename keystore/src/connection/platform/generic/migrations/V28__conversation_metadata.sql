@@ -0,0 +1,35 @@
+CREATE TABLE mls_conversation_metadata (
+    id BLOB PRIMARY KEY,
+    key_rotation_span INTEGER NOT NULL,
+    last_key_rotation INTEGER NOT NULL,
+    max_application_message_epoch_age INTEGER,
+    epoch_fence INTEGER NOT NULL
+);
+-- the settings of a conversation were spread over one table each, a conversation missing from one of them has the
+-- default value of that setting
+INSERT INTO mls_conversation_metadata (
+    id,
+    key_rotation_span,
+    last_key_rotation,
+    max_application_message_epoch_age,
+    epoch_fence
+)
+SELECT
+    ids.id,
+    COALESCE(rotation.span, 0),
+    COALESCE(rotation.last_update, 0),
+    policy.max_application_message_epoch_age,
+    COALESCE(fence.fence, 0)
+FROM (
+    SELECT id FROM mls_key_rotations
+    UNION SELECT id FROM mls_decryption_policies
+    UNION SELECT id FROM mls_epoch_fence_policies
+) AS ids
+LEFT JOIN mls_key_rotations AS rotation ON rotation.id = ids.id
+LEFT JOIN mls_decryption_policies AS policy ON policy.id = ids.id
+LEFT JOIN mls_epoch_fence_policies AS fence ON fence.id = ids.id;
+DROP TABLE mls_key_rotations;
+DROP TABLE mls_decryption_policies;
+DROP TABLE mls_epoch_fence_policies;
+-- the admins are now shared with the other members in the group context of the conversation
+DROP TABLE mls_conversation_admins;
@@ -14,16 +14,91 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
-use crate::connection::{DatabaseConnection, DatabaseConnectionRequirements};
-use crate::CryptoKeystoreResult;
+use crate::connection::{ConnectionOptions, DatabaseConnection, DatabaseConnectionRequirements};
+use crate::{CryptoKeystoreError, CryptoKeystoreResult};
 use blocking::unblock;
 
+mod file_protection;
+mod read_pool;
+pub use read_pool::{ReadPool, DEFAULT_READ_POOL_SIZE};
+mod unencrypted;
+
 refinery::embed_migrations!("src/connection/platform/generic/migrations");
 
+#[cfg(feature = "ios-wal-compat")]
+const LEGACY_ACCT_NAME: &str = "keystore_salt";
+#[cfg(feature = "ios-wal-compat")]
+const WIRE_SERVICE_NAME: &str = "wire.com";
+#[cfg(feature = "ios-wal-compat")]
+const CIPHER_PLAINTEXT_BYTES: u32 = 32;
+
+/// Maximum number of ids bound to a single `IN (...)` lookup, safely below SQLite's host parameter limit
+const FIND_MANY_BATCH_SIZE: usize = 500;
+
 #[derive(Debug)]
 pub struct SqlCipherConnection {
     conn: rusqlite::Connection,
     path: String,
+    /// Whether an explicit transaction spanning many operations is in progress, see [Self::begin_transaction]
+    in_transaction: bool,
+}
+
+/// Transaction used by entities to group their statements. Within an explicit transaction (see
+/// [SqlCipherConnection::begin_transaction]) it is a savepoint, so that its changes are only persisted when the
+/// outer transaction commits
+pub enum EntityTransaction<'conn> {
+    Transaction(rusqlite::Transaction<'conn>),
+    Savepoint(rusqlite::Savepoint<'conn>),
+}
+
+impl std::ops::Deref for EntityTransaction<'_> {
+    type Target = rusqlite::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Transaction(transaction) => transaction,
+            Self::Savepoint(savepoint) => savepoint,
+        }
+    }
+}
+
+impl EntityTransaction<'_> {
+    pub fn commit(self) -> rusqlite::Result<()> {
+        match self {
+            Self::Transaction(transaction) => transaction.commit(),
+            Self::Savepoint(savepoint) => savepoint.commit(),
+        }
+    }
+
+    pub fn rollback(self) -> rusqlite::Result<()> {
+        match self {
+            Self::Transaction(transaction) => transaction.rollback(),
+            // rolls back then releases the savepoint
+            Self::Savepoint(savepoint) => savepoint.finish(),
+        }
+    }
+
+    /// Looks up the rowids of the rows of `table` whose `id_column` is one of `ids`, with a single query per batch
+    /// of ids. Ids which aren't in the table are skipped
+    pub fn find_rowids<P: rusqlite::ToSql>(
+        &self,
+        table: &str,
+        id_column: &str,
+        ids: &[P],
+    ) -> rusqlite::Result<Vec<i64>> {
+        let mut rowids = Vec::with_capacity(ids.len());
+        for batch in ids.chunks(FIND_MANY_BATCH_SIZE) {
+            let placeholders = vec!["?"; batch.len()].join(", ");
+            let query = format!("SELECT rowid FROM {table} WHERE {id_column} IN ({placeholders}) ORDER BY rowid ASC");
+            let mut stmt = self.prepare(&query)?;
+            let batch_rowids = stmt.query_map(rusqlite::params_from_iter(batch), |r| r.get::<_, i64>(0))?;
+            for rowid in batch_rowids {
+                rowids.push(rowid?);
+            }
+        }
+
+        Ok(rowids)
+    }
 }
 
 unsafe impl Send for SqlCipherConnection {}
@@ -45,7 +120,12 @@ impl std::ops::DerefMut for SqlCipherConnection {
 
 impl SqlCipherConnection {
     #[allow(unused_mut)]
-    fn init_with_connection(mut conn: rusqlite::Connection, path: &str, key: &str) -> CryptoKeystoreResult<Self> {
+    fn init_with_connection(
+        mut conn: rusqlite::Connection,
+        path: &str,
+        key: &str,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
         cfg_if::cfg_if! {
             if #[cfg(feature = "log-queries")] {
                 fn log_query(q: &str) {
@@ -60,7 +140,9 @@ impl SqlCipherConnection {
 
         // ? iOS WAL journaling fix; see details here: https://github.com/sqlcipher/sqlcipher/issues/255
         #[cfg(feature = "ios-wal-compat")]
-        Self::handle_ios_wal_compat(&conn, path)?;
+        if options.wal_compat {
+            Self::handle_ios_wal_compat(&conn, path, options.file_protection)?;
+        }
 
         // Enable WAL journaling mode
         conn.pragma_update(None, "journal_mode", "wal")?;
@@ -71,22 +153,142 @@ impl SqlCipherConnection {
         let mut conn = Self {
             path: path.into(),
             conn,
+            in_transaction: false,
         };
         conn.run_migrations()?;
 
+        // the WAL and shared memory files exist once the migrations ran
+        if let Some(class) = options.file_protection.filter(|_| !path.is_empty()) {
+            file_protection::apply(path, class)?;
+        }
+
         Ok(conn)
     }
 
-    fn init_with_key(path: &str, key: &str) -> CryptoKeystoreResult<Self> {
-        let conn = rusqlite::Connection::open(path)?;
-        Self::init_with_connection(conn, path, key)
+    fn init_with_key(path: &str, key: &str, options: &ConnectionOptions) -> CryptoKeystoreResult<Self> {
+        rusqlite::Connection::open(path)
+            .map_err(CryptoKeystoreError::from)
+            .and_then(|conn| Self::init_with_connection(conn, path, key, options))
+            .map_err(|e| file_protection::map_device_locked(e, path))
     }
 
     fn init_with_key_in_memory(_path: &str, key: &str) -> CryptoKeystoreResult<Self> {
         let conn = rusqlite::Connection::open_in_memory()?;
-        Self::init_with_connection(conn, "", key)
+        Self::init_with_connection(conn, "", key, &ConnectionOptions::default())
+    }
+
+    #[cfg_attr(not(feature = "ios-wal-compat"), allow(unused_variables))]
+    fn init_read_only_with_key(path: &str, key: &str, options: &ConnectionOptions) -> CryptoKeystoreResult<Self> {
+        use rusqlite::OpenFlags;
+        let conn = rusqlite::Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+        )
+        .map_err(|e| file_protection::map_device_locked(e.into(), path))?;
+
+        conn.pragma_update(None, "key", key)?;
+
+        #[cfg(feature = "ios-wal-compat")]
+        if options.wal_compat {
+            Self::handle_ios_wal_compat_read_only(&conn, path)
+                .map_err(|e| file_protection::map_device_locked(e, path))?;
+        }
+
+        // Belt and braces: this connection must never be used to write
+        conn.pragma_update(None, "query_only", true)?;
+
+        Ok(Self {
+            path: path.into(),
+            conn,
+            in_transaction: false,
+        })
+    }
+
+    /// Opens the database `name`, creating it and running the migrations of its schema if needed
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::DeviceLocked] when it can't be accessed until the device is unlocked
+    pub(crate) async fn open_with_options(
+        name: &str,
+        key: &str,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        let name = name.to_string();
+        let key = key.to_string();
+        let options = options.clone();
+        unblock(move || Self::init_with_key(&name, &key, &options)).await
+    }
+
+    /// Opens a read-only connection to an existing database. Its schema must have been migrated beforehand
+    /// by a read-write connection
+    pub(crate) async fn open_read_only(
+        name: &str,
+        key: &str,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        let name = name.to_string();
+        let key = key.to_string();
+        let options = options.clone();
+        unblock(move || Self::init_read_only_with_key(&name, &key, &options)).await
     }
 
+    /// Starts a transaction for entities' statements. Shadows [rusqlite::Connection::transaction] so that entities
+    /// transparently take part in an explicit transaction when there's one in progress
+    pub fn transaction(&mut self) -> rusqlite::Result<EntityTransaction<'_>> {
+        if self.in_transaction {
+            Ok(EntityTransaction::Savepoint(self.conn.savepoint()?))
+        } else {
+            Ok(EntityTransaction::Transaction(self.conn.transaction()?))
+        }
+    }
+
+    /// Whether an explicit transaction is in progress
+    pub fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    /// Starts an explicit transaction: nothing written from now on is persisted until [Self::commit_transaction]
+    /// is called and everything is discarded by [Self::rollback_transaction]
+    pub fn begin_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        if self.in_transaction {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        self.in_transaction = true;
+        Ok(())
+    }
+
+    /// Persists everything written since [Self::begin_transaction]. When it fails, the transaction is rolled back
+    pub fn commit_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        if !self.in_transaction {
+            return Err(CryptoKeystoreError::NoTransactionInProgress);
+        }
+        self.in_transaction = false;
+        if let Err(e) = self.conn.execute_batch("COMMIT") {
+            // SQLite only rolls back by itself on some errors, e.g. it keeps the transaction open on SQLITE_BUSY
+            if !self.conn.is_autocommit() {
+                self.conn.execute_batch("ROLLBACK")?;
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Discards everything written since [Self::begin_transaction]
+    pub fn rollback_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        if !self.in_transaction {
+            return Err(CryptoKeystoreError::NoTransactionInProgress);
+        }
+        self.conn.execute_batch("ROLLBACK")?;
+        self.in_transaction = false;
+        Ok(())
+    }
+
+    /// Destroys the database in a way that its content cannot be recovered from disk afterwards: the content is
+    /// deleted with `secure_delete` enabled, the WAL is truncated, then every file of the database is overwritten
+    /// before being removed.
+    ///
+    /// Note that on storage doing wear leveling or copy-on-write, overwritten blocks might still physically exist
     pub async fn wipe(self) -> CryptoKeystoreResult<()> {
         if self.path.is_empty() {
             return Ok(());
@@ -94,8 +296,76 @@ impl SqlCipherConnection {
 
         let path = self.path.clone();
 
-        unblock(|| self.close()).await?;
-        async_fs::remove_file(&path).await?;
+        unblock(move || {
+            self.shred()?;
+            self.close()?;
+            Self::shred_files(&path)
+        })
+        .await
+    }
+
+    /// Deletes everything in the database, overwriting the freed pages, then moves the WAL content back into the
+    /// main file and truncates it. The read-only connections have to be closed beforehand, see [ReadPool::close]:
+    /// the WAL can't be truncated while they read from it
+    fn shred(&self) -> CryptoKeystoreResult<()> {
+        if self.in_transaction {
+            self.conn.execute_batch("ROLLBACK")?;
+        }
+
+        // deleted content is overwritten with zeroes instead of being merely marked as free
+        self.conn.pragma_update(None, "secure_delete", true)?;
+
+        let tables = self
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for table in tables {
+            self.conn.execute(&format!("DELETE FROM \"{table}\""), [])?;
+        }
+
+        // gets rid of the freelist pages
+        self.conn.execute_batch("VACUUM")?;
+        // the first column tells whether the checkpoint could not complete, in which case the WAL still holds the
+        // deleted content
+        let busy: i64 = self
+            .conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))?;
+        if busy != 0 {
+            return Err(CryptoKeystoreError::StoreBusy);
+        }
+
+        Ok(())
+    }
+
+    /// Overwrites with zeroes then removes the database file along with its WAL, shared memory & journal files
+    fn shred_files(path: &str) -> CryptoKeystoreResult<()> {
+        use std::io::Write as _;
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let zeroes = [0u8; CHUNK_SIZE];
+        let sidecars = ["-wal", "-shm", "-journal"].map(|suffix| format!("{path}{suffix}"));
+        for (i, file) in std::iter::once(path.to_string()).chain(sidecars).enumerate() {
+            let len = match std::fs::metadata(&file) {
+                Ok(metadata) => metadata.len(),
+                // the database file itself has to exist, the other ones are optional
+                Err(e) if i == 0 => return Err(e.into()),
+                Err(_) => continue,
+            };
+
+            let mut f = std::fs::OpenOptions::new().write(true).open(&file)?;
+            let mut left = len;
+            while left > 0 {
+                let n = left.min(CHUNK_SIZE as u64) as usize;
+                f.write_all(&zeroes[..n])?;
+                left -= n as u64;
+            }
+            f.sync_all()?;
+            drop(f);
+
+            std::fs::remove_file(&file)?;
+        }
+
         Ok(())
     }
 
@@ -109,20 +379,24 @@ impl SqlCipherConnection {
     /// and when it does so, it treats this file "specially" and avoids killing the app
     /// when doing background work
     /// See more: https://github.com/sqlcipher/sqlcipher/issues/255
+    /// The keychain item holding the salt is made accessible according to the protection class of the files, see
+    /// [crate::connection::ConnectionOptions::file_protection]
     #[cfg(feature = "ios-wal-compat")]
-    fn handle_ios_wal_compat(conn: &rusqlite::Connection, path: &str) -> CryptoKeystoreResult<()> {
+    fn handle_ios_wal_compat(
+        conn: &rusqlite::Connection,
+        path: &str,
+        file_protection: Option<crate::connection::FileProtectionClass>,
+    ) -> CryptoKeystoreResult<()> {
         const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
-        const LEGACY_ACCT_NAME: &str = "keystore_salt";
-        const WIRE_SERVICE_NAME: &str = "wire.com";
         use security_framework::passwords as ios_keychain;
-        use sha2::Digest as _;
 
         #[allow(non_upper_case_globals)]
         // This is to make sure that macOS/iOS keychain items that we create (see above for the *why*)
-        // are accessible in the background through a `kSecAttrAccessibleAfterFirstUnlock` attribute
+        // are accessible in the background through a `kSecAttrAccessibleAfterFirstUnlock` attribute,
+        // or only while the device is unlocked (`kSecAttrAccessibleWhenUnlocked`) when the files are as well
         // More on the topic: https://developer.apple.com/documentation/security/keychain_services/keychain_items/restricting_keychain_item_accessibility
         // More here on the specific attribute: https://developer.apple.com/documentation/security/ksecattraccessibleafterfirstunlock?language=swift
-        fn mark_password_as_accessible(key: &str) -> security_framework::base::Result<()> {
+        fn mark_password_as_accessible(key: &str, when_unlocked: bool) -> security_framework::base::Result<()> {
             use core_foundation::{
                 base::TCFType,
                 dictionary::CFDictionary,
@@ -137,8 +411,14 @@ impl SqlCipherConnection {
             // Import raw symbols from CoreFoundation
             extern "C" {
                 pub static kSecAttrAccessibleAfterFirstUnlock: CFStringRef;
+                pub static kSecAttrAccessibleWhenUnlocked: CFStringRef;
                 pub static kSecAttrAccessible: CFStringRef;
             }
+            let accessibility = if when_unlocked {
+                unsafe { kSecAttrAccessibleWhenUnlocked }
+            } else {
+                unsafe { kSecAttrAccessibleAfterFirstUnlock }
+            };
 
             // Create a query that matches a:
             let query_params = CFDictionary::from_CFType_pairs(&[
@@ -163,8 +443,9 @@ impl SqlCipherConnection {
                 // Keychain Accessibility setting
                 // See: https://developer.apple.com/documentation/security/ksecattraccessible
                 unsafe { CFString::wrap_under_get_rule(kSecAttrAccessible) },
-                // Set to AccessibleAfterFirstUnlock (i.e. is accessible after the first post-boot unlock)
-                unsafe { CFString::wrap_under_get_rule(kSecAttrAccessibleAfterFirstUnlock).as_CFType() },
+                // Set to AccessibleAfterFirstUnlock (i.e. is accessible after the first post-boot unlock) or
+                // AccessibleWhenUnlocked
+                unsafe { CFString::wrap_under_get_rule(accessibility).as_CFType() },
             )]);
 
             // Update the item in the keychain
@@ -179,9 +460,7 @@ impl SqlCipherConnection {
             }
         }
 
-        let mut path_hash = sha2::Sha256::default();
-        path_hash.update(path.as_bytes());
-        let keychain_key = format!("{LEGACY_ACCT_NAME}_{}", hex::encode(&path_hash.finalize()));
+        let keychain_key = Self::ios_keychain_key(path);
 
         // Old version compat fix
         if let Ok(salt) = ios_keychain::get_generic_password(WIRE_SERVICE_NAME, LEGACY_ACCT_NAME) {
@@ -205,15 +484,39 @@ impl SqlCipherConnection {
         }
 
         // We're doing it here to make sure we retroactively mark database salts as accessible
-        mark_password_as_accessible(&keychain_key)?;
+        mark_password_as_accessible(
+            &keychain_key,
+            file_protection::salt_accessible_when_unlocked(file_protection),
+        )?;
 
-        const CIPHER_PLAINTEXT_BYTES: u32 = 32;
         conn.pragma_update(None, "cipher_plaintext_header_size", CIPHER_PLAINTEXT_BYTES)?;
         conn.pragma_update(None, "user_version", 2u32)?;
 
         Ok(())
     }
 
+    /// Read-only counterpart of [Self::handle_ios_wal_compat]: the salt has already been stored in the keychain
+    /// by the read-write connection so we only have to apply it
+    #[cfg(feature = "ios-wal-compat")]
+    fn handle_ios_wal_compat_read_only(conn: &rusqlite::Connection, path: &str) -> CryptoKeystoreResult<()> {
+        use security_framework::passwords as ios_keychain;
+
+        let salt = ios_keychain::get_generic_password(WIRE_SERVICE_NAME, &Self::ios_keychain_key(path))?;
+        conn.pragma_update(None, "cipher_salt", format!("x'{}'", hex::encode(salt)))?;
+        conn.pragma_update(None, "cipher_plaintext_header_size", CIPHER_PLAINTEXT_BYTES)?;
+
+        Ok(())
+    }
+
+    /// Name of the keychain item holding the salt of the database located at `path`
+    #[cfg(feature = "ios-wal-compat")]
+    fn ios_keychain_key(path: &str) -> String {
+        use sha2::Digest as _;
+        let mut path_hash = sha2::Sha256::default();
+        path_hash.update(path.as_bytes());
+        format!("{LEGACY_ACCT_NAME}_{}", hex::encode(path_hash.finalize()))
+    }
+
     fn run_migrations(&mut self) -> CryptoKeystoreResult<()> {
         let report = migrations::runner().run(&mut self.conn).map_err(Box::new)?;
         if let Some(version) = report.applied_migrations().iter().map(|m| m.version()).max() {
@@ -230,9 +533,7 @@ impl DatabaseConnectionRequirements for SqlCipherConnection {}
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
 impl DatabaseConnection for SqlCipherConnection {
     async fn open(name: &str, key: &str) -> CryptoKeystoreResult<Self> {
-        let name = name.to_string();
-        let key = key.to_string();
-        Ok(unblock(move || Self::init_with_key(&name, &key)).await?)
+        Self::open_with_options(name, key, &ConnectionOptions::default()).await
     }
 
     async fn open_in_memory(name: &str, key: &str) -> CryptoKeystoreResult<Self> {
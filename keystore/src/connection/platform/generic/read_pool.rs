@@ -0,0 +1,83 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use super::SqlCipherConnection;
+use crate::connection::{ConnectionOptions, DatabaseConnection};
+use crate::CryptoKeystoreResult;
+use async_lock::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default number of read-only connections opened alongside the read-write one
+pub const DEFAULT_READ_POOL_SIZE: usize = 4;
+
+/// Pool of read-only connections to the same database as the read-write one.
+/// Since the database is WAL-journaled, readers do not block each other nor the writer, so lookups
+/// (`find`, `find_all`, `count`...) can run concurrently while writes stay serialized on the main connection.
+///
+/// An empty pool (e.g. for in-memory databases, which cannot be shared across connections) makes every
+/// read fall back on the read-write connection.
+#[derive(Debug, Default)]
+pub struct ReadPool {
+    readers: Vec<Mutex<SqlCipherConnection>>,
+    next: AtomicUsize,
+}
+
+impl ReadPool {
+    /// Opens `size` read-only connections to the database located at `path`
+    pub(crate) async fn open(
+        path: &str,
+        key: &str,
+        size: usize,
+        options: &ConnectionOptions,
+    ) -> CryptoKeystoreResult<Self> {
+        let mut readers = Vec::with_capacity(size);
+        for _ in 0..size {
+            readers.push(SqlCipherConnection::open_read_only(path, key, options).await?.into());
+        }
+
+        Ok(Self {
+            readers,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Number of read-only connections in the pool
+    pub fn size(&self) -> usize {
+        self.readers.len()
+    }
+
+    /// Acquires an idle reader if there's one, otherwise waits on the readers in a round-robin fashion.
+    /// Returns `None` when the pool is empty
+    pub(crate) async fn acquire(&self) -> Option<MutexGuard<'_, SqlCipherConnection>> {
+        if self.readers.is_empty() {
+            return None;
+        }
+
+        if let Some(reader) = self.readers.iter().find_map(Mutex::try_lock) {
+            return Some(reader);
+        }
+
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        Some(self.readers[i].lock().await)
+    }
+
+    pub(crate) async fn close(self) -> CryptoKeystoreResult<()> {
+        for reader in self.readers {
+            DatabaseConnection::close(reader.into_inner()).await?;
+        }
+        Ok(())
+    }
+}
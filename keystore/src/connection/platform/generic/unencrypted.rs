@@ -0,0 +1,128 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Very old installs created their keystore before it was encrypted with SQLCipher. Such a store is a plain SQLite
+//! database which [SqlCipherConnection::migrate_unencrypted] copies into an encrypted one, every table and row
+//! included. The copy only replaces the original once its row counts have been checked, then the original is shredded
+//! since it holds the keying material in plaintext.
+//!
+//! The files are swapped with renames so that an interrupted migration is either resumed or rolled back on the next
+//! attempt: `<path>.encrypted` is the copy being written, `<path>.unencrypted` the original set aside.
+
+use super::SqlCipherConnection;
+use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+use blocking::unblock;
+
+/// Table created by the migrations of every keystore, encrypted or not
+const SCHEMA_HISTORY_TABLE: &str = "refinery_schema_history";
+
+impl SqlCipherConnection {
+    /// Encrypts the keystore at `path` with `key` if it is an unencrypted one, see [the module documentation](self).
+    /// Returns whether it has been migrated: a store which is already encrypted, or doesn't exist, is left untouched.
+    /// The migrations of the schema are run afterwards when opening the store as usual
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::UnencryptedMigrationError] when the unencrypted database is not a keystore or its copy
+    /// doesn't have the same rows, in which case the original is left as it was
+    pub(crate) async fn migrate_unencrypted(path: &str, key: &str) -> CryptoKeystoreResult<bool> {
+        let path = path.to_string();
+        let key = key.to_string();
+        unblock(move || Self::migrate_unencrypted_sync(&path, &key)).await
+    }
+
+    fn migrate_unencrypted_sync(path: &str, key: &str) -> CryptoKeystoreResult<bool> {
+        let encrypted_path = format!("{path}.encrypted");
+        let unencrypted_path = format!("{path}.unencrypted");
+        let exists = |p: &str| std::path::Path::new(p).exists();
+
+        // an interrupted migration
+        if exists(&unencrypted_path) {
+            if exists(path) {
+                // the encrypted copy already replaced the original, which only had to be shredded
+                Self::shred_files(&unencrypted_path)?;
+                return Ok(true);
+            }
+            std::fs::rename(&unencrypted_path, path)?;
+        }
+        if exists(&encrypted_path) {
+            Self::shred_files(&encrypted_path)?;
+        }
+        if !exists(path) {
+            return Ok(false);
+        }
+
+        let conn = rusqlite::Connection::open(path)?;
+        let tables = match Self::tables(&conn) {
+            Ok(tables) => tables,
+            // an encrypted database cannot even be read without its key
+            Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::NotADatabase => {
+                return Ok(false)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if !tables.iter().any(|table| table == SCHEMA_HISTORY_TABLE) {
+            return Err(CryptoKeystoreError::UnencryptedMigrationError(
+                "the database is not a keystore".to_string(),
+            ));
+        }
+
+        conn.execute("ATTACH DATABASE ?1 AS encrypted KEY ?2", [encrypted_path.as_str(), key])?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        let mismatch = Self::row_count_mismatch(&conn, &tables)?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+        conn.close().map_err(|(_, e)| e)?;
+        if let Some(mismatch) = mismatch {
+            Self::shred_files(&encrypted_path)?;
+            return Err(CryptoKeystoreError::UnencryptedMigrationError(mismatch));
+        }
+
+        std::fs::rename(path, &unencrypted_path)?;
+        std::fs::rename(&encrypted_path, path)?;
+        // the salt of the original database, if any, would prevent the copy from being opened
+        #[cfg(feature = "ios-wal-compat")]
+        let _ = security_framework::passwords::delete_generic_password(
+            super::WIRE_SERVICE_NAME,
+            &Self::ios_keychain_key(path),
+        );
+        Self::shred_files(&unencrypted_path)?;
+
+        Ok(true)
+    }
+
+    fn tables(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<String>> {
+        let tables = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tables)
+    }
+
+    /// Describes the first table whose copy in the attached `encrypted` database doesn't have as many rows
+    fn row_count_mismatch(conn: &rusqlite::Connection, tables: &[String]) -> rusqlite::Result<Option<String>> {
+        for table in tables {
+            let count = |schema: &str| {
+                conn.query_row(&format!("SELECT COUNT(*) FROM {schema}.\"{table}\""), [], |row| {
+                    row.get::<_, u64>(0)
+                })
+            };
+            let (expected, copied) = (count("main")?, count("encrypted")?);
+            if expected != copied {
+                return Ok(Some(format!("{copied} rows of {table} copied out of {expected}")));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,92 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Browsers may evict the IndexedDB data of an origin under storage pressure unless it has been granted persistent
+//! storage, which is requested when a keystore is created. Each keystore records the names of its object stores in
+//! [METADATA_STORE] so that opening one which lost some of them fails with [CryptoKeystoreError::StoreEvicted],
+//! instead of every later read failing on a missing object store.
+
+use rexie::TransactionMode;
+use wasm_bindgen::JsValue;
+
+use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+
+/// Object store holding the [KeystoreMetadata] of the store
+pub(super) const METADATA_STORE: &str = "keystore_metadata";
+const METADATA_KEY: &str = "keystore";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct KeystoreMetadata {
+    /// Names of the object stores of the database the last time it was opened
+    object_stores: Vec<String>,
+}
+
+/// Fails with [CryptoKeystoreError::StoreEvicted] when some of the object stores recorded in the metadata of the
+/// store are missing. Otherwise records its current object stores, requesting persistent storage first when there
+/// was no metadata yet, i.e. the store has just been created or predates it
+pub(super) async fn ensure_not_evicted(rexie: &rexie::Rexie) -> CryptoKeystoreResult<()> {
+    let object_stores = rexie.store_names();
+    // always declared, hence created along with the database or when upgrading it
+    if !object_stores.iter().any(|name| name == METADATA_STORE) {
+        return Err(CryptoKeystoreError::StoreEvicted);
+    }
+
+    let metadata = {
+        let transaction = rexie.transaction(&[METADATA_STORE], TransactionMode::ReadOnly)?;
+        let store = transaction.store(METADATA_STORE)?;
+        store.get(&JsValue::from(METADATA_KEY)).await?
+    };
+    match metadata {
+        Some(metadata) => {
+            let metadata: KeystoreMetadata = serde_wasm_bindgen::from_value(metadata)?;
+            if metadata.object_stores.iter().any(|name| !object_stores.contains(name)) {
+                return Err(CryptoKeystoreError::StoreEvicted);
+            }
+            if metadata.object_stores.len() == object_stores.len() {
+                return Ok(());
+            }
+        }
+        None => {
+            // best effort: the browser may deny it, or not support it e.g. in a worker
+            let _ = request_persistence().await;
+        }
+    }
+
+    let metadata = serde_wasm_bindgen::to_value(&KeystoreMetadata { object_stores })?;
+    let transaction = rexie.transaction(&[METADATA_STORE], TransactionMode::ReadWrite)?;
+    let store = transaction.store(METADATA_STORE)?;
+    store.put(&metadata, Some(&JsValue::from(METADATA_KEY))).await?;
+
+    Ok(())
+}
+
+/// Requests persistent storage for the origin with the [StorageManager API](https://developer.mozilla.org/en-US/docs/Web/API/StorageManager/persist),
+/// so that its data is not evicted under storage pressure. Returns whether it is granted, or [None] when the runtime
+/// does not provide it
+async fn request_persistence() -> Option<bool> {
+    use wasm_bindgen::JsCast as _;
+
+    let navigator = js_sys::Reflect::get(&js_sys::global(), &"navigator".into()).ok()?;
+    let storage = js_sys::Reflect::get(&navigator, &"storage".into()).ok()?;
+    let persist = js_sys::Reflect::get(&storage, &"persist".into())
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    let promise = persist.call0(&storage).ok()?.dyn_into::<js_sys::Promise>().ok()?;
+    let persisted = wasm_bindgen_futures::JsFuture::from(promise).await.ok()?;
+
+    persisted.as_bool()
+}
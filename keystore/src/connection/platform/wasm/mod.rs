@@ -20,6 +20,7 @@ use crate::{
 };
 use rexie::{Index, ObjectStore};
 
+mod eviction;
 pub mod storage;
 use self::storage::{WasmEncryptedStorage, WasmStorageWrapper};
 
@@ -37,6 +38,11 @@ impl WasmConnection {
     pub fn storage_mut(&mut self) -> &mut WasmEncryptedStorage {
         &mut self.conn
     }
+
+    /// Whether a transaction is in progress
+    pub fn in_transaction(&self) -> bool {
+        self.conn.in_transaction()
+    }
 }
 
 impl DatabaseConnectionRequirements for WasmConnection {}
@@ -149,6 +155,51 @@ impl DatabaseConnection for WasmConnection {
                     .auto_increment(false)
                     .add_index(Index::new("id", "id")),
             )
+            .add_object_store(
+                ObjectStore::new("mls_conversation_metadata")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_pending_commits")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_pending_rotations")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_conversation_counters")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("entity_checksums")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_processed_welcomes")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_seen_credentials")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_epoch_history")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_groups_index")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
             .add_object_store(
                 ObjectStore::new("e2ei_enrollment")
                     .auto_increment(false)
@@ -188,12 +239,22 @@ impl DatabaseConnection for WasmConnection {
                 ObjectStore::new("proteus_sessions")
                     .auto_increment(false)
                     .add_index(Index::new("id", "id").unique(true)),
-            );
+            )
+            .add_object_store(
+                ObjectStore::new("proteus_remote_identities")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(ObjectStore::new(eviction::METADATA_STORE).auto_increment(false));
 
         #[cfg(feature = "idb-regression-test")]
         let rexie_builder = rexie_builder.add_object_store(ObjectStore::new("regression_check").auto_increment(false));
 
         let rexie = rexie_builder.build().await?;
+        if let Err(e) = eviction::ensure_not_evicted(&rexie).await {
+            rexie.close();
+            return Err(e);
+        }
 
         let storage = WasmStorageWrapper::Persistent(rexie);
         let conn = WasmEncryptedStorage::new(key, storage);
@@ -21,7 +21,7 @@ use wasm_bindgen::JsValue;
 
 use crate::{
     entities::{Entity, EntityFindParams},
-    CryptoKeystoreResult,
+    CryptoKeystoreError, CryptoKeystoreResult,
 };
 
 use super::WasmConnection;
@@ -43,9 +43,24 @@ impl std::fmt::Debug for WasmStorageWrapper {
     }
 }
 
+/// Writes estimated to be bigger than this (in bytes) are checked against the storage quota before being performed
+pub const STORAGE_ESTIMATION_THRESHOLD: usize = 1_000_000;
+
+/// Invoked when a write cannot fit in the storage left for the origin, with the size of the write and the
+/// space left (when the browser reports it), both in bytes. Gives the application a chance to prompt the user to
+/// free some space
+pub type StoragePressureCallback = Box<dyn Fn(usize, Option<u64>)>;
+
+/// Writes made within a transaction, by collection then by key: the key as given to IndexedDB along with the
+/// encrypted entity, or [None] when it has been deleted
+type PendingWrites = HashMap<String, HashMap<Vec<u8>, (JsValue, Option<JsValue>)>>;
+
 pub struct WasmEncryptedStorage {
     pub(crate) storage: WasmStorageWrapper,
     pub(crate) cipher: aes_gcm::Aes256Gcm,
+    pub(crate) storage_pressure_callback: Option<StoragePressureCallback>,
+    /// Writes of the transaction in progress, see [Self::begin_transaction]
+    pending_writes: Option<PendingWrites>,
 }
 
 impl std::fmt::Debug for WasmEncryptedStorage {
@@ -53,13 +68,18 @@ impl std::fmt::Debug for WasmEncryptedStorage {
         f.debug_struct("WasmEncryptedStorage")
             .field("storage", &self.storage)
             .field("cipher", &"[REDACTED]")
+            .field(
+                "storage_pressure_callback",
+                &self.storage_pressure_callback.as_ref().map(|_| "[CALLBACK]"),
+            )
+            .field("in_transaction", &self.pending_writes.is_some())
             .finish()
     }
 }
 
 impl WasmEncryptedStorage {
     pub fn new(key: impl AsRef<str>, storage: WasmStorageWrapper) -> Self {
-        let hashed_key: aes_gcm::Key<aes_gcm::Aes256Gcm> = {
+        let mut hashed_key: aes_gcm::Key<aes_gcm::Aes256Gcm> = {
             use sha2::Digest as _;
             let mut hasher = sha2::Sha256::new();
             hasher.update(key.as_ref().as_bytes());
@@ -69,7 +89,19 @@ impl WasmEncryptedStorage {
         use aes_gcm::KeyInit as _;
 
         let cipher = aes_gcm::Aes256Gcm::new(&hashed_key);
-        Self { cipher, storage }
+        // only the key schedule of the cipher is kept around, which is zeroized when dropped
+        zeroize::Zeroize::zeroize(hashed_key.as_mut_slice());
+        Self {
+            cipher,
+            storage,
+            storage_pressure_callback: None,
+            pending_writes: None,
+        }
+    }
+
+    /// Sets (or unsets) the callback invoked when a write would exceed the storage quota
+    pub fn set_storage_pressure_callback(&mut self, callback: Option<StoragePressureCallback>) {
+        self.storage_pressure_callback = callback;
     }
 
     pub fn is_persistent(&self) -> bool {
@@ -95,11 +127,143 @@ impl WasmEncryptedStorage {
         Ok(())
     }
 
+    /// Starts a transaction. Its writes are kept in memory until [Self::commit_transaction] persists them at once,
+    /// since an IndexedDB transaction does not survive awaiting anything else than its own requests. Lookups see
+    /// them in the meantime
+    pub fn begin_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        if self.pending_writes.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        self.pending_writes = Some(Default::default());
+        Ok(())
+    }
+
+    /// Persists every write made since [Self::begin_transaction] within a single IndexedDB transaction. When it fails,
+    /// they are discarded all the same
+    pub async fn commit_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        let pending_writes = self
+            .pending_writes
+            .take()
+            .ok_or(CryptoKeystoreError::NoTransactionInProgress)?;
+        match &mut self.storage {
+            WasmStorageWrapper::Persistent(rexie) => {
+                if pending_writes.is_empty() {
+                    return Ok(());
+                }
+                let callback = self.storage_pressure_callback.as_deref();
+                let size = pending_writes
+                    .values()
+                    .flat_map(HashMap::values)
+                    .filter_map(|(_, value)| value.as_ref())
+                    .map(estimate_size)
+                    .sum::<usize>();
+                if size >= STORAGE_ESTIMATION_THRESHOLD {
+                    ensure_storage_available(size, callback).await?;
+                }
+
+                let collections = pending_writes.keys().map(String::as_str).collect::<Vec<_>>();
+                let transaction = rexie.transaction(collections.as_slice(), TransactionMode::ReadWrite)?;
+                for (collection, writes) in &pending_writes {
+                    let store = transaction.store(collection)?;
+                    for (key, value) in writes.values() {
+                        if let Some(value) = value {
+                            store
+                                .put(value, Some(key))
+                                .await
+                                .map_err(|e| map_write_error(e, size, callback))?;
+                        } else {
+                            store.delete(key).await?;
+                        }
+                    }
+                }
+                transaction.done().await?;
+            }
+            WasmStorageWrapper::InMemory(map) => {
+                for (collection, writes) in pending_writes {
+                    let store = map.entry(collection).or_default();
+                    for (key, (_, value)) in writes {
+                        if let Some(value) = value {
+                            store.insert(key, value);
+                        } else {
+                            store.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a transaction is in progress, see [Self::begin_transaction]
+    pub fn in_transaction(&self) -> bool {
+        self.pending_writes.is_some()
+    }
+
+    /// Discards every write made since [Self::begin_transaction]
+    pub fn rollback_transaction(&mut self) -> CryptoKeystoreResult<()> {
+        self.pending_writes
+            .take()
+            .ok_or(CryptoKeystoreError::NoTransactionInProgress)?;
+        Ok(())
+    }
+
+    /// Writes of the transaction in progress to `collection`, if any
+    fn pending_writes(&self, collection: &str) -> Option<&HashMap<Vec<u8>, (JsValue, Option<JsValue>)>> {
+        self.pending_writes.as_ref()?.get(collection)
+    }
+
+    fn decrypt_value<R: Entity<ConnectionType = WasmConnection> + 'static>(
+        &self,
+        value: JsValue,
+    ) -> CryptoKeystoreResult<R> {
+        let mut entity: R = serde_wasm_bindgen::from_value(value)?;
+        entity.decrypt(&self.cipher)?;
+        Ok(entity)
+    }
+
+    /// Every encrypted entity of `collection` along with its key, including the writes of the transaction in
+    /// progress. Ordered by key, as IndexedDB does
+    async fn get_all_raw(&self, collection: &str) -> CryptoKeystoreResult<Vec<(Vec<u8>, JsValue)>> {
+        let mut entries = match &self.storage {
+            WasmStorageWrapper::Persistent(rexie) => {
+                let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
+                let store = transaction.store(collection)?;
+                store
+                    .get_all(None, None, None, None)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, v)| !v.is_null() && !v.is_undefined())
+                    .map(|(k, v)| (key_bytes(&k), v))
+                    .collect::<Vec<_>>()
+            }
+            WasmStorageWrapper::InMemory(map) => map
+                .get(collection)
+                .map(|store| store.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                .unwrap_or_default(),
+        };
+
+        if let Some(writes) = self.pending_writes(collection) {
+            entries.retain(|(k, _)| !writes.contains_key(k));
+            entries.extend(writes.iter().filter_map(|(k, (_, v))| Some((k.clone(), v.clone()?))));
+        }
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(entries)
+    }
+
     pub async fn get<R: Entity<ConnectionType = WasmConnection> + 'static>(
         &self,
         collection: &str,
         id: impl AsRef<[u8]>,
     ) -> CryptoKeystoreResult<Option<R>> {
+        if let Some((_, value)) = self
+            .pending_writes(collection)
+            .and_then(|writes| writes.get(id.as_ref()))
+        {
+            return value.clone().map(|value| self.decrypt_value(value)).transpose();
+        }
+
         match &self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
@@ -131,11 +295,71 @@ impl WasmEncryptedStorage {
         }
     }
 
+    /// Looks up all the given ids within a single transaction. Ids which aren't in the collection are skipped
+    pub async fn get_many<R: Entity<ConnectionType = WasmConnection> + 'static>(
+        &self,
+        collection: &str,
+        ids: &[impl AsRef<[u8]>],
+    ) -> CryptoKeystoreResult<Vec<R>> {
+        let mut res = Vec::with_capacity(ids.len());
+        if self.pending_writes(collection).is_some() {
+            for id in ids {
+                if let Some(entity) = self.get(collection, id).await? {
+                    res.push(entity);
+                }
+            }
+            return Ok(res);
+        }
+
+        match &self.storage {
+            WasmStorageWrapper::Persistent(rexie) => {
+                let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
+                let store = transaction.store(collection)?;
+                for id in ids {
+                    let js_key = js_sys::Uint8Array::from(id.as_ref());
+                    if let Some(entity_raw) = store.get(&js_key).await? {
+                        let mut entity: R = serde_wasm_bindgen::from_value(entity_raw)?;
+                        entity.decrypt(&self.cipher)?;
+                        res.push(entity);
+                    }
+                }
+            }
+            WasmStorageWrapper::InMemory(map) => {
+                if let Some(store) = map.get(collection) {
+                    for id in ids {
+                        if let Some(js_value) = store.get(id.as_ref()).cloned() {
+                            if let Some(mut entity) = serde_wasm_bindgen::from_value::<Option<R>>(js_value)? {
+                                entity.decrypt(&self.cipher)?;
+                                res.push(entity);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
     pub async fn get_all<R: Entity<ConnectionType = WasmConnection> + 'static>(
         &self,
         collection: &str,
         params: Option<EntityFindParams>,
     ) -> CryptoKeystoreResult<Vec<R>> {
+        if self.pending_writes(collection).is_some() {
+            let params = params.unwrap_or_default();
+            let mut entries = self.get_all_raw(collection).await?;
+            if params.reverse {
+                entries.reverse();
+            }
+            return Ok(entries
+                .into_iter()
+                .skip(params.offset.unwrap_or_default() as usize)
+                .take(params.limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+                .filter_map(|(_, v)| self.decrypt_value(v).ok())
+                .collect());
+        }
+
         match &self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
@@ -191,6 +415,10 @@ impl WasmEncryptedStorage {
     }
 
     pub async fn count(&self, collection: &str) -> CryptoKeystoreResult<usize> {
+        if self.pending_writes(collection).is_some() {
+            return Ok(self.get_all_raw(collection).await?.len());
+        }
+
         match &self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
@@ -209,16 +437,43 @@ impl WasmEncryptedStorage {
         values: &mut [R],
     ) -> CryptoKeystoreResult<()> {
         let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        if let Some(pending_writes) = &mut self.pending_writes {
+            let writes = pending_writes.entry(collection.into()).or_default();
+            for value in values {
+                let key = value.id()?;
+                value.encrypt(&self.cipher)?;
+                writes.insert(key_bytes(&key), (key, Some(value.serialize(&serializer)?)));
+            }
+            return Ok(());
+        }
+
         match &mut self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
-                let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
-                let store = transaction.store(collection)?;
-
+                let callback = self.storage_pressure_callback.as_deref();
+                let mut js_values = Vec::with_capacity(values.len());
+                let mut size = 0;
                 for value in values {
                     let key = value.id()?;
                     value.encrypt(&self.cipher)?;
                     let js_value = value.serialize(&serializer)?;
-                    store.put(&js_value, Some(&key)).await?;
+                    size += estimate_size(&js_value);
+                    js_values.push((key, js_value));
+                }
+
+                // Has to happen before opening the transaction since IndexedDB commits it as soon as we await
+                // on anything else
+                if size >= STORAGE_ESTIMATION_THRESHOLD {
+                    ensure_storage_available(size, callback).await?;
+                }
+
+                let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
+                let store = transaction.store(collection)?;
+
+                for (key, js_value) in js_values {
+                    store
+                        .put(&js_value, Some(&key))
+                        .await
+                        .map_err(|e| map_write_error(e, size, callback))?;
                 }
             }
             WasmStorageWrapper::InMemory(map) => {
@@ -241,6 +496,14 @@ impl WasmEncryptedStorage {
     }
 
     pub async fn delete(&mut self, collection: &str, ids: &[impl AsRef<[u8]>]) -> CryptoKeystoreResult<()> {
+        if let Some(pending_writes) = &mut self.pending_writes {
+            let writes = pending_writes.entry(collection.into()).or_default();
+            for k in ids {
+                writes.insert(k.as_ref().to_vec(), (Uint8Array::from(k.as_ref()).into(), None));
+            }
+            return Ok(());
+        }
+
         match &mut self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
@@ -262,4 +525,124 @@ impl WasmEncryptedStorage {
 
         Ok(())
     }
+
+    /// Removes the entities whose key is `prefix` followed by exactly `suffix_len` bytes and returns their keys
+    pub async fn delete_prefixed(
+        &mut self,
+        collection: &str,
+        prefix: &[u8],
+        suffix_len: usize,
+    ) -> CryptoKeystoreResult<Vec<Vec<u8>>> {
+        let upper_bound = [prefix, &vec![u8::MAX; suffix_len]].concat();
+        let len = upper_bound.len();
+        if self.pending_writes.is_some() {
+            let removed = self
+                .get_all_raw(collection)
+                .await?
+                .into_iter()
+                .map(|(k, _)| k)
+                .filter(|k| k.len() == len && k.starts_with(prefix))
+                .collect::<Vec<_>>();
+            self.delete(collection, &removed).await?;
+            return Ok(removed);
+        }
+
+        let mut removed = vec![];
+        match &mut self.storage {
+            WasmStorageWrapper::Persistent(rexie) => {
+                let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
+                let store = transaction.store(collection)?;
+                let range = rexie::KeyRange::bound(
+                    &Uint8Array::from(prefix).into(),
+                    &Uint8Array::from(upper_bound.as_slice()).into(),
+                    false,
+                    false,
+                )?;
+                for key in store.get_all_keys(Some(&range), None).await? {
+                    // binary keys come back as ArrayBuffers
+                    let id = Uint8Array::new(&key).to_vec();
+                    if id.len() == len {
+                        store.delete(&key).await?;
+                        removed.push(id);
+                    }
+                }
+            }
+            WasmStorageWrapper::InMemory(map) => {
+                if let Some(store) = map.get_mut(collection) {
+                    removed = store
+                        .keys()
+                        .filter(|k| k.len() == len && k.starts_with(prefix))
+                        .cloned()
+                        .collect();
+                    for k in &removed {
+                        store.remove(k);
+                    }
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Bytes of a key as given to IndexedDB: most ids are `Uint8Array`s (read back as `ArrayBuffer`s), some are strings
+fn key_bytes(key: &JsValue) -> Vec<u8> {
+    key.as_string()
+        .map(String::into_bytes)
+        .unwrap_or_else(|| Uint8Array::new(key).to_vec())
+}
+
+/// Rough size of a serialized entity, i.e. its length once encoded as JSON
+fn estimate_size(js_value: &JsValue) -> usize {
+    js_sys::JSON::stringify(js_value)
+        .map(|json| json.length() as usize)
+        .unwrap_or_default()
+}
+
+/// Space left for the origin according to the [StorageManager API](https://developer.mozilla.org/en-US/docs/Web/API/StorageManager/estimate),
+/// or [None] when the runtime does not provide it
+async fn available_storage() -> Option<u64> {
+    use wasm_bindgen::JsCast as _;
+
+    let navigator = js_sys::Reflect::get(&js_sys::global(), &"navigator".into()).ok()?;
+    let storage = js_sys::Reflect::get(&navigator, &"storage".into()).ok()?;
+    let estimate = js_sys::Reflect::get(&storage, &"estimate".into())
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    let promise = estimate.call0(&storage).ok()?.dyn_into::<js_sys::Promise>().ok()?;
+    let estimate = wasm_bindgen_futures::JsFuture::from(promise).await.ok()?;
+    let quota = js_sys::Reflect::get(&estimate, &"quota".into()).ok()?.as_f64()?;
+    let usage = js_sys::Reflect::get(&estimate, &"usage".into()).ok()?.as_f64()?;
+
+    Some((quota - usage).max(0.0) as u64)
+}
+
+/// Fails (and lets the application know) when a write of `size` bytes cannot fit in the space left for the origin.
+/// Does nothing when the runtime cannot estimate it
+async fn ensure_storage_available(
+    size: usize,
+    callback: Option<&dyn Fn(usize, Option<u64>)>,
+) -> CryptoKeystoreResult<()> {
+    match available_storage().await {
+        Some(available) if available < size as u64 => {
+            if let Some(callback) = callback {
+                callback(size, Some(available));
+            }
+            Err(CryptoKeystoreError::StorageQuotaExceeded(size))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Surfaces IndexedDB's `QuotaExceededError` as [CryptoKeystoreError::StorageQuotaExceeded]
+fn map_write_error(e: rexie::Error, size: usize, callback: Option<&dyn Fn(usize, Option<u64>)>) -> CryptoKeystoreError {
+    if format!("{e:?}").contains("QuotaExceededError") {
+        if let Some(callback) = callback {
+            callback(size, None);
+        }
+        CryptoKeystoreError::StorageQuotaExceeded(size)
+    } else {
+        e.into()
+    }
 }
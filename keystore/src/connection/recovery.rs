@@ -0,0 +1,127 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! When the disk is full (or the write fails for any other transient reason) while persisting a group, the caller
+//! already moved on in memory and would end up ahead of what is stored. Once a [PersistenceFailureCallback] is
+//! registered, such group states are kept aside as dirty instead of failing, served to the lookups and written again on
+//! the next successful write.
+//!
+//! A group is seldom written on its own though: merging a commit also writes the key material of the new epoch and
+//! deletes the one of the previous epoch, joining a group consumes a KeyPackage. When they are written within a
+//! transaction, a group which fails to persist is not kept aside: the failure is returned so that the transaction is
+//! rolled back as a whole rather than the group being written later without the rest.
+
+use std::{collections::HashMap, sync::Arc};
+
+use super::{Connection, KeystoreDatabaseConnection};
+use crate::{
+    entities::{Entity, PersistedMlsGroup},
+    CryptoKeystoreError,
+};
+
+/// Callback invoked with the group id and the error when persisting a group failed and its state has been kept in
+/// memory to be written again later
+#[cfg(not(target_family = "wasm"))]
+pub type PersistenceFailureCallback = Box<dyn Fn(&[u8], &CryptoKeystoreError) + Send + Sync>;
+/// Callback invoked with the group id and the error when persisting a group failed and its state has been kept in
+/// memory to be written again later
+#[cfg(target_family = "wasm")]
+pub type PersistenceFailureCallback = Box<dyn Fn(&[u8], &CryptoKeystoreError)>;
+
+#[derive(Default)]
+pub(crate) struct PersistenceRecovery {
+    /// Shared so that it can be invoked once the lock is released
+    callback: Option<Arc<PersistenceFailureCallback>>,
+    dirty_groups: HashMap<Vec<u8>, PersistedMlsGroup>,
+}
+
+impl std::fmt::Debug for PersistenceRecovery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistenceRecovery")
+            .field("callback", &self.callback.is_some())
+            .field("dirty_groups", &self.dirty_groups.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PersistenceRecovery {
+    pub(crate) fn dirty_group(&self, group_id: &[u8]) -> Option<&PersistedMlsGroup> {
+        self.dirty_groups.get(group_id)
+    }
+
+    pub(crate) fn dirty_groups(&self) -> impl Iterator<Item = &PersistedMlsGroup> {
+        self.dirty_groups.values()
+    }
+
+    pub(crate) fn discard(&mut self, group_id: &[u8]) {
+        self.dirty_groups.remove(group_id);
+    }
+
+    /// Keeps the group aside when recovery is enabled and returns the callback to notify of `error`. Returns `None`
+    /// when the error has to be handed back instead
+    pub(crate) fn mark_dirty(
+        &mut self,
+        group: PersistedMlsGroup,
+        error: &CryptoKeystoreError,
+    ) -> Option<Arc<PersistenceFailureCallback>> {
+        let callback = self.callback.clone()?;
+        // writing it again will never succeed
+        if matches!(error, CryptoKeystoreError::BlobTooBig) {
+            return None;
+        }
+
+        self.dirty_groups.insert(group.id.clone(), group);
+        Some(callback)
+    }
+}
+
+impl Connection {
+    /// Sets (or unsets) the callback invoked when persisting a group fails. While one is set, the state of such a group
+    /// is kept in memory, returned when the group is looked up and written again on the next successful write instead
+    /// of the error being returned. Unsetting it does not discard the groups still waiting to be written.
+    ///
+    /// Within a transaction, see [Self::begin_transaction], the error is returned nonetheless so that the group is
+    /// rolled back along with everything else written by the transaction
+    pub async fn set_persistence_failure_callback(&self, callback: Option<PersistenceFailureCallback>) {
+        #[allow(clippy::arc_with_non_send_sync)] // the callback is not Send on WASM
+        let callback = callback.map(Arc::new);
+        self.recovery.lock().await.callback = callback;
+    }
+
+    /// Ids of the groups whose latest state could not be written yet
+    pub async fn dirty_mls_groups(&self) -> Vec<Vec<u8>> {
+        self.recovery.lock().await.dirty_groups.keys().cloned().collect()
+    }
+
+    /// Tries to write every dirty group again, stopping at the first failure. Nothing is written within a transaction
+    /// since the groups would be lost if it were rolled back, they are written once it is committed
+    pub(crate) async fn flush_dirty_mls_groups(&self, conn: &mut KeystoreDatabaseConnection) {
+        if conn.in_transaction() {
+            return;
+        }
+        let mut recovery = self.recovery.lock().await;
+        let ids = recovery.dirty_groups.keys().cloned().collect::<Vec<_>>();
+        for id in ids {
+            let Some(group) = recovery.dirty_groups.get(&id) else {
+                continue;
+            };
+            if Self::stage_checksum(conn, group).await.is_err() || group.save(conn).await.is_err() {
+                break;
+            }
+            recovery.dirty_groups.remove(&id);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+cfg_if::cfg_if! {
+    if #[cfg(target_family = "wasm")] {
+        use fluvio_wasm_timer::Instant;
+    } else {
+        use std::time::Instant;
+    }
+}
+
+/// Number of slow operations kept, the oldest ones are dropped first
+pub const SLOW_OPERATION_LOG_CAPACITY: usize = 128;
+
+/// Kind of keystore operation, see [SlowOperation]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystoreOperation {
+    Save,
+    Insert,
+    Find,
+    FindAll,
+    FindMany,
+    Remove,
+    Count,
+}
+
+/// A keystore operation which took longer than the threshold set with
+/// [super::Connection::set_slow_operation_threshold]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowOperation {
+    /// Type of the entities, e.g. `PersistedMlsGroup`
+    pub entity: &'static str,
+    pub operation: KeystoreOperation,
+    /// Time the operation took, waiting for a connection included
+    pub duration: Duration,
+    /// Number of entities read or written
+    pub entities: usize,
+    /// Bytes written, only known for the MLS group states. 0 otherwise
+    pub bytes: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SlowOperationLog {
+    /// Threshold in microseconds plus one, 0 when the log is disabled
+    threshold_micros: AtomicU64,
+    operations: Mutex<VecDeque<SlowOperation>>,
+}
+
+impl SlowOperationLog {
+    pub(crate) fn set_threshold(&self, threshold: Option<Duration>) {
+        let micros = threshold
+            .map(|t| (t.as_micros() as u64).saturating_add(1))
+            .unwrap_or_default();
+        self.threshold_micros.store(micros, Ordering::Relaxed);
+        if threshold.is_none() {
+            self.lock().clear();
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<SlowOperation> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Starts timing an operation on entities of type `E`. It is recorded when the returned timer is dropped, hence
+    /// however the operation ends
+    pub(crate) fn start<E>(&self, operation: KeystoreOperation) -> OperationTimer<'_> {
+        let type_name = std::any::type_name::<E>();
+        OperationTimer {
+            log: self,
+            entity: type_name.rsplit("::").next().unwrap_or(type_name),
+            operation,
+            entities: 0,
+            bytes: 0,
+            start: Instant::now(),
+        }
+    }
+
+    fn record(&self, operation: SlowOperation) {
+        let mut operations = self.lock();
+        if operations.len() == SLOW_OPERATION_LOG_CAPACITY {
+            operations.pop_front();
+        }
+        operations.push_back(operation);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<SlowOperation>> {
+        // the log only holds plain values, it can't be left inconsistent by a panic
+        self.operations.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OperationTimer<'a> {
+    log: &'a SlowOperationLog,
+    entity: &'static str,
+    operation: KeystoreOperation,
+    pub(crate) entities: usize,
+    pub(crate) bytes: usize,
+    start: Instant,
+}
+
+impl Drop for OperationTimer<'_> {
+    fn drop(&mut self) {
+        let threshold = self.log.threshold_micros.load(Ordering::Relaxed);
+        if threshold == 0 {
+            return;
+        }
+        let duration = self.start.elapsed();
+        if duration.as_micros() as u64 >= threshold - 1 {
+            self.log.record(SlowOperation {
+                entity: self.entity,
+                operation: self.operation,
+                duration,
+                entities: self.entities,
+                bytes: self.bytes,
+            });
+        }
+    }
+}
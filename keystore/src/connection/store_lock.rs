@@ -0,0 +1,316 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Advisory lock held for as long as a persistent store is open, so that two instances (e.g. an app and its extension)
+//! cannot silently overwrite each other's writes. Native platforms lock a `<store path>.lock` file next to the
+//! database while WASM relies on the [Web Locks API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Locks_API).
+//!
+//! A store in use can still be taken over explicitly, and taken back the same way. The previous holder is not notified
+//! but every write it attempts afterwards fails with [CryptoKeystoreError::StoreAlreadyInUse].
+
+use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+
+#[derive(Debug)]
+pub(crate) struct StoreLock {
+    #[cfg(target_family = "wasm")]
+    name: String,
+    lock: std::sync::Mutex<platform::Lock>,
+}
+
+impl StoreLock {
+    /// Locks the store named `name`. When `takeover` is set, the lock is taken even if another instance holds it
+    pub(crate) async fn acquire(name: &str, takeover: bool) -> CryptoKeystoreResult<Self> {
+        Ok(Self {
+            #[cfg(target_family = "wasm")]
+            name: name.to_string(),
+            lock: std::sync::Mutex::new(platform::Lock::acquire(name, takeover).await?),
+        })
+    }
+
+    /// Takes the store (back) over from whichever instance holds it
+    pub(crate) async fn take_over(&self) -> CryptoKeystoreResult<()> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_family = "wasm")] {
+                // the previous lock is only released once stolen so that no other instance can slip in between
+                let lock = platform::Lock::acquire(&self.name, true).await?;
+                *self.lock()? = lock;
+            } else {
+                self.lock()?.take_over()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails when another instance took the store over since this lock was acquired
+    pub(crate) fn ensure_held(&self) -> CryptoKeystoreResult<()> {
+        if self.lock()?.is_held() {
+            Ok(())
+        } else {
+            Err(CryptoKeystoreError::StoreAlreadyInUse)
+        }
+    }
+
+    /// Releases the lock and removes what it leaves behind, once the store itself has been wiped
+    pub(crate) fn release_wiped(self) {
+        if let Ok(lock) = self.lock.into_inner() {
+            lock.release_wiped()
+        }
+    }
+
+    fn lock(&self) -> CryptoKeystoreResult<std::sync::MutexGuard<'_, platform::Lock>> {
+        self.lock.lock().map_err(|_| CryptoKeystoreError::LockPoisonError)
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod platform {
+    use std::{
+        fs::File,
+        io::{Seek as _, Write as _},
+        path::PathBuf,
+        time::{Duration, SystemTime},
+    };
+
+    use fs2::FileExt as _;
+
+    use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+
+    #[derive(Debug)]
+    pub(super) struct Lock {
+        path: PathBuf,
+        /// Holds the file lock as long as it's open, unless the store was taken over from a running instance
+        file: File,
+        /// Identifies the owner of the store, written in the lock file. Whoever takes the store over overwrites it
+        token: String,
+        /// Modification time of the lock file when it was last found to hold [Self::token]
+        verified: Option<SystemTime>,
+    }
+
+    /// Coarsest modification time resolution of the filesystems the store may live on (FAT has 2 seconds)
+    const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+    impl Lock {
+        pub(super) async fn acquire(name: &str, takeover: bool) -> CryptoKeystoreResult<Self> {
+            let path = PathBuf::from(format!("{name}.lock"));
+            let mut file = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+
+            if !Self::try_lock(&file)? && !takeover {
+                return Err(CryptoKeystoreError::StoreAlreadyInUse);
+            }
+            // when taken over, the current holder keeps its file lock but loses the ownership recorded here
+            let token = Self::write_token(&mut file)?;
+
+            Ok(Self {
+                path,
+                file,
+                token,
+                verified: None,
+            })
+        }
+
+        /// Takes the store back over, through the file this instance already has open
+        pub(super) fn take_over(&mut self) -> CryptoKeystoreResult<()> {
+            // the file lock is only obtained if the instance which took the store over is gone
+            Self::try_lock(&self.file)?;
+            self.token = Self::write_token(&mut self.file)?;
+            self.verified = None;
+            Ok(())
+        }
+
+        /// Whether the file could be locked, i.e. no other instance holds it
+        fn try_lock(file: &File) -> CryptoKeystoreResult<bool> {
+            match file.try_lock_exclusive() {
+                Ok(()) => Ok(true),
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => Ok(false),
+                Err(e) => Err(e.into()),
+            }
+        }
+
+        fn write_token(file: &mut File) -> CryptoKeystoreResult<String> {
+            let token = Self::new_token();
+            file.set_len(0)?;
+            file.rewind()?;
+            file.write_all(token.as_bytes())?;
+            file.sync_all()?;
+            Ok(token)
+        }
+
+        /// Unique among the instances of every process on this device
+        fn new_token() -> String {
+            static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            format!("{}-{nanos}-{count}", std::process::id())
+        }
+
+        /// The lock file is only read again when it was modified since its ownership was last verified. As with git's
+        /// "racy" index entries, a modification time too close to that verification isn't trusted since a takeover
+        /// within the timestamp granularity would leave it unchanged
+        pub(super) fn is_held(&mut self) -> bool {
+            let modified = self.file.metadata().and_then(|metadata| metadata.modified()).ok();
+            if modified.is_some() && modified == self.verified {
+                return true;
+            }
+
+            let checked_at = SystemTime::now();
+            let held = matches!(std::fs::read(&self.path), Ok(owner) if owner == self.token.as_bytes());
+            self.verified = modified.filter(|modified| held && *modified + MTIME_GRANULARITY < checked_at);
+            held
+        }
+
+        pub(super) fn release_wiped(mut self) {
+            // not removed on close: another instance could lock the file between its unlocking and its removal
+            if self.is_held() {
+                let _ = std::fs::remove_file(&self.path);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[async_std::test]
+        async fn takeover_is_noticed_once_ownership_is_cached() {
+            let name = format!("{}/store-lock-{}", std::env::temp_dir().display(), Lock::new_token());
+            let mut app = Lock::acquire(&name, false).await.unwrap();
+            // as if acquired long ago, so that the ownership gets cached
+            app.file
+                .set_modified(SystemTime::now() - MTIME_GRANULARITY * 2)
+                .unwrap();
+            assert!(app.is_held());
+            assert!(app.verified.is_some());
+            assert!(app.is_held());
+
+            let extension = Lock::acquire(&name, true).await.unwrap();
+            assert!(!app.is_held());
+            assert!(app.verified.is_none());
+
+            app.take_over().unwrap();
+            assert!(app.is_held());
+            drop(extension);
+            app.release_wiped();
+            assert!(!PathBuf::from(format!("{name}.lock")).exists());
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod platform {
+    use std::{cell::Cell, rc::Rc};
+
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+    use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+
+    #[derive(Debug)]
+    pub(super) struct Lock(Option<WebLock>);
+
+    #[derive(Debug)]
+    struct WebLock {
+        /// Resolves the promise returned to the Web Locks API, which releases the lock
+        release: js_sys::Function,
+        /// Set when the lock was stolen by another instance
+        lost: Rc<Cell<bool>>,
+    }
+
+    fn js_error(e: JsValue) -> CryptoKeystoreError {
+        CryptoKeystoreError::JsError(format!("Web Locks API failure: {e:?}"))
+    }
+
+    impl Lock {
+        pub(super) async fn acquire(name: &str, takeover: bool) -> CryptoKeystoreResult<Self> {
+            // older browsers and insecure contexts don't expose the API, the store is left unguarded there
+            let Some(locks) = js_sys::Reflect::get(&js_sys::global(), &"navigator".into())
+                .and_then(|navigator| js_sys::Reflect::get(&navigator, &"locks".into()))
+                .ok()
+                .filter(JsValue::is_object)
+            else {
+                return Ok(Self(None));
+            };
+
+            let mut granted_resolve = None;
+            let granted = js_sys::Promise::new(&mut |resolve, _| granted_resolve = Some(resolve));
+            let mut release = None;
+            let held = js_sys::Promise::new(&mut |resolve, _| release = Some(resolve));
+            let (Some(granted_resolve), Some(release)) = (granted_resolve, release) else {
+                return Err(CryptoKeystoreError::ImplementationError);
+            };
+
+            // the lock is held until the promise returned by this callback resolves
+            let callback = Closure::once_into_js(move |lock: JsValue| -> JsValue {
+                let is_granted = !lock.is_null();
+                let _ = granted_resolve.call1(&JsValue::NULL, &JsValue::from_bool(is_granted));
+                if is_granted {
+                    held.into()
+                } else {
+                    JsValue::UNDEFINED
+                }
+            });
+
+            let options = js_sys::Object::new();
+            js_sys::Reflect::set(&options, &"ifAvailable".into(), &JsValue::from_bool(!takeover)).map_err(js_error)?;
+            js_sys::Reflect::set(&options, &"steal".into(), &JsValue::from_bool(takeover)).map_err(js_error)?;
+
+            let request: js_sys::Function = js_sys::Reflect::get(&locks, &"request".into())
+                .and_then(JsCast::dyn_into)
+                .map_err(js_error)?;
+            let request: js_sys::Promise = request
+                .call3(&locks, &format!("core-crypto:{name}").into(), &options, &callback)
+                .and_then(JsCast::dyn_into)
+                .map_err(js_error)?;
+
+            let granted = wasm_bindgen_futures::JsFuture::from(granted).await.map_err(js_error)?;
+            if !granted.is_truthy() {
+                return Err(CryptoKeystoreError::StoreAlreadyInUse);
+            }
+
+            // a stolen lock rejects the request with an `AbortError`. The handler has to outlive this instance since
+            // the rejection can happen after it's gone, e.g. when it takes its own store back over
+            let lost = Rc::new(Cell::new(false));
+            let on_lost = {
+                let lost = lost.clone();
+                Closure::once_into_js(move |_: JsValue| lost.set(true))
+            };
+            js_sys::Reflect::get(&request, &"catch".into())
+                .and_then(JsCast::dyn_into::<js_sys::Function>)
+                .and_then(|catch| catch.call1(&request, &on_lost))
+                .map_err(js_error)?;
+
+            Ok(Self(Some(WebLock { release, lost })))
+        }
+
+        pub(super) fn is_held(&self) -> bool {
+            self.0.as_ref().map_or(true, |lock| !lock.lost.get())
+        }
+
+        pub(super) fn release_wiped(self) {}
+    }
+
+    impl Drop for WebLock {
+        fn drop(&mut self) {
+            let _ = self.release.call0(&JsValue::NULL);
+        }
+    }
+}
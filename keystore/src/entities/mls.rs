@@ -19,6 +19,13 @@ use crate::CryptoKeystoreResult;
 use openmls_traits::types::SignatureScheme;
 use zeroize::Zeroize;
 
+/// How long an external commit which is neither merged nor cleared is kept, see [Entity::TTL]
+pub const PENDING_GROUP_TTL: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 3600);
+/// How long a message buffered for a conversation which never catches up is kept, see [Entity::TTL]
+pub const PENDING_MESSAGE_TTL: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 3600);
+/// How long an enrollment stashed during an interrupted E2EI flow is kept, see [Entity::TTL]
+pub const E2EI_ENROLLMENT_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+
 /// Entity representing a persisted `MlsGroup`
 #[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
 #[zeroize(drop)]
@@ -75,6 +82,9 @@ pub struct PersistedMlsPendingGroup {
     pub state: Vec<u8>,
     pub parent_id: Option<Vec<u8>>,
     pub custom_configuration: Vec<u8>,
+    /// Unix timestamp in seconds of the last save, set by the keystore. See [Entity::TTL]
+    #[cfg_attr(any(target_family = "wasm", feature = "serde"), serde(default))]
+    pub created_at: u64,
 }
 
 /// Entity representing a buffered message
@@ -87,6 +97,168 @@ pub struct PersistedMlsPendingGroup {
 pub struct MlsPendingMessage {
     pub id: Vec<u8>,
     pub message: Vec<u8>,
+    /// Unix timestamp in seconds of the last save, set by the keystore. See [Entity::TTL]
+    #[cfg_attr(any(target_family = "wasm", feature = "serde"), serde(default))]
+    pub created_at: u64,
+}
+
+/// Entity recording a Welcome message which has already been processed, so that processing it again is harmless
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsProcessedWelcome {
+    /// Hash of the Welcome message
+    pub id: Vec<u8>,
+    /// Id of the conversation joined with it
+    pub conversation_id: Vec<u8>,
+}
+
+/// Entity recording when a credential was first seen in a conversation, to audit identity changes later on
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsSeenCredential {
+    /// Hash of the conversation id, client id and credential
+    pub id: Vec<u8>,
+    /// Conversation id
+    pub conversation_id: Vec<u8>,
+    /// Id of the client presenting the credential
+    pub client_id: Vec<u8>,
+    /// TLS-serialized credential
+    pub credential: Vec<u8>,
+    /// Unix timestamp (in seconds) of the first time the credential was seen
+    pub first_seen: u64,
+}
+
+/// Entity recording an epoch entered by a conversation, to audit its history later on
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsEpochRecord {
+    /// Conversation id followed by the epoch (big-endian u64)
+    pub id: Vec<u8>,
+    /// Conversation id
+    pub conversation_id: Vec<u8>,
+    /// Epoch number
+    pub epoch: u64,
+    /// Hash of the epoch authenticator
+    pub authenticator_hash: Vec<u8>,
+    /// Unix timestamp (in seconds) of when the epoch was entered
+    pub timestamp: u64,
+    /// Id of the client whose commit created the epoch, when known
+    pub commit_sender: Option<Vec<u8>>,
+}
+
+/// Entity counting the activity of a conversation, only recorded when the application opted in
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsConversationCounters {
+    /// Conversation id
+    pub id: Vec<u8>,
+    /// Application messages encrypted
+    pub messages_sent: u64,
+    /// Application messages decrypted
+    pub messages_received: u64,
+    /// Own commits merged
+    pub commits_sent: u64,
+    /// Incoming commits merged
+    pub commits_received: u64,
+    /// Epoch of the conversation when one of the above last happened
+    pub last_activity_epoch: u64,
+    /// Unix timestamp (in seconds) of when one of the above last happened
+    pub last_activity: u64,
+}
+
+/// Entity holding the settings of a conversation openmls knows nothing of, along with when its own key material was
+/// last rotated
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsConversationMetadata {
+    /// Conversation id
+    pub id: Vec<u8>,
+    /// Seconds after which the key material has to be rotated, 0 when the conversation has no key rotation span
+    pub key_rotation_span: u64,
+    /// Unix timestamp (in seconds) of the last rotation, 0 when it is unknown
+    pub last_key_rotation: u64,
+    /// How many epochs an application message can be behind the current one and still be accepted, if limited
+    pub max_application_message_epoch_age: Option<u64>,
+    /// How application messages are handled while a commit is in flight
+    pub epoch_fence: u8,
+}
+
+/// Entity holding the artifacts of the last commit generated in a conversation with an operation key, so that
+/// retrying the operation returns the same commit as long as it is pending
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsPendingCommit {
+    /// Conversation id
+    pub id: Vec<u8>,
+    /// Key of the operation, chosen by the application
+    pub operation_key: Vec<u8>,
+    /// Confirmation tag of the pending commit, telling it apart from any other commit of the conversation
+    pub confirmation_tag: Vec<u8>,
+    /// Serialized artifacts of the commit
+    pub bundle: Vec<u8>,
+}
+
+/// Entity tracking a credential rotation whose superseded KeyPackages are deleted once the commit of every conversation
+/// has been accepted
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsPendingRotation {
+    /// Random id of the rotation
+    pub id: Vec<u8>,
+    /// Serialized ids of the conversations whose commit has not been accepted yet
+    pub conversation_ids: Vec<u8>,
+    /// Serialized refs of the superseded KeyPackages
+    pub key_package_refs: Vec<u8>,
+}
+
+/// Entity describing a persisted `MlsGroup` so that conversations can be listed without restoring their state
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsGroupIndexEntry {
+    /// Conversation id
+    pub id: Vec<u8>,
+    /// Ciphersuite of the group
+    pub ciphersuite: u16,
+    /// Epoch of the group when it was last persisted
+    pub epoch: u64,
+    /// Number of members of the group when it was last persisted
+    pub member_count: u32,
+    /// Unix timestamp (in seconds) of the last time the group was persisted
+    pub last_persisted: u64,
+    /// Id of the parent group, for a sub-group
+    pub parent_id: Option<Vec<u8>>,
 }
 
 /// Entity representing a persisted `Credential`
@@ -157,11 +329,16 @@ pub struct MlsEncryptionKeyPair {
     pub pk: Vec<u8>,
 }
 
+/// Length of what follows the group id in the id of a [MlsEpochEncryptionKeyPair]: the epoch (u64) and the leaf
+/// index (u32)
+pub const EPOCH_KEYPAIR_ID_SUFFIX_LEN: usize = 12;
+
 /// Entity representing a list of [MlsEncryptionKeyPair]
 #[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
 #[zeroize(drop)]
 #[cfg_attr(target_family = "wasm", derive(serde::Serialize, serde::Deserialize))]
 pub struct MlsEpochEncryptionKeyPair {
+    /// Group id followed by [EPOCH_KEYPAIR_ID_SUFFIX_LEN] bytes
     pub id: Vec<u8>,
     pub keypairs: Vec<u8>,
 }
@@ -201,6 +378,9 @@ pub struct MlsKeyPackage {
 pub struct E2eiEnrollment {
     pub id: Vec<u8>,
     pub content: Vec<u8>,
+    /// Unix timestamp in seconds of the last save, set by the keystore. See [Entity::TTL]
+    #[cfg_attr(any(target_family = "wasm", feature = "serde"), serde(default))]
+    pub created_at: u64,
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -210,6 +390,18 @@ pub trait UniqueEntity: Entity {
     async fn replace(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<()>;
 }
 
+/// Entities belonging to a single conversation, which can be removed along with it without going through the
+/// whole collection
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait ConversationScopedEntity: Entity {
+    /// Removes every entity belonging to the conversation and returns their ids
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> CryptoKeystoreResult<Vec<Vec<u8>>>;
+}
+
 /// OIDC refresh token used in E2EI
 #[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
 #[zeroize(drop)]
@@ -44,6 +44,24 @@ pub use self::platform::*;
 
 use crate::connection::DatabaseConnection;
 use crate::{CryptoKeystoreError, CryptoKeystoreResult, MissingKeyErrorKind};
+use zeroize::Zeroize;
+
+/// Entity holding the checksum of the content of another entity, see
+/// [crate::connection::Connection::integrity_check]
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct EntityChecksum {
+    /// Collection of the checksummed entity followed by its id
+    pub id: Vec<u8>,
+    /// SHA-256 of the content of the entity
+    pub checksum: Vec<u8>,
+    /// Checksum of the content it replaced, which is still valid until the entity has actually been written
+    pub previous_checksum: Option<Vec<u8>>,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(
@@ -137,6 +155,8 @@ pub trait EntityBase: Send + Sized + Clone + PartialEq + Eq + std::fmt::Debug {
     }
     async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>>;
     async fn find_one(conn: &mut Self::ConnectionType, id: &StringEntityId) -> CryptoKeystoreResult<Option<Self>>;
+    /// Looks up the entities with the given ids, skipping the ones which aren't in store. Entities which are looked up
+    /// in bulk override it to fetch a whole batch of ids at once
     async fn find_many(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> CryptoKeystoreResult<Vec<Self>> {
         // Default, inefficient & naive method
         let mut ret = Vec::with_capacity(ids.len());
@@ -163,6 +183,25 @@ cfg_if::cfg_if! {
 
             fn id_raw(&self) -> &[u8];
 
+            /// Collection the checksum of the entity is kept under, none when its content isn't checksummed. See
+            /// [crate::connection::Connection::integrity_check]
+            const CHECKSUM_COLLECTION: Option<&'static str> = None;
+
+            /// Parts of the entity covered by its checksum, in clear
+            fn checksummed_content(&self) -> Vec<&[u8]> {
+                vec![]
+            }
+
+            /// Time after which an entity left behind is considered abandoned, none when it never expires. Expired
+            /// entities are no longer returned by the lookups of [crate::connection::Connection] and are deleted by
+            /// [crate::connection::Connection::purge_expired]
+            const TTL: Option<std::time::Duration> = None;
+
+            /// Unix timestamp in seconds the [Self::TTL] runs from
+            fn created_at(&self) -> u64 {
+                0
+            }
+
             fn aad(&self) -> &[u8] {
                 self.id_raw()
             }
@@ -225,6 +264,25 @@ cfg_if::cfg_if! {
     } else {
         pub trait Entity: EntityBase {
             fn id_raw(&self) -> &[u8];
+
+            /// Collection the checksum of the entity is kept under, none when its content isn't checksummed. See
+            /// [crate::connection::Connection::integrity_check]
+            const CHECKSUM_COLLECTION: Option<&'static str> = None;
+
+            /// Parts of the entity covered by its checksum
+            fn checksummed_content(&self) -> Vec<&[u8]> {
+                vec![]
+            }
+
+            /// Time after which an entity left behind is considered abandoned, none when it never expires. Expired
+            /// entities are no longer returned by the lookups of [crate::connection::Connection] and are deleted by
+            /// [crate::connection::Connection::purge_expired]
+            const TTL: Option<std::time::Duration> = None;
+
+            /// Unix timestamp in seconds the [Self::TTL] runs from
+            fn created_at(&self) -> u64 {
+                0
+            }
         }
     }
 }
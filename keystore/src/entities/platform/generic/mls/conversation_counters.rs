@@ -0,0 +1,124 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::KeystoreDatabaseConnection,
+    entities::{Entity, EntityBase, EntityFindParams, MlsConversationCounters, StringEntityId},
+    MissingKeyErrorKind,
+};
+
+impl Entity for MlsConversationCounters {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+}
+
+impl MlsConversationCounters {
+    fn from_row(r: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: r.get(0)?,
+            messages_sent: r.get::<_, i64>(1)? as u64,
+            messages_received: r.get::<_, i64>(2)? as u64,
+            commits_sent: r.get::<_, i64>(3)? as u64,
+            commits_received: r.get::<_, i64>(4)? as u64,
+            last_activity_epoch: r.get::<_, i64>(5)? as u64,
+            last_activity: r.get::<_, i64>(6)? as u64,
+        })
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsConversationCounters {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsConversationCounters
+    }
+
+    async fn find_all(
+        conn: &mut Self::ConnectionType,
+        params: EntityFindParams,
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let query: String = format!(
+            "SELECT id, messages_sent, messages_received, commits_sent, commits_received, last_activity_epoch, last_activity FROM mls_conversation_counters {}",
+            params.to_sql()
+        );
+
+        let mut stmt = transaction.prepare_cached(&query)?;
+        let entities = stmt.query_map([], Self::from_row)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entities)
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO mls_conversation_counters (id, messages_sent, messages_received, commits_sent, commits_received, last_activity_epoch, last_activity) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                self.id,
+                self.messages_sent as i64,
+                self.messages_received as i64,
+                self.commits_sent as i64,
+                self.commits_received as i64,
+                self.last_activity_epoch as i64,
+                self.last_activity as i64
+            ],
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        use rusqlite::OptionalExtension as _;
+
+        let transaction = conn.transaction()?;
+        Ok(transaction
+            .query_row(
+                "SELECT id, messages_sent, messages_received, commits_sent, commits_received, last_activity_epoch, last_activity FROM mls_conversation_counters WHERE id = ?",
+                [id.as_slice()],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        Ok(conn.query_row("SELECT COUNT(*) FROM mls_conversation_counters", [], |r| r.get(0))?)
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        let len = ids.len();
+        let mut updated = 0;
+        for id in ids {
+            updated += transaction.execute("DELETE FROM mls_conversation_counters WHERE id = ?", [id.as_slice()])?;
+        }
+
+        if updated == len {
+            transaction.commit()?;
+            Ok(())
+        } else {
+            transaction.rollback()?;
+            Err(Self::to_missing_key_err_kind().into())
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::KeystoreDatabaseConnection,
+    entities::{Entity, EntityBase, EntityFindParams, MlsConversationMetadata, StringEntityId},
+    MissingKeyErrorKind,
+};
+
+impl Entity for MlsConversationMetadata {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+}
+
+impl MlsConversationMetadata {
+    fn from_row(r: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: r.get(0)?,
+            key_rotation_span: r.get::<_, i64>(1)? as u64,
+            last_key_rotation: r.get::<_, i64>(2)? as u64,
+            max_application_message_epoch_age: r.get::<_, Option<i64>>(3)?.map(|age| age as u64),
+            epoch_fence: r.get(4)?,
+        })
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsConversationMetadata {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsConversationMetadata
+    }
+
+    async fn find_all(
+        conn: &mut Self::ConnectionType,
+        params: EntityFindParams,
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let query: String = format!(
+            "SELECT id, key_rotation_span, last_key_rotation, max_application_message_epoch_age, epoch_fence \
+            FROM mls_conversation_metadata {}",
+            params.to_sql()
+        );
+
+        let mut stmt = transaction.prepare_cached(&query)?;
+        let entities = stmt.query_map([], Self::from_row)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entities)
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO mls_conversation_metadata \
+            (id, key_rotation_span, last_key_rotation, max_application_message_epoch_age, epoch_fence) \
+            VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![
+                self.id,
+                self.key_rotation_span as i64,
+                self.last_key_rotation as i64,
+                self.max_application_message_epoch_age.map(|age| age as i64),
+                self.epoch_fence
+            ],
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        use rusqlite::OptionalExtension as _;
+
+        let transaction = conn.transaction()?;
+        Ok(transaction
+            .query_row(
+                "SELECT id, key_rotation_span, last_key_rotation, max_application_message_epoch_age, epoch_fence \
+                FROM mls_conversation_metadata WHERE id = ?",
+                [id.as_slice()],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        Ok(conn.query_row("SELECT COUNT(*) FROM mls_conversation_metadata", [], |r| r.get(0))?)
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        let len = ids.len();
+        let mut updated = 0;
+        for id in ids {
+            updated += transaction.execute("DELETE FROM mls_conversation_metadata WHERE id = ?", [id.as_slice()])?;
+        }
+
+        if updated == len {
+            transaction.commit()?;
+            Ok(())
+        } else {
+            transaction.rollback()?;
+            Err(Self::to_missing_key_err_kind().into())
+        }
+    }
+}
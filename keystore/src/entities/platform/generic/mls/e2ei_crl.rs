@@ -49,7 +49,7 @@ impl EntityBase for E2eiCrl {
             use std::io::Read as _;
             let (rowid, distribution_point) = row_res?;
 
-            let mut blob = transaction.blob_open(rusqlite::DatabaseName::Main, "e2ei_crls", "content", rowid, false)?;
+            let mut blob = transaction.blob_open(rusqlite::DatabaseName::Main, "e2ei_crls", "content", rowid, true)?;
 
             let mut content = vec![];
             blob.read_to_end(&mut content)?;
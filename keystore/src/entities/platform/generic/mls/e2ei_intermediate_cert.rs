@@ -57,7 +57,7 @@ impl EntityBase for E2eiIntermediateCert {
                 "e2ei_intermediate_certs",
                 "content",
                 rowid,
-                false,
+                true,
             )?;
 
             let mut content = vec![];
@@ -16,7 +16,7 @@
 
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
-    entities::{E2eiEnrollment, Entity, EntityBase, EntityFindParams, StringEntityId},
+    entities::{E2eiEnrollment, Entity, EntityBase, EntityFindParams, StringEntityId, E2EI_ENROLLMENT_TTL},
     CryptoKeystoreError, MissingKeyErrorKind,
 };
 
@@ -24,6 +24,12 @@ impl Entity for E2eiEnrollment {
     fn id_raw(&self) -> &[u8] {
         &self.id[..]
     }
+
+    const TTL: Option<std::time::Duration> = Some(E2EI_ENROLLMENT_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -41,22 +47,26 @@ impl EntityBase for E2eiEnrollment {
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
         let transaction = conn.transaction()?;
-        let query: String = format!("SELECT rowid, id FROM e2ei_enrollment {}", params.to_sql());
+        let query: String = format!("SELECT rowid, id, created_at FROM e2ei_enrollment {}", params.to_sql());
 
         let mut stmt = transaction.prepare_cached(&query)?;
-        let mut rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        let mut rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?;
         let entities = rows.try_fold(Vec::new(), |mut acc, row_res| {
             use std::io::Read as _;
-            let (rowid, id) = row_res?;
+            let (rowid, id, created_at) = row_res?;
 
             let mut blob =
-                transaction.blob_open(rusqlite::DatabaseName::Main, "e2ei_enrollment", "content", rowid, false)?;
+                transaction.blob_open(rusqlite::DatabaseName::Main, "e2ei_enrollment", "content", rowid, true)?;
 
             let mut content = vec![];
             blob.read_to_end(&mut content)?;
             blob.close()?;
 
-            acc.push(Self { id, content });
+            acc.push(Self {
+                id,
+                content,
+                created_at,
+            });
 
             crate::CryptoKeystoreResult::Ok(acc)
         })?;
@@ -82,7 +92,10 @@ impl EntityBase for E2eiEnrollment {
         } else {
             let zb = rusqlite::blob::ZeroBlob(self.content.len() as i32);
             let params: [rusqlite::types::ToSqlOutput; 2] = [self.id.to_sql()?, zb.to_sql()?];
-            transaction.execute("INSERT INTO e2ei_enrollment (id, content) VALUES (?, ?)", params)?;
+            transaction.execute(
+                "INSERT INTO e2ei_enrollment (id, content, created_at) VALUES (?, ?, unixepoch())",
+                params,
+            )?;
             transaction.last_insert_rowid()
         };
 
@@ -109,13 +122,15 @@ impl EntityBase for E2eiEnrollment {
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
-        let mut row_id = transaction
-            .query_row("SELECT rowid FROM e2ei_enrollment WHERE id = ?", [id.as_slice()], |r| {
-                r.get::<_, i64>(0)
-            })
+        let mut row = transaction
+            .query_row(
+                "SELECT rowid, created_at FROM e2ei_enrollment WHERE id = ?",
+                [id.as_slice()],
+                |r| Ok((r.get::<_, i64>(0)?, r.get(1)?)),
+            )
             .optional()?;
 
-        if let Some(rowid) = row_id.take() {
+        if let Some((rowid, created_at)) = row.take() {
             let mut blob =
                 transaction.blob_open(rusqlite::DatabaseName::Main, "e2ei_enrollment", "content", rowid, true)?;
             use std::io::Read as _;
@@ -128,6 +143,7 @@ impl EntityBase for E2eiEnrollment {
             Ok(Some(Self {
                 id: id.to_bytes(),
                 content: buf,
+                created_at,
             }))
         } else {
             Ok(None)
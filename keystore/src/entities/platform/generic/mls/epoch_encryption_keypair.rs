@@ -14,10 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
-use crate::entities::MlsEpochEncryptionKeyPair;
+use crate::entities::{MlsEpochEncryptionKeyPair, EPOCH_KEYPAIR_ID_SUFFIX_LEN};
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
-    entities::{Entity, EntityBase, EntityFindParams, StringEntityId},
+    entities::{ConversationScopedEntity, Entity, EntityBase, EntityFindParams, StringEntityId},
     MissingKeyErrorKind,
 };
 use std::io::{Read, Write};
@@ -26,6 +26,12 @@ impl Entity for MlsEpochEncryptionKeyPair {
     fn id_raw(&self) -> &[u8] {
         self.id.as_slice()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_epoch_encryption_keypairs");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.keypairs.as_slice()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -191,6 +197,44 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
         }
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_epoch_encryption_keypairs", "id", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for rowid in rowids {
+            let mut blob = transaction.blob_open(
+                rusqlite::DatabaseName::Main,
+                "mls_epoch_encryption_keypairs",
+                "id",
+                rowid,
+                true,
+            )?;
+            let mut id = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut id)?;
+            blob.close()?;
+
+            let mut blob = transaction.blob_open(
+                rusqlite::DatabaseName::Main,
+                "mls_epoch_encryption_keypairs",
+                "keypairs",
+                rowid,
+                true,
+            )?;
+            let mut keypairs = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut keypairs)?;
+            blob.close()?;
+
+            res.push(Self { id, keypairs });
+        }
+
+        Ok(res)
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_epoch_encryption_keypairs", [], |r| r.get(0))?)
     }
@@ -215,3 +259,31 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
         }
     }
 }
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsEpochEncryptionKeyPair {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> crate::CryptoKeystoreResult<Vec<Vec<u8>>> {
+        // The length check leaves out the groups whose id merely starts with this one
+        let upper_bound = [conversation_id, &[u8::MAX; EPOCH_KEYPAIR_ID_SUFFIX_LEN]].concat();
+        let len = upper_bound.len();
+
+        let transaction = conn.transaction()?;
+        let ids = transaction
+            .prepare_cached(
+                "SELECT id FROM mls_epoch_encryption_keypairs WHERE id BETWEEN ?1 AND ?2 AND length(id) = ?3",
+            )?
+            .query_map(rusqlite::params![conversation_id, upper_bound, len], |r| r.get(0))?
+            .collect::<Result<Vec<Vec<u8>>, _>>()?;
+        transaction.execute(
+            "DELETE FROM mls_epoch_encryption_keypairs WHERE id BETWEEN ?1 AND ?2 AND length(id) = ?3",
+            rusqlite::params![conversation_id, upper_bound, len],
+        )?;
+        transaction.commit()?;
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,142 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::KeystoreDatabaseConnection,
+    entities::{Entity, EntityBase, EntityFindParams, MlsEpochRecord, StringEntityId},
+    MissingKeyErrorKind,
+};
+
+impl Entity for MlsEpochRecord {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+}
+
+impl MlsEpochRecord {
+    fn from_row(r: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: r.get(0)?,
+            conversation_id: r.get(1)?,
+            epoch: r.get::<_, i64>(2)? as u64,
+            authenticator_hash: r.get(3)?,
+            timestamp: r.get::<_, i64>(4)? as u64,
+            commit_sender: r.get(5)?,
+        })
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsEpochRecord {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsEpochRecord
+    }
+
+    async fn find_all(
+        conn: &mut Self::ConnectionType,
+        params: EntityFindParams,
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let query: String = format!(
+            "SELECT id, conversation_id, epoch, authenticator_hash, timestamp, commit_sender FROM mls_epoch_history {}",
+            params.to_sql()
+        );
+
+        let mut stmt = transaction.prepare_cached(&query)?;
+        let entities = stmt.query_map([], Self::from_row)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entities)
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO mls_epoch_history (id, conversation_id, epoch, authenticator_hash, timestamp, commit_sender) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                self.id,
+                self.conversation_id,
+                self.epoch as i64,
+                self.authenticator_hash,
+                self.timestamp as i64,
+                self.commit_sender
+            ],
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        use rusqlite::OptionalExtension as _;
+
+        let transaction = conn.transaction()?;
+        Ok(transaction
+            .query_row(
+                "SELECT id, conversation_id, epoch, authenticator_hash, timestamp, commit_sender FROM mls_epoch_history WHERE id = ?",
+                [id.as_slice()],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_epoch_history", "id", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for rowid in rowids {
+            res.push(transaction.query_row(
+                "SELECT id, conversation_id, epoch, authenticator_hash, timestamp, commit_sender FROM mls_epoch_history WHERE rowid = ?",
+                [rowid],
+                Self::from_row,
+            )?);
+        }
+
+        Ok(res)
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        Ok(conn.query_row("SELECT COUNT(*) FROM mls_epoch_history", [], |r| r.get(0))?)
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        let len = ids.len();
+        let mut updated = 0;
+        for id in ids {
+            updated += transaction.execute("DELETE FROM mls_epoch_history WHERE id = ?", [id.as_slice()])?;
+        }
+
+        if updated == len {
+            transaction.commit()?;
+            Ok(())
+        } else {
+            transaction.rollback()?;
+            Err(Self::to_missing_key_err_kind().into())
+        }
+    }
+}
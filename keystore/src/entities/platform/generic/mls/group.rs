@@ -17,13 +17,19 @@
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
     entities::{Entity, EntityBase, EntityFindParams, PersistedMlsGroup, PersistedMlsGroupExt, StringEntityId},
-    CryptoKeystoreError, CryptoKeystoreResult, MissingKeyErrorKind,
+    CryptoKeystoreResult, MissingKeyErrorKind,
 };
 
 impl Entity for PersistedMlsGroup {
     fn id_raw(&self) -> &[u8] {
         self.id.as_slice()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_groups");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.state.as_slice(), self.parent_id.as_deref().unwrap_or_default()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -185,22 +191,11 @@ impl EntityBase for PersistedMlsGroup {
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        let mut stmt = conn.prepare_cached("SELECT rowid FROM mls_groups ORDER BY rowid ASC")?;
-        let rowids: Vec<i64> = stmt
-            .query_map([], |r| r.get(0))?
-            .map(|r| r.map_err(CryptoKeystoreError::from))
-            .collect::<crate::CryptoKeystoreResult<_>>()?;
-
-        drop(stmt);
-
-        if rowids.is_empty() {
-            return Ok(Default::default());
-        }
-
         let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_groups", "id", &ids)?;
 
         let mut res = Vec::with_capacity(rowids.len());
         for rowid in rowids.into_iter() {
@@ -167,6 +167,34 @@ impl EntityBase for MlsHpkePrivateKey {
         }
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_hpke_private_keys", "pk", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for rowid in rowids {
+            let mut blob =
+                transaction.blob_open(rusqlite::DatabaseName::Main, "mls_hpke_private_keys", "pk", rowid, true)?;
+            let mut pk = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut pk)?;
+            blob.close()?;
+
+            let mut blob =
+                transaction.blob_open(rusqlite::DatabaseName::Main, "mls_hpke_private_keys", "sk", rowid, true)?;
+            let mut sk = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut sk)?;
+            blob.close()?;
+
+            res.push(Self { pk, sk });
+        }
+
+        Ok(res)
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_hpke_private_keys", [], |r| r.get(0))?)
     }
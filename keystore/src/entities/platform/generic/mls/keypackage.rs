@@ -198,6 +198,47 @@ impl EntityBase for MlsKeyPackage {
         }
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_keypackages", "keypackage_ref", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for rowid in rowids {
+            let mut blob = transaction.blob_open(
+                rusqlite::DatabaseName::Main,
+                "mls_keypackages",
+                "keypackage_ref",
+                rowid,
+                true,
+            )?;
+            let mut keypackage_ref = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut keypackage_ref)?;
+            blob.close()?;
+
+            let mut blob = transaction.blob_open(
+                rusqlite::DatabaseName::Main,
+                "mls_keypackages",
+                "keypackage",
+                rowid,
+                true,
+            )?;
+            let mut keypackage = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut keypackage)?;
+            blob.close()?;
+
+            res.push(Self {
+                keypackage_ref,
+                keypackage,
+            });
+        }
+
+        Ok(res)
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         let count: usize = conn.query_row("SELECT COUNT(*) FROM mls_keypackages", [], |r| r.get(0))?;
         Ok(count)
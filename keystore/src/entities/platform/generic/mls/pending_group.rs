@@ -17,14 +17,30 @@
 use crate::connection::DatabaseConnection;
 use crate::{
     connection::KeystoreDatabaseConnection,
-    entities::{Entity, EntityBase, EntityFindParams, PersistedMlsPendingGroup, StringEntityId},
-    CryptoKeystoreError, MissingKeyErrorKind,
+    entities::{Entity, EntityBase, EntityFindParams, PersistedMlsPendingGroup, StringEntityId, PENDING_GROUP_TTL},
+    MissingKeyErrorKind,
 };
 
 impl Entity for PersistedMlsPendingGroup {
     fn id_raw(&self) -> &[u8] {
         self.id.as_slice()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_pending_groups");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![
+            self.state.as_slice(),
+            self.custom_configuration.as_slice(),
+            self.parent_id.as_deref().unwrap_or_default(),
+        ]
+    }
+
+    const TTL: Option<std::time::Duration> = Some(PENDING_GROUP_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -62,7 +78,7 @@ impl EntityBase for PersistedMlsPendingGroup {
         {
             use rusqlite::ToSql as _;
             transaction.execute(
-                "UPDATE mls_pending_groups SET state = ?, parent_id = ?, cfg = ? WHERE id = ?",
+                "UPDATE mls_pending_groups SET state = ?, parent_id = ?, cfg = ?, created_at = unixepoch() WHERE id = ?",
                 [&zb.to_sql()?, &zpid.to_sql()?, &zcfg.to_sql()?, &self.id.to_sql()?],
             )?;
             rowid
@@ -71,7 +87,7 @@ impl EntityBase for PersistedMlsPendingGroup {
 
             use rusqlite::ToSql as _;
             transaction.execute(
-                "INSERT INTO mls_pending_groups (id, state, cfg, parent_id) VALUES(?, ?, ?, ?)",
+                "INSERT INTO mls_pending_groups (id, state, cfg, parent_id, created_at) VALUES(?, ?, ?, ?, unixepoch())",
                 [&zid.to_sql()?, &zb.to_sql()?, &zcfg.to_sql()?, &zpid.to_sql()?],
             )?;
             let rowid = transaction.last_insert_rowid();
@@ -126,15 +142,15 @@ impl EntityBase for PersistedMlsPendingGroup {
         use std::io::Read as _;
 
         let transaction = conn.transaction()?;
-        let rowid: Option<i64> = transaction
+        let row: Option<(i64, u64)> = transaction
             .query_row(
-                "SELECT rowid FROM mls_pending_groups WHERE id = ?",
+                "SELECT rowid, created_at FROM mls_pending_groups WHERE id = ?",
                 [&id.as_slice()],
-                |r| r.get(0),
+                |r| Ok((r.get(0)?, r.get(1)?)),
             )
             .optional()?;
-        match rowid {
-            Some(rowid) => {
+        match row {
+            Some((rowid, created_at)) => {
                 let mut blob =
                     transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_groups", "id", rowid, true)?;
                 let mut id = vec![];
@@ -173,6 +189,7 @@ impl EntityBase for PersistedMlsPendingGroup {
                     state,
                     parent_id,
                     custom_configuration,
+                    created_at,
                 }))
             }
             None => Ok(None),
@@ -184,13 +201,13 @@ impl EntityBase for PersistedMlsPendingGroup {
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
         let transaction = conn.transaction()?;
-        let query: String = format!("SELECT rowid FROM mls_pending_groups {}", params.to_sql());
+        let query: String = format!("SELECT rowid, created_at FROM mls_pending_groups {}", params.to_sql());
 
         let mut stmt = transaction.prepare_cached(&query)?;
-        let mut rows = stmt.query_map([], |r| r.get(0))?;
-        let entities = rows.try_fold(Vec::new(), |mut acc, rowid_result| {
+        let mut rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        let entities = rows.try_fold(Vec::new(), |mut acc, row_result| {
             use std::io::Read as _;
-            let rowid = rowid_result?;
+            let (rowid, created_at) = row_result?;
 
             let mut blob =
                 transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_groups", "id", rowid, true)?;
@@ -230,6 +247,7 @@ impl EntityBase for PersistedMlsPendingGroup {
                 state,
                 parent_id,
                 custom_configuration,
+                created_at,
             });
             crate::CryptoKeystoreResult::Ok(acc)
         })?;
@@ -239,27 +257,22 @@ impl EntityBase for PersistedMlsPendingGroup {
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        let mut stmt = conn.prepare_cached("SELECT rowid FROM mls_pending_groups ORDER BY rowid ASC")?;
-        let rowids: Vec<i64> = stmt
-            .query_map([], |r| r.get(0))?
-            .map(|r| r.map_err(CryptoKeystoreError::from))
-            .collect::<crate::CryptoKeystoreResult<_>>()?;
-
-        drop(stmt);
-
-        if rowids.is_empty() {
-            return Ok(Default::default());
-        }
-
         let transaction = conn.transaction()?;
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        let rowids = transaction.find_rowids("mls_pending_groups", "id", &ids)?;
 
         let mut res = Vec::with_capacity(rowids.len());
         for rowid in rowids.into_iter() {
             use std::io::Read as _;
 
+            let created_at = transaction.query_row(
+                "SELECT created_at FROM mls_pending_groups WHERE rowid = ?",
+                [rowid],
+                |r| r.get(0),
+            )?;
+
             let mut blob =
                 transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_groups", "id", rowid, true)?;
             let mut id = vec![];
@@ -298,6 +311,7 @@ impl EntityBase for PersistedMlsPendingGroup {
                 state,
                 parent_id,
                 custom_configuration,
+                created_at,
             });
         }
 
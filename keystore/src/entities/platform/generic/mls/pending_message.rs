@@ -16,7 +16,10 @@
 
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
-    entities::{Entity, EntityBase, EntityFindParams, MlsPendingMessage, StringEntityId},
+    entities::{
+        ConversationScopedEntity, Entity, EntityBase, EntityFindParams, MlsPendingMessage, StringEntityId,
+        PENDING_MESSAGE_TTL,
+    },
     MissingKeyErrorKind,
 };
 
@@ -24,6 +27,12 @@ impl Entity for MlsPendingMessage {
     fn id_raw(&self) -> &[u8] {
         self.id.as_slice()
     }
+
+    const TTL: Option<std::time::Duration> = Some(PENDING_MESSAGE_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -49,7 +58,7 @@ impl EntityBase for MlsPendingMessage {
 
         use rusqlite::ToSql as _;
         transaction.execute(
-            "INSERT INTO mls_pending_messages (id, message) VALUES(?, ?)",
+            "INSERT INTO mls_pending_messages (id, message, created_at) VALUES(?, ?, unixepoch())",
             [&zid.to_sql()?, &zmsg.to_sql()?],
         )?;
         let rowid = transaction.last_insert_rowid();
@@ -83,15 +92,15 @@ impl EntityBase for MlsPendingMessage {
         use std::io::Read as _;
 
         let transaction = conn.transaction()?;
-        let rowid: Option<i64> = transaction
+        let row: Option<(i64, u64)> = transaction
             .query_row(
-                "SELECT rowid FROM mls_pending_messages WHERE id = ?",
+                "SELECT rowid, created_at FROM mls_pending_messages WHERE id = ?",
                 [&id.as_slice()],
-                |r| r.get(0),
+                |r| Ok((r.get(0)?, r.get(1)?)),
             )
             .optional()?;
-        match rowid {
-            Some(rowid) => {
+        match row {
+            Some((rowid, created_at)) => {
                 let mut blob =
                     transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_messages", "id", rowid, true)?;
                 let mut id = vec![];
@@ -109,7 +118,11 @@ impl EntityBase for MlsPendingMessage {
                 blob.read_to_end(&mut message)?;
                 blob.close()?;
 
-                Ok(Some(Self { id, message }))
+                Ok(Some(Self {
+                    id,
+                    message,
+                    created_at,
+                }))
             }
             None => Ok(None),
         }
@@ -120,13 +133,13 @@ impl EntityBase for MlsPendingMessage {
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
         let transaction = conn.transaction()?;
-        let query: String = format!("SELECT rowid FROM mls_pending_messages {}", params.to_sql());
+        let query: String = format!("SELECT rowid, created_at FROM mls_pending_messages {}", params.to_sql());
 
         let mut stmt = transaction.prepare_cached(&query)?;
-        let mut rows = stmt.query_map([], |r| r.get(0))?;
-        let entities = rows.try_fold(Vec::new(), |mut acc, rowid_result| {
+        let mut rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        let entities = rows.try_fold(Vec::new(), |mut acc, row_result| {
             use std::io::Read as _;
-            let rowid = rowid_result?;
+            let (rowid, created_at) = row_result?;
 
             let mut blob =
                 transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_messages", "id", rowid, true)?;
@@ -145,7 +158,11 @@ impl EntityBase for MlsPendingMessage {
             blob.read_to_end(&mut message)?;
             blob.close()?;
 
-            acc.push(Self { id, message });
+            acc.push(Self {
+                id,
+                message,
+                created_at,
+            });
             crate::CryptoKeystoreResult::Ok(acc)
         })?;
 
@@ -179,3 +196,19 @@ impl EntityBase for MlsPendingMessage {
         }
     }
 }
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsPendingMessage {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> crate::CryptoKeystoreResult<Vec<Vec<u8>>> {
+        // buffered messages are identified by the id of their conversation
+        let transaction = conn.transaction()?;
+        let removed = transaction.execute("DELETE FROM mls_pending_messages WHERE id = ?", [conversation_id])?;
+        transaction.commit()?;
+
+        Ok(vec![conversation_id.to_vec(); removed])
+    }
+}
@@ -0,0 +1,148 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::KeystoreDatabaseConnection,
+    entities::{ConversationScopedEntity, Entity, EntityBase, EntityFindParams, MlsSeenCredential, StringEntityId},
+    MissingKeyErrorKind,
+};
+
+impl Entity for MlsSeenCredential {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsSeenCredential {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsSeenCredential
+    }
+
+    async fn find_all(
+        conn: &mut Self::ConnectionType,
+        params: EntityFindParams,
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let query: String = format!(
+            "SELECT id, conversation_id, client_id, credential, first_seen FROM mls_seen_credentials {}",
+            params.to_sql()
+        );
+
+        let mut stmt = transaction.prepare_cached(&query)?;
+        let entities = stmt
+            .query_map([], |r| {
+                Ok(Self {
+                    id: r.get(0)?,
+                    conversation_id: r.get(1)?,
+                    client_id: r.get(2)?,
+                    credential: r.get(3)?,
+                    first_seen: r.get::<_, i64>(4)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entities)
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO mls_seen_credentials (id, conversation_id, client_id, credential, first_seen) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![
+                self.id,
+                self.conversation_id,
+                self.client_id,
+                self.credential,
+                self.first_seen as i64
+            ],
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        use rusqlite::OptionalExtension as _;
+
+        let transaction = conn.transaction()?;
+        Ok(transaction
+            .query_row(
+                "SELECT id, conversation_id, client_id, credential, first_seen FROM mls_seen_credentials WHERE id = ?",
+                [id.as_slice()],
+                |r| {
+                    Ok(Self {
+                        id: r.get(0)?,
+                        conversation_id: r.get(1)?,
+                        client_id: r.get(2)?,
+                        credential: r.get(3)?,
+                        first_seen: r.get::<_, i64>(4)? as u64,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        Ok(conn.query_row("SELECT COUNT(*) FROM mls_seen_credentials", [], |r| r.get(0))?)
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let transaction = conn.transaction()?;
+        let len = ids.len();
+        let mut updated = 0;
+        for id in ids {
+            updated += transaction.execute("DELETE FROM mls_seen_credentials WHERE id = ?", [id.as_slice()])?;
+        }
+
+        if updated == len {
+            transaction.commit()?;
+            Ok(())
+        } else {
+            transaction.rollback()?;
+            Err(Self::to_missing_key_err_kind().into())
+        }
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsSeenCredential {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> crate::CryptoKeystoreResult<Vec<Vec<u8>>> {
+        let transaction = conn.transaction()?;
+        let ids = transaction
+            .prepare_cached("SELECT id FROM mls_seen_credentials WHERE conversation_id = ?")?
+            .query_map([conversation_id], |r| r.get(0))?
+            .collect::<Result<Vec<Vec<u8>>, _>>()?;
+        transaction.execute(
+            "DELETE FROM mls_seen_credentials WHERE conversation_id = ?",
+            [conversation_id],
+        )?;
+        transaction.commit()?;
+
+        Ok(ids)
+    }
+}
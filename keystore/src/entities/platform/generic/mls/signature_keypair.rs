@@ -25,6 +25,12 @@ impl Entity for MlsSignatureKeyPair {
     fn id_raw(&self) -> &[u8] {
         self.pk.as_slice()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_signature_keypairs");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.keypair.as_slice(), self.credential_id.as_slice()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
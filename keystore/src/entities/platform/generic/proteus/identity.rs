@@ -27,6 +27,12 @@ impl Entity for ProteusIdentity {
     fn id_raw(&self) -> &[u8] {
         b"1"
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_identities");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.sk.as_slice(), self.pk.as_slice()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
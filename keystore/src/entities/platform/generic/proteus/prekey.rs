@@ -26,6 +26,12 @@ impl Entity for ProteusPrekey {
     fn id_raw(&self) -> &[u8] {
         self.id_bytes()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_prekeys");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.prekey.as_slice()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -95,6 +101,35 @@ impl EntityBase for ProteusPrekey {
         }
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let transaction = conn.transaction()?;
+        let ids: Vec<u16> = ids
+            .iter()
+            .map(|id| ProteusPrekey::id_from_slice(id.as_slice()))
+            .collect();
+        let rowids = transaction.find_rowids("proteus_prekeys", "id", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for row_id in rowids {
+            let id = transaction.query_row("SELECT id FROM proteus_prekeys WHERE rowid = ?", [row_id], |r| r.get(0))?;
+
+            let mut blob =
+                transaction.blob_open(rusqlite::DatabaseName::Main, "proteus_prekeys", "key", row_id, true)?;
+
+            use std::io::Read as _;
+            let mut buf = Vec::with_capacity(blob.len());
+            blob.read_to_end(&mut buf)?;
+            blob.close()?;
+
+            res.push(Self::from_raw(id, buf));
+        }
+
+        Ok(res)
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         Ok(conn.query_row("SELECT COUNT(*) FROM proteus_prekeys", [], |r| r.get(0))?)
     }
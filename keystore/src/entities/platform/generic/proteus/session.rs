@@ -15,7 +15,6 @@
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
 use crate::entities::{EntityFindParams, ProteusSession, StringEntityId};
-use crate::CryptoKeystoreError;
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
     entities::{Entity, EntityBase},
@@ -26,6 +25,12 @@ impl Entity for ProteusSession {
     fn id_raw(&self) -> &[u8] {
         self.id.as_bytes()
     }
+
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_sessions");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.session.as_slice()]
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -145,27 +150,22 @@ impl EntityBase for ProteusSession {
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        let mut stmt = conn.prepare_cached("SELECT rowid, id FROM proteus_sessions ORDER BY rowid ASC")?;
-        let rows: Vec<(i64, String)> = stmt
-            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
-            .map(|r| r.map_err(CryptoKeystoreError::from))
-            .collect::<crate::CryptoKeystoreResult<_>>()?;
-
-        drop(stmt);
-
-        if rows.is_empty() {
-            return Ok(Default::default());
-        }
-
         let transaction = conn.transaction()?;
-
-        let mut res = Vec::with_capacity(rows.len());
-        for (rowid, id) in rows.into_iter() {
+        // session ids are stored as text, which never compares equal to a blob
+        let ids = ids
+            .iter()
+            .map(|id| id.try_into())
+            .collect::<crate::CryptoKeystoreResult<Vec<String>>>()?;
+        let rowids = transaction.find_rowids("proteus_sessions", "id", &ids)?;
+
+        let mut res = Vec::with_capacity(rowids.len());
+        for rowid in rowids.into_iter() {
             use std::io::Read as _;
 
+            let id = transaction.query_row("SELECT id FROM proteus_sessions WHERE rowid = ?", [rowid], |r| r.get(0))?;
+
             let mut blob =
                 transaction.blob_open(rusqlite::DatabaseName::Main, "proteus_sessions", "session", rowid, true)?;
             let mut session = Vec::with_capacity(blob.len());
@@ -0,0 +1,87 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::{DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{Entity, EntityBase, EntityChecksum, EntityFindParams, StringEntityId},
+    CryptoKeystoreResult, MissingKeyErrorKind,
+};
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for EntityChecksum {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::EntityChecksum
+    }
+
+    async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>> {
+        let storage = conn.storage();
+        storage.get_all("entity_checksums", Some(params)).await
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        storage.save("entity_checksums", &mut [self.clone()]).await?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        conn.storage().get("entity_checksums", id.as_slice()).await
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        conn.storage().count("entity_checksums").await
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        let ids: Vec<Vec<u8>> = ids.iter().map(StringEntityId::to_bytes).collect();
+        storage.delete("entity_checksums", &ids).await
+    }
+}
+
+impl Entity for EntityChecksum {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+
+    // a checksum in clear would tell whether two entities have the same content
+    fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.checksum = Self::encrypt_data(cipher, self.checksum.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.checksum.len())?;
+        if let Some(previous_checksum) = self.previous_checksum.as_mut() {
+            *previous_checksum = Self::encrypt_data(cipher, previous_checksum.as_slice(), &self.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.checksum = Self::decrypt_data(cipher, self.checksum.as_slice(), self.aad())?;
+        if let Some(previous_checksum) = self.previous_checksum.as_mut() {
+            *previous_checksum = Self::decrypt_data(cipher, previous_checksum.as_slice(), &self.id)?;
+        }
+
+        Ok(())
+    }
+}
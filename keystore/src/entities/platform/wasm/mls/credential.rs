@@ -100,9 +100,9 @@ impl MlsCredentialExt for MlsCredential {
     async fn delete_by_credential(conn: &mut Self::ConnectionType, credential: Vec<u8>) -> CryptoKeystoreResult<()> {
         let storage = conn.storage_mut();
         let (collection, index) = ("mls_credentials", "credential");
-        match &mut storage.storage {
+        let id = match &storage.storage {
             WasmStorageWrapper::Persistent(rexie) => {
-                let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
+                let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
                 let store = transaction.store(collection)?;
                 let store_index = store.index(index)?;
                 let credential_js: wasm_bindgen::JsValue = js_sys::Uint8Array::from(&credential[..]).into();
@@ -114,14 +114,16 @@ impl MlsCredentialExt for MlsCredential {
 
                 let mut credential = serde_wasm_bindgen::from_value::<MlsCredential>(entity_raw)?;
                 credential.decrypt(&storage.cipher)?;
-
-                let id = js_sys::Uint8Array::from(credential.id.as_slice());
-                store.delete(&id.into()).await?;
+                credential.id.clone()
             }
             WasmStorageWrapper::InMemory(_) => {
                 // TODO: current table model does not fit in a hashmap (no more primary key)
+                return Ok(());
             }
-        }
+        };
+
+        // goes through the storage so that it takes part in the transaction in progress, if any
+        storage.delete(collection, &[id]).await?;
 
         Ok(())
     }
@@ -16,8 +16,8 @@
 
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
-    entities::{E2eiEnrollment, Entity, EntityBase, EntityFindParams, StringEntityId},
-    CryptoKeystoreError, CryptoKeystoreResult, MissingKeyErrorKind,
+    entities::{E2eiEnrollment, Entity, EntityBase, EntityFindParams, StringEntityId, E2EI_ENROLLMENT_TTL},
+    CryptoKeystoreResult, MissingKeyErrorKind,
 };
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
@@ -30,13 +30,15 @@ impl EntityBase for E2eiEnrollment {
         MissingKeyErrorKind::E2eiEnrollment
     }
 
-    async fn find_all(_conn: &mut Self::ConnectionType, _params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>> {
-        Err(CryptoKeystoreError::ImplementationError)
+    async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>> {
+        conn.storage().get_all("e2ei_enrollment", Some(params)).await
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<()> {
         let storage = conn.storage_mut();
-        storage.save("e2ei_enrollment", &mut [self.clone()]).await?;
+        let mut to_save = self.clone();
+        to_save.created_at = crate::connection::expiry::unix_timestamp()?;
+        storage.save("e2ei_enrollment", &mut [to_save]).await?;
         Ok(())
     }
 
@@ -44,8 +46,8 @@ impl EntityBase for E2eiEnrollment {
         conn.storage().get("e2ei_enrollment", id.as_slice()).await
     }
 
-    async fn count(_conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<usize> {
-        Err(CryptoKeystoreError::ImplementationError)
+    async fn count(conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<usize> {
+        conn.storage().count("e2ei_enrollment").await
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> CryptoKeystoreResult<()> {
@@ -60,6 +62,12 @@ impl Entity for E2eiEnrollment {
         &self.id[..]
     }
 
+    const TTL: Option<std::time::Duration> = Some(E2EI_ENROLLMENT_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.content = Self::encrypt_data(cipher, self.content.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.content.len())?;
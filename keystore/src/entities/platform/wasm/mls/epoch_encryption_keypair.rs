@@ -16,7 +16,10 @@
 
 use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
-    entities::{Entity, EntityBase, EntityFindParams, MlsEpochEncryptionKeyPair, StringEntityId},
+    entities::{
+        ConversationScopedEntity, Entity, EntityBase, EntityFindParams, MlsEpochEncryptionKeyPair, StringEntityId,
+        EPOCH_KEYPAIR_ID_SUFFIX_LEN,
+    },
     CryptoKeystoreResult, MissingKeyErrorKind,
 };
 
@@ -51,6 +54,14 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
         conn.storage().get("mls_epoch_encryption_keypairs", id.as_slice()).await
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("mls_epoch_encryption_keypairs", &ids).await
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         conn.storage().count("mls_epoch_encryption_keypairs").await
     }
@@ -67,6 +78,12 @@ impl Entity for MlsEpochEncryptionKeyPair {
         self.id.as_slice()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_epoch_encryption_keypairs");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.keypairs.as_slice()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.keypairs = Self::encrypt_data(cipher, self.keypairs.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.keypairs.len())?;
@@ -80,3 +97,20 @@ impl Entity for MlsEpochEncryptionKeyPair {
         Ok(())
     }
 }
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsEpochEncryptionKeyPair {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> CryptoKeystoreResult<Vec<Vec<u8>>> {
+        conn.storage_mut()
+            .delete_prefixed(
+                "mls_epoch_encryption_keypairs",
+                conversation_id,
+                EPOCH_KEYPAIR_ID_SUFFIX_LEN,
+            )
+            .await
+    }
+}
@@ -0,0 +1,98 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::{DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{Entity, EntityBase, EntityFindParams, MlsEpochRecord, StringEntityId},
+    CryptoKeystoreResult, MissingKeyErrorKind,
+};
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsEpochRecord {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsEpochRecord
+    }
+
+    async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>> {
+        let storage = conn.storage();
+        storage.get_all("mls_epoch_history", Some(params)).await
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        storage.save("mls_epoch_history", &mut [self.clone()]).await?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        conn.storage().get("mls_epoch_history", id.as_slice()).await
+    }
+
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("mls_epoch_history", &ids).await
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        conn.storage().count("mls_epoch_history").await
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        let ids: Vec<Vec<u8>> = ids.iter().map(StringEntityId::to_bytes).collect();
+        storage.delete("mls_epoch_history", &ids).await
+    }
+}
+
+impl Entity for MlsEpochRecord {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+
+    fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.conversation_id = Self::encrypt_data(cipher, self.conversation_id.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.conversation_id.len())?;
+        self.authenticator_hash = Self::encrypt_data(cipher, self.authenticator_hash.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.authenticator_hash.len())?;
+        if let Some(commit_sender) = self.commit_sender.as_mut() {
+            *commit_sender = Self::encrypt_data(cipher, commit_sender.as_slice(), &self.id)?;
+            Self::ConnectionType::check_buffer_size(commit_sender.len())?;
+        }
+
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.conversation_id = Self::decrypt_data(cipher, self.conversation_id.as_slice(), self.aad())?;
+        self.authenticator_hash = Self::decrypt_data(cipher, self.authenticator_hash.as_slice(), self.aad())?;
+        if let Some(commit_sender) = self.commit_sender.as_mut() {
+            *commit_sender = Self::decrypt_data(cipher, commit_sender.as_slice(), &self.id)?;
+        }
+
+        Ok(())
+    }
+}
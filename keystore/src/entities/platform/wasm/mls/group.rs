@@ -18,7 +18,7 @@ use crate::{
     connection::{DatabaseConnection, KeystoreDatabaseConnection},
     entities::{
         Entity, EntityBase, EntityFindParams, PersistedMlsGroup, PersistedMlsGroupExt, PersistedMlsPendingGroup,
-        StringEntityId,
+        StringEntityId, PENDING_GROUP_TTL,
     },
     CryptoKeystoreResult, MissingKeyErrorKind,
 };
@@ -53,11 +53,10 @@ impl EntityBase for PersistedMlsGroup {
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        let storage = conn.storage();
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        storage.get_all("mls_groups", None).await
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("mls_groups", &ids).await
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
@@ -78,6 +77,12 @@ impl Entity for PersistedMlsGroup {
         self.id.as_slice()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_groups");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.state.as_slice(), self.parent_id.as_deref().unwrap_or_default()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.state = Self::encrypt_data(cipher, self.state.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.state.len())?;
@@ -118,7 +123,9 @@ impl EntityBase for PersistedMlsPendingGroup {
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
         let storage = conn.storage_mut();
 
-        storage.save("mls_pending_groups", &mut [self.clone()]).await?;
+        let mut to_save = self.clone();
+        to_save.created_at = crate::connection::expiry::unix_timestamp()?;
+        storage.save("mls_pending_groups", &mut [to_save]).await?;
 
         Ok(())
     }
@@ -132,10 +139,10 @@ impl EntityBase for PersistedMlsPendingGroup {
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        conn.storage().get_all("mls_pending_groups", None).await
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("mls_pending_groups", &ids).await
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
@@ -154,6 +161,22 @@ impl Entity for PersistedMlsPendingGroup {
         self.id.as_slice()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_pending_groups");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![
+            self.state.as_slice(),
+            self.custom_configuration.as_slice(),
+            self.parent_id.as_deref().unwrap_or_default(),
+        ]
+    }
+
+    const TTL: Option<std::time::Duration> = Some(PENDING_GROUP_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
     fn id(&self) -> CryptoKeystoreResult<wasm_bindgen::JsValue> {
         Ok(js_sys::Uint8Array::from(self.id.as_slice()).into())
     }
@@ -49,6 +49,14 @@ impl EntityBase for MlsHpkePrivateKey {
         conn.storage().get("mls_hpke_private_keys", id.as_slice()).await
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("mls_hpke_private_keys", &ids).await
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         conn.storage().count("mls_hpke_private_keys").await
     }
@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
+pub mod conversation_counters;
+pub mod conversation_metadata;
 pub mod credential;
 pub mod e2ei_acme_ca;
 pub mod e2ei_crl;
@@ -21,10 +23,16 @@ pub mod e2ei_intermediate_cert;
 pub mod encryption_keypair;
 pub mod enrollment;
 pub mod epoch_encryption_keypair;
+pub mod epoch_record;
 pub mod group;
+pub mod group_index;
 pub mod hpke_private_key;
 pub mod keypackage;
+pub mod pending_commit;
 pub mod pending_message;
+pub mod pending_rotation;
+pub mod processed_welcome;
 pub mod psk_bundle;
 pub mod refresh_token;
+pub mod seen_credential;
 pub mod signature_keypair;
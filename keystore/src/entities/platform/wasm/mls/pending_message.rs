@@ -1,6 +1,9 @@
 use crate::{
     connection::KeystoreDatabaseConnection,
-    entities::{Entity, EntityBase, EntityFindParams, MlsPendingMessage, StringEntityId},
+    entities::{
+        ConversationScopedEntity, Entity, EntityBase, EntityFindParams, MlsPendingMessage, StringEntityId,
+        PENDING_MESSAGE_TTL,
+    },
     CryptoKeystoreResult, MissingKeyErrorKind,
 };
 
@@ -22,7 +25,9 @@ impl EntityBase for MlsPendingMessage {
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
         let storage = conn.storage_mut();
 
-        storage.save("mls_pending_messages", &mut [self.clone()]).await?;
+        let mut to_save = self.clone();
+        to_save.created_at = crate::connection::expiry::unix_timestamp()?;
+        storage.save("mls_pending_messages", &mut [to_save]).await?;
 
         Ok(())
     }
@@ -58,6 +63,12 @@ impl Entity for MlsPendingMessage {
         self.id.as_slice()
     }
 
+    const TTL: Option<std::time::Duration> = Some(PENDING_MESSAGE_TTL);
+
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
     fn id(&self) -> CryptoKeystoreResult<wasm_bindgen::JsValue> {
         Ok(js_sys::Uint8Array::from(self.id.as_slice()).into())
     }
@@ -78,3 +89,27 @@ impl Entity for MlsPendingMessage {
         Ok(())
     }
 }
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsPendingMessage {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> CryptoKeystoreResult<Vec<Vec<u8>>> {
+        // buffered messages are identified by the id of their conversation
+        if conn
+            .storage()
+            .get::<Self>("mls_pending_messages", conversation_id)
+            .await?
+            .is_none()
+        {
+            return Ok(vec![]);
+        }
+        conn.storage_mut()
+            .delete("mls_pending_messages", &[conversation_id])
+            .await?;
+
+        Ok(vec![conversation_id.to_vec()])
+    }
+}
@@ -0,0 +1,108 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::{
+    connection::{DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{ConversationScopedEntity, Entity, EntityBase, EntityFindParams, MlsSeenCredential, StringEntityId},
+    CryptoKeystoreResult, MissingKeyErrorKind,
+};
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl EntityBase for MlsSeenCredential {
+    type ConnectionType = KeystoreDatabaseConnection;
+    type AutoGeneratedFields = ();
+
+    fn to_missing_key_err_kind() -> MissingKeyErrorKind {
+        MissingKeyErrorKind::MlsSeenCredential
+    }
+
+    async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>> {
+        let storage = conn.storage();
+        storage.get_all("mls_seen_credentials", Some(params)).await
+    }
+
+    async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        storage.save("mls_seen_credentials", &mut [self.clone()]).await?;
+
+        Ok(())
+    }
+
+    async fn find_one(
+        conn: &mut Self::ConnectionType,
+        id: &StringEntityId,
+    ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        conn.storage().get("mls_seen_credentials", id.as_slice()).await
+    }
+
+    async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        conn.storage().count("mls_seen_credentials").await
+    }
+
+    async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let storage = conn.storage_mut();
+        let ids: Vec<Vec<u8>> = ids.iter().map(StringEntityId::to_bytes).collect();
+        storage.delete("mls_seen_credentials", &ids).await
+    }
+}
+
+impl Entity for MlsSeenCredential {
+    fn id_raw(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+
+    fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.conversation_id = Self::encrypt_data(cipher, self.conversation_id.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.conversation_id.len())?;
+        self.client_id = Self::encrypt_data(cipher, self.client_id.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.client_id.len())?;
+        self.credential = Self::encrypt_data(cipher, self.credential.as_slice(), self.aad())?;
+        Self::ConnectionType::check_buffer_size(self.credential.len())?;
+
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
+        self.conversation_id = Self::decrypt_data(cipher, self.conversation_id.as_slice(), self.aad())?;
+        self.client_id = Self::decrypt_data(cipher, self.client_id.as_slice(), self.aad())?;
+        self.credential = Self::decrypt_data(cipher, self.credential.as_slice(), self.aad())?;
+
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl ConversationScopedEntity for MlsSeenCredential {
+    async fn delete_all_by_conversation(
+        conn: &mut Self::ConnectionType,
+        conversation_id: &[u8],
+    ) -> CryptoKeystoreResult<Vec<Vec<u8>>> {
+        // The conversation id is encrypted at rest, so IndexedDB cannot index it
+        let ids = conn
+            .storage()
+            .get_all::<Self>("mls_seen_credentials", None)
+            .await?
+            .into_iter()
+            .filter(|e| e.conversation_id == conversation_id)
+            .map(|e| e.id.clone())
+            .collect::<Vec<_>>();
+        conn.storage_mut().delete("mls_seen_credentials", &ids).await?;
+
+        Ok(ids)
+    }
+}
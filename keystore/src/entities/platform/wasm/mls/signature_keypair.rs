@@ -65,6 +65,12 @@ impl Entity for MlsSignatureKeyPair {
         self.pk.as_slice()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("mls_signature_keypairs");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.keypair.as_slice(), self.credential_id.as_slice()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.keypair = Self::encrypt_data(cipher, self.keypair.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.keypair.len())?;
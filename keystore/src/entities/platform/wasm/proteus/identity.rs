@@ -69,6 +69,12 @@ impl Entity for ProteusIdentity {
         &[1u8]
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_identities");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.sk.as_slice(), self.pk.as_slice()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.pk = Self::encrypt_data(cipher, self.pk.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.pk.len())?;
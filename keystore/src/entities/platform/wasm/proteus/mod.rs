@@ -16,4 +16,5 @@
 
 pub(crate) mod identity;
 pub mod prekey;
+pub mod remote_identity;
 pub mod session;
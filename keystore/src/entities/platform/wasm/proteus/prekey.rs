@@ -48,6 +48,14 @@ impl EntityBase for ProteusPrekey {
         storage.get("proteus_prekeys", id.as_slice()).await
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("proteus_prekeys", &ids).await
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         let storage = conn.storage();
         storage.count("proteus_prekeys").await
@@ -65,6 +73,12 @@ impl Entity for ProteusPrekey {
         self.id_bytes()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_prekeys");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.prekey.as_slice()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.prekey = Self::encrypt_data(cipher, self.prekey.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.prekey.len())?;
@@ -48,6 +48,14 @@ impl EntityBase for ProteusSession {
         storage.get("proteus_sessions", id.as_slice()).await
     }
 
+    async fn find_many(
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
+    ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let ids: Vec<&[u8]> = ids.iter().map(StringEntityId::as_slice).collect();
+        conn.storage().get_many("proteus_sessions", &ids).await
+    }
+
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
         let storage = conn.storage();
         storage.count("proteus_sessions").await
@@ -65,6 +73,12 @@ impl Entity for ProteusSession {
         self.id.as_bytes()
     }
 
+    const CHECKSUM_COLLECTION: Option<&'static str> = Some("proteus_sessions");
+
+    fn checksummed_content(&self) -> Vec<&[u8]> {
+        vec![self.session.as_slice()]
+    }
+
     fn encrypt(&mut self, cipher: &aes_gcm::Aes256Gcm) -> CryptoKeystoreResult<()> {
         self.session = Self::encrypt_data(cipher, self.session.as_slice(), self.aad())?;
         Self::ConnectionType::check_buffer_size(self.session.len())?;
@@ -114,3 +114,18 @@ pub struct ProteusSession {
     pub id: String,
     pub session: Vec<u8>,
 }
+
+/// Remote identity a Proteus session has been created with. It outlives the session so that a later session with
+/// the same id can be checked against it
+#[derive(Debug, Clone, Zeroize, PartialEq, Eq)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ProteusRemoteIdentity {
+    /// Session id
+    pub id: String,
+    /// Hex-encoded fingerprint of the remote identity key
+    pub fingerprint: String,
+}
@@ -37,6 +37,24 @@ pub enum MissingKeyErrorKind {
     MlsPendingGroup,
     #[error("MLS Pending Messages")]
     MlsPendingMessages,
+    #[error("MLS Conversation Metadata")]
+    MlsConversationMetadata,
+    #[error("MLS Pending Commit")]
+    MlsPendingCommit,
+    #[error("MLS Pending Rotation")]
+    MlsPendingRotation,
+    #[error("MLS Conversation Counters")]
+    MlsConversationCounters,
+    #[error("MLS Processed Welcome")]
+    MlsProcessedWelcome,
+    #[error("MLS Seen Credential")]
+    MlsSeenCredential,
+    #[error("MLS Epoch Record")]
+    MlsEpochRecord,
+    #[error("MLS Group Index Entry")]
+    MlsGroupIndexEntry,
+    #[error("Entity Checksum")]
+    EntityChecksum,
     #[error("End-to-end identity enrollment")]
     E2eiEnrollment,
     #[error("OIDC refresh token")]
@@ -56,6 +74,9 @@ pub enum MissingKeyErrorKind {
     #[cfg(feature = "proteus-keystore")]
     #[error("Proteus Identity")]
     ProteusIdentity,
+    #[cfg(feature = "proteus-keystore")]
+    #[error("Proteus Remote Identity")]
+    ProteusRemoteIdentity,
 }
 
 /// Error type to represent various errors that can happen in the KeyStore
@@ -81,6 +102,32 @@ pub enum CryptoKeystoreError {
     AlreadyExists,
     #[error("The provided buffer is too big to be persisted in the store")]
     BlobTooBig,
+    #[error("A transaction is already in progress")]
+    TransactionInProgress,
+    #[error("There is no transaction in progress")]
+    NoTransactionInProgress,
+    #[error("Not enough storage left to persist {0} bytes")]
+    StorageQuotaExceeded(usize),
+    #[error("The store is already in use by another instance")]
+    StoreAlreadyInUse,
+    /// Another connection still reads from the store, hence its WAL could not be moved back into the database file
+    /// and truncated, e.g. when wiping it
+    #[error("Another connection is still reading from the store")]
+    StoreBusy,
+    /// The files of the store, or the keychain item holding its salt, can't be accessed until the device is unlocked,
+    /// see [crate::connection::ConnectionOptions::file_protection]
+    #[error("The store cannot be accessed while the device is locked")]
+    DeviceLocked,
+    /// The browser evicted some of the object stores of an existing store, e.g. under storage pressure: the keying
+    /// material it held is lost and the device has to be enrolled again. Only in browsers
+    #[error("The store has been evicted by the browser")]
+    StoreEvicted,
+    #[error("The key provider failed to wrap or unwrap the master key of the store: {0}")]
+    KeyProviderError(String),
+    /// The content read from the store doesn't match the checksum written along with it: the storage has been
+    /// corrupted, as opposed to a decryption failure caused by a wrong key
+    #[error("The stored {collection} {id} doesn't match its checksum, the storage is corrupted")]
+    CorruptedEntity { collection: &'static str, id: String },
     #[cfg(feature = "mls-keystore")]
     #[error(transparent)]
     KeyStoreValueTransformError(#[from] postcard::Error),
@@ -113,6 +160,9 @@ pub enum CryptoKeystoreError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     DbMigrationError(#[from] Box<refinery::Error>),
+    #[cfg(not(target_family = "wasm"))]
+    #[error("The unencrypted store cannot be migrated: {0}")]
+    UnencryptedMigrationError(String),
     #[cfg(test)]
     #[error(transparent)]
     MlsKeyPackageIdError(#[from] openmls::prelude::KeyPackageIdError),
@@ -199,13 +249,18 @@ impl proteus_traits::ProteusErrorCode for CryptoKeystoreError {
             CryptoKeystoreError::TryFromSliceError(_) => ProteusErrorKind::DecodeError,
             CryptoKeystoreError::LockPoisonError => ProteusErrorKind::OtherSystemError,
             CryptoKeystoreError::BlobTooBig => ProteusErrorKind::IoError,
+            CryptoKeystoreError::CorruptedEntity { .. } => ProteusErrorKind::IoError,
             #[cfg(feature = "mls-keystore")]
             CryptoKeystoreError::KeyStoreValueTransformError(_) => ProteusErrorKind::DecodeError,
             CryptoKeystoreError::IoError(_) => ProteusErrorKind::IoError,
+            CryptoKeystoreError::DeviceLocked => ProteusErrorKind::IoError,
+            CryptoKeystoreError::StoreEvicted => ProteusErrorKind::IoError,
             #[cfg(not(target_family = "wasm"))]
             CryptoKeystoreError::DbError(_) => ProteusErrorKind::IoError,
             #[cfg(not(target_family = "wasm"))]
             CryptoKeystoreError::DbMigrationError(_) => ProteusErrorKind::IoError,
+            #[cfg(not(target_family = "wasm"))]
+            CryptoKeystoreError::UnencryptedMigrationError(_) => ProteusErrorKind::IoError,
             CryptoKeystoreError::InvalidKeySize { .. } => ProteusErrorKind::InvalidArrayLen,
             CryptoKeystoreError::ParseIntError(_) => ProteusErrorKind::DecodeError,
             CryptoKeystoreError::HexDecodeError(_) => ProteusErrorKind::DecodeError,
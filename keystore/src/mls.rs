@@ -20,8 +20,8 @@ use openmls_traits::key_store::{MlsEntity, MlsEntityId};
 use crate::entities::MlsEpochEncryptionKeyPair;
 use crate::{
     entities::{
-        E2eiEnrollment, EntityFindParams, MlsEncryptionKeyPair, MlsHpkePrivateKey, MlsKeyPackage, MlsPskBundle,
-        MlsSignatureKeyPair, PersistedMlsGroup, PersistedMlsPendingGroup,
+        E2eiEnrollment, EntityFindParams, MlsEncryptionKeyPair, MlsGroupIndexEntry, MlsHpkePrivateKey, MlsKeyPackage,
+        MlsPskBundle, MlsSignatureKeyPair, PersistedMlsGroup, PersistedMlsPendingGroup,
     },
     CryptoKeystoreError, CryptoKeystoreResult, MissingKeyErrorKind,
 };
@@ -47,7 +47,8 @@ pub trait CryptoKeystoreMls: Sized {
     /// * `group_id` - group/conversation id
     async fn mls_group_exists(&self, group_id: &[u8]) -> bool;
 
-    /// Persists a `MlsGroup`
+    /// Persists a `MlsGroup`. When a [crate::connection::PersistenceFailureCallback] is registered, a failure is
+    /// reported to it and the state kept in memory to be written again on the next successful write
     ///
     /// # Arguments
     /// * `group_id` - group/conversation id
@@ -63,6 +64,30 @@ pub trait CryptoKeystoreMls: Sized {
         parent_group_id: Option<&[u8]>,
     ) -> CryptoKeystoreResult<()>;
 
+    /// Loads a single `MlsGroup` from the database. Returns its parent group id and its state
+    ///
+    /// # Arguments
+    /// * `group_id` - group/conversation id
+    ///
+    /// # Errors
+    /// Any common error that can happen during a database connection. IoError being a common error
+    /// for example.
+    async fn mls_group_load(&self, group_id: &[u8]) -> CryptoKeystoreResult<Option<(Option<Vec<u8>>, Vec<u8>)>>;
+
+    /// Loads several `MlsGroups` from the database at once. They are returned like in
+    /// [CryptoKeystoreMls::mls_groups_restore], the ones not found being left out
+    ///
+    /// # Arguments
+    /// * `group_ids` - group/conversation ids
+    ///
+    /// # Errors
+    /// Any common error that can happen during a database connection. IoError being a common error
+    /// for example.
+    async fn mls_groups_load(
+        &self,
+        group_ids: &[Vec<u8>],
+    ) -> CryptoKeystoreResult<std::collections::HashMap<Vec<u8>, (Option<Vec<u8>>, Vec<u8>)>>;
+
     /// Loads `MlsGroups` from the database. It will be returned as a `HashMap` where the key is
     /// the group/conversation id and the value the group state
     ///
@@ -73,6 +98,13 @@ pub trait CryptoKeystoreMls: Sized {
         &self,
     ) -> CryptoKeystoreResult<std::collections::HashMap<Vec<u8>, (Option<Vec<u8>>, Vec<u8>)>>;
 
+    /// Lists the metadata of the persisted `MlsGroups` without loading their state
+    ///
+    /// # Errors
+    /// Any common error that can happen during a database connection. IoError being a common error
+    /// for example.
+    async fn mls_groups_index(&self) -> CryptoKeystoreResult<Vec<MlsGroupIndexEntry>>;
+
     /// Deletes `MlsGroups` from the database.
     /// # Errors
     /// Any common error that can happen during a database connection. IoError being a common error
@@ -242,33 +274,100 @@ impl CryptoKeystoreMls for crate::connection::Connection {
         state: &[u8],
         parent_group_id: Option<&[u8]>,
     ) -> CryptoKeystoreResult<()> {
-        self.save(PersistedMlsGroup {
+        use crate::entities::Entity as _;
+
+        let mut timer = self
+            .slow_log
+            .start::<PersistedMlsGroup>(crate::connection::KeystoreOperation::Save);
+        timer.entities = 1;
+        timer.bytes = state.len();
+        let group = PersistedMlsGroup {
             id: group_id.into(),
             state: state.into(),
             parent_id: parent_group_id.map(Into::into),
-        })
-        .await?;
+        };
+        let mut conn = self.borrow_writer().await?;
+        let saved = match Self::stage_checksum(&mut conn, &group).await {
+            Ok(()) => group.save(&mut conn).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = saved {
+            // the group is rolled back along with the rest of the transaction instead, see `recovery`
+            if conn.in_transaction() {
+                return Err(e);
+            }
+            let callback = self.recovery.lock().await.mark_dirty(group, &e);
+            // the callback is free to use the keystore
+            drop(conn);
+            let Some(callback) = callback else {
+                return Err(e);
+            };
+            callback(group_id, &e);
+            return Ok(());
+        }
+        self.metrics.record_write();
+        self.metrics.record_bytes(state.len());
+        self.recovery.lock().await.discard(group_id);
+        self.flush_dirty_mls_groups(&mut conn).await;
 
         Ok(())
     }
 
     async fn mls_group_exists(&self, group_id: &[u8]) -> bool {
+        if self.recovery.lock().await.dirty_group(group_id).is_some() {
+            return true;
+        }
         matches!(self.find::<PersistedMlsGroup>(group_id).await, Ok(Some(_)))
     }
 
+    async fn mls_group_load(&self, group_id: &[u8]) -> CryptoKeystoreResult<Option<(Option<Vec<u8>>, Vec<u8>)>> {
+        let dirty = self.recovery.lock().await.dirty_group(group_id).cloned();
+        let group = match dirty {
+            Some(group) => Some(group),
+            None => self.find::<PersistedMlsGroup>(group_id).await?,
+        };
+        Ok(group.map(|group| (group.parent_id.clone(), group.state.clone())))
+    }
+
+    async fn mls_groups_load(
+        &self,
+        group_ids: &[Vec<u8>],
+    ) -> CryptoKeystoreResult<std::collections::HashMap<Vec<u8>, (Option<Vec<u8>>, Vec<u8>)>> {
+        let groups = self.find_many::<PersistedMlsGroup, _>(group_ids).await?;
+        let recovery = self.recovery.lock().await;
+        let dirty = group_ids.iter().filter_map(|id| recovery.dirty_group(id));
+        Ok(groups
+            .iter()
+            // the states which could not be persisted yet are more recent
+            .chain(dirty)
+            .map(|group: &PersistedMlsGroup| (group.id.clone(), (group.parent_id.clone(), group.state.clone())))
+            .collect())
+    }
+
     async fn mls_group_delete(&self, group_id: &[u8]) -> CryptoKeystoreResult<()> {
+        self.recovery.lock().await.discard(group_id);
         self.remove::<PersistedMlsGroup, _>(group_id).await?;
+        if self.find::<MlsGroupIndexEntry>(group_id).await?.is_some() {
+            self.remove::<MlsGroupIndexEntry, _>(group_id).await?;
+        }
 
         Ok(())
     }
 
+    async fn mls_groups_index(&self) -> CryptoKeystoreResult<Vec<MlsGroupIndexEntry>> {
+        self.find_all::<MlsGroupIndexEntry>(EntityFindParams::default()).await
+    }
+
     async fn mls_groups_restore(
         &self,
     ) -> CryptoKeystoreResult<std::collections::HashMap<Vec<u8>, (Option<Vec<u8>>, Vec<u8>)>> {
         let groups = self.find_all::<PersistedMlsGroup>(EntityFindParams::default()).await?;
+        let recovery = self.recovery.lock().await;
         Ok(groups
-            .into_iter()
-            .map(|group: PersistedMlsGroup| (group.id.clone(), (group.parent_id.clone(), group.state.clone())))
+            .iter()
+            // the states which could not be persisted yet are more recent
+            .chain(recovery.dirty_groups())
+            .map(|group: &PersistedMlsGroup| (group.id.clone(), (group.parent_id.clone(), group.state.clone())))
             .collect())
     }
 
@@ -284,8 +383,10 @@ impl CryptoKeystoreMls for crate::connection::Connection {
             state: mls_group.into(),
             custom_configuration: custom_configuration.into(),
             parent_id: parent_group_id.map(Into::into),
+            created_at: 0,
         })
         .await?;
+        self.metrics.record_bytes(mls_group.len() + custom_configuration.len());
         Ok(())
     }
 
@@ -306,8 +407,10 @@ impl CryptoKeystoreMls for crate::connection::Connection {
         self.save(E2eiEnrollment {
             id: id.into(),
             content: content.into(),
+            created_at: 0,
         })
         .await?;
+        self.metrics.record_bytes(content.len());
         Ok(())
     }
 
@@ -334,6 +437,14 @@ pub fn ser<T: MlsEntity>(value: &T) -> Result<Vec<u8>, CryptoKeystoreError> {
     Ok(postcard::to_stdvec(value)?)
 }
 
+/// Same as [ser] but serializes into `buf`, which is cleared first, so that its allocation can be reused
+#[inline(always)]
+pub fn ser_into<T: MlsEntity>(value: &T, buf: &mut Vec<u8>) -> Result<(), CryptoKeystoreError> {
+    buf.clear();
+    *buf = postcard::to_extend(value, std::mem::take(buf))?;
+    Ok(())
+}
+
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
 impl openmls_traits::key_store::OpenMlsKeyStore for crate::connection::Connection {
@@ -350,6 +461,7 @@ impl openmls_traits::key_store::OpenMlsKeyStore for crate::connection::Connectio
         }
 
         let data = ser(v)?;
+        let len = data.len();
 
         match V::ID {
             MlsEntityId::GroupState => {
@@ -402,6 +514,7 @@ impl openmls_traits::key_store::OpenMlsKeyStore for crate::connection::Connectio
             }
         }
 
+        self.metrics.record_bytes(len);
         Ok(())
     }
 
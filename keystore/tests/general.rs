@@ -44,6 +44,392 @@ pub mod tests {
         let store1 = setup("ios-wal-compat", false).await;
     }
 
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn can_read_concurrently_through_read_pool() {
+        use core_crypto_keystore::entities::MlsKeyPackage;
+
+        const NB_READERS: usize = 8;
+        let store = std::sync::Arc::new(
+            core_crypto_keystore::Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(
+            store.read_pool_size(),
+            core_crypto_keystore::connection::DEFAULT_READ_POOL_SIZE
+        );
+
+        let kp = MlsKeyPackage {
+            keypackage_ref: b"some-ref".to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+        store.save(kp.clone()).await.unwrap();
+
+        // writes are immediately visible to the readers
+        let readers = (0..NB_READERS)
+            .map(|_| {
+                let store = store.clone();
+                let kp = kp.clone();
+                async_std::task::spawn(async move {
+                    let found = store.find::<MlsKeyPackage>(&kp.keypackage_ref).await.unwrap();
+                    assert_eq!(found, Some(kp));
+                    assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 1);
+                })
+            })
+            .collect::<Vec<_>>();
+        for reader in readers {
+            reader.await;
+        }
+
+        let store = std::sync::Arc::into_inner(store).unwrap();
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn transaction_persists_only_on_commit(store: CryptoKeystore) {
+        use core_crypto_keystore::entities::MlsKeyPackage;
+
+        let store = store.await;
+        let kp = |r: &[u8]| MlsKeyPackage {
+            keypackage_ref: r.to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+
+        // rolled back writes are discarded
+        store.begin_transaction().await.unwrap();
+        assert!(store.begin_transaction().await.is_err());
+        store.save(kp(b"rolled-back")).await.unwrap();
+        // but visible within the transaction
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 1);
+        store.rollback_transaction().await.unwrap();
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 0);
+
+        // committed writes are persisted
+        store.begin_transaction().await.unwrap();
+        store.save(kp(b"committed-1")).await.unwrap();
+        store.save(kp(b"committed-2")).await.unwrap();
+        store.commit_transaction().await.unwrap();
+        assert!(store.commit_transaction().await.is_err());
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 2);
+
+        // so are removals
+        store.begin_transaction().await.unwrap();
+        store.remove::<MlsKeyPackage, _>(b"committed-1").await.unwrap();
+        assert!(store.find::<MlsKeyPackage>(b"committed-1").await.unwrap().is_none());
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 1);
+        store.rollback_transaction().await.unwrap();
+        assert!(store.find::<MlsKeyPackage>(b"committed-1").await.unwrap().is_some());
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), 2);
+
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn wipe_shreds_every_database_file() {
+        use core_crypto_keystore::entities::MlsKeyPackage;
+
+        let name = store_name();
+        let store = core_crypto_keystore::Connection::open_with_key(&name, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        store
+            .save(MlsKeyPackage {
+                keypackage_ref: b"some-ref".to_vec(),
+                keypackage: b"some-keypackage".to_vec(),
+            })
+            .await
+            .unwrap();
+        assert!(std::path::Path::new(&name).exists());
+
+        store.wipe().await.unwrap();
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            assert!(!std::path::Path::new(&format!("{name}{suffix}")).exists());
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn can_migrate_unencrypted_store() {
+        use core_crypto_keystore::entities::MlsKeyPackage;
+
+        // an empty key leaves the database unencrypted, as legacy stores are
+        let name = store_name();
+        let store = core_crypto_keystore::Connection::open_with_key(&name, "")
+            .await
+            .unwrap();
+        let refs = (0..10u32).map(|i| i.to_le_bytes().to_vec()).collect::<Vec<_>>();
+        for r in &refs {
+            store
+                .save(MlsKeyPackage {
+                    keypackage_ref: r.clone(),
+                    keypackage: b"some-keypackage".to_vec(),
+                })
+                .await
+                .unwrap();
+        }
+        store.close().await.unwrap();
+        let content = std::fs::read(&name).unwrap();
+        assert!(content.windows(15).any(|w| w == b"some-keypackage"));
+
+        let store = core_crypto_keystore::Connection::migrate_unencrypted(&name, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), refs.len());
+        store.close().await.unwrap();
+        let content = std::fs::read(&name).unwrap();
+        assert!(!content.windows(15).any(|w| w == b"some-keypackage"));
+        for suffix in [".encrypted", ".unencrypted"] {
+            assert!(!std::path::Path::new(&format!("{name}{suffix}")).exists());
+        }
+
+        // already encrypted, only opened
+        let store = core_crypto_keystore::Connection::migrate_unencrypted(&name, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        assert_eq!(store.count::<MlsKeyPackage>().await.unwrap(), refs.len());
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn vacuum_reclaims_space_of_deleted_entities() {
+        use core_crypto_keystore::entities::MlsKeyPackage;
+
+        let store = core_crypto_keystore::Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        let refs = (0..100u32).map(|i| i.to_le_bytes().to_vec()).collect::<Vec<_>>();
+        for r in &refs {
+            store
+                .save(MlsKeyPackage {
+                    keypackage_ref: r.clone(),
+                    keypackage: vec![42; 4096],
+                })
+                .await
+                .unwrap();
+        }
+        let before_removal = store.reclaimable_space().await.unwrap();
+
+        for r in &refs {
+            store.remove::<MlsKeyPackage, _>(r).await.unwrap();
+        }
+        assert!(store.reclaimable_space().await.unwrap() > before_removal);
+
+        store.vacuum().await.unwrap();
+        assert_eq!(store.reclaimable_space().await.unwrap(), 0);
+
+        teardown(store).await;
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    async fn slow_operations_are_logged_once_enabled() {
+        use core_crypto_keystore::{connection::KeystoreOperation, entities::MlsKeyPackage};
+
+        let store = CryptoKeystore::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        let kp = |r: &[u8]| MlsKeyPackage {
+            keypackage_ref: r.to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+
+        // disabled by default
+        store.save(kp(b"1")).await.unwrap();
+        assert!(store.slow_operations().is_empty());
+
+        // nothing is that slow
+        store.set_slow_operation_threshold(Some(std::time::Duration::from_secs(3600)));
+        store.save(kp(b"2")).await.unwrap();
+        assert!(store.slow_operations().is_empty());
+
+        // everything is
+        store.set_slow_operation_threshold(Some(std::time::Duration::ZERO));
+        store.save(kp(b"3")).await.unwrap();
+        store.find_all::<MlsKeyPackage>(Default::default()).await.unwrap();
+        let operations = store.slow_operations();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].entity, "MlsKeyPackage");
+        assert_eq!(operations[0].operation, KeystoreOperation::Save);
+        assert_eq!(operations[0].entities, 1);
+        assert_eq!(operations[1].operation, KeystoreOperation::FindAll);
+        assert_eq!(operations[1].entities, 3);
+
+        // only the latest ones are kept
+        for _ in 0..core_crypto_keystore::connection::SLOW_OPERATION_LOG_CAPACITY {
+            store.count::<MlsKeyPackage>().await.unwrap();
+        }
+        let operations = store.slow_operations();
+        assert_eq!(
+            operations.len(),
+            core_crypto_keystore::connection::SLOW_OPERATION_LOG_CAPACITY
+        );
+        assert!(operations.iter().all(|op| op.operation == KeystoreOperation::Count));
+
+        store.set_slow_operation_threshold(None);
+        assert!(store.slow_operations().is_empty());
+
+        teardown(store).await;
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    async fn store_is_used_by_one_instance_at_a_time() {
+        use core_crypto_keystore::{entities::MlsKeyPackage, CryptoKeystoreError};
+
+        let name = store_name();
+        let kp = |r: &[u8]| MlsKeyPackage {
+            keypackage_ref: r.to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+        let app = CryptoKeystore::open_with_key(&name, TEST_ENCRYPTION_KEY).await.unwrap();
+        assert!(matches!(
+            CryptoKeystore::open_with_key(&name, TEST_ENCRYPTION_KEY)
+                .await
+                .unwrap_err(),
+            CryptoKeystoreError::StoreAlreadyInUse
+        ));
+
+        // e.g. an app extension running while the app is suspended
+        let extension = CryptoKeystore::take_over_with_key(&name, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        extension.save(kp(b"extension")).await.unwrap();
+        assert!(matches!(
+            app.save(kp(b"app")).await.unwrap_err(),
+            CryptoKeystoreError::StoreAlreadyInUse
+        ));
+        // lookups are not affected
+        assert_eq!(app.count::<MlsKeyPackage>().await.unwrap(), 1);
+
+        // the app takes the store back when resumed
+        app.take_over().await.unwrap();
+        app.save(kp(b"app")).await.unwrap();
+        assert!(matches!(
+            extension.save(kp(b"extension-2")).await.unwrap_err(),
+            CryptoKeystoreError::StoreAlreadyInUse
+        ));
+        extension.close().await.unwrap();
+        assert_eq!(app.count::<MlsKeyPackage>().await.unwrap(), 2);
+
+        teardown(app).await;
+        #[cfg(not(target_family = "wasm"))]
+        assert!(!std::path::Path::new(&format!("{name}.lock")).exists());
+    }
+
+    /// Stands for the platform's secure hardware
+    #[derive(Debug, Default)]
+    struct XorKeyProvider {
+        broken: bool,
+        wrapped: std::sync::atomic::AtomicUsize,
+    }
+
+    #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+    impl core_crypto_keystore::connection::KeyProvider for XorKeyProvider {
+        async fn wrap_key(&self, key: &[u8]) -> core_crypto_keystore::CryptoKeystoreResult<Vec<u8>> {
+            self.wrapped.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(key.iter().map(|b| b ^ 0x5a).collect())
+        }
+
+        async fn unwrap_key(&self, wrapped_key: &[u8]) -> core_crypto_keystore::CryptoKeystoreResult<Vec<u8>> {
+            if self.broken {
+                return Err(core_crypto_keystore::CryptoKeystoreError::KeyProviderError(
+                    "hardware key is gone".to_string(),
+                ));
+            }
+            Ok(wrapped_key.iter().map(|b| b ^ 0x5a).collect())
+        }
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    async fn can_protect_master_key_with_key_provider() {
+        use core_crypto_keystore::{entities::MlsKeyPackage, CryptoKeystoreError};
+
+        let name = store_name();
+        let key_provider = XorKeyProvider::default();
+        let kp = MlsKeyPackage {
+            keypackage_ref: b"some-ref".to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+        let store = CryptoKeystore::open_with_key_provider(&name, &key_provider)
+            .await
+            .unwrap();
+        store.save(kp.clone()).await.unwrap();
+        store.close().await.unwrap();
+
+        // the master key is only generated once
+        let store = CryptoKeystore::open_with_key_provider(&name, &key_provider)
+            .await
+            .unwrap();
+        assert_eq!(key_provider.wrapped.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(store.find::<MlsKeyPackage>(&kp.keypackage_ref).await.unwrap(), Some(kp));
+        store.close().await.unwrap();
+
+        let broken_provider = XorKeyProvider {
+            broken: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            CryptoKeystore::open_with_key_provider(&name, &broken_provider)
+                .await
+                .unwrap_err(),
+            CryptoKeystoreError::KeyProviderError(_)
+        ));
+
+        let store = CryptoKeystore::open_with_key_provider(&name, &key_provider)
+            .await
+            .unwrap();
+        teardown(store).await;
+        #[cfg(not(target_family = "wasm"))]
+        assert!(!std::path::Path::new(&format!("{name}.key")).exists());
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    async fn can_open_with_options() {
+        use core_crypto_keystore::{
+            connection::{ConnectionOptions, FileProtectionClass},
+            entities::MlsKeyPackage,
+            CryptoKeystoreError,
+        };
+
+        let name = store_name();
+        let kp = MlsKeyPackage {
+            keypackage_ref: b"some-ref".to_vec(),
+            keypackage: b"some-keypackage".to_vec(),
+        };
+        let options = ConnectionOptions {
+            file_protection: Some(FileProtectionClass::CompleteUntilFirstUserAuthentication),
+            ..Default::default()
+        };
+        let store = CryptoKeystore::open_with_options(&name, TEST_ENCRYPTION_KEY, &options)
+            .await
+            .unwrap();
+        store.save(kp.clone()).await.unwrap();
+        assert!(matches!(
+            CryptoKeystore::open_with_options(&name, TEST_ENCRYPTION_KEY, &options)
+                .await
+                .unwrap_err(),
+            CryptoKeystoreError::StoreAlreadyInUse
+        ));
+        store.close().await.unwrap();
+
+        let options = ConnectionOptions {
+            file_protection: Some(FileProtectionClass::Complete),
+            ..options
+        };
+        let store = CryptoKeystore::open_with_options(&name, TEST_ENCRYPTION_KEY, &options)
+            .await
+            .unwrap();
+        assert_eq!(store.find::<MlsKeyPackage>(&kp.keypackage_ref).await.unwrap(), Some(kp));
+        teardown(store).await;
+    }
+
     #[cfg(target_family = "wasm")]
     #[wasm_bindgen_test]
     pub async fn can_migrate_new_idb_db_versions() {
@@ -251,4 +251,373 @@ pub mod tests {
 
     //     teardown(backend.unwrap_keystore()).await;
     // }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn keeps_groups_failing_to_persist_until_next_successful_write() {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        store.mls_group_persist(b"group", b"epoch-0", None).await.unwrap();
+
+        // simulates a full disk by preventing the database from growing any further
+        {
+            let conn = store.borrow_conn().await.unwrap();
+            let page_count = conn.query_row("PRAGMA page_count", [], |r| r.get::<_, u32>(0)).unwrap();
+            conn.pragma_update(None, "max_page_count", page_count).unwrap();
+        }
+        let large_state = vec![1u8; 1024 * 1024];
+
+        // without callback, the failure is returned as usual
+        assert!(store.mls_group_persist(b"group", &large_state, None).await.is_err());
+        assert!(store.dirty_mls_groups().await.is_empty());
+
+        let failures = std::sync::Arc::new(AtomicUsize::new(0));
+        let failures_clone = failures.clone();
+        store
+            .set_persistence_failure_callback(Some(Box::new(
+                move |group_id: &[u8], _: &core_crypto_keystore::CryptoKeystoreError| {
+                    assert_eq!(group_id, b"group");
+                    failures_clone.fetch_add(1, Ordering::SeqCst);
+                },
+            )))
+            .await;
+        store.mls_group_persist(b"group", &large_state, None).await.unwrap();
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+        assert_eq!(store.dirty_mls_groups().await, vec![b"group".to_vec()]);
+
+        // lookups see the latest state even though it is not on disk yet
+        let (_, state) = store.mls_group_load(b"group").await.unwrap().unwrap();
+        assert_eq!(state, large_state);
+        let (_, state) = store
+            .mls_groups_restore()
+            .await
+            .unwrap()
+            .remove(b"group".as_slice())
+            .unwrap();
+        assert_eq!(state, large_state);
+        let persisted = store.find::<PersistedMlsGroup>(b"group").await.unwrap().unwrap();
+        assert_eq!(persisted.state, b"epoch-0");
+
+        // once there's space again, the next write persists it as well
+        store
+            .borrow_conn()
+            .await
+            .unwrap()
+            .pragma_update(None, "max_page_count", 1_073_741_823)
+            .unwrap();
+        store
+            .save(MlsKeyPackage {
+                keypackage_ref: b"some-ref".to_vec(),
+                keypackage: b"some-keypackage".to_vec(),
+            })
+            .await
+            .unwrap();
+        assert!(store.dirty_mls_groups().await.is_empty());
+        let persisted = store.find::<PersistedMlsGroup>(b"group").await.unwrap().unwrap();
+        assert_eq!(persisted.state, large_state);
+
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn persistence_failure_callback_can_use_the_keystore() {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        use std::sync::{Arc, Mutex};
+
+        let store = Arc::new(
+            Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+                .await
+                .unwrap(),
+        );
+        store.mls_group_persist(b"group", b"epoch-0", None).await.unwrap();
+        {
+            let conn = store.borrow_conn().await.unwrap();
+            let page_count = conn.query_row("PRAGMA page_count", [], |r| r.get::<_, u32>(0)).unwrap();
+            conn.pragma_update(None, "max_page_count", page_count).unwrap();
+        }
+
+        let dirty_groups = Arc::new(Mutex::new(vec![]));
+        let (weak_store, dirty_groups_clone) = (Arc::downgrade(&store), dirty_groups.clone());
+        store
+            .set_persistence_failure_callback(Some(Box::new(
+                move |_: &[u8], _: &core_crypto_keystore::CryptoKeystoreError| {
+                    let store = weak_store.upgrade().unwrap();
+                    // would never complete if the keystore were still locked
+                    *dirty_groups_clone.lock().unwrap() = futures_lite::future::block_on(store.dirty_mls_groups());
+                },
+            )))
+            .await;
+        store
+            .mls_group_persist(b"group", &vec![1u8; 1024 * 1024], None)
+            .await
+            .unwrap();
+        assert_eq!(*dirty_groups.lock().unwrap(), vec![b"group".to_vec()]);
+
+        store.set_persistence_failure_callback(None).await;
+        teardown(Arc::try_unwrap(store).unwrap()).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn rolls_back_groups_failing_to_persist_within_a_transaction() {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        store.mls_group_persist(b"group", b"epoch-0", None).await.unwrap();
+        let failures = std::sync::Arc::new(AtomicUsize::new(0));
+        let failures_clone = failures.clone();
+        store
+            .set_persistence_failure_callback(Some(Box::new(
+                move |_: &[u8], _: &core_crypto_keystore::CryptoKeystoreError| {
+                    failures_clone.fetch_add(1, Ordering::SeqCst);
+                },
+            )))
+            .await;
+
+        store.begin_transaction().await.unwrap();
+        // e.g. a KeyPackage consumed by joining the group
+        store
+            .save(MlsKeyPackage {
+                keypackage_ref: b"some-ref".to_vec(),
+                keypackage: b"some-keypackage".to_vec(),
+            })
+            .await
+            .unwrap();
+        {
+            let conn = store.borrow_conn().await.unwrap();
+            let page_count = conn.query_row("PRAGMA page_count", [], |r| r.get::<_, u32>(0)).unwrap();
+            conn.pragma_update(None, "max_page_count", page_count).unwrap();
+        }
+
+        // the failure is returned so that the whole transaction is rolled back, nothing is kept aside
+        assert!(store
+            .mls_group_persist(b"group", &vec![1u8; 1024 * 1024], None)
+            .await
+            .is_err());
+        assert_eq!(failures.load(Ordering::SeqCst), 0);
+        assert!(store.dirty_mls_groups().await.is_empty());
+        store.rollback_transaction().await.unwrap();
+
+        assert!(store.find::<MlsKeyPackage>(b"some-ref").await.unwrap().is_none());
+        let persisted = store.find::<PersistedMlsGroup>(b"group").await.unwrap().unwrap();
+        assert_eq!(persisted.state, b"epoch-0");
+
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    async fn expires_abandoned_transient_entities() {
+        use core_crypto_keystore::{
+            entities::{E2eiEnrollment, EntityFindParams, MlsPendingMessage, PENDING_GROUP_TTL},
+            CryptoKeystoreMls as _,
+        };
+
+        let store = Connection::open_with_key(store_name(), TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        store
+            .mls_pending_groups_save(b"abandoned", b"state", b"cfg", None)
+            .await
+            .unwrap();
+        store
+            .mls_pending_groups_save(b"ongoing", b"state", b"cfg", None)
+            .await
+            .unwrap();
+        for message in [b"first".to_vec(), b"second".to_vec()] {
+            store
+                .save(MlsPendingMessage {
+                    id: b"abandoned".to_vec(),
+                    message,
+                    created_at: 0,
+                })
+                .await
+                .unwrap();
+        }
+        store.save_e2ei_enrollment(b"enrollment", b"content").await.unwrap();
+        assert_eq!(store.expired_count().await.unwrap(), 0);
+
+        // as if they had been left behind long ago, except for one of the buffered messages
+        {
+            let conn = store.borrow_conn().await.unwrap();
+            let age = PENDING_GROUP_TTL.as_secs() * 2;
+            conn.execute(
+                "UPDATE mls_pending_groups SET created_at = created_at - ? WHERE id = ?",
+                rusqlite::params![age, b"abandoned".as_slice()],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE mls_pending_messages SET created_at = created_at - ? WHERE message = ?",
+                rusqlite::params![age, b"first".as_slice()],
+            )
+            .unwrap();
+            conn.execute("UPDATE e2ei_enrollment SET created_at = created_at - ?", [age])
+                .unwrap();
+        }
+
+        // lookups no longer see them
+        assert!(store
+            .find::<PersistedMlsPendingGroup>(b"abandoned")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store.mls_pending_groups_load(b"abandoned").await.is_err());
+        assert!(store.pop_e2ei_enrollment(b"enrollment").await.is_err());
+        let messages = store
+            .find_all::<MlsPendingMessage>(EntityFindParams::default())
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].message, b"second");
+        assert_eq!(store.count::<PersistedMlsPendingGroup>().await.unwrap(), 2);
+
+        // the buffered messages stay as long as one of them is still valid
+        assert_eq!(store.expired_count().await.unwrap(), 2);
+        assert_eq!(store.purge_expired().await.unwrap(), 2);
+        assert_eq!(store.count::<PersistedMlsPendingGroup>().await.unwrap(), 1);
+        assert_eq!(store.count::<MlsPendingMessage>().await.unwrap(), 2);
+        assert_eq!(store.count::<E2eiEnrollment>().await.unwrap(), 0);
+        assert!(store.integrity_check().await.unwrap().is_empty());
+        assert_eq!(store.purge_expired().await.unwrap(), 0);
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn detects_corrupted_entities(store: Connection) {
+        use core_crypto_keystore::{
+            connection::CorruptedEntry, entities::EntityChecksum, CryptoKeystoreError, CryptoKeystoreMls as _,
+        };
+
+        let store = store.await;
+        store.mls_group_persist(b"group", b"state", None).await.unwrap();
+        store.mls_group_persist(b"other-group", b"state", None).await.unwrap();
+        assert!(store.integrity_check().await.unwrap().is_empty());
+
+        // the content changes behind the back of its checksum, as a filesystem corruption would do
+        let corrupted = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"garbage".to_vec(),
+            parent_id: None,
+        };
+        corrupted.save(&mut *store.borrow_conn().await.unwrap()).await.unwrap();
+
+        assert!(matches!(
+            store.find::<PersistedMlsGroup>(b"group").await.unwrap_err(),
+            CryptoKeystoreError::CorruptedEntity {
+                collection: "mls_groups",
+                ..
+            }
+        ));
+        assert!(store.find::<PersistedMlsGroup>(b"other-group").await.unwrap().is_some());
+        assert_eq!(
+            store.integrity_check().await.unwrap(),
+            vec![CorruptedEntry {
+                collection: "mls_groups",
+                id: b"group".to_vec(),
+            }]
+        );
+
+        // writing it again through the keystore repairs it
+        store.mls_group_persist(b"group", b"new-state", None).await.unwrap();
+        assert!(store.integrity_check().await.unwrap().is_empty());
+        assert_eq!(
+            store.find::<PersistedMlsGroup>(b"group").await.unwrap().unwrap().state,
+            b"new-state"
+        );
+
+        // the checksum goes away with the entity
+        store.mls_group_delete(b"group").await.unwrap();
+        assert_eq!(store.count::<EntityChecksum>().await.unwrap(), 1);
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn can_load_many_groups_at_once(store: Connection) {
+        use core_crypto_keystore::CryptoKeystoreMls as _;
+
+        let store = store.await;
+        store.mls_group_persist(b"first", b"first state", None).await.unwrap();
+        store
+            .mls_group_persist(b"second", b"second state", Some(b"first"))
+            .await
+            .unwrap();
+        store.mls_group_persist(b"third", b"third state", None).await.unwrap();
+
+        let ids = [b"first".to_vec(), b"second".to_vec(), b"missing".to_vec()];
+        let groups = store.mls_groups_load(&ids).await.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&b"first".to_vec()], (None, b"first state".to_vec()));
+        assert_eq!(
+            groups[&b"second".to_vec()],
+            (Some(b"first".to_vec()), b"second state".to_vec())
+        );
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn removes_entities_by_conversation(store: Connection) {
+        use core_crypto_keystore::entities::{MlsEpochEncryptionKeyPair, MlsSeenCredential};
+
+        let store = store.await;
+        for (id, conversation_id) in [(b"a1", b"alpha"), (b"a2", b"alpha"), (b"b1", b"bravo")] {
+            store
+                .save(MlsSeenCredential {
+                    id: id.to_vec(),
+                    conversation_id: conversation_id.to_vec(),
+                    client_id: b"client".to_vec(),
+                    credential: b"credential".to_vec(),
+                    first_seen: 0,
+                })
+                .await
+                .unwrap();
+        }
+        // the group id followed by the epoch and the leaf index, "alphabet" merely starts with "alpha"
+        for group_id in [b"alpha".as_slice(), b"alphabet", b"bravo"] {
+            store
+                .save(MlsEpochEncryptionKeyPair {
+                    id: [group_id, &[0; 12]].concat(),
+                    keypairs: b"keypairs".to_vec(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let removed = store
+            .remove_all_by_conversation::<MlsSeenCredential>(b"alpha")
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(store.count::<MlsSeenCredential>().await.unwrap(), 1);
+        assert!(store.find::<MlsSeenCredential>(b"b1").await.unwrap().is_some());
+
+        let removed = store
+            .remove_all_by_conversation::<MlsEpochEncryptionKeyPair>(b"alpha")
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(store.count::<MlsEpochEncryptionKeyPair>().await.unwrap(), 2);
+
+        // nothing left to remove
+        let removed = store
+            .remove_all_by_conversation::<MlsSeenCredential>(b"alpha")
+            .await
+            .unwrap();
+        assert_eq!(removed, 0);
+
+        teardown(store).await;
+    }
 }
@@ -93,7 +93,8 @@ mod tests_impl {
     ) {
         entity.random_update();
         store.save(entity.clone()).await.unwrap();
-        let entity2: R = store.find(entity.id_raw()).await.unwrap().unwrap();
+        let mut entity2: R = store.find(entity.id_raw()).await.unwrap().unwrap();
+        entity2.equalize();
         assert_eq!(*entity, entity2);
     }
 
@@ -124,6 +125,14 @@ mod tests_impl {
             let entities = store.find_many::<R, _>(&ids).await.unwrap();
             if !ignore_entity_count {
                 assert_eq!(entities.len(), ENTITY_COUNT);
+
+                // only the requested entities are returned
+                let subset = &ids[..ENTITY_COUNT / 2];
+                let entities = store.find_many::<R, _>(subset).await.unwrap();
+                assert_eq!(entities.len(), subset.len());
+                assert!(entities
+                    .iter()
+                    .all(|e| subset.iter().any(|id| id.as_slice() == e.id_raw())));
             }
         }
     }
@@ -150,11 +159,21 @@ pub mod tests {
 
     use core_crypto_keystore::entities::*;
 
+    test_for_entity!(test_entity_checksum, EntityChecksum);
+
     cfg_if::cfg_if! {
         if #[cfg(feature = "mls-keystore")] {
             test_for_entity!(test_persisted_mls_group, PersistedMlsGroup);
             test_for_entity!(test_persisted_mls_pending_group, PersistedMlsPendingGroup);
             test_for_entity!(test_mls_pending_message, MlsPendingMessage ignore_update:true ignore_find_many:true);
+            test_for_entity!(test_mls_conversation_metadata, MlsConversationMetadata);
+            test_for_entity!(test_mls_pending_commit, MlsPendingCommit);
+            test_for_entity!(test_mls_pending_rotation, MlsPendingRotation);
+            test_for_entity!(test_mls_conversation_counters, MlsConversationCounters);
+            test_for_entity!(test_mls_processed_welcome, MlsProcessedWelcome);
+            test_for_entity!(test_mls_seen_credential, MlsSeenCredential);
+            test_for_entity!(test_mls_epoch_record, MlsEpochRecord);
+            test_for_entity!(test_mls_group_index_entry, MlsGroupIndexEntry);
             test_for_entity!(test_mls_credential, MlsCredential ignore_update:true);
             test_for_entity!(test_mls_keypackage, MlsKeyPackage);
             test_for_entity!(test_mls_signature_keypair, MlsSignatureKeyPair ignore_update:true);
@@ -170,6 +189,7 @@ pub mod tests {
             test_for_entity!(test_proteus_identity, ProteusIdentity ignore_entity_count:true ignore_update:true);
             test_for_entity!(test_proteus_prekey, ProteusPrekey);
             test_for_entity!(test_proteus_session, ProteusSession);
+            test_for_entity!(test_proteus_remote_identity, ProteusRemoteIdentity);
         }
     }
 }
@@ -186,6 +206,28 @@ pub mod utils {
         fn equalize(&mut self) {}
     }
 
+    impl EntityTestExt for core_crypto_keystore::entities::EntityChecksum {
+        fn random() -> Self {
+            let mut rng = rand::thread_rng();
+
+            let id = format!("mls_groups/{}", uuid::Uuid::new_v4().hyphenated()).into_bytes();
+            let mut checksum = vec![0; 32];
+            rng.fill(&mut checksum[..]);
+
+            Self {
+                id,
+                checksum,
+                previous_checksum: None,
+            }
+        }
+
+        fn random_update(&mut self) {
+            let mut rng = rand::thread_rng();
+            self.previous_checksum = Some(self.checksum.clone());
+            rng.fill(&mut self.checksum[..]);
+        }
+    }
+
     cfg_if::cfg_if! {
         if #[cfg(feature = "mls-keystore")] {
             impl EntityTestExt for core_crypto_keystore::entities::MlsKeyPackage {
@@ -379,6 +421,7 @@ pub mod utils {
                         state,
                         custom_configuration,
                         parent_id: None,
+                        created_at: 0,
                     }
                 }
 
@@ -387,6 +430,213 @@ pub mod utils {
                     self.state = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
                     rng.fill(&mut self.state[..]);
                 }
+
+                fn equalize(&mut self) {
+                    self.created_at = 0;
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsProcessedWelcome {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let mut id = vec![0; 32];
+                    rng.fill(&mut id[..]);
+
+                    Self {
+                        id,
+                        conversation_id: uuid::Uuid::new_v4().into_bytes().into(),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    self.conversation_id = uuid::Uuid::new_v4().into_bytes().into();
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsGroupIndexEntry {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let id: [u8; 16] = uuid.into_bytes();
+
+                    Self {
+                        id: id.into(),
+                        ciphersuite: rng.gen(),
+                        epoch: rng.gen_range(0..u32::MAX as u64),
+                        member_count: rng.gen_range(1..u16::MAX as u32),
+                        last_persisted: rng.gen_range(1..u32::MAX as u64),
+                        parent_id: rng
+                            .gen::<bool>()
+                            .then(|| uuid::Uuid::new_v4().into_bytes().into()),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    self.epoch += 1;
+                    self.last_persisted += 1;
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsSeenCredential {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let mut id = vec![0; 32];
+                    rng.fill(&mut id[..]);
+                    let mut credential = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut credential[..]);
+
+                    Self {
+                        id,
+                        conversation_id: uuid::Uuid::new_v4().into_bytes().into(),
+                        client_id: uuid::Uuid::new_v4().into_bytes().into(),
+                        credential,
+                        first_seen: rng.gen_range(1..u32::MAX as u64),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.credential = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.credential[..]);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsEpochRecord {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let conversation_id: Vec<u8> = uuid::Uuid::new_v4().into_bytes().into();
+                    let epoch = rng.gen_range(0..u32::MAX as u64);
+                    let mut authenticator_hash = vec![0; 32];
+                    rng.fill(&mut authenticator_hash[..]);
+
+                    Self {
+                        id: [conversation_id.as_slice(), &epoch.to_be_bytes()].concat(),
+                        conversation_id,
+                        epoch,
+                        authenticator_hash,
+                        timestamp: rng.gen_range(1..u32::MAX as u64),
+                        commit_sender: rng
+                            .gen_bool(0.5)
+                            .then(|| uuid::Uuid::new_v4().into_bytes().into()),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    rng.fill(&mut self.authenticator_hash[..]);
+                    self.timestamp += 1;
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsConversationMetadata {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let id: [u8; 16] = uuid.into_bytes();
+
+                    Self {
+                        id: id.into(),
+                        key_rotation_span: rng.gen_range(1..u32::MAX as u64),
+                        last_key_rotation: rng.gen_range(1..u32::MAX as u64),
+                        max_application_message_epoch_age: rng.gen_bool(0.5).then(|| rng.gen_range(0..u32::MAX as u64)),
+                        epoch_fence: rng.gen_range(0..=2),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    self.last_key_rotation += 1;
+                    self.epoch_fence = (self.epoch_fence + 1) % 3;
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsPendingCommit {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let id: [u8; 16] = uuid.into_bytes();
+
+                    let mut operation_key = vec![0; 16];
+                    rng.fill(&mut operation_key[..]);
+                    let mut confirmation_tag = vec![0; 32];
+                    rng.fill(&mut confirmation_tag[..]);
+                    let mut bundle = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut bundle[..]);
+
+                    Self {
+                        id: id.into(),
+                        operation_key,
+                        confirmation_tag,
+                        bundle,
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.confirmation_tag = uuid::Uuid::new_v4().into_bytes().into();
+                    self.bundle = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.bundle[..]);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsPendingRotation {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let id: [u8; 16] = uuid.into_bytes();
+
+                    let mut conversation_ids = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut conversation_ids[..]);
+                    let mut key_package_refs = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut key_package_refs[..]);
+
+                    Self {
+                        id: id.into(),
+                        conversation_ids,
+                        key_package_refs,
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.conversation_ids = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.conversation_ids[..]);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsConversationCounters {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let id: [u8; 16] = uuid.into_bytes();
+
+                    Self {
+                        id: id.into(),
+                        messages_sent: rng.gen_range(0..u32::MAX as u64),
+                        messages_received: rng.gen_range(0..u32::MAX as u64),
+                        commits_sent: rng.gen_range(0..u32::MAX as u64),
+                        commits_received: rng.gen_range(0..u32::MAX as u64),
+                        last_activity_epoch: rng.gen_range(0..u32::MAX as u64),
+                        last_activity: rng.gen_range(1..u32::MAX as u64),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    self.messages_sent += 1;
+                    self.last_activity += 1;
+                }
             }
 
             impl EntityTestExt for core_crypto_keystore::entities::MlsPendingMessage {
@@ -403,6 +653,7 @@ pub mod utils {
                     Self {
                         id: id.into(),
                         message,
+                        created_at: 0,
                     }
                 }
 
@@ -411,6 +662,10 @@ pub mod utils {
                     self.message = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
                     rng.fill(&mut self.message[..]);
                 }
+
+                fn equalize(&mut self) {
+                    self.created_at = 0;
+                }
             }
         }
     }
@@ -487,6 +742,27 @@ pub mod utils {
                 }
             }
 
+            impl EntityTestExt for core_crypto_keystore::entities::ProteusRemoteIdentity {
+                fn random() -> Self {
+                    use rand::Rng as _;
+                    let mut rng = rand::thread_rng();
+
+                    let uuid = uuid::Uuid::new_v4();
+                    let key: [u8; 32] = rng.gen();
+
+                    Self {
+                        id: uuid.hyphenated().to_string(),
+                        fingerprint: hex::encode(key),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    use rand::Rng as _;
+                    let key: [u8; 32] = rand::thread_rng().gen();
+                    self.fingerprint = hex::encode(key);
+                }
+            }
+
             impl EntityTestExt for core_crypto_keystore::entities::E2eiIntermediateCert {
                 fn random() -> Self {
                     let mut rng = rand::thread_rng();
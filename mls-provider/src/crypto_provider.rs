@@ -65,8 +65,9 @@ impl OpenMlsCrypto for RustCrypto {
             Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519
             | Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
             | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
-            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384
-            | Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => Ok(()),
+            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => Ok(()),
+            #[cfg(feature = "pq-ciphersuites")]
+            Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519 => Ok(()),
             _ => Err(CryptoError::UnsupportedCiphersuite),
         }
     }
@@ -77,6 +78,7 @@ impl OpenMlsCrypto for RustCrypto {
             Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
             Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
             Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+            #[cfg(feature = "pq-ciphersuites")]
             Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519,
         ]
     }
@@ -315,6 +317,7 @@ impl OpenMlsCrypto for RustCrypto {
                     pk_r, info, aad, ptxt, &mut *rng,
                 )
             }
+            #[cfg(feature = "pq-ciphersuites")]
             HpkeConfig(HpkeKemType::X25519Kyber768Draft00, HpkeKdfType::HkdfSha256, HpkeAeadType::AesGcm128) => {
                 hpke_core::hpke_seal::<hpke::aead::AesGcm128, hpke::kdf::HkdfSha256, hpke::kem::X25519Kyber768Draft00>(
                     pk_r, info, aad, ptxt, &mut *rng,
@@ -369,6 +372,7 @@ impl OpenMlsCrypto for RustCrypto {
                     input.ciphertext.as_slice(),
                 )?
             }
+            #[cfg(feature = "pq-ciphersuites")]
             HpkeConfig(HpkeKemType::X25519Kyber768Draft00, HpkeKdfType::HkdfSha256, HpkeAeadType::AesGcm128) => {
                 hpke_core::hpke_open::<hpke::aead::AesGcm128, hpke::kdf::HkdfSha256, hpke::kem::X25519Kyber768Draft00>(
                     sk_r,
@@ -424,6 +428,7 @@ impl OpenMlsCrypto for RustCrypto {
                         hpke::kem::DhP384HkdfSha384,
                     >(pk_r, info, exporter_context, exporter_length, &mut *rng)?
                 }
+                #[cfg(feature = "pq-ciphersuites")]
                 HpkeConfig(HpkeKemType::X25519Kyber768Draft00, HpkeKdfType::HkdfSha256, HpkeAeadType::AesGcm128) => {
                     hpke_core::hpke_export_tx::<
                         hpke::aead::AesGcm128,
@@ -478,6 +483,7 @@ impl OpenMlsCrypto for RustCrypto {
                         hpke::kem::DhP384HkdfSha384,
                     >(enc, sk_r, info, exporter_context, exporter_length)?
                 }
+                #[cfg(feature = "pq-ciphersuites")]
                 HpkeConfig(HpkeKemType::X25519Kyber768Draft00, HpkeKdfType::HkdfSha256, HpkeAeadType::AesGcm128) => {
                     hpke_core::hpke_export_rx::<
                         hpke::aead::AesGcm128,
@@ -498,6 +504,7 @@ impl OpenMlsCrypto for RustCrypto {
             HpkeKemType::DhKemP256 => hpke_core::hpke_derive_keypair::<hpke::kem::DhP256HkdfSha256>(ikm),
             HpkeKemType::DhKemP384 => hpke_core::hpke_derive_keypair::<hpke::kem::DhP384HkdfSha384>(ikm),
             HpkeKemType::DhKem25519 => hpke_core::hpke_derive_keypair::<hpke::kem::X25519HkdfSha256>(ikm),
+            #[cfg(feature = "pq-ciphersuites")]
             HpkeKemType::X25519Kyber768Draft00 => {
                 hpke_core::hpke_derive_keypair::<hpke::kem::X25519Kyber768Draft00>(ikm)
             }
@@ -16,7 +16,10 @@
 
 #![doc = include_str!("../README.md")]
 
-pub use core_crypto_keystore::Connection as CryptoKeystore;
+pub use core_crypto_keystore::{
+    connection::{ConnectionOptions, FileProtectionClass, KeyProvider},
+    Connection as CryptoKeystore,
+};
 
 mod crypto_provider;
 mod error;
@@ -77,14 +80,23 @@ impl std::ops::DerefMut for EntropySeed {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct MlsCryptoProviderConfiguration<'a> {
     /// File path or database name of the persistent storage
     pub db_path: &'a str,
-    /// Encryption master key of the encrypted-at-rest persistent storage
+    /// Encryption master key of the encrypted-at-rest persistent storage. Unused when a `key_provider` is set
     pub identity_key: &'a str,
+    /// Protects the master key of the persistent storage with the platform's secure hardware instead of deriving it
+    /// from `identity_key`, see [CryptoKeystore::open_with_key_provider]
+    pub key_provider: Option<&'a dyn KeyProvider>,
     /// Dictates whether or not the backend storage is in memory or not
     pub in_memory: bool,
+    /// Opens the persistent storage even if another instance holds it, see [CryptoKeystore::take_over_with_key]
+    pub take_over_store: bool,
+    /// iOS data protection class of the files of the persistent storage, see [ConnectionOptions::file_protection]
+    pub file_protection: Option<FileProtectionClass>,
+    /// Keeps the salt of the persistent storage in the iOS keychain, see [ConnectionOptions::wal_compat]
+    pub wal_compat: bool,
     /// External seed for the ChaCha20 PRNG entropy pool
     pub entropy_seed: Option<EntropySeed>,
 }
@@ -100,10 +112,17 @@ impl MlsCryptoProvider {
     /// Initialize a CryptoProvider with a backend following the provided `config` (see: [MlsCryptoProviderConfiguration])
     pub async fn try_new_with_configuration(config: MlsCryptoProviderConfiguration<'_>) -> MlsProviderResult<Self> {
         let crypto = config.entropy_seed.map(RustCrypto::new_with_seed).unwrap_or_default();
+        let options = ConnectionOptions {
+            take_over: config.take_over_store,
+            file_protection: config.file_protection,
+            wal_compat: config.wal_compat,
+        };
         let key_store = if config.in_memory {
             CryptoKeystore::open_in_memory_with_key("", config.identity_key).await?
+        } else if let Some(key_provider) = config.key_provider {
+            CryptoKeystore::open_with_key_provider_and_options(config.db_path, key_provider, &options).await?
         } else {
-            CryptoKeystore::open_with_key(config.db_path, config.identity_key).await?
+            CryptoKeystore::open_with_options(config.db_path, config.identity_key, &options).await?
         };
         Ok(Self {
             crypto,